@@ -44,7 +44,8 @@ module.exports = grammar({
     module_field: $ => choice(
       $.type,
       $.module_method,
-      $.interceptor
+      $.interceptor,
+      $.enum
     ),
     module_method: $ => seq(
       repeat(field("annotation", $.annotation)),
@@ -64,6 +65,13 @@ module.exports = grammar({
       optional(commaSep(field("args", $.argument))),
       ")",
     ),
+    enum: $ => seq(
+      "enum",
+      field("name", $.term),
+      "{",
+      repeat(seq(field("value", $.term), optional(","))),
+      "}"
+    ),
     context: $ => seq(
       repeat(field("annotation", $.annotation)),
       "context",
@@ -92,7 +100,13 @@ module.exports = grammar({
     ),
     annotation_multiple_params: $ => commaSep(field("exprs", $.expression)),
     annotation_map_params: $ => commaSep(field("param", $.annotation_map_param)),
-    annotation_map_param: $ => seq(field("name", $.term), "=", field("expr", $.expression)),
+    annotation_map_param: $ => seq(field("name", $.term), choice("=", ":"), field("expr", $.annotation_map_param_value)),
+    // List and object literals may appear only as (a part of) a named annotation parameter
+    annotation_map_param_value: $ => choice(
+      $.expression,
+      $.literal_list,
+      $.literal_object
+    ),
     argument: $ => seq(
       repeat(field("annotation", $.annotation)),
       field("name", $.term),
@@ -213,6 +227,9 @@ module.exports = grammar({
     literal_str: $ => seq("\"", field("value", $.str), "\""),
     literal_boolean: $ => choice("true", "false"),
     literal_number: $ => field("value", $.number),
+    literal_list: $ => seq("[", optional(commaSep(field("element", $.literal_str))), "]"),
+    literal_object: $ => seq("{", optional(commaSep(field("entry", $.literal_object_entry))), "}"),
+    literal_object_entry: $ => seq(field("name", $.term), ":", field("value", $.annotation_map_param_value)),
     comment: $ => token(choice(
       seq('//', /.*/),
       seq(
@@ -89,6 +89,7 @@ fn matches(expr: &AstExpr<Typed>, operation_name: &str, operation_kind: Operatio
         AstExpr::StringList(_, _) => {
             panic!("List not supported in interceptor expression")
         }
+        AstExpr::ObjectLiteral(_, _) => unreachable!(), // Parser allows objects only as named annotation parameters
     }
 }
 
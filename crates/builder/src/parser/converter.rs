@@ -8,8 +8,6 @@
 // by the Apache License, Version 2.0.
 
 use std::convert::TryInto;
-#[cfg(not(target_family = "wasm"))]
-use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::{collections::HashMap, path::Path};
 
@@ -19,11 +17,13 @@ use codemap_diagnostic::{Diagnostic, Level, SpanLabel, SpanStyle};
 use core_model_builder::ast::ast_types::{FieldSelectionElement, Identifier};
 use tree_sitter_c2rust::{Node, Tree, TreeCursor};
 
+#[cfg(not(target_family = "wasm"))]
+use super::resolve_import_path;
 use super::{sitter_ffi, span_from_node};
 use crate::ast::ast_types::{
-    AstAnnotation, AstAnnotationParams, AstArgument, AstExpr, AstField, AstFieldDefault,
-    AstFieldDefaultKind, AstFieldType, AstInterceptor, AstMethod, AstModel, AstModelKind,
-    AstModule, AstSystem, FieldSelection, LogicalOp, RelationalOp, Untyped,
+    AstAnnotation, AstAnnotationParams, AstArgument, AstEnum, AstEnumField, AstExpr, AstField,
+    AstFieldDefault, AstFieldDefaultKind, AstFieldType, AstInterceptor, AstMethod, AstModel,
+    AstModelKind, AstModule, AstSystem, FieldSelection, LogicalOp, RelationalOp, Untyped,
 };
 use crate::error::ParserError;
 
@@ -94,44 +94,11 @@ pub fn convert_root(
                                 }])
                             }
 
-                            let check_existence =
-                                |import_path: PathBuf| -> Result<Option<PathBuf>, ParserError> {
-                                    match import_path.canonicalize() {
-                                        Ok(path) if path.is_file() => Ok(Some(path)),
-                                        _ => Err(compute_diagnosis(
-                                            import_path,
-                                            source_span,
-                                            first_child,
-                                        )),
-                                    }
-                                };
-
-                            // Resolve the import path
-                            // 1. If the path exists and it is a file, return the path
-                            // 2. If the path exists and it is a directory, check for <path>/index.exo
-                            // 3. If the path doesn't exist, check for <path>.exo
-                            match import_path.canonicalize() {
-                                Ok(path) if path.is_file() => Ok(Some(path)),
-                                Ok(path) if path.is_dir() => {
-                                    // If the path is a directory, try to find <directory>/index.exo
-                                    let with_index_exo = path.join("index.exo");
-                                    check_existence(with_index_exo)
-                                }
-                                _ => {
-                                    // If no extension is given, try if a file with the same name but with ".exo" extension exists.
-                                    if import_path.extension() == Some(OsStr::new("exo")) {
-                                        // Already has the .exo extension, so further checks are not necessary (it is a failure since the file does not exist).
-                                        Err(compute_diagnosis(
-                                            import_path,
-                                            source_span,
-                                            first_child,
-                                        ))
-                                    } else {
-                                        let with_extension = import_path.with_extension("exo");
-                                        check_existence(with_extension)
-                                    }
-                                }
-                            }
+                            resolve_import_path(import_path)
+                                .map(Some)
+                                .map_err(|import_path| {
+                                    compute_diagnosis(import_path, source_span, first_child)
+                                })
                         }
 
                         #[cfg(target_family = "wasm")]
@@ -245,6 +212,9 @@ fn convert_module(
         interceptors: matching_nodes(node, &mut node.walk(), "interceptor")
             .map(|n| convert_interceptor(n, source, source_span))
             .collect(),
+        enums: matching_nodes(node, &mut node.walk(), "enum")
+            .map(|n| convert_enum(n, source, source_span))
+            .collect(),
         annotations,
         base_exofile: filepath.into(),
         span: span_from_node(source_span, node),
@@ -297,6 +267,22 @@ fn convert_interceptor(node: Node, source: &[u8], source_span: Span) -> AstInter
     }
 }
 
+fn convert_enum(node: Node, source: &[u8], source_span: Span) -> AstEnum {
+    let mut cursor = node.walk();
+
+    AstEnum {
+        name: text_child(node, source, "name"),
+        fields: node
+            .children_by_field_name("value", &mut cursor)
+            .map(|c| AstEnumField {
+                name: c.utf8_text(source).unwrap().to_string(),
+                span: span_from_node(source_span, c),
+            })
+            .collect(),
+        span: span_from_node(source_span, node),
+    }
+}
+
 fn convert_fields(node: Node, source: &[u8], source_span: Span) -> Vec<AstField<Untyped>> {
     let mut cursor = node.walk();
     node.children_by_field_name("field", &mut cursor)
@@ -476,7 +462,7 @@ fn convert_annotation_params(
                 .map(|(name, p)| {
                     (
                         name.clone(),
-                        convert_expression(
+                        convert_annotation_map_param_value(
                             p.child_by_field_name("expr").unwrap(),
                             source,
                             source_span,
@@ -503,6 +489,58 @@ fn convert_annotation_params(
     }
 }
 
+fn convert_annotation_map_param_value(
+    node: Node,
+    source: &[u8],
+    source_span: Span,
+) -> AstExpr<Untyped> {
+    assert_eq!(node.kind(), "annotation_map_param_value");
+    let first_child = node.child(0).unwrap();
+
+    match first_child.kind() {
+        "expression" => convert_expression(first_child, source, source_span),
+        "literal_list" => {
+            let mut cursor = first_child.walk();
+            // The grammar allows only string literals as list elements
+            let (string_list, mut spans): (Vec<_>, Vec<_>) = first_child
+                .children_by_field_name("element", &mut cursor)
+                .map(|node| {
+                    (
+                        text_child(node, source, "value"),
+                        span_from_node(source_span, node),
+                    )
+                })
+                .unzip();
+
+            // An empty list has no elements to take the span from
+            if spans.is_empty() {
+                spans.push(span_from_node(source_span, first_child));
+            }
+
+            AstExpr::StringList(string_list, spans)
+        }
+        "literal_object" => {
+            let mut cursor = first_child.walk();
+            let entries = first_child
+                .children_by_field_name("entry", &mut cursor)
+                .map(|entry| {
+                    (
+                        text_child(entry, source, "name"),
+                        convert_annotation_map_param_value(
+                            entry.child_by_field_name("value").unwrap(),
+                            source,
+                            source_span,
+                        ),
+                    )
+                })
+                .collect();
+
+            AstExpr::ObjectLiteral(entries, span_from_node(source_span, first_child))
+        }
+        o => panic!("unsupported annotation parameter kind: {o}"),
+    }
+}
+
 fn convert_expression(node: Node, source: &[u8], source_span: Span) -> AstExpr<Untyped> {
     assert_eq!(node.kind(), "expression");
     let first_child = node.child(0).unwrap();
@@ -761,6 +799,28 @@ mod tests {
         );
     }
 
+    #[multiplatform_test]
+    fn enum_declaration() {
+        parsing_test!(
+            r#"
+            @postgres
+            module TestModule {
+                enum Status { DRAFT, PUBLISHED }
+
+                enum Priority {
+                    LOW
+                    HIGH
+                }
+
+                type Todo {
+                    status: Status
+                }
+            }
+        "#,
+            "enum_declaration"
+        );
+    }
+
     #[multiplatform_test]
     fn bb_schema() {
         parsing_test!(
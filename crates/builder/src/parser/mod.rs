@@ -10,7 +10,7 @@
 use std::path::Path;
 
 #[cfg(not(target_family = "wasm"))]
-use std::{fs, path::PathBuf};
+use std::{ffi::OsStr, fs, path::PathBuf};
 
 use codemap::{CodeMap, Span};
 use codemap_diagnostic::{Diagnostic, Level, SpanLabel, SpanStyle};
@@ -39,9 +39,27 @@ fn span_from_node(source_span: Span, node: Node<'_>) -> Span {
 pub fn parse_file(
     input_file: impl AsRef<Path>,
     codemap: &mut CodeMap,
+) -> Result<AstSystem<Untyped>, ParserError> {
+    parse_file_with_sources(input_file, codemap, &|path| fs::read_to_string(path))
+}
+
+#[cfg(not(target_family = "wasm"))]
+/// Parse a file and return the AST, reading the source of each file (the input file and the files
+/// it imports) through `read_source`.
+///
+/// This allows parsing files that have unsaved changes (for example, in an editor).
+///
+/// # Arguments
+/// * `input_file` - The file to parse
+/// * `codemap` - The codemap to accumulate errors
+/// * `read_source` - Reads the source of a file
+pub fn parse_file_with_sources(
+    input_file: impl AsRef<Path>,
+    codemap: &mut CodeMap,
+    read_source: &dyn Fn(&Path) -> std::io::Result<String>,
 ) -> Result<AstSystem<Untyped>, ParserError> {
     let mut already_parsed = vec![];
-    _parse_file(input_file, codemap, &mut already_parsed)
+    _parse_file(input_file, codemap, read_source, &mut already_parsed)
 }
 
 #[cfg(not(target_family = "wasm"))]
@@ -52,20 +70,25 @@ pub fn parse_file(
 /// # Arguments
 /// * `input_file` - The file to parse
 /// * `codemap` - The codemap to accumulate errors
+/// * `read_source` - Reads the source of a file
 /// * already_parsed - a vector of files that have already been parsed. Used to ensure that recursive imports do not cause an infinite loop
 fn _parse_file(
     input_file: impl AsRef<Path>,
     codemap: &mut CodeMap,
+    read_source: &dyn Fn(&Path) -> std::io::Result<String>,
     already_parsed: &mut Vec<PathBuf>,
 ) -> Result<AstSystem<Untyped>, ParserError> {
     let input_file_path = Path::new(input_file.as_ref());
-    if !input_file_path.exists() {
-        return Err(ParserError::FileNotFound(
-            input_file.as_ref().display().to_string(),
-        ));
-    }
+    let source = match read_source(input_file_path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(ParserError::FileNotFound(
+                input_file.as_ref().display().to_string(),
+            ));
+        }
+        Err(e) => return Err(e.into()),
+    };
 
-    let source = fs::read_to_string(input_file.as_ref())?;
     let mut system = parse_str(&source, codemap, input_file_path)?;
 
     // add to already parsed list since we're parsing it currently
@@ -74,7 +97,7 @@ fn _parse_file(
     for import in system.imports.iter() {
         if !already_parsed.contains(import) {
             // parse import
-            let mut imported_system = _parse_file(import, codemap, already_parsed)?;
+            let mut imported_system = _parse_file(import, codemap, read_source, already_parsed)?;
 
             // merge import into system
             system.types.append(&mut imported_system.types);
@@ -85,6 +108,41 @@ fn _parse_file(
     Ok(system)
 }
 
+#[cfg(not(target_family = "wasm"))]
+/// Resolve the path of an imported file:
+/// 1. If the path exists and it is a file, return the path
+/// 2. If the path exists and it is a directory, check for <path>/index.exo
+/// 3. If the path doesn't exist, check for <path>.exo
+///
+/// Returns the (canonicalized) path of the file if it exists, or the last path checked otherwise.
+pub fn resolve_import_path(import_path: PathBuf) -> Result<PathBuf, PathBuf> {
+    let check_existence = |import_path: PathBuf| -> Result<PathBuf, PathBuf> {
+        match import_path.canonicalize() {
+            Ok(path) if path.is_file() => Ok(path),
+            _ => Err(import_path),
+        }
+    };
+
+    match import_path.canonicalize() {
+        Ok(path) if path.is_file() => Ok(path),
+        Ok(path) if path.is_dir() => {
+            // If the path is a directory, try to find <directory>/index.exo
+            let with_index_exo = path.join("index.exo");
+            check_existence(with_index_exo)
+        }
+        _ => {
+            // If no extension is given, try if a file with the same name but with ".exo" extension exists.
+            if import_path.extension() == Some(OsStr::new("exo")) {
+                // Already has the .exo extension, so further checks are not necessary (it is a failure since the file does not exist).
+                Err(import_path)
+            } else {
+                let with_extension = import_path.with_extension("exo");
+                check_existence(with_extension)
+            }
+        }
+    }
+}
+
 pub fn parse_str(
     source: &str,
     codemap: &mut CodeMap,
@@ -9,7 +9,9 @@
 
 use std::collections::{HashMap, HashSet};
 
-use crate::ast::ast_types::{AstAnnotation, AstAnnotationParams, Untyped};
+use crate::ast::ast_types::{
+    AstAnnotation, AstAnnotationParams, AstExpr, FieldSelection, FieldSelectionElement, Untyped,
+};
 use codemap_diagnostic::{Diagnostic, Level, SpanLabel, SpanStyle};
 use core_model::mapped_arena::MappedArena;
 use core_model_builder::typechecker::{annotation::AnnotationSpec, Typed};
@@ -160,6 +162,73 @@ impl TypecheckFrom<AstAnnotation<Untyped>> for AstAnnotation<Typed> {
             },
         }
 
-        self.params.pass(type_env, annotation_env, scope, errors)
+        match (&mut self.params, spec.mapped_params) {
+            (AstAnnotationParams::Map(params, _), Some(param_specs)) => {
+                params
+                    .iter_mut()
+                    .map(|(name, param)| {
+                        let keywords = param_specs
+                            .iter()
+                            .find(|param_spec| param_spec.name == name)
+                            .map(|param_spec| param_spec.keywords)
+                            .unwrap_or_default();
+
+                        if keywords.is_empty() {
+                            param.pass(type_env, annotation_env, scope, errors)
+                        } else {
+                            resolve_keyword(
+                                param,
+                                name,
+                                keywords,
+                                |param, errors| param.pass(type_env, annotation_env, scope, errors),
+                                errors,
+                            )
+                        }
+                    })
+                    .filter(|b| *b)
+                    .count()
+                    > 0
+            }
+            _ => self.params.pass(type_env, annotation_env, scope, errors),
+        }
+    }
+}
+
+/// Resolve a keyword value of a mapped parameter (see `MappedAnnotationParamSpec::keywords`) to a
+/// string literal. Any other identifier is reported as an error, while other expressions are
+/// checked as usual.
+fn resolve_keyword(
+    param: &mut AstExpr<Typed>,
+    name: &str,
+    keywords: &[&str],
+    pass: impl FnOnce(&mut AstExpr<Typed>, &mut Vec<Diagnostic>) -> bool,
+    errors: &mut Vec<Diagnostic>,
+) -> bool {
+    match param {
+        AstExpr::FieldSelection(FieldSelection::Single(
+            FieldSelectionElement::Identifier(value, span, _),
+            _,
+        )) => {
+            if keywords.contains(&value.as_str()) {
+                *param = AstExpr::StringLiteral(value.clone(), *span);
+                true
+            } else {
+                errors.push(Diagnostic {
+                    level: Level::Error,
+                    message: format!("Invalid value `{value}` for parameter `{name}`"),
+                    code: Some("A000".to_string()),
+                    spans: vec![SpanLabel {
+                        span: *span,
+                        label: Some(format!(
+                            "expected {}",
+                            util::join_strings(keywords, Some("or"))
+                        )),
+                        style: SpanStyle::Primary,
+                    }],
+                });
+                false
+            }
+        }
+        _ => pass(param, errors),
     }
 }
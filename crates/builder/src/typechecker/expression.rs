@@ -31,6 +31,13 @@ impl TypecheckFrom<AstExpr<Untyped>> for AstExpr<Typed> {
             AstExpr::BooleanLiteral(v, s) => AstExpr::BooleanLiteral(*v, *s),
             AstExpr::NumberLiteral(v, s) => AstExpr::NumberLiteral(*v, *s),
             AstExpr::StringList(v, s) => AstExpr::StringList(v.clone(), s.clone()),
+            AstExpr::ObjectLiteral(entries, s) => AstExpr::ObjectLiteral(
+                entries
+                    .iter()
+                    .map(|(name, value)| (name.clone(), AstExpr::shallow(value)))
+                    .collect(),
+                *s,
+            ),
         }
     }
 
@@ -47,6 +54,11 @@ impl TypecheckFrom<AstExpr<Untyped>> for AstExpr<Typed> {
             AstExpr::RelationalOp(relation) => {
                 relation.pass(type_env, annotation_env, scope, errors)
             }
+            AstExpr::ObjectLiteral(entries, _) => {
+                entries.values_mut().fold(false, |changed, value| {
+                    value.pass(type_env, annotation_env, scope, errors) || changed
+                })
+            }
             AstExpr::StringList(_, _)
             | AstExpr::StringLiteral(_, _)
             | AstExpr::BooleanLiteral(_, _)
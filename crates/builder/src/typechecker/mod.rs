@@ -110,22 +110,89 @@ fn populate_annotation_env(
                     MappedAnnotationParamSpec {
                         name: "query",
                         optional: true,
+                        keywords: &[],
                     },
                     MappedAnnotationParamSpec {
                         name: "mutation",
                         optional: true,
+                        keywords: &[],
                     },
                     MappedAnnotationParamSpec {
                         name: "create",
                         optional: true,
+                        keywords: &[],
                     },
                     MappedAnnotationParamSpec {
                         name: "update",
                         optional: true,
+                        keywords: &[],
                     },
                     MappedAnnotationParamSpec {
                         name: "delete",
                         optional: true,
+                        keywords: &[],
+                    },
+                ]),
+            },
+        ),
+        (
+            "cache",
+            AnnotationSpec {
+                targets: &[AnnotationTarget::Type, AnnotationTarget::Method],
+                no_params: false,
+                single_params: false,
+                mapped_params: Some(&[
+                    MappedAnnotationParamSpec {
+                        name: "maxAge",
+                        optional: false,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "scope",
+                        optional: true,
+                        keywords: &["PUBLIC", "PRIVATE"],
+                    },
+                ]),
+            },
+        ),
+        (
+            "cost",
+            AnnotationSpec {
+                targets: &[
+                    AnnotationTarget::Type,
+                    AnnotationTarget::Field,
+                    AnnotationTarget::Method,
+                ],
+                no_params: false,
+                single_params: true,
+                mapped_params: None,
+            },
+        ),
+        (
+            "rateLimit",
+            AnnotationSpec {
+                targets: &[
+                    AnnotationTarget::Type,
+                    AnnotationTarget::Module,
+                    AnnotationTarget::Method,
+                ],
+                no_params: false,
+                single_params: false,
+                mapped_params: Some(&[
+                    MappedAnnotationParamSpec {
+                        name: "limit",
+                        optional: false,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "window",
+                        optional: false,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "key",
+                        optional: true,
+                        keywords: &[],
                     },
                 ]),
             },
@@ -241,6 +308,23 @@ fn populate_annotation_env(
     }
 }
 
+/// The names of the types available to exo files without declaring them (such as `Int`)
+pub fn builtin_type_names() -> Vec<String> {
+    let mut types_arena: MappedArena<Type> = MappedArena::default();
+    populate_type_env(&mut types_arena);
+    types_arena.keys().cloned().collect()
+}
+
+/// The annotations available to exo files (the common ones as well as the ones supported by the
+/// subsystems)
+pub fn annotation_specs(
+    subsystem_builders: &[Box<dyn SubsystemBuilder + Send + Sync>],
+) -> HashMap<String, AnnotationSpec> {
+    let mut annotation_env = HashMap::new();
+    populate_annotation_env(subsystem_builders, &mut annotation_env);
+    annotation_env
+}
+
 pub fn build(
     subsystem_builders: &[Box<dyn SubsystemBuilder + Send + Sync>],
     ast_system: AstSystem<Untyped>,
@@ -259,6 +343,16 @@ pub fn build(
         modules_arena.add(&module.name, Module(AstModule::shallow(module)));
 
         validate_module(module)?;
+
+        for ast_enum in module.enums.iter() {
+            types_arena.add(
+                ast_enum.name.as_str(),
+                Type::Primitive(PrimitiveType::Enum(
+                    ast_enum.name.clone(),
+                    ast_enum.fields.iter().map(|f| f.name.clone()).collect(),
+                )),
+            );
+        }
     }
 
     let ast_types_iter = ast_system.types.iter().chain(ast_module_types.iter());
@@ -370,7 +464,58 @@ fn validate_module(module: &AstModule<Untyped>) -> Result<(), ParserError> {
         |model| &model.name,
         |model| model.span,
         "model/type",
-    )
+    )?;
+
+    validate_no_duplicates(
+        &module.enums,
+        |ast_enum| &ast_enum.name,
+        |ast_enum| ast_enum.span,
+        "enum",
+    )?;
+
+    for ast_enum in module.enums.iter() {
+        if ast_enum.fields.is_empty() {
+            return Err(ParserError::Diagnosis(vec![Diagnostic {
+                level: Level::Error,
+                message: format!("Enum `{}` must have at least one value", ast_enum.name),
+                code: Some("C000".to_string()),
+                spans: vec![SpanLabel {
+                    span: ast_enum.span,
+                    style: SpanStyle::Primary,
+                    label: None,
+                }],
+            }]));
+        }
+
+        if let Some(model) = module.types.iter().find(|t| t.name == ast_enum.name) {
+            return Err(ParserError::Diagnosis(vec![Diagnostic {
+                level: Level::Error,
+                message: format!("Duplicate model/type and enum: {}", ast_enum.name),
+                code: Some("C000".to_string()),
+                spans: vec![
+                    SpanLabel {
+                        span: model.span,
+                        style: SpanStyle::Primary,
+                        label: Some("defined as a type here".to_string()),
+                    },
+                    SpanLabel {
+                        span: ast_enum.span,
+                        style: SpanStyle::Secondary,
+                        label: Some("defined as an enum here".to_string()),
+                    },
+                ],
+            }]));
+        }
+
+        validate_no_duplicates(
+            &ast_enum.fields,
+            |field| &field.name,
+            |field| field.span,
+            "enum value",
+        )?;
+    }
+
+    Ok(())
 }
 
 fn validate_no_duplicates<T>(
@@ -453,6 +598,7 @@ pub mod test_support {
 #[cfg(test)]
 mod tests {
     use super::test_support::{build, parse_sorted};
+    use super::{PrimitiveType, Type};
     use multiplatform_test::multiplatform_test;
 
     // Due to a change in insta version 1.12, test names (hence the snapshot names) get derived
@@ -899,6 +1045,150 @@ mod tests {
         assert_err(model);
     }
 
+    #[multiplatform_test]
+    fn enum_declaration() {
+        let src = r#"
+        @postgres
+        module TodoModule {
+            enum Status {
+                DRAFT,
+                PUBLISHED
+            }
+            @access(true)
+            type Todo {
+                @pk id: Int = autoIncrement()
+                status: Status
+            }
+        }
+        "#;
+
+        let checked = build(src).unwrap();
+        assert_eq!(
+            checked.types.get_by_key("Status"),
+            Some(&Type::Primitive(PrimitiveType::Enum(
+                "Status".to_string(),
+                vec!["DRAFT".to_string(), "PUBLISHED".to_string()]
+            )))
+        );
+    }
+
+    #[multiplatform_test]
+    fn enum_with_duplicate_values() {
+        let src = r#"
+        @postgres
+        module TodoModule {
+            enum Status {
+                DRAFT,
+                DRAFT
+            }
+        }
+        "#;
+
+        assert_err(src);
+    }
+
+    #[multiplatform_test]
+    fn enum_and_type_with_same_name() {
+        let src = r#"
+        @postgres
+        module TodoModule {
+            enum Status { DRAFT }
+            type Status {
+                @pk id: Int = autoIncrement()
+            }
+        }
+        "#;
+
+        assert_err(src);
+    }
+
+    #[multiplatform_test]
+    fn keyword_annotation_param() {
+        let src = r#"
+        @postgres
+        module ConcertModule {
+            type Concert {
+                @pk id: Int = autoIncrement()
+                @relation(onDelete: CASCADE) venue: Venue
+            }
+            type Venue {
+                @pk id: Int = autoIncrement()
+                concerts: Set<Concert>
+            }
+        }
+        "#;
+
+        assert!(build(src).is_ok());
+    }
+
+    #[multiplatform_test]
+    fn invalid_keyword_annotation_param() {
+        // Not one of the keywords of `onDelete`
+        let unknown_keyword = r#"
+        @postgres
+        module ConcertModule {
+            type Concert {
+                @pk id: Int = autoIncrement()
+                @relation(onDelete: CASCAD) venue: Venue
+            }
+            type Venue {
+                @pk id: Int = autoIncrement()
+                concerts: Set<Concert>
+            }
+        }
+        "#;
+
+        // A parameter without keywords
+        let no_keywords = r#"
+        @postgres
+        module UserModule {
+            type User {
+                @range(min=MIN, max=10) id: Int
+            }
+        }
+        "#;
+
+        assert_err(unknown_keyword);
+        assert_err(no_keywords);
+    }
+
+    #[multiplatform_test]
+    fn list_and_object_literals_outside_named_params() {
+        let list_in_expression = r#"
+        @postgres
+        module ConcertModule {
+            @access(self.id == [1])
+            type Concert {
+                @pk id: Int = autoIncrement()
+            }
+        }
+        "#;
+
+        let object_in_expression = r#"
+        @postgres
+        module ConcertModule {
+            @access(self.id == {id: 1})
+            type Concert {
+                @pk id: Int = autoIncrement()
+            }
+        }
+        "#;
+
+        let non_string_list_element = r#"
+        @postgres
+        module ConcertModule {
+            @access(query=[1])
+            type Concert {
+                @pk id: Int = autoIncrement()
+            }
+        }
+        "#;
+
+        assert_err(list_in_expression);
+        assert_err(object_in_expression);
+        assert_err(non_string_list_element);
+    }
+
     fn assert_err(src: &str) {
         assert!(build(src).is_err());
     }
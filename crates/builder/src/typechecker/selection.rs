@@ -10,7 +10,7 @@
 use std::collections::HashMap;
 
 use codemap_diagnostic::{Diagnostic, Level, SpanLabel, SpanStyle};
-use core_model::mapped_arena::MappedArena;
+use core_model::{mapped_arena::MappedArena, primitive_type::PrimitiveType};
 use core_model_builder::{
     ast::ast_types::{AstExpr, FieldSelectionElement},
     typechecker::{annotation::AnnotationSpec, Typed},
@@ -126,6 +126,7 @@ impl TypecheckHofCallFrom<FieldSelectionElement<Untyped>> for FieldSelectionElem
                 }
             }
             FieldSelectionElement::HofCall {
+                name,
                 param_name: elem_name,
                 expr,
                 typ,
@@ -138,7 +139,14 @@ impl TypecheckHofCallFrom<FieldSelectionElement<Untyped>> for FieldSelectionElem
                         .unwrap(),
                 )]));
                 let updated = expr.pass(type_env, annotation_env, &function_scope, errors);
-                *typ = expr.typ().clone();
+                *typ = match expr.typ() {
+                    // `count` evaluates to the number of elements satisfying the predicate (the
+                    // rest, such as `some`, evaluate to a boolean just like the predicate)
+                    expr_typ if name.0 == "count" && expr_typ.is_complete() => {
+                        Type::Primitive(PrimitiveType::Int)
+                    }
+                    expr_typ => expr_typ.clone(),
+                };
                 updated
             }
         }
@@ -68,13 +68,16 @@ pub(crate) async fn build_system_with_static_builders(
     model: &Path,
     trusted_documents_dir: Option<&Path>,
 ) -> Result<SerializableSystem, ParserError> {
-    let static_builders: Vec<Box<dyn SubsystemBuilder + Send + Sync>> = vec![
+    builder::build_system(model, trusted_documents_dir, static_builders()).await
+}
+
+/// The subsystem builders linked into the CLI
+pub(crate) fn static_builders() -> Vec<Box<dyn SubsystemBuilder + Send + Sync>> {
+    vec![
         Box::new(postgres_model_builder::PostgresSubsystemBuilder {}),
         Box::new(deno_model_builder::DenoSubsystemBuilder {}),
         Box::new(wasm_model_builder::WasmSubsystemBuilder {}),
-    ];
-
-    builder::build_system(model, trusted_documents_dir, static_builders).await
+    ]
 }
 
 /// Build exo_ir file
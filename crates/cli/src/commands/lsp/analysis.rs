@@ -0,0 +1,978 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Analysis of an exo project for the language server.
+//!
+//! An [Analysis] parses and typechecks a project (starting from its root file) and answers the
+//! queries of the editor (definitions, hover information, and completions) about positions in
+//! the project's files.
+
+use std::{
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use builder::{error::ParserError, parser, typechecker};
+use codemap::{CodeMap, File, Pos, Span};
+use codemap_diagnostic::{Diagnostic, Level, SpanStyle};
+use core_model::primitive_type::PrimitiveType;
+use core_model_builder::{
+    ast::ast_types::{
+        AstEnum, AstFieldType, AstModel, AstModelKind, AstModule, AstSystem, Untyped,
+    },
+    typechecker::{
+        annotation::AnnotationSpec,
+        typ::{Type, TypecheckedSystem},
+    },
+};
+use core_plugin_interface::interface::SubsystemBuilder;
+use tower_lsp::lsp_types::{
+    self, CompletionItem, CompletionItemKind, DiagnosticSeverity, NumberOrString, Position, Range,
+};
+
+/// The result of parsing and typechecking a project
+pub(super) struct Analysis {
+    /// The files of the project (by path)
+    files: HashMap<PathBuf, Arc<File>>,
+    /// The parsed system (`None` if the project couldn't be parsed)
+    system: Option<AstSystem<Untyped>>,
+    /// The typechecked system (`None` if the project couldn't be parsed or typechecked)
+    typechecked_system: Option<TypecheckedSystem>,
+    /// The diagnostics of the project (by file)
+    diagnostics: HashMap<PathBuf, Vec<lsp_types::Diagnostic>>,
+}
+
+/// A declaration that may be referred to by name
+enum Declaration<'a> {
+    Model(&'a AstModel<Untyped>, Option<&'a AstModule<Untyped>>),
+    Enum(&'a AstEnum, &'a AstModule<Untyped>),
+    Module(&'a AstModule<Untyped>),
+}
+
+/// An identifier in a document along with the identifier qualifying it (if any), such as `id` in
+/// `AuthContext.id`
+struct Word<'a> {
+    text: &'a str,
+    qualifier: Option<&'a str>,
+    /// The character preceding the identifier (and its qualifier)
+    preceding_char: Option<char>,
+    start: usize,
+    end: usize,
+}
+
+impl Analysis {
+    /// Analyze the project starting at `root_file`, reading the source of each file through
+    /// `read_source` (so that unsaved changes are taken into account)
+    pub(super) fn new(
+        root_file: &Path,
+        subsystem_builders: &[Box<dyn SubsystemBuilder + Send + Sync>],
+        read_source: &dyn Fn(&Path) -> std::io::Result<String>,
+    ) -> Self {
+        // An internal error while analyzing a model that is being edited shouldn't bring down the
+        // server, so report it as a diagnostic instead
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            Self::analyze(root_file, subsystem_builders, read_source)
+        }))
+        .unwrap_or_else(|_| Self {
+            files: HashMap::new(),
+            system: None,
+            typechecked_system: None,
+            diagnostics: HashMap::from([(
+                root_file.to_path_buf(),
+                vec![error_diagnostic(
+                    Range::default(),
+                    "Internal error while analyzing the model".to_string(),
+                )],
+            )]),
+        })
+    }
+
+    fn analyze(
+        root_file: &Path,
+        subsystem_builders: &[Box<dyn SubsystemBuilder + Send + Sync>],
+        read_source: &dyn Fn(&Path) -> std::io::Result<String>,
+    ) -> Self {
+        let mut codemap = CodeMap::new();
+        let mut errors = vec![];
+
+        let system = parser::parse_file_with_sources(root_file, &mut codemap, read_source)
+            .map_err(|error| errors.push(error))
+            .ok();
+
+        let typechecked_system = system.as_ref().and_then(|system| {
+            typechecker::build(subsystem_builders, system.clone())
+                .map_err(|error| errors.push(error))
+                .ok()
+        });
+
+        let mut files = HashMap::new();
+        if let Some(system) = &system {
+            let spans = system
+                .types
+                .iter()
+                .map(|model| model.span)
+                .chain(system.modules.iter().map(|module| module.span));
+
+            for span in spans {
+                let file = codemap.find_file(span.low());
+                files.insert(PathBuf::from(file.name()), file.clone());
+            }
+        }
+
+        let mut diagnostics: HashMap<PathBuf, Vec<lsp_types::Diagnostic>> = HashMap::new();
+        for error in errors {
+            for (path, diagnostic) in lsp_diagnostics(&codemap, root_file, error) {
+                diagnostics.entry(path).or_default().push(diagnostic);
+            }
+        }
+
+        Self {
+            files,
+            system,
+            typechecked_system,
+            diagnostics,
+        }
+    }
+
+    /// Was the project parsed successfully (so that it can answer queries)?
+    pub(super) fn is_parsed(&self) -> bool {
+        self.system.is_some()
+    }
+
+    pub(super) fn diagnostics(&self) -> &HashMap<PathBuf, Vec<lsp_types::Diagnostic>> {
+        &self.diagnostics
+    }
+
+    /// The paths of all files in the project
+    pub(super) fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.keys()
+    }
+
+    /// The location of the declaration referred to at `position` (the imported file for an
+    /// `import` statement, a type, context, enum, or module for a name, or a field for a
+    /// qualified name such as `AuthContext.id`)
+    pub(super) fn definition(
+        &self,
+        path: &Path,
+        source: &str,
+        position: Position,
+    ) -> Option<(PathBuf, Range)> {
+        let offset = position_to_offset(source, position);
+
+        if let Some(import_path) = import_at(source, offset) {
+            let import_path = path.parent()?.join(import_path);
+            return parser::resolve_import_path(import_path)
+                .ok()
+                .map(|path| (path, Range::default()));
+        }
+
+        let word = word_at(source, offset).filter(|word| !word.text.is_empty())?;
+
+        match word.qualifier {
+            Some(qualifier) => {
+                if let Some(model) = self.qualifier_model(qualifier, path, offset) {
+                    let field = model.fields.iter().find(|field| field.name == word.text)?;
+                    return self.name_location(field.span, &field.name, |_, after| {
+                        after.trim_start().starts_with(':')
+                    });
+                }
+
+                // A type qualified by its module (such as `ConcertModule.Venue`)
+                let module = self.module(qualifier)?;
+                let declaration = self.declaration(word.text)?;
+                let declaring_module = match &declaration {
+                    Declaration::Model(_, declaring_module) => *declaring_module,
+                    Declaration::Enum(_, declaring_module) => Some(*declaring_module),
+                    Declaration::Module(_) => None,
+                }?;
+
+                if declaring_module.name == module.name {
+                    self.declaration_location(&declaration)
+                } else {
+                    None
+                }
+            }
+            None => self.declaration_location(&self.declaration(word.text)?),
+        }
+    }
+
+    /// The hover information (in markdown) for the name at `position`
+    pub(super) fn hover(
+        &self,
+        path: &Path,
+        source: &str,
+        position: Position,
+        annotation_specs: &HashMap<String, AnnotationSpec>,
+    ) -> Option<(String, Range)> {
+        let offset = position_to_offset(source, position);
+        let word = word_at(source, offset).filter(|word| !word.text.is_empty())?;
+        let range = Range::new(
+            offset_to_position(source, word.start),
+            offset_to_position(source, word.end),
+        );
+
+        if word.preceding_char == Some('@') {
+            let spec = annotation_specs.get(word.text)?;
+            return Some((
+                format!(
+                    "```exo\n{}\n```\nApplies to: {}",
+                    annotation_signature(word.text, spec),
+                    annotation_targets(spec)
+                ),
+                range,
+            ));
+        }
+
+        // A field accessed through a qualifier (such as `AuthContext.id` or `self.venue`), or a
+        // field declared in the enclosing type
+        let model = match word.qualifier {
+            Some(qualifier) => self.qualifier_model(qualifier, path, offset),
+            None => self.enclosing_model(path, offset),
+        };
+        let field =
+            model.and_then(|model| model.fields.iter().find(|field| field.name == word.text));
+
+        if let Some(field) = field {
+            let declaration = self.source_text(field.span)?.trim().to_string();
+            let type_name = underlying_type_name(&field.typ);
+
+            let mut contents = format!("```exo\n{declaration}\n```");
+            if let Some(description) = self.type_description(&type_name) {
+                contents.push_str(&format!("\n`{type_name}` is {description}"));
+            }
+            return Some((contents, range));
+        }
+
+        if word.qualifier.is_some() && model.is_some() {
+            // A qualified name that isn't a field of the qualifying type
+            return None;
+        }
+
+        match self.declaration(word.text) {
+            Some(declaration) => Some((self.declaration_summary(&declaration), range)),
+            None => {
+                let description = self.type_description(word.text)?;
+                Some((format!("`{}` is {description}", word.text), range))
+            }
+        }
+    }
+
+    /// The completions at `position`: annotations after `@`, fields after a qualifier such as
+    /// `AuthContext.` or `self.`, types after a module name, and type names otherwise
+    pub(super) fn completions(
+        &self,
+        path: &Path,
+        source: &str,
+        position: Position,
+        annotation_specs: &HashMap<String, AnnotationSpec>,
+    ) -> Vec<CompletionItem> {
+        let offset = position_to_offset(source, position);
+        let word = match word_at(source, offset) {
+            Some(word) => word,
+            None => return vec![],
+        };
+
+        if word.preceding_char == Some('@') {
+            let mut items: Vec<_> = annotation_specs
+                .iter()
+                .map(|(name, spec)| CompletionItem {
+                    label: name.clone(),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    detail: Some(annotation_signature(name, spec)),
+                    documentation: Some(lsp_types::Documentation::String(format!(
+                        "Applies to: {}",
+                        annotation_targets(spec)
+                    ))),
+                    ..Default::default()
+                })
+                .collect();
+            items.sort_by(|a, b| a.label.cmp(&b.label));
+            return items;
+        }
+
+        if let Some(qualifier) = word.qualifier {
+            if let Some(model) = self.qualifier_model(qualifier, path, offset) {
+                return model
+                    .fields
+                    .iter()
+                    .map(|field| CompletionItem {
+                        label: field.name.clone(),
+                        kind: Some(CompletionItemKind::FIELD),
+                        detail: Some(field_type_name(&field.typ)),
+                        ..Default::default()
+                    })
+                    .collect();
+            }
+
+            return match self.module(qualifier) {
+                Some(module) => module
+                    .types
+                    .iter()
+                    .map(model_completion)
+                    .chain(module.enums.iter().map(enum_completion))
+                    .collect(),
+                None => vec![],
+            };
+        }
+
+        let builtin_types =
+            typechecker::builtin_type_names()
+                .into_iter()
+                .map(|name| CompletionItem {
+                    label: name,
+                    kind: Some(CompletionItemKind::CLASS),
+                    detail: Some("primitive type".to_string()),
+                    ..Default::default()
+                });
+
+        let declared_types = self
+            .models()
+            .map(|(model, _)| model_completion(model))
+            .chain(
+                self.modules()
+                    .flat_map(|module| module.enums.iter().map(enum_completion)),
+            );
+
+        let mut items: Vec<_> = builtin_types.chain(declared_types).collect();
+        items.sort_by(|a, b| a.label.cmp(&b.label));
+        items
+    }
+
+    fn modules(&self) -> impl Iterator<Item = &AstModule<Untyped>> {
+        self.system.iter().flat_map(|system| system.modules.iter())
+    }
+
+    /// All types and contexts, along with the module that declares them (`None` for contexts)
+    fn models(&self) -> impl Iterator<Item = (&AstModel<Untyped>, Option<&AstModule<Untyped>>)> {
+        let contexts = self
+            .system
+            .iter()
+            .flat_map(|system| system.types.iter().map(|model| (model, None)));
+
+        let types = self
+            .modules()
+            .flat_map(|module| module.types.iter().map(move |model| (model, Some(module))));
+
+        contexts.chain(types)
+    }
+
+    fn module(&self, name: &str) -> Option<&AstModule<Untyped>> {
+        self.modules().find(|module| module.name == name)
+    }
+
+    fn declaration(&self, name: &str) -> Option<Declaration> {
+        self.models()
+            .find(|(model, _)| model.name == name)
+            .map(|(model, module)| Declaration::Model(model, module))
+            .or_else(|| {
+                self.modules().find_map(|module| {
+                    module
+                        .enums
+                        .iter()
+                        .find(|ast_enum| ast_enum.name == name)
+                        .map(|ast_enum| Declaration::Enum(ast_enum, module))
+                })
+            })
+            .or_else(|| self.module(name).map(Declaration::Module))
+    }
+
+    /// The type or context whose fields are accessed through `qualifier` (`self` refers to the
+    /// type enclosing `offset`)
+    fn qualifier_model(
+        &self,
+        qualifier: &str,
+        path: &Path,
+        offset: usize,
+    ) -> Option<&AstModel<Untyped>> {
+        if qualifier == "self" {
+            self.enclosing_model(path, offset)
+        } else {
+            self.models()
+                .find(|(model, _)| model.name == qualifier)
+                .map(|(model, _)| model)
+        }
+    }
+
+    /// The type or context declaration enclosing `offset` in the file at `path`
+    fn enclosing_model(&self, path: &Path, offset: usize) -> Option<&AstModel<Untyped>> {
+        let file = self.files.get(path)?;
+        let pos = file.span.low() + offset as u64;
+
+        self.models()
+            .map(|(model, _)| model)
+            .find(|model| span_contains(model.span, pos))
+    }
+
+    fn declaration_location(&self, declaration: &Declaration) -> Option<(PathBuf, Range)> {
+        let (span, name, keywords): (_, _, &[&str]) = match declaration {
+            Declaration::Model(model, _) => (model.span, &model.name, &["type", "context"]),
+            Declaration::Enum(ast_enum, _) => (ast_enum.span, &ast_enum.name, &["enum"]),
+            Declaration::Module(module) => (module.span, &module.name, &["module"]),
+        };
+
+        self.name_location(span, name, |before, _| {
+            let before = before.trim_end();
+            keywords.iter().any(|keyword| before.ends_with(keyword))
+        })
+    }
+
+    /// The location of `name` within `span`, where `is_name` tells apart the occurrence of `name`
+    /// being declared (given the text before and after the occurrence) from others (for example,
+    /// in annotations). Falls back to the whole span.
+    fn name_location(
+        &self,
+        span: Span,
+        name: &str,
+        is_name: impl Fn(&str, &str) -> bool,
+    ) -> Option<(PathBuf, Range)> {
+        let file = self.file_of(span)?;
+        let start = (span.low() - file.span.low()) as usize;
+        let end = (span.high() - file.span.low()) as usize;
+        let text = &file.source()[start..end];
+
+        let (name_start, name_end) = text
+            .match_indices(name)
+            .map(|(index, _)| index)
+            .find(|&index| {
+                let before = &text[..index];
+                let after = &text[index + name.len()..];
+                !before.ends_with(is_identifier_char)
+                    && !after.starts_with(is_identifier_char)
+                    && is_name(before, after)
+            })
+            .map(|index| (start + index, start + index + name.len()))
+            .unwrap_or((start, end));
+
+        Some((
+            PathBuf::from(file.name()),
+            Range::new(
+                offset_to_position(file.source(), name_start),
+                offset_to_position(file.source(), name_end),
+            ),
+        ))
+    }
+
+    fn file_of(&self, span: Span) -> Option<&Arc<File>> {
+        self.files.values().find(|file| {
+            span_contains(file.span, span.low()) && span_contains(file.span, span.high())
+        })
+    }
+
+    fn source_text(&self, span: Span) -> Option<&str> {
+        self.file_of(span).map(|file| file.source_slice(span))
+    }
+
+    fn declaration_summary(&self, declaration: &Declaration) -> String {
+        match declaration {
+            Declaration::Model(model, module) => {
+                let keyword = match model.kind {
+                    AstModelKind::Type => "type",
+                    AstModelKind::Context => "context",
+                };
+                let fields: String = model
+                    .fields
+                    .iter()
+                    .map(|field| format!("  {}: {}\n", field.name, field_type_name(&field.typ)))
+                    .collect();
+
+                let mut summary = format!("```exo\n{keyword} {} {{\n{fields}}}\n```", model.name);
+                if let Some(module) = module {
+                    summary.push_str(&format!("\nDeclared in module `{}`", module.name));
+                }
+                summary
+            }
+            Declaration::Enum(ast_enum, module) => {
+                let values: Vec<_> = ast_enum
+                    .fields
+                    .iter()
+                    .map(|field| field.name.as_str())
+                    .collect();
+                format!(
+                    "```exo\nenum {} {{\n  {}\n}}\n```\nDeclared in module `{}`",
+                    ast_enum.name,
+                    values.join(",\n  "),
+                    module.name
+                )
+            }
+            Declaration::Module(module) => {
+                let types: Vec<_> = module
+                    .types
+                    .iter()
+                    .map(|model| format!("`{}`", model.name))
+                    .collect();
+                let mut summary = format!("```exo\nmodule {}\n```", module.name);
+                if !types.is_empty() {
+                    summary.push_str(&format!("\nTypes: {}", types.join(", ")));
+                }
+                summary
+            }
+        }
+    }
+
+    /// Describe the type named `type_name`. Uses the typechecked system (if the project
+    /// typechecks) to report what the name resolves to, and the parsed system otherwise.
+    fn type_description(&self, type_name: &str) -> Option<String> {
+        let declaring_module = |name: &str| match self.declaration(name) {
+            Some(Declaration::Model(_, Some(module)) | Declaration::Enum(_, module)) => {
+                format!(" declared in module `{}`", module.name)
+            }
+            _ => String::new(),
+        };
+
+        match &self.typechecked_system {
+            Some(typechecked_system) => match typechecked_system.types.get_by_key(type_name)? {
+                Type::Primitive(PrimitiveType::Enum(name, values)) => Some(format!(
+                    "an enum{} with values {}",
+                    declaring_module(name),
+                    values.join(", ")
+                )),
+                Type::Primitive(_) => Some("a primitive type".to_string()),
+                Type::Composite(model) => Some(match model.kind {
+                    AstModelKind::Context => "a context".to_string(),
+                    AstModelKind::Type => format!("a type{}", declaring_module(&model.name)),
+                }),
+                _ => None,
+            },
+            None => match self.declaration(type_name) {
+                Some(Declaration::Model(model, _)) => Some(match model.kind {
+                    AstModelKind::Context => "a context".to_string(),
+                    AstModelKind::Type => format!("a type{}", declaring_module(type_name)),
+                }),
+                Some(Declaration::Enum(..)) => {
+                    Some(format!("an enum{}", declaring_module(type_name)))
+                }
+                Some(Declaration::Module(_)) => None,
+                None => typechecker::builtin_type_names()
+                    .iter()
+                    .any(|name| name == type_name)
+                    .then(|| "a primitive type".to_string()),
+            },
+        }
+    }
+}
+
+fn model_completion(model: &AstModel<Untyped>) -> CompletionItem {
+    let (kind, detail) = match model.kind {
+        AstModelKind::Type => (CompletionItemKind::STRUCT, "type"),
+        AstModelKind::Context => (CompletionItemKind::INTERFACE, "context"),
+    };
+
+    CompletionItem {
+        label: model.name.clone(),
+        kind: Some(kind),
+        detail: Some(detail.to_string()),
+        ..Default::default()
+    }
+}
+
+fn enum_completion(ast_enum: &AstEnum) -> CompletionItem {
+    CompletionItem {
+        label: ast_enum.name.clone(),
+        kind: Some(CompletionItemKind::ENUM),
+        detail: Some("enum".to_string()),
+        ..Default::default()
+    }
+}
+
+/// The type of a field as written (for example, `Set<Concert>` or `concerts.Venue?`)
+fn field_type_name(typ: &AstFieldType<Untyped>) -> String {
+    match typ {
+        AstFieldType::Plain(module, name, params, _, _) => {
+            let name = match module {
+                Some(module) => format!("{module}.{name}"),
+                None => name.clone(),
+            };
+
+            if params.is_empty() {
+                name
+            } else {
+                let params: Vec<_> = params.iter().map(field_type_name).collect();
+                format!("{name}<{}>", params.join(", "))
+            }
+        }
+        AstFieldType::Optional(underlying) => format!("{}?", field_type_name(underlying)),
+    }
+}
+
+/// The name of the type underlying a field type (for example, `Concert` for `Set<Concert>?`)
+fn underlying_type_name(typ: &AstFieldType<Untyped>) -> String {
+    match typ {
+        AstFieldType::Plain(_, _, params, _, _) if !params.is_empty() => {
+            underlying_type_name(&params[0])
+        }
+        AstFieldType::Plain(_, name, _, _, _) => name.clone(),
+        AstFieldType::Optional(underlying) => underlying_type_name(underlying),
+    }
+}
+
+/// The forms of an annotation (for example, `@cache(maxAge: ..., scope?: ...)`)
+fn annotation_signature(name: &str, spec: &AnnotationSpec) -> String {
+    let mut forms = vec![];
+
+    if spec.no_params {
+        forms.push(format!("@{name}"));
+    }
+    if spec.single_params {
+        forms.push(format!("@{name}(...)"));
+    }
+    if let Some(mapped_params) = spec.mapped_params {
+        let params: Vec<_> = mapped_params
+            .iter()
+            .map(|param| {
+                let optional = if param.optional { "?" } else { "" };
+                format!("{}{optional}: ...", param.name)
+            })
+            .collect();
+        forms.push(format!("@{name}({})", params.join(", ")));
+    }
+
+    forms.join("\n")
+}
+
+fn annotation_targets(spec: &AnnotationSpec) -> String {
+    let targets: Vec<_> = spec
+        .targets
+        .iter()
+        .map(|target| format!("{target:?}").to_lowercase())
+        .collect();
+    targets.join(", ")
+}
+
+/// Convert an error from building the system to LSP diagnostics (along with the file each belongs
+/// to). Errors without a location are reported at the start of the root file.
+fn lsp_diagnostics(
+    codemap: &CodeMap,
+    root_file: &Path,
+    error: ParserError,
+) -> Vec<(PathBuf, lsp_types::Diagnostic)> {
+    let diagnostics = match error {
+        ParserError::Diagnosis(diagnostics) => diagnostics,
+        error => {
+            return vec![(
+                root_file.to_path_buf(),
+                error_diagnostic(Range::default(), error.to_string()),
+            )]
+        }
+    };
+
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let Diagnostic {
+                level,
+                message,
+                code,
+                spans,
+            } = diagnostic;
+
+            let primary = spans
+                .iter()
+                .find(|span_label| span_label.style == SpanStyle::Primary)
+                .or(spans.first());
+
+            let (path, range) = match primary {
+                Some(span_label) => {
+                    let file = codemap.find_file(span_label.span.low());
+                    let start = (span_label.span.low() - file.span.low()) as usize;
+                    let end = (span_label.span.high() - file.span.low()) as usize;
+                    (
+                        PathBuf::from(file.name()),
+                        Range::new(
+                            offset_to_position(file.source(), start),
+                            offset_to_position(file.source(), end),
+                        ),
+                    )
+                }
+                None => (root_file.to_path_buf(), Range::default()),
+            };
+
+            let message = match primary.and_then(|span_label| span_label.label.as_ref()) {
+                Some(label) => format!("{message} ({label})"),
+                None => message,
+            };
+
+            let severity = match level {
+                Level::Bug | Level::Error => DiagnosticSeverity::ERROR,
+                Level::Warning => DiagnosticSeverity::WARNING,
+                Level::Note => DiagnosticSeverity::INFORMATION,
+                Level::Help => DiagnosticSeverity::HINT,
+            };
+
+            (
+                path,
+                lsp_types::Diagnostic {
+                    range,
+                    severity: Some(severity),
+                    code: code.map(NumberOrString::String),
+                    source: Some("exo".to_string()),
+                    message,
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
+
+fn error_diagnostic(range: Range, message: String) -> lsp_types::Diagnostic {
+    lsp_types::Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("exo".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+fn span_contains(span: Span, pos: Pos) -> bool {
+    span.low() <= pos && pos <= span.high()
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The start of the identifier ending at `end`
+fn identifier_start(source: &str, end: usize) -> usize {
+    source[..end]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !is_identifier_char(*c))
+        .map(|(index, c)| index + c.len_utf8())
+        .unwrap_or(0)
+}
+
+/// The (possibly empty) identifier at `offset`
+fn word_at(source: &str, offset: usize) -> Option<Word> {
+    if offset > source.len() || !source.is_char_boundary(offset) {
+        return None;
+    }
+
+    let start = identifier_start(source, offset);
+    let end = source[offset..]
+        .find(|c: char| !is_identifier_char(c))
+        .map(|index| offset + index)
+        .unwrap_or(source.len());
+
+    let (qualifier, qualified_start) = match source[..start].strip_suffix('.') {
+        Some(before_dot) => {
+            let qualifier_start = identifier_start(source, before_dot.len());
+            let qualifier = &source[qualifier_start..before_dot.len()];
+            if qualifier.is_empty() {
+                (None, start)
+            } else {
+                (Some(qualifier), qualifier_start)
+            }
+        }
+        None => (None, start),
+    };
+
+    Some(Word {
+        text: &source[start..end],
+        qualifier,
+        preceding_char: source[..qualified_start].chars().next_back(),
+        start,
+        end,
+    })
+}
+
+/// The path of the `import` statement at `offset` (if the line at `offset` is one)
+fn import_at(source: &str, offset: usize) -> Option<&str> {
+    let line_start = source[..offset]
+        .rfind('\n')
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    let line_end = source[offset..]
+        .find('\n')
+        .map(|index| offset + index)
+        .unwrap_or(source.len());
+
+    let line = source[line_start..line_end].trim();
+    let path = line.strip_prefix("import")?.trim().strip_prefix('"')?;
+    path.split('"').next()
+}
+
+/// Convert a byte offset into an LSP position (whose character offsets count UTF-16 code units)
+pub(super) fn offset_to_position(source: &str, offset: usize) -> Position {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let character = before[line_start..].encode_utf16().count();
+
+    Position::new(line as u32, character as u32)
+}
+
+/// Convert an LSP position into a byte offset (clamped to the end of its line)
+pub(super) fn position_to_offset(source: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match source[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return source.len(),
+        }
+    }
+
+    let line_end = source[line_start..]
+        .find('\n')
+        .map(|index| line_start + index)
+        .unwrap_or(source.len());
+
+    let mut character = 0;
+    for (index, c) in source[line_start..line_end].char_indices() {
+        if character >= position.character as usize {
+            return line_start + index;
+        }
+        character += c.len_utf16();
+    }
+
+    line_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::commands::build::static_builders;
+
+    const MODEL: &str = r#"context AuthContext {
+  @jwt id: Int
+  @jwt role: String
+}
+
+@postgres
+module ConcertModule {
+  @access(AuthContext.role == "admin")
+  type Concert {
+    @pk id: Int = autoIncrement()
+    title: String
+    venue: Venue
+  }
+
+  @access(true)
+  type Venue {
+    @pk id: Int = autoIncrement()
+    name: String
+    concerts: Set<Concert>?
+  }
+}
+"#;
+
+    fn analyze(source: &'static str) -> Analysis {
+        Analysis::new(
+            Path::new("/project/src/index.exo"),
+            &static_builders(),
+            &|_| Ok(source.to_string()),
+        )
+    }
+
+    /// The position of the `nth` occurrence of `text` (plus `delta` characters) in `MODEL`
+    fn position_of(text: &str, nth: usize, delta: usize) -> Position {
+        let (offset, _) = MODEL.match_indices(text).nth(nth).unwrap();
+        offset_to_position(MODEL, offset + delta)
+    }
+
+    #[test]
+    fn diagnostics() {
+        let analysis = analyze(MODEL);
+        assert!(analysis.diagnostics().is_empty());
+
+        let analysis = analyze("context AuthContext {\n  @jwt id: Unknown\n}\n");
+        let diagnostics = &analysis.diagnostics()[Path::new("/project/src/index.exo")];
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.line, 1);
+    }
+
+    #[test]
+    fn definition() {
+        let analysis = analyze(MODEL);
+        let path = Path::new("/project/src/index.exo");
+
+        // `Venue` in `venue: Venue`
+        let (file, range) = analysis
+            .definition(path, MODEL, position_of("venue: Venue", 0, 8))
+            .unwrap();
+        assert_eq!(file, path);
+        assert_eq!(range.start, position_of("Venue {", 0, 0));
+
+        // `role` in `AuthContext.role`
+        let (_, range) = analysis
+            .definition(path, MODEL, position_of("AuthContext.role", 0, 13))
+            .unwrap();
+        assert_eq!(range.start, position_of("role: String", 0, 0));
+    }
+
+    #[test]
+    fn hover() {
+        let analysis = analyze(MODEL);
+        let path = Path::new("/project/src/index.exo");
+        let annotation_specs = typechecker::annotation_specs(&static_builders());
+
+        let (contents, _) = analysis
+            .hover(
+                path,
+                MODEL,
+                position_of("concerts: ", 0, 1),
+                &annotation_specs,
+            )
+            .unwrap();
+        assert!(contents.contains("concerts: Set<Concert>?"));
+        assert!(contents.ends_with("`Concert` is a type declared in module `ConcertModule`"));
+
+        let (contents, _) = analysis
+            .hover(path, MODEL, position_of("@access", 0, 2), &annotation_specs)
+            .unwrap();
+        assert!(contents.starts_with("```exo\n@access"));
+    }
+
+    #[test]
+    fn completions() {
+        let analysis = analyze(MODEL);
+        let path = Path::new("/project/src/index.exo");
+        let annotation_specs = typechecker::annotation_specs(&static_builders());
+
+        let labels = |position| -> Vec<String> {
+            analysis
+                .completions(path, MODEL, position, &annotation_specs)
+                .into_iter()
+                .map(|item| item.label)
+                .collect()
+        };
+
+        // After `AuthContext.`
+        assert_eq!(
+            labels(position_of("AuthContext.role", 0, 12)),
+            ["id", "role"]
+        );
+
+        // After `@`
+        let annotations = labels(position_of("@pk", 0, 1));
+        assert!(annotations.contains(&"pk".to_string()));
+        assert!(annotations.contains(&"access".to_string()));
+
+        // A type name
+        let types = labels(position_of("venue: Venue", 0, 7));
+        assert!(types.contains(&"Venue".to_string()));
+        assert!(types.contains(&"Int".to_string()));
+    }
+
+    #[test]
+    fn position_conversion() {
+        let source = "a\néb\n";
+        assert_eq!(offset_to_position(source, 4), Position::new(1, 2));
+        assert_eq!(position_to_offset(source, Position::new(1, 2)), 4);
+        assert_eq!(position_to_offset(source, Position::new(1, 10)), 5);
+        assert_eq!(
+            position_to_offset(source, Position::new(5, 0)),
+            source.len()
+        );
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! A language server for exo files (communicating with the editor over stdio)
+
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::{ArgMatches, Command};
+
+use super::command::CommandDefinition;
+
+mod analysis;
+mod server;
+
+pub struct LspCommandDefinition {}
+
+#[async_trait]
+impl CommandDefinition for LspCommandDefinition {
+    fn command(&self) -> Command {
+        Command::new("lsp").about("Run the language server for exo files (over stdio)")
+    }
+
+    /// Serve diagnostics, go-to-definition, hover, and completion for exo files until the editor
+    /// shuts the server down
+    async fn execute(&self, _matches: &ArgMatches) -> Result<()> {
+        server::serve().await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use builder::typechecker;
+use core_model_builder::typechecker::annotation::AnnotationSpec;
+use core_plugin_interface::interface::SubsystemBuilder;
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{
+        CompletionOptions, CompletionParams, CompletionResponse, Diagnostic,
+        DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+        DidSaveTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover,
+        HoverContents, HoverParams, HoverProviderCapability, InitializeParams, InitializeResult,
+        Location, MarkupContent, MarkupKind, OneOf, Position, ServerCapabilities, ServerInfo,
+        TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+    },
+    Client, LanguageServer, LspService, Server,
+};
+
+use crate::commands::{build::static_builders, command::default_model_file};
+
+use super::analysis::Analysis;
+
+pub(super) async fn serve() {
+    let (service, socket) = LspService::new(ExoLanguageServer::new);
+
+    Server::new(tokio::io::stdin(), tokio::io::stdout(), socket)
+        .serve(service)
+        .await;
+}
+
+struct ExoLanguageServer {
+    client: Client,
+    subsystem_builders: Vec<Box<dyn SubsystemBuilder + Send + Sync>>,
+    annotation_specs: HashMap<String, AnnotationSpec>,
+    /// The content of open documents (which may have unsaved changes)
+    documents: Mutex<HashMap<PathBuf, String>>,
+    /// The latest successfully parsed analysis of each project (by root file). We keep the last
+    /// parsed one (instead of the latest one) so that navigation and completion keep working
+    /// while a file has syntax errors.
+    analyses: Mutex<HashMap<PathBuf, Analysis>>,
+    /// The files with published diagnostics of each project (by root file), so that we can clear
+    /// them once fixed
+    published: Mutex<HashMap<PathBuf, HashSet<PathBuf>>>,
+}
+
+impl ExoLanguageServer {
+    fn new(client: Client) -> Self {
+        let subsystem_builders = static_builders();
+        let annotation_specs = typechecker::annotation_specs(&subsystem_builders);
+
+        Self {
+            client,
+            subsystem_builders,
+            annotation_specs,
+            documents: Mutex::new(HashMap::new()),
+            analyses: Mutex::new(HashMap::new()),
+            published: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Analyze the project containing the file at `path` and publish its diagnostics
+    async fn on_change(&self, path: PathBuf) {
+        let (root_file, analysis) = self.analyze(&path);
+
+        let mut diagnostics: Vec<(PathBuf, Vec<Diagnostic>)> = analysis
+            .diagnostics()
+            .iter()
+            .map(|(path, diagnostics)| (path.clone(), diagnostics.clone()))
+            .collect();
+
+        {
+            let current: HashSet<_> = diagnostics.iter().map(|(path, _)| path.clone()).collect();
+            let mut published = self.published.lock().unwrap();
+            let previous = published
+                .insert(root_file.clone(), current.clone())
+                .unwrap_or_default();
+
+            diagnostics.extend(
+                previous
+                    .difference(&current)
+                    .map(|path| (path.clone(), vec![])),
+            );
+        }
+
+        if analysis.is_parsed() {
+            self.analyses.lock().unwrap().insert(root_file, analysis);
+        }
+
+        for (path, diagnostics) in diagnostics {
+            if let Ok(uri) = Url::from_file_path(&path) {
+                self.client
+                    .publish_diagnostics(uri, diagnostics, None)
+                    .await;
+            }
+        }
+    }
+
+    /// Analyze the project containing the file at `path` (or the file by itself if no project
+    /// imports it) and return the project's root file along with the analysis
+    fn analyze(&self, path: &Path) -> (PathBuf, Analysis) {
+        let documents = self.documents.lock().unwrap().clone();
+        let read_source = |path: &Path| match documents.get(path) {
+            Some(source) => Ok(source.clone()),
+            None => fs::read_to_string(path),
+        };
+
+        let root_file = project_root_file(path);
+        let analysis = Analysis::new(&root_file, &self.subsystem_builders, &read_source);
+
+        if root_file != path && analysis.is_parsed() && !analysis.files().any(|file| file == path) {
+            let analysis = Analysis::new(path, &self.subsystem_builders, &read_source);
+            (path.to_path_buf(), analysis)
+        } else {
+            (root_file, analysis)
+        }
+    }
+
+    /// Run `query` against the analysis of the project containing the document at `uri`
+    fn query<T>(
+        &self,
+        uri: &Url,
+        query: impl FnOnce(&Analysis, &Path, &str) -> Option<T>,
+    ) -> Option<T> {
+        let path = document_path(uri)?;
+        let source = match self.documents.lock().unwrap().get(&path) {
+            Some(source) => source.clone(),
+            None => fs::read_to_string(&path).ok()?,
+        };
+
+        let analyses = self.analyses.lock().unwrap();
+        let analysis = analyses
+            .values()
+            .find(|analysis| analysis.files().any(|file| file == &path))?;
+
+        query(analysis, &path, &source)
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for ExoLanguageServer {
+    async fn initialize(&self, _params: InitializeParams) -> Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                definition_provider: Some(OneOf::Left(true)),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec!["@".to_string(), ".".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            server_info: Some(ServerInfo {
+                name: "exo".to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        if let Some(path) = document_path(&params.text_document.uri) {
+            self.documents
+                .lock()
+                .unwrap()
+                .insert(path.clone(), params.text_document.text);
+            self.on_change(path).await;
+        }
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // With full synchronization, the (only) change holds the whole document
+        if let (Some(path), Some(change)) = (
+            document_path(&params.text_document.uri),
+            params.content_changes.pop(),
+        ) {
+            self.documents
+                .lock()
+                .unwrap()
+                .insert(path.clone(), change.text);
+            self.on_change(path).await;
+        }
+    }
+
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        if let Some(path) = document_path(&params.text_document.uri) {
+            self.on_change(path).await;
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        if let Some(path) = document_path(&params.text_document.uri) {
+            // Unsaved changes are discarded, so analyze the file as saved
+            self.documents.lock().unwrap().remove(&path);
+            self.on_change(path).await;
+        }
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+
+        Ok(self
+            .query(&position.text_document.uri, |analysis, path, source| {
+                analysis.definition(path, source, position.position)
+            })
+            .and_then(|(path, range)| {
+                let uri = Url::from_file_path(path).ok()?;
+                Some(GotoDefinitionResponse::Scalar(Location::new(uri, range)))
+            }))
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+
+        Ok(self
+            .query(&position.text_document.uri, |analysis, path, source| {
+                analysis.hover(path, source, position.position, &self.annotation_specs)
+            })
+            .map(|(contents, range)| Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: contents,
+                }),
+                range: Some(range),
+            }))
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let position: Position = params.text_document_position.position;
+
+        Ok(self
+            .query(
+                &params.text_document_position.text_document.uri,
+                |analysis, path, source| {
+                    Some(analysis.completions(path, source, position, &self.annotation_specs))
+                },
+            )
+            .map(CompletionResponse::Array))
+    }
+}
+
+/// The (canonical) path of the document at `uri`
+fn document_path(uri: &Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+    Some(path.canonicalize().unwrap_or(path))
+}
+
+/// The root file (`src/index.exo`) of the closest project directory enclosing `path`, or `path`
+/// itself if it isn't in a project
+fn project_root_file(path: &Path) -> PathBuf {
+    path.ancestors()
+        .map(|dir| dir.join(default_model_file()))
+        .find(|root_file| root_file.is_file())
+        .and_then(|root_file| root_file.canonicalize().ok())
+        .unwrap_or_else(|| path.to_path_buf())
+}
@@ -13,6 +13,7 @@ pub(crate) mod build;
 pub(crate) mod command;
 pub(crate) mod deploy;
 pub(crate) mod dev;
+pub(crate) mod lsp;
 pub(crate) mod new;
 pub(crate) mod playground;
 pub(crate) mod schema;
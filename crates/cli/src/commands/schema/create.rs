@@ -20,7 +20,7 @@ use crate::{
     util::open_file_for_output,
 };
 
-use super::util::{self, use_ir_arg};
+use super::util::{self, row_level_security_arg, use_ir_arg};
 
 pub(super) struct CreateCommandDefinition {}
 
@@ -31,11 +31,13 @@ impl CommandDefinition for CreateCommandDefinition {
             .about("Create a database schema from a Exograph model")
             .arg(output_arg())
             .arg(use_ir_arg())
+            .arg(row_level_security_arg())
     }
 
     /// Create a database schema from a exograph model
     async fn execute(&self, matches: &clap::ArgMatches) -> Result<()> {
         let use_ir: bool = matches.get_flag("use-ir");
+        let row_level_security: bool = matches.get_flag("row-level-security");
 
         let model: PathBuf = default_model_file();
         let output: Option<PathBuf> = get(matches, "output");
@@ -45,10 +47,13 @@ impl CommandDefinition for CreateCommandDefinition {
         let mut buffer: Box<dyn Write> = open_file_for_output(output.as_deref())?;
 
         // Creating the schema from the model is the same as migrating from an empty database.
-        let migrations = Migration::from_schemas(
+        let mut migrations = Migration::from_schemas(
             &DatabaseSpec::new(vec![], vec![]),
             &DatabaseSpec::from_database(&postgres_subsystem.database),
         );
+        if row_level_security {
+            migrations.add_row_level_security(&postgres_subsystem)?;
+        }
         migrations.write(&mut buffer, true)?;
 
         Ok(())
@@ -11,20 +11,27 @@ use anyhow::Result;
 use async_trait::async_trait;
 use clap::Command;
 use exo_sql::schema::column_spec::{ColumnSpec, ColumnTypeSpec};
+use exo_sql::schema::constraint::CheckSpec;
 use exo_sql::schema::database_spec::DatabaseSpec;
+use exo_sql::schema::enum_spec::EnumSpec;
+use exo_sql::schema::index_spec::IndexKind;
 use exo_sql::schema::issue::WithIssues;
 use exo_sql::schema::table_spec::TableSpec;
+use exo_sql::{
+    PhysicalTableName, ReferentialAction, VectorDistanceFunction, DEFAULT_TEXT_SEARCH_LANGUAGE,
+};
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::path::PathBuf;
 
-use heck::ToUpperCamelCase;
+use heck::{ToLowerCamelCase, ToSnakeCase, ToUpperCamelCase};
 
 use exo_sql::schema::issue::Issue;
 
 use crate::commands::command::{database_arg, get, output_arg, CommandDefinition};
 use crate::util::open_file_for_output;
 
-use super::util;
+use super::{migrate::open_database, util};
 
 pub(super) struct ImportCommandDefinition {}
 
@@ -40,15 +47,16 @@ impl CommandDefinition for ImportCommandDefinition {
     /// Create a exograph model file based on a database schema
     async fn execute(&self, matches: &clap::ArgMatches) -> Result<()> {
         let output: Option<PathBuf> = get(matches, "output");
+        let database: Option<String> = get(matches, "database");
         let mut issues = Vec::new();
-        let mut schema = import_schema().await?;
+        let mut schema = import_schema(database.as_deref()).await?;
         let mut model = schema.value.to_model();
 
         issues.append(&mut schema.issues);
         issues.append(&mut model.issues);
 
         let mut buffer: Box<dyn std::io::Write> = open_file_for_output(output.as_deref())?;
-        buffer.write_all(schema.value.to_model().value.as_bytes())?;
+        buffer.write_all(model.value.as_bytes())?;
 
         for issue in &issues {
             eprintln!("{issue}");
@@ -62,8 +70,8 @@ impl CommandDefinition for ImportCommandDefinition {
     }
 }
 
-async fn import_schema() -> Result<WithIssues<DatabaseSpec>> {
-    let database_client = util::database_manager_from_env().await?;
+async fn import_schema(database: Option<&str>) -> Result<WithIssues<DatabaseSpec>> {
+    let database_client = open_database(database).await?;
     let client = database_client.get_client().await?;
     let database = DatabaseSpec::from_live_database(&client).await?;
     Ok(database)
@@ -73,21 +81,36 @@ pub trait ToModel {
     fn to_model(&self) -> WithIssues<String>;
 }
 
-/// Converts the name of a SQL table to a exograph model name (for example, concert_artist -> ConcertArtist).
-fn to_model_name(name: &str) -> String {
-    name.to_upper_camel_case()
-}
+const MODULE_NAME: &str = "Database";
 
 impl ToModel for DatabaseSpec {
-    /// Converts the schema specification to a exograph file.
+    /// Converts the schema specification to a exograph file (a `@postgres` module with a type for
+    /// each table).
     fn to_model(&self) -> WithIssues<String> {
+        let context = ImportContext::new(self);
         let mut issues = Vec::new();
-        let stmt = self.tables.iter().fold(String::new(), |mut acc, table| {
-            let mut model = table.to_model();
+
+        let mut stmt = format!("@postgres\nmodule {MODULE_NAME} {{\n");
+
+        for enum_spec in self.enums.iter() {
+            let _ = write!(stmt, "{}\n\n", enum_to_model(enum_spec));
+        }
+
+        for table in self.tables.iter() {
+            let mut model = context.table_to_model(table);
             issues.append(&mut model.issues);
-            let _ = write!(acc, "{}\n\n", model.value);
-            acc
-        });
+            let _ = write!(stmt, "{}\n\n", model.value);
+        }
+
+        // Remove the blank line after the last type
+        let mut stmt = stmt.trim_end().to_string();
+        stmt.push_str("\n}\n");
+
+        if !self.tables.is_empty() {
+            issues.push(Issue::Hint(
+                "all types have been imported with `@access(false)`; update the access rules to expose them".to_string(),
+            ));
+        }
 
         WithIssues {
             value: stmt,
@@ -96,77 +119,987 @@ impl ToModel for DatabaseSpec {
     }
 }
 
-impl ToModel for TableSpec {
-    /// Converts the table specification to a exograph model.
-    fn to_model(&self) -> WithIssues<String> {
-        let mut issues = Vec::new();
+fn enum_to_model(enum_spec: &EnumSpec) -> String {
+    let variants: String = enum_spec
+        .variants
+        .iter()
+        .map(|variant| format!("    {variant}\n"))
+        .collect();
 
-        let table_annot = match &self.name.schema {
-            Some(schema) => format!("@table(name=\"{}\", schema=\"{}\")", self.name.name, schema),
-            None => format!("@table(\"{}\")", self.name.name),
+    format!(
+        "  enum {} {{\n{}  }}",
+        enum_spec.name.to_upper_camel_case(),
+        variants
+    )
+}
+
+/// The names of the type corresponding to a table
+struct ModelName {
+    /// The singular name (for example, `Concert`) used as the type name
+    name: String,
+    /// The plural name (for example, `Concerts`) used for collection queries and the default
+    /// table name
+    plural: String,
+}
+
+impl ModelName {
+    fn new(table_name: &str) -> Self {
+        let plural = table_name.to_upper_camel_case();
+        let name = to_singular(&plural);
+
+        // A table name that isn't in the plural form (for example, `person`)
+        let plural = if plural == name {
+            to_plural(&name)
+        } else {
+            plural
         };
-        let column_stmts = self.columns.iter().fold(String::new(), |mut acc, c| {
-            let mut model = c.to_model();
-            issues.append(&mut model.issues);
-            let _ = writeln!(acc, "  {}", model.value);
-            acc
-        });
 
-        // not a robust check
-        if self.name.name.ends_with('s') {
+        Self { name, plural }
+    }
+
+    /// The name of the table that the model builder will compute for this type (unless overridden
+    /// using `@table`)
+    fn default_table_name(&self) -> String {
+        self.plural.to_snake_case()
+    }
+}
+
+/// Converts a plural name to singular (for example, `Concerts` -> `Concert` and `Categories` ->
+/// `Category`).
+fn to_singular(plural: &str) -> String {
+    if let Some(stem) = plural.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if ["sses", "shes", "ches", "xes", "zzes"]
+        .iter()
+        .any(|suffix| plural.ends_with(suffix))
+    {
+        plural[..plural.len() - 2].to_string()
+    } else if plural.ends_with("ss") {
+        plural.to_string()
+    } else {
+        plural
+            .strip_suffix('s')
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or(plural)
+            .to_string()
+    }
+}
+
+/// Converts a singular name to plural (for example, `Category` -> `Categories`).
+fn to_plural(singular: &str) -> String {
+    let is_vowel = |c: char| "aeiouAEIOU".contains(c);
+
+    if let Some(stem) = singular.strip_suffix('y') {
+        if stem.chars().last().is_some_and(|c| !is_vowel(c)) {
+            return format!("{stem}ies");
+        }
+    }
+
+    if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| singular.ends_with(suffix))
+    {
+        format!("{singular}es")
+    } else {
+        format!("{singular}s")
+    }
+}
+
+/// Information about all tables needed to convert any one of them (the names of the types, and
+/// the foreign keys that lead to fields on both sides of a relation).
+struct ImportContext<'a> {
+    database: &'a DatabaseSpec,
+    model_names: HashMap<&'a PhysicalTableName, ModelName>,
+}
+
+impl<'a> ImportContext<'a> {
+    fn new(database: &'a DatabaseSpec) -> Self {
+        let model_names = database
+            .tables
+            .iter()
+            .map(|table| {
+                let model_name = ModelName::new(&table.name.name);
+
+                // Tables with the same name in different schemas need distinct type names, so
+                // prefix the name with the schema
+                let is_ambiguous = database
+                    .tables
+                    .iter()
+                    .filter(|other| ModelName::new(&other.name.name).name == model_name.name)
+                    .count()
+                    > 1;
+
+                let model_name = match &table.name.schema {
+                    Some(schema) if is_ambiguous => {
+                        let prefix = schema.to_upper_camel_case();
+                        ModelName {
+                            name: format!("{prefix}{}", model_name.name),
+                            plural: format!("{prefix}{}", model_name.plural),
+                        }
+                    }
+                    _ => model_name,
+                };
+
+                (&table.name, model_name)
+            })
+            .collect();
+
+        Self {
+            database,
+            model_names,
+        }
+    }
+
+    fn model_name(&self, table_name: &PhysicalTableName) -> &ModelName {
+        &self.model_names[table_name]
+    }
+
+    /// The number of foreign keys between two tables (in either direction)
+    fn relation_count(&self, table1: &PhysicalTableName, table2: &PhysicalTableName) -> usize {
+        let references = |from: &PhysicalTableName, to: &PhysicalTableName| {
+            self.database
+                .tables
+                .iter()
+                .filter(|table| &table.name == from)
+                .flat_map(|table| table.columns.iter())
+                .filter(|column| referenced_table(column) == Some(to))
+                .count()
+        };
+
+        if table1 == table2 {
+            references(table1, table2)
+        } else {
+            references(table1, table2) + references(table2, table1)
+        }
+    }
+
+    /// Does the relation through the column need an explicit `@column` annotation (on both sides)
+    /// to let the model builder match the fields of a relation?
+    fn needs_column_annotation(&self, table: &TableSpec, column: &ColumnSpec) -> bool {
+        let Some(foreign_table_name) = referenced_table(column) else {
+            return false;
+        };
+
+        let field_name = field_name(column);
+        column.name != format!("{}_id", field_name.to_snake_case())
+            || self.relation_count(&table.name, foreign_table_name) > 1
+    }
+
+    /// Converts the table specification to a exograph type.
+    fn table_to_model(&self, table: &TableSpec) -> WithIssues<String> {
+        let mut issues = Vec::new();
+        let model_name = self.model_name(&table.name);
+
+        let mut type_annots = vec!["@access(false)".to_string()];
+
+        let is_default_table_name = table.name.name == model_name.default_table_name();
+        match (&table.name.schema, is_default_table_name) {
+            (None, true) => {}
+            (None, false) => type_annots.push(format!("@table(\"{}\")", table.name.name)),
+            (Some(schema), true) => type_annots.push(format!("@table(schema=\"{schema}\")")),
+            (Some(schema), false) => type_annots.push(format!(
+                "@table(name=\"{}\", schema=\"{schema}\")",
+                table.name.name
+            )),
+        }
+
+        if model_name.plural != format!("{}s", model_name.name) {
+            type_annots.push(format!("@plural(\"{}\")", model_name.plural));
+        }
+
+        let table_model_name = table.name.name.to_upper_camel_case();
+        if to_singular(&table_model_name) == table_model_name {
             issues.push(Issue::Hint(format!(
-                "model name `{}` should be changed to singular",
-                to_model_name(&self.name.name)
+                "table name `{}` is not in the plural form; consider renaming the type `{}` (and adding `@plural`)",
+                table.name.fully_qualified_name(),
+                model_name.name
             )));
         }
 
+        // Check constraints follow the naming used by `@range`, `@maxLength`, and `@check`, so we
+        // can map them back to the annotations
+        let table_prefix = table.name.fully_qualified_name_with_sep("_");
+        let check_annot = |check: &CheckSpec, column_name: &str| {
+            let column_prefix = format!("{table_prefix}_{column_name}");
+
+            if check.name == format!("{column_prefix}_max_length_check") {
+                // The column type (`VARCHAR(n)`) already leads to `@maxLength`
+                Some(None)
+            } else if check.name == format!("{column_prefix}_range_check") {
+                Some(Some(match range_from_check(&check.expr, column_name) {
+                    Some((min, max)) => format!("@range(min={min}, max={max})"),
+                    None => format!("@check(\"{}\")", check.expr.replace('"', "\\\"")),
+                }))
+            } else if check.name == format!("{column_prefix}_check") {
+                Some(Some(format!(
+                    "@check(\"{}\")",
+                    check.expr.replace('"', "\\\"")
+                )))
+            } else {
+                None
+            }
+        };
+
+        for check in table.checks.iter() {
+            if check.name == format!("{table_prefix}_check") {
+                type_annots.push(format!("@check(\"{}\")", check.expr.replace('"', "\\\"")));
+            } else if !table
+                .columns
+                .iter()
+                .any(|c| check_annot(check, &c.name).is_some())
+            {
+                issues.push(Issue::Hint(format!(
+                    "check constraint `{}` ({}) on `{}` is not a part of the model; consider adding it using `@check`",
+                    check.name,
+                    check.expr,
+                    table.name.fully_qualified_name()
+                )));
+            }
+        }
+
+        let mut field_stmts = String::new();
+
+        for column in table
+            .columns
+            .iter()
+            .filter(|c| !matches!(c.typ, ColumnTypeSpec::TsVector { .. }))
+        {
+            let mut field = self.column_to_model(table, column);
+            issues.append(&mut field.issues);
+
+            let check_annots = table
+                .checks
+                .iter()
+                .filter_map(|check| check_annot(check, &column.name).flatten());
+
+            let annots: Vec<_> = field.value.annots.into_iter().chain(check_annots).collect();
+
+            let _ = writeln!(
+                field_stmts,
+                "    {}",
+                with_annots(&annots, &field.value.decl)
+            );
+        }
+
+        for field in self.back_reference_fields(table) {
+            let _ = writeln!(field_stmts, "    {field}");
+        }
+
+        let type_annots: String = type_annots
+            .iter()
+            .map(|annot| format!("  {annot}\n"))
+            .collect();
+
         WithIssues {
             value: format!(
-                "{}\nmodel {} {{\n{}}}",
-                table_annot,
-                to_model_name(&self.name.name),
-                column_stmts
+                "{}  type {} {{\n{}  }}",
+                type_annots, model_name.name, field_stmts
             ),
             issues,
         }
     }
-}
 
-impl ToModel for ColumnSpec {
-    /// Converts the column specification to a exograph model.
-    fn to_model(&self) -> WithIssues<String> {
+    /// Converts the column specification to a exograph field.
+    fn column_to_model(&self, table: &TableSpec, column: &ColumnSpec) -> WithIssues<Field> {
         let mut issues = Vec::new();
+        let model_name = self.model_name(&table.name);
 
-        let pk_str = if self.is_pk { " @pk" } else { "" };
-        let autoinc_str = if self.is_auto_increment {
-            " = autoIncrement()"
-        } else {
-            ""
-        };
+        let mut annots = vec![];
+        if column.is_pk {
+            annots.push("@pk".to_string());
+        }
 
-        let (mut data_type, annots) = self.typ.to_model();
-        if let ColumnTypeSpec::ColumnReference {
-            foreign_table_name, ..
-        } = &self.typ
-        {
-            data_type = to_model_name(&data_type);
+        let (mut data_type, type_annots) = column.typ.to_model();
+        let mut field_name = field_name(column);
 
-            issues.push(Issue::Hint(format!(
-                "consider adding a field to `{}` of type `[{}]` to create a one-to-many relationship",
-                foreign_table_name.fully_qualified_name(), to_model_name(&self.name),
-            )));
+        match &column.typ {
+            ColumnTypeSpec::ColumnReference {
+                foreign_table_name,
+                foreign_pk_type,
+                ..
+            } if foreign_table_name == &table.name => {
+                // The model builder can't tell the two sides of a self-referencing relation apart,
+                // so we import only the column's value
+                issues.push(Issue::Warning(format!(
+                    "self-referencing foreign key `{}.{}` is not supported; imported it as a plain field",
+                    table.name.fully_qualified_name(),
+                    column.name
+                )));
+                let (foreign_data_type, foreign_type_annots) = foreign_pk_type.to_model();
+                data_type = foreign_data_type;
+                field_name = column.name.to_lower_camel_case();
+                annots.extend(split_annots(&foreign_type_annots));
+            }
+            ColumnTypeSpec::ColumnReference {
+                foreign_table_name,
+                foreign_pk_type,
+                composite_key_columns,
+                ..
+            } if !composite_key_columns.is_empty() => {
+                // A model can't refer to a type with a composite primary key, so we import only the
+                // values of the foreign key columns (and warn once per foreign key)
+                if composite_key_columns[0].0 == column.name {
+                    let column_names: Vec<_> = composite_key_columns
+                        .iter()
+                        .map(|(column_name, _)| column_name.as_str())
+                        .collect();
+                    issues.push(Issue::Warning(format!(
+                        "composite foreign key `{}({})` referring to `{}` is not supported; imported its columns as plain fields",
+                        table.name.fully_qualified_name(),
+                        column_names.join(", "),
+                        foreign_table_name.fully_qualified_name()
+                    )));
+                }
+                let (foreign_data_type, foreign_type_annots) = foreign_pk_type.to_model();
+                data_type = foreign_data_type;
+                field_name = column.name.to_lower_camel_case();
+                annots.extend(split_annots(&foreign_type_annots));
+            }
+            ColumnTypeSpec::ColumnReference {
+                foreign_table_name,
+                on_delete,
+                ..
+            } => {
+                data_type = self.model_name(foreign_table_name).name.clone();
+
+                if self.needs_column_annotation(table, column) {
+                    annots.push(format!("@column(\"{}\")", column.name));
+                }
+
+                if *on_delete != ReferentialAction::NoAction {
+                    annots.push(format!(
+                        "@relation(onDelete={})",
+                        on_delete.to_sql().replace(' ', "_")
+                    ));
+                }
+            }
+            _ => {
+                if field_name.to_snake_case() != column.name {
+                    annots.push(format!("@column(\"{}\")", column.name));
+                }
+                annots.extend(split_annots(&type_annots));
+            }
+        }
+
+        if !column.unique_constraints.is_empty() {
+            // Unique constraints are named `unique_constraint_<type>_<name>`, where `<name>` is the
+            // name given to `@unique` (or the field name, if none)
+            let prefix = format!("unique_constraint_{}_", model_name.name.to_snake_case());
+
+            let constraint_names: Vec<_> = column
+                .unique_constraints
+                .iter()
+                .map(|constraint| match constraint.strip_prefix(&prefix) {
+                    Some(name) => name.to_string(),
+                    None => {
+                        issues.push(Issue::Hint(format!(
+                            "unique constraint `{constraint}` on `{}` will be renamed to `{prefix}{}`",
+                            table.name.fully_qualified_name(),
+                            constraint.to_snake_case()
+                        )));
+                        constraint.clone()
+                    }
+                })
+                .collect();
+
+            let is_single_column = |constraint: &String| {
+                table
+                    .columns
+                    .iter()
+                    .filter(|c| c.unique_constraints.contains(constraint))
+                    .count()
+                    == 1
+            };
+
+            if constraint_names == [field_name.to_snake_case()]
+                && is_single_column(&column.unique_constraints[0])
+            {
+                annots.push("@unique".to_string());
+            } else {
+                annots.push(format!("@unique({})", quoted_list(&constraint_names)));
+            }
+        }
+
+        let indices: Vec<_> = table
+            .indices
+            .iter()
+            .filter(|index| index.columns.contains(&column.name))
+            .filter(|index| index.index_kind != IndexKind::Gin)
+            .collect();
+
+        if !indices.is_empty() {
+            let default_index_name =
+                format!("{}_{}_idx", model_name.name, field_name).to_ascii_lowercase();
+            let index_names: Vec<_> = indices.iter().map(|index| index.name.clone()).collect();
+
+            if index_names == [default_index_name] {
+                annots.push("@index".to_string());
+            } else {
+                annots.push(format!("@index({})", quoted_list(&index_names)));
+            }
+
+            let distance_function = indices.iter().find_map(|index| match &index.index_kind {
+                IndexKind::HNWS {
+                    distance_function, ..
+                } => Some(distance_function),
+                _ => None,
+            });
+            if let Some(distance_function) = distance_function {
+                if *distance_function != VectorDistanceFunction::default() {
+                    annots.push(format!(
+                        "@distanceFunction(\"{}\")",
+                        distance_function.model_string()
+                    ));
+                }
+            }
+        }
+
+        // Generated text-search columns aren't a part of the model; instead, their source columns
+        // are marked `@searchable`
+        let text_search_language = table.columns.iter().find_map(|c| match &c.typ {
+            ColumnTypeSpec::TsVector {
+                language,
+                source_column,
+            } if source_column == &column.name => Some(language.clone()),
+            _ => None,
+        });
+        match text_search_language {
+            Some(language) if language == DEFAULT_TEXT_SEARCH_LANGUAGE => {
+                annots.push("@searchable".to_string())
+            }
+            Some(language) => annots.push(format!("@searchable(\"{language}\")")),
+            None => {}
         }
 
-        if self.is_nullable {
+        if column.is_nullable {
             data_type += "?"
         }
 
+        let default_value = if column.is_auto_increment {
+            " = autoIncrement()".to_string()
+        } else {
+            match &column.default_value {
+                Some(default_value) => match default_value_to_model(default_value, &column.typ) {
+                    Some(value) => format!(" = {value}"),
+                    None => {
+                        issues.push(Issue::Hint(format!(
+                            "default value `{default_value}` of column `{}.{}` is not a part of the model",
+                            table.name.fully_qualified_name(),
+                            column.name
+                        )));
+                        "".to_string()
+                    }
+                },
+                None => "".to_string(),
+            }
+        };
+
         WithIssues {
-            value: format!(
-                "{}: {}{}{}{}",
-                self.name, data_type, &annots, autoinc_str, pk_str,
-            ),
-            issues: Vec::new(),
+            value: Field {
+                annots,
+                decl: format!("{field_name}: {data_type}{default_value}"),
+            },
+            issues,
+        }
+    }
+
+    /// The fields for the "other side" of foreign keys that refer to the table (a `Set` of the
+    /// referring type or, if the foreign key is unique, an optional referring type)
+    fn back_reference_fields(&self, table: &TableSpec) -> Vec<String> {
+        self.database
+            .tables
+            .iter()
+            .filter(|referring_table| referring_table.name != table.name)
+            .flat_map(|referring_table| {
+                referring_table
+                    .columns
+                    .iter()
+                    .filter(|column| referenced_table(column) == Some(&table.name))
+                    .map(move |column| (referring_table, column))
+            })
+            .map(|(referring_table, column)| {
+                let referring_model_name = self.model_name(&referring_table.name);
+
+                let is_one_to_one = column.unique_constraints.iter().any(|constraint| {
+                    referring_table
+                        .columns
+                        .iter()
+                        .filter(|c| c.unique_constraints.contains(constraint))
+                        .count()
+                        == 1
+                });
+
+                let (back_field_name, back_field_type) = if is_one_to_one {
+                    (
+                        referring_model_name.name.to_lower_camel_case(),
+                        format!("{}?", referring_model_name.name),
+                    )
+                } else {
+                    (
+                        referring_model_name.plural.to_lower_camel_case(),
+                        format!("Set<{}>?", referring_model_name.name),
+                    )
+                };
+
+                if self.needs_column_annotation(referring_table, column) {
+                    // Qualify the name with that of the field on the other side to distinguish
+                    // multiple relations with the same type
+                    format!(
+                        "@column(\"{}\") {}{}: {back_field_type}",
+                        column.name,
+                        field_name(column),
+                        back_field_name.to_upper_camel_case()
+                    )
+                } else {
+                    format!("{back_field_name}: {back_field_type}")
+                }
+            })
+            .collect()
+    }
+}
+
+/// A field declaration along with its annotations
+struct Field {
+    annots: Vec<String>,
+    decl: String,
+}
+
+fn with_annots(annots: &[String], decl: &str) -> String {
+    if annots.is_empty() {
+        decl.to_string()
+    } else {
+        format!("{} {decl}", annots.join(" "))
+    }
+}
+
+/// Split annotations such as ` @precision(10) @scale(2)` returned by `ColumnTypeSpec::to_model`
+fn split_annots(annots: &str) -> Vec<String> {
+    annots
+        .split(" @")
+        .map(str::trim)
+        .filter(|annot| !annot.is_empty())
+        .map(|annot| format!("@{}", annot.trim_start_matches('@')))
+        .collect()
+}
+
+fn quoted_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("\"{name}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The table referred to by a foreign key column (which becomes a relation in the model). Composite
+/// foreign keys are imported as plain fields, so they don't refer to a table here.
+fn referenced_table(column: &ColumnSpec) -> Option<&PhysicalTableName> {
+    match &column.typ {
+        ColumnTypeSpec::ColumnReference {
+            foreign_table_name,
+            composite_key_columns,
+            ..
+        } if composite_key_columns.is_empty() => Some(foreign_table_name),
+        _ => None,
+    }
+}
+
+/// The name of the field for a column (for example, `venue_id` -> `venue` for a foreign key and
+/// `published_on` -> `publishedOn` otherwise)
+fn field_name(column: &ColumnSpec) -> String {
+    let name = match column.typ {
+        ColumnTypeSpec::ColumnReference { .. } => {
+            column.name.strip_suffix("_id").unwrap_or(&column.name)
+        }
+        _ => &column.name,
+    };
+
+    name.to_lower_camel_case()
+}
+
+/// Converts the default value of a column (as reported by Postgres, for example, `'draft'::text`)
+/// to a model's default value (for example, `"draft"`)
+fn default_value_to_model(default_value: &str, typ: &ColumnTypeSpec) -> Option<String> {
+    match default_value {
+        "now()" | "CURRENT_TIMESTAMP" => return Some("now()".to_string()),
+        "gen_random_uuid()" => return Some("generate_uuid()".to_string()),
+        _ => {}
+    }
+
+    match typ.underlying_type() {
+        ColumnTypeSpec::Boolean => {
+            matches!(default_value, "true" | "false").then(|| default_value.to_string())
+        }
+        ColumnTypeSpec::String { .. } => {
+            let (literal, _) = default_value.rsplit_once("::")?;
+            let literal = literal.strip_prefix('\'')?.strip_suffix('\'')?;
+
+            Some(format!(
+                "\"{}\"",
+                literal
+                    .replace("''", "'")
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+            ))
+        }
+        ColumnTypeSpec::Int { .. }
+        | ColumnTypeSpec::Float { .. }
+        | ColumnTypeSpec::Numeric { .. } => {
+            let value = default_value
+                .split("::")
+                .next()?
+                .trim_matches(['(', ')', '\'']);
+
+            // The model doesn't support negative literals
+            (!value.starts_with('-') && value.parse::<f64>().is_ok()).then(|| value.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Extract the bounds from a check expression of the form `column >= min AND column <= max` (as
+/// reported by Postgres, which adds parentheses and may cast negative numbers)
+fn range_from_check(expr: &str, column_name: &str) -> Option<(i64, i64)> {
+    let expr = expr.replace(['(', ')', '"'], "");
+    let (lower, upper) = expr.split_once(" AND ")?;
+
+    let bound = |part: &str, op: &str| -> Option<i64> {
+        let (column, value) = part.trim().split_once(op)?;
+        if column.trim() != column_name {
+            return None;
+        }
+        let value = value.trim();
+        let value = value.split("::").next().unwrap_or(value);
+        value.trim_matches('\'').parse().ok()
+    };
+
+    Some((bound(lower, ">=")?, bound(upper, "<=")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exo_sql::schema::index_spec::IndexSpec;
+    use exo_sql::IntBits;
+    use std::collections::HashSet;
+
+    fn column(name: &str, typ: ColumnTypeSpec) -> ColumnSpec {
+        ColumnSpec {
+            name: name.to_string(),
+            typ,
+            is_pk: false,
+            is_auto_increment: false,
+            is_nullable: false,
+            unique_constraints: vec![],
+            default_value: None,
+            renamed_from: None,
+        }
+    }
+
+    fn pk_column() -> ColumnSpec {
+        ColumnSpec {
+            is_pk: true,
+            is_auto_increment: true,
+            ..column("id", ColumnTypeSpec::Int { bits: IntBits::_32 })
         }
     }
+
+    #[test]
+    fn model_names() {
+        let names = |table_name: &str| {
+            let model_name = ModelName::new(table_name);
+            (model_name.name, model_name.plural)
+        };
+
+        assert_eq!(names("concerts"), ("Concert".into(), "Concerts".into()));
+        assert_eq!(
+            names("concert_artists"),
+            ("ConcertArtist".into(), "ConcertArtists".into())
+        );
+        assert_eq!(
+            names("categories"),
+            ("Category".into(), "Categories".into())
+        );
+        assert_eq!(names("addresses"), ("Address".into(), "Addresses".into()));
+        assert_eq!(names("person"), ("Person".into(), "Persons".into()));
+    }
+
+    #[test]
+    fn default_values() {
+        let string = ColumnTypeSpec::String { max_length: None };
+        let int = ColumnTypeSpec::Int { bits: IntBits::_32 };
+
+        assert_eq!(
+            default_value_to_model("'it''s'::text", &string),
+            Some("\"it's\"".to_string())
+        );
+        assert_eq!(default_value_to_model("42", &int), Some("42".to_string()));
+        assert_eq!(
+            default_value_to_model("now()", &ColumnTypeSpec::Date),
+            Some("now()".to_string())
+        );
+        assert_eq!(
+            default_value_to_model("gen_random_uuid()", &ColumnTypeSpec::Uuid),
+            Some("generate_uuid()".to_string())
+        );
+        assert_eq!(default_value_to_model("nextval('seq')", &int), None);
+    }
+
+    #[test]
+    fn relations_constraints_and_defaults() {
+        let concerts = TableSpec::new(
+            PhysicalTableName::new("concerts", None),
+            vec![
+                pk_column(),
+                ColumnSpec {
+                    default_value: Some("'Untitled'::text".to_string()),
+                    ..column("title", ColumnTypeSpec::String { max_length: None })
+                },
+                column(
+                    "venue_id",
+                    ColumnTypeSpec::ColumnReference {
+                        foreign_table_name: PhysicalTableName::new("venues", None),
+                        foreign_pk_column_name: "id".to_string(),
+                        foreign_pk_type: Box::new(ColumnTypeSpec::Int { bits: IntBits::_32 }),
+                        on_delete: ReferentialAction::Cascade,
+                        composite_key_columns: vec![],
+                    },
+                ),
+                ColumnSpec {
+                    default_value: Some("false".to_string()),
+                    ..column("published", ColumnTypeSpec::Boolean)
+                },
+            ],
+            vec![IndexSpec::new(
+                "concert_title_idx".to_string(),
+                HashSet::from(["title".to_string()]),
+                IndexKind::default(),
+            )],
+            vec![],
+        );
+
+        let venues = TableSpec::new(
+            PhysicalTableName::new("venues", None),
+            vec![
+                pk_column(),
+                ColumnSpec {
+                    unique_constraints: vec!["unique_constraint_venue_name".to_string()],
+                    ..column("name", ColumnTypeSpec::String { max_length: None })
+                },
+            ],
+            vec![],
+            vec![],
+        );
+
+        let model = DatabaseSpec::new(vec![concerts, venues], vec![]).to_model();
+
+        assert_eq!(
+            model.value,
+            r#"@postgres
+module Database {
+  @access(false)
+  type Concert {
+    @pk id: Int = autoIncrement()
+    @index title: String = "Untitled"
+    @relation(onDelete=CASCADE) venue: Venue
+    published: Boolean = false
+  }
+
+  @access(false)
+  type Venue {
+    @pk id: Int = autoIncrement()
+    @unique name: String
+    concerts: Set<Concert>?
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn composite_keys() {
+        let int = || ColumnTypeSpec::Int { bits: IntBits::_32 };
+        let membership_reference = |foreign_pk_column_name: &str| ColumnTypeSpec::ColumnReference {
+            foreign_table_name: PhysicalTableName::new("memberships", None),
+            foreign_pk_column_name: foreign_pk_column_name.to_string(),
+            foreign_pk_type: Box::new(int()),
+            on_delete: ReferentialAction::NoAction,
+            composite_key_columns: vec![
+                ("membership_user_id".to_string(), "user_id".to_string()),
+                ("membership_group_id".to_string(), "group_id".to_string()),
+            ],
+        };
+
+        let users = TableSpec::new(
+            PhysicalTableName::new("users", None),
+            vec![pk_column()],
+            vec![],
+            vec![],
+        );
+
+        let memberships = TableSpec::new(
+            PhysicalTableName::new("memberships", None),
+            vec![
+                ColumnSpec {
+                    is_pk: true,
+                    ..column(
+                        "user_id",
+                        ColumnTypeSpec::ColumnReference {
+                            foreign_table_name: PhysicalTableName::new("users", None),
+                            foreign_pk_column_name: "id".to_string(),
+                            foreign_pk_type: Box::new(int()),
+                            on_delete: ReferentialAction::NoAction,
+                            composite_key_columns: vec![],
+                        },
+                    )
+                },
+                ColumnSpec {
+                    is_pk: true,
+                    ..column("group_id", int())
+                },
+            ],
+            vec![],
+            vec![],
+        );
+
+        let notes = TableSpec::new(
+            PhysicalTableName::new("notes", None),
+            vec![
+                pk_column(),
+                column("membership_user_id", membership_reference("user_id")),
+                column("membership_group_id", membership_reference("group_id")),
+            ],
+            vec![],
+            vec![],
+        );
+
+        let model = DatabaseSpec::new(vec![users, memberships, notes], vec![]).to_model();
+
+        assert_eq!(
+            model.value,
+            r#"@postgres
+module Database {
+  @access(false)
+  type User {
+    @pk id: Int = autoIncrement()
+    memberships: Set<Membership>?
+  }
+
+  @access(false)
+  type Membership {
+    @pk user: User
+    @pk groupId: Int
+  }
+
+  @access(false)
+  type Note {
+    @pk id: Int = autoIncrement()
+    membershipUserId: Int
+    membershipGroupId: Int
+  }
+}
+"#
+        );
+
+        // The composite foreign key is reported once
+        assert_eq!(
+            model
+                .issues
+                .iter()
+                .filter(|issue| matches!(issue, Issue::Warning(_)))
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn imported_model_builds() {
+        let int = || ColumnTypeSpec::Int { bits: IntBits::_32 };
+
+        let concerts = TableSpec {
+            checks: vec![
+                CheckSpec {
+                    name: "concerts_rating_range_check".to_string(),
+                    expr: r#"(("rating" >= 0) AND ("rating" <= 5))"#.to_string(),
+                },
+                CheckSpec {
+                    name: "concerts_price_check".to_string(),
+                    expr: "(price >= 0)".to_string(),
+                },
+            ],
+            ..TableSpec::new(
+                PhysicalTableName::new("concerts", None),
+                vec![
+                    pk_column(),
+                    column(
+                        "venue_id",
+                        ColumnTypeSpec::ColumnReference {
+                            foreign_table_name: PhysicalTableName::new("venues", None),
+                            foreign_pk_column_name: "id".to_string(),
+                            foreign_pk_type: Box::new(int()),
+                            on_delete: ReferentialAction::Cascade,
+                            composite_key_columns: vec![],
+                        },
+                    ),
+                    column("rating", int()),
+                    column("price", int()),
+                ],
+                vec![],
+                vec![],
+            )
+        };
+
+        let venues = TableSpec::new(
+            PhysicalTableName::new("venues", Some("events")),
+            vec![
+                pk_column(),
+                column("name", ColumnTypeSpec::String { max_length: None }),
+            ],
+            vec![],
+            vec![],
+        );
+
+        let model = DatabaseSpec::new(vec![concerts, venues], vec![]).to_model();
+
+        let system = builder::build_system_from_str(
+            &model.value,
+            "index.exo".to_string(),
+            vec![Box::new(
+                postgres_model_builder::PostgresSubsystemBuilder {},
+            )],
+        )
+        .await
+        .unwrap_or_else(|e| panic!("Imported model failed to build: {e:?}\n{}", model.value));
+
+        let postgres_subsystem = util::deserialize_postgres_subsystem(system).unwrap();
+        let built = DatabaseSpec::from_database(&postgres_subsystem.database);
+
+        let concerts = built
+            .tables
+            .iter()
+            .find(|table| table.name == PhysicalTableName::new("concerts", None))
+            .unwrap();
+
+        assert_eq!(
+            concerts
+                .checks
+                .iter()
+                .map(|check| check.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["concerts_rating_range_check", "concerts_price_check"]
+        );
+
+        let venue_column = concerts
+            .columns
+            .iter()
+            .find(|column| column.name == "venue_id")
+            .unwrap();
+        assert!(matches!(
+            &venue_column.typ,
+            ColumnTypeSpec::ColumnReference {
+                foreign_table_name,
+                on_delete: ReferentialAction::Cascade,
+                ..
+            } if foreign_table_name == &PhysicalTableName::new("venues", Some("events"))
+        ));
+    }
 }
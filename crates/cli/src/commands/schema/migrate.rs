@@ -18,7 +18,7 @@ use crate::{
     util::open_file_for_output,
 };
 
-use super::util::{self, use_ir_arg};
+use super::util::{self, row_level_security_arg, use_ir_arg};
 use anyhow::Result;
 use async_trait::async_trait;
 use clap::{Arg, Command};
@@ -49,6 +49,9 @@ impl CommandDefinition for MigrateCommandDefinition {
         .arg(
             use_ir_arg()
         )
+        .arg(
+            row_level_security_arg()
+        )
     }
 
     /// Perform a database migration for a exograph model
@@ -59,6 +62,7 @@ impl CommandDefinition for MigrateCommandDefinition {
         let apply_to_database: bool = matches.get_flag("apply-to-database");
         let allow_destructive_changes: bool = matches.get_flag("allow-destructive-changes");
         let use_ir: bool = matches.get_flag("use-ir");
+        let row_level_security: bool = matches.get_flag("row-level-security");
 
         if output.is_some() && apply_to_database {
             return Err(anyhow!(
@@ -69,7 +73,10 @@ impl CommandDefinition for MigrateCommandDefinition {
         let postgres_subsystem = util::create_postgres_system(&model, None, use_ir).await?;
 
         let db_client = open_database(database.as_deref()).await?;
-        let migrations = Migration::from_db_and_model(&db_client, &postgres_subsystem).await?;
+        let mut migrations = Migration::from_db_and_model(&db_client, &postgres_subsystem).await?;
+        if row_level_security {
+            migrations.add_row_level_security(&postgres_subsystem)?;
+        }
 
         if apply_to_database {
             if migrations.has_destructive_changes() {
@@ -27,7 +27,7 @@ use common::env_const::{
     DATABASE_URL, EXO_CHECK_CONNECTION_ON_STARTUP, EXO_CONNECTION_POOL_SIZE, EXO_POSTGRES_URL,
 };
 
-fn deserialize_postgres_subsystem(
+pub(super) fn deserialize_postgres_subsystem(
     system: SerializableSystem,
 ) -> Result<PostgresSubsystem, ParserError> {
     system
@@ -102,3 +102,11 @@ pub(super) fn use_ir_arg() -> Arg {
         .required(false)
         .num_args(0)
 }
+
+pub(super) fn row_level_security_arg() -> Arg {
+    Arg::new("row-level-security")
+        .help("Also enforce access rules with row-level security policies (the server must run with EXO_POSTGRES_ROW_LEVEL_SECURITY=true to set the context for the policies)")
+        .long("row-level-security")
+        .required(false)
+        .num_args(0)
+}
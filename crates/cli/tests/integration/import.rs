@@ -0,0 +1,78 @@
+use std::{ffi::OsStr, path::Path, process::Command};
+
+use exo_sql::{
+    testing::db::{EphemeralDatabase, EphemeralDatabaseLauncher, EphemeralDatabaseServer},
+    DatabaseClientManager,
+};
+
+fn exo<I, S>(cwd: impl AsRef<Path>, args: I) -> Command
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let bin = env!("CARGO_BIN_EXE_exo");
+
+    let mut cmd = Command::new(bin);
+    cmd.current_dir(cwd).args(args);
+    cmd
+}
+
+const SCHEMA: &str = include_str!("import.sql");
+
+/// Import a model from a live schema (with relations, constraints, and defaults) and verify that
+/// the model is compatible with the same schema
+#[tokio::test]
+async fn import_verify_round_trip() {
+    let server =
+        EphemeralDatabaseLauncher::create_server().expect("Failed to launch a database server");
+    let database = server
+        .create_database("exo_import_round_trip")
+        .expect("Failed to create a database");
+    let url = database.url();
+
+    {
+        let client_manager = DatabaseClientManager::from_url(&url, true, None)
+            .await
+            .expect("Failed to connect to the database");
+        client_manager
+            .get_client()
+            .await
+            .expect("Failed to connect to the database")
+            .batch_execute(SCHEMA)
+            .await
+            .expect("Failed to create the schema");
+    }
+
+    let cargo_tmp_dir = env!("CARGO_TARGET_TMPDIR");
+    let project_dir = tempfile::tempdir_in(cargo_tmp_dir).expect("Failed to create tempdir");
+    std::fs::create_dir(project_dir.path().join("src")).expect("Failed to create src directory");
+
+    let import = exo(
+        project_dir.path(),
+        [
+            "schema",
+            "import",
+            "--database",
+            &url,
+            "--output",
+            "src/index.exo",
+        ],
+    )
+    .output()
+    .expect("Failed to run exo schema import");
+    assert!(
+        import.status.success(),
+        "Import failed: {}",
+        String::from_utf8_lossy(&import.stderr)
+    );
+
+    let verify = exo(project_dir.path(), ["schema", "verify", "--database", &url])
+        .output()
+        .expect("Failed to run exo schema verify");
+    assert!(
+        verify.status.success(),
+        "Imported model is not compatible with the schema: {}\n{}",
+        String::from_utf8_lossy(&verify.stderr),
+        std::fs::read_to_string(project_dir.path().join("src/index.exo")).unwrap_or_default()
+    );
+}
@@ -0,0 +1,20 @@
+CREATE SCHEMA "events";
+
+CREATE TABLE "events"."venues" (
+	"id" SERIAL PRIMARY KEY,
+	"name" VARCHAR(100) NOT NULL CONSTRAINT "unique_constraint_venue_name" UNIQUE
+);
+
+CREATE TABLE "concerts" (
+	"id" SERIAL PRIMARY KEY,
+	"title" TEXT NOT NULL DEFAULT 'Untitled',
+	"venue_id" INT NOT NULL,
+	"rating" INT NOT NULL,
+	"price" INT NOT NULL,
+	"published" BOOLEAN NOT NULL DEFAULT false
+);
+
+ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "events"."venues" ON DELETE CASCADE;
+ALTER TABLE "concerts" ADD CONSTRAINT "concerts_rating_range_check" CHECK ("rating" >= 0 AND "rating" <= 5);
+ALTER TABLE "concerts" ADD CONSTRAINT "concerts_price_check" CHECK (price >= 0);
+CREATE INDEX "concert_title_idx" ON "concerts" ("title");
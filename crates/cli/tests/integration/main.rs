@@ -1,2 +1,3 @@
+mod import;
 #[cfg(unix)]
 mod smoke;
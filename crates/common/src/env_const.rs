@@ -6,6 +6,8 @@ pub const EXO_INTROSPECTION_LIVE_UPDATE: &str = "EXO_INTROSPECTION_LIVE_UPDATE";
 
 pub const EXO_CORS_DOMAINS: &str = "EXO_CORS_DOMAINS";
 
+pub const EXO_PERSISTED_QUERY_CACHE_SIZE: &str = "EXO_PERSISTED_QUERY_CACHE_SIZE"; // number of queries registered through automatic persisted queries (0 disables them)
+
 pub const EXO_JWT_SECRET: &str = "EXO_JWT_SECRET";
 pub const EXO_OIDC_URL: &str = "EXO_OIDC_URL";
 
@@ -13,8 +15,12 @@ pub const EXO_POSTGRES_URL: &str = "EXO_POSTGRES_URL";
 pub const DATABASE_URL: &str = "DATABASE_URL";
 pub const EXO_CONNECTION_POOL_SIZE: &str = "EXO_CONNECTION_POOL_SIZE";
 pub const EXO_CHECK_CONNECTION_ON_STARTUP: &str = "EXO_CHECK_CONNECTION_ON_STARTUP";
+pub const EXO_QUERY_CACHE_SIZE: &str = "EXO_QUERY_CACHE_SIZE"; // number of cached query responses (for types with `@cache`)
+pub const EXO_POSTGRES_ROW_LEVEL_SECURITY: &str = "EXO_POSTGRES_ROW_LEVEL_SECURITY"; // set the context for row-level security policies (see `exo schema create --row-level-security`)
+pub const EXO_POSTGRES_READINESS_VERIFY_SCHEMA: &str = "EXO_POSTGRES_READINESS_VERIFY_SCHEMA"; // verify that the database schema matches the model when checking readiness
 
 pub const EXO_SERVER_PORT: &str = "EXO_SERVER_PORT";
+pub const EXO_METRICS_ENABLED: &str = "EXO_METRICS_ENABLED"; // serve Prometheus metrics at `/metrics`
 
 pub const _EXO_DEPLOYMENT_MODE: &str = "_EXO_DEPLOYMENT_MODE"; // "yolo", "dev", "playground" or "prod" (default)
 pub const _EXO_ENFORCE_TRUSTED_DOCUMENTS: &str = "_EXO_ENFORCE_TRUSTED_DOCUMENTS";
@@ -83,6 +83,7 @@ pub struct AstModule<T: NodeTypedness> {
     pub types: Vec<AstModel<T>>,
     pub methods: Vec<AstMethod<T>>,
     pub interceptors: Vec<AstInterceptor<T>>,
+    pub enums: Vec<AstEnum>,
     pub base_exofile: PathBuf, // The exo file in which this module is defined. Used to resolve relative imports and js/ts/wasm sources
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
@@ -132,6 +133,25 @@ pub struct AstInterceptor<T: NodeTypedness> {
     pub span: Span,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AstEnum {
+    pub name: String,
+    pub fields: Vec<AstEnumField>,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    #[serde(default = "default_span")]
+    pub span: Span,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AstEnumField {
+    pub name: String,
+    #[serde(skip_serializing)]
+    #[serde(skip_deserializing)]
+    #[serde(default = "default_span")]
+    pub span: Span,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum AstModelKind {
     Type,    // a type in a module (with semantics assigned by each module plugin)
@@ -283,6 +303,14 @@ pub enum AstExpr<T: NodeTypedness> {
         #[serde(skip_deserializing)]
         Vec<Span>,
     ),
+    /// An object such as `{net: ["api.stripe.com"], read: false}`
+    ObjectLiteral(
+        HashMap<String, AstExpr<T>>,
+        #[serde(skip_serializing)]
+        #[serde(skip_deserializing)]
+        #[serde(default = "default_span")]
+        Span,
+    ),
 }
 
 impl<T: NodeTypedness> AstExpr<T> {
@@ -298,6 +326,7 @@ impl<T: NodeTypedness> AstExpr<T> {
             AstExpr::RelationalOp(r) => r.span(),
             AstExpr::BooleanLiteral(_, s) => *s,
             AstExpr::NumberLiteral(_, s) => *s,
+            AstExpr::ObjectLiteral(_, s) => *s,
             AstExpr::StringList(_, s) => {
                 let mut span = s[0].to_owned();
                 for s in s.iter().skip(1) {
@@ -7,12 +7,23 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use codemap::Span;
 use codemap_diagnostic::{Diagnostic, Level, SpanLabel, SpanStyle};
-use core_model::{mapped_arena::MappedArena, primitive_type::PrimitiveType, types::FieldType};
+use core_model::{
+    cache::{CachePolicy, CacheScope},
+    context_type::ContextSelection,
+    mapped_arena::MappedArena,
+    primitive_type::PrimitiveType,
+    rate_limit::RateLimit,
+    types::FieldType,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    ast::ast_types::{AstAnnotation, AstAnnotationParams, AstExpr, AstField, AstModelKind},
+    ast::ast_types::{
+        AstAnnotation, AstAnnotationParams, AstExpr, AstField, AstModelKind, FieldSelection,
+        FieldSelectionElement, LogicalOp,
+    },
     error::ModelBuildingError,
     typechecker::{AnnotationMap, Type, Typed},
 };
@@ -47,6 +58,114 @@ impl AstAnnotationHelper for AstAnnotation<Typed> {
     }
 }
 
+/// Compute the caching policy from a `@cache(maxAge: 60, scope: PUBLIC)` annotation.
+///
+/// The scope is optional and defaults to `PUBLIC`. However, if the response depends on the request
+/// (`request_dependent`, for example, when the access rules refer to `AuthContext`), a shared cache
+/// must not reuse it, so the scope defaults to `PRIVATE` and `PUBLIC` is rejected.
+pub fn build_cache_policy(
+    annotation: Option<&AstAnnotationParams<Typed>>,
+    request_dependent: bool,
+    span: Span,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<CachePolicy> {
+    let params = match annotation? {
+        AstAnnotationParams::Map(params, _) => params,
+        _ => return None,
+    };
+
+    let mut error = |message: String| {
+        errors.push(Diagnostic {
+            level: Level::Error,
+            message,
+            code: Some("C000".to_string()),
+            spans: vec![SpanLabel {
+                span,
+                style: SpanStyle::Primary,
+                label: None,
+            }],
+        })
+    };
+
+    let max_age = match params.get("maxAge") {
+        Some(AstExpr::NumberLiteral(max_age, _)) if *max_age >= 0 => {
+            u32::try_from(*max_age).unwrap_or(u32::MAX)
+        }
+        _ => {
+            error("maxAge of @cache must be a non-negative number of seconds".to_string());
+            return None;
+        }
+    };
+
+    let scope = match params.get("scope") {
+        None if request_dependent => CacheScope::Private,
+        None => CacheScope::default(),
+        Some(AstExpr::StringLiteral(scope, _)) => match CacheScope::from_model(scope) {
+            Some(scope) => scope,
+            None => {
+                error(format!(
+                    "Invalid scope '{scope}' of @cache (expected PUBLIC or PRIVATE)"
+                ));
+                return None;
+            }
+        },
+        Some(_) => {
+            error("Invalid scope of @cache (expected PUBLIC or PRIVATE)".to_string());
+            return None;
+        }
+    };
+
+    if request_dependent && scope == CacheScope::Public {
+        error(
+            "@cache(scope: PUBLIC) cannot be used when the response depends on the request (such as through access rules that refer to a context)"
+                .to_string(),
+        );
+        return None;
+    }
+
+    Some(CachePolicy { max_age, scope })
+}
+
+/// Does the expression refer to a context (such as `AuthContext.id`)? The value of such an
+/// expression may differ from one request to another.
+pub fn refers_to_context(expr: &AstExpr<Typed>, types: &MappedArena<Type>) -> bool {
+    match expr {
+        AstExpr::FieldSelection(selection) => {
+            let path = selection.path();
+
+            let is_context = match path.first() {
+                Some(FieldSelectionElement::Identifier(name, _, _)) => matches!(
+                    types.get_by_key(name),
+                    Some(Type::Composite(model)) if model.kind == AstModelKind::Context
+                ),
+                _ => false,
+            };
+
+            // The function passed to a higher-order function (such as `some`) may refer to a context
+            is_context
+                || path.iter().any(|elem| match elem {
+                    FieldSelectionElement::HofCall { expr, .. } => refers_to_context(expr, types),
+                    FieldSelectionElement::Identifier(..) => false,
+                })
+        }
+        AstExpr::LogicalOp(op) => match op {
+            LogicalOp::Not(expr, _, _) => refers_to_context(expr, types),
+            LogicalOp::And(left, right, _, _) | LogicalOp::Or(left, right, _, _) => {
+                refers_to_context(left, types) || refers_to_context(right, types)
+            }
+        },
+        AstExpr::RelationalOp(op) => {
+            let (left, right) = op.sides();
+            refers_to_context(left, types) || refers_to_context(right, types)
+        }
+        AstExpr::StringLiteral(..)
+        | AstExpr::BooleanLiteral(..)
+        | AstExpr::NumberLiteral(..)
+        | AstExpr::StringList(..)
+        | AstExpr::ObjectLiteral(..) => false,
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ResolvedContext {
     pub name: String,
@@ -236,3 +355,144 @@ fn extract_context_source(
         }
     }
 }
+
+/// Compute the weight for query cost analysis from a `@cost(5)` annotation
+pub fn build_cost_weight(
+    annotation: Option<&AstAnnotationParams<Typed>>,
+    span: Span,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<u32> {
+    match annotation? {
+        AstAnnotationParams::Single(AstExpr::NumberLiteral(weight, _), _) if *weight >= 0 => {
+            Some(u32::try_from(*weight).unwrap_or(u32::MAX))
+        }
+        _ => {
+            errors.push(Diagnostic {
+                level: Level::Error,
+                message: "The weight of @cost must be a non-negative number".to_string(),
+                code: Some("C000".to_string()),
+                spans: vec![SpanLabel {
+                    span,
+                    style: SpanStyle::Primary,
+                    label: None,
+                }],
+            });
+            None
+        }
+    }
+}
+
+/// Compute the rate limit from a `@rateLimit(limit: 10, window: "1m", key: AuthContext.id)`
+/// annotation. The `scope` names the annotated element, so that all operations limited through the
+/// annotation share the same allowance.
+pub fn build_rate_limit(
+    annotation: Option<&AstAnnotationParams<Typed>>,
+    scope: &str,
+    types: &MappedArena<Type>,
+    span: Span,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<RateLimit> {
+    let params = match annotation? {
+        AstAnnotationParams::Map(params, _) => params,
+        _ => return None,
+    };
+
+    let mut error = |message: String| {
+        errors.push(Diagnostic {
+            level: Level::Error,
+            message,
+            code: Some("C000".to_string()),
+            spans: vec![SpanLabel {
+                span,
+                style: SpanStyle::Primary,
+                label: None,
+            }],
+        })
+    };
+
+    let limit = match params.get("limit") {
+        Some(AstExpr::NumberLiteral(limit, _)) if *limit > 0 => {
+            u32::try_from(*limit).unwrap_or(u32::MAX)
+        }
+        _ => {
+            error("limit of @rateLimit must be a positive number".to_string());
+            return None;
+        }
+    };
+
+    let window_secs = match params.get("window") {
+        Some(AstExpr::StringLiteral(window, _)) => match RateLimit::parse_window(window) {
+            Some(window_secs) => window_secs,
+            None => {
+                error(format!(
+                    "Invalid window '{window}' of @rateLimit (expected a duration such as \"30s\", \"1m\", \"1h\", or \"1d\")"
+                ));
+                return None;
+            }
+        },
+        _ => {
+            error("window of @rateLimit must be a string such as \"1m\"".to_string());
+            return None;
+        }
+    };
+
+    let key = match params.get("key") {
+        None => None,
+        Some(AstExpr::FieldSelection(selection)) => match context_selection(selection, types) {
+            Some(key) => Some(key),
+            None => {
+                error(
+                    "key of @rateLimit must be a context field such as AuthContext.id".to_string(),
+                );
+                return None;
+            }
+        },
+        Some(_) => {
+            error("key of @rateLimit must be a context field such as AuthContext.id".to_string());
+            return None;
+        }
+    };
+
+    Some(RateLimit {
+        scope: scope.to_string(),
+        limit,
+        window_secs,
+        key,
+    })
+}
+
+fn context_selection(
+    selection: &FieldSelection<Typed>,
+    types: &MappedArena<Type>,
+) -> Option<ContextSelection> {
+    let is_simple_path = selection
+        .path()
+        .iter()
+        .all(|elem| matches!(elem, FieldSelectionElement::Identifier(..)));
+
+    if !is_simple_path {
+        return None;
+    }
+
+    let mut path = selection.context_path().into_iter();
+    let context_name = path.next()?;
+    let field_name = path.next()?;
+
+    // Only top-level fields of a context may be used as a key
+    if path.next().is_some() {
+        return None;
+    }
+
+    let is_context_field = match types.get_by_key(&context_name) {
+        Some(Type::Composite(model)) => {
+            model.kind == AstModelKind::Context
+                && model.fields.iter().any(|field| field.name == field_name)
+        }
+        _ => false,
+    };
+
+    is_context_field.then(|| ContextSelection {
+        context_name,
+        path: (field_name, vec![]),
+    })
+}
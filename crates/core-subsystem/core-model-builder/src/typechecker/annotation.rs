@@ -36,4 +36,8 @@ pub struct MappedAnnotationParamSpec {
     pub name: &'static str,
     /// Is this parameter optional?
     pub optional: bool,
+    /// Keywords the parameter accepts as its value (such as `CASCADE` in `@relation(onDelete:
+    /// CASCADE)`). A keyword is resolved to a string literal; other identifiers are reported as
+    /// errors.
+    pub keywords: &'static [&'static str],
 }
@@ -0,0 +1,138 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+/// The caching policy of a query (specified using `@cache(maxAge: 60, scope: PUBLIC)`)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// The number of seconds a response may be reused
+    pub max_age: u32,
+    pub scope: CacheScope,
+}
+
+/// Who may reuse a cached response
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheScope {
+    /// Any cache (including shared caches such as a CDN)
+    #[default]
+    Public,
+    /// Only the client's own cache (the response depends on the user making the request)
+    Private,
+}
+
+impl CacheScope {
+    pub fn from_model(scope: &str) -> Option<Self> {
+        match scope {
+            "PUBLIC" => Some(CacheScope::Public),
+            "PRIVATE" => Some(CacheScope::Private),
+            _ => None,
+        }
+    }
+}
+
+impl CachePolicy {
+    pub const HEADER_NAME: &'static str = "cache-control";
+
+    /// The value of the `Cache-Control` header for this policy (for example, `public, max-age=60`)
+    pub fn header_value(&self) -> String {
+        let scope = match self.scope {
+            CacheScope::Public => "public",
+            CacheScope::Private => "private",
+        };
+
+        format!("{scope}, max-age={}", self.max_age)
+    }
+
+    /// Parse the value of a `Cache-Control` header produced by [CachePolicy::header_value]
+    pub fn from_header_value(value: &str) -> Option<Self> {
+        let (scope, max_age) = value.split_once(',')?;
+
+        let scope = match scope.trim() {
+            "public" => CacheScope::Public,
+            "private" => CacheScope::Private,
+            _ => return None,
+        };
+        let max_age = max_age.trim().strip_prefix("max-age=")?.parse().ok()?;
+
+        Some(CachePolicy { max_age, scope })
+    }
+
+    /// The policy for a response that combines responses with `self` and `other` policies. The
+    /// combined response may be reused only as long as (and only by whom) both parts may be.
+    pub fn combine(self, other: CachePolicy) -> CachePolicy {
+        CachePolicy {
+            max_age: self.max_age.min(other.max_age),
+            scope: if self.scope == CacheScope::Private || other.scope == CacheScope::Private {
+                CacheScope::Private
+            } else {
+                CacheScope::Public
+            },
+        }
+    }
+
+    /// The policy for a response that combines responses with the given policies (`None` if any of
+    /// the parts may not be cached)
+    pub fn combine_all(
+        policies: impl IntoIterator<Item = Option<CachePolicy>>,
+    ) -> Option<CachePolicy> {
+        policies
+            .into_iter()
+            .try_fold(None, |acc: Option<CachePolicy>, policy| {
+                let policy = policy?;
+                Some(Some(match acc {
+                    Some(acc) => acc.combine(policy),
+                    None => policy,
+                }))
+            })
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_value_round_trip() {
+        let policy = CachePolicy {
+            max_age: 60,
+            scope: CacheScope::Private,
+        };
+
+        assert_eq!(policy.header_value(), "private, max-age=60");
+        assert_eq!(
+            CachePolicy::from_header_value(&policy.header_value()),
+            Some(policy)
+        );
+        assert_eq!(CachePolicy::from_header_value("no-store"), None);
+    }
+
+    #[test]
+    fn combine_policies() {
+        let public = CachePolicy {
+            max_age: 60,
+            scope: CacheScope::Public,
+        };
+        let private = CachePolicy {
+            max_age: 300,
+            scope: CacheScope::Private,
+        };
+
+        assert_eq!(
+            CachePolicy::combine_all([Some(public), Some(private)]),
+            Some(CachePolicy {
+                max_age: 60,
+                scope: CacheScope::Private
+            })
+        );
+        assert_eq!(CachePolicy::combine_all([Some(public), None]), None);
+        assert_eq!(CachePolicy::combine_all([]), None);
+    }
+}
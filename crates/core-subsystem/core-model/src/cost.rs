@@ -0,0 +1,62 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! The weights used for query cost analysis (specified using `@cost(5)` on types, fields, and
+//! module methods).
+//!
+//! Weights are carried in the GraphQL schema as a `cost` directive on the corresponding type or
+//! field definition, so that the resolver can compute the cost of an operation while validating it.
+
+use async_graphql_parser::{types::ConstDirective, Positioned};
+use async_graphql_value::{ConstValue, Number};
+
+use crate::type_normalization::{default_positioned, default_positioned_name};
+
+const COST_DIRECTIVE: &str = "cost";
+const WEIGHT_ARGUMENT: &str = "weight";
+
+/// The directives to attach to a type or field definition with the given weight (if any)
+pub fn cost_directives(weight: Option<u32>) -> Vec<Positioned<ConstDirective>> {
+    weight
+        .map(|weight| {
+            default_positioned(ConstDirective {
+                name: default_positioned_name(COST_DIRECTIVE),
+                arguments: vec![(
+                    default_positioned_name(WEIGHT_ARGUMENT),
+                    default_positioned(ConstValue::Number(Number::from(weight))),
+                )],
+            })
+        })
+        .into_iter()
+        .collect()
+}
+
+/// The weight specified by the directives produced by [cost_directives] (if any)
+pub fn directive_weight(directives: &[Positioned<ConstDirective>]) -> Option<u32> {
+    directives
+        .iter()
+        .find(|directive| directive.node.name.node.as_str() == COST_DIRECTIVE)
+        .and_then(|directive| directive.node.get_argument(WEIGHT_ARGUMENT))
+        .and_then(|weight| match &weight.node {
+            ConstValue::Number(weight) => weight.as_u64(),
+            _ => None,
+        })
+        .map(|weight| u32::try_from(weight).unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weight_round_trip() {
+        assert_eq!(directive_weight(&cost_directives(Some(5))), Some(5));
+        assert_eq!(directive_weight(&cost_directives(None)), None);
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// The env var to set the timeout of modules that don't specify their own (such as `10s`)
+pub const EXO_MODULE_TIMEOUT: &str = "EXO_MODULE_TIMEOUT";
+/// The env var to set the memory limit of modules that don't specify their own (such as `256MB`)
+pub const EXO_MODULE_MEMORY: &str = "EXO_MODULE_MEMORY";
+
+/// The limits on running the code of a module (specified using `@deno(path: "...", timeout: "5s",
+/// memory: "128MB")` or the `EXO_MODULE_TIMEOUT` and `EXO_MODULE_MEMORY` env vars)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// The wall-clock time allowed for each call in milliseconds
+    pub timeout_ms: Option<u64>,
+    /// The memory available to the module in bytes
+    pub memory_bytes: Option<u64>,
+}
+
+impl ExecutionLimits {
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+
+    /// The limits with those not specified taken from `defaults`
+    pub fn or(self, defaults: ExecutionLimits) -> Self {
+        Self {
+            timeout_ms: self.timeout_ms.or(defaults.timeout_ms),
+            memory_bytes: self.memory_bytes.or(defaults.memory_bytes),
+        }
+    }
+
+    /// The limits set through the `EXO_MODULE_TIMEOUT` and `EXO_MODULE_MEMORY` env vars (`get_env`
+    /// looks up an env var)
+    pub fn from_env(get_env: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let timeout_ms = get_env(EXO_MODULE_TIMEOUT)
+            .map(|timeout| {
+                Self::parse_timeout(&timeout).ok_or_else(|| {
+                    format!("{EXO_MODULE_TIMEOUT} env var must be set to a duration such as 10s")
+                })
+            })
+            .transpose()?;
+
+        let memory_bytes = get_env(EXO_MODULE_MEMORY)
+            .map(|memory| {
+                Self::parse_memory(&memory).ok_or_else(|| {
+                    format!("{EXO_MODULE_MEMORY} env var must be set to a size such as 256MB")
+                })
+            })
+            .transpose()?;
+
+        Ok(Self {
+            timeout_ms,
+            memory_bytes,
+        })
+    }
+
+    /// Parse a timeout such as `500ms`, `30s`, or `2m` into milliseconds
+    pub fn parse_timeout(timeout: &str) -> Option<u64> {
+        let (value, unit) = split_unit(timeout)?;
+
+        let multiplier = match unit {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60 * 1000,
+            _ => return None,
+        };
+
+        value.checked_mul(multiplier).filter(|ms| *ms > 0)
+    }
+
+    /// Parse a memory size such as `64MB` or `1GB` into bytes
+    pub fn parse_memory(memory: &str) -> Option<u64> {
+        let (value, unit) = split_unit(memory)?;
+
+        let multiplier = match unit {
+            "KB" => 1024,
+            "MB" => 1024 * 1024,
+            "GB" => 1024 * 1024 * 1024,
+            _ => return None,
+        };
+
+        value.checked_mul(multiplier).filter(|bytes| *bytes > 0)
+    }
+}
+
+fn split_unit(value: &str) -> Option<(u64, &str)> {
+    let unit_index = value.find(|c: char| !c.is_ascii_digit())?;
+    let (value, unit) = value.split_at(unit_index);
+    Some((value.parse().ok()?, unit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timeout() {
+        assert_eq!(ExecutionLimits::parse_timeout("500ms"), Some(500));
+        assert_eq!(ExecutionLimits::parse_timeout("30s"), Some(30_000));
+        assert_eq!(ExecutionLimits::parse_timeout("2m"), Some(120_000));
+
+        assert_eq!(ExecutionLimits::parse_timeout("0s"), None);
+        assert_eq!(ExecutionLimits::parse_timeout("10"), None);
+        assert_eq!(ExecutionLimits::parse_timeout("s"), None);
+        assert_eq!(ExecutionLimits::parse_timeout("1h"), None);
+    }
+
+    #[test]
+    fn parse_memory() {
+        assert_eq!(ExecutionLimits::parse_memory("512KB"), Some(512 * 1024));
+        assert_eq!(
+            ExecutionLimits::parse_memory("64MB"),
+            Some(64 * 1024 * 1024)
+        );
+        assert_eq!(
+            ExecutionLimits::parse_memory("1GB"),
+            Some(1024 * 1024 * 1024)
+        );
+
+        assert_eq!(ExecutionLimits::parse_memory("0MB"), None);
+        assert_eq!(ExecutionLimits::parse_memory("64"), None);
+        assert_eq!(ExecutionLimits::parse_memory("64mb"), None);
+    }
+
+    #[test]
+    fn from_env() {
+        let limits = ExecutionLimits::from_env(|key| match key {
+            EXO_MODULE_TIMEOUT => Some("10s".to_string()),
+            _ => None,
+        });
+        assert_eq!(
+            limits,
+            Ok(ExecutionLimits {
+                timeout_ms: Some(10_000),
+                memory_bytes: None,
+            })
+        );
+
+        let limits = ExecutionLimits::from_env(|key| match key {
+            EXO_MODULE_MEMORY => Some("lots".to_string()),
+            _ => None,
+        });
+        assert!(limits.is_err());
+    }
+
+    #[test]
+    fn or() {
+        let limits = ExecutionLimits {
+            timeout_ms: Some(1000),
+            memory_bytes: None,
+        };
+        let defaults = ExecutionLimits {
+            timeout_ms: Some(5000),
+            memory_bytes: Some(1024),
+        };
+
+        assert_eq!(
+            limits.or(defaults),
+            ExecutionLimits {
+                timeout_ms: Some(1000),
+                memory_bytes: Some(1024),
+            }
+        );
+    }
+}
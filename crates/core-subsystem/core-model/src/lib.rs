@@ -8,9 +8,13 @@
 // by the Apache License, Version 2.0.
 
 pub mod access;
+pub mod cache;
 pub mod context_type;
+pub mod cost;
+pub mod execution_limits;
 pub mod mapped_arena;
 pub mod primitive_type;
+pub mod rate_limit;
 
 pub mod type_normalization;
 pub mod types;
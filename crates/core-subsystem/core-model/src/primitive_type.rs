@@ -28,6 +28,8 @@ pub enum PrimitiveType {
     Blob,
     Uuid,
     Vector,
+    /// A user-declared enum (`enum Status { DRAFT, PUBLISHED }`) with its values in declaration order.
+    Enum(String, Vec<String>),
     // TODO: This should not be a primitive type, but a type with modifier or some variation of it
     /// An array version of a primitive type.
     Array(Box<PrimitiveType>),
@@ -59,6 +61,7 @@ impl PrimitiveType {
             PrimitiveType::Blob => "Blob".to_owned(),
             PrimitiveType::Uuid => "Uuid".to_owned(),
             PrimitiveType::Vector => "Vector".to_owned(),
+            PrimitiveType::Enum(name, _) => name.to_owned(),
             PrimitiveType::Exograph => "Exograph".to_owned(),
             PrimitiveType::ExographPriv => "ExographPriv".to_owned(),
             PrimitiveType::Interception(name) => name.to_owned(),
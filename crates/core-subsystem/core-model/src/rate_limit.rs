@@ -0,0 +1,64 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+use crate::context_type::ContextSelection;
+
+/// The rate limit of operations (specified using `@rateLimit(limit: 10, window: "1m", key: AuthContext.id)`)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimit {
+    /// The name of the annotated element (such as a type or a module method). All operations
+    /// limited through the same annotation share the same allowance.
+    pub scope: String,
+    /// The number of operations allowed within the window
+    pub limit: u32,
+    /// The length of the window in seconds
+    pub window_secs: u64,
+    /// The context value that identifies a client (such as `AuthContext.id`). If not specified, all
+    /// clients share the same allowance.
+    pub key: Option<ContextSelection>,
+}
+
+impl RateLimit {
+    /// Parse a window such as `30s`, `1m`, `2h`, or `1d` into seconds
+    pub fn parse_window(window: &str) -> Option<u64> {
+        let unit_index = window.find(|c: char| !c.is_ascii_digit())?;
+        let (value, unit) = window.split_at(unit_index);
+        let value: u64 = value.parse().ok()?;
+
+        let multiplier = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return None,
+        };
+
+        value.checked_mul(multiplier).filter(|secs| *secs > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_window() {
+        assert_eq!(RateLimit::parse_window("30s"), Some(30));
+        assert_eq!(RateLimit::parse_window("1m"), Some(60));
+        assert_eq!(RateLimit::parse_window("2h"), Some(7200));
+        assert_eq!(RateLimit::parse_window("1d"), Some(86400));
+
+        assert_eq!(RateLimit::parse_window("0s"), None);
+        assert_eq!(RateLimit::parse_window("10"), None);
+        assert_eq!(RateLimit::parse_window("m"), None);
+        assert_eq!(RateLimit::parse_window("1w"), None);
+    }
+}
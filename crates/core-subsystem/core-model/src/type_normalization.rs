@@ -16,7 +16,7 @@ use async_graphql_parser::{
 };
 use async_graphql_value::Name;
 
-use crate::primitive_type::vector_introspection_type;
+use crate::{cost::cost_directives, primitive_type::vector_introspection_type};
 
 pub trait FieldDefinitionProvider<S> {
     fn field_definition(&self, system: &S) -> FieldDefinition;
@@ -182,6 +182,11 @@ pub trait Operation {
     fn name(&self) -> &String;
     fn parameters(&self) -> Vec<&dyn Parameter>;
     fn return_type(&self) -> Type;
+
+    /// The weight of the operation for query cost analysis (`@cost(...)`)
+    fn cost(&self) -> Option<u32> {
+        None
+    }
 }
 
 // Field definition for the query such as `venue(id: Int!): Venue`, combining such fields will form
@@ -198,7 +203,7 @@ impl<T: Operation, S> FieldDefinitionProvider<S> for T {
             description: None,
             name: default_positioned_name(self.name()),
             arguments: fields,
-            directives: vec![],
+            directives: cost_directives(self.cost()),
             ty: default_positioned(self.return_type()),
         }
     }
@@ -37,8 +37,7 @@ impl TrustedDocuments {
         query_hash: Option<&str>,
         enforcement: TrustedDocumentEnforcement,
     ) -> Result<&str, TrustedDocumentResolutionError> {
-        let allow_untrusted = matches!(self, TrustedDocuments::All(_))
-            || matches!(enforcement, TrustedDocumentEnforcement::DoNotEnforce);
+        let allow_untrusted = self.allows_untrusted(&enforcement);
 
         match (query, query_hash) {
             (Some(query), None) => {
@@ -71,6 +70,16 @@ impl TrustedDocuments {
         }
     }
 
+    /// Whether documents other than the trusted ones may be executed
+    pub fn allows_untrusted(&self, enforcement: &TrustedDocumentEnforcement) -> bool {
+        matches!(self, TrustedDocuments::All(_))
+            || matches!(enforcement, TrustedDocumentEnforcement::DoNotEnforce)
+    }
+
+    pub fn contains(&self, query_hash: &str) -> bool {
+        self.get(query_hash).is_some()
+    }
+
     fn get<'a>(&'a self, key: &str) -> Option<&'a str> {
         match self {
             TrustedDocuments::All(mapping) => mapping.get(key),
@@ -79,7 +88,7 @@ impl TrustedDocuments {
         .map(|s| s.as_str())
     }
 
-    fn sha256(query: &str) -> String {
+    pub fn sha256(query: &str) -> String {
         let query_hash = sha2::Sha256::digest(query.as_bytes());
         base16ct::lower::encode_string(&query_hash)
     }
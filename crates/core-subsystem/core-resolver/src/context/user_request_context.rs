@@ -126,4 +126,17 @@ impl<'a> UserRequestContext<'a> {
             .await
             .ensure_transaction();
     }
+
+    /// Run `f` once the changes made so far are committed (see [TransactionHolder::on_commit])
+    pub async fn on_commit(&self, f: impl FnOnce() + Send + 'static) {
+        self.transaction_holder.as_ref().lock().await.on_commit(f);
+    }
+
+    pub async fn in_transaction(&self) -> bool {
+        self.transaction_holder
+            .as_ref()
+            .lock()
+            .await
+            .in_transaction()
+    }
 }
@@ -77,13 +77,27 @@ impl Schema {
             mutations
         };
 
-        Self::new(type_definitions, queries, mutations)
+        let subscriptions = {
+            let mut subscriptions = subsystem_resolvers
+                .iter()
+                .fold(vec![], |mut acc, resolver| {
+                    acc.extend(resolver.schema_subscriptions());
+                    acc
+                });
+
+            // ensure introspection outputs subscriptions in a stable order
+            subscriptions.sort_by_key(|s| s.name.clone());
+            subscriptions
+        };
+
+        Self::new(type_definitions, queries, mutations, subscriptions)
     }
 
     pub fn new(
         type_definitions: Vec<TypeDefinition>,
         queries: Vec<FieldDefinition>,
         mutations: Vec<FieldDefinition>,
+        subscriptions: Vec<FieldDefinition>,
     ) -> Schema {
         let mut type_definitions = type_definitions;
 
@@ -122,6 +136,19 @@ impl Schema {
             });
         };
 
+        if !subscriptions.is_empty() {
+            type_definitions.push(TypeDefinition {
+                extend: false,
+                description: None,
+                name: default_positioned_name(SUBSCRIPTION_ROOT_TYPENAME),
+                directives: vec![],
+                kind: TypeKind::Object(ObjectType {
+                    implements: vec![],
+                    fields: subscriptions.into_iter().map(default_positioned).collect(),
+                }),
+            });
+        };
+
         type_definitions.push(Self::create_schema_type_definition());
         type_definitions.push(Self::create_type_definition());
         type_definitions.push(Self::create_field_definition());
@@ -163,9 +190,17 @@ impl Schema {
             }
         }
 
-        let used_scalars = scalars
-            .into_iter()
-            .filter(|td| !unused_scalars_names.contains(td.name.node.as_str()));
+        // A type exposed with a richer kind (such as an enum) may also be exposed as a scalar (for
+        // example, as the operand type of a filter), so prefer the richer definition
+        let non_scalar_names: HashSet<&str> = type_definitions
+            .iter()
+            .map(|td| td.name.node.as_str())
+            .collect();
+
+        let used_scalars = scalars.into_iter().filter(|td| {
+            !unused_scalars_names.contains(td.name.node.as_str())
+                && !non_scalar_names.contains(td.name.node.as_str())
+        });
         // Create a unique list of scalars (each subsystem may expose the same scalar)
         let unique_scalars: HashMap<_, _> = used_scalars
             .into_iter()
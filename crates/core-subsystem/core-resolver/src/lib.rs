@@ -12,9 +12,13 @@ pub mod context;
 pub mod context_extractor;
 pub mod http;
 pub mod introspection;
+pub mod metrics;
 pub mod number_cmp;
 pub mod operation_resolver;
+pub mod persisted_queries;
 pub mod plugin;
+pub mod rate_limit;
+pub mod readiness;
 pub mod system_resolver;
 pub mod validation;
 pub mod value;
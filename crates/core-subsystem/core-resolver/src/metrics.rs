@@ -0,0 +1,317 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Server metrics exposed in the Prometheus text format.
+//!
+//! Metrics are collected only after [enable] has been called (servers do so when `EXO_METRICS_ENABLED`
+//! is set to `true`). Operation counts, latencies, and errors are recorded as requests are resolved,
+//! whereas pool utilization is sampled from the subsystem resolvers when the metrics are rendered.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+/// Upper bounds (in seconds) of the operation latency histogram buckets
+const DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Operation label used for operations that failed validation
+const INVALID_OPERATION: &str = "invalid";
+
+/// Operation label used for operations once there are [MAX_OPERATION_LABELS] distinct labels
+const OTHER_OPERATIONS: &str = "other";
+
+/// The maximum number of distinct operation labels. Clients choose which root fields an operation
+/// selects, so without a bound they could create an unbounded number of series.
+const MAX_OPERATION_LABELS: usize = 1000;
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// Start collecting metrics
+pub fn enable() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::default)
+}
+
+/// The metrics registry (`None` if metrics have not been enabled)
+pub fn get() -> Option<&'static Metrics> {
+    METRICS.get()
+}
+
+/// The label to record an operation under: the names of its (validated) root fields, such as
+/// `concerts,venues`. We don't use the operation name, since clients may set it to any value.
+pub fn operation_label<'a>(root_field_names: impl IntoIterator<Item = &'a str>) -> String {
+    root_field_names
+        .into_iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Utilization of a pool of resources (such as database connections, Deno workers, or WASM
+/// instances)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolStats {
+    /// The kind of the pool (for example, "postgres", "deno", or "wasm")
+    pub pool: &'static str,
+    /// The name of the pool within its kind (for example, the path of a Deno module)
+    pub name: String,
+    /// The number of resources currently in the pool
+    pub size: usize,
+    /// The number of resources currently in use
+    pub in_use: usize,
+    /// The maximum number of resources the pool may hold (if bounded)
+    pub max_size: Option<usize>,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    operations: Mutex<BTreeMap<String, OperationStats>>,
+    errors: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+#[derive(Default)]
+struct OperationStats {
+    successes: u64,
+    failures: u64,
+    bucket_counts: [u64; DURATION_BUCKETS.len()],
+    duration_sum: f64,
+}
+
+impl Metrics {
+    /// Record the outcome of resolving an operation (`operation_label` is `None` if the operation
+    /// failed validation; see [operation_label])
+    pub fn record_operation(
+        &self,
+        operation_label: Option<&str>,
+        duration: Duration,
+        success: bool,
+    ) {
+        let mut operations = self.operations.lock().unwrap();
+
+        let label = match operation_label {
+            Some(label)
+                if operations.contains_key(label) || operations.len() < MAX_OPERATION_LABELS =>
+            {
+                label
+            }
+            Some(_) => OTHER_OPERATIONS,
+            None => INVALID_OPERATION,
+        };
+        let stats = operations.entry(label.to_string()).or_default();
+
+        if success {
+            stats.successes += 1;
+        } else {
+            stats.failures += 1;
+        }
+
+        let seconds = duration.as_secs_f64();
+        stats.duration_sum += seconds;
+        for (bound, count) in DURATION_BUCKETS.iter().zip(stats.bucket_counts.iter_mut()) {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Record an error (`kind` is typically [crate::system_resolver::SystemResolutionError::kind])
+    pub fn record_error(&self, kind: &'static str) {
+        *self.errors.lock().unwrap().entry(kind).or_default() += 1;
+    }
+
+    /// Render the metrics (along with the supplied pool utilization) in the Prometheus text format
+    pub fn render(&self, pool_stats: &[PoolStats]) -> String {
+        let mut out = String::new();
+
+        {
+            let operations = self.operations.lock().unwrap();
+
+            write_header(
+                &mut out,
+                "exograph_operations_total",
+                "counter",
+                "Number of resolved operations",
+            );
+            for (name, stats) in operations.iter() {
+                let name = escape_label_value(name);
+                for (status, count) in [("success", stats.successes), ("error", stats.failures)] {
+                    writeln!(
+                        out,
+                        "exograph_operations_total{{operation=\"{name}\",status=\"{status}\"}} {count}"
+                    )
+                    .unwrap();
+                }
+            }
+
+            write_header(
+                &mut out,
+                "exograph_operation_duration_seconds",
+                "histogram",
+                "Time taken to resolve operations",
+            );
+            for (name, stats) in operations.iter() {
+                let name = escape_label_value(name);
+                for (bound, count) in DURATION_BUCKETS.iter().zip(stats.bucket_counts.iter()) {
+                    writeln!(
+                        out,
+                        "exograph_operation_duration_seconds_bucket{{operation=\"{name}\",le=\"{bound}\"}} {count}"
+                    )
+                    .unwrap();
+                }
+                let total = stats.successes + stats.failures;
+                writeln!(
+                    out,
+                    "exograph_operation_duration_seconds_bucket{{operation=\"{name}\",le=\"+Inf\"}} {total}"
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "exograph_operation_duration_seconds_sum{{operation=\"{name}\"}} {}",
+                    stats.duration_sum
+                )
+                .unwrap();
+                writeln!(
+                    out,
+                    "exograph_operation_duration_seconds_count{{operation=\"{name}\"}} {total}"
+                )
+                .unwrap();
+            }
+        }
+
+        write_header(
+            &mut out,
+            "exograph_errors_total",
+            "counter",
+            "Number of errors by kind",
+        );
+        for (kind, count) in self.errors.lock().unwrap().iter() {
+            writeln!(out, "exograph_errors_total{{kind=\"{kind}\"}} {count}").unwrap();
+        }
+
+        let pool_gauges: [(&str, &str, fn(&PoolStats) -> Option<usize>); 3] = [
+            (
+                "exograph_pool_size",
+                "Number of resources in the pool",
+                |stats| Some(stats.size),
+            ),
+            (
+                "exograph_pool_in_use",
+                "Number of resources in the pool that are in use",
+                |stats| Some(stats.in_use),
+            ),
+            (
+                "exograph_pool_max_size",
+                "Maximum number of resources in the pool",
+                |stats| stats.max_size,
+            ),
+        ];
+
+        for (metric, help, value) in pool_gauges {
+            write_header(&mut out, metric, "gauge", help);
+            for stats in pool_stats {
+                if let Some(value) = value(stats) {
+                    writeln!(
+                        out,
+                        "{metric}{{pool=\"{}\",name=\"{}\"}} {value}",
+                        stats.pool,
+                        escape_label_value(&stats.name)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn write_header(out: &mut String, metric: &str, metric_type: &str, help: &str) {
+    writeln!(out, "# HELP {metric} {help}").unwrap();
+    writeln!(out, "# TYPE {metric} {metric_type}").unwrap();
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_metrics() {
+        let metrics = Metrics::default();
+
+        metrics.record_operation(Some("concerts"), Duration::from_millis(20), true);
+        metrics.record_operation(Some("concerts"), Duration::from_millis(200), false);
+        metrics.record_operation(None, Duration::from_secs(20), false);
+        metrics.record_error("validation");
+
+        let rendered = metrics.render(&[PoolStats {
+            pool: "postgres",
+            name: "default".to_string(),
+            size: 4,
+            in_use: 1,
+            max_size: Some(10),
+        }]);
+
+        for expected in [
+            "exograph_operations_total{operation=\"concerts\",status=\"success\"} 1",
+            "exograph_operations_total{operation=\"concerts\",status=\"error\"} 1",
+            "exograph_operations_total{operation=\"invalid\",status=\"error\"} 1",
+            "exograph_operation_duration_seconds_bucket{operation=\"concerts\",le=\"0.01\"} 0",
+            "exograph_operation_duration_seconds_bucket{operation=\"concerts\",le=\"0.025\"} 1",
+            "exograph_operation_duration_seconds_bucket{operation=\"concerts\",le=\"0.25\"} 2",
+            "exograph_operation_duration_seconds_bucket{operation=\"invalid\",le=\"10\"} 0",
+            "exograph_operation_duration_seconds_bucket{operation=\"invalid\",le=\"+Inf\"} 1",
+            "exograph_operation_duration_seconds_count{operation=\"concerts\"} 2",
+            "exograph_errors_total{kind=\"validation\"} 1",
+            "exograph_pool_size{pool=\"postgres\",name=\"default\"} 4",
+            "exograph_pool_in_use{pool=\"postgres\",name=\"default\"} 1",
+            "exograph_pool_max_size{pool=\"postgres\",name=\"default\"} 10",
+        ] {
+            assert!(
+                rendered.lines().any(|line| line == expected),
+                "Missing `{expected}` in:\n{rendered}"
+            );
+        }
+    }
+
+    #[test]
+    fn label_by_root_fields() {
+        assert_eq!(
+            operation_label(["venues", "concerts", "venues"]),
+            "concerts,venues"
+        );
+    }
+
+    #[test]
+    fn bound_operation_labels() {
+        let metrics = Metrics::default();
+
+        for i in 0..MAX_OPERATION_LABELS + 10 {
+            metrics.record_operation(Some(&format!("field{i}")), Duration::from_millis(1), true);
+        }
+        // An existing label is still recorded under its own name
+        metrics.record_operation(Some("field0"), Duration::from_millis(1), true);
+
+        let operations = metrics.operations.lock().unwrap();
+        assert_eq!(operations.len(), MAX_OPERATION_LABELS + 1);
+        assert_eq!(operations[OTHER_OPERATIONS].successes, 10);
+        assert_eq!(operations["field0"].successes, 2);
+    }
+}
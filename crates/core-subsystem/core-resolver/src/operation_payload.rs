@@ -35,11 +35,9 @@ impl OperationsPayload {
             let query_hash = raw_payload.extensions.as_ref().and_then(|extensions| {
                 extensions
                     .get("persistedQuery")
-                    .and_then(|persisted_query| {
-                        persisted_query
-                            .get("sha256Hash")
-                            .map(|hash| hash.as_str().unwrap().to_string())
-                    })
+                    .and_then(|persisted_query| persisted_query.get("sha256Hash"))
+                    .and_then(|hash| hash.as_str())
+                    .map(|hash| hash.to_string())
             });
 
             OperationsPayload {
@@ -59,6 +57,8 @@ impl OperationsPayload {
             extensions: self.query_hash.as_ref().map(|query_hash| {
                 let mut extensions = Map::new();
                 let mut persisted_query = Map::new();
+                // The only version of the automatic persisted queries protocol
+                persisted_query.insert("version".to_string(), Value::from(1));
                 persisted_query.insert("sha256Hash".to_string(), Value::String(query_hash.clone()));
                 extensions.insert("persistedQuery".to_string(), Value::Object(persisted_query));
                 extensions
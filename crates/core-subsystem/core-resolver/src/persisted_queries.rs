@@ -0,0 +1,111 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Support for the [Automatic Persisted Queries](https://www.apollographql.com/docs/apollo-server/performance/apq/)
+//! (APQ) protocol.
+//!
+//! A client first sends only the SHA-256 hash of a query (in `extensions.persistedQuery.sha256Hash`).
+//! If the server doesn't know the hash, it responds with a `PersistedQueryNotFound` error, and the
+//! client resends the request with both the query and its hash, which registers the query for
+//! subsequent requests.
+//!
+//! Unlike trusted documents, which are fixed at build time, persisted queries are registered by
+//! clients. Therefore, registration (as well as lookup of registered queries) is available only
+//! when trusted documents aren't enforced for a request.
+
+use std::sync::Mutex;
+
+use indexmap::IndexMap;
+use thiserror::Error;
+
+/// Storage of queries registered through the APQ protocol (keyed by the SHA-256 hash of the query)
+pub trait PersistedQueryStore: Send + Sync {
+    fn get(&self, hash: &str) -> Option<String>;
+
+    fn insert(&self, hash: String, query: String);
+}
+
+/// A [PersistedQueryStore] that keeps up to `capacity` queries in memory (evicting the least
+/// recently used query when full)
+pub struct InMemoryPersistedQueryStore {
+    capacity: usize,
+    queries: Mutex<IndexMap<String, String>>,
+}
+
+impl InMemoryPersistedQueryStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            queries: Mutex::new(IndexMap::new()),
+        }
+    }
+}
+
+impl PersistedQueryStore for InMemoryPersistedQueryStore {
+    fn get(&self, hash: &str) -> Option<String> {
+        let mut queries = self.queries.lock().unwrap();
+
+        // Mark the query as the most recently used one
+        let index = queries.get_index_of(hash)?;
+        let last = queries.len() - 1;
+        queries.move_index(index, last);
+        queries.get_index(last).map(|(_, query)| query.clone())
+    }
+
+    fn insert(&self, hash: String, query: String) {
+        let mut queries = self.queries.lock().unwrap();
+
+        if !queries.contains_key(&hash) && queries.len() >= self.capacity {
+            queries.shift_remove_index(0);
+        }
+
+        queries.shift_remove(&hash);
+        queries.insert(hash, query);
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PersistedQueryError {
+    // The message (and the code below) is what APQ clients look for to resend the query
+    #[error("PersistedQueryNotFound")]
+    NotFound,
+
+    #[error("The persisted query hash does not match the query")]
+    HashMismatch,
+}
+
+impl PersistedQueryError {
+    /// The value of `extensions.code` in the error response
+    pub fn code(&self) -> &'static str {
+        match self {
+            PersistedQueryError::NotFound => "PERSISTED_QUERY_NOT_FOUND",
+            PersistedQueryError::HashMismatch => "PERSISTED_QUERY_HASH_MISMATCH",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let store = InMemoryPersistedQueryStore::new(2);
+
+        store.insert("a".into(), "query a".into());
+        store.insert("b".into(), "query b".into());
+        // Using "a" makes "b" the least recently used query
+        assert_eq!(store.get("a"), Some("query a".to_string()));
+        store.insert("c".into(), "query c".into());
+
+        assert_eq!(store.get("a"), Some("query a".to_string()));
+        assert_eq!(store.get("b"), None);
+        assert_eq!(store.get("c"), Some("query c".to_string()));
+    }
+}
@@ -8,4 +8,4 @@
 // by the Apache License, Version 2.0.
 
 pub mod subsystem_resolver;
-pub use subsystem_resolver::{SubsystemResolutionError, SubsystemResolver};
+pub use subsystem_resolver::{SubscriptionStream, SubsystemResolutionError, SubsystemResolver};
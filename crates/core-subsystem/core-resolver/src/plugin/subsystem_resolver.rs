@@ -8,12 +8,14 @@
 // by the Apache License, Version 2.0.
 
 use crate::{
-    context::RequestContext, system_resolver::SystemResolver, validation::field::ValidatedField,
+    context::RequestContext, metrics::PoolStats, rate_limit::RateLimitBucket,
+    readiness::ReadinessCheck, system_resolver::SystemResolver, validation::field::ValidatedField,
     InterceptedOperation, QueryResponse,
 };
 use async_graphql_parser::types::{FieldDefinition, OperationType, TypeDefinition};
 use async_trait::async_trait;
 use core_plugin_shared::interception::InterceptorIndex;
+use futures::stream::BoxStream;
 use thiserror::Error;
 use tokio::runtime::Handle;
 
@@ -92,6 +94,42 @@ pub trait SubsystemResolver: Sync {
         system_resolver: &'a SystemResolver,
     ) -> Result<Option<QueryResponse>, SubsystemResolutionError>;
 
+    /// Subscribe to an individual subscription operation
+    ///
+    /// Returns `None` if the operation is not handled by this subsystem. Each item in the returned
+    /// stream is the response for one event (in the same shape as the response to the equivalent
+    /// query). The stream ends when the subsystem has no more events to deliver.
+    async fn subscribe<'a>(
+        &'a self,
+        _operation: &'a ValidatedField,
+        _request_context: &'a RequestContext<'a>,
+        _system_resolver: &'a SystemResolver,
+    ) -> Result<Option<SubscriptionStream<'a>>, SubsystemResolutionError> {
+        Ok(None)
+    }
+
+    /// The rate limit bucket the operation draws from (see [crate::rate_limit])
+    ///
+    /// Returns `None` if the operation is not handled by this subsystem or is not rate limited
+    async fn rate_limit<'a>(
+        &'a self,
+        _operation: &'a ValidatedField,
+        _operation_type: OperationType,
+        _request_context: &'a RequestContext<'a>,
+    ) -> Result<Option<RateLimitBucket>, SubsystemResolutionError> {
+        Ok(None)
+    }
+
+    /// Utilization of the pools (database connections, workers, etc.) held by this subsystem
+    async fn pool_stats(&self) -> Vec<PoolStats> {
+        vec![]
+    }
+
+    /// Check if the resources this subsystem depends on (databases, modules, etc.) are available
+    async fn check_readiness(&self) -> Vec<ReadinessCheck> {
+        vec![]
+    }
+
     // Support for schema creation (and in turn, validation)
 
     /// Queries supported by this subsystem
@@ -99,11 +137,18 @@ pub trait SubsystemResolver: Sync {
     /// Mutations supported by this subsystem
 
     fn schema_mutations(&self) -> Vec<FieldDefinition>;
+    /// Subscriptions supported by this subsystem
+    fn schema_subscriptions(&self) -> Vec<FieldDefinition> {
+        vec![]
+    }
     /// Types supported by this subsystem. This includes types explicitly defined by user types as
     /// well as types derived from user types (such as for predicates)
     fn schema_types(&self) -> Vec<TypeDefinition>;
 }
 
+/// Stream of responses produced by a subscription
+pub type SubscriptionStream<'a> = BoxStream<'a, Result<QueryResponse, SubsystemResolutionError>>;
+
 #[derive(Error, Debug)]
 pub enum SubsystemResolutionError {
     #[error("Invalid field {0} for {1}")]
@@ -117,6 +162,9 @@ pub enum SubsystemResolutionError {
 
     #[error("No interceptor found")]
     NoInterceptorFound, // Almost certainly a programming error (we asked a wrong subsystem)
+
+    #[error("Operation timed out")]
+    Timeout, // User code (such as a Deno or WASM function) exceeded its time limit
 }
 
 impl SubsystemResolutionError {
@@ -128,6 +176,7 @@ impl SubsystemResolutionError {
             SubsystemResolutionError::Authorization => Some("Not authorized".to_string()),
             SubsystemResolutionError::UserDisplayError(message) => Some(message.to_string()),
             SubsystemResolutionError::NoInterceptorFound => None,
+            SubsystemResolutionError::Timeout => Some("Operation timed out".to_string()),
         }
     }
 }
@@ -0,0 +1,407 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Enforcement of rate limits (specified using `@rateLimit(limit: 10, window: "1m", key: AuthContext.id)`).
+//!
+//! Subsystems map an operation to a [RateLimitBucket] (see
+//! [SubsystemResolver::rate_limit](crate::plugin::SubsystemResolver::rate_limit)), and the
+//! [SystemResolver](crate::system_resolver::SystemResolver) acquires a permit from its
+//! [RateLimitStore] before dispatching the operation. Only operations sent by clients are limited;
+//! operations executed on their behalf (such as through `executeQuery` in a Deno module) are not.
+//!
+//! Each bucket is a token bucket holding up to `limit` tokens, which refills continuously at the
+//! rate of `limit` tokens per `window`. Each operation consumes one token. The top-level fields of a
+//! request acquire their tokens together: if any bucket is exhausted, none is drawn from.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use core_model::rate_limit::RateLimit;
+use thiserror::Error;
+
+use crate::{
+    context::{ContextExtractionError, RequestContext},
+    context_extractor::ContextExtractor,
+    value::Val,
+};
+
+/// The allowance an operation draws from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitBucket {
+    /// Identifies the allowance. Combines the scope of the rate limit with the value of its key, so
+    /// that each client (for example, each user) gets its own allowance.
+    pub key: String,
+    pub limit: u32,
+    pub window: Duration,
+}
+
+impl RateLimitBucket {
+    /// Compute the bucket for the current request. If the key of the rate limit isn't available
+    /// in the request (for example, for an unauthenticated request), all such requests share the
+    /// same allowance.
+    pub async fn new<'a>(
+        rate_limit: &RateLimit,
+        context_extractor: &(impl ContextExtractor + Sync),
+        request_context: &'a RequestContext<'a>,
+    ) -> Result<Self, ContextExtractionError> {
+        let key_value = match &rate_limit.key {
+            Some(key) => {
+                match context_extractor
+                    .extract_context_selection(request_context, key)
+                    .await?
+                {
+                    Some(Val::Null) | None => String::new(),
+                    Some(value) => value.to_string(),
+                }
+            }
+            None => String::new(),
+        };
+
+        Ok(Self {
+            key: format!("{}:{}", rate_limit.scope, key_value),
+            limit: rate_limit.limit,
+            window: Duration::from_secs(rate_limit.window_secs),
+        })
+    }
+}
+
+/// Storage of the state of rate limit buckets.
+///
+/// The in-process [InMemoryRateLimitStore] limits each server instance independently. To share
+/// allowances across instances, implement this trait over a shared store (such as Redis).
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Consume one token from each of the buckets (a bucket listed multiple times gives up that
+    /// many tokens). Either all the tokens are consumed, or none is and the error reports when
+    /// enough tokens will be available.
+    async fn acquire(&self, buckets: &[RateLimitBucket]) -> Result<(), RateLimitError>;
+}
+
+#[derive(Error, Debug)]
+pub enum RateLimitError {
+    #[error("Rate limit exceeded. Retry after {} seconds", ceil_secs(.retry_after))]
+    Exceeded { retry_after: Duration },
+
+    #[error("Rate limit store error: {0}")]
+    Store(String),
+}
+
+impl RateLimitError {
+    /// The value of `extensions.code` in the error response
+    pub fn code(&self) -> &'static str {
+        match self {
+            RateLimitError::Exceeded { .. } => "RATE_LIMITED",
+            RateLimitError::Store(_) => "INTERNAL_SERVER_ERROR",
+        }
+    }
+
+    /// The value of the `Retry-After` header (in whole seconds)
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            RateLimitError::Exceeded { retry_after } => Some(ceil_secs(retry_after)),
+            RateLimitError::Store(_) => None,
+        }
+    }
+}
+
+fn ceil_secs(retry_after: &Duration) -> u64 {
+    // Round up, so that a retry after the suggested time succeeds
+    let secs = retry_after.as_secs();
+    if retry_after.subsec_nanos() > 0 {
+        secs + 1
+    } else {
+        secs
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub use in_memory::InMemoryRateLimitStore;
+
+#[cfg(not(target_family = "wasm"))]
+mod in_memory {
+    use std::{
+        collections::HashMap,
+        sync::Mutex,
+        time::{Duration, Instant},
+    };
+
+    use async_trait::async_trait;
+
+    use super::{RateLimitBucket, RateLimitError, RateLimitStore};
+
+    /// How often we drop buckets that have fully refilled (which are equivalent to absent ones)
+    const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// The maximum number of buckets. To make room for a new bucket beyond that, we drop the one
+    /// that will refill the soonest (thus granting its client a full allowance a bit early).
+    const MAX_BUCKETS: usize = 100_000;
+
+    struct BucketState {
+        tokens: f64,
+        last_refill: Instant,
+        full_at: Instant,
+    }
+
+    #[derive(Default)]
+    struct Buckets {
+        states: HashMap<String, BucketState>,
+        /// When we last dropped the full buckets (`None` if we haven't yet)
+        last_pruned: Option<Instant>,
+    }
+
+    impl Buckets {
+        fn prune(&mut self, now: Instant) {
+            let due = self.last_pruned.map_or(true, |last_pruned| {
+                now.saturating_duration_since(last_pruned) >= PRUNE_INTERVAL
+            });
+
+            if due {
+                self.states.retain(|_, state| state.full_at > now);
+                self.last_pruned = Some(now);
+            }
+        }
+
+        fn make_room(&mut self, key: &str, max_buckets: usize) {
+            if self.states.len() < max_buckets || self.states.contains_key(key) {
+                return;
+            }
+
+            let refilling_soonest = self
+                .states
+                .iter()
+                .min_by_key(|(_, state)| state.full_at)
+                .map(|(key, _)| key.clone());
+
+            if let Some(refilling_soonest) = refilling_soonest {
+                self.states.remove(&refilling_soonest);
+            }
+        }
+    }
+
+    /// A [RateLimitStore] that keeps buckets in the memory of the current process
+    pub struct InMemoryRateLimitStore {
+        buckets: Mutex<Buckets>,
+        max_buckets: usize,
+    }
+
+    impl Default for InMemoryRateLimitStore {
+        fn default() -> Self {
+            Self {
+                buckets: Mutex::default(),
+                max_buckets: MAX_BUCKETS,
+            }
+        }
+    }
+
+    impl InMemoryRateLimitStore {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        fn acquire_at(
+            &self,
+            buckets_to_acquire: &[RateLimitBucket],
+            now: Instant,
+        ) -> Result<(), RateLimitError> {
+            // The number of tokens to consume from each bucket
+            let mut demands: Vec<(&RateLimitBucket, f64)> = vec![];
+            for bucket in buckets_to_acquire {
+                match demands.iter_mut().find(|(b, _)| b.key == bucket.key) {
+                    Some((_, tokens)) => *tokens += 1.0,
+                    None => demands.push((bucket, 1.0)),
+                }
+            }
+
+            let mut buckets = self.buckets.lock().unwrap();
+
+            buckets.prune(now);
+
+            // Check every bucket before consuming from any, so that a rejected request doesn't
+            // draw from the allowance of its other buckets
+            let retry_after = demands
+                .iter()
+                .filter_map(|(bucket, demand)| {
+                    let available = buckets
+                        .states
+                        .get(&bucket.key)
+                        .map_or(bucket.limit as f64, |state| {
+                            refilled_tokens(state, bucket, now)
+                        });
+
+                    (available < *demand).then(|| {
+                        Duration::from_secs_f64((demand - available) / refill_per_sec(bucket))
+                    })
+                })
+                .max();
+
+            if let Some(retry_after) = retry_after {
+                return Err(RateLimitError::Exceeded { retry_after });
+            }
+
+            for (bucket, demand) in demands {
+                buckets.make_room(&bucket.key, self.max_buckets);
+
+                let capacity = bucket.limit as f64;
+
+                let state =
+                    buckets
+                        .states
+                        .entry(bucket.key.clone())
+                        .or_insert_with(|| BucketState {
+                            tokens: capacity,
+                            last_refill: now,
+                            full_at: now,
+                        });
+
+                state.tokens = refilled_tokens(state, bucket, now) - demand;
+                state.last_refill = now;
+                state.full_at = now
+                    + Duration::from_secs_f64((capacity - state.tokens) / refill_per_sec(bucket));
+            }
+
+            Ok(())
+        }
+    }
+
+    fn refill_per_sec(bucket: &RateLimitBucket) -> f64 {
+        bucket.limit as f64 / bucket.window.as_secs_f64()
+    }
+
+    /// The tokens in the bucket, accounting for the refill since it was last updated
+    fn refilled_tokens(state: &BucketState, bucket: &RateLimitBucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(state.last_refill);
+        (state.tokens + elapsed.as_secs_f64() * refill_per_sec(bucket)).min(bucket.limit as f64)
+    }
+
+    #[async_trait]
+    impl RateLimitStore for InMemoryRateLimitStore {
+        async fn acquire(&self, buckets: &[RateLimitBucket]) -> Result<(), RateLimitError> {
+            self.acquire_at(buckets, Instant::now())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn bucket(key: &str) -> RateLimitBucket {
+            RateLimitBucket {
+                key: key.to_string(),
+                limit: 2,
+                window: Duration::from_secs(4),
+            }
+        }
+
+        #[test]
+        fn refills_over_the_window() {
+            let store = InMemoryRateLimitStore::new();
+            let start = Instant::now();
+
+            assert!(store.acquire_at(&[bucket("a")], start).is_ok());
+            assert!(store.acquire_at(&[bucket("a")], start).is_ok());
+
+            // The allowance is exhausted, and a token refills every 2 seconds
+            match store.acquire_at(&[bucket("a")], start) {
+                Err(error @ RateLimitError::Exceeded { .. }) => {
+                    assert_eq!(error.retry_after_secs(), Some(2))
+                }
+                other => panic!("Expected the rate limit to be exceeded, got {other:?}"),
+            }
+
+            // Other keys have their own allowance
+            assert!(store.acquire_at(&[bucket("b")], start).is_ok());
+
+            let later = start + Duration::from_secs(2);
+            assert!(store.acquire_at(&[bucket("a")], later).is_ok());
+            assert!(store.acquire_at(&[bucket("a")], later).is_err());
+        }
+
+        #[test]
+        fn prunes_full_buckets_periodically() {
+            let store = InMemoryRateLimitStore::new();
+            let start = Instant::now();
+
+            assert!(store.acquire_at(&[bucket("a")], start).is_ok());
+            assert!(store.acquire_at(&[bucket("b")], start).is_ok());
+            assert!(store.acquire_at(&[bucket("b")], start).is_ok());
+
+            // Bucket "a" has fully refilled, but it isn't time to prune yet
+            let refilled = start + Duration::from_secs(4);
+            assert!(store.acquire_at(&[bucket("c")], refilled).is_ok());
+            assert_eq!(store.buckets.lock().unwrap().states.len(), 3);
+
+            // Only bucket "c" (which has just been drawn from) is still refilling
+            let pruned = start + PRUNE_INTERVAL;
+            assert!(store
+                .acquire_at(&[bucket("c")], pruned - Duration::from_secs(1))
+                .is_ok());
+            assert!(store.acquire_at(&[bucket("d")], pruned).is_ok());
+
+            let buckets = store.buckets.lock().unwrap();
+            let mut keys: Vec<_> = buckets.states.keys().map(String::as_str).collect();
+            keys.sort();
+            assert_eq!(keys, vec!["c", "d"]);
+        }
+
+        #[test]
+        fn bounds_the_number_of_buckets() {
+            let store = InMemoryRateLimitStore {
+                max_buckets: 2,
+                ..InMemoryRateLimitStore::new()
+            };
+            let start = Instant::now();
+
+            // Bucket "a" is exhausted, whereas bucket "b" will refill sooner
+            assert!(store.acquire_at(&[bucket("a")], start).is_ok());
+            assert!(store.acquire_at(&[bucket("a")], start).is_ok());
+            assert!(store.acquire_at(&[bucket("b")], start).is_ok());
+
+            // Making room for "c" drops "b"
+            assert!(store.acquire_at(&[bucket("c")], start).is_ok());
+
+            let buckets = store.buckets.lock().unwrap();
+            let mut keys: Vec<_> = buckets.states.keys().map(String::as_str).collect();
+            keys.sort();
+            assert_eq!(keys, vec!["a", "c"]);
+            drop(buckets);
+
+            // The exhausted bucket is still enforced
+            assert!(store.acquire_at(&[bucket("a")], start).is_err());
+        }
+
+        #[test]
+        fn acquires_all_buckets_or_none() {
+            let store = InMemoryRateLimitStore::new();
+            let start = Instant::now();
+
+            assert!(store.acquire_at(&[bucket("a"), bucket("b")], start).is_ok());
+            assert!(store.acquire_at(&[bucket("a")], start).is_ok());
+
+            // Bucket "a" is exhausted, so bucket "b" keeps its remaining token
+            match store.acquire_at(&[bucket("b"), bucket("a")], start) {
+                Err(error @ RateLimitError::Exceeded { .. }) => {
+                    assert_eq!(error.retry_after_secs(), Some(2))
+                }
+                other => panic!("Expected the rate limit to be exceeded, got {other:?}"),
+            }
+            assert!(store.acquire_at(&[bucket("b")], start).is_ok());
+
+            // A bucket listed twice gives up two tokens (and the wait covers both)
+            assert!(store.acquire_at(&[bucket("c"), bucket("c")], start).is_ok());
+            let later = start + Duration::from_secs(2);
+            match store.acquire_at(&[bucket("c"), bucket("c")], later) {
+                Err(error @ RateLimitError::Exceeded { .. }) => {
+                    assert_eq!(error.retry_after_secs(), Some(2))
+                }
+                other => panic!("Expected the rate limit to be exceeded, got {other:?}"),
+            }
+            assert!(store.acquire_at(&[bucket("c")], later).is_ok());
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Checks that determine if the server is ready to serve requests (for example, to respond to a
+//! readiness probe of a container orchestrator).
+
+use serde::Serialize;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadinessStatus {
+    Ok,
+    Error,
+}
+
+/// The outcome of checking a resource that a subsystem depends on
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessCheck {
+    /// What was checked (for example, "postgres.connection")
+    pub name: String,
+    pub status: ReadinessStatus,
+    /// Details for the server log (not serialized, since they may reveal database errors or the
+    /// schema to unauthenticated clients)
+    #[serde(skip)]
+    pub message: Option<String>,
+}
+
+impl ReadinessCheck {
+    pub fn ok(name: impl Into<String>, message: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: ReadinessStatus::Ok,
+            message,
+        }
+    }
+
+    pub fn error(name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: ReadinessStatus::Error,
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// The combined outcome of the checks of all subsystems (the server is ready only if every check
+/// succeeded)
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    pub status: ReadinessStatus,
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl ReadinessReport {
+    pub fn new(checks: Vec<ReadinessCheck>) -> Self {
+        let status = if checks
+            .iter()
+            .all(|check| check.status == ReadinessStatus::Ok)
+        {
+            ReadinessStatus::Ok
+        } else {
+            ReadinessStatus::Error
+        };
+
+        Self { status, checks }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.status == ReadinessStatus::Ok
+    }
+
+    /// Log the details of the failed checks
+    pub fn log_failures(&self) {
+        for check in &self.checks {
+            if check.status == ReadinessStatus::Error {
+                warn!(
+                    "Readiness check `{}` failed: {}",
+                    check.name,
+                    check.message.as_deref().unwrap_or("Unknown error")
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_is_ready_only_if_every_check_is_ok() {
+        let ready = ReadinessReport::new(vec![
+            ReadinessCheck::ok("postgres.connection", None),
+            ReadinessCheck::ok("deno.module.src/index.ts", Some("Loaded".to_string())),
+        ]);
+        assert!(ready.is_ready());
+
+        let not_ready = ReadinessReport::new(vec![
+            ReadinessCheck::ok("postgres.connection", None),
+            ReadinessCheck::error("postgres.schema", "- Table `concerts` is missing"),
+        ]);
+        assert!(!not_ready.is_ready());
+
+        assert_eq!(
+            serde_json::to_value(&not_ready).unwrap(),
+            serde_json::json!({
+                "status": "error",
+                "checks": [
+                    { "name": "postgres.connection", "status": "ok" },
+                    { "name": "postgres.schema", "status": "error" }
+                ]
+            })
+        );
+    }
+}
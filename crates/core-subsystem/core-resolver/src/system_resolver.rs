@@ -21,7 +21,7 @@ use core_plugin_shared::{
         TrustedDocumentEnforcement, TrustedDocumentResolutionError, TrustedDocuments,
     },
 };
-use futures::{future::BoxFuture, StreamExt};
+use futures::{future::BoxFuture, stream::BoxStream, StreamExt};
 use serde_json::{Map, Value};
 use thiserror::Error;
 use tokio::runtime::Handle;
@@ -34,7 +34,11 @@ use crate::{context::provider::jwt::JwtAuthenticator, OperationsPayload};
 use crate::{
     context::RequestContext,
     introspection::definition::schema::Schema,
+    metrics::PoolStats,
+    persisted_queries::{PersistedQueryError, PersistedQueryStore},
     plugin::{subsystem_resolver::SubsystemResolver, SubsystemResolutionError},
+    rate_limit::{RateLimitBucket, RateLimitError, RateLimitStore},
+    readiness::ReadinessReport,
     validation::{
         document_validator::DocumentValidator, field::ValidatedField,
         operation::ValidatedOperation, validation_error::ValidationError,
@@ -60,11 +64,14 @@ pub struct SystemResolver {
     query_interception_map: InterceptionMap,
     mutation_interception_map: InterceptionMap,
     trusted_documents: TrustedDocuments,
+    persisted_queries: Option<Box<dyn PersistedQueryStore>>,
     schema: Schema,
     pub jwt_authenticator: Arc<Option<JwtAuthenticator>>,
     pub env: Box<dyn Environment>,
     normal_query_depth_limit: usize,
     introspection_query_depth_limit: usize,
+    max_query_cost: Option<usize>,
+    rate_limit_store: Option<Box<dyn RateLimitStore>>,
 }
 
 impl SystemResolver {
@@ -74,11 +81,14 @@ impl SystemResolver {
         query_interception_map: InterceptionMap,
         mutation_interception_map: InterceptionMap,
         trusted_documents: TrustedDocuments,
+        persisted_queries: Option<Box<dyn PersistedQueryStore>>,
         schema: Schema,
         jwt_authenticator: Arc<Option<JwtAuthenticator>>,
         env: Box<dyn Environment>,
         normal_query_depth_limit: usize,
         introspection_query_depth_limit: usize,
+        max_query_cost: Option<usize>,
+        rate_limit_store: Option<Box<dyn RateLimitStore>>,
     ) -> Self {
         #[cfg(not(target_family = "wasm"))]
         let trusted_documents = if is_production() || get_enforce_trusted_documents() {
@@ -96,11 +106,14 @@ impl SystemResolver {
             query_interception_map,
             mutation_interception_map,
             trusted_documents,
+            persisted_queries,
             schema,
             jwt_authenticator,
             env,
             normal_query_depth_limit,
             introspection_query_depth_limit,
+            max_query_cost,
+            rate_limit_store,
         }
     }
 
@@ -136,18 +149,91 @@ impl SystemResolver {
         request_context: &RequestContext<'a>,
         trusted_document_enforcement: TrustedDocumentEnforcement,
     ) -> Result<Vec<(String, QueryResponse)>, SystemResolutionError> {
+        let operation =
+            self.validate_operations_payload(operations_payload, trusted_document_enforcement)?;
+
+        self.resolve_validated_operation(&operation, request_context)
+            .await
+    }
+
+    /// Resolve an already validated query or mutation operation.
+    ///
+    /// Subscriptions need a transport that can push multiple responses, so they must go through
+    /// [SystemResolver::subscribe] instead.
+    pub async fn resolve_validated_operation<'a>(
+        &self,
+        operation: &ValidatedOperation,
+        request_context: &RequestContext<'a>,
+    ) -> Result<Vec<(String, QueryResponse)>, SystemResolutionError> {
+        if operation.typ == OperationType::Subscription {
+            return Err(SystemResolutionError::SubscriptionNotSupported);
+        }
+
+        // If multiple operations are present, we need to ensure that we have a transaction
+        if operation.fields.len() > 1 {
+            request_context.ensure_transaction().await;
+        }
+        operation
+            .resolve_fields(&operation.fields, self, request_context)
+            .await
+    }
+
+    /// Subscribe to the provided subscription field.
+    ///
+    /// The field must come from a validated subscription operation (which guarantees that there is
+    /// exactly one top-level field). Each item of the returned stream carries the output name of
+    /// the field along with the response for one event.
+    #[instrument(
+        name = "SystemResolver::subscribe"
+        skip_all
+        )]
+    pub async fn subscribe<'a>(
+        &'a self,
+        operation: &'a ValidatedField,
+        request_context: &'a RequestContext<'a>,
+    ) -> Result<
+        BoxStream<'a, Result<(String, QueryResponse), SystemResolutionError>>,
+        SystemResolutionError,
+    > {
+        for resolver in self.subsystem_resolvers.iter() {
+            if let Some(stream) = resolver.subscribe(operation, request_context, self).await? {
+                let output_name = operation.output_name();
+
+                return Ok(stream
+                    .map(move |response| {
+                        response
+                            .map(|response| (output_name.clone(), response))
+                            .map_err(|e| e.into())
+                    })
+                    .boxed());
+            }
+        }
+
+        Err(SystemResolutionError::NoResolverFound)
+    }
+
+    /// Resolve the query in the payload (taking trusted documents into account) and validate it
+    /// against the schema.
+    pub fn validate_operations_payload(
+        &self,
+        operations_payload: OperationsPayload,
+        trusted_document_enforcement: TrustedDocumentEnforcement,
+    ) -> Result<ValidatedOperation, SystemResolutionError> {
+        let operations_payload =
+            self.resolve_persisted_query(operations_payload, &trusted_document_enforcement)?;
+
         let query = self.trusted_documents.resolve(
             operations_payload.query.as_deref(),
             operations_payload.query_hash.as_deref(),
             trusted_document_enforcement,
         );
 
-        let operation = match query {
-            Ok(query) => self.validate_operation(
+        match query {
+            Ok(query) => Ok(self.validate_operation(
                 query,
                 operations_payload.operation_name,
                 operations_payload.variables,
-            )?,
+            )?),
             // Special handing on introspection queries made by tools to be implicitly trusted
             // Introspection queries made by the playground and tools such as graphql-codegen send queries as a string
             // and have top-level field `__schema` (but we also allow `__type` and `__typename` to be more widely useful).
@@ -175,20 +261,53 @@ impl SystemResolver {
                     .into());
                 }
 
-                operation
+                Ok(operation)
             }
-            Err(e) => {
-                return Err(e.into());
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Handle the automatic persisted queries protocol (see [crate::persisted_queries]).
+    ///
+    /// Registers the query if the payload contains both the query and its hash, and looks up the
+    /// query if it contains only a hash (that isn't of a trusted document). Returns the payload to
+    /// be resolved against trusted documents. If trusted documents are enforced, the payload is
+    /// returned unchanged (thus refusing any registration).
+    fn resolve_persisted_query(
+        &self,
+        mut operations_payload: OperationsPayload,
+        trusted_document_enforcement: &TrustedDocumentEnforcement,
+    ) -> Result<OperationsPayload, SystemResolutionError> {
+        let persisted_queries = match &self.persisted_queries {
+            Some(persisted_queries)
+                if self
+                    .trusted_documents
+                    .allows_untrusted(trusted_document_enforcement) =>
+            {
+                persisted_queries
             }
+            _ => return Ok(operations_payload),
         };
 
-        // If multiple operations are present, we need to ensure that we have a transaction
-        if operation.fields.len() > 1 {
-            request_context.ensure_transaction().await;
+        match (&operations_payload.query, &operations_payload.query_hash) {
+            (Some(query), Some(query_hash)) => {
+                if !TrustedDocuments::sha256(query).eq_ignore_ascii_case(query_hash) {
+                    return Err(PersistedQueryError::HashMismatch.into());
+                }
+                persisted_queries.insert(query_hash.clone(), query.clone());
+                operations_payload.query_hash = None;
+            }
+            (None, Some(query_hash)) if !self.trusted_documents.contains(query_hash) => {
+                let query = persisted_queries
+                    .get(query_hash)
+                    .ok_or(PersistedQueryError::NotFound)?;
+                operations_payload.query = Some(query);
+                operations_payload.query_hash = None;
+            }
+            _ => {}
         }
-        operation
-            .resolve_fields(&operation.fields, self, request_context)
-            .await
+
+        Ok(operations_payload)
     }
 
     /// Should we allow introspection queries?
@@ -201,6 +320,24 @@ impl SystemResolver {
             .any(|subsystem_resolver| subsystem_resolver.id() == "introspection")
     }
 
+    /// Utilization of the pools (database connections, Deno workers, etc.) held by the subsystems
+    pub async fn pool_stats(&self) -> Vec<PoolStats> {
+        let mut stats = vec![];
+        for resolver in self.subsystem_resolvers.iter() {
+            stats.extend(resolver.pool_stats().await);
+        }
+        stats
+    }
+
+    /// Check if the resources the subsystems depend on (databases, modules, etc.) are available
+    pub async fn check_readiness(&self) -> ReadinessReport {
+        let mut checks = vec![];
+        for resolver in self.subsystem_resolvers.iter() {
+            checks.extend(resolver.check_readiness().await);
+        }
+        ReadinessReport::new(checks)
+    }
+
     /// Obtain the interception tree associated with the given operation
     pub fn applicable_interception_tree(
         &self,
@@ -247,6 +384,62 @@ impl SystemResolver {
         Err(SystemResolutionError::NoResolverFound)
     }
 
+    /// Consume a permit from the rate limit bucket of each top-level field of the operation.
+    ///
+    /// The permits are consumed together: if any of the buckets is exhausted, the operation fails
+    /// without drawing from the others (so a rejected operation doesn't eat into the allowance of
+    /// its other fields).
+    ///
+    /// Must be called only for operations sent by clients (and before resolving them, so that an
+    /// operation exceeding the limit doesn't do any work, including running interceptors).
+    /// Operations executed on behalf of a client, such as through `executeQuery` in a Deno module,
+    /// aren't limited, since the client operation has already drawn from its allowance.
+    pub async fn check_rate_limits<'a>(
+        &self,
+        operation: &ValidatedOperation,
+        request_context: &RequestContext<'a>,
+    ) -> Result<(), SystemResolutionError> {
+        let rate_limit_store = match &self.rate_limit_store {
+            Some(rate_limit_store) => rate_limit_store,
+            None => return Ok(()),
+        };
+
+        let mut buckets = vec![];
+        for field in operation.fields.iter() {
+            if let Some(bucket) = self
+                .rate_limit_bucket(operation.typ, field, request_context)
+                .await?
+            {
+                buckets.push(bucket);
+            }
+        }
+
+        if !buckets.is_empty() {
+            rate_limit_store.acquire(&buckets).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The rate limit bucket of the operation (if any)
+    async fn rate_limit_bucket<'a>(
+        &self,
+        operation_type: OperationType,
+        operation: &ValidatedField,
+        request_context: &RequestContext<'a>,
+    ) -> Result<Option<RateLimitBucket>, SystemResolutionError> {
+        for resolver in self.subsystem_resolvers.iter() {
+            if let Some(bucket) = resolver
+                .rate_limit(operation, operation_type, request_context)
+                .await?
+            {
+                return Ok(Some(bucket));
+            }
+        }
+
+        Ok(None)
+    }
+
     pub(super) async fn invoke_interceptor<'a>(
         &self,
         interceptor: &InterceptorIndexWithSubsystemIndex,
@@ -291,6 +484,7 @@ impl SystemResolver {
             variables,
             self.normal_query_depth_limit,
             self.introspection_query_depth_limit,
+            self.max_query_cost,
         );
 
         document_validator.validate(document)
@@ -437,11 +631,45 @@ pub enum SystemResolutionError {
     #[error("{0}")]
     TrustedDocumentResolution(#[from] TrustedDocumentResolutionError),
 
+    #[error("{0}")]
+    PersistedQuery(#[from] PersistedQueryError),
+
     #[error("Invalid request {0}")]
     RequestError(#[from] RequestError),
+
+    #[error("Subscriptions are only supported over a WebSocket connection")]
+    SubscriptionNotSupported,
+
+    #[error("{0}")]
+    RateLimited(#[from] RateLimitError),
 }
 
 impl SystemResolutionError {
+    /// A short name for the kind of the error (for example, to aggregate errors in metrics)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SystemResolutionError::Delegate(error) => error
+                .downcast_ref::<SystemResolutionError>()
+                .map(|error| error.kind())
+                .unwrap_or("delegate"),
+            SystemResolutionError::Validation(_) => "validation",
+            SystemResolutionError::NoResolverFound => "no_resolver_found",
+            SystemResolutionError::SubsystemResolutionError(error) => match error {
+                SubsystemResolutionError::Authorization => "authorization",
+                SubsystemResolutionError::Timeout => "timeout",
+                _ => "subsystem",
+            },
+            SystemResolutionError::Generic(_) => "generic",
+            SystemResolutionError::AroundInterceptorReturnedNoResponse
+            | SystemResolutionError::NoInterceptionTree => "interceptor",
+            SystemResolutionError::TrustedDocumentResolution(_) => "trusted_document",
+            SystemResolutionError::PersistedQuery(_) => "persisted_query",
+            SystemResolutionError::RequestError(_) => "request",
+            SystemResolutionError::SubscriptionNotSupported => "subscription_not_supported",
+            SystemResolutionError::RateLimited(_) => "rate_limit",
+        }
+    }
+
     // Message that should be emitted when the error is returned to the user.
     // This should hide any internal details of the error.
     // TODO: Log the details of the error.
@@ -458,6 +686,11 @@ impl SystemResolutionError {
                 warn!("Error executing: {e}");
                 Some("Operation not allowed".to_string())
             }
+            SystemResolutionError::SubscriptionNotSupported
+            | SystemResolutionError::PersistedQuery(_) => Some(self.to_string()),
+            SystemResolutionError::RateLimited(error @ RateLimitError::Exceeded { .. }) => {
+                Some(error.to_string())
+            }
             SystemResolutionError::Delegate(error) => error
                 .downcast_ref::<SystemResolutionError>()
                 .map(|error| error.user_error_message()),
@@ -465,3 +698,150 @@ impl SystemResolutionError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use exo_env::MapEnvironment;
+
+    use crate::persisted_queries::InMemoryPersistedQueryStore;
+
+    use super::*;
+
+    const QUERY: &str = "{ __typename }";
+
+    fn system_resolver(trusted_documents: TrustedDocuments) -> SystemResolver {
+        let mut system_resolver = SystemResolver::new(
+            vec![],
+            InterceptionMap {
+                map: HashMap::new(),
+            },
+            InterceptionMap {
+                map: HashMap::new(),
+            },
+            TrustedDocuments::all(),
+            Some(Box::new(InMemoryPersistedQueryStore::new(10))),
+            Schema::new_from_resolvers(&[]),
+            Arc::new(None),
+            Box::new(MapEnvironment::from(HashMap::new())),
+            10,
+            10,
+            None,
+            None,
+        );
+        // Bypass the environment-based relaxation in `new` so that enforcement can be tested
+        system_resolver.trusted_documents = trusted_documents;
+        system_resolver
+    }
+
+    fn payload(query: Option<&str>, query_hash: Option<String>) -> OperationsPayload {
+        OperationsPayload {
+            operation_name: None,
+            query: query.map(|query| query.to_string()),
+            variables: None,
+            query_hash,
+        }
+    }
+
+    #[test]
+    fn registers_and_looks_up_query() {
+        let system_resolver = system_resolver(TrustedDocuments::all());
+        let hash = TrustedDocuments::sha256(QUERY);
+
+        let registered = system_resolver
+            .resolve_persisted_query(
+                payload(Some(QUERY), Some(hash.clone())),
+                &TrustedDocumentEnforcement::DoNotEnforce,
+            )
+            .unwrap();
+        assert_eq!(registered.query.as_deref(), Some(QUERY));
+        assert_eq!(registered.query_hash, None);
+
+        let looked_up = system_resolver
+            .resolve_persisted_query(
+                payload(None, Some(hash)),
+                &TrustedDocumentEnforcement::DoNotEnforce,
+            )
+            .unwrap();
+        assert_eq!(looked_up.query.as_deref(), Some(QUERY));
+        assert_eq!(looked_up.query_hash, None);
+    }
+
+    #[test]
+    fn rejects_mismatched_hash() {
+        let system_resolver = system_resolver(TrustedDocuments::all());
+
+        let result = system_resolver.resolve_persisted_query(
+            payload(Some(QUERY), Some(TrustedDocuments::sha256("{ other }"))),
+            &TrustedDocumentEnforcement::DoNotEnforce,
+        );
+        assert!(matches!(
+            result,
+            Err(SystemResolutionError::PersistedQuery(
+                PersistedQueryError::HashMismatch
+            ))
+        ));
+
+        // The mismatched query must not have been registered under either hash
+        for hash in [
+            TrustedDocuments::sha256(QUERY),
+            TrustedDocuments::sha256("{ other }"),
+        ] {
+            let result = system_resolver.resolve_persisted_query(
+                payload(None, Some(hash)),
+                &TrustedDocumentEnforcement::DoNotEnforce,
+            );
+            assert!(matches!(
+                result,
+                Err(SystemResolutionError::PersistedQuery(
+                    PersistedQueryError::NotFound
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn reports_unknown_hash_on_cold_cache() {
+        let system_resolver = system_resolver(TrustedDocuments::all());
+
+        let result = system_resolver.resolve_persisted_query(
+            payload(None, Some(TrustedDocuments::sha256(QUERY))),
+            &TrustedDocumentEnforcement::DoNotEnforce,
+        );
+        assert!(matches!(
+            result,
+            Err(SystemResolutionError::PersistedQuery(
+                PersistedQueryError::NotFound
+            ))
+        ));
+    }
+
+    #[test]
+    fn refuses_registration_when_enforced() {
+        let system_resolver = system_resolver(TrustedDocuments::MatchingOnly(HashMap::new()));
+        let hash = TrustedDocuments::sha256(QUERY);
+
+        // The payload is passed through as is (to be rejected as untrusted later)
+        let unchanged = system_resolver
+            .resolve_persisted_query(
+                payload(Some(QUERY), Some(hash.clone())),
+                &TrustedDocumentEnforcement::Enforce,
+            )
+            .unwrap();
+        assert_eq!(unchanged.query.as_deref(), Some(QUERY));
+        assert_eq!(unchanged.query_hash.as_deref(), Some(hash.as_str()));
+
+        // ... and nothing was registered
+        let result = system_resolver.resolve_persisted_query(
+            payload(None, Some(hash)),
+            &TrustedDocumentEnforcement::DoNotEnforce,
+        );
+        assert!(matches!(
+            result,
+            Err(SystemResolutionError::PersistedQuery(
+                PersistedQueryError::NotFound
+            ))
+        ));
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Compute the cost of an operation to reject operations that would be too expensive to resolve.
+//!
+//! Each selected field costs its weight plus the cost of its subfields. The weight comes from the
+//! `@cost` annotation of the field (or else of the field's type), and defaults to 1 for fields of
+//! an object type and 0 for fields of a scalar type. The cost of a field returning a list is
+//! multiplied by the number of elements it may return: the value of its `limit` argument if
+//! specified, or [DEFAULT_LIST_SIZE] otherwise.
+
+use async_graphql_parser::types::{BaseType, FieldDefinition, TypeDefinition, TypeKind};
+use core_model::{cost::directive_weight, type_normalization::TypeDefinitionIntrospection};
+
+use crate::{introspection::definition::schema::Schema, value::val::Val};
+
+use super::{field::ValidatedField, underlying_type};
+
+/// The assumed number of elements returned by a list field without a `limit` argument
+const DEFAULT_LIST_SIZE: usize = 10;
+
+const LIMIT_ARGUMENT: &str = "limit";
+
+/// Compute the cost of selecting `fields` of `container_type`
+pub(super) fn selection_cost(
+    schema: &Schema,
+    container_type: &TypeDefinition,
+    fields: &[ValidatedField],
+) -> usize {
+    fields
+        .iter()
+        .map(|field| field_cost(schema, container_type, field))
+        .fold(0, usize::saturating_add)
+}
+
+fn field_cost(schema: &Schema, container_type: &TypeDefinition, field: &ValidatedField) -> usize {
+    // Introspection fields (`__typename`, `__schema`, and `__type`) are free
+    if field.name.starts_with("__") {
+        return 0;
+    }
+
+    let field_definition = match field_definition(container_type, field) {
+        Some(field_definition) => field_definition,
+        None => return 0,
+    };
+
+    let field_type = schema.get_type_definition(underlying_type(&field_definition.ty.node));
+
+    let weight = directive_weight(&field_definition.directives)
+        .or_else(|| field_type.and_then(|field_type| directive_weight(&field_type.directives)))
+        .map(|weight| weight as usize)
+        .unwrap_or_else(|| match field_type.map(|field_type| &field_type.kind) {
+            Some(TypeKind::Object(_) | TypeKind::Interface(_) | TypeKind::Union(_)) => 1,
+            _ => 0,
+        });
+
+    let subfields_cost = field_type
+        .map(|field_type| selection_cost(schema, field_type, &field.subfields))
+        .unwrap_or(0);
+
+    let cost = weight.saturating_add(subfields_cost);
+
+    match field_definition.ty.node.base {
+        BaseType::List(_) => cost.saturating_mul(list_size(field)),
+        BaseType::Named(_) => cost,
+    }
+}
+
+fn field_definition<'a>(
+    container_type: &'a TypeDefinition,
+    field: &ValidatedField,
+) -> Option<&'a FieldDefinition> {
+    container_type
+        .fields()?
+        .iter()
+        .find(|field_definition| field_definition.node.name.node == field.name)
+        .map(|field_definition| &field_definition.node)
+}
+
+fn list_size(field: &ValidatedField) -> usize {
+    match field.arguments.get(LIMIT_ARGUMENT) {
+        Some(Val::Number(limit)) => limit
+            .as_u64()
+            .map(|limit| usize::try_from(limit).unwrap_or(usize::MAX))
+            .unwrap_or(DEFAULT_LIST_SIZE),
+        _ => DEFAULT_LIST_SIZE,
+    }
+}
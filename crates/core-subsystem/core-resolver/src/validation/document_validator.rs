@@ -24,6 +24,7 @@ pub struct DocumentValidator<'a> {
     variables: Option<Map<String, Value>>,
     normal_query_depth_limit: usize,
     introspection_query_depth_limit: usize,
+    max_cost: Option<usize>,
 }
 
 impl<'a> DocumentValidator<'a> {
@@ -33,6 +34,7 @@ impl<'a> DocumentValidator<'a> {
         variables: Option<Map<String, Value>>,
         normal_query_depth_limit: usize,
         introspection_query_depth_limit: usize,
+        max_cost: Option<usize>,
     ) -> Self {
         Self {
             schema,
@@ -40,6 +42,7 @@ impl<'a> DocumentValidator<'a> {
             variables,
             normal_query_depth_limit,
             introspection_query_depth_limit,
+            max_cost,
         }
     }
 
@@ -100,6 +103,7 @@ impl<'a> DocumentValidator<'a> {
             document.fragments,
             self.normal_query_depth_limit,
             self.introspection_query_depth_limit,
+            self.max_cost,
         );
 
         operation_validator.validate(raw_operation)
@@ -140,7 +144,7 @@ mod tests {
     async fn argument_valid() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -166,7 +170,7 @@ mod tests {
     async fn with_operation_name_valid() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query ConcertById {
@@ -192,7 +196,7 @@ mod tests {
     async fn stray_argument_invalid() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -218,7 +222,7 @@ mod tests {
     async fn unspecified_required_argument_invalid() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -252,7 +256,7 @@ mod tests {
             }"#,
         );
 
-        let validator = DocumentValidator::new(&schema, None, Some(variables), 10, 10);
+        let validator = DocumentValidator::new(&schema, None, Some(variables), 10, 10, None);
 
         let query = r#"
             query($concert_id: Int!, $venue_id: Int!) {
@@ -279,7 +283,7 @@ mod tests {
         let schema = create_test_schema().await;
 
         let variables = create_variables(r#"{ "concert_id": 2 }"#);
-        let validator = DocumentValidator::new(&schema, None, Some(variables), 10, 10);
+        let validator = DocumentValidator::new(&schema, None, Some(variables), 10, 10, None);
 
         let query = r#"
             query($concert_id: Int!, $venue_id: Int!) { # venue_id is not a specified in variables
@@ -305,7 +309,7 @@ mod tests {
     async fn invalid_subfield() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -328,7 +332,7 @@ mod tests {
     async fn aliases_valid() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -350,7 +354,7 @@ mod tests {
     async fn mergeable_leaf_fields() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -375,7 +379,7 @@ mod tests {
     async fn mergeable_leaf_fields_with_alias() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -400,7 +404,7 @@ mod tests {
     async fn unmergeable_leaf_fields_all_aliases() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -423,7 +427,7 @@ mod tests {
     async fn unmergeable_leaf_fields_mixed_aliases() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -446,7 +450,7 @@ mod tests {
     async fn mergeable_non_leaf_fields() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -479,7 +483,7 @@ mod tests {
     async fn unmergeable_non_leaf_fields() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -508,7 +512,7 @@ mod tests {
     async fn mergeable_non_leaf_fields_with_alias() {
         let schema = create_test_schema().await;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         let query = r#"
             query {
@@ -557,14 +561,16 @@ mod tests {
             }
         "#;
 
-        let validator = DocumentValidator::new(&schema, Some("concert1".to_string()), None, 10, 10);
+        let validator =
+            DocumentValidator::new(&schema, Some("concert1".to_string()), None, 10, 10, None);
 
         assert_debug!(
             validator.validate(create_query_document(query)),
             "multi_operations_valid"
         );
 
-        let validator = DocumentValidator::new(&schema, Some("concert2".to_string()), None, 10, 10);
+        let validator =
+            DocumentValidator::new(&schema, Some("concert2".to_string()), None, 10, 10, None);
 
         assert_debug!(
             validator.validate(create_query_document(query)),
@@ -593,7 +599,7 @@ mod tests {
             }
         "#;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         assert_debug!(
             validator.validate(create_query_document(query)),
@@ -622,7 +628,8 @@ mod tests {
             }
         "#;
 
-        let validator = DocumentValidator::new(&schema, Some("foo".to_string()), None, 10, 10);
+        let validator =
+            DocumentValidator::new(&schema, Some("foo".to_string()), None, 10, 10, None);
 
         assert_debug!(
             validator.validate(create_query_document(query)),
@@ -647,7 +654,7 @@ mod tests {
             }
         "#;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         assert_debug!(
             validator.validate(create_query_document(query)),
@@ -676,7 +683,7 @@ mod tests {
             }
         "#;
 
-        let validator = DocumentValidator::new(&schema, None, None, 10, 10);
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
 
         assert_debug!(
             validator.validate(create_query_document(query)),
@@ -707,14 +714,14 @@ mod tests {
         "#;
 
         // valid
-        let validator = DocumentValidator::new(&schema, None, None, 6, usize::MAX);
+        let validator = DocumentValidator::new(&schema, None, None, 6, usize::MAX, None);
         assert_debug!(
             validator.validate(create_query_document(query)),
             "query_depth_limit_direct"
         );
 
         // invalid: one level too deep
-        let validator = DocumentValidator::new(&schema, None, None, 5, usize::MAX);
+        let validator = DocumentValidator::new(&schema, None, None, 5, usize::MAX, None);
         assert_debug!(
             validator.validate(create_query_document(query)),
             "query_depth_limit_direct-2"
@@ -746,14 +753,14 @@ mod tests {
         "#;
 
         // valid
-        let validator = DocumentValidator::new(&schema, None, None, 6, usize::MAX);
+        let validator = DocumentValidator::new(&schema, None, None, 6, usize::MAX, None);
         assert_debug!(
             validator.validate(create_query_document(query)),
             "query_depth_limit_through_fragment"
         );
 
         // invalid: one level too deep
-        let validator = DocumentValidator::new(&schema, None, None, 5, usize::MAX);
+        let validator = DocumentValidator::new(&schema, None, None, 5, usize::MAX, None);
         assert_debug!(
             validator.validate(create_query_document(query)),
             "query_depth_limit_through_fragment-2"
@@ -777,26 +784,112 @@ mod tests {
         "#;
 
         // valid
-        let validator = DocumentValidator::new(&schema, None, None, usize::MAX, 3);
+        let validator = DocumentValidator::new(&schema, None, None, usize::MAX, 3, None);
         assert_debug!(
             validator.validate(create_query_document(query)),
             "introspection_query_depth_limit_direct"
         );
 
         // invalid: one level too deep
-        let validator = DocumentValidator::new(&schema, None, None, usize::MAX, 2);
+        let validator = DocumentValidator::new(&schema, None, None, usize::MAX, 2, None);
         assert_debug!(
             validator.validate(create_query_document(query)),
             "introspection_query_depth_limit_direct-2"
         );
     }
 
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn query_cost_limit() {
+        let schema = create_test_schema().await;
+
+        let query = r#"
+            query {
+                concerts(limit: 2) { # 2 * (1 + 1)
+                    id
+                    venue { # 1
+                        name
+                    }
+                }
+                venues { # 10 * 1
+                    name
+                }
+            }
+        "#;
+
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, Some(14));
+        assert_eq!(
+            validator
+                .validate(create_query_document(query))
+                .unwrap()
+                .cost,
+            14
+        );
+
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, Some(13));
+        assert!(matches!(
+            validator.validate(create_query_document(query)),
+            Err(ValidationError::OperationCostTooHigh(14, 13, _))
+        ));
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn query_cost_with_annotations() {
+        let test_exo = r#"
+            @postgres
+            module LogModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                    @cost(20) summary: String
+                    venue: Venue
+                }
+
+                @cost(5)
+                type Venue {
+                    @pk id: Int = autoIncrement()
+                    name: String
+                    concerts: Set<Concert>
+                }
+            }
+        "#;
+        let schema = create_schema(test_exo).await;
+
+        let query = r#"
+            query {
+                concerts(limit: 3) { # 3 * (1 + 20 + 5)
+                    title
+                    summary
+                    venue {
+                        name
+                    }
+                }
+                venue(id: 1) { # 5 + 10 * 1
+                    concerts {
+                        title
+                    }
+                }
+            }
+        "#;
+
+        let validator = DocumentValidator::new(&schema, None, None, 10, 10, None);
+        assert_eq!(
+            validator
+                .validate(create_query_document(query))
+                .unwrap()
+                .cost,
+            93
+        );
+    }
+
     fn create_variables(variables: &str) -> Map<String, Value> {
         serde_json::from_str(variables).unwrap()
     }
 
     async fn create_test_schema() -> Schema {
-        let test_exo = r#"
+        create_schema(
+            r#"
             @postgres
             module LogModule {
                 type Concert {
@@ -812,7 +905,12 @@ mod tests {
                     concerts: Set<Concert>
                 }
             }
-        "#;
+        "#,
+        )
+        .await
+    }
+
+    async fn create_schema(test_exo: &str) -> Schema {
         let postgres_subsystem =
             create_postgres_system_from_str(test_exo, "test.exo".to_string()).await;
 
@@ -820,6 +918,7 @@ mod tests {
             postgres_subsystem.schema_types(),
             postgres_subsystem.schema_queries(),
             postgres_subsystem.schema_mutations(),
+            postgres_subsystem.schema_subscriptions(),
         )
     }
 
@@ -19,4 +19,6 @@ pub struct ValidatedOperation {
     pub typ: OperationType,
     /// The operation's fields (individual queries or mutations).
     pub fields: Vec<ValidatedField>,
+    /// The estimated cost of resolving the operation (used to limit expensive operations)
+    pub cost: usize,
 }
@@ -17,11 +17,16 @@ use async_graphql_value::{ConstValue, Name};
 use serde_json::{Map, Value};
 
 use crate::{
-    introspection::definition::schema::{Schema, MUTATION_ROOT_TYPENAME, QUERY_ROOT_TYPENAME},
+    introspection::definition::schema::{
+        Schema, MUTATION_ROOT_TYPENAME, QUERY_ROOT_TYPENAME, SUBSCRIPTION_ROOT_TYPENAME,
+    },
     validation::validation_error::ValidationError,
 };
 
-use super::{operation::ValidatedOperation, selection_set_validator::SelectionSetValidator};
+use super::{
+    cost::selection_cost, operation::ValidatedOperation,
+    selection_set_validator::SelectionSetValidator,
+};
 
 /// Context for validating an operation.
 pub struct OperationValidator<'a> {
@@ -31,6 +36,7 @@ pub struct OperationValidator<'a> {
     fragment_definitions: HashMap<Name, Positioned<FragmentDefinition>>,
     normal_query_depth_limit: usize,
     introspection_query_depth_limit: usize,
+    max_cost: Option<usize>,
 }
 
 impl<'a> OperationValidator<'a> {
@@ -42,6 +48,7 @@ impl<'a> OperationValidator<'a> {
         fragment_definitions: HashMap<Name, Positioned<FragmentDefinition>>,
         normal_query_depth_limit: usize,
         introspection_query_depth_limit: usize,
+        max_cost: Option<usize>,
     ) -> Self {
         Self {
             schema,
@@ -50,6 +57,7 @@ impl<'a> OperationValidator<'a> {
             fragment_definitions,
             normal_query_depth_limit,
             introspection_query_depth_limit,
+            max_cost,
         }
     }
 
@@ -94,6 +102,8 @@ impl<'a> OperationValidator<'a> {
     /// - Each variables in [OperationDefinition.variable_definitions] is
     ///   available (see [`validate_variables`] for details)
     /// - The selected fields are valid (see [SelectionSetValidator] for details)])
+    /// - A subscription selects exactly one top-level field
+    /// - The cost of the operation is within the maximum allowed cost (see [selection_cost])
     ///
     /// # Returns
     ///   A validated operation with all variables and fields resolved and normalized.
@@ -104,7 +114,7 @@ impl<'a> OperationValidator<'a> {
         let operation_type_name = match operation.node.ty {
             OperationType::Query => QUERY_ROOT_TYPENAME,
             OperationType::Mutation => MUTATION_ROOT_TYPENAME,
-            OperationType::Subscription => todo!(),
+            OperationType::Subscription => SUBSCRIPTION_ROOT_TYPENAME,
         };
 
         let container_type = match self.schema.get_type_definition(operation_type_name) {
@@ -132,10 +142,28 @@ impl<'a> OperationValidator<'a> {
             &self.selection_depth_check(),
         )?;
 
+        // Per the GraphQL spec, a subscription must have exactly one root field (after fragments
+        // have been expanded)
+        if operation.node.ty == OperationType::Subscription && fields.len() != 1 {
+            return Err(ValidationError::SubscriptionRootFieldCount(operation.pos));
+        }
+
+        let cost = selection_cost(self.schema, container_type, &fields);
+        if let Some(max_cost) = self.max_cost {
+            if cost > max_cost {
+                return Err(ValidationError::OperationCostTooHigh(
+                    cost,
+                    max_cost,
+                    operation.pos,
+                ));
+            }
+        }
+
         Ok(ValidatedOperation {
             name: self.operation_name,
             typ: operation.node.ty,
             fields,
+            cost,
         })
     }
 
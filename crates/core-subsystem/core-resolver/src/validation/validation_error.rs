@@ -74,6 +74,12 @@ pub enum ValidationError {
 
     #[error("Selection set too deep")]
     SelectionSetTooDeep(Pos),
+
+    #[error("Subscription operations must select exactly one top-level field")]
+    SubscriptionRootFieldCount(Pos),
+
+    #[error("Operation cost {0} exceeds the maximum allowed cost {1}")]
+    OperationCostTooHigh(usize, usize, Pos),
 }
 
 impl ValidationError {
@@ -99,6 +105,8 @@ impl ValidationError {
             ValidationError::InvalidArgumentType { pos, .. } => vec![*pos],
             ValidationError::FragmentCycle(_, pos) => vec![*pos],
             ValidationError::SelectionSetTooDeep(pos) => vec![*pos],
+            ValidationError::SubscriptionRootFieldCount(pos) => vec![*pos],
+            ValidationError::OperationCostTooHigh(_, _, pos) => vec![*pos],
         }
     }
 }
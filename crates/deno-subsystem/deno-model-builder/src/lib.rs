@@ -10,5 +10,6 @@
 pub use plugin::DenoSubsystemBuilder;
 
 mod module_skeleton_generator;
+mod permissions_builder;
 mod plugin;
 mod system_builder;
@@ -598,6 +598,7 @@ mod tests {
             base_exofile: PathBuf::new(),
             interceptors: vec![],
             methods: vec![],
+            enums: vec![],
             span,
         }
     }
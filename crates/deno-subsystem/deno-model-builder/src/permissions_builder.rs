@@ -0,0 +1,84 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use core_plugin_interface::core_model_builder::{
+    ast::ast_types::{AstAnnotationParams, AstExpr, AstModule},
+    builder::resolved_builder::AnnotationMapHelper,
+    error::ModelBuildingError,
+    typechecker::Typed,
+};
+use exo_deno::{DenoPermissions, PermissionGrant};
+
+/// The kinds of access that may be granted in the `permissions` parameter of `@deno`
+const PERMISSION_KINDS: [&str; 7] = ["net", "env", "read", "write", "run", "sys", "ffi"];
+
+/// Build the permissions of a module from its `@deno` annotation such as `@deno(path: "stripe.ts",
+/// permissions: {net: ["api.stripe.com"], env: ["STRIPE_KEY"], read: false})`.
+///
+/// Each kind of access in the `permissions` object may be `true` (grant all access of that kind),
+/// `false` (deny it), or a list of entries to grant access to. The kinds not specified are denied.
+/// If the `permissions` parameter isn't specified, we return `None` (the module has unrestricted
+/// access).
+pub(crate) fn build_permissions(
+    module: &AstModule<Typed>,
+) -> Result<Option<DenoPermissions>, ModelBuildingError> {
+    let permissions = match module.annotations.get("deno") {
+        Some(AstAnnotationParams::Map(params, _)) => match params.get("permissions") {
+            Some(AstExpr::ObjectLiteral(permissions, _)) => permissions,
+            Some(_) => {
+                return Err(ModelBuildingError::Generic(format!(
+                    "The permissions of module '{}' must be an object such as `{{net: [\"api.stripe.com\"]}}`",
+                    module.name
+                )))
+            }
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    if let Some(unknown) = permissions
+        .keys()
+        .find(|name| !PERMISSION_KINDS.contains(&name.as_str()))
+    {
+        return Err(ModelBuildingError::Generic(format!(
+            "Unknown permission '{unknown}' for module '{}' (expected one of: {})",
+            module.name,
+            PERMISSION_KINDS.join(", ")
+        )));
+    }
+
+    let grant = |name: &str| match permissions.get(name) {
+        None | Some(AstExpr::BooleanLiteral(false, _)) => Ok(PermissionGrant::Denied),
+        Some(AstExpr::BooleanLiteral(true, _)) => Ok(PermissionGrant::All),
+        Some(AstExpr::StringList(entries, _)) => Ok(PermissionGrant::Only(entries.clone())),
+        Some(_) => Err(ModelBuildingError::Generic(format!(
+            "The '{name}' permission of module '{}' must be a boolean or a list of strings",
+            module.name
+        ))),
+    };
+    let permissions = DenoPermissions {
+        net: grant("net")?,
+        env: grant("env")?,
+        read: grant("read")?,
+        write: grant("write")?,
+        run: grant("run")?,
+        sys: grant("sys")?,
+        ffi: grant("ffi")?,
+    };
+
+    // Report invalid entries now (instead of when the module is loaded)
+    permissions.to_container().map_err(|e| {
+        ModelBuildingError::Generic(format!(
+            "Invalid permissions for module '{}': {e}",
+            module.name
+        ))
+    })?;
+
+    Ok(Some(permissions))
+}
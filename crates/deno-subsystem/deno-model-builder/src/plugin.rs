@@ -15,7 +15,7 @@ use core_plugin_interface::{
         error::ModelBuildingError,
         plugin::{Interception, SubsystemBuild},
         typechecker::{
-            annotation::{AnnotationSpec, AnnotationTarget},
+            annotation::{AnnotationSpec, AnnotationTarget, MappedAnnotationParamSpec},
             typ::TypecheckedSystem,
         },
     },
@@ -41,7 +41,28 @@ impl SubsystemBuilder for DenoSubsystemBuilder {
                 targets: &[AnnotationTarget::Module],
                 no_params: false,
                 single_params: true,
-                mapped_params: None,
+                mapped_params: Some(&[
+                    MappedAnnotationParamSpec {
+                        name: "path",
+                        optional: false,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "permissions",
+                        optional: true,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "timeout",
+                        optional: true,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "memory",
+                        optional: true,
+                        keywords: &[],
+                    },
+                ]),
             },
         )]
     }
@@ -42,10 +42,10 @@ use deno_runtime::deno_node::{
     analyze::NodeCodeTranslator, NodeResolution, NodeResolutionMode, NodeResolver,
 };
 use deno_virtual_fs::virtual_fs::{VfsBuilder, VirtualDirectory};
-use exo_deno::deno_executor_pool::{DenoScriptDefn, ResolvedModule};
+use exo_deno::deno_executor_pool::{DenoLimits, DenoScriptDefn, ResolvedModule};
 use url::Url;
 
-use crate::module_skeleton_generator;
+use crate::{module_skeleton_generator, permissions_builder};
 
 pub struct ModelDenoSystemWithInterceptors {
     pub underlying: DenoSubsystem,
@@ -121,6 +121,8 @@ fn process_script(
         local.block_on(&rt, future)
     }
 
+    let permissions = permissions_builder::build_permissions(module)?;
+
     let root = Url::from_file_path(std::fs::canonicalize(module_fs_path).unwrap()).unwrap();
     let root_clone = root.clone();
 
@@ -230,6 +232,8 @@ fn process_script(
                     vfs.0,
                     vfs.1,
                 )),
+                permissions,
+                limits: DenoLimits::default(),
             })
         };
         run_local(future)
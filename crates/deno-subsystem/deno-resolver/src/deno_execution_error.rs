@@ -38,6 +38,9 @@ impl DenoExecutionError {
         match self {
             DenoExecutionError::Authorization => Some("Not authorized".to_string()),
             DenoExecutionError::Deno(DenoError::Explicit(error)) => Some(error.to_string()),
+            DenoExecutionError::Deno(error @ DenoError::PermissionDenied(_)) => {
+                Some(error.to_string())
+            }
             _ => self.explicit_message(),
         }
     }
@@ -60,6 +63,7 @@ impl DenoExecutionError {
         // invocation, SystemResolutionError).
         match root_error.downcast_ref::<DenoError>() {
             Some(DenoError::Explicit(error)) => Some(error.to_string()),
+            Some(error @ DenoError::PermissionDenied(_)) => Some(error.to_string()),
             _ => match root_error.downcast_ref::<SubsystemResolutionError>() {
                 Some(error) => error.user_error_message(),
                 _ => root_error
@@ -10,6 +10,7 @@
 use indexmap::IndexMap;
 
 use core_plugin_interface::{
+    core_model::cache::CachePolicy,
     core_resolver::{
         access_solver::{AccessSolver, AccessSolverError},
         context::RequestContext,
@@ -24,12 +25,15 @@ use core_plugin_interface::{
 };
 
 use deno_model::{
-    module::{Argument, ModuleMethod},
+    module::{Argument, ModuleMethod, Script},
     subsystem::DenoSubsystem,
     types::{ModuleCompositeType, ModuleOperationReturnType, ModuleTypeKind},
 };
 
-use exo_deno::{deno_executor_pool::DenoScriptDefn, Arg};
+use exo_deno::{
+    deno_executor_pool::{DenoLimits, DenoScriptDefn},
+    Arg,
+};
 use futures::StreamExt;
 
 use crate::{
@@ -106,14 +110,12 @@ impl<'a> DenoOperation<'a> {
             exograph_proceed: None,
         };
 
-        let deserialized: DenoScriptDefn = serde_json::from_slice(&script.script).unwrap();
-
         let (result, response) = self
             .subsystem_resolver
             .executor
             .execute_and_get_r(
                 &script.path,
-                deserialized,
+                deno_script(script),
                 &self.method.name,
                 arg_sequence,
                 None,
@@ -122,9 +124,17 @@ impl<'a> DenoOperation<'a> {
             .await
             .map_err(DenoExecutionError::Deno)?;
 
+        let mut headers = response.map(|r| r.headers).unwrap_or_default();
+        if let Some(cache_policy) = &self.method.cache {
+            headers.push((
+                CachePolicy::HEADER_NAME.to_string(),
+                cache_policy.header_value(),
+            ));
+        }
+
         Ok(QueryResponse {
             body: QueryResponseBody::Json(result),
-            headers: response.map(|r| r.headers).unwrap_or_default(),
+            headers,
         })
     }
 
@@ -143,6 +153,22 @@ impl<'a> DenoOperation<'a> {
     }
 }
 
+/// The script definition to execute (along with the limits to run it within)
+pub fn deno_script(script: &Script) -> DenoScriptDefn {
+    let script_defn: DenoScriptDefn = serde_json::from_slice(&script.script).unwrap();
+
+    DenoScriptDefn {
+        limits: DenoLimits {
+            timeout: script.limits.timeout(),
+            max_heap_bytes: script
+                .limits
+                .memory_bytes
+                .map(|bytes| usize::try_from(bytes).unwrap_or(usize::MAX)),
+        },
+        ..script_defn
+    }
+}
+
 pub async fn construct_arg_sequence<'a>(
     field_args: &IndexMap<String, Val>,
     args: &[Argument],
@@ -11,11 +11,14 @@ use core_plugin_interface::core_resolver::{
     context::RequestContext, system_resolver::ExographExecuteQueryFn, InterceptedOperation,
 };
 use deno_model::interceptor::Interceptor;
-use exo_deno::{deno_executor_pool::DenoScriptDefn, Arg};
+use exo_deno::Arg;
 use indexmap::IndexMap;
 use serde_json::Value;
 
-use crate::{deno_operation::construct_arg_sequence, plugin::DenoSubsystemResolver};
+use crate::{
+    deno_operation::{construct_arg_sequence, deno_script},
+    plugin::DenoSubsystemResolver,
+};
 
 use super::{
     deno_execution_error::DenoExecutionError,
@@ -47,13 +50,11 @@ pub async fn execute_interceptor<'a>(
         exograph_proceed: Some(&intercepted_operation_resolver),
     };
 
-    let deserialized: DenoScriptDefn = serde_json::from_slice(&script.script).unwrap();
-
     subsystem_resolver
         .executor
         .execute_and_get_r(
             &script.path,
-            deserialized,
+            deno_script(script),
             &interceptor.method_name,
             arg_sequence,
             Some(InterceptedOperationInfo {
@@ -11,11 +11,14 @@ use async_graphql_parser::types::{FieldDefinition, OperationType, TypeDefinition
 use async_trait::async_trait;
 
 use core_plugin_interface::{
-    core_model::mapped_arena::SerializableSlabIndex,
+    core_model::{execution_limits::ExecutionLimits, mapped_arena::SerializableSlabIndex},
     core_resolver::{
         context::RequestContext,
         exograph_execute_query,
+        metrics::PoolStats,
         plugin::{SubsystemResolutionError, SubsystemResolver},
+        rate_limit::RateLimitBucket,
+        readiness::ReadinessCheck,
         system_resolver::SystemResolver,
         validation::field::ValidatedField,
         InterceptedOperation, QueryResponse, QueryResponseBody,
@@ -27,12 +30,12 @@ use core_plugin_interface::{
 };
 
 use deno_model::{module::ModuleMethod, subsystem::DenoSubsystem};
-use exo_deno::DenoExecutorPool;
+use exo_deno::{deno_error::DenoError, DenoExecutorPool};
 use exo_env::Environment;
 
 use super::{
     deno_execution_error::DenoExecutionError,
-    deno_operation::DenoOperation,
+    deno_operation::{deno_script, DenoOperation},
     exo_execution::{exo_config, ExographMethodResponse, RequestFromDenoMessage},
     exograph_ops::InterceptedOperationInfo,
 };
@@ -54,10 +57,17 @@ impl SubsystemLoader for DenoSubsystemLoader {
     async fn init(
         &mut self,
         serialized_subsystem: Vec<u8>,
-        _env: &dyn Environment,
+        env: &dyn Environment,
     ) -> Result<Box<dyn SubsystemResolver + Send + Sync>, SubsystemLoadingError> {
         deno_core::JsRuntime::init_platform(None);
-        let subsystem = DenoSubsystem::deserialize(serialized_subsystem)?;
+        let mut subsystem = DenoSubsystem::deserialize(serialized_subsystem)?;
+
+        // Modules without their own limits run within those set through the environment
+        let default_limits =
+            ExecutionLimits::from_env(|key| env.get(key)).map_err(SubsystemLoadingError::Config)?;
+        for (_, script) in subsystem.scripts.iter_mut() {
+            script.limits = script.limits.or(default_limits);
+        }
 
         let executor = DenoExecutorPool::new_from_config(exo_config());
 
@@ -164,6 +174,88 @@ impl SubsystemResolver for DenoSubsystemResolver {
         }))
     }
 
+    async fn rate_limit<'a>(
+        &'a self,
+        field: &'a ValidatedField,
+        operation_type: OperationType,
+        request_context: &'a RequestContext<'a>,
+    ) -> Result<Option<RateLimitBucket>, SubsystemResolutionError> {
+        let method_id = match operation_type {
+            OperationType::Query => self
+                .subsystem
+                .queries
+                .get_by_key(&field.name)
+                .and_then(|query| query.method_id),
+            OperationType::Mutation => self
+                .subsystem
+                .mutations
+                .get_by_key(&field.name)
+                .and_then(|mutation| mutation.method_id),
+            OperationType::Subscription => None,
+        };
+
+        let Some(rate_limit) =
+            method_id.and_then(|method_id| self.subsystem.methods[method_id].rate_limit.as_ref())
+        else {
+            return Ok(None);
+        };
+
+        let bucket = RateLimitBucket::new(rate_limit, &self.subsystem, request_context)
+            .await
+            .map_err(DenoExecutionError::from)?;
+
+        Ok(Some(bucket))
+    }
+
+    async fn pool_stats(&self) -> Vec<PoolStats> {
+        self.executor
+            .stats()
+            .await
+            .into_iter()
+            .map(|stats| PoolStats {
+                pool: "deno",
+                name: stats.script_path,
+                size: stats.size,
+                in_use: stats.busy,
+                max_size: None,
+            })
+            .collect()
+    }
+
+    async fn check_readiness(&self) -> Vec<ReadinessCheck> {
+        let stats = self.executor.stats().await;
+        let mut checks = vec![];
+
+        for (_, script) in self.subsystem.scripts.iter() {
+            let name = format!("deno.module.{}", script.path);
+            let stats = stats
+                .iter()
+                .find(|stats| stats.script_path == script.path && stats.size > 0);
+
+            let check = match stats {
+                // Modules are otherwise loaded only when one of their methods is first called, so
+                // start loading it (the module is ready once a later check finds it loaded)
+                None => match self
+                    .executor
+                    .preload(&script.path, deno_script(script))
+                    .await
+                {
+                    Ok(()) => ReadinessCheck::error(name, "Loading"),
+                    Err(e) => ReadinessCheck::error(name, format!("Failed to load: {e}")),
+                },
+                Some(stats) if stats.stopped == stats.size => {
+                    ReadinessCheck::error(name, "Failed to load")
+                }
+                Some(stats) if stats.loaded == 0 => ReadinessCheck::error(name, "Loading"),
+                Some(_) => ReadinessCheck::ok(name, Some("Loaded".to_string())),
+            };
+
+            checks.push(check);
+        }
+
+        checks
+    }
+
     fn schema_queries(&self) -> Vec<FieldDefinition> {
         self.subsystem.schema_queries()
     }
@@ -201,6 +293,7 @@ impl From<DenoExecutionError> for SubsystemResolutionError {
     fn from(e: DenoExecutionError) -> Self {
         match e {
             DenoExecutionError::Authorization => SubsystemResolutionError::Authorization,
+            DenoExecutionError::Deno(DenoError::Timeout(_)) => SubsystemResolutionError::Timeout,
             _ => SubsystemResolutionError::UserDisplayError(
                 e.user_error_message()
                     .unwrap_or_else(|| "Internal server error".to_string()),
@@ -44,6 +44,11 @@ impl ResolvedAccess {
         )
     }
 
+    /// The rule for querying (if any)
+    pub fn query_rule(&self) -> Option<&AstExpr<Typed>> {
+        self.query.as_ref().or(self.default.as_ref())
+    }
+
     pub fn update_allowed(&self) -> bool {
         !matches!(
             self.update
@@ -131,11 +131,21 @@ pub fn compute_input_predicate_expression(
                     }
                 }
                 JsonPathSelection::Function(lead_path, function_call) => {
-                    compute_input_function_expr(
-                        lead_path,
-                        function_call.parameter_name,
-                        function_call.expr,
-                    )
+                    if function_call.name == "some" {
+                        compute_input_function_expr(
+                            lead_path,
+                            function_call.parameter_name,
+                            function_call.expr,
+                        )
+                    } else {
+                        compute_counted_function_expr(
+                            function_call,
+                            |count_call| input_count_expr(lead_path.clone(), count_call),
+                            InputAccessPrimitiveExpression::Common(
+                                CommonAccessPrimitiveExpression::NumberLiteral(0),
+                            ),
+                        )
+                    }
                 }
             })
         }
@@ -173,6 +183,9 @@ pub fn compute_input_predicate_expression(
         AstExpr::StringList(_, _) => Err(ModelBuildingError::Generic(
             "Top-level expression cannot be a list literal".to_string(),
         )),
+        AstExpr::ObjectLiteral(_, _) => Err(ModelBuildingError::Generic(
+            "Top-level expression cannot be an object literal".to_string(),
+        )),
     }
 }
 
@@ -226,16 +239,20 @@ pub fn compute_predicate_expression(
                     }
                 }
                 DatabasePathSelection::Function(column_path, function_call) => {
-                    if function_call.name != "some" {
-                        Err(ModelBuildingError::Generic(
-                            "Only `some` function is supported".to_string(),
-                        ))
-                    } else {
+                    if function_call.name == "some" {
                         compute_function_expr(
                             column_path,
                             function_call.parameter_name,
                             function_call.expr,
                         )
+                    } else {
+                        compute_counted_function_expr(
+                            function_call,
+                            |count_call| db_count_expr(column_path.clone(), count_call),
+                            DatabaseAccessPrimitiveExpression::Common(
+                                CommonAccessPrimitiveExpression::NumberLiteral(0),
+                            ),
+                        )
                     }
                 }
                 DatabasePathSelection::Context(context_selection, field_type) => {
@@ -458,6 +475,129 @@ fn compute_input_function_expr(
     }
 }
 
+/// Compute the predicate for a function that counts the elements satisfying its body (`all` and
+/// `none`). For example, `self.items.none(i => i.shipped)` is the same as `self.items.count(i =>
+/// i.shipped) == 0`.
+///
+/// Similarly, `self.items.all(i => i.shipped)` is the same as `self.items.count(i => i.shipped) ==
+/// self.items.count(i => true)`. We don't count the elements satisfying `!i.shipped` instead, since
+/// the body may evaluate to `NULL` (for example, if `shipped` is `NULL`), in which case, neither
+/// the body nor its negation holds (but the element must still fail `all`).
+fn compute_counted_function_expr<PrimExpr: Send + Sync>(
+    function_call: FunctionCall<PrimExpr>,
+    count_expr: impl Fn(FunctionCall<PrimExpr>) -> Result<PrimExpr, ModelBuildingError>,
+    zero: PrimExpr,
+) -> Result<AccessPredicateExpression<PrimExpr>, ModelBuildingError> {
+    let FunctionCall {
+        name,
+        parameter_name,
+        expr,
+    } = function_call;
+
+    let count = |expr| {
+        count_expr(FunctionCall {
+            name: "count".to_string(),
+            parameter_name: parameter_name.clone(),
+            expr,
+        })
+    };
+
+    let (counted, expected) = match name.as_str() {
+        "none" => (count(expr)?, zero),
+        "all" => (
+            count(expr)?,
+            count(AccessPredicateExpression::BooleanLiteral(true))?,
+        ),
+        "count" => {
+            return Err(ModelBuildingError::Generic(
+                "The result of `count` must be compared with a number (for example, `self.drafts.count(d => d.open) < 5`)".to_string(),
+            ))
+        }
+        _ => return Err(unsupported_function_error(&name)),
+    };
+
+    Ok(AccessPredicateExpression::RelationalOp(
+        AccessRelationalOp::Eq(Box::new(counted), Box::new(expected)),
+    ))
+}
+
+fn db_count_expr(
+    column_path: PhysicalColumnPath,
+    function_call: FunctionCall<DatabaseAccessPrimitiveExpression>,
+) -> Result<DatabaseAccessPrimitiveExpression, ModelBuildingError> {
+    validate_count_function(&function_call, &|expr| match expr {
+        DatabaseAccessPrimitiveExpression::Column(_, parameter_name) => parameter_name.is_some(),
+        DatabaseAccessPrimitiveExpression::Function(_, _) => false,
+        DatabaseAccessPrimitiveExpression::Common(_) => true,
+    })?;
+
+    Ok(DatabaseAccessPrimitiveExpression::Function(
+        column_path,
+        function_call,
+    ))
+}
+
+fn input_count_expr(
+    path: Vec<String>,
+    function_call: FunctionCall<InputAccessPrimitiveExpression>,
+) -> Result<InputAccessPrimitiveExpression, ModelBuildingError> {
+    validate_count_function(&function_call, &|expr| match expr {
+        InputAccessPrimitiveExpression::Path(_, parameter_name) => parameter_name.is_some(),
+        InputAccessPrimitiveExpression::Function(_, _) => false,
+        InputAccessPrimitiveExpression::Common(_) => true,
+    })?;
+
+    Ok(InputAccessPrimitiveExpression::Function(
+        path,
+        function_call,
+    ))
+}
+
+/// Ensure that the function is `count` and that its body refers only to its parameter (and
+/// contexts). Since the body is evaluated separately for each element, it cannot refer to `self`
+/// (or call another function).
+fn validate_count_function<PrimExpr: Send + Sync>(
+    function_call: &FunctionCall<PrimExpr>,
+    is_valid_operand: &impl Fn(&PrimExpr) -> bool,
+) -> Result<(), ModelBuildingError> {
+    fn validate_body<PrimExpr: Send + Sync>(
+        expr: &AccessPredicateExpression<PrimExpr>,
+        is_valid_operand: &impl Fn(&PrimExpr) -> bool,
+    ) -> bool {
+        match expr {
+            AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Not(p)) => {
+                validate_body(p, is_valid_operand)
+            }
+            AccessPredicateExpression::LogicalOp(
+                AccessLogicalExpression::And(left, right)
+                | AccessLogicalExpression::Or(left, right),
+            ) => validate_body(left, is_valid_operand) && validate_body(right, is_valid_operand),
+            AccessPredicateExpression::RelationalOp(op) => {
+                let (left, right) = op.sides();
+                is_valid_operand(left) && is_valid_operand(right)
+            }
+            AccessPredicateExpression::BooleanLiteral(_) => true,
+        }
+    }
+
+    if function_call.name != "count" {
+        Err(unsupported_function_error(&function_call.name))
+    } else if !validate_body(&function_call.expr, is_valid_operand) {
+        Err(ModelBuildingError::Generic(format!(
+            "The body of `{}` may only refer to its parameter `{}` and contexts",
+            function_call.name, function_call.parameter_name
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+fn unsupported_function_error(name: &str) -> ModelBuildingError {
+    ModelBuildingError::Generic(format!(
+        "Unsupported function `{name}` (supported functions are `some`, `all`, `none`, and `count`)"
+    ))
+}
+
 fn compute_primitive_db_expr(
     expr: &AstExpr<Typed>,
     self_type_info: &EntityType,
@@ -477,16 +617,16 @@ fn compute_primitive_db_expr(
             subsystem_entity_types,
             database,
         )
-        .map(|selection| match selection {
-            DatabasePathSelection::Column(column_path, _, parameter_name) => {
-                DatabaseAccessPrimitiveExpression::Column(column_path, parameter_name)
-            }
+        .and_then(|selection| match selection {
+            DatabasePathSelection::Column(column_path, _, parameter_name) => Ok(
+                DatabaseAccessPrimitiveExpression::Column(column_path, parameter_name),
+            ),
             DatabasePathSelection::Function(column_path, function_call) => {
-                DatabaseAccessPrimitiveExpression::Function(column_path, function_call)
+                db_count_expr(column_path, function_call)
             }
-            DatabasePathSelection::Context(c, _) => DatabaseAccessPrimitiveExpression::Common(
+            DatabasePathSelection::Context(c, _) => Ok(DatabaseAccessPrimitiveExpression::Common(
                 CommonAccessPrimitiveExpression::ContextSelection(c),
-            ),
+            )),
         }),
         AstExpr::StringLiteral(value, _) => Ok(DatabaseAccessPrimitiveExpression::Common(
             CommonAccessPrimitiveExpression::StringLiteral(value.clone()),
@@ -500,6 +640,9 @@ fn compute_primitive_db_expr(
         AstExpr::StringList(_, _) => Err(ModelBuildingError::Generic(
             "Access expressions do not support lists yet".to_string(),
         )),
+        AstExpr::ObjectLiteral(_, _) => Err(ModelBuildingError::Generic(
+            "Access expressions do not support objects".to_string(),
+        )),
         AstExpr::LogicalOp(_) => unreachable!(), // Parser ensures that the two sides are primitive expressions
         AstExpr::RelationalOp(_) => unreachable!(), // Parser ensures that the two sides are primitive expressions
     }
@@ -520,15 +663,15 @@ fn compute_primitive_json_expr(
             subsystem_primitive_types,
             subsystem_entity_types,
         )
-        .map(|selection| match selection {
+        .and_then(|selection| match selection {
             JsonPathSelection::Path(path, _, parameter_name) => {
-                InputAccessPrimitiveExpression::Path(path, parameter_name)
+                Ok(InputAccessPrimitiveExpression::Path(path, parameter_name))
             }
-            JsonPathSelection::Context(c, _) => InputAccessPrimitiveExpression::Common(
+            JsonPathSelection::Context(c, _) => Ok(InputAccessPrimitiveExpression::Common(
                 CommonAccessPrimitiveExpression::ContextSelection(c),
-            ),
+            )),
             JsonPathSelection::Function(path, function_call) => {
-                InputAccessPrimitiveExpression::Function(path, function_call)
+                input_count_expr(path, function_call)
             }
         }),
         AstExpr::StringLiteral(value, _) => Ok(InputAccessPrimitiveExpression::Common(
@@ -543,6 +686,9 @@ fn compute_primitive_json_expr(
         AstExpr::StringList(_, _) => Err(ModelBuildingError::Generic(
             "Access expressions do not support lists yet".to_string(),
         )),
+        AstExpr::ObjectLiteral(_, _) => Err(ModelBuildingError::Generic(
+            "Access expressions do not support objects".to_string(),
+        )),
         AstExpr::LogicalOp(_) => unreachable!(), // Parser has already ensures that the two sides are primitive expressions
         AstExpr::RelationalOp(_) => unreachable!(), // Parser has already ensures that the two sides are primitive expressions
     }
@@ -1077,12 +1223,15 @@ fn reduce_nested_primitive_expr(
                 ColumnPathLink::Relation(r)
                     if r.foreign_column_id.table_id == parent_entity.table_id =>
                 {
-                    // Eliminate the head link. For example if the expression is self.user.id, then
-                    // we can reduce it to just id (assuming that the parent entity is user)
-                    NestedPredicatePart::Parent(DatabaseAccessPrimitiveExpression::Column(
-                        tail.unwrap(),
-                        Some(fc.parameter_name.clone()),
-                    ))
+                    match tail {
+                        // Eliminate the head link. For example if the expression is
+                        // self.user.documents.count(...), then we can reduce it to just
+                        // documents.count(...) (assuming that the parent entity is user)
+                        Some(tail) => NestedPredicatePart::Parent(
+                            DatabaseAccessPrimitiveExpression::Function(tail, fc.clone()),
+                        ),
+                        None => NestedPredicatePart::Nested(expr),
+                    }
                 }
                 _ => NestedPredicatePart::Nested(expr),
             }
@@ -72,7 +72,7 @@ impl MutationBuilder for DeleteMutationBuilder {
         entity_type: &EntityType,
         building: &SystemContextBuilding,
     ) -> PostgresMutationParameters {
-        PostgresMutationParameters::Delete(query_builder::pk_predicate_param(
+        PostgresMutationParameters::Delete(query_builder::pk_predicate_params(
             entity_type,
             &building.predicate_types,
             &building.database,
@@ -94,9 +94,9 @@ impl MutationBuilder for DeleteMutationBuilder {
         entity_type: &EntityType,
         building: &SystemContextBuilding,
     ) -> PostgresMutationParameters {
-        PostgresMutationParameters::Delete(query_builder::collection_predicate_param(
+        PostgresMutationParameters::Delete(vec![query_builder::collection_predicate_param(
             entity_type,
             &building.predicate_types,
-        ))
+        )])
     }
 }
@@ -23,10 +23,13 @@ mod predicate_builder;
 mod query_builder;
 mod reference_input_type_builder;
 mod resolved_builder;
+mod restore_mutation_builder;
 mod shallow;
+mod subscription_builder;
 mod system_builder;
 mod type_builder;
 mod update_mutation_builder;
+mod upsert_mutation_builder;
 mod utils;
 
 #[cfg(test)]
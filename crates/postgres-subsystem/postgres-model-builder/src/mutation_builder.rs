@@ -8,7 +8,8 @@
 // by the Apache License, Version 2.0.
 
 //! Build mutation input types (`<Type>CreationInput`, `<Type>UpdateInput`, `<Type>ReferenceInput`) and
-//! mutations (`create<Type>`, `update<Type>`, and `delete<Type>` as well as their plural versions)
+//! mutations (`create<Type>`, `update<Type>`, `delete<Type>`, `upsert<Type>`, and `restore<Type>` as well as
+//! their plural versions)
 
 use core_plugin_interface::{
     core_model::{
@@ -43,8 +44,10 @@ use super::{
     naming::ToPostgresTypeNames,
     reference_input_type_builder::ReferenceInputTypeBuilder,
     resolved_builder::{ResolvedCompositeType, ResolvedType},
+    restore_mutation_builder::RestoreMutationBuilder,
     system_builder::SystemContextBuilding,
     update_mutation_builder::UpdateMutationBuilder,
+    upsert_mutation_builder::UpsertMutationBuilder,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -66,6 +69,8 @@ pub fn build_shallow(
     CreateMutationBuilder {}.build_shallow(resolved_types, building);
     UpdateMutationBuilder {}.build_shallow(resolved_types, building);
     DeleteMutationBuilder {}.build_shallow(resolved_types, building);
+    UpsertMutationBuilder {}.build_shallow(resolved_types, building);
+    RestoreMutationBuilder {}.build_shallow(resolved_types, building);
 }
 
 /// Expand the mutation input types as well as build the mutation
@@ -75,6 +80,8 @@ pub fn build_expanded(building: &mut SystemContextBuilding) -> Result<(), ModelB
     CreateMutationBuilder {}.build_expanded(building)?;
     UpdateMutationBuilder {}.build_expanded(building)?;
     DeleteMutationBuilder {}.build_expanded(building)?;
+    UpsertMutationBuilder {}.build_expanded(building)?;
+    RestoreMutationBuilder {}.build_expanded(building)?;
 
     Ok(())
 }
@@ -252,6 +259,7 @@ pub trait DataParamBuilder<D> {
                         has_default_value: field.has_default_value,
                         access: field.access.clone(),
                         dynamic_default_value: field.dynamic_default_value.clone(),
+                        cost: None,
                         readonly: field.readonly,
                     })
                 } else {
@@ -280,6 +288,7 @@ pub trait DataParamBuilder<D> {
                             relation: field.relation.clone(),
                             has_default_value: field.has_default_value,
                             dynamic_default_value: field.dynamic_default_value.clone(),
+                            cost: None,
                             readonly: field.readonly,
                         })
                     }
@@ -296,6 +305,7 @@ pub trait DataParamBuilder<D> {
                 relation: field.relation.clone(),
                 has_default_value: field.has_default_value,
                 dynamic_default_value: field.dynamic_default_value.clone(),
+                cost: None,
                 readonly: field.readonly,
             }),
             PostgresRelation::OneToMany { .. } => {
@@ -329,6 +339,7 @@ pub trait DataParamBuilder<D> {
                         relation: field.relation.clone(),
                         has_default_value: field.has_default_value,
                         dynamic_default_value: field.dynamic_default_value.clone(),
+                        cost: None,
                         readonly: field.readonly,
                     }),
                 }
@@ -373,6 +384,7 @@ pub trait DataParamBuilder<D> {
                         relation: field.relation.clone(),
                         has_default_value: field.has_default_value,
                         dynamic_default_value: field.dynamic_default_value.clone(),
+                        cost: None,
                         readonly: field.readonly,
                     }),
                 }
@@ -46,6 +46,10 @@ pub(super) trait ToPostgresQueryName {
     fn collection_query(&self) -> String;
     /// Aggregate query name (e.g. `concertAgg`)
     fn aggregate_query(&self) -> String;
+    /// Connection query name (e.g. `concertsConnection`)
+    fn connection_query(&self) -> String;
+    /// Group-by query name (e.g. `concertsGroupBy`)
+    fn group_by_query(&self) -> String;
 
     /// Unique query name (e.g. `concertByTitle`)
     /// `constraint_name` is the name of the unique constraint in the database (possibly in snake case or camel case)
@@ -69,6 +73,14 @@ impl<T: ToPlural> ToPostgresQueryName for T {
         format!("{}Agg", self.collection_query())
     }
 
+    fn connection_query(&self) -> String {
+        format!("{}Connection", self.collection_query())
+    }
+
+    fn group_by_query(&self) -> String {
+        format!("{}GroupBy", self.collection_query())
+    }
+
     fn unique_query(&self, constraint_name: &str) -> String {
         format!(
             "{}By{}",
@@ -90,6 +102,14 @@ fn to_update(name: &str) -> String {
     format!("update{name}")
 }
 
+fn to_upsert(name: &str) -> String {
+    format!("upsert{name}")
+}
+
+fn to_restore(name: &str) -> String {
+    format!("restore{name}")
+}
+
 /// A type that can generate GraphQL mutation names.
 pub(crate) trait ToPostgresMutationNames {
     /// Single create name (e.g. `createConcert`)
@@ -104,6 +124,14 @@ pub(crate) trait ToPostgresMutationNames {
     fn collection_delete(&self) -> String;
     /// Plural update name (e.g. `updateConcerts`)
     fn collection_update(&self) -> String;
+    /// Single upsert name (e.g. `upsertConcert`)
+    fn pk_upsert(&self) -> String;
+    /// Plural upsert name (e.g. `upsertConcerts`)
+    fn collection_upsert(&self) -> String;
+    /// Single restore name (e.g. `restoreConcert`)
+    fn pk_restore(&self) -> String;
+    /// Plural restore name (e.g. `restoreConcerts`)
+    fn collection_restore(&self) -> String;
 }
 
 impl<T: ToPlural> ToPostgresMutationNames for T {
@@ -130,6 +158,22 @@ impl<T: ToPlural> ToPostgresMutationNames for T {
     fn collection_update(&self) -> String {
         to_update(&self.to_plural())
     }
+
+    fn pk_upsert(&self) -> String {
+        to_upsert(&self.to_singular())
+    }
+
+    fn collection_upsert(&self) -> String {
+        to_upsert(&self.to_plural())
+    }
+
+    fn pk_restore(&self) -> String {
+        to_restore(&self.to_singular())
+    }
+
+    fn collection_restore(&self) -> String {
+        to_restore(&self.to_plural())
+    }
 }
 
 fn to_creation_type(name: &str) -> String {
@@ -144,6 +188,14 @@ fn to_reference_type(name: &str) -> String {
     format!("{name}ReferenceInput")
 }
 
+fn to_on_conflict_type(name: &str) -> String {
+    format!("{name}OnConflictInput")
+}
+
+fn to_upsert_constraint_type(name: &str) -> String {
+    format!("{name}UpsertConstraint")
+}
+
 /// A type that can generate GraphQL type names.
 pub(crate) trait ToPostgresTypeNames {
     /// Creation type name (e.g. `ConcertCreationInput`)
@@ -152,6 +204,10 @@ pub(crate) trait ToPostgresTypeNames {
     fn update_type(&self) -> String;
     /// Reference type name (e.g. `ConcertReferenceInput`)
     fn reference_type(&self) -> String;
+    /// Upsert conflict input type name (e.g. `ConcertOnConflictInput`)
+    fn on_conflict_type(&self) -> String;
+    /// Upsert constraint enum name (e.g. `ConcertUpsertConstraint`)
+    fn upsert_constraint_type(&self) -> String;
 }
 
 impl ToPostgresTypeNames for str {
@@ -166,6 +222,14 @@ impl ToPostgresTypeNames for str {
     fn reference_type(&self) -> String {
         to_reference_type(self)
     }
+
+    fn on_conflict_type(&self) -> String {
+        to_on_conflict_type(self)
+    }
+
+    fn upsert_constraint_type(&self) -> String {
+        to_upsert_constraint_type(self)
+    }
 }
 
 impl<T: ToPlural> ToPostgresTypeNames for T {
@@ -180,6 +244,14 @@ impl<T: ToPlural> ToPostgresTypeNames for T {
     fn reference_type(&self) -> String {
         to_reference_type(&self.to_singular())
     }
+
+    fn on_conflict_type(&self) -> String {
+        to_on_conflict_type(&self.to_singular())
+    }
+
+    fn upsert_constraint_type(&self) -> String {
+        to_upsert_constraint_type(&self.to_singular())
+    }
 }
 
 pub(crate) trait ToTableName {
@@ -52,6 +52,9 @@ impl Shallow for OrderByParameterTypeWrapper {
     }
 }
 
+/// The ordering type for `@searchable` fields (which may also be ordered by search rank)
+const TEXT_SEARCH_ORDERING_TYPE_NAME: &str = "SearchableStringOrdering";
+
 pub fn build_shallow(resolved_env: &ResolvedTypeEnv, building: &mut SystemContextBuilding) {
     let type_name = "Ordering".to_string();
     let primitive_type = OrderByParameterType {
@@ -71,6 +74,14 @@ pub fn build_shallow(resolved_env: &ResolvedTypeEnv, building: &mut SystemContex
         .order_by_types
         .add(&vector_ordering_type_name, vector_ordering_type);
 
+    building.order_by_types.add(
+        TEXT_SEARCH_ORDERING_TYPE_NAME,
+        OrderByParameterType {
+            name: TEXT_SEARCH_ORDERING_TYPE_NAME.to_string(),
+            kind: OrderByParameterTypeKind::TextSearch,
+        },
+    );
+
     for (_, typ) in resolved_env.resolved_types.iter() {
         if let ResolvedType::Composite(ResolvedCompositeType { .. }) = typ {
             let shallow_type = create_shallow_type(typ);
@@ -154,9 +165,18 @@ fn new_param(
     order_by_types: &MappedArena<OrderByParameterType>,
     access: Option<Access>,
     type_hint: Option<&ResolvedTypeHint>,
+    searchable: bool,
 ) -> OrderByParameter {
-    let (param_type_name, param_type_id) =
-        order_by_param_type(entity_type_name, is_primitive, order_by_types);
+    let (param_type_name, param_type_id) = if searchable {
+        (
+            TEXT_SEARCH_ORDERING_TYPE_NAME.to_string(),
+            order_by_types
+                .get_id(TEXT_SEARCH_ORDERING_TYPE_NAME)
+                .unwrap(),
+        )
+    } else {
+        order_by_param_type(entity_type_name, is_primitive, order_by_types)
+    };
 
     let base_param = FieldType::Plain(OrderByParameterTypeWrapper {
         name: param_type_name,
@@ -227,6 +247,7 @@ pub fn new_field_param(
         order_by_types,
         Some(entity_field.access.clone()),
         resolved_field.type_hint.as_ref(),
+        resolved_field.searchable.is_some(),
     ))
 }
 
@@ -243,6 +264,7 @@ pub fn new_root_param(
         order_by_types,
         None,
         None,
+        false,
     )
 }
 
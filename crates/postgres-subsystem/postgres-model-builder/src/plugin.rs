@@ -47,7 +47,18 @@ impl SubsystemBuilder for PostgresSubsystemBuilder {
                     targets: &[AnnotationTarget::Field],
                     no_params: false,
                     single_params: true,
-                    mapped_params: None,
+                    mapped_params: Some(&[
+                        MappedAnnotationParamSpec {
+                            name: "name",
+                            optional: true,
+                            keywords: &[],
+                        },
+                        MappedAnnotationParamSpec {
+                            name: "renamedFrom",
+                            optional: true,
+                            keywords: &[],
+                        },
+                    ]),
                 },
             ),
             (
@@ -105,10 +116,12 @@ impl SubsystemBuilder for PostgresSubsystemBuilder {
                         MappedAnnotationParamSpec {
                             name: "min",
                             optional: false,
+                            keywords: &[],
                         },
                         MappedAnnotationParamSpec {
                             name: "max",
                             optional: false,
+                            keywords: &[],
                         },
                     ]),
                 },
@@ -176,6 +189,43 @@ impl SubsystemBuilder for PostgresSubsystemBuilder {
                     mapped_params: None,
                 },
             ),
+            (
+                "relation", // referential action of a many-to-one relation's foreign key
+                AnnotationSpec {
+                    targets: &[AnnotationTarget::Field],
+                    no_params: false,
+                    single_params: false,
+                    mapped_params: Some(&[MappedAnnotationParamSpec {
+                        name: "onDelete",
+                        optional: false,
+                        keywords: &[
+                            "NO_ACTION",
+                            "RESTRICT",
+                            "CASCADE",
+                            "SET_NULL",
+                            "SET_DEFAULT",
+                        ],
+                    }]),
+                },
+            ),
+            (
+                "check", // a CHECK constraint expression (on a field or the whole type)
+                AnnotationSpec {
+                    targets: &[AnnotationTarget::Field, AnnotationTarget::Type],
+                    no_params: false,
+                    single_params: true,
+                    mapped_params: None,
+                },
+            ),
+            (
+                "searchable", // full-text search (with an optional text-search language)
+                AnnotationSpec {
+                    targets: &[AnnotationTarget::Field],
+                    no_params: true,
+                    single_params: true,
+                    mapped_params: None,
+                },
+            ),
             (
                 "doublePrecision",
                 AnnotationSpec {
@@ -195,10 +245,17 @@ impl SubsystemBuilder for PostgresSubsystemBuilder {
                         MappedAnnotationParamSpec {
                             name: "name",
                             optional: true,
+                            keywords: &[],
                         },
                         MappedAnnotationParamSpec {
                             name: "schema",
                             optional: true,
+                            keywords: &[],
+                        },
+                        MappedAnnotationParamSpec {
+                            name: "renamedFrom",
+                            optional: true,
+                            keywords: &[],
                         },
                     ]),
                 },
@@ -239,6 +296,24 @@ impl SubsystemBuilder for PostgresSubsystemBuilder {
                     mapped_params: None,
                 },
             ),
+            (
+                "softDelete", // mark rows as deleted instead of deleting them (with an optional column name)
+                AnnotationSpec {
+                    targets: &[AnnotationTarget::Type],
+                    no_params: true,
+                    single_params: true,
+                    mapped_params: None,
+                },
+            ),
+            (
+                "subscription",
+                AnnotationSpec {
+                    targets: &[AnnotationTarget::Type],
+                    no_params: true,
+                    single_params: false,
+                    mapped_params: None,
+                },
+            ),
         ]
     }
 
@@ -272,9 +347,21 @@ impl SubsystemBuilder for PostgresSubsystemBuilder {
                     .iter()
                     .map(|(_, q)| q.name.clone());
 
+                let connection_query_names = subsystem
+                    .connection_queries
+                    .iter()
+                    .map(|(_, q)| q.name.clone());
+
+                let group_by_query_names = subsystem
+                    .group_by_queries
+                    .iter()
+                    .map(|(_, q)| q.name.clone());
+
                 pk_query_names
                     .chain(collection_query_names)
                     .chain(aggregate_query_names)
+                    .chain(connection_query_names)
+                    .chain(group_by_query_names)
                     .collect()
             },
             mutation_names: subsystem
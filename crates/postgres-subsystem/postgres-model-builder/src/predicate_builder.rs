@@ -51,6 +51,9 @@ impl Shallow for PredicateParameterTypeWrapper {
     }
 }
 
+/// The filter type for `@searchable` fields (the `String` operators along with `search`)
+const SEARCHABLE_STRING_FILTER_TYPE_NAME: &str = "SearchableStringFilter";
+
 pub fn build_shallow(types: &MappedArena<ResolvedType>, building: &mut SystemContextBuilding) {
     for (_, typ) in types.iter() {
         match typ {
@@ -106,6 +109,13 @@ pub fn build_shallow(types: &MappedArena<ResolvedType>, building: &mut SystemCon
             kind: PredicateParameterTypeKind::Vector,
         },
     );
+    building.predicate_types.add(
+        SEARCHABLE_STRING_FILTER_TYPE_NAME,
+        PredicateParameterType {
+            name: SEARCHABLE_STRING_FILTER_TYPE_NAME.to_string(),
+            kind: PredicateParameterTypeKind::ImplicitEqual, // Will be set to the correct value in build_expanded
+        },
+    );
 }
 
 pub fn build_expanded(resolved_env: &ResolvedTypeEnv, building: &mut SystemContextBuilding) {
@@ -115,6 +125,15 @@ pub fn build_expanded(resolved_env: &ResolvedTypeEnv, building: &mut SystemConte
 
         let new_kind = expand_primitive_type(primitive_type, building);
         building.predicate_types[existing_param_id.unwrap()].kind = new_kind;
+
+        if primitive_type.name == "String" {
+            let searchable_kind = expand_searchable_string_type(primitive_type, building);
+            let searchable_param_id = building
+                .predicate_types
+                .get_id(SEARCHABLE_STRING_FILTER_TYPE_NAME)
+                .unwrap();
+            building.predicate_types[searchable_param_id].kind = searchable_kind;
+        }
     }
 
     for (_, entity_type) in building.entity_types.iter() {
@@ -155,6 +174,34 @@ fn expand_primitive_type(
     create_operator_filter_type_kind(typ, building)
 }
 
+fn expand_searchable_string_type(
+    typ: &PostgresPrimitiveType,
+    building: &SystemContextBuilding,
+) -> PredicateParameterTypeKind {
+    match create_operator_filter_type_kind(typ, building) {
+        PredicateParameterTypeKind::Operator(mut parameters) => {
+            let string_type_id = building.predicate_types.get_id(&typ.name).unwrap();
+
+            // The search terms (for example, `{title: {search: "rust -java"}}`)
+            parameters.push(PredicateParameter {
+                name: "search".to_string(),
+                typ: FieldType::Optional(Box::new(FieldType::Plain(
+                    PredicateParameterTypeWrapper {
+                        name: typ.name.clone(),
+                        type_id: string_type_id,
+                    },
+                ))),
+                column_path_link: None,
+                access: None,
+                vector_distance_function: None,
+            });
+
+            PredicateParameterTypeKind::Operator(parameters)
+        }
+        kind => kind,
+    }
+}
+
 fn expand_entity_type(
     resolved_type: &ResolvedType,
     entity_type: &EntityType,
@@ -166,10 +213,6 @@ fn expand_entity_type(
         .fields
         .iter()
         .map(|field| {
-            let param_type_name = get_filter_type_name(field.typ.name());
-
-            let column_path_link = Some(field.relation.column_path_link(&building.database));
-
             let resolved_field = resolved_type
                 .as_composite()
                 .fields
@@ -177,6 +220,14 @@ fn expand_entity_type(
                 .find(|f| f.name == field.name)
                 .unwrap();
 
+            let param_type_name = if resolved_field.searchable.is_some() {
+                SEARCHABLE_STRING_FILTER_TYPE_NAME.to_string()
+            } else {
+                get_filter_type_name(field.typ.name())
+            };
+
+            let column_path_link = Some(field.relation.column_path_link(&building.database));
+
             PredicateParameter {
                 name: field.name.to_string(),
                 typ: FieldType::Optional(Box::new(FieldType::Plain(
@@ -280,6 +331,8 @@ fn expand_unique_type(
     PredicateParameterTypeKind::Reference(field_predicates)
 }
 
+const ENUM_OPERATORS: [&str; 3] = ["eq", "neq", "in"];
+
 lazy_static! {
     // immutable map defining the operators allowed for each type
     // TODO: could probably be done better?
@@ -357,18 +410,34 @@ fn create_operator_filter_type_kind(
         };
         let predicate_param_type_id = building.predicate_types.get_id(operand_type).unwrap();
 
+        let operand_field_type = FieldType::Plain(PredicateParameterTypeWrapper {
+            name: operand_type.to_owned(),
+            type_id: predicate_param_type_id,
+        });
+        // The `in` operator takes a list of values (for example, `{status: {in: [DRAFT, PUBLISHED]}}`)
+        let operand_field_type = if operator == &"in" {
+            FieldType::List(Box::new(operand_field_type))
+        } else {
+            operand_field_type
+        };
+
         PredicateParameter {
             name: operator.to_string(),
-            typ: FieldType::Optional(Box::new(FieldType::Plain(PredicateParameterTypeWrapper {
-                name: operand_type.to_owned(),
-                type_id: predicate_param_type_id,
-            }))),
+            typ: FieldType::Optional(Box::new(operand_field_type)),
             column_path_link: None,
             access: None,
             vector_distance_function: None,
         }
     };
 
+    // Enums aren't listed in TYPE_OPERATORS, since their names are user-defined
+    if primitive_type.enum_values.is_some() {
+        let parameters: Vec<PredicateParameter> =
+            ENUM_OPERATORS.iter().map(parameter_constructor).collect();
+
+        return PredicateParameterTypeKind::Operator(parameters);
+    }
+
     // look up type in (type, operations) table
     if let Some(maybe_operators) = TYPE_OPERATORS.get(&primitive_type.name as &str) {
         if let Some(operators) = maybe_operators {
@@ -12,16 +12,28 @@ use core_plugin_interface::core_model::{
     types::{BaseOperationReturnType, FieldType, Named, OperationReturnType},
 };
 
-use exo_sql::Database;
+use exo_sql::{Database, PhysicalColumnType};
+use heck::ToUpperCamelCase;
 use postgres_model::{
+    aggregate::{AggregateFieldType, AggregateType},
+    connection::{connection_type_name, CursorParameter, CursorParameterType},
+    group_by::{
+        aggregate_filter_type_name, aggregate_ordering_type_name, group_key_type_name,
+        group_type_name, GroupAggregateField, GroupByParameter, GroupHavingParameter,
+        GroupKeyField, GroupOrderByParameter,
+    },
     limit_offset::{LimitParameter, LimitParameterType, OffsetParameter, OffsetParameterType},
     order::{OrderByParameter, OrderByParameterType},
     predicate::{PredicateParameter, PredicateParameterType, PredicateParameterTypeWrapper},
     query::{
         AggregateQuery, AggregateQueryParameters, CollectionQuery, CollectionQueryParameters,
-        PkQuery, PkQueryParameters, UniqueQuery, UniqueQueryParameters,
+        ConnectionQuery, ConnectionQueryParameters, GroupByQuery, GroupByQueryParameters, PkQuery,
+        PkQueryParameters, UniqueQuery, UniqueQueryParameters,
     },
     relation::PostgresRelation,
+    soft_delete::{
+        IncludeDeletedParameter, IncludeDeletedParameterType, INCLUDE_DELETED_PARAM_NAME,
+    },
     types::{EntityType, PostgresField, PostgresPrimitiveType},
 };
 
@@ -43,6 +55,8 @@ pub fn build_shallow(types: &MappedArena<ResolvedType>, building: &mut SystemCon
             let collection_query = shallow_collection_query(entity_type_id, c);
             let aggregate_query = shallow_aggregate_query(entity_type_id, c);
             let unique_queries = shallow_unique_queries(entity_type_id, c);
+            let connection_query = shallow_connection_query(entity_type_id, c);
+            let group_by_query = shallow_group_by_query(entity_type_id, c);
 
             building
                 .pk_queries
@@ -58,6 +72,12 @@ pub fn build_shallow(types: &MappedArena<ResolvedType>, building: &mut SystemCon
                     .unique_queries
                     .add(&unique_query.name.to_owned(), unique_query);
             }
+            building
+                .connection_queries
+                .add(&connection_query.name.to_owned(), connection_query);
+            building
+                .group_by_queries
+                .add(&group_by_query.name.to_owned(), group_by_query);
         }
     }
 }
@@ -66,6 +86,7 @@ pub fn build_expanded(resolved_env: &ResolvedTypeEnv, building: &mut SystemConte
     for (_, entity_type) in building.entity_types.iter() {
         expand_pk_query(
             entity_type,
+            &building.primitive_types,
             &building.predicate_types,
             &mut building.pk_queries,
             &building.database,
@@ -76,6 +97,7 @@ pub fn build_expanded(resolved_env: &ResolvedTypeEnv, building: &mut SystemConte
             &building.predicate_types,
             &building.order_by_types,
             &mut building.collection_queries,
+            &building.database,
         );
         expand_aggregate_query(
             entity_type,
@@ -89,6 +111,21 @@ pub fn build_expanded(resolved_env: &ResolvedTypeEnv, building: &mut SystemConte
             resolved_env,
             &building.database,
         );
+        expand_connection_query(
+            entity_type,
+            &building.primitive_types,
+            &building.predicate_types,
+            &building.order_by_types,
+            &mut building.connection_queries,
+        );
+        expand_group_by_query(
+            entity_type,
+            &building.entity_types,
+            &building.aggregate_types,
+            &building.predicate_types,
+            &mut building.group_by_queries,
+            &building.database,
+        );
     }
 }
 
@@ -100,7 +137,8 @@ fn shallow_pk_query(
     PkQuery {
         name: operation_name,
         parameters: PkQueryParameters {
-            predicate_param: PredicateParameter::shallow(),
+            predicate_params: vec![],
+            include_deleted_param: None,
         },
         return_type: OperationReturnType::Optional(Box::new(OperationReturnType::Plain(
             BaseOperationReturnType {
@@ -113,23 +151,35 @@ fn shallow_pk_query(
 
 fn expand_pk_query(
     entity_type: &EntityType,
+    primitive_types: &MappedArena<PostgresPrimitiveType>,
     predicate_types: &MappedArena<PredicateParameterType>,
     pk_queries: &mut MappedArena<PkQuery>,
     database: &Database,
 ) {
     let operation_name = entity_type.pk_query();
     let existing_query = &mut pk_queries.get_by_key_mut(&operation_name).unwrap();
-    existing_query.parameters.predicate_param =
-        pk_predicate_param(entity_type, predicate_types, database);
+    existing_query.parameters.predicate_params =
+        pk_predicate_params(entity_type, predicate_types, database);
+    existing_query.parameters.include_deleted_param =
+        include_deleted_param(entity_type, primitive_types, database);
 }
 
-pub fn pk_predicate_param(
+/// Parameters to identify a row by its primary key (one for each field of a composite primary key)
+pub fn pk_predicate_params(
     entity_type: &EntityType,
     predicate_types: &MappedArena<PredicateParameterType>,
     database: &Database,
-) -> PredicateParameter {
-    let pk_field = entity_type.pk_field().unwrap();
-    implicit_equals_predicate_param(pk_field, predicate_types, database)
+) -> Vec<PredicateParameter> {
+    entity_type
+        .pk_fields()
+        .into_iter()
+        .map(|pk_field| match &pk_field.relation {
+            PostgresRelation::ManyToOne(_) => {
+                reference_predicate_param(pk_field, predicate_types, database)
+            }
+            _ => implicit_equals_predicate_param(pk_field, predicate_types, database),
+        })
+        .collect()
 }
 
 fn implicit_equals_predicate_param(
@@ -152,6 +202,28 @@ fn implicit_equals_predicate_param(
     }
 }
 
+/// A parameter that identifies the row referred to by a many-to-one field (such as `venue: {id: 1}`)
+fn reference_predicate_param(
+    field: &PostgresField<EntityType>,
+    predicate_types: &MappedArena<PredicateParameterType>,
+    database: &Database,
+) -> PredicateParameter {
+    let param_type_name = get_unique_filter_type_name(field.typ.name());
+    let param_type_id = predicate_types.get_id(&param_type_name).unwrap();
+    let param_type = PredicateParameterTypeWrapper {
+        name: param_type_name,
+        type_id: param_type_id,
+    };
+
+    PredicateParameter {
+        name: field.name.to_string(),
+        typ: FieldType::Plain(param_type),
+        column_path_link: Some(field.relation.column_path_link(database)),
+        access: None,
+        vector_distance_function: None,
+    }
+}
+
 fn shallow_collection_query(
     entity_type_id: SerializableSlabIndex<EntityType>,
     resolved_entity_type: &ResolvedCompositeType,
@@ -164,6 +236,7 @@ fn shallow_collection_query(
             order_by_param: OrderByParameter::shallow(),
             limit_param: LimitParameter::shallow(),
             offset_param: OffsetParameter::shallow(),
+            include_deleted_param: None,
         },
         return_type: OperationReturnType::List(Box::new(OperationReturnType::Plain(
             BaseOperationReturnType {
@@ -180,6 +253,7 @@ fn expand_collection_query(
     predicate_types: &MappedArena<PredicateParameterType>,
     order_by_types: &MappedArena<OrderByParameterType>,
     collection_queries: &mut MappedArena<CollectionQuery>,
+    database: &Database,
 ) {
     let operation_name = entity_type.collection_query();
 
@@ -188,6 +262,7 @@ fn expand_collection_query(
         order_by_type_builder::new_root_param(&entity_type.name, false, order_by_types);
     let limit_param = limit_param(primitive_types);
     let offset_param = offset_param(primitive_types);
+    let include_deleted_param = include_deleted_param(entity_type, primitive_types, database);
 
     let existing_query = &mut collection_queries.get_by_key_mut(&operation_name).unwrap();
 
@@ -195,6 +270,7 @@ fn expand_collection_query(
     existing_query.parameters.order_by_param = order_by_param;
     existing_query.parameters.limit_param = limit_param;
     existing_query.parameters.offset_param = offset_param;
+    existing_query.parameters.include_deleted_param = include_deleted_param;
 }
 
 fn shallow_aggregate_query(
@@ -226,6 +302,210 @@ fn expand_aggregate_query(
     existing_query.parameters.predicate_param = predicate_param;
 }
 
+fn shallow_connection_query(
+    entity_type_id: SerializableSlabIndex<EntityType>,
+    resolved_entity_type: &ResolvedCompositeType,
+) -> ConnectionQuery {
+    ConnectionQuery {
+        name: resolved_entity_type.connection_query(),
+        parameters: ConnectionQueryParameters {
+            predicate_param: PredicateParameter::shallow(),
+            order_by_param: OrderByParameter::shallow(),
+            first_param: LimitParameter::shallow(),
+            after_param: CursorParameter::shallow(),
+            last_param: LimitParameter::shallow(),
+            before_param: CursorParameter::shallow(),
+        },
+        return_type: OperationReturnType::Plain(BaseOperationReturnType {
+            associated_type_id: entity_type_id,
+            type_name: connection_type_name(&resolved_entity_type.name),
+        }),
+    }
+}
+
+fn expand_connection_query(
+    entity_type: &EntityType,
+    primitive_types: &MappedArena<PostgresPrimitiveType>,
+    predicate_types: &MappedArena<PredicateParameterType>,
+    order_by_types: &MappedArena<OrderByParameterType>,
+    connection_queries: &mut MappedArena<ConnectionQuery>,
+) {
+    let operation_name = entity_type.connection_query();
+
+    let predicate_param = collection_predicate_param(entity_type, predicate_types);
+    let order_by_param =
+        order_by_type_builder::new_root_param(&entity_type.name, false, order_by_types);
+
+    let existing_query = &mut connection_queries.get_by_key_mut(&operation_name).unwrap();
+
+    existing_query.parameters.predicate_param = predicate_param;
+    existing_query.parameters.order_by_param = order_by_param;
+    existing_query.parameters.first_param = count_param("first", primitive_types);
+    existing_query.parameters.after_param = cursor_param("after", primitive_types);
+    existing_query.parameters.last_param = count_param("last", primitive_types);
+    existing_query.parameters.before_param = cursor_param("before", primitive_types);
+}
+
+fn shallow_group_by_query(
+    entity_type_id: SerializableSlabIndex<EntityType>,
+    resolved_entity_type: &ResolvedCompositeType,
+) -> GroupByQuery {
+    GroupByQuery {
+        name: resolved_entity_type.group_by_query(),
+        parameters: GroupByQueryParameters {
+            by_param: GroupByParameter::shallow(),
+            predicate_param: PredicateParameter::shallow(),
+            having_param: GroupHavingParameter::shallow(),
+            order_by_param: GroupOrderByParameter::shallow(),
+        },
+        return_type: OperationReturnType::List(Box::new(OperationReturnType::Plain(
+            BaseOperationReturnType {
+                associated_type_id: entity_type_id,
+                type_name: group_type_name(&resolved_entity_type.name),
+            },
+        ))),
+    }
+}
+
+fn expand_group_by_query(
+    entity_type: &EntityType,
+    entity_types: &MappedArena<EntityType>,
+    aggregate_types: &MappedArena<AggregateType>,
+    predicate_types: &MappedArena<PredicateParameterType>,
+    group_by_queries: &mut MappedArena<GroupByQuery>,
+    database: &Database,
+) {
+    let operation_name = entity_type.group_by_query();
+
+    let by_param = GroupByParameter {
+        name: "by".to_string(),
+        type_name: format!("{}GroupByField", entity_type.name),
+        fields: group_key_fields(entity_type, entity_types, database),
+    };
+
+    let entity_aggregate_type_name = aggregate_type_name(&entity_type.name);
+
+    let having_param = GroupHavingParameter {
+        name: "having".to_string(),
+        type_name: aggregate_filter_type_name(&entity_aggregate_type_name),
+        fields: group_aggregate_fields(entity_type, aggregate_types, predicate_types, database),
+    };
+
+    let order_by_param = GroupOrderByParameter {
+        name: "orderBy".to_string(),
+        type_name: format!("{}GroupOrdering", entity_type.name),
+        key_type_name: format!("{}Ordering", group_key_type_name(&entity_type.name)),
+        agg_type_name: aggregate_ordering_type_name(&entity_aggregate_type_name),
+    };
+
+    let existing_query = &mut group_by_queries.get_by_key_mut(&operation_name).unwrap();
+
+    existing_query.parameters.by_param = by_param;
+    existing_query.parameters.predicate_param =
+        collection_predicate_param(entity_type, predicate_types);
+    existing_query.parameters.having_param = having_param;
+    existing_query.parameters.order_by_param = order_by_param;
+}
+
+/// Fields that rows may be grouped by: scalar fields and the foreign key of many-to-one relations
+/// (such as `userId` for the `user` field).
+fn group_key_fields(
+    entity_type: &EntityType,
+    entity_types: &MappedArena<EntityType>,
+    database: &Database,
+) -> Vec<GroupKeyField> {
+    entity_type
+        .fields
+        .iter()
+        .filter_map(|field| match &field.relation {
+            PostgresRelation::Pk { column_id } | PostgresRelation::Scalar { column_id } => {
+                groupable_column_type(&column_id.get_column(database).typ).then(|| GroupKeyField {
+                    name: field.name.clone(),
+                    field_name: field.name.clone(),
+                    type_name: field.typ.name().to_string(),
+                    column_id: *column_id,
+                })
+            }
+            PostgresRelation::ManyToOne(relation) => {
+                let foreign_entity_type =
+                    &entity_types[relation.foreign_pk_field_id.entity_type_id()];
+                let foreign_pk_field = foreign_entity_type.pk_field()?;
+
+                Some(GroupKeyField {
+                    name: format!(
+                        "{}{}",
+                        field.name,
+                        foreign_pk_field.name.to_upper_camel_case()
+                    ),
+                    field_name: field.name.clone(),
+                    type_name: foreign_pk_field.typ.name().to_string(),
+                    column_id: relation.self_column_id(database),
+                })
+            }
+            PostgresRelation::OneToMany(_) => None,
+        })
+        .collect()
+}
+
+/// Fields whose aggregates groups may be filtered and ordered by (such as `id` for `TodoAgg.id`).
+/// Only the aggregates that have a corresponding filter type (such as `IntFilter`) are included.
+fn group_aggregate_fields(
+    entity_type: &EntityType,
+    aggregate_types: &MappedArena<AggregateType>,
+    predicate_types: &MappedArena<PredicateParameterType>,
+    database: &Database,
+) -> Vec<GroupAggregateField> {
+    entity_type
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let column_id = match &field.relation {
+                PostgresRelation::Pk { column_id } | PostgresRelation::Scalar { column_id } => {
+                    *column_id
+                }
+                _ => return None,
+            };
+
+            if matches!(
+                column_id.get_column(database).typ,
+                PhysicalColumnType::Vector { .. }
+            ) {
+                return None;
+            }
+
+            let type_name = aggregate_type_name(field.typ.name());
+            let aggregate_type = aggregate_types.get_by_key(&type_name)?;
+
+            let aggregates: Vec<_> = aggregate_type
+                .fields
+                .iter()
+                .filter_map(|aggregate_field| match &aggregate_field.typ {
+                    AggregateFieldType::Scalar { type_name, kind } => predicate_types
+                        .get_id(&predicate_builder::get_filter_type_name(type_name))
+                        .map(|_| (*kind, type_name.clone())),
+                    AggregateFieldType::Composite { .. } => None,
+                })
+                .collect();
+
+            (!aggregates.is_empty()).then(|| GroupAggregateField {
+                name: field.name.clone(),
+                type_name,
+                column_id,
+                aggregates,
+            })
+        })
+        .collect()
+}
+
+fn groupable_column_type(column_type: &PhysicalColumnType) -> bool {
+    !matches!(
+        column_type,
+        PhysicalColumnType::Json
+            | PhysicalColumnType::Vector { .. }
+            | PhysicalColumnType::Array { .. }
+    )
+}
+
 fn shallow_unique_queries(
     entity_type_id: SerializableSlabIndex<EntityType>,
     resolved_entity_type: &ResolvedCompositeType,
@@ -271,22 +551,7 @@ pub fn expand_unique_queries(
                             implicit_equals_predicate_param(entity_field, predicate_types, database)
                         }
                         PostgresRelation::ManyToOne { .. } => {
-                            let param_type_name = get_unique_filter_type_name(field.typ.name());
-                            let param_type_id = predicate_types.get_id(&param_type_name).unwrap();
-                            let param_type = PredicateParameterTypeWrapper {
-                                name: param_type_name,
-                                type_id: param_type_id,
-                            };
-
-                            PredicateParameter {
-                                name: field.name.to_string(),
-                                typ: FieldType::Plain(param_type),
-                                column_path_link: Some(
-                                    entity_field.relation.column_path_link(database),
-                                ),
-                                access: None,
-                                vector_distance_function: None,
-                            }
+                            reference_predicate_param(entity_field, predicate_types, database)
                         }
                         PostgresRelation::OneToMany { .. } => {
                             panic!("OneToMany relations cannot be used in unique queries")
@@ -302,10 +567,14 @@ pub fn expand_unique_queries(
 }
 
 pub fn limit_param(primitive_types: &MappedArena<PostgresPrimitiveType>) -> LimitParameter {
+    count_param("limit", primitive_types)
+}
+
+fn count_param(name: &str, primitive_types: &MappedArena<PostgresPrimitiveType>) -> LimitParameter {
     let param_type_name = "Int".to_string();
 
     LimitParameter {
-        name: "limit".to_string(),
+        name: name.to_string(),
         typ: FieldType::Optional(Box::new(FieldType::Plain(LimitParameterType {
             type_name: param_type_name.clone(),
             type_id: primitive_types.get_id(&param_type_name).unwrap(),
@@ -313,6 +582,25 @@ pub fn limit_param(primitive_types: &MappedArena<PostgresPrimitiveType>) -> Limi
     }
 }
 
+/// The `includeDeleted` parameter for the queries of a `@softDelete` type
+fn include_deleted_param(
+    entity_type: &EntityType,
+    primitive_types: &MappedArena<PostgresPrimitiveType>,
+    database: &Database,
+) -> Option<IncludeDeletedParameter> {
+    database.get_soft_delete_column_id(entity_type.table_id)?;
+
+    let param_type_name = "Boolean".to_string();
+
+    Some(IncludeDeletedParameter {
+        name: INCLUDE_DELETED_PARAM_NAME.to_string(),
+        typ: FieldType::Optional(Box::new(FieldType::Plain(IncludeDeletedParameterType {
+            type_name: param_type_name.clone(),
+            type_id: primitive_types.get_id(&param_type_name).unwrap(),
+        }))),
+    })
+}
+
 pub fn offset_param(primitive_types: &MappedArena<PostgresPrimitiveType>) -> OffsetParameter {
     let param_type_name = "Int".to_string();
 
@@ -325,6 +613,21 @@ pub fn offset_param(primitive_types: &MappedArena<PostgresPrimitiveType>) -> Off
     }
 }
 
+fn cursor_param(
+    name: &str,
+    primitive_types: &MappedArena<PostgresPrimitiveType>,
+) -> CursorParameter {
+    let param_type_name = "String".to_string();
+
+    CursorParameter {
+        name: name.to_string(),
+        typ: FieldType::Optional(Box::new(FieldType::Plain(CursorParameterType {
+            type_name: param_type_name.clone(),
+            type_id: primitive_types.get_id(&param_type_name).unwrap(),
+        }))),
+    }
+}
+
 pub fn collection_predicate_param(
     entity_type: &EntityType,
     predicate_types: &MappedArena<PredicateParameterType>,
@@ -381,3 +684,52 @@ impl Shallow for OffsetParameterType {
         }
     }
 }
+
+impl Shallow for CursorParameter {
+    fn shallow() -> Self {
+        CursorParameter {
+            name: String::default(),
+            typ: FieldType::Plain(CursorParameterType::shallow()),
+        }
+    }
+}
+
+impl Shallow for CursorParameterType {
+    fn shallow() -> Self {
+        CursorParameterType {
+            type_name: String::default(),
+            type_id: SerializableSlabIndex::shallow(),
+        }
+    }
+}
+
+impl Shallow for GroupByParameter {
+    fn shallow() -> Self {
+        GroupByParameter {
+            name: String::default(),
+            type_name: String::default(),
+            fields: vec![],
+        }
+    }
+}
+
+impl Shallow for GroupHavingParameter {
+    fn shallow() -> Self {
+        GroupHavingParameter {
+            name: String::default(),
+            type_name: String::default(),
+            fields: vec![],
+        }
+    }
+}
+
+impl Shallow for GroupOrderByParameter {
+    fn shallow() -> Self {
+        GroupOrderByParameter {
+            name: String::default(),
+            type_name: String::default(),
+            key_type_name: String::default(),
+            agg_type_name: String::default(),
+        }
+    }
+}
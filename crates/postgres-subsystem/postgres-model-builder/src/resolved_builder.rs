@@ -20,8 +20,10 @@ use codemap_diagnostic::{Diagnostic, Level, SpanLabel, SpanStyle};
 
 use core_plugin_interface::{
     core_model::{
+        cache::CachePolicy,
         mapped_arena::MappedArena,
         primitive_type::PrimitiveType,
+        rate_limit::RateLimit,
         types::{FieldType, Named},
     },
     core_model_builder::{
@@ -29,7 +31,10 @@ use core_plugin_interface::{
             default_span, AstAnnotationParams, AstExpr, AstField, AstFieldDefault,
             AstFieldDefaultKind, AstFieldType, AstModel, AstModelKind,
         },
-        builder::resolved_builder::AnnotationMapHelper,
+        builder::resolved_builder::{
+            build_cache_policy, build_cost_weight, build_rate_limit, refers_to_context,
+            AnnotationMapHelper,
+        },
         error::ModelBuildingError,
         typechecker::{
             typ::{Module, Type, TypecheckedSystem},
@@ -37,7 +42,9 @@ use core_plugin_interface::{
         },
     },
 };
-use exo_sql::{PhysicalTableName, VectorDistanceFunction};
+use exo_sql::{
+    PhysicalTableName, ReferentialAction, VectorDistanceFunction, DEFAULT_TEXT_SEARCH_LANGUAGE,
+};
 
 use super::{
     access_builder::{build_access, ResolvedAccess},
@@ -89,8 +96,22 @@ pub struct ResolvedCompositeType {
     pub name: String,
     pub plural_name: String,
     pub fields: Vec<ResolvedField>,
+    /// Should changes to this type be available as a subscription (`@subscription`)?
+    pub subscription: bool,
     pub table_name: PhysicalTableName,
+    /// The earlier name of the table (`@table(renamedFrom: "...")`)
+    pub table_renamed_from: Option<PhysicalTableName>,
     pub access: ResolvedAccess,
+    /// The table-level CHECK constraint expression (`@check("...")`)
+    pub check: Option<String>,
+    /// The caching policy for queries on this type (`@cache(...)`)
+    pub cache: Option<CachePolicy>,
+    /// The weight of the type for query cost analysis (`@cost(...)`)
+    pub cost: Option<u32>,
+    /// The rate limit of operations on this type (`@rateLimit(...)` on the type or its module)
+    pub rate_limit: Option<RateLimit>,
+    /// The column marking soft-deleted rows (`@softDelete` or `@softDelete("<column name>")`)
+    pub soft_delete_column: Option<String>,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     #[serde(default = "default_span")]
@@ -144,6 +165,16 @@ pub struct ResolvedField {
     pub default_value: Option<ResolvedFieldDefault>,
     pub update_sync: bool,
     pub readonly: bool,
+    /// The earlier name of the column (`@column(renamedFrom: "...")`)
+    pub column_renamed_from: Option<String>,
+    /// The text-search language if the field is `@searchable`
+    pub searchable: Option<String>,
+    /// The action on deleting the referenced row (`@relation(onDelete: ...)`)
+    pub on_delete: Option<ReferentialAction>,
+    /// The column-level CHECK constraint expression (`@check("...")`)
+    pub check: Option<String>,
+    /// The weight of the field for query cost analysis (`@cost(...)`)
+    pub cost: Option<u32>,
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
     #[serde(default = "default_span")]
@@ -190,8 +221,9 @@ pub enum ResolvedTypeHint {
 }
 
 impl ResolvedCompositeType {
-    pub fn pk_field(&self) -> Option<&ResolvedField> {
-        self.fields.iter().find(|f| f.is_pk)
+    /// The fields annotated with `@pk` (more than one for a composite primary key)
+    pub fn pk_fields(&self) -> Vec<&ResolvedField> {
+        self.fields.iter().filter(|f| f.is_pk).collect()
     }
 
     pub fn field_by_column_name(&self, column_name: &str) -> Option<&ResolvedField> {
@@ -280,6 +312,14 @@ fn resolve(
     for (_, Module(module)) in typechecked_system.modules.iter() {
         // Process each persistent type to create a PostgresType
         if module.annotations.get("postgres").is_some() {
+            let module_rate_limit = build_rate_limit(
+                module.annotations.get("rateLimit"),
+                &module.name,
+                &typechecked_system.types,
+                module.span,
+                errors,
+            );
+
             for typ in module.types.iter() {
                 if let Some(Type::Composite(ct)) = typechecked_system.types.get_by_key(&typ.name) {
                     if ct.kind == AstModelKind::Type {
@@ -291,6 +331,7 @@ fn resolve(
                         let TableInfo {
                             name: table_name,
                             schema: schema_name,
+                            renamed_from: table_renamed_from,
                         } = extract_table_annotation(
                             ct.annotations.get("table"),
                             &ct.name,
@@ -298,6 +339,34 @@ fn resolve(
                         );
 
                         let access = build_access(ct.annotations.get("access"));
+                        let subscription = ct.annotations.contains("subscription");
+                        let check = build_check(ct.annotations.get("check"), ct.span, errors);
+                        // Responses depend on the request if the rules for querying the type (or
+                        // any of its fields) refer to a context
+                        let request_dependent = std::iter::once(&ct.annotations)
+                            .chain(ct.fields.iter().map(|field| &field.annotations))
+                            .any(|annotations| {
+                                build_access(annotations.get("access"))
+                                    .query_rule()
+                                    .is_some_and(|rule| {
+                                        refers_to_context(rule, &typechecked_system.types)
+                                    })
+                            });
+                        let cache = build_cache_policy(
+                            ct.annotations.get("cache"),
+                            request_dependent,
+                            ct.span,
+                            errors,
+                        );
+                        let cost = build_cost_weight(ct.annotations.get("cost"), ct.span, errors);
+                        let rate_limit = build_rate_limit(
+                            ct.annotations.get("rateLimit"),
+                            &ct.name,
+                            &typechecked_system.types,
+                            ct.span,
+                            errors,
+                        )
+                        .or_else(|| module_rate_limit.clone());
                         let name = ct.name.clone();
                         let plural_name =
                             plural_annotation_value.unwrap_or_else(|| ct.name.to_plural()); // fallback to automatically pluralizing name
@@ -315,6 +384,7 @@ fn resolve(
                                 match column_info {
                                     Ok(ColumnInfo {
                                         name: column_name,
+                                        renamed_from: column_renamed_from,
                                         self_column,
                                         access,
                                         unique_constraints,
@@ -330,12 +400,26 @@ fn resolve(
                                             .as_ref()
                                             .map(|v| resolve_field_default_type(v, &typ, errors));
 
+                                        let searchable = build_searchable(field, &typ, errors);
+                                        let on_delete = build_on_delete(field, &typ, errors);
+                                        let is_pk = build_is_pk(field, &typ, errors);
+                                        let check = build_check(
+                                            field.annotations.get("check"),
+                                            field.span,
+                                            errors,
+                                        );
+                                        let cost = build_cost_weight(
+                                            field.annotations.get("cost"),
+                                            field.span,
+                                            errors,
+                                        );
+
                                         Some(ResolvedField {
                                             name: field.name.clone(),
                                             typ,
                                             column_name,
                                             self_column,
-                                            is_pk: field.annotations.contains("pk"),
+                                            is_pk,
                                             access,
                                             type_hint: build_type_hint(
                                                 field,
@@ -347,6 +431,11 @@ fn resolve(
                                             default_value,
                                             update_sync,
                                             readonly,
+                                            column_renamed_from,
+                                            searchable,
+                                            on_delete,
+                                            check,
+                                            cost,
                                             span: field.span,
                                         })
                                     }
@@ -356,7 +445,15 @@ fn resolve(
                                     }
                                 }
                             })
-                            .collect();
+                            .collect::<Vec<_>>();
+
+                        let soft_delete_column = build_soft_delete_column(
+                            ct.annotations.get("softDelete"),
+                            &ct.name,
+                            &resolved_fields,
+                            ct.span,
+                            errors,
+                        );
 
                         resolved_postgres_types.add(
                             &ct.name,
@@ -364,11 +461,23 @@ fn resolve(
                                 name,
                                 plural_name: plural_name.clone(),
                                 fields: resolved_fields,
+                                subscription,
+                                table_renamed_from: table_renamed_from.map(|name| {
+                                    PhysicalTableName {
+                                        name,
+                                        schema: schema_name.clone(),
+                                    }
+                                }),
                                 table_name: PhysicalTableName {
                                     name: table_name,
                                     schema: schema_name,
                                 },
                                 access: access.clone(),
+                                check,
+                                cache,
+                                cost,
+                                rate_limit,
+                                soft_delete_column,
                                 span: ct.span,
                             }),
                         );
@@ -472,6 +581,244 @@ fn resolve_field_default_type(
     }
 }
 
+/// Compute the text-search language for a `@searchable` (or `@searchable("language")`) field
+fn build_searchable(
+    field: &AstField<Typed>,
+    typ: &FieldType<ResolvedFieldType>,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<String> {
+    let annotation = field.annotations.get("searchable")?;
+
+    let error = |message: String| Diagnostic {
+        level: Level::Error,
+        message,
+        code: Some("C000".to_string()),
+        spans: vec![SpanLabel {
+            span: field.span,
+            style: SpanStyle::Primary,
+            label: None,
+        }],
+    };
+
+    let is_string = match typ {
+        FieldType::Plain(t) => t.type_name == "String",
+        FieldType::Optional(inner) => {
+            matches!(inner.as_ref(), FieldType::Plain(t) if t.type_name == "String")
+        }
+        FieldType::List(_) => false,
+    };
+
+    if !is_string {
+        errors.push(error(format!(
+            "@searchable can only be used on String fields (field '{}')",
+            field.name
+        )));
+        return None;
+    }
+
+    let language = match annotation {
+        AstAnnotationParams::Single(value, _) => value.as_string(),
+        _ => DEFAULT_TEXT_SEARCH_LANGUAGE.to_string(),
+    };
+
+    // The language is a text-search configuration name such as "english" or "simple"
+    if language.is_empty()
+        || !language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        errors.push(error(format!(
+            "Invalid text-search language '{language}' for field '{}'",
+            field.name
+        )));
+        return None;
+    }
+
+    Some(language)
+}
+
+/// Compute the referential action for a many-to-one field annotated with `@relation(onDelete: ...)`
+fn build_on_delete(
+    field: &AstField<Typed>,
+    typ: &FieldType<ResolvedFieldType>,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<ReferentialAction> {
+    let annotation = field.annotations.get("relation")?;
+
+    let error = |message: String| Diagnostic {
+        level: Level::Error,
+        message,
+        code: Some("C000".to_string()),
+        spans: vec![SpanLabel {
+            span: field.span,
+            style: SpanStyle::Primary,
+            label: None,
+        }],
+    };
+
+    // Only a many-to-one field (a non-list reference to another type) has a foreign key
+    let is_many_to_one = match typ {
+        FieldType::Plain(t) => !t.is_primitive,
+        FieldType::Optional(inner) => {
+            matches!(inner.as_ref(), FieldType::Plain(t) if !t.is_primitive)
+        }
+        FieldType::List(_) => false,
+    };
+
+    if !is_many_to_one {
+        errors.push(error(format!(
+            "@relation can only be used on many-to-one relation fields (field '{}')",
+            field.name
+        )));
+        return None;
+    }
+
+    let action = match annotation {
+        AstAnnotationParams::Map(m, _) => m.get("onDelete").map(|value| value.as_string()),
+        _ => None,
+    }?;
+
+    match ReferentialAction::from_sql(&action) {
+        Some(ReferentialAction::SetNull) if !matches!(typ, FieldType::Optional(_)) => {
+            errors.push(error(format!(
+                "onDelete: SET_NULL requires the field '{}' to be optional",
+                field.name
+            )));
+            None
+        }
+        Some(action) => Some(action),
+        None => {
+            errors.push(error(format!(
+                "Invalid onDelete action '{action}' for field '{}' (expected one of NO_ACTION, RESTRICT, CASCADE, SET_NULL, or SET_DEFAULT)",
+                field.name
+            )));
+            None
+        }
+    }
+}
+
+/// Determine if a field is a part of the primary key (annotated with `@pk`). Besides a scalar
+/// field, a many-to-one field may be a part of a composite primary key (such as in a join type
+/// keyed on both of its relations).
+fn build_is_pk(
+    field: &AstField<Typed>,
+    typ: &FieldType<ResolvedFieldType>,
+    errors: &mut Vec<Diagnostic>,
+) -> bool {
+    if !field.annotations.contains("pk") {
+        return false;
+    }
+
+    let message = match typ {
+        FieldType::Plain(_) => return true,
+        FieldType::Optional(_) => format!("@pk field '{}' cannot be optional", field.name),
+        FieldType::List(_) => format!(
+            "@pk cannot be used on a collection field (field '{}')",
+            field.name
+        ),
+    };
+
+    errors.push(Diagnostic {
+        level: Level::Error,
+        message,
+        code: Some("C000".to_string()),
+        spans: vec![SpanLabel {
+            span: field.span,
+            style: SpanStyle::Primary,
+            label: None,
+        }],
+    });
+
+    false
+}
+
+/// Compute the expression of a `@check("...")` annotation (on a field or a type)
+fn build_check(
+    annotation: Option<&AstAnnotationParams<Typed>>,
+    span: Span,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<String> {
+    // The expression may quote identifiers using escaped quotes (`@check("\"from\" < \"to\"")`)
+    let expr = match annotation? {
+        AstAnnotationParams::Single(value, _) => value.as_string().replace("\\\"", "\""),
+        _ => return None,
+    };
+
+    if expr.trim().is_empty() {
+        errors.push(Diagnostic {
+            level: Level::Error,
+            message: "@check requires a non-empty expression".to_string(),
+            code: Some("C000".to_string()),
+            spans: vec![SpanLabel {
+                span,
+                style: SpanStyle::Primary,
+                label: None,
+            }],
+        });
+        return None;
+    }
+
+    Some(expr)
+}
+
+/// The default column marking soft-deleted rows
+const DEFAULT_SOFT_DELETE_COLUMN: &str = "deleted_at";
+
+/// Compute the column marking soft-deleted rows of a type annotated with `@softDelete` (or
+/// `@softDelete("<column name>")`).
+///
+/// Typically, the column is added to the table without a corresponding field. However, a type may
+/// declare an optional `Instant` field mapped to the column (such as `deletedAt: Instant?`) to
+/// expose the deletion time.
+fn build_soft_delete_column(
+    annotation: Option<&AstAnnotationParams<Typed>>,
+    type_name: &str,
+    fields: &[ResolvedField],
+    span: Span,
+    errors: &mut Vec<Diagnostic>,
+) -> Option<String> {
+    let column_name = match annotation? {
+        AstAnnotationParams::Single(value, _) => value.as_string(),
+        _ => DEFAULT_SOFT_DELETE_COLUMN.to_string(),
+    };
+
+    let error = |message: String| Diagnostic {
+        level: Level::Error,
+        message,
+        code: Some("C000".to_string()),
+        spans: vec![SpanLabel {
+            span,
+            style: SpanStyle::Primary,
+            label: None,
+        }],
+    };
+
+    if column_name.trim().is_empty() {
+        errors.push(error(format!(
+            "@softDelete requires a non-empty column name (type '{type_name}')"
+        )));
+        return None;
+    }
+
+    if let Some(field) = fields.iter().find(|field| field.column_name == column_name) {
+        let is_optional_instant = matches!(
+            &field.typ,
+            FieldType::Optional(inner)
+                if matches!(inner.as_ref(), FieldType::Plain(t) if t.type_name == "Instant")
+        );
+
+        if !is_optional_instant {
+            errors.push(error(format!(
+                "The field '{}' maps to the soft-deletion column '{column_name}', so it must be of type 'Instant?'",
+                field.name
+            )));
+            return None;
+        }
+    }
+
+    Some(column_name)
+}
+
 fn build_type_hint(
     field: &AstField<Typed>,
     types: &MappedArena<Type>,
@@ -756,6 +1103,7 @@ fn build_type_hint(
 
 struct ColumnInfo {
     name: String,
+    renamed_from: Option<String>,
     self_column: bool,
     unique_constraints: Vec<String>,
     indices: Vec<String>,
@@ -777,7 +1125,11 @@ fn compute_column_info(
         let user_supplied_column_name = field
             .annotations
             .get("column")
-            .map(|p| p.as_single().as_string());
+            .and_then(column_annotation_name);
+        let renamed_from = field
+            .annotations
+            .get("column")
+            .and_then(column_annotation_renamed_from);
 
         let compute_column_name = |field_name: &str| {
             user_supplied_column_name
@@ -909,6 +1261,7 @@ fn compute_column_info(
                                     }
                                     Cardinality::One => Ok(ColumnInfo {
                                         name: id_column_name(&matching_field.name),
+                                        renamed_from: None,
                                         self_column: false,
                                         access,
                                         unique_constraints,
@@ -916,6 +1269,7 @@ fn compute_column_info(
                                     }),
                                     Cardinality::Unbounded => Ok(ColumnInfo {
                                         name: id_column_name(&field.name),
+                                        renamed_from: renamed_from.clone(),
                                         self_column: true,
                                         access,
                                         unique_constraints,
@@ -934,6 +1288,7 @@ fn compute_column_info(
 
                                 Ok(ColumnInfo {
                                     name: id_column_name(&field.name),
+                                    renamed_from: renamed_from.clone(),
                                     self_column: true,
                                     access,
                                     unique_constraints,
@@ -954,6 +1309,7 @@ fn compute_column_info(
                             };
                             Ok(ColumnInfo {
                                 name: id_column_name(&matching_field.name),
+                                renamed_from: None,
                                 self_column: false,
                                 access,
                                 unique_constraints,
@@ -983,6 +1339,7 @@ fn compute_column_info(
                             // base type is a primitive, which means this is an Array
                             Ok(ColumnInfo {
                                 name: compute_column_name(&field.name),
+                                renamed_from: renamed_from.clone(),
                                 self_column: true,
                                 access,
                                 unique_constraints,
@@ -1003,6 +1360,7 @@ fn compute_column_info(
                     }
                     _ => Ok(ColumnInfo {
                         name: compute_column_name(&field.name),
+                        renamed_from: renamed_from.clone(),
                         self_column: true,
                         access,
                         unique_constraints,
@@ -1037,10 +1395,9 @@ fn get_matching_field<'a>(
     types: &MappedArena<Type>,
 ) -> Result<&'a AstField<Typed>, Diagnostic> {
     let user_supplied_column_name = field
-        .annotations
         .annotations
         .get("column")
-        .map(|p| p.params.as_single().as_string());
+        .and_then(column_annotation_name);
 
     let matching_fields: Vec<_> = field_type
         .fields
@@ -1048,10 +1405,8 @@ fn get_matching_field<'a>(
         .filter(|f| {
             // If the user supplied a column name, then we look for the corresponding field
             // with the same name. We still need to check if the field is the same type though.
-            let field_column_annotation = f
-                .annotations
-                .get("column")
-                .map(|p| p.as_single().as_string());
+            let field_column_annotation =
+                f.annotations.get("column").and_then(column_annotation_name);
 
             let column_name_matches = user_supplied_column_name == field_column_annotation;
             let field_underlying_type = f.typ.to_typ(types);
@@ -1130,9 +1485,29 @@ fn field_cardinality(field_type: &AstFieldType<Typed>) -> Cardinality {
     }
 }
 
+/// Extract the column name from `@column("name")` or `@column(name: "name")`
+fn column_annotation_name(annotation_params: &AstAnnotationParams<Typed>) -> Option<String> {
+    match annotation_params {
+        AstAnnotationParams::Single(value, _) => Some(value.as_string()),
+        AstAnnotationParams::Map(m, _) => m.get("name").map(|value| value.as_string()),
+        AstAnnotationParams::None => None,
+    }
+}
+
+/// Extract the earlier column name from `@column(renamedFrom: "old_name")`
+fn column_annotation_renamed_from(
+    annotation_params: &AstAnnotationParams<Typed>,
+) -> Option<String> {
+    match annotation_params {
+        AstAnnotationParams::Map(m, _) => m.get("renamedFrom").map(|value| value.as_string()),
+        _ => None,
+    }
+}
+
 struct TableInfo {
     name: String,
     schema: Option<String>,
+    renamed_from: Option<String>,
 }
 
 /// Given parameters for `@table(name=<table-name>, schema=<schema-name>)` extract table and schema name.
@@ -1156,6 +1531,7 @@ fn extract_table_annotation(
             AstAnnotationParams::Single(value, _) => TableInfo {
                 name: value.as_string(),
                 schema: None,
+                renamed_from: None,
             },
             AstAnnotationParams::Map(m, _) => {
                 let name = m
@@ -1163,8 +1539,13 @@ fn extract_table_annotation(
                     .map(|value| value.as_string())
                     .unwrap_or_else(default_table_name);
                 let schema = m.get("schema").cloned().map(|value| value.as_string());
+                let renamed_from = m.get("renamedFrom").map(|value| value.as_string());
 
-                TableInfo { name, schema }
+                TableInfo {
+                    name,
+                    schema,
+                    renamed_from,
+                }
             }
             _ => panic!(),
         },
@@ -1173,6 +1554,7 @@ fn extract_table_annotation(
             TableInfo {
                 name: name.clone(),
                 schema: None,
+                renamed_from: None,
             }
         }
     }
@@ -1521,4 +1903,40 @@ mod tests {
             "non_public_schema"
         );
     }
+
+    #[multiplatform_test]
+    fn cache_scope_with_context_access() {
+        let src = |scope: &str| {
+            format!(
+                r#"
+        context AuthContext {{
+            @jwt("role") role: String
+        }}
+
+        @postgres
+        module ConcertModule {{
+            @cache(maxAge: 60{scope})
+            @access(query=AuthContext.role == "ADMIN" || self.public, mutation=false)
+            type Concert {{
+              @pk id: Int = autoIncrement()
+              public: Boolean
+            }}
+        }}
+        "#
+            )
+        };
+
+        let resolved = create_resolved_system(&src("")).unwrap();
+        let concert = match resolved.get_by_key("Concert") {
+            Some(ResolvedType::Composite(concert)) => concert,
+            _ => panic!("Concert not resolved"),
+        };
+        assert_eq!(
+            concert.cache.map(|cache| cache.scope),
+            Some(core_plugin_interface::core_model::cache::CacheScope::Private)
+        );
+
+        // The response depends on the user, so a shared cache must not store it
+        assert!(create_resolved_system(&src(", scope: PUBLIC")).is_err());
+    }
 }
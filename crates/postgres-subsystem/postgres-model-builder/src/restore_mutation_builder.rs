@@ -0,0 +1,110 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Build the restore mutations (`restore<Type>`, and `restore<Type>s`) for types annotated with
+//! `@softDelete`
+//!
+//! A restore mutation clears the deletion time of soft-deleted entities. Since it is an update
+//! under the hood, it is subject to the type's update access rules.
+
+use core_plugin_interface::{
+    core_model::{
+        access::AccessPredicateExpression,
+        mapped_arena::MappedArena,
+        types::{BaseOperationReturnType, OperationReturnType},
+    },
+    core_model_builder::error::ModelBuildingError,
+};
+use postgres_model::mutation::PostgresMutationParameters;
+use postgres_model::types::EntityType;
+
+use super::{
+    builder::Builder,
+    mutation_builder::MutationBuilder,
+    naming::ToPostgresMutationNames,
+    query_builder,
+    resolved_builder::{ResolvedCompositeType, ResolvedType},
+    system_builder::SystemContextBuilding,
+};
+
+pub struct RestoreMutationBuilder;
+
+impl Builder for RestoreMutationBuilder {
+    fn type_names(
+        &self,
+        _resolved_composite_type: &ResolvedCompositeType,
+        _types: &MappedArena<ResolvedType>,
+    ) -> Vec<String> {
+        // restore mutations don't need any special input type (the type for the PK and the type for filtering suffice)
+        vec![]
+    }
+
+    fn build_expanded(
+        &self,
+        building: &mut SystemContextBuilding,
+    ) -> Result<(), ModelBuildingError> {
+        for (entity_type_id, entity_type) in building.entity_types.iter() {
+            if building
+                .database
+                .get_soft_delete_column_id(entity_type.table_id)
+                .is_none()
+            {
+                continue;
+            }
+            if let AccessPredicateExpression::BooleanLiteral(false) =
+                building.database_access_expressions.borrow()[entity_type.access.update.database]
+            {
+                continue;
+            }
+            for mutation in self.build_mutations(entity_type_id, entity_type, building) {
+                building.mutations.add(&mutation.name.to_owned(), mutation);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MutationBuilder for RestoreMutationBuilder {
+    fn single_mutation_name(entity_type: &EntityType) -> String {
+        entity_type.pk_restore()
+    }
+
+    fn single_mutation_parameters(
+        entity_type: &EntityType,
+        building: &SystemContextBuilding,
+    ) -> PostgresMutationParameters {
+        PostgresMutationParameters::Restore(query_builder::pk_predicate_params(
+            entity_type,
+            &building.predicate_types,
+            &building.database,
+        ))
+    }
+
+    fn single_mutation_modified_type(
+        base_type: BaseOperationReturnType<EntityType>,
+    ) -> OperationReturnType<EntityType> {
+        // We return null if the specified id doesn't exist (or isn't deleted)
+        OperationReturnType::Optional(Box::new(OperationReturnType::Plain(base_type)))
+    }
+
+    fn multi_mutation_name(entity_type: &EntityType) -> String {
+        entity_type.collection_restore()
+    }
+
+    fn multi_mutation_parameters(
+        entity_type: &EntityType,
+        building: &SystemContextBuilding,
+    ) -> PostgresMutationParameters {
+        PostgresMutationParameters::Restore(vec![query_builder::collection_predicate_param(
+            entity_type,
+            &building.predicate_types,
+        )])
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Build subscriptions for types annotated with `@subscription`
+
+use core_plugin_interface::core_model::{
+    mapped_arena::{MappedArena, SerializableSlabIndex},
+    types::{BaseOperationReturnType, OperationReturnType},
+};
+
+use postgres_model::{
+    predicate::PredicateParameter,
+    subscription::{CollectionSubscription, CollectionSubscriptionParameters},
+    types::EntityType,
+};
+
+use crate::{query_builder::collection_predicate_param, shallow::Shallow};
+
+use super::{
+    naming::ToPostgresQueryName,
+    resolved_builder::{ResolvedCompositeType, ResolvedType},
+    system_builder::SystemContextBuilding,
+};
+
+pub fn build_shallow(types: &MappedArena<ResolvedType>, building: &mut SystemContextBuilding) {
+    for (_, typ) in types.iter() {
+        if let ResolvedType::Composite(c) = &typ {
+            if c.subscription {
+                let entity_type_id = building.get_entity_type_id(c.name.as_str()).unwrap();
+                let subscription = shallow_collection_subscription(entity_type_id, c);
+
+                building
+                    .subscriptions
+                    .add(&subscription.name.to_owned(), subscription);
+            }
+        }
+    }
+}
+
+pub fn build_expanded(building: &mut SystemContextBuilding) {
+    for (_, entity_type) in building.entity_types.iter() {
+        let operation_name = entity_type.collection_query();
+
+        if let Some(existing_subscription) = building.subscriptions.get_by_key_mut(&operation_name)
+        {
+            existing_subscription.parameters.predicate_param =
+                collection_predicate_param(entity_type, &building.predicate_types);
+        }
+    }
+}
+
+fn shallow_collection_subscription(
+    entity_type_id: SerializableSlabIndex<EntityType>,
+    resolved_entity_type: &ResolvedCompositeType,
+) -> CollectionSubscription {
+    CollectionSubscription {
+        name: resolved_entity_type.collection_query(),
+        parameters: CollectionSubscriptionParameters {
+            predicate_param: PredicateParameter::shallow(),
+        },
+        // Each event carries a single (inserted or updated) entity
+        return_type: OperationReturnType::Plain(BaseOperationReturnType {
+            associated_type_id: entity_type_id,
+            type_name: resolved_entity_type.name.clone(),
+        }),
+    }
+}
@@ -26,7 +26,8 @@ use postgres_model::{
     mutation::PostgresMutation,
     order::OrderByParameterType,
     predicate::PredicateParameterType,
-    query::{AggregateQuery, CollectionQuery, PkQuery, UniqueQuery},
+    query::{AggregateQuery, CollectionQuery, ConnectionQuery, GroupByQuery, PkQuery, UniqueQuery},
+    subscription::CollectionSubscription,
     subsystem::PostgresSubsystem,
     types::{EntityType, MutationType, PostgresPrimitiveType},
     vector_distance::VectorDistanceType,
@@ -38,7 +39,7 @@ use crate::aggregate_type_builder;
 
 use super::{
     mutation_builder, order_by_type_builder, predicate_builder, query_builder, resolved_builder,
-    type_builder, type_builder::ResolvedTypeEnv,
+    subscription_builder, type_builder, type_builder::ResolvedTypeEnv,
 };
 
 pub fn build(
@@ -69,9 +70,12 @@ pub fn build(
             collection_queries: building.collection_queries,
             aggregate_queries: building.aggregate_queries,
             unique_queries: building.unique_queries,
+            connection_queries: building.connection_queries,
+            group_by_queries: building.group_by_queries,
             database: building.database,
             mutation_types: building.mutation_types.values(),
             mutations: building.mutations,
+            subscriptions: building.subscriptions,
 
             input_access_expressions: building.input_access_expressions.into_inner().elems,
             database_access_expressions: building.database_access_expressions.into_inner().elems,
@@ -102,11 +106,12 @@ fn build_shallow(resolved_env: &ResolvedTypeEnv, building: &mut SystemContextBui
 
     aggregate_type_builder::build_shallow(resolved_env, building);
 
-    // The next two shallow builders need POSTGRES types build above (the order of the next two is unimportant)
-    // Specifically, the OperationReturn type in Query and Mutation looks for the id for the return type, so requires
+    // The next three shallow builders need POSTGRES types build above (the order of the next three is unimportant)
+    // Specifically, the OperationReturn type in Query, Mutation, and Subscription looks for the id for the return type, so requires
     // type_builder::build_shallow to have run
     query_builder::build_shallow(&resolved_env.resolved_types, building);
     mutation_builder::build_shallow(&resolved_env.resolved_types, building);
+    subscription_builder::build_shallow(&resolved_env.resolved_types, building);
 }
 
 fn build_expanded(
@@ -125,6 +130,7 @@ fn build_expanded(
     // Finally expand queries, mutations, and module methods
     query_builder::build_expanded(resolved_env, building);
     mutation_builder::build_expanded(building)?;
+    subscription_builder::build_expanded(building);
 
     Ok(())
 }
@@ -143,10 +149,14 @@ pub struct SystemContextBuilding {
     pub collection_queries: MappedArena<CollectionQuery>,
     pub aggregate_queries: MappedArena<AggregateQuery>,
     pub unique_queries: MappedArena<UniqueQuery>,
+    pub connection_queries: MappedArena<ConnectionQuery>,
+    pub group_by_queries: MappedArena<GroupByQuery>,
 
     pub mutation_types: MappedArena<MutationType>,
     pub mutations: MappedArena<PostgresMutation>,
 
+    pub subscriptions: MappedArena<CollectionSubscription>,
+
     pub input_access_expressions:
         RefCell<AccessExpressionsBuilding<InputAccessPrimitiveExpression>>,
     pub database_access_expressions:
@@ -404,6 +414,194 @@ mod tests {
         assert!(!mutation_type_names.contains("TodoUpdateInput"));
     }
 
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn subscription() {
+        let src = r#"
+        @postgres
+        module TodoModule {
+            @subscription
+            type Todo {
+                @pk id: Int = autoIncrement()
+                title: String
+            }
+
+            type User {
+                @pk id: Int = autoIncrement()
+                name: String
+            }
+        }
+        "#;
+
+        let system = create_system(src).await;
+        assert!(system.subscriptions.get_by_key("todos").is_some());
+        assert!(system.subscriptions.get_by_key("users").is_none());
+
+        assert!(get_table_from_arena("todos", &system.database).notify_changes);
+        assert!(!get_table_from_arena("users", &system.database).notify_changes);
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn soft_delete() {
+        let src = r#"
+        @postgres
+        module TodoModule {
+            @access(true)
+            @softDelete
+            type Todo {
+                @pk id: Int = autoIncrement()
+                title: String
+            }
+
+            @access(true)
+            @softDelete("removed_at")
+            type Note {
+                @pk id: Int = autoIncrement()
+                removedAt: Instant?
+            }
+
+            @access(true)
+            type User {
+                @pk id: Int = autoIncrement()
+                name: String
+            }
+        }
+        "#;
+
+        let system = create_system(src).await;
+        assert!(system.mutations.get_by_key("restoreTodo").is_some());
+        assert!(system.mutations.get_by_key("restoreTodos").is_some());
+        assert!(system.mutations.get_by_key("restoreUser").is_none());
+        assert!(system.mutations.get_by_key("restoreUsers").is_none());
+
+        let todos = get_table_from_arena("todos", &system.database);
+        assert_eq!(todos.soft_delete_column.as_deref(), Some("deleted_at"));
+        assert!(get_column_from_table("deleted_at", todos).is_nullable);
+
+        let notes = get_table_from_arena("notes", &system.database);
+        assert_eq!(notes.soft_delete_column.as_deref(), Some("removed_at"));
+        assert_eq!(
+            notes
+                .columns
+                .iter()
+                .filter(|column| column.name == "removed_at")
+                .count(),
+            1
+        );
+
+        let users = get_table_from_arena("users", &system.database);
+        assert!(users.soft_delete_column.is_none());
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn composite_primary_key() {
+        let src = r#"
+        @postgres
+        module AccountModule {
+            @access(true)
+            type Account {
+                @pk tenantId: Int
+                @pk id: Int
+                name: String
+            }
+
+            @access(true)
+            type User {
+                @pk id: Int = autoIncrement()
+                memberships: Set<Membership>?
+            }
+
+            @access(true)
+            type Group {
+                @pk id: Int = autoIncrement()
+                memberships: Set<Membership>?
+            }
+
+            @access(true)
+            type Membership {
+                @pk user: User
+                @pk group: Group
+                role: String
+            }
+        }
+        "#;
+
+        let system = create_system(src).await;
+
+        let pk_column_names = |table_name| {
+            get_table_from_arena(table_name, &system.database)
+                .get_pk_physical_columns()
+                .into_iter()
+                .map(|column| column.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pk_column_names("accounts"), vec!["tenant_id", "id"]);
+        assert_eq!(pk_column_names("memberships"), vec!["user_id", "group_id"]);
+
+        // Each relation of the join type is over a single column (that is a part of its key)
+        let memberships = get_table_from_arena("memberships", &system.database);
+        let relation_columns: HashSet<_> = system
+            .database
+            .relations
+            .iter()
+            .map(|relation| relation.self_column_id.get_column(&system.database))
+            .map(|column| column.name.as_str())
+            .collect();
+        assert_eq!(relation_columns, HashSet::from(["user_id", "group_id"]));
+        assert!(get_column_from_table("user_id", memberships).is_pk);
+
+        // Operations that identify a single entity take all parts of the key
+        let pk_param_names = |query_name| {
+            system
+                .pk_queries
+                .get_by_key(query_name)
+                .unwrap()
+                .parameters
+                .predicate_params
+                .iter()
+                .map(|param| param.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pk_param_names("account"), vec!["tenantId", "id"]);
+        assert_eq!(pk_param_names("membership"), vec!["user", "group"]);
+
+        for mutation_name in ["updateAccount", "deleteAccount", "upsertAccount"] {
+            assert!(system.mutations.get_by_key(mutation_name).is_some());
+        }
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn relation_to_composite_primary_key() {
+        let src = r#"
+        @postgres
+        module AccountModule {
+            @access(true)
+            type Account {
+                @pk tenantId: Int
+                @pk id: Int
+            }
+
+            @access(true)
+            type Note {
+                @pk id: Int = autoIncrement()
+                account: Account
+            }
+        }
+        "#;
+
+        let system = builder::build_system_from_str(
+            src,
+            "test.exo".to_string(),
+            vec![Box::new(crate::PostgresSubsystemBuilder {})],
+        )
+        .await;
+
+        assert!(system.is_err());
+    }
+
     fn get_mutation_type_names(system: &PostgresSubsystem) -> HashSet<String> {
         system
             .mutation_types
@@ -525,6 +723,76 @@ mod tests {
         };
     }
 
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn check_constraints() {
+        let src = r#"
+            @postgres
+            module ConcertModule {
+                @check("starts_at < ends_at")
+                type Concert {
+                  @pk id: Int = autoIncrement()
+                  @range(min=0, max=300) reserved: Int
+                  @maxLength(12) title: String
+                  @check("price >= 0") price: Int
+                  startsAt: Instant
+                  endsAt: Instant
+                }
+            }
+        "#;
+
+        let system = create_system(src).await;
+        let concerts = get_table_from_arena("concerts", &system.database);
+
+        let checks: Vec<_> = concerts
+            .checks
+            .iter()
+            .map(|check| (check.name.as_str(), check.expr.as_str()))
+            .collect();
+
+        assert_eq!(
+            checks,
+            vec![
+                (
+                    "concerts_reserved_range_check",
+                    r#""reserved" >= 0 AND "reserved" <= 300"#
+                ),
+                (
+                    "concerts_title_max_length_check",
+                    r#"char_length("title") <= 12"#
+                ),
+                ("concerts_price_check", "price >= 0"),
+                ("concerts_check", "starts_at < ends_at"),
+            ]
+        );
+
+        let concert_type = system
+            .entity_types
+            .iter()
+            .find(|(_, t)| t.name == "Concert")
+            .unwrap()
+            .1;
+
+        let descriptions: Vec<_> = concert_type
+            .checks
+            .iter()
+            .map(|check| (check.field_name.as_deref(), check.description.as_str()))
+            .collect();
+
+        assert_eq!(
+            descriptions,
+            vec![
+                (Some("reserved"), "must be between 0 and 300"),
+                (Some("title"), "must be at most 12 characters long"),
+                (
+                    Some("price"),
+                    "violates the constraint `concerts_price_check`"
+                ),
+                (None, "violates the constraint `concerts_check`"),
+            ]
+        );
+    }
+
     fn get_table_from_arena<'a>(name: &'a str, database: &'a Database) -> &'a PhysicalTable {
         for (_, item) in database.tables().iter() {
             if item.name.name == name {
@@ -29,9 +29,9 @@ use core_plugin_interface::{
 };
 
 use exo_sql::{
-    schema::index_spec::IndexKind, ColumnId, FloatBits, IntBits, ManyToOne, PhysicalColumn,
-    PhysicalColumnType, PhysicalIndex, PhysicalTable, TableId, VectorDistanceFunction,
-    DEFAULT_VECTOR_SIZE,
+    schema::index_spec::IndexKind, ColumnId, FloatBits, IntBits, ManyToOne, PhysicalCheck,
+    PhysicalColumn, PhysicalColumnType, PhysicalEnum, PhysicalIndex, PhysicalTable, TableId,
+    VectorDistanceFunction, DEFAULT_VECTOR_SIZE,
 };
 
 use heck::ToSnakeCase;
@@ -43,8 +43,8 @@ use postgres_model::{
     aggregate::{AggregateField, AggregateFieldType},
     relation::{ManyToOneRelation, OneToManyRelation, PostgresRelation, RelationCardinality},
     types::{
-        get_field_id, EntityType, PostgresField, PostgresFieldType, PostgresPrimitiveType,
-        TypeIndex,
+        get_field_id, CheckConstraint, EntityType, PostgresField, PostgresFieldType,
+        PostgresPrimitiveType, TypeIndex,
     },
     vector_distance::{VectorDistanceField, VectorDistanceType},
 };
@@ -82,7 +82,7 @@ pub(super) fn build_expanded(
     // Ensure that all types have a primary key
     for (_, resolved_type) in resolved_env.resolved_types.iter() {
         if let ResolvedType::Composite(c) = &resolved_type {
-            if c.pk_field().is_none() {
+            if c.pk_fields().is_empty() {
                 let diagnostic = Diagnostic {
                     level: Level::Error,
                     message: format!(
@@ -102,12 +102,19 @@ pub(super) fn build_expanded(
         }
     }
 
+    let composite_pk_diagnostics = composite_pk_diagnostics(resolved_env);
+    if !composite_pk_diagnostics.is_empty() {
+        Err(ModelBuildingError::Diagnosis(composite_pk_diagnostics))?;
+    }
+
     for (_, resolved_type) in resolved_env.resolved_types.iter() {
         if let ResolvedType::Composite(c) = &resolved_type {
             expand_type_no_fields(c, resolved_env, building);
         }
     }
 
+    expand_enums(resolved_env, building);
+
     for (_, resolved_type) in resolved_env.resolved_types.iter() {
         if let ResolvedType::Composite(c) = &resolved_type {
             expand_type_relations(c, resolved_env, building);
@@ -152,6 +159,10 @@ fn create_shallow_type(
                 &resolved_type.name(),
                 PostgresPrimitiveType {
                     name: resolved_type.name(),
+                    enum_values: match pt {
+                        PrimitiveType::Enum(_, values) => Some(values.clone()),
+                        _ => None,
+                    },
                 },
             );
             if matches!(pt, PrimitiveType::Vector) {
@@ -173,6 +184,10 @@ fn create_shallow_type(
                 collection_query: SerializableSlabIndex::shallow(),
                 aggregate_query: SerializableSlabIndex::shallow(),
                 access: restrictive_access(),
+                checks: vec![],
+                cache: None,
+                cost: None,
+                rate_limit: None,
             };
 
             building.entity_types.add(&resolved_type.name(), typ);
@@ -199,16 +214,64 @@ fn expand_type_no_fields(
         name: resolved_type.table_name.clone(),
         columns: vec![],
         indices: vec![],
+        checks: vec![],
+        notify_changes: resolved_type.subscription,
+        renamed_from: resolved_type.table_renamed_from.clone(),
+        soft_delete_column: resolved_type.soft_delete_column.clone(),
     };
 
     let table_id = building.database.insert_table(table);
 
     {
-        let columns = resolved_type
+        let mut columns: Vec<_> = resolved_type
             .fields
             .iter()
             .flat_map(|field| create_column(field, table_id, resolved_type, resolved_env))
             .collect();
+
+        // Each `@searchable` field gets a generated `tsvector` column to search against
+        columns.extend(resolved_type.fields.iter().filter_map(|field| {
+            field.searchable.as_ref().map(|language| PhysicalColumn {
+                table_id,
+                name: text_search_column_name(&field.column_name),
+                typ: PhysicalColumnType::TsVector {
+                    language: language.clone(),
+                    source_column: field.column_name.clone(),
+                },
+                is_pk: false,
+                is_auto_increment: false,
+                is_nullable: false,
+                unique_constraints: vec![],
+                default_value: None,
+                update_sync: false,
+                renamed_from: None,
+            })
+        }));
+
+        // A `@softDelete` type gets a column for the deletion time (unless a field already maps to it)
+        if let Some(soft_delete_column) = &resolved_type.soft_delete_column {
+            if !columns
+                .iter()
+                .any(|column| &column.name == soft_delete_column)
+            {
+                columns.push(PhysicalColumn {
+                    table_id,
+                    name: soft_delete_column.clone(),
+                    typ: PhysicalColumnType::Timestamp {
+                        timezone: true,
+                        precision: None,
+                    },
+                    is_pk: false,
+                    is_auto_increment: false,
+                    is_nullable: true,
+                    unique_constraints: vec![],
+                    default_value: None,
+                    update_sync: false,
+                    renamed_from: None,
+                });
+            }
+        }
+
         building.database.get_table_mut(table_id).columns = columns;
     }
 
@@ -245,9 +308,24 @@ fn expand_type_no_fields(
                 }
             })
         });
+
+        indices.extend(resolved_type.fields.iter().filter_map(|field| {
+            field.searchable.as_ref().map(|_| PhysicalIndex {
+                name: format!("{}_{}_search_idx", resolved_type.name, field.name)
+                    .to_ascii_lowercase(),
+                columns: HashSet::from_iter([text_search_column_name(&field.column_name)]),
+                index_kind: IndexKind::Gin,
+            })
+        }));
+
         building.database.get_table_mut(table_id).indices = indices;
     }
 
+    let checks = compute_checks(resolved_type);
+
+    building.database.get_table_mut(table_id).checks =
+        checks.iter().map(|(check, _)| check.clone()).collect();
+
     let pk_query = building
         .pk_queries
         .get_id(&resolved_type.pk_query())
@@ -269,6 +347,124 @@ fn expand_type_no_fields(
     existing_type.pk_query = pk_query;
     existing_type.collection_query = collection_query;
     existing_type.aggregate_query = aggregate_query;
+    existing_type.checks = checks.into_iter().map(|(_, check)| check).collect();
+    existing_type.cache = resolved_type.cache;
+    existing_type.cost = resolved_type.cost;
+    existing_type.rate_limit = resolved_type.rate_limit.clone();
+}
+
+/// Compute the CHECK constraints implied by `@range`, `@maxLength`, and `@check` annotations.
+///
+/// Each constraint is paired with its description, so that a violation may be reported in terms of
+/// the field (or type) that declares it. The description names the constraint rather than quoting
+/// a `@check` condition, since it is reported to clients.
+fn compute_checks(resolved_type: &ResolvedCompositeType) -> Vec<(PhysicalCheck, CheckConstraint)> {
+    let table_prefix = resolved_type.table_name.fully_qualified_name_with_sep("_");
+
+    let field_checks = resolved_type.fields.iter().flat_map(|field| {
+        let column = format!("\"{}\"", field.column_name);
+
+        let hint_check = match &field.type_hint {
+            Some(ResolvedTypeHint::Int {
+                range: Some((min, max)),
+                ..
+            }) => Some((
+                format!("{table_prefix}_{}_range_check", field.column_name),
+                format!("{column} >= {min} AND {column} <= {max}"),
+                format!("must be between {min} and {max}"),
+            )),
+            Some(ResolvedTypeHint::String { max_length }) => Some((
+                format!("{table_prefix}_{}_max_length_check", field.column_name),
+                format!("char_length({column}) <= {max_length}"),
+                format!("must be at most {max_length} characters long"),
+            )),
+            _ => None,
+        };
+
+        let explicit_check = field.check.as_ref().map(|expr| {
+            let name = format!("{table_prefix}_{}_check", field.column_name);
+            let description = format!("violates the constraint `{name}`");
+            (name, expr.clone(), description)
+        });
+
+        hint_check
+            .into_iter()
+            .chain(explicit_check)
+            .map(|(name, expr, description)| {
+                (
+                    PhysicalCheck {
+                        name: name.clone(),
+                        expr,
+                    },
+                    CheckConstraint {
+                        name,
+                        field_name: Some(field.name.clone()),
+                        description,
+                    },
+                )
+            })
+    });
+
+    let type_check = resolved_type.check.as_ref().map(|expr| {
+        let name = format!("{table_prefix}_check");
+        (
+            PhysicalCheck {
+                name: name.clone(),
+                expr: expr.clone(),
+            },
+            CheckConstraint {
+                name,
+                field_name: None,
+                description: format!("violates the constraint `{name}`"),
+            },
+        )
+    });
+
+    field_checks.chain(type_check).collect()
+}
+
+/// The name of the generated `tsvector` column for a `@searchable` field's column
+fn text_search_column_name(column_name: &str) -> String {
+    format!("{column_name}_tsv")
+}
+
+/// Add the enums used by any column to the database.
+///
+/// Enums are declared in modules (possibly not handled by this subsystem), so we include only those
+/// that a table refers to.
+fn expand_enums(resolved_env: &ResolvedTypeEnv, building: &mut SystemContextBuilding) {
+    fn enum_name(typ: &PhysicalColumnType) -> Option<&str> {
+        match typ {
+            PhysicalColumnType::Enum { enum_name } => Some(enum_name),
+            PhysicalColumnType::Array { typ } => enum_name(typ),
+            _ => None,
+        }
+    }
+
+    let used_enum_names: HashSet<String> = building
+        .database
+        .tables()
+        .iter()
+        .flat_map(|(_, table)| table.columns.iter())
+        .flat_map(|column| enum_name(&column.typ).map(|name| name.to_string()))
+        .collect();
+
+    for (_, resolved_type) in resolved_env.resolved_types.iter() {
+        if let ResolvedType::Primitive(PrimitiveType::Enum(name, values)) = resolved_type {
+            let db_name = enum_db_name(name);
+
+            if used_enum_names.contains(&db_name) {
+                building.database.enums.push(PhysicalEnum {
+                    name: db_name,
+                    variants: values.clone(),
+                });
+            }
+        }
+    }
+}
+
+fn enum_db_name(name: &str) -> String {
+    name.to_snake_case()
 }
 
 fn expand_type_relations(
@@ -635,6 +831,7 @@ fn create_persistent_field(
         access,
         has_default_value: field.default_value.is_some(),
         dynamic_default_value: None,
+        cost: field.cost,
         readonly: field.readonly || field.update_sync,
     })
 }
@@ -758,6 +955,7 @@ fn create_column(
 
     let default_value = default_value(field);
     let update_sync = field.update_sync;
+    let renamed_from = field.column_renamed_from.clone();
 
     match typ {
         FieldType::Plain(ResolvedFieldType { type_name, .. }) => {
@@ -783,6 +981,7 @@ fn create_column(
                     unique_constraints: unique_constraint_name,
                     default_value,
                     update_sync,
+                    renamed_from,
                 }),
                 ResolvedType::Composite(_) => {
                     // Many-to-one:
@@ -792,12 +991,13 @@ fn create_column(
                         table_id,
                         name: field.column_name.to_string(),
                         typ: PhysicalColumnType::Boolean, // A placeholder value. Will be resolved in the next phase (see expand_type_relations)
-                        is_pk: false,
+                        is_pk: field.is_pk,
                         is_auto_increment: false,
                         is_nullable: optional,
                         unique_constraints: unique_constraint_name,
                         default_value,
                         update_sync,
+                        renamed_from,
                     })
                 }
             }
@@ -845,6 +1045,7 @@ fn create_column(
                     unique_constraints: unique_constraint_name,
                     default_value,
                     update_sync,
+                    renamed_from,
                 })
             } else {
                 // this is a OneToMany relation, so the other side has the associated column
@@ -855,6 +1056,62 @@ fn create_column(
     }
 }
 
+/// Check the uses of types with a composite primary key (multiple `@pk` fields). Such a type
+/// cannot be referenced through a many-to-one field (its rows can't be identified by a single
+/// foreign key column) and cannot be subscribed to (change notifications carry a single key).
+fn composite_pk_diagnostics(resolved_env: &ResolvedTypeEnv) -> Vec<Diagnostic> {
+    let has_composite_pk = |type_name: &str| match resolved_env.get_by_key(type_name) {
+        Some(ResolvedType::Composite(c)) => c.pk_fields().len() > 1,
+        _ => false,
+    };
+
+    let error = |message: String, span| Diagnostic {
+        level: Level::Error,
+        message,
+        code: Some("C000".to_string()),
+        spans: vec![SpanLabel {
+            span,
+            style: SpanStyle::Primary,
+            label: None,
+        }],
+    };
+
+    let mut diagnostics = vec![];
+
+    for (_, resolved_type) in resolved_env.resolved_types.iter() {
+        if let ResolvedType::Composite(c) = &resolved_type {
+            if c.subscription && has_composite_pk(&c.name) {
+                diagnostics.push(error(
+                    format!(
+                        "Type '{}' has a composite primary key and cannot be annotated with @subscription",
+                        c.name
+                    ),
+                    c.span,
+                ));
+            }
+
+            for field in c.fields.iter() {
+                let field_type_name = &field.typ.innermost().type_name;
+
+                if field.self_column
+                    && !field.typ.innermost().is_primitive
+                    && has_composite_pk(field_type_name)
+                {
+                    diagnostics.push(error(
+                        format!(
+                            "Field '{}' of '{}' refers to '{}', which has a composite primary key. Relations to types with a composite primary key are not supported (declare fields for the parts of the key instead)",
+                            field.name, c.name, field_type_name
+                        ),
+                        field.span,
+                    ));
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
 fn compute_many_to_one_relation(
     field: &ResolvedField,
     self_column_id: ColumnId,
@@ -873,11 +1130,13 @@ fn compute_many_to_one_relation(
                     // Column from the current table (but of the type of the pk column of the other table)
                     // and it refers to the pk column in the other table.
 
+                    // Types with a composite primary key cannot be referenced (see `build_expanded`),
+                    // so the foreign table has exactly one pk column.
                     let foreign_table_id = building.database.get_table_id(&ct.table_name).unwrap();
-                    let foreign_pk_column_id = building
+                    let foreign_pk_column_id = *building
                         .database
-                        .get_pk_column_id(foreign_table_id)
-                        .unwrap();
+                        .get_pk_column_ids(foreign_table_id)
+                        .first()?;
 
                     let field_alias = field.name.to_snake_case().to_plural();
 
@@ -885,6 +1144,7 @@ fn compute_many_to_one_relation(
                         self_column_id,
                         foreign_pk_column_id,
                         foreign_table_alias: Some(field_alias),
+                        on_delete: field.on_delete.unwrap_or_default(),
                     })
                 }
                 _ => None,
@@ -1047,6 +1307,9 @@ fn determine_column_type<'a>(
             PrimitiveType::Vector => PhysicalColumnType::Vector {
                 size: DEFAULT_VECTOR_SIZE,
             },
+            PrimitiveType::Enum(name, _) => PhysicalColumnType::Enum {
+                enum_name: enum_db_name(name),
+            },
             PrimitiveType::Array(_)
             | PrimitiveType::Exograph
             | PrimitiveType::ExographPriv
@@ -1077,7 +1340,8 @@ fn create_relation(
     let self_type = &building.entity_types[type_id];
     let self_table_id = &self_type.table_id;
 
-    if field.is_pk {
+    // A many-to-one field that is a part of the primary key is handled below (as a relation)
+    if field.is_pk && field.typ.innermost().is_primitive {
         let column_id = building
             .database
             .get_column_id(*self_table_id, &field.column_name)
@@ -1262,6 +1526,7 @@ fn compute_one_to_many_relation(
         cardinality,
         foreign_pk_field_id,
         relation_id,
+        is_pk: field.is_pk,
     })
 }
 
@@ -99,7 +99,7 @@ impl MutationBuilder for UpdateMutationBuilder {
     ) -> PostgresMutationParameters {
         PostgresMutationParameters::Update {
             data_param: Self::data_param(entity_type, building, false),
-            predicate_param: query_builder::pk_predicate_param(
+            predicate_params: query_builder::pk_predicate_params(
                 entity_type,
                 &building.predicate_types,
                 &building.database,
@@ -124,10 +124,10 @@ impl MutationBuilder for UpdateMutationBuilder {
     ) -> PostgresMutationParameters {
         PostgresMutationParameters::Update {
             data_param: Self::data_param(entity_type, building, true),
-            predicate_param: query_builder::collection_predicate_param(
+            predicate_params: vec![query_builder::collection_predicate_param(
                 entity_type,
                 &building.predicate_types,
-            ),
+            )],
         }
     }
 }
@@ -241,6 +241,7 @@ impl DataParamBuilder<DataParameter> for UpdateMutationBuilder {
                         relation: field.relation.clone(),
                         has_default_value: field.has_default_value,
                         dynamic_default_value: None,
+                        cost: None,
                         readonly: field.readonly,
                     }
                 })
@@ -277,18 +278,22 @@ impl DataParamBuilder<DataParameter> for UpdateMutationBuilder {
                         let base_type = tpe.1.clone();
                         let mut base_type_fields = base_type.fields;
 
-                        let base_type_pk_field = base_type_fields
+                        let base_type_pk_fields: Vec<_> = base_type_fields
                             .iter_mut()
-                            .find(|f| matches!(f.relation, PostgresRelation::Pk { .. }));
+                            .filter(|f| matches!(f.relation, PostgresRelation::Pk { .. }))
+                            .collect();
 
-                        // For a non-nested type ("base type"), we already have the PK field, but it is optional. So here
-                        // we make it required (by not wrapping the entity_pk_field it as optional)
-                        if let Some(base_type_pk_field) = base_type_pk_field {
-                            let entity_pk_field = entity_type.pk_field().unwrap();
-                            base_type_pk_field.typ = to_mutation_type(&entity_pk_field.typ);
-                        } else {
+                        if base_type_pk_fields.is_empty() {
                             panic!("Expected a PK field in the base type")
-                        };
+                        }
+
+                        // For a non-nested type ("base type"), we already have the PK fields, but they are optional. So here
+                        // we make them required (by not wrapping the entity pk field types as optional)
+                        for base_type_pk_field in base_type_pk_fields {
+                            let entity_pk_field =
+                                entity_type.field_by_name(&base_type_pk_field.name).unwrap();
+                            base_type_pk_field.typ = to_mutation_type(&entity_pk_field.typ);
+                        }
 
                         let type_with_id = MutationType {
                             name: nested_existing_type_name,
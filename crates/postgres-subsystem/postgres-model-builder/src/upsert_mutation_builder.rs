@@ -0,0 +1,187 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Build the upsert mutations (`upsert<Type>`, and `upsert<Type>s`)
+//!
+//! An upsert mutation reuses the creation input type (`<Type>CreationInput`) for its data and
+//! takes an `onConflict` argument to pick the primary key or the unique constraint to detect a
+//! conflict on.
+
+use core_plugin_interface::{
+    core_model::{
+        access::AccessPredicateExpression,
+        mapped_arena::MappedArena,
+        types::{BaseOperationReturnType, OperationReturnType},
+    },
+    core_model_builder::error::ModelBuildingError,
+};
+use heck::ToLowerCamelCase;
+use postgres_model::{
+    mutation::{ConflictTarget, OnConflictParameter, PostgresMutationParameters},
+    types::EntityType,
+};
+
+use super::{
+    builder::Builder,
+    create_mutation_builder::CreateMutationBuilder,
+    mutation_builder::{DataParamBuilder, MutationBuilder},
+    naming::{ToPostgresMutationNames, ToPostgresTypeNames},
+    resolved_builder::{ResolvedCompositeType, ResolvedType},
+    system_builder::SystemContextBuilding,
+};
+
+pub struct UpsertMutationBuilder;
+
+impl Builder for UpsertMutationBuilder {
+    fn type_names(
+        &self,
+        _resolved_composite_type: &ResolvedCompositeType,
+        _types: &MappedArena<ResolvedType>,
+    ) -> Vec<String> {
+        // upsert mutations reuse the creation input types
+        vec![]
+    }
+
+    fn build_expanded(
+        &self,
+        building: &mut SystemContextBuilding,
+    ) -> Result<(), ModelBuildingError> {
+        // An upsert may either create or update, so we need both to be possible
+        let upsert_access_is_false = |entity_type: &EntityType| -> bool {
+            matches!(
+                building.input_access_expressions.borrow()[entity_type.access.creation],
+                AccessPredicateExpression::BooleanLiteral(false)
+            ) || matches!(
+                building.input_access_expressions.borrow()[entity_type.access.update.input],
+                AccessPredicateExpression::BooleanLiteral(false)
+            ) || matches!(
+                building.database_access_expressions.borrow()[entity_type.access.update.database],
+                AccessPredicateExpression::BooleanLiteral(false)
+            )
+        };
+
+        for (entity_type_id, entity_type) in building.entity_types.iter() {
+            if upsert_access_is_false(entity_type)
+                || conflict_targets(entity_type, building).is_empty()
+            {
+                continue;
+            }
+
+            for mutation in self.build_mutations(entity_type_id, entity_type, building) {
+                building.mutations.add(&mutation.name.to_owned(), mutation);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MutationBuilder for UpsertMutationBuilder {
+    fn single_mutation_name(entity_type: &EntityType) -> String {
+        entity_type.pk_upsert()
+    }
+
+    fn single_mutation_parameters(
+        entity_type: &EntityType,
+        building: &SystemContextBuilding,
+    ) -> PostgresMutationParameters {
+        PostgresMutationParameters::Upsert {
+            data_param: CreateMutationBuilder::data_param(entity_type, building, false),
+            on_conflict_param: on_conflict_param(entity_type, building),
+        }
+    }
+
+    fn single_mutation_modified_type(
+        base_type: BaseOperationReturnType<EntityType>,
+    ) -> OperationReturnType<EntityType> {
+        // We return null if the conflicting row may not be updated
+        OperationReturnType::Optional(Box::new(OperationReturnType::Plain(base_type)))
+    }
+
+    fn multi_mutation_name(entity_type: &EntityType) -> String {
+        entity_type.collection_upsert()
+    }
+
+    fn multi_mutation_parameters(
+        entity_type: &EntityType,
+        building: &SystemContextBuilding,
+    ) -> PostgresMutationParameters {
+        PostgresMutationParameters::Upsert {
+            data_param: CreateMutationBuilder::data_param(entity_type, building, true),
+            on_conflict_param: on_conflict_param(entity_type, building),
+        }
+    }
+}
+
+fn on_conflict_param(
+    entity_type: &EntityType,
+    building: &SystemContextBuilding,
+) -> OnConflictParameter {
+    OnConflictParameter {
+        name: "onConflict".to_string(),
+        type_name: entity_type.on_conflict_type(),
+        constraint_type_name: entity_type.upsert_constraint_type(),
+        targets: conflict_targets(entity_type, building),
+    }
+}
+
+/// The name of the conflict target for a composite primary key
+const COMPOSITE_PK_CONFLICT_TARGET: &str = "primaryKey";
+
+/// Compute the primary key and unique constraints a conflict may be detected on.
+///
+/// We skip an auto-incremented primary key, since the creation input doesn't accept a value for it
+/// (and thus an insert will never conflict on it). A composite primary key is exposed as
+/// `primaryKey`.
+fn conflict_targets(
+    entity_type: &EntityType,
+    building: &SystemContextBuilding,
+) -> Vec<ConflictTarget> {
+    let database = &building.database;
+
+    let pk_column_ids = database.get_pk_column_ids(entity_type.table_id);
+    let pk_name = match entity_type.pk_fields()[..] {
+        [pk_field] => pk_field.name.clone(),
+        _ => COMPOSITE_PK_CONFLICT_TARGET.to_string(),
+    };
+
+    let pk_target = (!pk_column_ids.is_empty()
+        && pk_column_ids
+            .iter()
+            .all(|column_id| !column_id.get_column(database).is_auto_increment))
+    .then(|| ConflictTarget {
+        name: pk_name.clone(),
+        column_ids: pk_column_ids,
+    });
+
+    let mut unique_targets: Vec<ConflictTarget> = vec![];
+
+    for column_id in database.get_column_ids(entity_type.table_id) {
+        for constraint_name in column_id.get_column(database).unique_constraints.iter() {
+            let name = constraint_name.to_lower_camel_case();
+
+            match unique_targets.iter_mut().find(|target| target.name == name) {
+                Some(target) => target.column_ids.push(column_id),
+                None => unique_targets.push(ConflictTarget {
+                    name,
+                    column_ids: vec![column_id],
+                }),
+            }
+        }
+    }
+
+    pk_target
+        .into_iter()
+        .chain(
+            unique_targets
+                .into_iter()
+                .filter(|target| target.name != pk_name),
+        )
+        .collect()
+}
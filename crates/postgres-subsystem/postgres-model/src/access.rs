@@ -36,7 +36,7 @@ pub struct UpdateAccessExpression {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum DatabaseAccessPrimitiveExpression {
     Column(PhysicalColumnPath, Option<String>), // Column path, for example self.user.id and parameter name (such as "du", default: "self")
-    Function(PhysicalColumnPath, FunctionCall<Self>), // Function, for example self.documentUser.some(du => du.id == AuthContext.id && du.read)
+    Function(PhysicalColumnPath, FunctionCall<Self>), // Function (`count` after building the model), for example self.drafts.count(d => d.open)
     Common(CommonAccessPrimitiveExpression),          // expression shared by all access expressions
 }
 
@@ -44,6 +44,6 @@ pub enum DatabaseAccessPrimitiveExpression {
 #[derive(Serialize, Deserialize, Debug)]
 pub enum InputAccessPrimitiveExpression {
     Path(Vec<String>, Option<String>), // JSON path, for example self.user.id and parameter name (such as "du", default: "self")
-    Function(Vec<String>, FunctionCall<Self>), // Function, for example self.documentUser.some(du => du.id == AuthContext.id && du.read)
+    Function(Vec<String>, FunctionCall<Self>), // Function (`count` after building the model), for example self.drafts.count(d => d.open)
     Common(CommonAccessPrimitiveExpression),   // expression shared by all access expressions
 }
@@ -0,0 +1,166 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Types to support Relay-style cursor pagination.
+//!
+//! For each entity type such as `Todo`, we generate a `todosConnection` query that returns a
+//! `TodoConnection`:
+//! ```graphql
+//! type TodoConnection {
+//!   edges: [TodoEdge!]!
+//!   pageInfo: PageInfo!
+//! }
+//!
+//! type TodoEdge {
+//!   cursor: String!
+//!   node: Todo!
+//! }
+//!
+//! type PageInfo {
+//!   hasNextPage: Boolean!
+//!   hasPreviousPage: Boolean!
+//!   startCursor: String
+//!   endCursor: String
+//! }
+//! ```
+
+use async_graphql_parser::types::{
+    BaseType, FieldDefinition, ObjectType, Type, TypeDefinition, TypeKind,
+};
+use async_graphql_value::Name;
+use core_plugin_interface::core_model::{
+    mapped_arena::SerializableSlabIndex,
+    type_normalization::{default_positioned, default_positioned_name, Parameter},
+    types::{FieldType, Named},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::types::PostgresPrimitiveType;
+
+pub const PAGE_INFO_TYPE_NAME: &str = "PageInfo";
+
+pub const EDGES_FIELD: &str = "edges";
+pub const PAGE_INFO_FIELD: &str = "pageInfo";
+pub const CURSOR_FIELD: &str = "cursor";
+pub const NODE_FIELD: &str = "node";
+
+pub const HAS_NEXT_PAGE_FIELD: &str = "hasNextPage";
+pub const HAS_PREVIOUS_PAGE_FIELD: &str = "hasPreviousPage";
+pub const START_CURSOR_FIELD: &str = "startCursor";
+pub const END_CURSOR_FIELD: &str = "endCursor";
+
+/// The connection type name for an entity (such as `TodoConnection` for `Todo`)
+pub fn connection_type_name(entity_type_name: &str) -> String {
+    format!("{entity_type_name}Connection")
+}
+
+/// The edge type name for an entity (such as `TodoEdge` for `Todo`)
+pub fn edge_type_name(entity_type_name: &str) -> String {
+    format!("{entity_type_name}Edge")
+}
+
+/// Cursor parameter such as `after: "..."` in `todosConnection(first: 10, after: "...")`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CursorParameter {
+    pub name: String,
+    pub typ: FieldType<CursorParameterType>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CursorParameterType {
+    pub type_name: String,
+    pub type_id: SerializableSlabIndex<PostgresPrimitiveType>,
+}
+
+impl Named for CursorParameterType {
+    fn name(&self) -> &str {
+        &self.type_name
+    }
+}
+
+impl Parameter for CursorParameter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        (&self.typ).into()
+    }
+}
+
+/// Type definitions for the connection and edge types of an entity
+pub fn connection_type_definitions(entity_type_name: &str) -> Vec<TypeDefinition> {
+    let edge_type_name = edge_type_name(entity_type_name);
+
+    vec![
+        object_type_definition(
+            &connection_type_name(entity_type_name),
+            vec![
+                field_definition(
+                    EDGES_FIELD,
+                    Type {
+                        base: BaseType::List(Box::new(named_type(&edge_type_name, false))),
+                        nullable: false,
+                    },
+                ),
+                field_definition(PAGE_INFO_FIELD, named_type(PAGE_INFO_TYPE_NAME, false)),
+            ],
+        ),
+        object_type_definition(
+            &edge_type_name,
+            vec![
+                field_definition(CURSOR_FIELD, named_type("String", false)),
+                field_definition(NODE_FIELD, named_type(entity_type_name, false)),
+            ],
+        ),
+    ]
+}
+
+/// Type definition for `PageInfo` (shared by all connection types)
+pub fn page_info_type_definition() -> TypeDefinition {
+    object_type_definition(
+        PAGE_INFO_TYPE_NAME,
+        vec![
+            field_definition(HAS_NEXT_PAGE_FIELD, named_type("Boolean", false)),
+            field_definition(HAS_PREVIOUS_PAGE_FIELD, named_type("Boolean", false)),
+            field_definition(START_CURSOR_FIELD, named_type("String", true)),
+            field_definition(END_CURSOR_FIELD, named_type("String", true)),
+        ],
+    )
+}
+
+fn object_type_definition(name: &str, fields: Vec<FieldDefinition>) -> TypeDefinition {
+    TypeDefinition {
+        extend: false,
+        description: None,
+        name: default_positioned_name(name),
+        directives: vec![],
+        kind: TypeKind::Object(ObjectType {
+            implements: vec![],
+            fields: fields.into_iter().map(default_positioned).collect(),
+        }),
+    }
+}
+
+fn field_definition(name: &str, ty: Type) -> FieldDefinition {
+    FieldDefinition {
+        description: None,
+        name: default_positioned_name(name),
+        arguments: vec![],
+        ty: default_positioned(ty),
+        directives: vec![],
+    }
+}
+
+fn named_type(name: &str, nullable: bool) -> Type {
+    Type {
+        base: BaseType::Named(Name::new(name)),
+        nullable,
+    }
+}
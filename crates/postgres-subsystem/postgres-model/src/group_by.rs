@@ -0,0 +1,387 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Types to support group-by queries.
+//!
+//! For each entity type such as `Todo`, we generate a `todosGroupBy` query that returns a
+//! `TodoGroup` for each distinct combination of values of the fields in its `by` argument:
+//! ```graphql
+//! type TodoGroup {
+//!   key: TodoGroupKey!
+//!   agg: TodoAgg!
+//! }
+//!
+//! type TodoGroupKey {
+//!   completed: Boolean
+//!   userId: Int
+//! }
+//!
+//! enum TodoGroupByField {
+//!   completed
+//!   userId
+//! }
+//! ```
+//!
+//! The `having` argument filters groups based on their aggregates (`TodoAggFilter` such as `{id:
+//! {count: {gt: 2}}}`) and the `orderBy` argument orders groups by their key or aggregates
+//! (`TodoGroupOrdering` such as `{agg: {id: {count: DESC}}}`).
+
+use async_graphql_parser::types::{
+    BaseType, EnumType, EnumValueDefinition, FieldDefinition, InputObjectType,
+    InputValueDefinition, ObjectType, Type, TypeDefinition, TypeKind,
+};
+use async_graphql_value::Name;
+use core_plugin_interface::core_model::type_normalization::{
+    default_positioned, default_positioned_name, Parameter,
+};
+use exo_sql::ColumnId;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregate::ScalarAggregateFieldKind;
+
+pub const KEY_FIELD: &str = "key";
+pub const AGG_FIELD: &str = "agg";
+
+/// The group type name for an entity (such as `TodoGroup` for `Todo`)
+pub fn group_type_name(entity_type_name: &str) -> String {
+    format!("{entity_type_name}Group")
+}
+
+/// The group key type name for an entity (such as `TodoGroupKey` for `Todo`)
+pub fn group_key_type_name(entity_type_name: &str) -> String {
+    format!("{entity_type_name}GroupKey")
+}
+
+/// The `by` parameter such as `by: [completed, userId]` in `todosGroupBy(by: [completed, userId])`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupByParameter {
+    /// Name of the parameter (typically `by`)
+    pub name: String,
+    /// The name of the enum listing the fields such as `TodoGroupByField`
+    pub type_name: String,
+    /// The fields that rows may be grouped by
+    pub fields: Vec<GroupKeyField>,
+}
+
+/// A field that rows may be grouped by
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupKeyField {
+    /// The name exposed as the enum value and the field of the key type (such as `completed` or `userId`)
+    pub name: String,
+    /// The name of the entity field this key stems from (such as `completed` or `user`)
+    pub field_name: String,
+    /// The name of the key's type such as `Boolean` or `Int`
+    pub type_name: String,
+    /// The column holding the key
+    pub column_id: ColumnId,
+}
+
+/// An aggregated field that groups may be filtered or ordered by
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupAggregateField {
+    /// The name of the field such as `id`
+    pub name: String,
+    /// The name of the aggregate type such as `IntAgg`
+    pub type_name: String,
+    /// The column being aggregated
+    pub column_id: ColumnId,
+    /// The aggregates along with the name of their return type such as `(Avg, "Float")`
+    pub aggregates: Vec<(ScalarAggregateFieldKind, String)>,
+}
+
+/// The `having` parameter such as `having: {id: {count: {gt: 2}}}`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupHavingParameter {
+    /// Name of the parameter (typically `having`)
+    pub name: String,
+    /// The name of the input type such as `TodoAggFilter`
+    pub type_name: String,
+    /// The fields that groups may be filtered by
+    pub fields: Vec<GroupAggregateField>,
+}
+
+/// The `orderBy` parameter such as `orderBy: [{agg: {id: {count: DESC}}}]`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupOrderByParameter {
+    /// Name of the parameter (typically `orderBy`)
+    pub name: String,
+    /// The name of the input type such as `TodoGroupOrdering`
+    pub type_name: String,
+    /// The name of the input type for ordering by the key such as `TodoGroupKeyOrdering`
+    pub key_type_name: String,
+    /// The name of the input type for ordering by the aggregates such as `TodoAggOrdering`
+    pub agg_type_name: String,
+}
+
+impl GroupByParameter {
+    pub fn field(&self, name: &str) -> Option<&GroupKeyField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+impl GroupHavingParameter {
+    pub fn field(&self, name: &str) -> Option<&GroupAggregateField> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+impl Parameter for GroupByParameter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        list_type(&self.type_name, false)
+    }
+}
+
+impl Parameter for GroupHavingParameter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        named_type(&self.type_name, true)
+    }
+}
+
+impl Parameter for GroupOrderByParameter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        list_type(&self.type_name, true)
+    }
+}
+
+/// Filter type name for an aggregate type (such as `IntAggFilter` for `IntAgg`)
+pub fn aggregate_filter_type_name(aggregate_type_name: &str) -> String {
+    format!("{aggregate_type_name}Filter")
+}
+
+/// Ordering type name for an aggregate type (such as `IntAggOrdering` for `IntAgg`)
+pub fn aggregate_ordering_type_name(aggregate_type_name: &str) -> String {
+    format!("{aggregate_type_name}Ordering")
+}
+
+/// Type definitions specific to an entity's group-by query (`TodoGroup`, `TodoGroupKey`,
+/// `TodoGroupByField`, `TodoAggFilter`, `TodoGroupOrdering`, `TodoGroupKeyOrdering`, and
+/// `TodoAggOrdering`)
+pub fn group_by_type_definitions(
+    entity_type_name: &str,
+    aggregate_type_name: &str,
+    by_param: &GroupByParameter,
+    having_param: &GroupHavingParameter,
+    order_by_param: &GroupOrderByParameter,
+) -> Vec<TypeDefinition> {
+    let group_type = object_type_definition(
+        &group_type_name(entity_type_name),
+        vec![
+            field_definition(
+                KEY_FIELD,
+                named_type(&group_key_type_name(entity_type_name), false),
+            ),
+            field_definition(AGG_FIELD, named_type(aggregate_type_name, false)),
+        ],
+    );
+
+    let key_type = object_type_definition(
+        &group_key_type_name(entity_type_name),
+        by_param
+            .fields
+            .iter()
+            .map(|field| field_definition(&field.name, named_type(&field.type_name, true)))
+            .collect(),
+    );
+
+    let by_type = TypeDefinition {
+        extend: false,
+        description: None,
+        name: default_positioned_name(&by_param.type_name),
+        directives: vec![],
+        kind: TypeKind::Enum(EnumType {
+            values: by_param
+                .fields
+                .iter()
+                .map(|field| {
+                    default_positioned(EnumValueDefinition {
+                        description: None,
+                        value: default_positioned_name(&field.name),
+                        directives: vec![],
+                    })
+                })
+                .collect(),
+        }),
+    };
+
+    let having_type =
+        input_object_type_definition(
+            &having_param.type_name,
+            having_param
+                .fields
+                .iter()
+                .map(|field| {
+                    input_value_definition(
+                        &field.name,
+                        named_type(&aggregate_filter_type_name(&field.type_name), true),
+                    )
+                })
+                .chain(["and", "or"].into_iter().map(|name| {
+                    input_value_definition(name, list_type(&having_param.type_name, true))
+                }))
+                .chain(std::iter::once(input_value_definition(
+                    "not",
+                    named_type(&having_param.type_name, true),
+                )))
+                .collect(),
+        );
+
+    let ordering_type = input_object_type_definition(
+        &order_by_param.type_name,
+        vec![
+            input_value_definition(KEY_FIELD, named_type(&order_by_param.key_type_name, true)),
+            input_value_definition(AGG_FIELD, named_type(&order_by_param.agg_type_name, true)),
+        ],
+    );
+
+    let key_ordering_type = input_object_type_definition(
+        &order_by_param.key_type_name,
+        by_param
+            .fields
+            .iter()
+            .map(|field| input_value_definition(&field.name, named_type("Ordering", true)))
+            .collect(),
+    );
+
+    let agg_ordering_type = input_object_type_definition(
+        &order_by_param.agg_type_name,
+        having_param
+            .fields
+            .iter()
+            .map(|field| {
+                input_value_definition(
+                    &field.name,
+                    named_type(&aggregate_ordering_type_name(&field.type_name), true),
+                )
+            })
+            .collect(),
+    );
+
+    vec![
+        group_type,
+        key_type,
+        by_type,
+        having_type,
+        ordering_type,
+        key_ordering_type,
+        agg_ordering_type,
+    ]
+}
+
+/// Type definitions for filtering and ordering by the aggregates of a field such as
+/// ```graphql
+/// input IntAggFilter {
+///   min: IntFilter
+///   avg: FloatFilter
+///   count: IntFilter
+/// }
+///
+/// input IntAggOrdering {
+///   min: Ordering
+///   avg: Ordering
+///   count: Ordering
+/// }
+/// ```
+/// These are shared by all fields with the same aggregate type.
+pub fn aggregate_field_type_definitions(field: &GroupAggregateField) -> Vec<TypeDefinition> {
+    let filter_type = input_object_type_definition(
+        &aggregate_filter_type_name(&field.type_name),
+        field
+            .aggregates
+            .iter()
+            .map(|(kind, return_type_name)| {
+                input_value_definition(
+                    kind.name(),
+                    named_type(&format!("{return_type_name}Filter"), true),
+                )
+            })
+            .collect(),
+    );
+
+    let ordering_type = input_object_type_definition(
+        &aggregate_ordering_type_name(&field.type_name),
+        field
+            .aggregates
+            .iter()
+            .map(|(kind, _)| input_value_definition(kind.name(), named_type("Ordering", true)))
+            .collect(),
+    );
+
+    vec![filter_type, ordering_type]
+}
+
+fn object_type_definition(name: &str, fields: Vec<FieldDefinition>) -> TypeDefinition {
+    TypeDefinition {
+        extend: false,
+        description: None,
+        name: default_positioned_name(name),
+        directives: vec![],
+        kind: TypeKind::Object(ObjectType {
+            implements: vec![],
+            fields: fields.into_iter().map(default_positioned).collect(),
+        }),
+    }
+}
+
+fn input_object_type_definition(name: &str, fields: Vec<InputValueDefinition>) -> TypeDefinition {
+    TypeDefinition {
+        extend: false,
+        description: None,
+        name: default_positioned_name(name),
+        directives: vec![],
+        kind: TypeKind::InputObject(InputObjectType {
+            fields: fields.into_iter().map(default_positioned).collect(),
+        }),
+    }
+}
+
+fn field_definition(name: &str, ty: Type) -> FieldDefinition {
+    FieldDefinition {
+        description: None,
+        name: default_positioned_name(name),
+        arguments: vec![],
+        ty: default_positioned(ty),
+        directives: vec![],
+    }
+}
+
+fn input_value_definition(name: &str, ty: Type) -> InputValueDefinition {
+    InputValueDefinition {
+        description: None,
+        name: default_positioned_name(name),
+        ty: default_positioned(ty),
+        default_value: None,
+        directives: vec![],
+    }
+}
+
+fn named_type(name: &str, nullable: bool) -> Type {
+    Type {
+        base: BaseType::Named(Name::new(name)),
+        nullable,
+    }
+}
+
+fn list_type(element_type_name: &str, nullable: bool) -> Type {
+    Type {
+        base: BaseType::List(Box::new(named_type(element_type_name, false))),
+        nullable,
+    }
+}
@@ -9,6 +9,8 @@
 
 pub mod access;
 pub mod aggregate;
+pub mod connection;
+pub mod group_by;
 pub mod limit_offset;
 pub mod migration;
 pub mod mutation;
@@ -17,6 +19,9 @@ pub mod order;
 pub mod predicate;
 pub mod query;
 pub mod relation;
+pub mod row_level_security;
+pub mod soft_delete;
+pub mod subscription;
 pub mod subsystem;
 pub mod types;
 pub mod vector_distance;
@@ -9,10 +9,13 @@
 
 use std::fmt::Display;
 
-use super::subsystem::PostgresSubsystem;
+use super::{row_level_security, subsystem::PostgresSubsystem};
 use exo_sql::{
     database_error::DatabaseError,
-    schema::{database_spec::DatabaseSpec, issue::WithIssues, op::SchemaOp, spec::diff},
+    schema::{
+        column_spec::ColumnTypeSpec, database_spec::DatabaseSpec, issue::WithIssues, op::SchemaOp,
+        spec::diff,
+    },
     DatabaseClientManager,
 };
 use serde::Serialize;
@@ -66,18 +69,37 @@ impl Migration {
             let is_destructive = match diff {
                 SchemaOp::DeleteSchema { .. }
                 | SchemaOp::DeleteTable { .. }
-                | SchemaOp::DeleteColumn { .. }
-                | SchemaOp::RemoveExtension { .. } => true,
+                | SchemaOp::RemoveExtension { .. }
+                | SchemaOp::RecreateEnum { .. } => true, // Fails if a removed value is still in use
+
+                // Generated columns can always be recomputed from their source columns
+                SchemaOp::DeleteColumn { column, .. } => {
+                    !matches!(column.typ, ColumnTypeSpec::TsVector { .. })
+                }
+
+                // Narrowing conversions may fail or lose data
+                SchemaOp::AlterColumnType { old_column, column, .. } => {
+                    old_column.typ.alter_type_safety(&column.typ) != Some(true)
+                }
 
                 // Explicitly matching the other cases here to ensure that we have thought about each case
                 SchemaOp::CreateSchema { .. }
+                | SchemaOp::CreateEnum { .. }
+                | SchemaOp::DeleteEnum { .. } // Only unused enums are deleted (columns using them are deleted by other operations)
+                | SchemaOp::AddEnumValue { .. }
                 | SchemaOp::CreateTable { .. }
+                | SchemaOp::RenameTable { .. }
                 | SchemaOp::CreateColumn { .. }
+                | SchemaOp::RenameColumn { .. }
                 | SchemaOp::CreateIndex { .. }
                 | SchemaOp::DeleteIndex { .. } // Creating and deleting index is not considered destructive (they affect performance but not data loss)
                 | SchemaOp::CreateExtension { .. }
                 | SchemaOp::CreateUniqueConstraint { .. }
                 | SchemaOp::RemoveUniqueConstraint { .. }
+                | SchemaOp::CreateForeignKey { .. } // Fails if an existing row refers to a missing row
+                | SchemaOp::DeleteForeignKey { .. }
+                | SchemaOp::CreateCheckConstraint { .. } // Fails if an existing row violates the check
+                | SchemaOp::DeleteCheckConstraint { .. }
                 | SchemaOp::SetColumnDefaultValue { .. }
                 | SchemaOp::UnsetColumnDefaultValue { .. }
                 | SchemaOp::SetNotNull { .. }
@@ -95,7 +117,10 @@ impl Migration {
                 pre_statements.push(MigrationStatement::new(constraint, is_destructive));
             }
 
-            statements.push(MigrationStatement::new(statement.statement, is_destructive));
+            // Some operations (such as adding a foreign key) consist only of post-statements
+            if !statement.statement.is_empty() {
+                statements.push(MigrationStatement::new(statement.statement, is_destructive));
+            }
 
             for constraint in statement.post_statements.into_iter() {
                 post_statements.push(MigrationStatement::new(constraint, is_destructive));
@@ -125,6 +150,21 @@ impl Migration {
         Ok(Migration::from_schemas(&old_schema.value, &database_spec))
     }
 
+    /// Add statements that enforce the access rules using row-level security policies (see
+    /// [`row_level_security`])
+    pub fn add_row_level_security(
+        &mut self,
+        postgres_subsystem: &PostgresSubsystem,
+    ) -> Result<(), anyhow::Error> {
+        let policy_statements = row_level_security::policy_statements(postgres_subsystem)?;
+
+        self.statements
+            .insert(0, row_level_security::drop_policies_statement());
+        self.statements.extend(policy_statements);
+
+        Ok(())
+    }
+
     pub fn has_destructive_changes(&self) -> bool {
         self.statements
             .iter()
@@ -571,6 +611,218 @@ mod tests {
         ).await
     }
 
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn update_relation_on_delete() {
+        // venue: Venue? <-> @relation(onDelete: SET_NULL) venue: Venue?
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                    venue: Venue?
+                }
+                type Venue {
+                    @pk id: Int = autoIncrement()
+                    name: String
+                    concerts: Set<Concert>?
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                    @relation(onDelete: SET_NULL) venue: Venue?
+                }
+                type Venue {
+                    @pk id: Int = autoIncrement()
+                    name: String
+                    concerts: Set<Concert>?
+                }
+            }
+            "#,
+            vec![
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" TEXT NOT NULL,
+                    |    "venue_id" INT
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "venues" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "name" TEXT NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "venues";"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" TEXT NOT NULL,
+                    |    "venue_id" INT
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "venues" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "name" TEXT NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "venues" ON DELETE SET NULL;"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_venue_id_fk";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "venues" ON DELETE SET NULL;"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_venue_id_fk";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "venues";"#,
+                    false,
+                ),
+            ],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn update_relation_target() {
+        // venue: Venue <-> venue: Hall (with the same column name)
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    venue: Venue
+                }
+                type Venue {
+                    @pk id: Int = autoIncrement()
+                    concerts: Set<Concert>?
+                }
+                type Hall {
+                    @pk id: Int = autoIncrement()
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    venue: Hall
+                }
+                type Venue {
+                    @pk id: Int = autoIncrement()
+                }
+                type Hall {
+                    @pk id: Int = autoIncrement()
+                    concerts: Set<Concert>?
+                }
+            }
+            "#,
+            vec![
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "venue_id" INT NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "venues" (
+                    |    "id" SERIAL PRIMARY KEY
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "halls" (
+                    |    "id" SERIAL PRIMARY KEY
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "venues";"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "venue_id" INT NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "venues" (
+                    |    "id" SERIAL PRIMARY KEY
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "halls" (
+                    |    "id" SERIAL PRIMARY KEY
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "halls";"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_venue_id_fk";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "halls";"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_venue_id_fk";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_venue_id_fk" FOREIGN KEY ("venue_id") REFERENCES "venues";"#,
+                    false,
+                ),
+            ],
+        )
+        .await
+    }
+
     #[cfg_attr(not(target_family = "wasm"), tokio::test)]
     #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
     async fn add_indices() {
@@ -1366,51 +1618,566 @@ mod tests {
 
     #[cfg_attr(not(target_family = "wasm"), tokio::test)]
     #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
-    async fn introduce_vector_field() {
+    async fn change_string_max_length() {
         assert_changes(
             r#"
             @postgres
-            module DocumentDatabase {
-              @access(true)
-              type Document {
-                @pk id: Int = autoIncrement()
-                content: String
-              }
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @maxLength(50) title: String
+                }
             }
             "#,
             r#"
             @postgres
-            module DocumentDatabase {
-              @access(true)
-              type Document {
-                @pk id: Int = autoIncrement()
-                content: String
-                contentVector: Vector?
-              }
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @maxLength(200) title: String
+                }
             }
             "#,
-            vec![(
-                r#"CREATE TABLE "documents" (
-                 |    "id" SERIAL PRIMARY KEY,
-                 |    "content" TEXT NOT NULL
-                 |);"#,
-                false,
-            )],
             vec![
-                (r#"CREATE EXTENSION "vector";"#, false),
                 (
-                    r#"CREATE TABLE "documents" (
-                 |    "id" SERIAL PRIMARY KEY,
-                 |    "content" TEXT NOT NULL,
-                 |    "content_vector" Vector(1536)
-                 |);"#,
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" VARCHAR(50) NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_title_max_length_check" CHECK (char_length("title") <= 50);"#,
                     false,
                 ),
             ],
             vec![
-                ("CREATE EXTENSION \"vector\";", false),
                 (
-                    "ALTER TABLE \"documents\" ADD \"content_vector\" Vector(1536);",
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" VARCHAR(200) NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_title_max_length_check" CHECK (char_length("title") <= 200);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" ALTER COLUMN "title" TYPE VARCHAR(200);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_title_max_length_check";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_title_max_length_check" CHECK (char_length("title") <= 200);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" ALTER COLUMN "title" TYPE VARCHAR(50);"#,
+                    true,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_title_max_length_check";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_title_max_length_check" CHECK (char_length("title") <= 50);"#,
+                    false,
+                ),
+            ],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn add_range_check() {
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @bits16 rating: Int
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @bits16 @range(min=0, max=5) rating: Int
+                }
+            }
+            "#,
+            vec![(
+                r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "rating" SMALLINT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "rating" SMALLINT NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_rating_range_check" CHECK ("rating" >= 0 AND "rating" <= 5);"#,
+                    false,
+                ),
+            ],
+            vec![(
+                r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_rating_range_check" CHECK ("rating" >= 0 AND "rating" <= 5);"#,
+                false,
+            )],
+            vec![(
+                r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_rating_range_check";"#,
+                false,
+            )],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn change_check_expression() {
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                @check("starts_at < ends_at")
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @check("price >= 0") price: Int
+                    startsAt: Instant
+                    endsAt: Instant
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                @check("starts_at <= ends_at")
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @check("price >= 0") price: Int
+                    startsAt: Instant
+                    endsAt: Instant
+                }
+            }
+            "#,
+            vec![
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "price" INT NOT NULL,
+                    |    "starts_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+                    |    "ends_at" TIMESTAMP WITH TIME ZONE NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_price_check" CHECK (price >= 0);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_check" CHECK (starts_at < ends_at);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "price" INT NOT NULL,
+                    |    "starts_at" TIMESTAMP WITH TIME ZONE NOT NULL,
+                    |    "ends_at" TIMESTAMP WITH TIME ZONE NOT NULL
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_price_check" CHECK (price >= 0);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_check" CHECK (starts_at <= ends_at);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_check";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_check" CHECK (starts_at <= ends_at);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "concerts" DROP CONSTRAINT "concerts_check";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "concerts" ADD CONSTRAINT "concerts_check" CHECK (starts_at < ends_at);"#,
+                    false,
+                ),
+            ],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn change_int_bits() {
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    attendance: Int
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @bits64 attendance: Int
+                }
+            }
+            "#,
+            vec![(
+                r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "attendance" INT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![(
+                r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "attendance" BIGINT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![(
+                r#"ALTER TABLE "concerts" ALTER COLUMN "attendance" TYPE BIGINT USING "attendance"::BIGINT;"#,
+                false,
+            )],
+            vec![(
+                r#"ALTER TABLE "concerts" ALTER COLUMN "attendance" TYPE INT USING "attendance"::INT;"#,
+                true,
+            )],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn rename_column() {
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    @column(renamedFrom="title") name: String
+                }
+            }
+            "#,
+            vec![(
+                r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" TEXT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![(
+                r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "name" TEXT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![(
+                r#"ALTER TABLE "concerts" RENAME COLUMN "title" TO "name";"#,
+                false,
+            )],
+            // The old model doesn't have a rename hint, so going back recreates the column
+            vec![
+                (r#"ALTER TABLE "concerts" DROP COLUMN "name";"#, true),
+                (
+                    r#"ALTER TABLE "concerts" ADD "title" TEXT NOT NULL;"#,
+                    false,
+                ),
+            ],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn rename_table() {
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                @table(renamedFrom="concerts")
+                type Show {
+                    @pk id: Int = autoIncrement()
+                    @column(renamedFrom="title") name: String
+                }
+            }
+            "#,
+            vec![(
+                r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" TEXT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![(
+                r#"CREATE TABLE "shows" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "name" TEXT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![
+                (r#"ALTER TABLE "concerts" RENAME TO "shows";"#, false),
+                (
+                    r#"ALTER TABLE "shows" RENAME COLUMN "title" TO "name";"#,
+                    false,
+                ),
+            ],
+            vec![
+                (r#"DROP TABLE "shows" CASCADE;"#, true),
+                (
+                    r#"CREATE TABLE "concerts" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" TEXT NOT NULL
+                    |);"#,
+                    false,
+                ),
+            ],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn add_enum_field() {
+        assert_changes(
+            r#"
+            @postgres
+            module TodoModule {
+                type Todo {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module TodoModule {
+                enum Status { DRAFT, PUBLISHED }
+
+                type Todo {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                    status: Status
+                }
+            }
+            "#,
+            vec![(
+                r#"CREATE TABLE "todos" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" TEXT NOT NULL
+                    |);"#,
+                false,
+            )],
+            vec![
+                (
+                    r#"CREATE TYPE "status" AS ENUM ('DRAFT', 'PUBLISHED');"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "todos" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "title" TEXT NOT NULL,
+                    |    "status" "status" NOT NULL
+                    |);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"CREATE TYPE "status" AS ENUM ('DRAFT', 'PUBLISHED');"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "todos" ADD "status" "status" NOT NULL;"#,
+                    false,
+                ),
+            ],
+            vec![
+                (r#"ALTER TABLE "todos" DROP COLUMN "status";"#, true),
+                (r#"DROP TYPE "status";"#, false),
+            ],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn add_enum_value() {
+        assert_changes(
+            r#"
+            @postgres
+            module TodoModule {
+                enum Status { DRAFT, PUBLISHED }
+
+                type Todo {
+                    @pk id: Int = autoIncrement()
+                    status: Status
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module TodoModule {
+                enum Status { DRAFT, REVIEWED, PUBLISHED, ARCHIVED }
+
+                type Todo {
+                    @pk id: Int = autoIncrement()
+                    status: Status
+                }
+            }
+            "#,
+            vec![
+                (r#"CREATE TYPE "status" AS ENUM ('DRAFT', 'PUBLISHED');"#, false),
+                (
+                    r#"CREATE TABLE "todos" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "status" "status" NOT NULL
+                    |);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"CREATE TYPE "status" AS ENUM ('DRAFT', 'REVIEWED', 'PUBLISHED', 'ARCHIVED');"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "todos" (
+                    |    "id" SERIAL PRIMARY KEY,
+                    |    "status" "status" NOT NULL
+                    |);"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TYPE "status" ADD VALUE 'REVIEWED' BEFORE 'PUBLISHED';"#,
+                    false,
+                ),
+                (r#"ALTER TYPE "status" ADD VALUE 'ARCHIVED';"#, false),
+            ],
+            vec![
+                (r#"ALTER TYPE "status" RENAME TO "status_old";"#, true),
+                (r#"CREATE TYPE "status" AS ENUM ('DRAFT', 'PUBLISHED');"#, true),
+                (
+                    r#"ALTER TABLE "todos" ALTER COLUMN "status" TYPE "status" USING "status"::text::"status";"#,
+                    true,
+                ),
+                (r#"DROP TYPE "status_old";"#, true),
+            ],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn introduce_vector_field() {
+        assert_changes(
+            r#"
+            @postgres
+            module DocumentDatabase {
+              @access(true)
+              type Document {
+                @pk id: Int = autoIncrement()
+                content: String
+              }
+            }
+            "#,
+            r#"
+            @postgres
+            module DocumentDatabase {
+              @access(true)
+              type Document {
+                @pk id: Int = autoIncrement()
+                content: String
+                contentVector: Vector?
+              }
+            }
+            "#,
+            vec![(
+                r#"CREATE TABLE "documents" (
+                 |    "id" SERIAL PRIMARY KEY,
+                 |    "content" TEXT NOT NULL
+                 |);"#,
+                false,
+            )],
+            vec![
+                (r#"CREATE EXTENSION "vector";"#, false),
+                (
+                    r#"CREATE TABLE "documents" (
+                 |    "id" SERIAL PRIMARY KEY,
+                 |    "content" TEXT NOT NULL,
+                 |    "content_vector" Vector(1536)
+                 |);"#,
+                    false,
+                ),
+            ],
+            vec![
+                ("CREATE EXTENSION \"vector\";", false),
+                (
+                    "ALTER TABLE \"documents\" ADD \"content_vector\" Vector(1536);",
                     false,
                 ),
             ],
@@ -1611,6 +2378,153 @@ mod tests {
         .await
     }
 
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn introduce_searchable_field() {
+        assert_changes(
+            r#"
+            @postgres
+            module DocumentDatabase {
+              @access(true)
+              type Document {
+                @pk id: Int = autoIncrement()
+                title: String
+              }
+            }
+            "#,
+            r#"
+            @postgres
+            module DocumentDatabase {
+              @access(true)
+              type Document {
+                @pk id: Int = autoIncrement()
+                @searchable title: String
+              }
+            }
+            "#,
+            vec![(
+                r#"CREATE TABLE "documents" (
+                 |    "id" SERIAL PRIMARY KEY,
+                 |    "title" TEXT NOT NULL
+                 |);"#,
+                false,
+            )],
+            vec![
+                (
+                    r#"CREATE TABLE "documents" (
+                 |    "id" SERIAL PRIMARY KEY,
+                 |    "title" TEXT NOT NULL,
+                 |    "title_tsv" tsvector GENERATED ALWAYS AS (to_tsvector('english', COALESCE("title", ''))) STORED NOT NULL
+                 |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE INDEX "document_title_search_idx" ON "documents" USING gin ("title_tsv");"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "documents" ADD "title_tsv" tsvector GENERATED ALWAYS AS (to_tsvector('english', COALESCE("title", ''))) STORED NOT NULL;"#,
+                    false,
+                ),
+                (
+                    r#"CREATE INDEX "document_title_search_idx" ON "documents" USING gin ("title_tsv");"#,
+                    false,
+                ),
+            ],
+            vec![(
+                r#"ALTER TABLE "documents" DROP COLUMN "title_tsv";"#,
+                false,
+            )],
+        )
+        .await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn searchable_language_change() {
+        assert_changes(
+            r#"
+            @postgres
+            module DocumentDatabase {
+              @access(true)
+              type Document {
+                @pk id: Int = autoIncrement()
+                @searchable title: String
+              }
+            }
+            "#,
+            r#"
+            @postgres
+            module DocumentDatabase {
+              @access(true)
+              type Document {
+                @pk id: Int = autoIncrement()
+                @searchable("simple") title: String
+              }
+            }
+            "#,
+            vec![
+                (
+                    r#"CREATE TABLE "documents" (
+                 |    "id" SERIAL PRIMARY KEY,
+                 |    "title" TEXT NOT NULL,
+                 |    "title_tsv" tsvector GENERATED ALWAYS AS (to_tsvector('english', COALESCE("title", ''))) STORED NOT NULL
+                 |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE INDEX "document_title_search_idx" ON "documents" USING gin ("title_tsv");"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"CREATE TABLE "documents" (
+                 |    "id" SERIAL PRIMARY KEY,
+                 |    "title" TEXT NOT NULL,
+                 |    "title_tsv" tsvector GENERATED ALWAYS AS (to_tsvector('simple', COALESCE("title", ''))) STORED NOT NULL
+                 |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE INDEX "document_title_search_idx" ON "documents" USING gin ("title_tsv");"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "documents" DROP COLUMN "title_tsv";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "documents" ADD "title_tsv" tsvector GENERATED ALWAYS AS (to_tsvector('simple', COALESCE("title", ''))) STORED NOT NULL;"#,
+                    false,
+                ),
+                (
+                    r#"CREATE INDEX "document_title_search_idx" ON "documents" USING gin ("title_tsv");"#,
+                    false,
+                ),
+            ],
+            vec![
+                (
+                    r#"ALTER TABLE "documents" DROP COLUMN "title_tsv";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "documents" ADD "title_tsv" tsvector GENERATED ALWAYS AS (to_tsvector('english', COALESCE("title", ''))) STORED NOT NULL;"#,
+                    false,
+                ),
+                (
+                    r#"CREATE INDEX "document_title_search_idx" ON "documents" USING gin ("title_tsv");"#,
+                    false,
+                ),
+            ],
+        )
+        .await
+    }
+
     #[cfg_attr(not(target_family = "wasm"), tokio::test)]
     #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
     async fn add_update_sync_field() {
@@ -1704,6 +2618,131 @@ mod tests {
         ).await
     }
 
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn add_subscription_annotation() {
+        assert_changes(
+            r#"
+            @postgres
+            module ConcertModule {
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                }
+            }
+            "#,
+            r#"
+            @postgres
+            module ConcertModule {
+                @subscription
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                }
+            }
+            "#,
+            vec![
+                ("CREATE TABLE \"concerts\" (\n    \"id\" SERIAL PRIMARY KEY,\n    \"title\" TEXT NOT NULL\n);", false)
+            ],
+            vec![
+                ("CREATE TABLE \"concerts\" (\n    \"id\" SERIAL PRIMARY KEY,\n    \"title\" TEXT NOT NULL\n);", false),
+                ("CREATE FUNCTION exograph_notify_concerts() RETURNS TRIGGER AS $$ BEGIN PERFORM pg_notify('exograph_changes_concerts', json_build_object('op', TG_OP, 'pk', NEW.\"id\")::text); RETURN NULL; END; $$ language 'plpgsql';", false),
+                ("CREATE TRIGGER exograph_on_insert_notify_concerts AFTER INSERT ON concerts FOR EACH ROW EXECUTE FUNCTION exograph_notify_concerts();", false),
+                ("CREATE TRIGGER exograph_on_update_notify_concerts AFTER UPDATE ON concerts FOR EACH ROW EXECUTE FUNCTION exograph_notify_concerts();", false)
+            ],
+            vec![
+                ("CREATE FUNCTION exograph_notify_concerts() RETURNS TRIGGER AS $$ BEGIN PERFORM pg_notify('exograph_changes_concerts', json_build_object('op', TG_OP, 'pk', NEW.\"id\")::text); RETURN NULL; END; $$ language 'plpgsql';", false),
+                ("CREATE TRIGGER exograph_on_insert_notify_concerts AFTER INSERT ON concerts FOR EACH ROW EXECUTE FUNCTION exograph_notify_concerts();", false),
+                ("CREATE TRIGGER exograph_on_update_notify_concerts AFTER UPDATE ON concerts FOR EACH ROW EXECUTE FUNCTION exograph_notify_concerts();", false)
+            ],
+            vec![
+                ("DROP TRIGGER exograph_on_insert_notify_concerts on \"concerts\";", false),
+                ("DROP TRIGGER exograph_on_update_notify_concerts on \"concerts\";", false),
+                ("DROP FUNCTION exograph_notify_concerts;", false)
+            ],
+        ).await
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn composite_primary_keys() {
+        let new_system = compute_spec(
+            r#"
+            @postgres
+            module AccountModule {
+                type Account {
+                    @pk tenantId: Int
+                    @pk id: Int
+                    name: String
+                }
+                type User {
+                    @pk id: Int = autoIncrement()
+                    memberships: Set<Membership>?
+                }
+                type Group {
+                    @pk id: Int = autoIncrement()
+                    memberships: Set<Membership>?
+                }
+                type Membership {
+                    @pk user: User
+                    @pk group: Group
+                    role: String
+                }
+            }
+            "#,
+        )
+        .await;
+
+        // The same statements that `exo schema create` emits
+        assert_change(
+            &DatabaseSpec::new(vec![], vec![]),
+            &new_system,
+            vec![
+                (
+                    r#"CREATE TABLE "accounts" (
+                    |    "tenant_id" INT,
+                    |    "id" INT,
+                    |    "name" TEXT NOT NULL,
+                    |    PRIMARY KEY ("tenant_id", "id")
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "users" (
+                    |    "id" SERIAL PRIMARY KEY
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "groups" (
+                    |    "id" SERIAL PRIMARY KEY
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"CREATE TABLE "memberships" (
+                    |    "user_id" INT,
+                    |    "group_id" INT,
+                    |    "role" TEXT NOT NULL,
+                    |    PRIMARY KEY ("user_id", "group_id")
+                    |);"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "memberships" ADD CONSTRAINT "memberships_user_id_fk" FOREIGN KEY ("user_id") REFERENCES "users";"#,
+                    false,
+                ),
+                (
+                    r#"ALTER TABLE "memberships" ADD CONSTRAINT "memberships_group_id_fk" FOREIGN KEY ("group_id") REFERENCES "groups";"#,
+                    false,
+                ),
+            ],
+            "Create schema",
+        );
+
+        assert_change(&new_system, &new_system, vec![], "Idempotent");
+    }
+
     async fn create_postgres_system_from_str(
         model_str: &str,
         file_name: String,
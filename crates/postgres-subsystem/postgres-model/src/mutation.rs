@@ -7,17 +7,24 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use async_graphql_parser::types::Type;
+use async_graphql_parser::types::{
+    BaseType, EnumType, EnumValueDefinition, InputObjectType, InputValueDefinition, Type,
+    TypeDefinition, TypeKind,
+};
+use async_graphql_value::Name;
+use exo_sql::ColumnId;
 use serde::{Deserialize, Serialize};
 
 use crate::{predicate::PredicateParameter, types::MutationType};
 use core_plugin_interface::core_model::mapped_arena::SerializableSlabIndex;
-use core_plugin_interface::core_model::type_normalization::Parameter;
+use core_plugin_interface::core_model::type_normalization::{
+    default_positioned, default_positioned_name, Parameter,
+};
 use core_plugin_interface::core_model::types::{FieldType, Named};
 
 use super::operation::{OperationParameters, PostgresOperation};
 
-/// A mutation such as `createTodo`, `updateTodo`, or `deleteTodo`
+/// A mutation such as `createTodo`, `updateTodo`, `deleteTodo`, `upsertTodo`, or `restoreTodo`
 pub type PostgresMutation = PostgresOperation<PostgresMutationParameters>;
 
 /// Mutation parameters
@@ -30,18 +37,35 @@ pub enum PostgresMutationParameters {
     Create(DataParameter),
 
     /// Parameters for a delete mutation such as `deleteTodo` or `deleteTodos`
-    /// The only parameter is a predicate such as `id: 1` or `where: {complete: {eq: true}}`
+    /// The parameters form a predicate such as `id: 1` or `where: {complete: {eq: true}}`
     /// `{ deleteTodo(id: 1)` or `{ deleteTodos(where: { complete: {eq: true }}) }`
-    Delete(PredicateParameter),
+    /// (there are multiple parameters for the primary key parts of a composite primary key)
+    Delete(Vec<PredicateParameter>),
+
+    /// Parameters for a restore mutation (of a `@softDelete` type) such as `restoreTodo` or `restoreTodos`
+    /// The parameters form a predicate (as in a delete mutation) to pick the soft-deleted entities to restore
+    /// `{ restoreTodo(id: 1) }` or `{ restoreTodos(where: { complete: {eq: true }}) }`
+    Restore(Vec<PredicateParameter>),
 
     /// Parameters for an update mutation such as `updateTodo` or `updateTodos`
-    /// It takes two parameters: a predicate such as `id: 1` or `where: {complete: {eq: true}}`
+    /// It takes two kinds of parameters: a predicate such as `id: 1` or `where: {complete: {eq: true}}`
+    /// (with multiple parameters for the parts of a composite primary key)
     /// and the data to be updated such as `data: { title: "New title" }`.
     /// This allows mutations such as `{ updateTodo(id: 1, data: { title: "New title" }) }` and
     /// `{ updateTodos(where: { complete: {eq: true }}, data: { title: "New title" }) }`
     Update {
         data_param: DataParameter,
-        predicate_param: PredicateParameter,
+        predicate_params: Vec<PredicateParameter>,
+    },
+
+    /// Parameters for an upsert mutation such as `upsertTodo` or `upsertTodos`
+    /// It takes two parameters: the data to be created (or to update the conflicting row with) such
+    /// as `data: { email: "jane@example.com", name: "Jane" }` and the constraint to detect a
+    /// conflict on such as `onConflict: { constraint: email }`.
+    /// This allows mutations such as `{ upsertUser(data: { email: "jane@example.com", name: "Jane" }, onConflict: { constraint: email }) }`
+    Upsert {
+        data_param: DataParameter,
+        on_conflict_param: OnConflictParameter,
     },
 }
 
@@ -49,11 +73,23 @@ impl OperationParameters for PostgresMutationParameters {
     fn introspect(&self) -> Vec<&dyn Parameter> {
         match &self {
             PostgresMutationParameters::Create(data_param) => vec![data_param],
-            PostgresMutationParameters::Delete(predicate_param) => vec![predicate_param],
+            PostgresMutationParameters::Delete(predicate_params)
+            | PostgresMutationParameters::Restore(predicate_params) => predicate_params
+                .iter()
+                .map(|p| p as &dyn Parameter)
+                .collect(),
             PostgresMutationParameters::Update {
                 data_param,
-                predicate_param,
-            } => vec![predicate_param, data_param],
+                predicate_params,
+            } => predicate_params
+                .iter()
+                .map(|p| p as &dyn Parameter)
+                .chain([data_param as &dyn Parameter])
+                .collect(),
+            PostgresMutationParameters::Upsert {
+                data_param,
+                on_conflict_param,
+            } => vec![data_param, on_conflict_param],
         }
     }
 }
@@ -92,3 +128,101 @@ impl Parameter for DataParameter {
         (&self.typ).into()
     }
 }
+
+/// The `onConflict` parameter of an upsert mutation such as `onConflict: { constraint: email }`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OnConflictParameter {
+    /// Name of the parameter (typically `onConflict`).
+    pub name: String,
+    /// The name of the input type such as `TodoOnConflictInput`.
+    pub type_name: String,
+    /// The name of the enum listing the constraints such as `TodoUpsertConstraint`.
+    pub constraint_type_name: String,
+    /// The primary key and the unique constraints that a conflict may be detected on.
+    pub targets: Vec<ConflictTarget>,
+}
+
+/// A primary key or a unique constraint that an upsert may detect a conflict on.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConflictTarget {
+    /// The name exposed as an enum value (the pk field name or the unique constraint name such as `email`).
+    pub name: String,
+    /// The columns making up the primary key or the unique constraint.
+    pub column_ids: Vec<ColumnId>,
+}
+
+/// The field of the `onConflict` input type that selects the constraint
+pub const ON_CONFLICT_CONSTRAINT_FIELD: &str = "constraint";
+
+impl OnConflictParameter {
+    pub fn target(&self, name: &str) -> Option<&ConflictTarget> {
+        self.targets.iter().find(|target| target.name == name)
+    }
+
+    /// Type definitions for the input type and the constraint enum such as:
+    /// ```graphql
+    /// input TodoOnConflictInput {
+    ///   constraint: TodoUpsertConstraint!
+    /// }
+    ///
+    /// enum TodoUpsertConstraint {
+    ///   id
+    ///   title
+    /// }
+    /// ```
+    pub fn type_definitions(&self) -> Vec<TypeDefinition> {
+        let input_type = TypeDefinition {
+            extend: false,
+            description: None,
+            name: default_positioned_name(&self.type_name),
+            directives: vec![],
+            kind: TypeKind::InputObject(InputObjectType {
+                fields: vec![default_positioned(InputValueDefinition {
+                    description: None,
+                    name: default_positioned_name(ON_CONFLICT_CONSTRAINT_FIELD),
+                    ty: default_positioned(Type {
+                        base: BaseType::Named(Name::new(&self.constraint_type_name)),
+                        nullable: false,
+                    }),
+                    default_value: None,
+                    directives: vec![],
+                })],
+            }),
+        };
+
+        let constraint_type = TypeDefinition {
+            extend: false,
+            description: None,
+            name: default_positioned_name(&self.constraint_type_name),
+            directives: vec![],
+            kind: TypeKind::Enum(EnumType {
+                values: self
+                    .targets
+                    .iter()
+                    .map(|target| {
+                        default_positioned(EnumValueDefinition {
+                            description: None,
+                            value: default_positioned_name(&target.name),
+                            directives: vec![],
+                        })
+                    })
+                    .collect(),
+            }),
+        };
+
+        vec![input_type, constraint_type]
+    }
+}
+
+impl Parameter for OnConflictParameter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        Type {
+            base: BaseType::Named(Name::new(&self.type_name)),
+            nullable: false,
+        }
+    }
+}
@@ -23,7 +23,7 @@
 //! }
 //! ```
 //!
-//! Queries like `todos`, `todo`, and `todoAgg` as well as mutations like `createTodo`, `updateTodo`, and `deleteTodo` will be
+//! Queries like `todos`, `todo`, `todoAgg`, and `todosConnection` as well as mutations like `createTodo`, `updateTodo`, and `deleteTodo` will be
 //! generated by the postgres subsystem builder.
 
 use std::fmt::Debug;
@@ -64,7 +64,12 @@ pub struct OrderByParameterType {
 pub enum OrderByParameterTypeKind {
     Primitive,
     Vector,
-    Composite { parameters: Vec<OrderByParameter> },
+    /// Ordering a `@searchable` field by its rank for the given search terms (`{rank: "...", order: DESC}`)
+    /// or by its value (`{order: ASC}`)
+    TextSearch,
+    Composite {
+        parameters: Vec<OrderByParameter>,
+    },
 }
 
 pub const PRIMITIVE_ORDERING_OPTIONS: [&str; 2] = ["ASC", "DESC"];
@@ -147,6 +152,41 @@ impl TypeDefinitionProvider<PostgresSubsystem> for OrderByParameterType {
                 .map(default_positioned)
                 .collect();
 
+                TypeDefinition {
+                    extend: false,
+                    description: None,
+                    name: default_positioned_name(&self.name),
+                    directives: vec![],
+                    kind: TypeKind::InputObject(InputObjectType { fields }),
+                }
+            }
+            OrderByParameterTypeKind::TextSearch => {
+                let fields = vec![
+                    InputValueDefinition {
+                        description: None,
+                        name: default_positioned_name("rank"),
+                        directives: vec![],
+                        default_value: None,
+                        ty: default_positioned(Type {
+                            base: BaseType::Named(Name::new("String")),
+                            nullable: true,
+                        }),
+                    },
+                    InputValueDefinition {
+                        description: None,
+                        name: default_positioned_name("order"),
+                        directives: vec![],
+                        default_value: None,
+                        ty: default_positioned(Type {
+                            base: BaseType::Named(Name::new("Ordering")),
+                            nullable: true,
+                        }),
+                    },
+                ]
+                .into_iter()
+                .map(default_positioned)
+                .collect();
+
                 TypeDefinition {
                     extend: false,
                     description: None,
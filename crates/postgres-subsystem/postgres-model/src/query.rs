@@ -12,9 +12,12 @@ use serde::{Deserialize, Serialize};
 use core_plugin_interface::core_model::type_normalization::Parameter;
 
 use crate::{
+    connection::CursorParameter,
+    group_by::{GroupByParameter, GroupHavingParameter, GroupOrderByParameter},
     limit_offset::{LimitParameter, OffsetParameter},
     order::OrderByParameter,
     predicate::PredicateParameter,
+    soft_delete::IncludeDeletedParameter,
 };
 
 use super::operation::{OperationParameters, PostgresOperation};
@@ -22,15 +25,27 @@ use super::operation::{OperationParameters, PostgresOperation};
 /// Query by primary key such as `todo(id: 1)`
 pub type PkQuery = PostgresOperation<PkQueryParameters>;
 
-/// Primary key query parameter such as `id: 1` in `todo(id: 1)`
+/// Primary key query parameters such as `id: 1` in `todo(id: 1)` (or `tenantId: 1, id: 2` in
+/// `account(tenantId: 1, id: 2)` for a composite primary key)
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PkQueryParameters {
-    pub predicate_param: PredicateParameter,
+    pub predicate_params: Vec<PredicateParameter>,
+    /// The parameter to include a soft-deleted entity such as `includeDeleted: true` (only for a
+    /// `@softDelete` type)
+    pub include_deleted_param: Option<IncludeDeletedParameter>,
 }
 
 impl OperationParameters for PkQueryParameters {
     fn introspect(&self) -> Vec<&dyn Parameter> {
-        vec![&self.predicate_param]
+        self.predicate_params
+            .iter()
+            .map(|p| p as &dyn Parameter)
+            .chain(
+                self.include_deleted_param
+                    .iter()
+                    .map(|p| p as &dyn Parameter),
+            )
+            .collect()
     }
 }
 
@@ -48,15 +63,55 @@ pub struct CollectionQueryParameters {
     pub limit_param: LimitParameter,
     /// The offset parameter such as `offset: 20`
     pub offset_param: OffsetParameter,
+    /// The parameter to include soft-deleted entities such as `includeDeleted: true` (only for a
+    /// `@softDelete` type)
+    pub include_deleted_param: Option<IncludeDeletedParameter>,
 }
 
 impl OperationParameters for CollectionQueryParameters {
     fn introspect(&self) -> Vec<&dyn Parameter> {
-        vec![
+        let mut params: Vec<&dyn Parameter> = vec![
             &self.predicate_param,
             &self.order_by_param,
             &self.limit_param,
             &self.offset_param,
+        ];
+        if let Some(include_deleted_param) = &self.include_deleted_param {
+            params.push(include_deleted_param);
+        }
+        params
+    }
+}
+
+/// Relay-style query that returns a page of a collection such as `todosConnection(first: 10, after: "...")`
+pub type ConnectionQuery = PostgresOperation<ConnectionQueryParameters>;
+
+/// Connection query parameters
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConnectionQueryParameters {
+    /// The predicate parameter such as `where: { title: { eq: "Hello" } }`
+    pub predicate_param: PredicateParameter,
+    /// The order by parameter such as `orderBy: { title: ASC }` (also determines the cursor's content)
+    pub order_by_param: OrderByParameter,
+    /// The number of items from the start such as `first: 10`
+    pub first_param: LimitParameter,
+    /// The cursor to start after such as `after: "..."`
+    pub after_param: CursorParameter,
+    /// The number of items from the end such as `last: 10`
+    pub last_param: LimitParameter,
+    /// The cursor to end before such as `before: "..."`
+    pub before_param: CursorParameter,
+}
+
+impl OperationParameters for ConnectionQueryParameters {
+    fn introspect(&self) -> Vec<&dyn Parameter> {
+        vec![
+            &self.predicate_param,
+            &self.order_by_param,
+            &self.first_param,
+            &self.after_param,
+            &self.last_param,
+            &self.before_param,
         ]
     }
 }
@@ -76,6 +131,33 @@ impl OperationParameters for AggregateQueryParameters {
     }
 }
 
+/// Query that returns aggregates for each group of rows such as `todosGroupBy(by: [completed])`
+pub type GroupByQuery = PostgresOperation<GroupByQueryParameters>;
+
+/// Group-by query parameters
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GroupByQueryParameters {
+    /// The fields to group by such as `by: [completed, userId]`
+    pub by_param: GroupByParameter,
+    /// The predicate parameter (applied before grouping) such as `where: { title: { eq: "Hello" } }`
+    pub predicate_param: PredicateParameter,
+    /// The predicate over the aggregates (applied after grouping) such as `having: { id: { count: { gt: 2 } } }`
+    pub having_param: GroupHavingParameter,
+    /// The order by parameter such as `orderBy: [{ agg: { id: { count: DESC } } }]`
+    pub order_by_param: GroupOrderByParameter,
+}
+
+impl OperationParameters for GroupByQueryParameters {
+    fn introspect(&self) -> Vec<&dyn Parameter> {
+        vec![
+            &self.by_param,
+            &self.predicate_param,
+            &self.having_param,
+            &self.order_by_param,
+        ]
+    }
+}
+
 /// Query by unique constrained parameters such as `userByEmail(email: "hello@example.com")` or `userByFirstAndLastName(firstName: "John", lastName: "Doe")`
 pub type UniqueQuery = PostgresOperation<UniqueQueryParameters>;
 
@@ -36,9 +36,13 @@ pub struct ManyToOneRelation {
     // - foreign_pk_field_id: Venue.id
     // - relation_id.self_column_id: concerts.venue_id
     // - relation_id.foreign_pk_column_id: venues.id
+    // - is_pk: false (true if declared as `@pk venue: Venue`)
     pub cardinality: RelationCardinality,
     pub foreign_pk_field_id: EntityFieldId,
     pub relation_id: ManyToOneId,
+    /// Is the relation a part of the (composite) primary key of the self type (such as for a join
+    /// type keyed on both of its relations)?
+    pub is_pk: bool,
 }
 
 impl ManyToOneRelation {
@@ -46,6 +50,11 @@ impl ManyToOneRelation {
         let relation = self.relation_id.deref(database);
         relation.column_path_link()
     }
+
+    /// The foreign key column (such as `concerts.venue_id`)
+    pub fn self_column_id(&self, database: &Database) -> ColumnId {
+        self.relation_id.deref(database).self_column_id
+    }
 }
 
 /// Model for a one-to-many relation.
@@ -69,6 +78,15 @@ impl OneToManyRelation {
 }
 
 impl PostgresRelation {
+    /// Is the field with this relation a part of the primary key?
+    pub fn is_pk(&self) -> bool {
+        match self {
+            PostgresRelation::Pk { .. } => true,
+            PostgresRelation::ManyToOne(relation) => relation.is_pk,
+            PostgresRelation::Scalar { .. } | PostgresRelation::OneToMany(_) => false,
+        }
+    }
+
     pub fn column_path_link(&self, database: &Database) -> ColumnPathLink {
         match &self {
             PostgresRelation::Pk { column_id, .. } | PostgresRelation::Scalar { column_id, .. } => {
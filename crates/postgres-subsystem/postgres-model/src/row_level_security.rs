@@ -0,0 +1,713 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Row-level security policies derived from access rules.
+//!
+//! Access rules are normally enforced only by the resolver, so anyone connecting directly to the
+//! database bypasses them. In the row-level security mode, each table gets policies that express
+//! the same rules in SQL. A context selection such as `AuthContext.id` becomes a session setting
+//! such as `exo.auth_context.id`, which the resolver sets for each transaction.
+//!
+//! A setting that hasn't been set evaluates to `NULL`, so any rule that depends on it denies
+//! access.
+
+use anyhow::{anyhow, bail, Result};
+use core_plugin_interface::core_model::{
+    access::{
+        AccessLogicalExpression, AccessPredicateExpression, AccessRelationalOp,
+        CommonAccessPrimitiveExpression, FunctionCall,
+    },
+    context_type::ContextSelection,
+};
+use exo_sql::{ColumnId, ColumnPathLink, PhysicalColumnPath, PhysicalColumnType};
+use heck::ToSnakeCase;
+
+use crate::{
+    access::{DatabaseAccessPrimitiveExpression, InputAccessPrimitiveExpression},
+    migration::MigrationStatement,
+    relation::PostgresRelation,
+    subsystem::PostgresSubsystem,
+    types::EntityType,
+};
+
+/// The prefix of the names of policies (and session settings) managed by Exograph
+const PREFIX: &str = "exo";
+
+type DatabasePredicate = AccessPredicateExpression<DatabaseAccessPrimitiveExpression>;
+type InputPredicate = AccessPredicateExpression<InputAccessPrimitiveExpression>;
+
+/// The name of the session setting that holds the value of a context selection (for example,
+/// `exo.auth_context.id` for `AuthContext.id`)
+pub fn setting_name(selection: &ContextSelection) -> String {
+    let (head, tail) = &selection.path;
+
+    std::iter::once(PREFIX.to_string())
+        .chain(std::iter::once(selection.context_name.to_snake_case()))
+        .chain(std::iter::once(head.to_snake_case()))
+        .chain(tail.iter().map(|part| part.to_snake_case()))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The context selections referred to by the access rules (and thus need a session setting)
+pub fn context_selections(subsystem: &PostgresSubsystem) -> Vec<&ContextSelection> {
+    let database_selections = subsystem
+        .database_access_expressions
+        .iter()
+        .flat_map(|(_, expr)| database_selections(expr));
+    let input_selections = subsystem
+        .input_access_expressions
+        .iter()
+        .flat_map(|(_, expr)| input_selections(expr));
+
+    let mut selections: Vec<&ContextSelection> = vec![];
+    for selection in database_selections.chain(input_selections) {
+        let name = setting_name(selection);
+        if !selections.iter().any(|s| setting_name(s) == name) {
+            selections.push(selection);
+        }
+    }
+    selections
+}
+
+/// A statement to drop all policies previously created by Exograph (so that a migration may drop
+/// any columns or tables that they refer to)
+pub fn drop_policies_statement() -> MigrationStatement {
+    MigrationStatement::new(
+        format!(
+            "DO $$ DECLARE p record; BEGIN FOR p IN SELECT schemaname, tablename, policyname FROM pg_policies WHERE policyname LIKE '{PREFIX}\\_%' LOOP EXECUTE format('DROP POLICY %I ON %I.%I', p.policyname, p.schemaname, p.tablename); END LOOP; END $$;"
+        ),
+        false,
+    )
+}
+
+/// Statements to enable row-level security and create the policies for each table
+pub fn policy_statements(subsystem: &PostgresSubsystem) -> Result<Vec<MigrationStatement>> {
+    let mut statements = vec![];
+
+    for (_, entity_type) in subsystem.entity_types.iter() {
+        let table_name = subsystem
+            .database
+            .get_table(entity_type.table_id)
+            .name
+            .sql_name();
+        let access = &entity_type.access;
+
+        let mut builder = PolicyBuilder {
+            subsystem,
+            next_alias: 0,
+        };
+        let read = &subsystem.database_access_expressions[access.read];
+        let creation = builder.input_predicate(
+            &subsystem.input_access_expressions[access.creation],
+            entity_type,
+        )?;
+        let update_database = &subsystem.database_access_expressions[access.update.database];
+        let update_input = builder.input_predicate(
+            &subsystem.input_access_expressions[access.update.input],
+            entity_type,
+        )?;
+        let delete = &subsystem.database_access_expressions[access.delete];
+
+        let policies = [
+            ("select", "SELECT", Some(read), None),
+            ("insert", "INSERT", None, Some(&creation)),
+            (
+                "update",
+                "UPDATE",
+                Some(update_database),
+                Some(&update_input),
+            ),
+            ("delete", "DELETE", Some(delete), None),
+        ];
+
+        // Without forcing, Postgres doesn't apply the policies to the owner of the table (typically
+        // the role that Exograph connects with)
+        statements.push(MigrationStatement::new(
+            format!("ALTER TABLE {table_name} ENABLE ROW LEVEL SECURITY;"),
+            false,
+        ));
+        statements.push(MigrationStatement::new(
+            format!("ALTER TABLE {table_name} FORCE ROW LEVEL SECURITY;"),
+            false,
+        ));
+
+        for (name, command, using, with_check) in policies {
+            // Without a permissive policy, Postgres denies the operation, so there is no need to
+            // create a policy for a rule that is always false
+            if [using, with_check].into_iter().flatten().any(is_false) {
+                continue;
+            }
+
+            let mut statement =
+                format!("CREATE POLICY \"{PREFIX}_{name}\" ON {table_name} FOR {command}");
+            if let Some(using) = using {
+                let using = builder.predicate(using, &table_name)?;
+                statement.push_str(&format!(" USING ({using})"));
+            }
+            if let Some(with_check) = with_check {
+                let with_check = builder.predicate(with_check, &table_name)?;
+                statement.push_str(&format!(" WITH CHECK ({with_check})"));
+            }
+            statement.push(';');
+
+            statements.push(MigrationStatement::new(statement, false));
+        }
+    }
+
+    Ok(statements)
+}
+
+fn is_false(expr: &DatabasePredicate) -> bool {
+    matches!(expr, AccessPredicateExpression::BooleanLiteral(false))
+}
+
+fn operands<PrimExpr: Send + Sync>(expr: &AccessPredicateExpression<PrimExpr>) -> Vec<&PrimExpr> {
+    match expr {
+        AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Not(expr)) => operands(expr),
+        AccessPredicateExpression::LogicalOp(
+            AccessLogicalExpression::And(left, right) | AccessLogicalExpression::Or(left, right),
+        ) => {
+            let mut operands_list = operands(left);
+            operands_list.extend(operands(right));
+            operands_list
+        }
+        AccessPredicateExpression::RelationalOp(op) => {
+            let (left, right) = op.sides();
+            vec![left, right]
+        }
+        AccessPredicateExpression::BooleanLiteral(_) => vec![],
+    }
+}
+
+fn database_selections(expr: &DatabasePredicate) -> Vec<&ContextSelection> {
+    operands(expr)
+        .into_iter()
+        .flat_map(|operand| match operand {
+            DatabaseAccessPrimitiveExpression::Column(_, _) => vec![],
+            DatabaseAccessPrimitiveExpression::Function(_, function_call) => {
+                database_selections(&function_call.expr)
+            }
+            DatabaseAccessPrimitiveExpression::Common(common) => common_selection(common),
+        })
+        .collect()
+}
+
+fn input_selections(expr: &InputPredicate) -> Vec<&ContextSelection> {
+    operands(expr)
+        .into_iter()
+        .flat_map(|operand| match operand {
+            InputAccessPrimitiveExpression::Path(_, _) => vec![],
+            InputAccessPrimitiveExpression::Function(_, function_call) => {
+                input_selections(&function_call.expr)
+            }
+            InputAccessPrimitiveExpression::Common(common) => common_selection(common),
+        })
+        .collect()
+}
+
+fn common_selection(common: &CommonAccessPrimitiveExpression) -> Vec<&ContextSelection> {
+    match common {
+        CommonAccessPrimitiveExpression::ContextSelection(selection) => vec![selection],
+        _ => vec![],
+    }
+}
+
+/// An operand of a relational expression along with the alias of the table that its column path
+/// starts at
+enum Operand<'a> {
+    Column(PhysicalColumnPath, String),
+    Count(
+        PhysicalColumnPath,
+        &'a FunctionCall<DatabaseAccessPrimitiveExpression>,
+        String,
+    ),
+    Common(&'a CommonAccessPrimitiveExpression),
+}
+
+impl<'a> Operand<'a> {
+    fn new(expr: &'a DatabaseAccessPrimitiveExpression, alias: &str) -> Self {
+        match expr {
+            DatabaseAccessPrimitiveExpression::Column(path, _) => {
+                Operand::Column(path.clone(), alias.to_string())
+            }
+            DatabaseAccessPrimitiveExpression::Function(path, function_call) => {
+                Operand::Count(path.clone(), function_call, alias.to_string())
+            }
+            DatabaseAccessPrimitiveExpression::Common(common) => Operand::Common(common),
+        }
+    }
+
+    /// Split an operand whose path starts with a relation into the relation (self and foreign
+    /// columns), the alias of the table the relation starts at, and the rest of the path.
+    ///
+    /// A relation followed only by the primary key of the related table (such as `self.venue.id`)
+    /// isn't split off, since the foreign key column holds the same value. For a count, the last
+    /// relation is the one being counted, so it is never split off.
+    fn split_relation(&self) -> Option<((ColumnId, ColumnId), String, PhysicalColumnPath)> {
+        let (path, alias) = match self {
+            Operand::Column(path, alias) | Operand::Count(path, _, alias) => (path, alias),
+            Operand::Common(_) => return None,
+        };
+
+        match path.split_head() {
+            (ColumnPathLink::Relation(relation), Some(tail))
+                if tail != PhysicalColumnPath::leaf(relation.foreign_column_id) =>
+            {
+                Some((
+                    (relation.self_column_id, relation.foreign_column_id),
+                    alias.clone(),
+                    tail,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn with_path(self, path: PhysicalColumnPath, alias: String) -> Self {
+        match self {
+            Operand::Column(_, _) => Operand::Column(path, alias),
+            Operand::Count(_, function_call, _) => Operand::Count(path, function_call, alias),
+            Operand::Common(_) => self,
+        }
+    }
+}
+
+/// The SQL for an operand along with its type (if known)
+enum Value {
+    Typed(String, String),
+    Untyped(String),
+    Setting(String),
+}
+
+struct PolicyBuilder<'a> {
+    subsystem: &'a PostgresSubsystem,
+    next_alias: usize,
+}
+
+impl<'a> PolicyBuilder<'a> {
+    fn predicate(&mut self, expr: &DatabasePredicate, alias: &str) -> Result<String> {
+        Ok(match expr {
+            AccessPredicateExpression::BooleanLiteral(value) => boolean_literal(*value),
+            AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Not(expr)) => {
+                format!("NOT ({})", self.predicate(expr, alias)?)
+            }
+            AccessPredicateExpression::LogicalOp(AccessLogicalExpression::And(left, right)) => {
+                format!(
+                    "({} AND {})",
+                    self.predicate(left, alias)?,
+                    self.predicate(right, alias)?
+                )
+            }
+            AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Or(left, right)) => {
+                format!(
+                    "({} OR {})",
+                    self.predicate(left, alias)?,
+                    self.predicate(right, alias)?
+                )
+            }
+            AccessPredicateExpression::RelationalOp(op) => {
+                let (left, right) = op.sides();
+                self.relational(op, Operand::new(left, alias), Operand::new(right, alias))?
+            }
+        })
+    }
+
+    fn relational(
+        &mut self,
+        op: &AccessRelationalOp<DatabaseAccessPrimitiveExpression>,
+        left: Operand,
+        right: Operand,
+    ) -> Result<String> {
+        // A path through a relation (such as `self.venue.owner`) becomes a correlated `EXISTS`
+        // subquery over the related table (with the rest of the path relative to it)
+        if let Some((relation, alias, tail)) = left.split_relation() {
+            let (foreign_alias, from) = self.relation_from(relation, &alias);
+            let inner = self.relational(op, left.with_path(tail, foreign_alias), right)?;
+            return Ok(format!("EXISTS (SELECT 1 {from} AND {inner})"));
+        }
+        if let Some((relation, alias, tail)) = right.split_relation() {
+            let (foreign_alias, from) = self.relation_from(relation, &alias);
+            let inner = self.relational(op, left, right.with_path(tail, foreign_alias))?;
+            return Ok(format!("EXISTS (SELECT 1 {from} AND {inner})"));
+        }
+
+        let left = self.value(left)?;
+        let right = self.value(right)?;
+
+        if let AccessRelationalOp::In(_, _) = op {
+            let (left, element_type) = match left {
+                Value::Typed(sql, typ) => (sql, Some(typ)),
+                Value::Untyped(sql) => (sql, None),
+                Value::Setting(name) => (setting(&name, None), None),
+            };
+            let right = match right {
+                Value::Setting(name) => {
+                    let array_type = format!("{}[]", element_type.as_deref().unwrap_or("text"));
+                    setting(&name, Some(&array_type))
+                }
+                _ => bail!("The right side of `in` must be a context selection in a row-level security policy"),
+            };
+            return Ok(format!("{left} = ANY({right})"));
+        }
+
+        let (left, right) = match (left, right) {
+            (Value::Setting(left), Value::Setting(right)) => {
+                (setting(&left, None), setting(&right, None))
+            }
+            (Value::Setting(name), Value::Typed(sql, typ)) => (setting(&name, Some(&typ)), sql),
+            (Value::Typed(sql, typ), Value::Setting(name)) => (sql, setting(&name, Some(&typ))),
+            (Value::Setting(name), Value::Untyped(sql)) => (setting(&name, None), sql),
+            (Value::Untyped(sql), Value::Setting(name)) => (sql, setting(&name, None)),
+            (
+                Value::Typed(left, _) | Value::Untyped(left),
+                Value::Typed(right, _) | Value::Untyped(right),
+            ) => (left, right),
+        };
+
+        let operator = match op {
+            AccessRelationalOp::Eq(_, _) => "=",
+            AccessRelationalOp::Neq(_, _) => "<>",
+            AccessRelationalOp::Lt(_, _) => "<",
+            AccessRelationalOp::Lte(_, _) => "<=",
+            AccessRelationalOp::Gt(_, _) => ">",
+            AccessRelationalOp::Gte(_, _) => ">=",
+            AccessRelationalOp::In(_, _) => unreachable!("Handled above"),
+        };
+
+        Ok(format!("{left} {operator} {right}"))
+    }
+
+    fn value(&mut self, operand: Operand) -> Result<Value> {
+        Ok(match operand {
+            Operand::Column(path, alias) => {
+                let column_id = match path.split_head() {
+                    // The primary key of a related table (such as `self.venue.id`) is the same as
+                    // the foreign key column (see `split_relation`)
+                    (ColumnPathLink::Relation(relation), Some(_)) => relation.self_column_id,
+                    _ => path.leaf_column(),
+                };
+                let column = column_id.get_column(&self.subsystem.database);
+                Value::Typed(
+                    format!("{alias}.\"{}\"", column.name),
+                    sql_type(&column.typ),
+                )
+            }
+            Operand::Count(path, function_call, alias) => {
+                let relation = match path.split_head() {
+                    (ColumnPathLink::Relation(relation), None) => {
+                        (relation.self_column_id, relation.foreign_column_id)
+                    }
+                    _ => bail!("Function calls must be on a relation"),
+                };
+                let (foreign_alias, from) = self.relation_from(relation, &alias);
+                let predicate = self.predicate(&function_call.expr, &foreign_alias)?;
+                Value::Typed(
+                    format!("(SELECT COUNT(*) {from} AND {predicate})"),
+                    "bigint".to_string(),
+                )
+            }
+            Operand::Common(common) => match common {
+                CommonAccessPrimitiveExpression::ContextSelection(selection) => {
+                    Value::Setting(setting_name(selection))
+                }
+                CommonAccessPrimitiveExpression::StringLiteral(value) => {
+                    Value::Untyped(string_literal(value))
+                }
+                CommonAccessPrimitiveExpression::BooleanLiteral(value) => {
+                    Value::Typed(boolean_literal(*value), "boolean".to_string())
+                }
+                CommonAccessPrimitiveExpression::NumberLiteral(value) => {
+                    Value::Typed(value.to_string(), "bigint".to_string())
+                }
+            },
+        })
+    }
+
+    /// The alias for the foreign table of a relation and the `FROM ... WHERE ...` clause that
+    /// correlates it with the table it starts at
+    fn relation_from(
+        &mut self,
+        (self_column_id, foreign_column_id): (ColumnId, ColumnId),
+        alias: &str,
+    ) -> (String, String) {
+        let database = &self.subsystem.database;
+
+        self.next_alias += 1;
+        let foreign_alias = format!("\"{PREFIX}_{}\"", self.next_alias);
+
+        let foreign_table = database.get_table(foreign_column_id.table_id);
+        let from = format!(
+            "FROM {} AS {foreign_alias} WHERE {foreign_alias}.\"{}\" = {alias}.\"{}\"",
+            foreign_table.name.sql_name(),
+            foreign_column_id.get_column(database).name,
+            self_column_id.get_column(database).name,
+        );
+
+        (foreign_alias, from)
+    }
+
+    /// Express an input rule as a rule on the new row. For example, `self.venue.id ==
+    /// AuthContext.venueId` becomes `venue_id = current_setting('exo.auth_context.venue_id')`.
+    fn input_predicate(
+        &self,
+        expr: &InputPredicate,
+        entity_type: &EntityType,
+    ) -> Result<DatabasePredicate> {
+        Ok(match expr {
+            AccessPredicateExpression::BooleanLiteral(value) => {
+                AccessPredicateExpression::BooleanLiteral(*value)
+            }
+            AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Not(expr)) => {
+                AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Not(Box::new(
+                    self.input_predicate(expr, entity_type)?,
+                )))
+            }
+            AccessPredicateExpression::LogicalOp(AccessLogicalExpression::And(left, right)) => {
+                AccessPredicateExpression::LogicalOp(AccessLogicalExpression::And(
+                    Box::new(self.input_predicate(left, entity_type)?),
+                    Box::new(self.input_predicate(right, entity_type)?),
+                ))
+            }
+            AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Or(left, right)) => {
+                AccessPredicateExpression::LogicalOp(AccessLogicalExpression::Or(
+                    Box::new(self.input_predicate(left, entity_type)?),
+                    Box::new(self.input_predicate(right, entity_type)?),
+                ))
+            }
+            AccessPredicateExpression::RelationalOp(op) => {
+                let (left, right) = op.sides();
+                AccessPredicateExpression::RelationalOp(op.combiner()(
+                    Box::new(self.input_operand(left, entity_type)?),
+                    Box::new(self.input_operand(right, entity_type)?),
+                ))
+            }
+        })
+    }
+
+    fn input_operand(
+        &self,
+        expr: &InputAccessPrimitiveExpression,
+        entity_type: &EntityType,
+    ) -> Result<DatabaseAccessPrimitiveExpression> {
+        match expr {
+            InputAccessPrimitiveExpression::Path(path, _) => {
+                let column_id = self.input_column(path, entity_type)?;
+                Ok(DatabaseAccessPrimitiveExpression::Column(
+                    PhysicalColumnPath::leaf(column_id),
+                    None,
+                ))
+            }
+            InputAccessPrimitiveExpression::Function(path, function_call) => Err(anyhow!(
+                "Function `{}` on `{}` in a creation or update rule of `{}` is not supported in a row-level security policy",
+                function_call.name,
+                path.join("."),
+                entity_type.name
+            )),
+            InputAccessPrimitiveExpression::Common(common) => {
+                Ok(DatabaseAccessPrimitiveExpression::Common(common.clone()))
+            }
+        }
+    }
+
+    /// The column of the new row that corresponds to an input path (a scalar field such as
+    /// `self.title` or the primary key of a many-to-one relation such as `self.venue.id`)
+    fn input_column(&self, path: &[String], entity_type: &EntityType) -> Result<ColumnId> {
+        let unsupported = || {
+            anyhow!(
+                "Path `self.{}` in a creation or update rule of `{}` is not supported in a row-level security policy",
+                path.join("."),
+                entity_type.name
+            )
+        };
+
+        let (field_name, rest) = path.split_first().ok_or_else(unsupported)?;
+        let field = entity_type
+            .field_by_name(field_name)
+            .ok_or_else(unsupported)?;
+
+        match (&field.relation, rest) {
+            (PostgresRelation::Pk { column_id } | PostgresRelation::Scalar { column_id }, []) => {
+                Ok(*column_id)
+            }
+            (PostgresRelation::ManyToOne(relation), [pk_field_name])
+                if &relation
+                    .foreign_pk_field_id
+                    .resolve(&self.subsystem.entity_types)
+                    .name
+                    == pk_field_name =>
+            {
+                Ok(relation.self_column_id(&self.subsystem.database))
+            }
+            _ => Err(unsupported()),
+        }
+    }
+}
+
+fn setting(name: &str, typ: Option<&str>) -> String {
+    // A setting that was set only in earlier transactions reverts to an empty string (instead of
+    // `NULL`), so treat both the same way
+    let value = format!("NULLIF(current_setting('{name}', true), '')");
+
+    match typ {
+        Some(typ) => format!("{value}::{typ}"),
+        None => value,
+    }
+}
+
+fn sql_type(typ: &PhysicalColumnType) -> String {
+    match typ {
+        PhysicalColumnType::Enum { enum_name } => format!("\"{enum_name}\""),
+        PhysicalColumnType::Array { typ } => format!("{}[]", sql_type(typ)),
+        _ => typ.get_pg_type().name().to_string(),
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn boolean_literal(value: bool) -> String {
+    if value { "TRUE" } else { "FALSE" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_plugin_interface::{
+        error::ModelSerializationError, serializable_system::SerializableSystem,
+        system_serializer::SystemSerializer,
+    };
+
+    #[cfg_attr(not(target_family = "wasm"), tokio::test)]
+    #[cfg_attr(target_family = "wasm", wasm_bindgen_test::wasm_bindgen_test)]
+    async fn policies_from_access_rules() {
+        let subsystem = create_postgres_system_from_str(
+            r#"
+            context AuthContext {
+                @jwt id: Int
+                @jwt role: String
+            }
+
+            @postgres
+            module ConcertModule {
+                @access(query=self.venue.owner.id == AuthContext.id || AuthContext.role == "admin", mutation=AuthContext.role == "admin")
+                type Concert {
+                    @pk id: Int = autoIncrement()
+                    title: String
+                    venue: Venue
+                }
+
+                @access(query=true, create=self.owner.id == AuthContext.id, update=self.owner.id == AuthContext.id, delete=false)
+                type Venue {
+                    @pk id: Int = autoIncrement()
+                    name: String
+                    owner: User
+                    concerts: Set<Concert>?
+                }
+
+                @access(self.id == AuthContext.id)
+                type User {
+                    @pk id: Int = autoIncrement()
+                    venues: Set<Venue>?
+                }
+            }
+            "#,
+        )
+        .await
+        .unwrap();
+
+        let statements: Vec<_> = policy_statements(&subsystem)
+            .unwrap()
+            .into_iter()
+            .map(|statement| statement.statement)
+            .collect();
+
+        let admin = r#"NULLIF(current_setting('exo.auth_context.role', true), '') = 'admin'"#;
+        let user_id = r#"NULLIF(current_setting('exo.auth_context.id', true), '')::int4"#;
+
+        assert_eq!(
+            statements,
+            vec![
+                r#"ALTER TABLE "concerts" ENABLE ROW LEVEL SECURITY;"#.to_string(),
+                r#"ALTER TABLE "concerts" FORCE ROW LEVEL SECURITY;"#.to_string(),
+                format!(
+                    r#"CREATE POLICY "exo_select" ON "concerts" FOR SELECT USING ((EXISTS (SELECT 1 FROM "venues" AS "exo_1" WHERE "exo_1"."id" = "concerts"."venue_id" AND "exo_1"."owner_id" = {user_id}) OR {admin}));"#
+                ),
+                format!(
+                    r#"CREATE POLICY "exo_insert" ON "concerts" FOR INSERT WITH CHECK ({admin});"#
+                ),
+                format!(
+                    r#"CREATE POLICY "exo_update" ON "concerts" FOR UPDATE USING ({admin}) WITH CHECK ({admin});"#
+                ),
+                format!(r#"CREATE POLICY "exo_delete" ON "concerts" FOR DELETE USING ({admin});"#),
+                r#"ALTER TABLE "venues" ENABLE ROW LEVEL SECURITY;"#.to_string(),
+                r#"ALTER TABLE "venues" FORCE ROW LEVEL SECURITY;"#.to_string(),
+                r#"CREATE POLICY "exo_select" ON "venues" FOR SELECT USING (TRUE);"#.to_string(),
+                format!(
+                    r#"CREATE POLICY "exo_insert" ON "venues" FOR INSERT WITH CHECK ("venues"."owner_id" = {user_id});"#
+                ),
+                format!(
+                    r#"CREATE POLICY "exo_update" ON "venues" FOR UPDATE USING ("venues"."owner_id" = {user_id}) WITH CHECK ("venues"."owner_id" = {user_id});"#
+                ),
+                r#"ALTER TABLE "users" ENABLE ROW LEVEL SECURITY;"#.to_string(),
+                r#"ALTER TABLE "users" FORCE ROW LEVEL SECURITY;"#.to_string(),
+                format!(
+                    r#"CREATE POLICY "exo_select" ON "users" FOR SELECT USING ("users"."id" = {user_id});"#
+                ),
+                format!(
+                    r#"CREATE POLICY "exo_insert" ON "users" FOR INSERT WITH CHECK ("users"."id" = {user_id});"#
+                ),
+                format!(
+                    r#"CREATE POLICY "exo_update" ON "users" FOR UPDATE USING ("users"."id" = {user_id}) WITH CHECK ("users"."id" = {user_id});"#
+                ),
+                format!(
+                    r#"CREATE POLICY "exo_delete" ON "users" FOR DELETE USING ("users"."id" = {user_id});"#
+                ),
+            ]
+        );
+
+        let settings: Vec<_> = context_selections(&subsystem)
+            .into_iter()
+            .map(setting_name)
+            .collect();
+        assert_eq!(settings.len(), 2);
+        assert!(settings.contains(&"exo.auth_context.id".to_string()));
+        assert!(settings.contains(&"exo.auth_context.role".to_string()));
+    }
+
+    async fn create_postgres_system_from_str(
+        model_str: &str,
+    ) -> Result<PostgresSubsystem, ModelSerializationError> {
+        let system = builder::build_system_from_str(
+            model_str,
+            "test.exo".to_string(),
+            vec![Box::new(
+                postgres_model_builder::PostgresSubsystemBuilder {},
+            )],
+        )
+        .await
+        .unwrap();
+
+        deserialize_postgres_subsystem(system)
+    }
+
+    fn deserialize_postgres_subsystem(
+        system: SerializableSystem,
+    ) -> Result<PostgresSubsystem, ModelSerializationError> {
+        system
+            .subsystems
+            .into_iter()
+            .find_map(|subsystem| {
+                (subsystem.id == "postgres")
+                    .then(|| PostgresSubsystem::deserialize(subsystem.serialized_subsystem))
+            })
+            .unwrap_or_else(|| Ok(PostgresSubsystem::default()))
+    }
+}
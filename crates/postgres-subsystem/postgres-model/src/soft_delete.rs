@@ -0,0 +1,57 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Soft deletion of the entities of a type annotated with `@softDelete`.
+//!
+//! The delete mutations of such a type set the deletion time (instead of deleting the rows) and
+//! queries skip the rows with it set. Queries take an `includeDeleted` argument to include them,
+//! and the `restore<Type>` mutations clear the deletion time.
+
+use async_graphql_parser::types::Type;
+use core_plugin_interface::core_model::{
+    mapped_arena::SerializableSlabIndex,
+    type_normalization::Parameter,
+    types::{FieldType, Named},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::types::PostgresPrimitiveType;
+
+/// The name of the parameter to include soft-deleted entities in a query's result
+pub const INCLUDE_DELETED_PARAM_NAME: &str = "includeDeleted";
+
+/// The parameter to include soft-deleted entities such as `includeDeleted: true` in
+/// `todos(includeDeleted: true)`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IncludeDeletedParameter {
+    pub name: String,
+    pub typ: FieldType<IncludeDeletedParameterType>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IncludeDeletedParameterType {
+    pub type_name: String,
+    pub type_id: SerializableSlabIndex<PostgresPrimitiveType>,
+}
+
+impl Named for IncludeDeletedParameterType {
+    fn name(&self) -> &str {
+        &self.type_name
+    }
+}
+
+impl Parameter for IncludeDeletedParameter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typ(&self) -> Type {
+        (&self.typ).into()
+    }
+}
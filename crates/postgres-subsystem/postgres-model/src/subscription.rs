@@ -0,0 +1,35 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+use core_plugin_interface::core_model::type_normalization::Parameter;
+
+use crate::predicate::PredicateParameter;
+
+use super::operation::{OperationParameters, PostgresOperation};
+
+/// Subscription to changes in a collection such as `todos(where: { completed: { eq: false } })`.
+///
+/// Each inserted or updated row that matches the predicate (and is accessible to the subscriber)
+/// produces an event.
+pub type CollectionSubscription = PostgresOperation<CollectionSubscriptionParameters>;
+
+/// Collection subscription parameters
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollectionSubscriptionParameters {
+    /// The predicate parameter such as `where: { completed: { eq: false } }`
+    pub predicate_param: PredicateParameter,
+}
+
+impl OperationParameters for CollectionSubscriptionParameters {
+    fn introspect(&self) -> Vec<&dyn Parameter> {
+        vec![&self.predicate_param]
+    }
+}
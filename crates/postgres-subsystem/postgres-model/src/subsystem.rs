@@ -7,18 +7,23 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::vec;
+use std::{collections::HashSet, vec};
 
 use async_graphql_parser::types::{FieldDefinition, TypeDefinition};
 
 use super::{
-    mutation::PostgresMutation, order::OrderByParameterType, predicate::PredicateParameterType,
+    mutation::{PostgresMutation, PostgresMutationParameters},
+    order::OrderByParameterType,
+    predicate::PredicateParameterType,
     query::PkQuery,
 };
 use crate::{
     access::{DatabaseAccessPrimitiveExpression, InputAccessPrimitiveExpression},
     aggregate::AggregateType,
-    query::{AggregateQuery, CollectionQuery, UniqueQuery},
+    connection::{connection_type_definitions, page_info_type_definition},
+    group_by::{aggregate_field_type_definitions, group_by_type_definitions},
+    query::{AggregateQuery, CollectionQuery, ConnectionQuery, GroupByQuery, UniqueQuery},
+    subscription::CollectionSubscription,
     types::{EntityType, MutationType, PostgresPrimitiveType},
 };
 use core_plugin_interface::{
@@ -50,11 +55,16 @@ pub struct PostgresSubsystem {
     pub collection_queries: MappedArena<CollectionQuery>,
     pub aggregate_queries: MappedArena<AggregateQuery>,
     pub unique_queries: MappedArena<UniqueQuery>,
+    pub connection_queries: MappedArena<ConnectionQuery>,
+    pub group_by_queries: MappedArena<GroupByQuery>,
 
     // mutation related
     pub mutation_types: SerializableSlab<MutationType>, // create, update, delete input types such as `PersonUpdateInput`
     pub mutations: MappedArena<PostgresMutation>,
 
+    // subscription related
+    pub subscriptions: MappedArena<CollectionSubscription>,
+
     pub input_access_expressions:
         SerializableSlab<AccessPredicateExpression<InputAccessPrimitiveExpression>>,
     pub database_access_expressions:
@@ -85,10 +95,22 @@ impl PostgresSubsystem {
             .iter()
             .map(|(_, query)| query.field_definition(self));
 
+        let connection_queries_defn = self
+            .connection_queries
+            .iter()
+            .map(|(_, query)| query.field_definition(self));
+
+        let group_by_queries_defn = self
+            .group_by_queries
+            .iter()
+            .map(|(_, query)| query.field_definition(self));
+
         pk_queries_defn
             .chain(collection_queries_defn)
             .chain(aggregate_queries_defn)
             .chain(unique_queries_defn)
+            .chain(connection_queries_defn)
+            .chain(group_by_queries_defn)
             .collect()
     }
 
@@ -99,6 +121,13 @@ impl PostgresSubsystem {
             .collect()
     }
 
+    pub fn schema_subscriptions(&self) -> Vec<FieldDefinition> {
+        self.subscriptions
+            .iter()
+            .map(|(_, subscription)| subscription.field_definition(self))
+            .collect()
+    }
+
     pub fn schema_types(&self) -> Vec<TypeDefinition> {
         let mut all_type_definitions = vec![];
 
@@ -126,6 +155,50 @@ impl PostgresSubsystem {
             all_type_definitions.push(parameter_type.1.type_definition(self))
         });
 
+        self.connection_queries.iter().for_each(|(_, query)| {
+            let entity_type = query.return_type.typ(&self.entity_types);
+            all_type_definitions.extend(connection_type_definitions(&entity_type.name))
+        });
+
+        if !self.connection_queries.is_empty() {
+            all_type_definitions.push(page_info_type_definition());
+        }
+
+        // Fields with the same aggregate type (such as `IntAgg`) share the types to filter and order by them
+        let mut aggregate_field_type_names = HashSet::new();
+        self.group_by_queries.iter().for_each(|(_, query)| {
+            let entity_type = query.return_type.typ(&self.entity_types);
+            let aggregate_query = &self.aggregate_queries[entity_type.aggregate_query];
+            let parameters = &query.parameters;
+
+            all_type_definitions.extend(group_by_type_definitions(
+                &entity_type.name,
+                aggregate_query.return_type.type_name(),
+                &parameters.by_param,
+                &parameters.having_param,
+                &parameters.order_by_param,
+            ));
+
+            parameters.having_param.fields.iter().for_each(|field| {
+                if aggregate_field_type_names.insert(field.type_name.as_str()) {
+                    all_type_definitions.extend(aggregate_field_type_definitions(field))
+                }
+            });
+        });
+
+        // The single and multi upsert mutations share the same `onConflict` parameter type
+        let mut on_conflict_type_names = HashSet::new();
+        self.mutations.iter().for_each(|(_, mutation)| {
+            if let PostgresMutationParameters::Upsert {
+                on_conflict_param, ..
+            } = &mutation.parameters
+            {
+                if on_conflict_type_names.insert(on_conflict_param.type_name.as_str()) {
+                    all_type_definitions.extend(on_conflict_param.type_definitions())
+                }
+            }
+        });
+
         all_type_definitions
     }
 }
@@ -143,8 +216,11 @@ impl Default for PostgresSubsystem {
             collection_queries: MappedArena::default(),
             aggregate_queries: MappedArena::default(),
             unique_queries: MappedArena::default(),
+            connection_queries: MappedArena::default(),
+            group_by_queries: MappedArena::default(),
             mutation_types: SerializableSlab::new(),
             mutations: MappedArena::default(),
+            subscriptions: MappedArena::default(),
 
             input_access_expressions: SerializableSlab::new(),
             database_access_expressions: SerializableSlab::new(),
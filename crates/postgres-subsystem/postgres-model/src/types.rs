@@ -16,11 +16,15 @@ use crate::relation::OneToManyRelation;
 use crate::subsystem::PostgresSubsystem;
 use crate::vector_distance::VectorDistanceField;
 use async_graphql_parser::types::{
-    FieldDefinition, InputObjectType, ObjectType, Type, TypeDefinition, TypeKind,
+    EnumType, EnumValueDefinition, FieldDefinition, InputObjectType, ObjectType, Type,
+    TypeDefinition, TypeKind,
 };
 use core_plugin_interface::core_model::access::AccessPredicateExpression;
 use core_plugin_interface::core_model::context_type::ContextSelection;
 use core_plugin_interface::core_model::primitive_type::vector_introspection_base_type;
+use core_plugin_interface::core_model::{
+    cache::CachePolicy, cost::cost_directives, rate_limit::RateLimit,
+};
 use core_plugin_interface::core_model::{
     mapped_arena::{SerializableSlab, SerializableSlabIndex},
     type_normalization::{
@@ -69,6 +73,8 @@ impl<'a, CT: Named> PostgresType<'a, CT> {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PostgresPrimitiveType {
     pub name: String,
+    /// The values if this is a user-declared enum (exposed as a GraphQL enum instead of a scalar)
+    pub enum_values: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -84,6 +90,25 @@ pub struct EntityType {
     pub collection_query: SerializableSlabIndex<CollectionQuery>,
     pub aggregate_query: SerializableSlabIndex<AggregateQuery>,
     pub access: Access,
+    /// The CHECK constraints on the entity's table (used to report violations)
+    pub checks: Vec<CheckConstraint>,
+    /// The caching policy for queries returning this entity (`@cache(...)`)
+    pub cache: Option<CachePolicy>,
+    /// The weight of the entity for query cost analysis (`@cost(...)`)
+    pub cost: Option<u32>,
+    /// The rate limit of operations on this entity (`@rateLimit(...)`)
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// A CHECK constraint derived from `@range`, `@maxLength`, or `@check`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckConstraint {
+    /// The name of the constraint in the database
+    pub name: String,
+    /// The field the constraint applies to (`None` for a type-level `@check`)
+    pub field_name: Option<String>,
+    /// A human-readable description of the requirement (such as "must be at most 12 characters long")
+    pub description: String,
 }
 
 pub fn get_field_id(
@@ -141,19 +166,32 @@ impl EntityType {
         self.fields.iter().find(|field| field.name == name)
     }
 
-    pub fn pk_field(&self) -> Option<&PostgresField<EntityType>> {
+    /// The fields that make up the primary key (more than one for a composite primary key)
+    pub fn pk_fields(&self) -> Vec<&PostgresField<EntityType>> {
         self.fields
             .iter()
-            .find(|field| matches!(&field.relation, PostgresRelation::Pk { .. }))
+            .filter(|field| field.relation.is_pk())
+            .collect()
     }
 
+    /// The primary key field. Returns `None` if the primary key is composite.
+    pub fn pk_field(&self) -> Option<&PostgresField<EntityType>> {
+        match self.pk_fields()[..] {
+            [field] if matches!(&field.relation, PostgresRelation::Pk { .. }) => Some(field),
+            _ => None,
+        }
+    }
+
+    /// The id of the primary key field. Returns `None` if the primary key is composite.
     pub fn pk_field_id(
         &self,
         entity_id: SerializableSlabIndex<EntityType>,
     ) -> Option<EntityFieldId> {
+        let pk_field = self.pk_field()?;
+
         self.fields
             .iter()
-            .position(|field| matches!(&field.relation, PostgresRelation::Pk { .. }))
+            .position(|field| field.name == pk_field.name)
             .map(|field_index| EntityFieldId(field_index, entity_id))
     }
 
@@ -177,6 +215,8 @@ pub struct PostgresField<CT> {
     pub dynamic_default_value: Option<ContextSelection>,
     pub readonly: bool,
     pub access: Access,
+    /// The weight of the field for query cost analysis (`@cost(...)`)
+    pub cost: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -208,7 +248,21 @@ impl TypeDefinitionProvider<PostgresSubsystem> for PostgresPrimitiveType {
             description: None,
             name: default_positioned_name(&self.name),
             directives: vec![],
-            kind: TypeKind::Scalar,
+            kind: match &self.enum_values {
+                Some(values) => TypeKind::Enum(EnumType {
+                    values: values
+                        .iter()
+                        .map(|value| {
+                            default_positioned(EnumValueDefinition {
+                                description: None,
+                                value: default_positioned_name(value),
+                                directives: vec![],
+                            })
+                        })
+                        .collect(),
+                }),
+                None => TypeKind::Scalar,
+            },
         }
     }
 }
@@ -249,7 +303,7 @@ impl TypeDefinitionProvider<PostgresSubsystem> for EntityType {
             extend: false,
             description: None,
             name: default_positioned_name(&self.name),
-            directives: vec![],
+            directives: cost_directives(self.cost),
             kind,
         }
     }
@@ -293,7 +347,7 @@ impl<CT> FieldDefinitionProvider<PostgresSubsystem> for PostgresField<CT> {
                     base: base_list_type,
                     nullable: matches!(self.typ, FieldType::Optional(_)),
                 }),
-                directives: vec![],
+                directives: cost_directives(self.cost),
             };
         }
 
@@ -314,6 +368,7 @@ impl<CT> FieldDefinitionProvider<PostgresSubsystem> for PostgresField<CT> {
                     order_by_param,
                     limit_param,
                     offset_param,
+                    include_deleted_param,
                 } = &collection_query.parameters;
 
                 [
@@ -323,6 +378,7 @@ impl<CT> FieldDefinitionProvider<PostgresSubsystem> for PostgresField<CT> {
                     offset_param.input_value(),
                 ]
                 .into_iter()
+                .chain(include_deleted_param.iter().map(|p| p.input_value()))
                 .map(default_positioned)
                 .collect()
             }
@@ -333,7 +389,7 @@ impl<CT> FieldDefinitionProvider<PostgresSubsystem> for PostgresField<CT> {
             name: default_positioned_name(&self.name),
             arguments,
             ty: field_type,
-            directives: vec![],
+            directives: cost_directives(self.cost),
         }
     }
 }
@@ -7,11 +7,13 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use exo_sql::AbstractOperation;
+use exo_sql::{AbstractOperation, TransactionHolder};
 
 use core_plugin_interface::core_resolver::{
-    context::RequestContext, QueryResponse, QueryResponseBody,
+    context::RequestContext, context_extractor::ContextExtractor, value::Val, QueryResponse,
+    QueryResponseBody,
 };
+use postgres_model::row_level_security::setting_name;
 use tokio_postgres::types::FromSqlOwned;
 use tokio_postgres::Row;
 
@@ -25,14 +27,79 @@ pub async fn resolve_operation<'e>(
     subsystem_resolver: &'e PostgresSubsystemResolver,
     request_context: &'e RequestContext<'e>,
 ) -> Result<QueryResponse, PostgresExecutionError> {
+    // Extract the context before locking the transaction holder (extracting a context may
+    // require executing queries of its own)
+    let settings = row_level_security_settings(subsystem_resolver, request_context).await?;
+
     let ctx = request_context.get_base_context();
     let mut tx = ctx.transaction_holder.try_lock().unwrap();
+    tx.set_config(settings);
+
+    resolve_operation_in_transaction(op, subsystem_resolver, &mut tx).await
+}
+
+/// The session settings for row-level security policies (if enabled using
+/// `EXO_POSTGRES_ROW_LEVEL_SECURITY`). Context selections without a value are left unset, so the
+/// policies that depend on them deny access.
+pub(crate) async fn row_level_security_settings(
+    subsystem_resolver: &PostgresSubsystemResolver,
+    request_context: &RequestContext<'_>,
+) -> Result<Vec<(String, String)>, PostgresExecutionError> {
+    let mut settings = vec![];
+
+    for selection in subsystem_resolver
+        .row_level_security_contexts
+        .iter()
+        .flatten()
+    {
+        let value = subsystem_resolver
+            .subsystem
+            .extract_context_selection(request_context, selection)
+            .await?;
+
+        if let Some(value) = value.and_then(setting_value) {
+            settings.push((setting_name(selection), value));
+        }
+    }
 
+    Ok(settings)
+}
+
+fn setting_value(value: &Val) -> Option<String> {
+    match value {
+        Val::Bool(value) => Some(value.to_string()),
+        Val::Number(value) => Some(value.to_string()),
+        Val::String(value) | Val::Enum(value) => Some(value.clone()),
+        Val::List(values) => {
+            // An array literal such as `{"admin","user"}`
+            let elements = values
+                .iter()
+                .map(|value| {
+                    setting_value(value).map(|element| {
+                        format!("\"{}\"", element.replace('\\', "\\\\").replace('"', "\\\""))
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(format!("{{{}}}", elements.join(",")))
+        }
+        Val::Null | Val::Object(_) | Val::Binary(_) => None,
+    }
+}
+
+/// Resolve an operation using the given transaction holder (instead of the one associated with the
+/// request). The caller is responsible for finalizing the transaction.
+pub async fn resolve_operation_in_transaction(
+    op: &AbstractOperation,
+    subsystem_resolver: &PostgresSubsystemResolver,
+    tx: &mut TransactionHolder,
+) -> Result<QueryResponse, PostgresExecutionError> {
     let mut result = subsystem_resolver
         .executor
-        .execute(op, &mut tx, &subsystem_resolver.subsystem.database)
+        .execute(op, tx, &subsystem_resolver.subsystem.database)
         .await
-        .map_err(PostgresExecutionError::Postgres)?;
+        .map_err(|e| {
+            PostgresExecutionError::from_database_error(e, &subsystem_resolver.subsystem)
+        })?;
 
     let body = if result.len() == 1 {
         let string_result = extractor(result.swap_remove(0))?;
@@ -68,6 +68,7 @@ impl<'a> AccessPredicate<'a> for AbstractPredicateWrapper {
 pub enum SolvedPrimitiveExpression {
     Common(Option<Val>),
     Column(PhysicalColumnPath),
+    Count(ColumnPath),
 }
 
 #[derive(Debug)]
@@ -100,9 +101,33 @@ impl<'a> AccessSolver<'a, DatabaseAccessPrimitiveExpression, AbstractPredicateWr
                 DatabaseAccessPrimitiveExpression::Column(column_path, _) => {
                     Some(SolvedPrimitiveExpression::Column(column_path.clone()))
                 }
-                DatabaseAccessPrimitiveExpression::Function(_, _) => {
-                    // TODO: Fix this through better types
-                    unreachable!("Function calls should not remain in the resolver expression")
+                DatabaseAccessPrimitiveExpression::Function(column_path, function_call) => {
+                    // The model builder leaves only `count` calls (it inlines `some` and expresses
+                    // `all` and `none` in terms of `count`)
+                    let predicate = <PostgresSubsystem as AccessSolver<
+                        '_,
+                        DatabaseAccessPrimitiveExpression,
+                        AbstractPredicateWrapper,
+                    >>::solve(
+                        solver, request_context, None, &function_call.expr
+                    )
+                    .await?;
+
+                    match (predicate, column_path.split_last_relation()) {
+                        (Some(predicate), Some((path, foreign_column_id))) => {
+                            Some(SolvedPrimitiveExpression::Count(ColumnPath::Count {
+                                path,
+                                foreign_column_id,
+                                predicate: Box::new(predicate.0),
+                            }))
+                        }
+                        (None, _) => None,
+                        (_, None) => {
+                            return Err(AccessSolverError::Generic(
+                                "Function calls must be on a relation".into(),
+                            ))
+                        }
+                    }
                 }
             })
         }
@@ -134,6 +159,27 @@ impl<'a> AccessSolver<'a, DatabaseAccessPrimitiveExpression, AbstractPredicateWr
                     to_column_path(&right_col),
                 ))),
 
+                (
+                    SolvedPrimitiveExpression::Count(left_count),
+                    SolvedPrimitiveExpression::Count(right_count),
+                ) => Ok(Some(column_predicate(left_count, right_count))),
+                (
+                    SolvedPrimitiveExpression::Count(count),
+                    SolvedPrimitiveExpression::Column(column),
+                ) => Ok(Some(column_predicate(count, to_column_path(&column)))),
+                (
+                    SolvedPrimitiveExpression::Column(column),
+                    SolvedPrimitiveExpression::Count(count),
+                ) => Ok(Some(column_predicate(to_column_path(&column), count))),
+                (
+                    SolvedPrimitiveExpression::Count(count),
+                    SolvedPrimitiveExpression::Common(Some(value)),
+                ) => Ok(Some(column_predicate(count, count_literal(value)?))),
+                (
+                    SolvedPrimitiveExpression::Common(Some(value)),
+                    SolvedPrimitiveExpression::Count(count),
+                ) => Ok(Some(column_predicate(count_literal(value)?, count))),
+
                 (
                     SolvedPrimitiveExpression::Common(Some(left_value)),
                     SolvedPrimitiveExpression::Common(Some(right_value)),
@@ -228,6 +274,7 @@ impl<'a> AccessSolver<'a, InputAccessPrimitiveExpression, AbstractPredicateWrapp
         async fn reduce_primitive_expression<'a>(
             solver: &PostgresSubsystem,
             request_context: &'a RequestContext<'a>,
+            input_context: Option<&'a Val>,
             expr: &'a InputAccessPrimitiveExpression,
         ) -> Result<Option<SolvedJsonPrimitiveExpression>, AccessSolverError> {
             Ok(match expr {
@@ -239,15 +286,52 @@ impl<'a> AccessSolver<'a, InputAccessPrimitiveExpression, AbstractPredicateWrapp
                 InputAccessPrimitiveExpression::Path(path, _) => {
                     Some(SolvedJsonPrimitiveExpression::Path(path.clone()))
                 }
-                InputAccessPrimitiveExpression::Function(_, _) => {
-                    unreachable!("Function calls should not remain in the resolver expression")
+                InputAccessPrimitiveExpression::Function(path, function_call) => {
+                    // The model builder leaves only `count` calls (see the database counterpart)
+                    let elements = match input_context.and_then(|input| resolve_value(input, path))
+                    {
+                        Some(Val::List(elements)) => elements.iter().collect(),
+                        Some(Val::Null) => vec![],
+                        Some(element) => vec![element],
+                        None => {
+                            // The user didn't provide the elements, so treat the count the same
+                            // way as any other path without a value (see below)
+                            return Ok(Some(SolvedJsonPrimitiveExpression::Path(path.clone())));
+                        }
+                    };
+
+                    let mut count: u64 = 0;
+                    for element in elements {
+                        let predicate =
+                            <PostgresSubsystem as AccessSolver<
+                                '_,
+                                InputAccessPrimitiveExpression,
+                                AbstractPredicateWrapper,
+                            >>::solve(
+                                solver, request_context, Some(element), &function_call.expr
+                            )
+                            .await?;
+
+                        match predicate {
+                            Some(AbstractPredicateWrapper(AbstractPredicate::True)) => count += 1,
+                            Some(AbstractPredicateWrapper(AbstractPredicate::False)) => {}
+                            // An input predicate can't have a residue, so an element that doesn't
+                            // reduce to true or false leaves the count undetermined
+                            _ => return Ok(None),
+                        }
+                    }
+
+                    Some(SolvedJsonPrimitiveExpression::Common(Some(Val::Number(
+                        count.into(),
+                    ))))
                 }
             })
         }
 
         let (left, right) = op.sides();
-        let left = reduce_primitive_expression(self, request_context, left).await?;
-        let right = reduce_primitive_expression(self, request_context, right).await?;
+        let left = reduce_primitive_expression(self, request_context, input_context, left).await?;
+        let right =
+            reduce_primitive_expression(self, request_context, input_context, right).await?;
 
         let (left, right) = match (left, right) {
             (Some(left), Some(right)) => (left, right),
@@ -327,9 +411,14 @@ fn match_paths<'a>(
     input_context: Option<&'a Val>,
     match_values: fn(&Val, &Val) -> bool,
 ) -> bool {
-    let left_value = resolve_value(input_context.unwrap(), left_path).unwrap();
-    let right_value = resolve_value(input_context.unwrap(), right_path).unwrap();
-    match_values(left_value, right_value)
+    let left_value = resolve_value(input_context.unwrap(), left_path);
+    let right_value = resolve_value(input_context.unwrap(), right_path);
+    match (left_value, right_value) {
+        (Some(left_value), Some(right_value)) => match_values(left_value, right_value),
+        // As with a single path, if the user didn't provide a value, the original value will
+        // remain unchanged (such as the elements counted by both sides of `all`)
+        _ => true,
+    }
 }
 
 fn resolve_value<'a>(val: &'a Val, path: &'a Vec<String>) -> Option<&'a Val> {
@@ -349,6 +438,17 @@ fn to_column_path(column_id: &PhysicalColumnPath) -> ColumnPath {
     ColumnPath::Physical(column_id.clone())
 }
 
+/// Converts a value to a literal column path to compare with a count (which is a `bigint`)
+fn count_literal(value: Val) -> Result<ColumnPath, AccessSolverError> {
+    match value {
+        Val::Number(v) => v
+            .as_i64()
+            .map(|v| ColumnPath::Param(SQLParamContainer::i64(v)))
+            .ok_or_else(|| AccessSolverError::Generic("Invalid count literal".into())),
+        _ => Err(AccessSolverError::Generic("Invalid count literal".into())),
+    }
+}
+
 /// Converts a value to a literal column path
 fn literal_column(value: Val) -> ColumnPath {
     match value {
@@ -501,11 +601,14 @@ mod tests {
                 map: HashMap::new(),
             },
             TrustedDocuments::all(),
-            Schema::new(vec![], vec![], vec![]),
+            None,
+            Schema::new(vec![], vec![], vec![], vec![]),
             None.into(),
             Box::new(MapEnvironment::from(HashMap::new())),
             10,
             10,
+            None,
+            None,
         );
 
         TestSystem {
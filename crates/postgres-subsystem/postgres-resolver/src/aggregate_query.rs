@@ -14,7 +14,6 @@ use super::{
 use crate::operation_resolver::OperationSelectionResolver;
 use async_recursion::async_recursion;
 use async_trait::async_trait;
-use core_plugin_interface::core_model::types::OperationReturnType;
 use core_plugin_interface::core_resolver::{
     context::RequestContext, validation::field::ValidatedField,
 };
@@ -59,7 +58,8 @@ impl OperationSelectionResolver for AggregateQuery {
         let root_physical_table_id = return_postgres_type.table_id;
 
         let content_object = content_select(
-            &self.return_type,
+            return_postgres_type,
+            self.return_type.type_name(),
             &field.subfields,
             subsystem,
             request_context,
@@ -73,19 +73,33 @@ impl OperationSelectionResolver for AggregateQuery {
             order_by: None,
             offset: None,
             limit: None,
+            keyset: None,
+            group_by: None,
+            include_deleted: false,
         })
     }
 }
 
+/// Compute the selection for an aggregate type such as `ConcertAgg` (of the given `entity_type`)
 #[async_recursion]
-async fn content_select<'content>(
-    return_type: &OperationReturnType<EntityType>,
+pub(crate) async fn content_select<'content>(
+    entity_type: &EntityType,
+    agg_type_name: &str,
     fields: &'content [ValidatedField],
     subsystem: &'content PostgresSubsystem,
     request_context: &'content RequestContext<'content>,
 ) -> Result<Vec<AliasedSelectionElement>, PostgresExecutionError> {
     futures::stream::iter(fields.iter())
-        .then(|field| async { map_field(return_type, field, subsystem, request_context).await })
+        .then(|field| async {
+            map_field(
+                entity_type,
+                agg_type_name,
+                field,
+                subsystem,
+                request_context,
+            )
+            .await
+        })
         .collect::<Vec<Result<_, _>>>()
         .await
         .into_iter()
@@ -93,16 +107,15 @@ async fn content_select<'content>(
 }
 
 async fn map_field<'content>(
-    return_type: &OperationReturnType<EntityType>,
+    entity_type: &EntityType,
+    agg_type_name: &str,
     field: &'content ValidatedField,
-    subsystem: &'content PostgresSubsystem,
+    _subsystem: &'content PostgresSubsystem,
     _request_context: &'content RequestContext<'content>,
 ) -> Result<AliasedSelectionElement, PostgresExecutionError> {
     let selection_elem = if field.name == "__typename" {
-        SelectionElement::Constant(return_type.type_name().to_string())
+        SelectionElement::Constant(agg_type_name.to_string())
     } else {
-        let entity_type = &return_type.typ(&subsystem.entity_types);
-
         let model_field = entity_type.field_by_name(&field.name).unwrap();
         let model_field_type = &model_field.typ.innermost().type_name;
         // This is duplicated from builder.
@@ -20,6 +20,7 @@ use exo_sql::{
     array_util::{self, ArrayEntry},
     Column, FloatBits, IntBits, PhysicalColumn, PhysicalColumnType, SQLParamContainer,
 };
+use postgres_model::subsystem::PostgresSubsystem;
 #[cfg(feature = "bigdecimal")]
 use std::str::FromStr;
 
@@ -59,6 +60,41 @@ pub(crate) fn literal_column(
         .map_err(PostgresExecutionError::CastError)
 }
 
+/// Check that a string value is no longer than the `@maxLength` of its field allows.
+///
+/// The column type (`VARCHAR(n)`) rejects a longer string before Postgres evaluates the
+/// `<table>_<column>_max_length_check` constraint, so we check the length here to report it as a
+/// violation of that constraint.
+pub(crate) fn check_max_length(
+    value: &Val,
+    associated_column: &PhysicalColumn,
+    subsystem: &PostgresSubsystem,
+) -> Result<(), PostgresExecutionError> {
+    match (value, &associated_column.typ) {
+        (
+            Val::String(string),
+            PhysicalColumnType::String {
+                max_length: Some(max_length),
+            },
+        ) if string.chars().count() > *max_length => {
+            let table = subsystem.database.get_table(associated_column.table_id);
+            let constraint_name = format!(
+                "{}_{}_max_length_check",
+                table.name.fully_qualified_name_with_sep("_"),
+                associated_column.name
+            );
+
+            // Without such a constraint (for example, with `@dbtype("VARCHAR(n)")`), we let the
+            // database reject the value
+            match PostgresExecutionError::check_violation(&constraint_name, subsystem) {
+                Some(validation_error) => Err(validation_error),
+                None => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
 pub(crate) fn literal_column_path(
     value: &Val,
     destination_type: &PhysicalColumnType,
@@ -77,7 +113,12 @@ pub(crate) fn cast_value(
         Val::String(v) => cast_string(v, destination_type).map(Some),
         Val::Bool(v) => Ok(Some(SQLParamContainer::bool(*v))),
         Val::Null => Ok(None),
-        Val::Enum(v) => Ok(Some(SQLParamContainer::string(v.to_string()))), // We might need guidance from the database to do a correct translation
+        Val::Enum(v) => Ok(Some(match destination_type {
+            PhysicalColumnType::Enum { enum_name } => {
+                SQLParamContainer::enum_value(v.to_string(), enum_name)
+            }
+            _ => SQLParamContainer::string(v.to_string()), // We might need guidance from the database to do a correct translation
+        })),
         Val::List(elems) => cast_list(elems, destination_type),
         Val::Object(_) => Ok(Some(cast_object(value, destination_type))),
         Val::Binary(bytes) => Ok(Some(SQLParamContainer::bytes(bytes.clone()))),
@@ -125,12 +166,32 @@ fn cast_list(
                         .map_err(|error| DatabaseError::BoxedError(error.into()))
                 };
 
-            array_util::to_sql_param(elems, destination_type, array_entry, &cast_value_with_error)
-                .map_err(CastError::Postgres)
+            let param = array_util::to_sql_param(
+                elems,
+                destination_type,
+                array_entry,
+                &cast_value_with_error,
+            )
+            .map_err(CastError::Postgres)?;
+
+            Ok(param.map(|param| match enum_cast_type(destination_type) {
+                Some(cast_type) => param.with_cast(cast_type),
+                None => param,
+            }))
         }
     }
 }
 
+/// The SQL type to cast a parameter of the given type to (enum values are sent as text, which
+/// Postgres will not implicitly convert to an enum)
+fn enum_cast_type(destination_type: &PhysicalColumnType) -> Option<String> {
+    match destination_type {
+        PhysicalColumnType::Enum { enum_name } => Some(format!("\"{enum_name}\"")),
+        PhysicalColumnType::Array { typ } => enum_cast_type(typ).map(|typ| format!("{typ}[]")),
+        _ => None,
+    }
+}
+
 fn cast_number(
     number: &serde_json::Number,
     destination_type: &PhysicalColumnType,
@@ -316,6 +377,10 @@ fn cast_string(
 
         PhysicalColumnType::Array { typ } => cast_string(string, typ)?,
 
+        PhysicalColumnType::Enum { enum_name } => {
+            SQLParamContainer::enum_value(string.to_owned(), enum_name)
+        }
+
         _ => SQLParamContainer::string(string.to_owned()),
     };
 
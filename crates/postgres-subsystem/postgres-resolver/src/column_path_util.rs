@@ -7,7 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use exo_sql::{ColumnPathLink, PhysicalColumnPath};
+use exo_sql::{ColumnPathLink, Database, PhysicalColumnPath};
 
 pub fn to_column_path(
     parent_column_path: &Option<PhysicalColumnPath>,
@@ -26,3 +26,20 @@ pub fn to_column_path(
             .map(|next_column_path_link| PhysicalColumnPath::init(next_column_path_link.clone())),
     }
 }
+
+/// Compute the path to the generated `tsvector` column of a `@searchable` field (whose own column
+/// is the leaf of `column_path_link`) along with its text-search language
+pub fn to_text_search_column_path(
+    parent_column_path: &Option<PhysicalColumnPath>,
+    column_path_link: &Option<ColumnPathLink>,
+    database: &Database,
+) -> Option<(PhysicalColumnPath, String)> {
+    let column_id = column_path_link.as_ref()?.self_column_id();
+    let (text_search_column_id, language) = database.get_text_search_column(column_id)?;
+
+    to_column_path(
+        parent_column_path,
+        &Some(ColumnPathLink::Leaf(text_search_column_id)),
+    )
+    .map(|path| (path, language.to_string()))
+}
@@ -0,0 +1,473 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Resolution of Relay-style connection queries such as `todosConnection(first: 10, after: "...")`.
+//!
+//! We execute a single select that returns one JSON object per row, holding the selected node(s)
+//! and the values of the order-by columns (from which we form the cursor). To page through the
+//! rows, we use the keyset of the previous page's cursor (see [`AbstractKeyset`]) rather than an
+//! offset, and we fetch one more row than requested to tell if there is another page. The rest
+//! of the connection (edges, cursors, and page info) is then assembled from those rows.
+
+use base64::Engine;
+use core_plugin_interface::core_resolver::{
+    context::RequestContext, validation::field::ValidatedField, value::Val, QueryResponse,
+    QueryResponseBody,
+};
+use exo_sql::{
+    AbstractKeyset, AbstractOperation, AbstractOrderBy, AbstractOrderByExpr, AbstractPredicate,
+    AbstractSelect, AliasedSelectionElement, ColumnId, ColumnPath, ColumnPathLink, Database, Limit,
+    Ordering, PhysicalColumnPath, Selection, SelectionCardinality, SelectionElement,
+};
+use postgres_model::{
+    connection::{
+        edge_type_name, CURSOR_FIELD, EDGES_FIELD, END_CURSOR_FIELD, HAS_NEXT_PAGE_FIELD,
+        HAS_PREVIOUS_PAGE_FIELD, NODE_FIELD, PAGE_INFO_FIELD, PAGE_INFO_TYPE_NAME,
+        START_CURSOR_FIELD,
+    },
+    query::{ConnectionQuery, ConnectionQueryParameters},
+    subsystem::PostgresSubsystem,
+    types::EntityType,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    abstract_operation_resolver::resolve_operation,
+    auth_util::check_access,
+    cast::literal_column_path,
+    plugin::subsystem_resolver::PostgresSubsystemResolver,
+    postgres_execution_error::PostgresExecutionError,
+    postgres_query::{compute_order_by, content_select},
+    predicate_mapper::compute_predicate,
+    sql_mapper::{extract_and_map, SQLOperationKind},
+    util::find_arg,
+};
+
+/// The key (in each row) that holds the order-by values for the cursor. Node selections use keys
+/// of the form `<edges-output-name>.<node-output-name>`, so this cannot clash with them.
+const CURSOR_KEY: &str = "__cursor";
+
+pub(crate) async fn resolve_connection_query<'a>(
+    query: &'a ConnectionQuery,
+    field: &'a ValidatedField,
+    request_context: &'a RequestContext<'a>,
+    subsystem_resolver: &'a PostgresSubsystemResolver,
+) -> Result<QueryResponse, PostgresExecutionError> {
+    let subsystem = &subsystem_resolver.subsystem;
+    let entity_type = query.return_type.typ(&subsystem.entity_types);
+
+    let ConnectionQueryParameters {
+        predicate_param,
+        order_by_param,
+        first_param,
+        after_param,
+        last_param,
+        before_param,
+    } = &query.parameters;
+
+    let arguments = &field.arguments;
+
+    let first = extract_and_map(first_param, arguments, subsystem, request_context).await?;
+    let last = extract_and_map(last_param, arguments, subsystem, request_context).await?;
+
+    let page_size = match (&first, &last) {
+        (Some(_), Some(_)) => {
+            return Err(PostgresExecutionError::Validation(
+                last_param.name.clone(),
+                format!("Cannot be combined with '{}'", first_param.name),
+            ))
+        }
+        (Some(Limit(count)), None) => Some(page_size(*count, &first_param.name)?),
+        (None, Some(Limit(count))) => Some(page_size(*count, &last_param.name)?),
+        (None, None) => None,
+    };
+    // When paginating backward, we select rows in the reverse order and reverse them again once
+    // fetched (so the edges are still in the requested order)
+    let backward = last.is_some();
+
+    let keyset_columns = keyset_columns(
+        compute_order_by(order_by_param, arguments, subsystem, request_context).await?,
+        entity_type,
+        &order_by_param.name,
+        &subsystem.database,
+    )?;
+
+    let after = find_arg(arguments, &after_param.name)
+        .map(|cursor| decode_cursor(cursor, &after_param.name, &keyset_columns, subsystem))
+        .transpose()?;
+    let before = find_arg(arguments, &before_param.name)
+        .map(|cursor| decode_cursor(cursor, &before_param.name, &keyset_columns, subsystem))
+        .transpose()?;
+
+    let order_by = AbstractOrderBy(
+        keyset_columns
+            .iter()
+            .map(|(column_id, ordering)| {
+                let ordering = match (backward, ordering) {
+                    (true, Ordering::Asc) => Ordering::Desc,
+                    (true, Ordering::Desc) => Ordering::Asc,
+                    (false, ordering) => *ordering,
+                };
+                (
+                    AbstractOrderByExpr::Column(PhysicalColumnPath::leaf(*column_id)),
+                    ordering,
+                )
+            })
+            .collect(),
+    );
+
+    let keyset = if backward {
+        AbstractKeyset::new(&order_by, before, after, &subsystem.database)
+    } else {
+        AbstractKeyset::new(&order_by, after, before, &subsystem.database)
+    }?;
+
+    let node_fields = node_fields(field);
+
+    let access_predicate = {
+        let mut access_predicate = check_access(
+            entity_type,
+            &[],
+            &SQLOperationKind::Retrieve,
+            subsystem,
+            request_context,
+            None,
+        )
+        .await?;
+
+        for (_, node_field) in node_fields.iter() {
+            let node_access_predicate = check_access(
+                entity_type,
+                &node_field.subfields,
+                &SQLOperationKind::Retrieve,
+                subsystem,
+                request_context,
+                None,
+            )
+            .await?;
+            access_predicate = AbstractPredicate::and(access_predicate, node_access_predicate);
+        }
+
+        access_predicate
+    };
+
+    let predicate = AbstractPredicate::and(
+        compute_predicate(predicate_param, arguments, subsystem, request_context).await?,
+        access_predicate,
+    );
+
+    let mut row_selection = vec![AliasedSelectionElement::new(
+        CURSOR_KEY.to_string(),
+        SelectionElement::Object(
+            keyset_columns
+                .iter()
+                .enumerate()
+                .map(|(index, (column_id, _))| {
+                    (index.to_string(), SelectionElement::Physical(*column_id))
+                })
+                .collect(),
+        ),
+    )];
+
+    for (key, node_field) in node_fields.iter() {
+        let node_content = content_select(
+            entity_type,
+            &node_field.subfields,
+            subsystem,
+            request_context,
+        )
+        .await?;
+
+        row_selection.push(AliasedSelectionElement::new(
+            key.clone(),
+            SelectionElement::Object(
+                node_content
+                    .into_iter()
+                    .map(AliasedSelectionElement::into_parts)
+                    .collect(),
+            ),
+        ));
+    }
+
+    let select = AbstractSelect {
+        table_id: entity_type.table_id,
+        selection: Selection::Json(row_selection, SelectionCardinality::Many),
+        predicate,
+        order_by: Some(order_by),
+        offset: None,
+        // Fetch one extra row to determine if there is another page
+        limit: page_size.map(|page_size| Limit(page_size + 1)),
+        keyset: Some(keyset),
+        group_by: None,
+        include_deleted: false,
+    };
+
+    let response = resolve_operation(
+        &AbstractOperation::Select(select),
+        subsystem_resolver,
+        request_context,
+    )
+    .await?;
+
+    let mut rows = match response.body.to_json() {
+        Ok(Value::Array(rows)) => rows,
+        Ok(_) => vec![],
+        Err(e) => {
+            return Err(PostgresExecutionError::Generic(format!(
+                "Unable to parse the connection result: {e}"
+            )))
+        }
+    };
+
+    let has_more = match page_size {
+        Some(page_size) if rows.len() > page_size as usize => {
+            rows.truncate(page_size as usize);
+            true
+        }
+        _ => false,
+    };
+
+    if backward {
+        rows.reverse();
+    }
+
+    let page_info = PageInfo {
+        has_next_page: has_more && !backward,
+        has_previous_page: has_more && backward,
+        start_cursor: rows.first().map(encode_cursor),
+        end_cursor: rows.last().map(encode_cursor),
+    };
+
+    Ok(QueryResponse {
+        body: QueryResponseBody::Json(connection_value(
+            field,
+            query.return_type.type_name(),
+            entity_type,
+            &rows,
+            &page_info,
+        )),
+        headers: vec![],
+    })
+}
+
+struct PageInfo {
+    has_next_page: bool,
+    has_previous_page: bool,
+    start_cursor: Option<String>,
+    end_cursor: Option<String>,
+}
+
+/// Compute the columns that determine the position of each row (and thus the cursor).
+///
+/// These are the order-by columns followed by the primary key (unless already ordered by it), so
+/// that the position is unique even if the order-by columns have duplicate values.
+fn keyset_columns(
+    order_by: Option<AbstractOrderBy>,
+    entity_type: &EntityType,
+    order_by_param_name: &str,
+    database: &Database,
+) -> Result<Vec<(ColumnId, Ordering)>, PostgresExecutionError> {
+    let mut columns = order_by
+        .map(|order_by| {
+            order_by
+                .0
+                .into_iter()
+                .map(|(expr, ordering)| match expr {
+                    AbstractOrderByExpr::Column(path) => match path.split_head() {
+                        (ColumnPathLink::Leaf(column_id), None) => Ok((column_id, ordering)),
+                        _ => Err(PostgresExecutionError::Validation(
+                            order_by_param_name.into(),
+                            format!(
+                                "Connection queries may order only by the scalar fields of '{}'",
+                                entity_type.name
+                            ),
+                        )),
+                    },
+                    AbstractOrderByExpr::VectorDistance(..) => {
+                        Err(PostgresExecutionError::Validation(
+                            order_by_param_name.into(),
+                            "Connection queries may not order by vector distance".into(),
+                        ))
+                    }
+                    AbstractOrderByExpr::TextSearchRank(..) => {
+                        Err(PostgresExecutionError::Validation(
+                            order_by_param_name.into(),
+                            "Connection queries may not order by search rank".into(),
+                        ))
+                    }
+                    AbstractOrderByExpr::Function(..) => Err(PostgresExecutionError::Validation(
+                        order_by_param_name.into(),
+                        "Connection queries may not order by an aggregate".into(),
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let pk_column_ids = database.get_pk_column_ids(entity_type.table_id);
+
+    if pk_column_ids.is_empty() {
+        return Err(PostgresExecutionError::Generic(format!(
+            "Connection queries require a primary key for '{}'",
+            entity_type.name
+        )));
+    }
+
+    // Break ties by the primary key (all of its columns for a composite primary key)
+    for pk_column_id in pk_column_ids {
+        if !columns
+            .iter()
+            .any(|(column_id, _)| *column_id == pk_column_id)
+        {
+            columns.push((pk_column_id, Ordering::Asc));
+        }
+    }
+
+    Ok(columns)
+}
+
+/// The `node` fields along with the key under which each is selected in a row
+fn node_fields(field: &ValidatedField) -> Vec<(String, &ValidatedField)> {
+    field
+        .subfields
+        .iter()
+        .filter(|edges_field| edges_field.name == EDGES_FIELD)
+        .flat_map(|edges_field| {
+            edges_field
+                .subfields
+                .iter()
+                .filter(|node_field| node_field.name == NODE_FIELD)
+                .map(|node_field| (node_key(edges_field, node_field), node_field))
+        })
+        .collect()
+}
+
+fn node_key(edges_field: &ValidatedField, node_field: &ValidatedField) -> String {
+    format!("{}.{}", edges_field.output_name(), node_field.output_name())
+}
+
+fn page_size(count: i64, param_name: &str) -> Result<i64, PostgresExecutionError> {
+    if count < 0 {
+        Err(PostgresExecutionError::Validation(
+            param_name.into(),
+            "Must not be negative".into(),
+        ))
+    } else {
+        Ok(count)
+    }
+}
+
+fn encode_cursor(row: &Value) -> String {
+    let values = row
+        .get(CURSOR_KEY)
+        .and_then(Value::as_object)
+        .map(|values| values.values().cloned().collect())
+        .unwrap_or_default();
+
+    base64::engine::general_purpose::STANDARD.encode(Value::Array(values).to_string())
+}
+
+fn decode_cursor(
+    cursor: &Val,
+    param_name: &str,
+    keyset_columns: &[(ColumnId, Ordering)],
+    subsystem: &PostgresSubsystem,
+) -> Result<Vec<ColumnPath>, PostgresExecutionError> {
+    let invalid_cursor =
+        || PostgresExecutionError::Validation(param_name.into(), "Invalid cursor".into());
+
+    let Val::String(cursor) = cursor else {
+        return Err(invalid_cursor());
+    };
+
+    let values = base64::engine::general_purpose::STANDARD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<Vec<Value>>(&bytes).ok())
+        .ok_or_else(invalid_cursor)?;
+
+    // A cursor is valid only for the same order-by it was produced with
+    if values.len() != keyset_columns.len() {
+        return Err(invalid_cursor());
+    }
+
+    values
+        .into_iter()
+        .zip(keyset_columns)
+        .map(|(value, (column_id, _))| {
+            let column = column_id.get_column(&subsystem.database);
+            literal_column_path(&Val::from(value), &column.typ)
+        })
+        .collect()
+}
+
+/// Form the connection value with the fields selected in the query
+fn connection_value(
+    field: &ValidatedField,
+    connection_type_name: &str,
+    entity_type: &EntityType,
+    rows: &[Value],
+    page_info: &PageInfo,
+) -> Value {
+    let mut connection = Map::new();
+
+    for subfield in field.subfields.iter() {
+        let value = match subfield.name.as_str() {
+            "__typename" => Value::String(connection_type_name.to_string()),
+            EDGES_FIELD => Value::Array(
+                rows.iter()
+                    .map(|row| edge_value(subfield, entity_type, row))
+                    .collect(),
+            ),
+            PAGE_INFO_FIELD => page_info_value(subfield, page_info),
+            _ => Value::Null,
+        };
+        connection.insert(subfield.output_name(), value);
+    }
+
+    Value::Object(connection)
+}
+
+fn edge_value(edges_field: &ValidatedField, entity_type: &EntityType, row: &Value) -> Value {
+    let mut edge = Map::new();
+
+    for subfield in edges_field.subfields.iter() {
+        let value = match subfield.name.as_str() {
+            "__typename" => Value::String(edge_type_name(&entity_type.name)),
+            CURSOR_FIELD => Value::String(encode_cursor(row)),
+            NODE_FIELD => row
+                .get(node_key(edges_field, subfield))
+                .cloned()
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        };
+        edge.insert(subfield.output_name(), value);
+    }
+
+    Value::Object(edge)
+}
+
+fn page_info_value(page_info_field: &ValidatedField, page_info: &PageInfo) -> Value {
+    let mut value = Map::new();
+
+    for subfield in page_info_field.subfields.iter() {
+        let field_value = match subfield.name.as_str() {
+            "__typename" => Value::String(PAGE_INFO_TYPE_NAME.to_string()),
+            HAS_NEXT_PAGE_FIELD => Value::Bool(page_info.has_next_page),
+            HAS_PREVIOUS_PAGE_FIELD => Value::Bool(page_info.has_previous_page),
+            START_CURSOR_FIELD => page_info.start_cursor.clone().into(),
+            END_CURSOR_FIELD => page_info.end_cursor.clone().into(),
+            _ => Value::Null,
+        };
+        value.insert(subfield.output_name(), field_value);
+    }
+
+    Value::Object(value)
+}
@@ -14,7 +14,7 @@ use core_plugin_interface::core_resolver::context_extractor::ContextExtractor;
 use core_plugin_interface::core_resolver::value::Val;
 use exo_sql::{
     AbstractInsert, AbstractSelect, ColumnId, ColumnValuePair, InsertionElement, InsertionRow,
-    ManyToOne, NestedInsertion,
+    NestedInsertion,
 };
 use futures::future::{join_all, try_join_all};
 use postgres_model::{
@@ -127,8 +127,8 @@ async fn map_single<'a>(
                     map_self_column(*column_id, field, field_arg, subsystem).await
                 }
 
-                PostgresRelation::ManyToOne(ManyToOneRelation { relation_id, .. }) => {
-                    let ManyToOne { self_column_id, .. } = relation_id.deref(&subsystem.database);
+                PostgresRelation::ManyToOne(relation) => {
+                    let self_column_id = relation.self_column_id(&subsystem.database);
                     map_self_column(self_column_id, field, field_arg, subsystem).await
                 }
 
@@ -179,6 +179,8 @@ async fn map_self_column<'a>(
         _ => argument,
     };
 
+    cast::check_max_length(argument_value, key_column, subsystem)?;
+
     let value_column = cast::literal_column(argument_value, key_column).with_context(format!(
         "trying to convert the '{}' field to the '{}' type",
         field.name,
@@ -0,0 +1,549 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Resolution of group-by queries such as `todosGroupBy(by: [completed], having: {id: {count: {gt: 2}}})`.
+//!
+//! We form a single select with a group-by clause over the `by` columns. Each group is returned as
+//! an object with the grouped values (`key`) and the aggregates over the group's rows (`agg`).
+
+use async_trait::async_trait;
+use core_plugin_interface::core_resolver::{
+    context::RequestContext, validation::field::ValidatedField, value::Val,
+};
+use exo_sql::{
+    AbstractGroupBy, AbstractHaving, AbstractOrderBy, AbstractOrderByExpr, AbstractPredicate,
+    AbstractSelect, AggregateOperand, AliasedSelectionElement, ColumnId, FloatBits, Function,
+    IntBits, PhysicalColumnPath, PhysicalColumnType, Predicate, Selection, SelectionCardinality,
+    SelectionElement,
+};
+use postgres_model::{
+    aggregate::ScalarAggregateFieldKind,
+    group_by::{
+        group_key_type_name, group_type_name, GroupAggregateField, GroupByParameter,
+        GroupHavingParameter, GroupOrderByParameter, AGG_FIELD, KEY_FIELD,
+    },
+    query::GroupByQuery,
+    subsystem::PostgresSubsystem,
+    types::EntityType,
+};
+
+use crate::{
+    aggregate_query::content_select,
+    auth_util::{check_access, check_retrieve_access},
+    cast::cast_value,
+    operation_resolver::OperationSelectionResolver,
+    order_by_mapper::ordering,
+    postgres_execution_error::PostgresExecutionError,
+    predicate_mapper::compute_predicate,
+    sql_mapper::SQLOperationKind,
+    util::{find_arg, Arguments},
+};
+
+#[async_trait]
+impl OperationSelectionResolver for GroupByQuery {
+    async fn resolve_select<'a>(
+        &'a self,
+        field: &'a ValidatedField,
+        request_context: &'a RequestContext<'a>,
+        subsystem: &'a PostgresSubsystem,
+    ) -> Result<AbstractSelect, PostgresExecutionError> {
+        let entity_type = self.return_type.typ(&subsystem.entity_types);
+        let parameters = &self.parameters;
+        let arguments = &field.arguments;
+
+        let group_column_ids = group_column_ids(&parameters.by_param, arguments)?;
+
+        let access_predicate = {
+            let agg_fields = field
+                .subfields
+                .iter()
+                .filter(|subfield| subfield.name == AGG_FIELD)
+                .flat_map(|subfield| subfield.subfields.iter())
+                .collect::<Vec<_>>();
+
+            let mut access_predicate = check_access(
+                entity_type,
+                &[],
+                &SQLOperationKind::Retrieve,
+                subsystem,
+                request_context,
+                None,
+            )
+            .await?;
+
+            // Grouping by a field reveals its values (even if not selected), so we check the access
+            // of the grouped fields along with the aggregated ones
+            let field_names = parameters
+                .by_param
+                .fields
+                .iter()
+                .filter(|key_field| group_column_ids.contains(&key_field.column_id))
+                .map(|key_field| key_field.field_name.as_str())
+                .chain(agg_fields.iter().map(|field| field.name.as_str()));
+
+            for field_name in field_names {
+                if let Some(entity_field) = entity_type.field_by_name(field_name) {
+                    let field_access_predicate = check_retrieve_access(
+                        &subsystem.database_access_expressions[entity_field.access.read],
+                        subsystem,
+                        request_context,
+                    )
+                    .await?;
+
+                    if field_access_predicate == AbstractPredicate::False {
+                        return Err(PostgresExecutionError::Authorization);
+                    }
+                    access_predicate =
+                        AbstractPredicate::and(access_predicate, field_access_predicate);
+                }
+            }
+
+            access_predicate
+        };
+
+        let predicate = AbstractPredicate::and(
+            compute_predicate(
+                &parameters.predicate_param,
+                arguments,
+                subsystem,
+                request_context,
+            )
+            .await?,
+            access_predicate,
+        );
+
+        let having = match find_arg(arguments, &parameters.having_param.name) {
+            Some(having) => compute_having(having, &parameters.having_param, subsystem)?,
+            None => AbstractHaving::True,
+        };
+
+        let order_by = match find_arg(arguments, &parameters.order_by_param.name) {
+            Some(order_by) => compute_order_by(
+                order_by,
+                &parameters.order_by_param,
+                &parameters.by_param,
+                &parameters.having_param,
+                &group_column_ids,
+            )?,
+            None => None,
+        };
+
+        let content_object = group_content_select(
+            entity_type,
+            &field.subfields,
+            &parameters.by_param,
+            &group_column_ids,
+            subsystem,
+            request_context,
+        )
+        .await?;
+
+        Ok(AbstractSelect {
+            table_id: entity_type.table_id,
+            selection: Selection::Json(content_object, SelectionCardinality::Many),
+            predicate,
+            order_by,
+            offset: None,
+            limit: None,
+            keyset: None,
+            group_by: Some(AbstractGroupBy {
+                column_ids: group_column_ids,
+                having,
+            }),
+            include_deleted: false,
+        })
+    }
+}
+
+/// The columns to group by based on the `by` argument (such as `by: [completed, userId]`)
+fn group_column_ids(
+    by_param: &GroupByParameter,
+    arguments: &Arguments,
+) -> Result<Vec<ColumnId>, PostgresExecutionError> {
+    let validation_error =
+        |message: String| PostgresExecutionError::Validation(by_param.name.clone(), message);
+
+    let values = match find_arg(arguments, &by_param.name) {
+        Some(Val::List(values)) => values.iter().collect(),
+        Some(value @ (Val::Enum(_) | Val::String(_))) => vec![value],
+        _ => vec![],
+    };
+
+    let mut column_ids = vec![];
+
+    for value in values {
+        let name = match value {
+            Val::Enum(name) => name.as_str(),
+            Val::String(name) => name.as_str(),
+            value => return Err(validation_error(format!("Invalid group-by field {value}"))),
+        };
+
+        let key_field = by_param
+            .field(name)
+            .ok_or_else(|| validation_error(format!("Invalid group-by field '{name}'")))?;
+
+        if !column_ids.contains(&key_field.column_id) {
+            column_ids.push(key_field.column_id);
+        }
+    }
+
+    if column_ids.is_empty() {
+        Err(validation_error(
+            "Must specify at least one field to group by".to_string(),
+        ))
+    } else {
+        Ok(column_ids)
+    }
+}
+
+async fn group_content_select<'content>(
+    entity_type: &EntityType,
+    fields: &'content [ValidatedField],
+    by_param: &GroupByParameter,
+    group_column_ids: &[ColumnId],
+    subsystem: &'content PostgresSubsystem,
+    request_context: &'content RequestContext<'content>,
+) -> Result<Vec<AliasedSelectionElement>, PostgresExecutionError> {
+    let mut elements = vec![];
+
+    for field in fields {
+        let selection_elem = if field.name == "__typename" {
+            SelectionElement::Constant(group_type_name(&entity_type.name))
+        } else if field.name == KEY_FIELD {
+            SelectionElement::Object(
+                field
+                    .subfields
+                    .iter()
+                    .map(|subfield| {
+                        key_selection_element(subfield, entity_type, by_param, group_column_ids)
+                            .map(|elem| (subfield.output_name(), elem))
+                    })
+                    .collect::<Result<_, _>>()?,
+            )
+        } else if field.name == AGG_FIELD {
+            let agg_type_name = subsystem.aggregate_queries[entity_type.aggregate_query]
+                .return_type
+                .type_name();
+
+            let agg_content = content_select(
+                entity_type,
+                agg_type_name,
+                &field.subfields,
+                subsystem,
+                request_context,
+            )
+            .await?;
+
+            SelectionElement::Object(
+                agg_content
+                    .into_iter()
+                    .map(AliasedSelectionElement::into_parts)
+                    .collect(),
+            )
+        } else {
+            return Err(PostgresExecutionError::Generic(format!(
+                "Invalid field '{}' for a group",
+                field.name
+            )));
+        };
+
+        elements.push(AliasedSelectionElement::new(
+            field.output_name(),
+            selection_elem,
+        ));
+    }
+
+    Ok(elements)
+}
+
+fn key_selection_element(
+    field: &ValidatedField,
+    entity_type: &EntityType,
+    by_param: &GroupByParameter,
+    group_column_ids: &[ColumnId],
+) -> Result<SelectionElement, PostgresExecutionError> {
+    if field.name == "__typename" {
+        return Ok(SelectionElement::Constant(group_key_type_name(
+            &entity_type.name,
+        )));
+    }
+
+    match by_param.field(&field.name) {
+        Some(key_field) if group_column_ids.contains(&key_field.column_id) => {
+            Ok(SelectionElement::Physical(key_field.column_id))
+        }
+        _ => Err(PostgresExecutionError::Validation(
+            by_param.name.clone(),
+            format!(
+                "Field '{}' must be included in '{}' to be selected in '{KEY_FIELD}'",
+                field.name, by_param.name
+            ),
+        )),
+    }
+}
+
+/// Compute the predicate for the `having` argument such as `having: {id: {count: {gt: 2}}}`
+fn compute_having(
+    argument: &Val,
+    having_param: &GroupHavingParameter,
+    subsystem: &PostgresSubsystem,
+) -> Result<AbstractHaving, PostgresExecutionError> {
+    let validation_error =
+        |message: String| PostgresExecutionError::Validation(having_param.name.clone(), message);
+
+    let arguments = match argument {
+        Val::Object(arguments) => arguments,
+        Val::Null => return Ok(AbstractHaving::True),
+        _ => return Err(validation_error(format!("Invalid argument {argument}"))),
+    };
+
+    arguments.iter().try_fold(
+        AbstractHaving::True,
+        |acc, (name, value)| -> Result<_, PostgresExecutionError> {
+            let predicate = match (name.as_str(), value) {
+                (_, Val::Null) => AbstractHaving::True,
+                ("and", Val::List(elems)) => elems.iter().try_fold(
+                    AbstractHaving::True,
+                    |acc, elem| -> Result<_, PostgresExecutionError> {
+                        Ok(Predicate::and(
+                            acc,
+                            compute_having(elem, having_param, subsystem)?,
+                        ))
+                    },
+                )?,
+                ("or", Val::List(elems)) => elems.iter().try_fold(
+                    AbstractHaving::False,
+                    |acc, elem| -> Result<_, PostgresExecutionError> {
+                        Ok(Predicate::or(
+                            acc,
+                            compute_having(elem, having_param, subsystem)?,
+                        ))
+                    },
+                )?,
+                ("not", value) => !compute_having(value, having_param, subsystem)?,
+                (name, value) => {
+                    let field = having_param
+                        .field(name)
+                        .ok_or_else(|| validation_error(format!("Invalid field '{name}'")))?;
+                    field_having(value, field, having_param, subsystem)?
+                }
+            };
+
+            Ok(Predicate::and(acc, predicate))
+        },
+    )
+}
+
+/// Compute the predicate for a field such as `id: {count: {gt: 2}, max: {lt: 10}}`
+fn field_having(
+    argument: &Val,
+    field: &GroupAggregateField,
+    having_param: &GroupHavingParameter,
+    subsystem: &PostgresSubsystem,
+) -> Result<AbstractHaving, PostgresExecutionError> {
+    let validation_error =
+        |message: String| PostgresExecutionError::Validation(having_param.name.clone(), message);
+
+    let Val::Object(aggregates) = argument else {
+        return Err(validation_error(format!(
+            "Invalid argument for field '{}'",
+            field.name
+        )));
+    };
+
+    let column_type = &field.column_id.get_column(&subsystem.database).typ;
+
+    aggregates.iter().try_fold(
+        AbstractHaving::True,
+        |acc, (kind_name, ops)| -> Result<_, PostgresExecutionError> {
+            let Some((kind, _)) = field
+                .aggregates
+                .iter()
+                .find(|(kind, _)| kind.name() == kind_name)
+            else {
+                return Err(validation_error(format!(
+                    "Invalid aggregate '{kind_name}' for field '{}'",
+                    field.name
+                )));
+            };
+
+            let ops = match ops {
+                Val::Object(ops) => ops,
+                Val::Null => return Ok(acc),
+                _ => {
+                    return Err(validation_error(format!(
+                        "Invalid argument for aggregate '{kind_name}' of field '{}'",
+                        field.name
+                    )))
+                }
+            };
+
+            let function = AggregateOperand::Function(Function::Named {
+                function_name: kind.name().to_string(),
+                column_id: field.column_id,
+            });
+            let (param_type, param_cast) = aggregate_param_type(kind, column_type);
+
+            ops.iter().try_fold(
+                acc,
+                |acc, (op_name, value)| -> Result<_, PostgresExecutionError> {
+                    let param = match cast_value(value, &param_type)
+                        .map_err(PostgresExecutionError::CastError)?
+                    {
+                        Some(param) => match param_cast {
+                            Some(cast) => param.with_cast(cast.to_string()),
+                            None => param,
+                        },
+                        None => return Ok(acc),
+                    };
+
+                    let lhs = function.clone();
+                    let rhs = AggregateOperand::Param(param);
+
+                    let predicate = match op_name.as_str() {
+                        "eq" => Predicate::Eq(lhs, rhs),
+                        "neq" => Predicate::Neq(lhs, rhs),
+                        "lt" => Predicate::Lt(lhs, rhs),
+                        "lte" => Predicate::Lte(lhs, rhs),
+                        "gt" => Predicate::Gt(lhs, rhs),
+                        "gte" => Predicate::Gte(lhs, rhs),
+                        _ => {
+                            return Err(validation_error(format!(
+                                "Operator '{op_name}' is not supported for aggregates"
+                            )))
+                        }
+                    };
+
+                    Ok(Predicate::and(acc, predicate))
+                },
+            )
+        },
+    )
+}
+
+/// The type of the parameter to compare an aggregate with (along with a cast, if the database may
+/// not infer the type of the parameter correctly)
+fn aggregate_param_type(
+    kind: &ScalarAggregateFieldKind,
+    column_type: &PhysicalColumnType,
+) -> (PhysicalColumnType, Option<&'static str>) {
+    let int64 = PhysicalColumnType::Int { bits: IntBits::_64 };
+    let float64 = PhysicalColumnType::Float {
+        bits: FloatBits::_64,
+    };
+
+    match (kind, column_type) {
+        (ScalarAggregateFieldKind::Count, _) => (int64, None),
+        (ScalarAggregateFieldKind::Min | ScalarAggregateFieldKind::Max, _) => {
+            (column_type.clone(), None)
+        }
+        // `sum` over a `bigint` column returns a `numeric`
+        (ScalarAggregateFieldKind::Sum, PhysicalColumnType::Int { .. }) => (int64, Some("bigint")),
+        // `avg` over an integer column returns a `numeric`
+        (
+            ScalarAggregateFieldKind::Sum | ScalarAggregateFieldKind::Avg,
+            PhysicalColumnType::Int { .. } | PhysicalColumnType::Float { .. },
+        ) => (float64, Some("double precision")),
+        (ScalarAggregateFieldKind::Sum | ScalarAggregateFieldKind::Avg, _) => {
+            (column_type.clone(), None)
+        }
+    }
+}
+
+/// Compute the order-by for the `orderBy` argument such as `orderBy: [{agg: {id: {count: DESC}}}, {key: {userId: ASC}}]`
+fn compute_order_by(
+    argument: &Val,
+    order_by_param: &GroupOrderByParameter,
+    by_param: &GroupByParameter,
+    having_param: &GroupHavingParameter,
+    group_column_ids: &[ColumnId],
+) -> Result<Option<AbstractOrderBy>, PostgresExecutionError> {
+    let validation_error =
+        |message: String| PostgresExecutionError::Validation(order_by_param.name.clone(), message);
+
+    // Each element must specify exactly one entry (since the order of entries in an object is not significant)
+    let single_entry = |value: &'_ Val| -> Result<Option<(String, Val)>, PostgresExecutionError> {
+        match value {
+            Val::Object(entries) => {
+                let mut entries = entries.iter().filter(|(_, value)| **value != Val::Null);
+                match (entries.next(), entries.next()) {
+                    (Some((name, value)), None) => Ok(Some((name.clone(), value.clone()))),
+                    (None, _) => Ok(None),
+                    _ => Err(validation_error(
+                        "Each element must specify exactly one field to order by".to_string(),
+                    )),
+                }
+            }
+            Val::Null => Ok(None),
+            _ => Err(validation_error(format!("Invalid argument {value}"))),
+        }
+    };
+
+    let elements = match argument {
+        Val::List(elements) => elements.iter().collect(),
+        Val::Null => vec![],
+        element => vec![element],
+    };
+
+    let mut order_by = vec![];
+
+    for element in elements {
+        let Some((part, value)) = single_entry(element)? else {
+            continue;
+        };
+        let Some((field_name, value)) = single_entry(&value)? else {
+            continue;
+        };
+
+        let expr = match part.as_str() {
+            KEY_FIELD => match by_param.field(&field_name) {
+                Some(key_field) if group_column_ids.contains(&key_field.column_id) => {
+                    AbstractOrderByExpr::Column(PhysicalColumnPath::leaf(key_field.column_id))
+                }
+                _ => {
+                    return Err(validation_error(format!(
+                        "Field '{field_name}' must be included in '{}' to order by",
+                        by_param.name
+                    )))
+                }
+            },
+            AGG_FIELD => {
+                let field = having_param
+                    .field(&field_name)
+                    .ok_or_else(|| validation_error(format!("Invalid field '{field_name}'")))?;
+                let Some((kind_name, ordering_value)) = single_entry(&value)? else {
+                    continue;
+                };
+                let (kind, _) = field
+                    .aggregates
+                    .iter()
+                    .find(|(kind, _)| kind.name() == kind_name)
+                    .ok_or_else(|| {
+                        validation_error(format!(
+                            "Invalid aggregate '{kind_name}' for field '{field_name}'"
+                        ))
+                    })?;
+
+                order_by.push((
+                    AbstractOrderByExpr::Function(Function::Named {
+                        function_name: kind.name().to_string(),
+                        column_id: field.column_id,
+                    }),
+                    ordering(&ordering_value)?,
+                ));
+                continue;
+            }
+            _ => return Err(validation_error(format!("Invalid field '{part}'"))),
+        };
+
+        order_by.push((expr, ordering(&value)?));
+    }
+
+    Ok((!order_by.is_empty()).then_some(AbstractOrderBy(order_by)))
+}
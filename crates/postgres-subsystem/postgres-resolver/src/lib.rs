@@ -15,7 +15,9 @@ mod aggregate_query;
 mod auth_util;
 mod cast;
 mod column_path_util;
+mod connection_query;
 mod create_data_param_mapper;
+mod group_by_query;
 mod limit_offset_mapper;
 mod operation_resolver;
 mod order_by_mapper;
@@ -23,7 +25,9 @@ mod plugin;
 mod postgres_execution_error;
 mod postgres_mutation;
 mod postgres_query;
+mod postgres_subscription;
 mod predicate_mapper;
+mod query_cache;
 mod sql_mapper;
 mod update_data_param_mapper;
 mod util;
@@ -12,8 +12,10 @@ use futures::future::join_all;
 
 use crate::util::to_pg_vector;
 use crate::{
-    auth_util::check_retrieve_access, column_path_util::to_column_path,
-    postgres_execution_error::PostgresExecutionError, sql_mapper::SQLMapper,
+    auth_util::check_retrieve_access,
+    column_path_util::{to_column_path, to_text_search_column_path},
+    postgres_execution_error::PostgresExecutionError,
+    sql_mapper::SQLMapper,
 };
 use core_plugin_interface::core_resolver::context::RequestContext;
 use core_plugin_interface::core_resolver::value::Val;
@@ -163,6 +165,67 @@ async fn order_by_pair<'a>(
                                 "Invalid vector order by parameter".into(),
                             )),
                         },
+                        // A `@searchable` field may be ordered either by its rank for the search
+                        // terms (`{title: {rank: "...", order: DESC}}`) or by its value (`{title: {order: ASC}}`)
+                        OrderByParameterTypeKind::TextSearch => {
+                            match parameter_value {
+                                Val::Object(elems) => match elems.get("rank") {
+                                    Some(Val::String(terms)) => {
+                                        let (text_search_column_path, language) =
+                                            to_text_search_column_path(
+                                                &parent_column_path,
+                                                &parameter.column_path_link,
+                                                &subsystem.database,
+                                            )
+                                            .ok_or_else(|| {
+                                                PostgresExecutionError::Validation(
+                                                parameter_name.into(),
+                                                "Only @searchable fields may be ordered by rank"
+                                                    .into(),
+                                            )
+                                            })?;
+
+                                        // Best matches first, unless specified otherwise
+                                        let default_order = Val::String("DESC".to_owned());
+                                        let order = elems.get("order").unwrap_or(&default_order);
+
+                                        ordering(order).map(|ordering| {
+                                            AbstractOrderBy(vec![(
+                                                AbstractOrderByExpr::TextSearchRank(
+                                                    ColumnPath::Physical(text_search_column_path),
+                                                    ColumnPath::Param(SQLParamContainer::string(
+                                                        terms.clone(),
+                                                    )),
+                                                    language,
+                                                ),
+                                                ordering,
+                                            )])
+                                        })
+                                    }
+                                    None | Some(Val::Null) => {
+                                        let new_column_path = new_column_path.unwrap();
+
+                                        let default_order = Val::String("ASC".to_owned());
+                                        let order = elems.get("order").unwrap_or(&default_order);
+
+                                        ordering(order).map(|ordering| {
+                                            AbstractOrderBy(vec![(
+                                                AbstractOrderByExpr::Column(new_column_path),
+                                                ordering,
+                                            )])
+                                        })
+                                    }
+                                    _ => Err(PostgresExecutionError::Validation(
+                                        parameter_name.into(),
+                                        "Invalid search terms".into(),
+                                    )),
+                                },
+                                _ => Err(PostgresExecutionError::Validation(
+                                    parameter_name.into(),
+                                    "Invalid search order by parameter".into(),
+                                )),
+                            }
+                        }
                         OrderByParameterTypeKind::Composite { .. } => {
                             OrderByParameterInput {
                                 param: parameter,
@@ -186,7 +249,7 @@ async fn order_by_pair<'a>(
     }
 }
 
-fn ordering(argument: &Val) -> Result<Ordering, PostgresExecutionError> {
+pub(crate) fn ordering(argument: &Val) -> Result<Ordering, PostgresExecutionError> {
     fn str_ordering(value: &str) -> Result<Ordering, PostgresExecutionError> {
         if value == "ASC" {
             Ok(Ordering::Asc)
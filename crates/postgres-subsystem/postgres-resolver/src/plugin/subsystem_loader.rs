@@ -10,6 +10,7 @@
 use super::PostgresSubsystemResolver;
 use async_trait::async_trait;
 
+use common::env_const::{EXO_POSTGRES_READINESS_VERIFY_SCHEMA, EXO_POSTGRES_ROW_LEVEL_SECURITY};
 use core_plugin_interface::{
     core_resolver::plugin::SubsystemResolver,
     interface::{SubsystemLoader, SubsystemLoadingError},
@@ -17,7 +18,7 @@ use core_plugin_interface::{
 };
 use exo_env::Environment;
 use exo_sql::{DatabaseClientManager, DatabaseExecutor};
-use postgres_model::subsystem::PostgresSubsystem;
+use postgres_model::{row_level_security, subsystem::PostgresSubsystem};
 
 pub struct PostgresSubsystemLoader {
     pub existing_client: Option<DatabaseClientManager>,
@@ -36,6 +37,9 @@ impl SubsystemLoader for PostgresSubsystemLoader {
     ) -> Result<Box<dyn SubsystemResolver + Send + Sync>, SubsystemLoadingError> {
         let subsystem = PostgresSubsystem::deserialize(serialized_subsystem)?;
 
+        #[cfg(feature = "network")]
+        let mut notification_listener = None;
+
         let database_client = if let Some(existing) = self.existing_client.take() {
             existing
         } else {
@@ -57,6 +61,12 @@ impl SubsystemLoader for PostgresSubsystemLoader {
                     .map(|s| s == "true")
                     .unwrap_or(true);
 
+                // Change notifications need their own connection, so we create a listener
+                // only if the subsystem has subscriptions (it connects lazily)
+                if !subsystem.subscriptions.is_empty() {
+                    notification_listener = Some(exo_sql::NotificationListener::new(&url));
+                }
+
                 DatabaseClientManager::from_url(&url, check_connection, pool_size)
                     .await
                     .map_err(|e| SubsystemLoadingError::BoxedError(Box::new(e)))?
@@ -70,10 +80,38 @@ impl SubsystemLoader for PostgresSubsystemLoader {
         };
         let executor = DatabaseExecutor { database_client };
 
+        // The cache relies on the system clock to expire entries, which isn't available in a WASM
+        // environment
+        #[cfg(not(target_family = "wasm"))]
+        let query_cache = crate::query_cache::QueryCache::from_env(env).map(std::sync::Arc::new);
+        #[cfg(target_family = "wasm")]
+        let query_cache = None;
+
+        let enable_row_level_security = env
+            .get(EXO_POSTGRES_ROW_LEVEL_SECURITY)
+            .map(|s| s == "true")
+            .unwrap_or(false);
+        let row_level_security_contexts = enable_row_level_security.then(|| {
+            row_level_security::context_selections(&subsystem)
+                .into_iter()
+                .cloned()
+                .collect()
+        });
+
+        let verify_schema_on_readiness = env
+            .get(EXO_POSTGRES_READINESS_VERIFY_SCHEMA)
+            .map(|s| s == "true")
+            .unwrap_or(false);
+
         Ok(Box::new(PostgresSubsystemResolver {
             id: self.id(),
             subsystem,
             executor,
+            query_cache,
+            row_level_security_contexts,
+            verify_schema_on_readiness,
+            #[cfg(feature = "network")]
+            notification_listener,
         }))
     }
 }
@@ -7,29 +7,49 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::sync::Arc;
+
 use crate::{
-    abstract_operation_resolver::resolve_operation, operation_resolver::OperationResolver,
-    postgres_execution_error::PostgresExecutionError,
+    abstract_operation_resolver::resolve_operation, connection_query::resolve_connection_query,
+    operation_resolver::OperationResolver, postgres_execution_error::PostgresExecutionError,
+    query_cache::QueryCache,
 };
 use async_graphql_parser::types::{FieldDefinition, OperationType, TypeDefinition};
 use async_trait::async_trait;
 use core_plugin_interface::{
+    core_model::{cache::CachePolicy, context_type::ContextSelection, rate_limit::RateLimit},
     core_resolver::{
         context::RequestContext,
-        plugin::{SubsystemResolutionError, SubsystemResolver},
+        metrics::PoolStats,
+        plugin::{SubscriptionStream, SubsystemResolutionError, SubsystemResolver},
+        rate_limit::RateLimitBucket,
+        readiness::ReadinessCheck,
         system_resolver::SystemResolver,
         validation::field::ValidatedField,
         InterceptedOperation, QueryResponse,
     },
     interception::InterceptorIndex,
 };
-use exo_sql::DatabaseExecutor;
-use postgres_model::subsystem::PostgresSubsystem;
+#[cfg(feature = "network")]
+use exo_sql::NotificationListener;
+use exo_sql::{AbstractOperation, DatabaseExecutor};
+use postgres_model::{migration::Migration, subsystem::PostgresSubsystem};
 
 pub struct PostgresSubsystemResolver {
     pub id: &'static str,
     pub subsystem: PostgresSubsystem,
     pub executor: DatabaseExecutor,
+    /// Listener for change notifications (available only with a direct database connection)
+    #[cfg(feature = "network")]
+    pub notification_listener: Option<NotificationListener>,
+    /// Cache of responses to queries on `@cache` types (if enabled with `EXO_QUERY_CACHE_SIZE`)
+    pub query_cache: Option<Arc<QueryCache>>,
+    /// The context selections to set as session settings for row-level security policies (if
+    /// enabled with `EXO_POSTGRES_ROW_LEVEL_SECURITY`)
+    pub row_level_security_contexts: Option<Vec<ContextSelection>>,
+    /// Whether the readiness check should verify that the database schema matches the model (if
+    /// enabled with `EXO_POSTGRES_READINESS_VERIFY_SCHEMA`)
+    pub verify_schema_on_readiness: bool,
 }
 
 #[async_trait]
@@ -47,6 +67,18 @@ impl SubsystemResolver for PostgresSubsystemResolver {
     ) -> Result<Option<QueryResponse>, SubsystemResolutionError> {
         let operation_name = &field.name;
 
+        // Connection queries assemble their response from the selected rows (instead of returning
+        // the database's response as is), so are resolved separately
+        if matches!(operation_type, OperationType::Query) {
+            if let Some(query) = self.subsystem.connection_queries.get_by_key(operation_name) {
+                let response =
+                    resolve_connection_query(query, field, request_context, self).await?;
+                let cache_policy = query.return_type.typ(&self.subsystem.entity_types).cache;
+
+                return Ok(Some(with_cache_control(response, cache_policy)));
+            }
+        }
+
         let operation = match operation_type {
             OperationType::Query => match self.subsystem.pk_queries.get_by_key(operation_name) {
                 Some(query) => Some(query.resolve(field, request_context, &self.subsystem).await),
@@ -62,7 +94,16 @@ impl SubsystemResolver for PostgresSubsystemResolver {
                             Some(query) => {
                                 Some(query.resolve(field, request_context, &self.subsystem).await)
                             }
-                            None => None,
+                            None => {
+                                match self.subsystem.group_by_queries.get_by_key(operation_name) {
+                                    Some(query) => Some(
+                                        query
+                                            .resolve(field, request_context, &self.subsystem)
+                                            .await,
+                                    ),
+                                    None => None,
+                                }
+                            }
                         },
                     },
                 },
@@ -79,20 +120,49 @@ impl SubsystemResolver for PostgresSubsystemResolver {
                     None => None,
                 }
             }
-            OperationType::Subscription => Some(Err(PostgresExecutionError::Generic(
-                "Subscriptions are not supported".to_string(),
-            ))),
+            // Subscriptions are resolved through `subscribe`
+            OperationType::Subscription => None,
         };
 
         match operation {
             Some(Ok(operation)) => Ok(Some(
-                resolve_operation(&operation, self, request_context).await?,
+                self.resolve_cached_operation(&operation, request_context)
+                    .await?,
             )),
             Some(Err(e)) => Err(e.into()),
             None => Ok(None),
         }
     }
 
+    async fn subscribe<'a>(
+        &'a self,
+        field: &'a ValidatedField,
+        request_context: &'a RequestContext<'a>,
+        _system_resolver: &'a SystemResolver,
+    ) -> Result<Option<SubscriptionStream<'a>>, SubsystemResolutionError> {
+        let Some(subscription) = self.subsystem.subscriptions.get_by_key(&field.name) else {
+            return Ok(None);
+        };
+
+        #[cfg(feature = "network")]
+        {
+            let stream =
+                crate::postgres_subscription::subscribe(subscription, field, request_context, self)
+                    .await?;
+
+            Ok(Some(stream))
+        }
+
+        #[cfg(not(feature = "network"))]
+        {
+            let _ = (subscription, request_context);
+            Err(PostgresExecutionError::Generic(
+                "Subscriptions are not supported in this environment".to_string(),
+            )
+            .into())
+        }
+    }
+
     async fn invoke_interceptor<'a>(
         &'a self,
         _interceptor_index: InterceptorIndex,
@@ -103,6 +173,70 @@ impl SubsystemResolver for PostgresSubsystemResolver {
         Err(SubsystemResolutionError::NoInterceptorFound)
     }
 
+    async fn rate_limit<'a>(
+        &'a self,
+        field: &'a ValidatedField,
+        operation_type: OperationType,
+        request_context: &'a RequestContext<'a>,
+    ) -> Result<Option<RateLimitBucket>, SubsystemResolutionError> {
+        let Some(rate_limit) = self.operation_rate_limit(&field.name, operation_type) else {
+            return Ok(None);
+        };
+
+        let bucket = RateLimitBucket::new(rate_limit, &self.subsystem, request_context)
+            .await
+            .map_err(PostgresExecutionError::from)?;
+
+        Ok(Some(bucket))
+    }
+
+    async fn pool_stats(&self) -> Vec<PoolStats> {
+        self.executor
+            .database_client
+            .pool_status()
+            .map(|status| PoolStats {
+                pool: "postgres",
+                name: "default".to_string(),
+                size: status.size,
+                in_use: status.size.saturating_sub(status.available),
+                max_size: Some(status.max_size),
+            })
+            .into_iter()
+            .collect()
+    }
+
+    async fn check_readiness(&self) -> Vec<ReadinessCheck> {
+        let database_client = &self.executor.database_client;
+
+        let connection = match database_client.get_client().await {
+            Ok(client) => client
+                .simple_query("SELECT 1")
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        let mut checks = vec![];
+
+        let connected = connection.is_ok();
+        checks.push(match connection {
+            Ok(()) => ReadinessCheck::ok("postgres.connection", None),
+            Err(e) => ReadinessCheck::error("postgres.connection", e),
+        });
+
+        if self.verify_schema_on_readiness && connected {
+            checks.push(
+                match Migration::verify(database_client, &self.subsystem).await {
+                    Ok(()) => ReadinessCheck::ok("postgres.schema", None),
+                    Err(e) => ReadinessCheck::error("postgres.schema", e.to_string().trim_end()),
+                },
+            );
+        }
+
+        checks
+    }
+
     fn schema_queries(&self) -> Vec<FieldDefinition> {
         self.subsystem.schema_queries()
     }
@@ -111,11 +245,149 @@ impl SubsystemResolver for PostgresSubsystemResolver {
         self.subsystem.schema_mutations()
     }
 
+    fn schema_subscriptions(&self) -> Vec<FieldDefinition> {
+        self.subsystem.schema_subscriptions()
+    }
+
     fn schema_types(&self) -> Vec<TypeDefinition> {
         self.subsystem.schema_types()
     }
 }
 
+impl PostgresSubsystemResolver {
+    /// Resolve an operation, taking the caching policy of the selected entity type into account.
+    ///
+    /// A query sets the `Cache-Control` header and may be served from (and saved to) the query
+    /// cache. A mutation invalidates the cached responses that depend on the tables it modifies
+    /// (once its changes are committed, so that a concurrent query can't cache the old data).
+    async fn resolve_cached_operation<'a>(
+        &'a self,
+        operation: &AbstractOperation,
+        request_context: &'a RequestContext<'a>,
+    ) -> Result<QueryResponse, PostgresExecutionError> {
+        let database = &self.subsystem.database;
+
+        let AbstractOperation::Select(select) = operation else {
+            let response = resolve_operation(operation, self, request_context).await?;
+
+            if let Some(query_cache) = &self.query_cache {
+                let query_cache = query_cache.clone();
+                let table_ids = operation.table_ids(database);
+                request_context
+                    .get_base_context()
+                    .on_commit(move || query_cache.invalidate(&table_ids))
+                    .await;
+            }
+
+            return Ok(response);
+        };
+
+        let cache_policy = self
+            .subsystem
+            .entity_types
+            .iter()
+            .find(|(_, entity_type)| entity_type.table_id == select.table_id)
+            .and_then(|(_, entity_type)| entity_type.cache);
+
+        let Some(cache_policy) = cache_policy else {
+            return resolve_operation(operation, self, request_context).await;
+        };
+
+        // A query in a transaction may see its uncommitted changes, so must bypass the cache
+        let query_cache = match &self.query_cache {
+            Some(query_cache) if !request_context.get_base_context().in_transaction().await => {
+                // The SQL parameters include the arguments as well as the context values used by
+                // the access rules, so the key identifies the result
+                select.cache_key(database).map(|key| (query_cache, key))
+            }
+            _ => None,
+        };
+
+        let response = match query_cache {
+            Some((query_cache, key)) => match query_cache.get(&key) {
+                Some(response) => response,
+                None => {
+                    let generation = query_cache.generation();
+                    let response = resolve_operation(operation, self, request_context).await?;
+                    query_cache.insert(
+                        key,
+                        response.clone(),
+                        operation.table_ids(database),
+                        cache_policy.max_age,
+                        generation,
+                    );
+                    response
+                }
+            },
+            None => resolve_operation(operation, self, request_context).await?,
+        };
+
+        Ok(with_cache_control(response, Some(cache_policy)))
+    }
+
+    /// The rate limit of the entity type that an operation queries or mutates (if any)
+    fn operation_rate_limit(
+        &self,
+        operation_name: &str,
+        operation_type: OperationType,
+    ) -> Option<&RateLimit> {
+        let subsystem = &self.subsystem;
+
+        let return_type = match operation_type {
+            OperationType::Query => subsystem
+                .pk_queries
+                .get_by_key(operation_name)
+                .map(|query| &query.return_type)
+                .or_else(|| {
+                    subsystem
+                        .collection_queries
+                        .get_by_key(operation_name)
+                        .map(|query| &query.return_type)
+                })
+                .or_else(|| {
+                    subsystem
+                        .unique_queries
+                        .get_by_key(operation_name)
+                        .map(|query| &query.return_type)
+                })
+                .or_else(|| {
+                    subsystem
+                        .aggregate_queries
+                        .get_by_key(operation_name)
+                        .map(|query| &query.return_type)
+                })
+                .or_else(|| {
+                    subsystem
+                        .connection_queries
+                        .get_by_key(operation_name)
+                        .map(|query| &query.return_type)
+                })
+                .or_else(|| {
+                    subsystem
+                        .group_by_queries
+                        .get_by_key(operation_name)
+                        .map(|query| &query.return_type)
+                }),
+            OperationType::Mutation => subsystem
+                .mutations
+                .get_by_key(operation_name)
+                .map(|mutation| &mutation.return_type),
+            OperationType::Subscription => None,
+        }?;
+
+        return_type.typ(&subsystem.entity_types).rate_limit.as_ref()
+    }
+}
+
+fn with_cache_control(mut response: QueryResponse, policy: Option<CachePolicy>) -> QueryResponse {
+    if let Some(policy) = policy {
+        response
+            .headers
+            .push((CachePolicy::HEADER_NAME.to_string(), policy.header_value()));
+    }
+    response
+}
+
 impl From<PostgresExecutionError> for SubsystemResolutionError {
     fn from(e: PostgresExecutionError) -> Self {
         match e {
@@ -10,6 +10,8 @@
 use core_plugin_interface::core_resolver::{
     access_solver::AccessSolverError, context::ContextExtractionError,
 };
+use exo_sql::database_error::DatabaseError;
+use postgres_model::subsystem::PostgresSubsystem;
 use thiserror::Error;
 use tracing::error;
 
@@ -53,6 +55,50 @@ impl PostgresExecutionError {
         PostgresExecutionError::WithContext(context, Box::new(self))
     }
 
+    /// Convert a database error, reporting a violated CHECK constraint (derived from `@range`,
+    /// `@maxLength`, or `@check`) as a validation error on the field (or type) that declares it.
+    ///
+    /// The reported error doesn't include the underlying database error (which may expose details
+    /// such as the constraint's expression or the data involved), so we log it instead.
+    pub fn from_database_error(
+        error: DatabaseError,
+        subsystem: &PostgresSubsystem,
+    ) -> PostgresExecutionError {
+        match error
+            .violated_check_constraint()
+            .and_then(|constraint_name| Self::check_violation(constraint_name, subsystem))
+        {
+            Some(validation_error) => {
+                error!("Check constraint violated: {:?}", error);
+                validation_error
+            }
+            None => PostgresExecutionError::Postgres(error),
+        }
+    }
+
+    /// The validation error for a violation of the CHECK constraint named `constraint_name` (if
+    /// the constraint is derived from an annotation)
+    pub(crate) fn check_violation(
+        constraint_name: &str,
+        subsystem: &PostgresSubsystem,
+    ) -> Option<PostgresExecutionError> {
+        subsystem.entity_types.iter().find_map(|(_, entity_type)| {
+            entity_type
+                .checks
+                .iter()
+                .find(|check| check.name == constraint_name)
+                .map(|check| {
+                    PostgresExecutionError::Validation(
+                        check
+                            .field_name
+                            .clone()
+                            .unwrap_or_else(|| entity_type.name.clone()),
+                        check.description.clone(),
+                    )
+                })
+        })
+    }
+
     pub fn user_error_message(&self) -> String {
         match self {
             PostgresExecutionError::Authorization => "Not authorized".to_string(),
@@ -11,24 +11,29 @@ use super::{
     auth_util::check_access,
     postgres_execution_error::PostgresExecutionError,
     sql_mapper::SQLOperationKind,
-    util::{find_arg, return_type_info},
+    util::{find_arg, get_argument_field, return_type_info, Arguments},
 };
 use crate::{
     create_data_param_mapper::InsertOperation, operation_resolver::OperationResolver,
-    postgres_query::compute_select, predicate_mapper::compute_predicate, sql_mapper::SQLMapper,
+    postgres_query::compute_select, predicate_mapper::compute_predicates, sql_mapper::SQLMapper,
     update_data_param_mapper::UpdateOperation,
 };
 use async_trait::async_trait;
 use core_plugin_interface::core_model::types::OperationReturnType;
 use core_plugin_interface::core_resolver::{
-    context::RequestContext, validation::field::ValidatedField,
+    context::RequestContext, validation::field::ValidatedField, value::Val,
 };
 use exo_sql::{
-    AbstractDelete, AbstractInsert, AbstractOperation, AbstractPredicate, AbstractSelect,
-    AbstractUpdate, Predicate,
+    AbstractDelete, AbstractInsert, AbstractOnConflict, AbstractOperation, AbstractPredicate,
+    AbstractSelect, AbstractUpdate, Column, ColumnId, ColumnPath, PhysicalColumnPath, Predicate,
+    SQLParamContainer,
 };
+use futures::future::try_join_all;
 use postgres_model::{
-    mutation::{DataParameter, PostgresMutation, PostgresMutationParameters},
+    mutation::{
+        ConflictTarget, DataParameter, OnConflictParameter, PostgresMutation,
+        PostgresMutationParameters, ON_CONFLICT_CONSTRAINT_FIELD,
+    },
     predicate::PredicateParameter,
     subsystem::PostgresSubsystem,
     types::EntityType,
@@ -71,10 +76,37 @@ impl OperationResolver for PostgresMutation {
                 )
                 .await?,
             ),
-            PostgresMutationParameters::Delete(predicate_param) => AbstractOperation::Delete(
-                delete_operation(
+            PostgresMutationParameters::Delete(predicate_params) => {
+                match soft_delete_column_id(return_type, subsystem) {
+                    Some(soft_delete_column_id) => AbstractOperation::Update(
+                        soft_delete_operation(
+                            return_type,
+                            predicate_params,
+                            soft_delete_column_id,
+                            field,
+                            abstract_select,
+                            subsystem,
+                            request_context,
+                        )
+                        .await?,
+                    ),
+                    None => AbstractOperation::Delete(
+                        delete_operation(
+                            return_type,
+                            predicate_params,
+                            field,
+                            abstract_select,
+                            subsystem,
+                            request_context,
+                        )
+                        .await?,
+                    ),
+                }
+            }
+            PostgresMutationParameters::Restore(predicate_params) => AbstractOperation::Update(
+                restore_operation(
                     return_type,
-                    predicate_param,
+                    predicate_params,
                     field,
                     abstract_select,
                     subsystem,
@@ -84,12 +116,27 @@ impl OperationResolver for PostgresMutation {
             ),
             PostgresMutationParameters::Update {
                 data_param,
-                predicate_param,
+                predicate_params,
             } => AbstractOperation::Update(
                 update_operation(
                     return_type,
                     data_param,
-                    predicate_param,
+                    predicate_params,
+                    field,
+                    abstract_select,
+                    subsystem,
+                    request_context,
+                )
+                .await?,
+            ),
+            PostgresMutationParameters::Upsert {
+                data_param,
+                on_conflict_param,
+            } => AbstractOperation::Insert(
+                upsert_operation(
+                    return_type,
+                    data_param,
+                    on_conflict_param,
                     field,
                     abstract_select,
                     subsystem,
@@ -124,7 +171,7 @@ async fn create_operation<'content>(
 
 async fn delete_operation<'content>(
     return_type: &'content OperationReturnType<EntityType>,
-    predicate_param: &'content PredicateParameter,
+    predicate_params: &'content [PredicateParameter],
     field: &'content ValidatedField,
     select: AbstractSelect,
     subsystem: &'content PostgresSubsystem,
@@ -142,8 +189,8 @@ async fn delete_operation<'content>(
     )
     .await?;
 
-    let arg_predicate = compute_predicate(
-        predicate_param,
+    let arg_predicate = compute_predicates(
+        predicate_params,
         &field.arguments,
         subsystem,
         request_context,
@@ -158,10 +205,138 @@ async fn delete_operation<'content>(
     })
 }
 
+/// The column marking soft-deleted rows of the return type's table (for a `@softDelete` type)
+fn soft_delete_column_id(
+    return_type: &OperationReturnType<EntityType>,
+    subsystem: &PostgresSubsystem,
+) -> Option<ColumnId> {
+    let (table_id, _, _) = return_type_info(return_type, subsystem);
+    subsystem.database.get_soft_delete_column_id(table_id)
+}
+
+/// A predicate to pick rows that are (or aren't) soft-deleted
+fn soft_deleted_predicate(soft_delete_column_id: ColumnId, deleted: bool) -> AbstractPredicate {
+    let column_path = ColumnPath::Physical(PhysicalColumnPath::leaf(soft_delete_column_id));
+
+    if deleted {
+        AbstractPredicate::Neq(column_path, ColumnPath::Null)
+    } else {
+        AbstractPredicate::Eq(column_path, ColumnPath::Null)
+    }
+}
+
+/// Compute the update that soft-deletes the rows a delete mutation picks (by setting the deletion
+/// time). The delete access rules apply as in a regular delete.
+async fn soft_delete_operation<'content>(
+    return_type: &'content OperationReturnType<EntityType>,
+    predicate_params: &'content [PredicateParameter],
+    soft_delete_column_id: ColumnId,
+    field: &'content ValidatedField,
+    select: AbstractSelect,
+    subsystem: &'content PostgresSubsystem,
+    request_context: &'content RequestContext<'content>,
+) -> Result<AbstractUpdate, PostgresExecutionError> {
+    let delete = delete_operation(
+        return_type,
+        predicate_params,
+        field,
+        select,
+        subsystem,
+        request_context,
+    )
+    .await?;
+
+    Ok(soft_delete_update(delete, soft_delete_column_id))
+}
+
+/// Express a delete of rows of a `@softDelete` type as an update that sets their deletion time
+/// (skipping rows that are already soft-deleted). The rows are those that the delete's predicate
+/// (including its access rules) picks.
+pub(crate) fn soft_delete_update(
+    delete: AbstractDelete,
+    soft_delete_column_id: ColumnId,
+) -> AbstractUpdate {
+    let AbstractDelete {
+        table_id,
+        predicate,
+        selection,
+    } = delete;
+
+    AbstractUpdate {
+        table_id,
+        predicate: Predicate::and(
+            predicate,
+            soft_deleted_predicate(soft_delete_column_id, false),
+        ),
+        column_values: vec![(
+            soft_delete_column_id,
+            Column::Param(SQLParamContainer::timestamp_utc(chrono::Utc::now())),
+        )],
+        nested_updates: vec![],
+        nested_inserts: vec![],
+        nested_deletes: vec![],
+        // The rows are soft-deleted by now, but we still need to return them
+        selection: AbstractSelect {
+            include_deleted: true,
+            ..selection
+        },
+    }
+}
+
+/// Compute the update that restores soft-deleted rows (by clearing the deletion time). Since this
+/// is an update, the update access rules apply.
+async fn restore_operation<'content>(
+    return_type: &'content OperationReturnType<EntityType>,
+    predicate_params: &'content [PredicateParameter],
+    field: &'content ValidatedField,
+    select: AbstractSelect,
+    subsystem: &'content PostgresSubsystem,
+    request_context: &'content RequestContext<'content>,
+) -> Result<AbstractUpdate, PostgresExecutionError> {
+    let (table_id, _, _) = return_type_info(return_type, subsystem);
+    let soft_delete_column_id = soft_delete_column_id(return_type, subsystem).ok_or_else(|| {
+        PostgresExecutionError::Generic(format!(
+            "Type '{}' doesn't support soft deletion",
+            return_type.type_name()
+        ))
+    })?;
+
+    let access_predicate = check_access(
+        return_type.typ(&subsystem.entity_types),
+        &field.subfields,
+        &SQLOperationKind::Update,
+        subsystem,
+        request_context,
+        None,
+    )
+    .await?;
+
+    let arg_predicate = compute_predicates(
+        predicate_params,
+        &field.arguments,
+        subsystem,
+        request_context,
+    )
+    .await?;
+
+    Ok(AbstractUpdate {
+        table_id,
+        predicate: Predicate::and(
+            Predicate::and(access_predicate, arg_predicate),
+            soft_deleted_predicate(soft_delete_column_id, true),
+        ),
+        column_values: vec![(soft_delete_column_id, Column::Null)],
+        nested_updates: vec![],
+        nested_inserts: vec![],
+        nested_deletes: vec![],
+        selection: select,
+    })
+}
+
 async fn update_operation<'content>(
     return_type: &'content OperationReturnType<EntityType>,
     data_param: &'content DataParameter,
-    predicate_param: &'content PredicateParameter,
+    predicate_params: &'content [PredicateParameter],
     field: &'content ValidatedField,
     select: AbstractSelect,
     subsystem: &'content PostgresSubsystem,
@@ -178,8 +353,8 @@ async fn update_operation<'content>(
     )
     .await?;
 
-    let arg_predicate = compute_predicate(
-        predicate_param,
+    let arg_predicate = compute_predicates(
+        predicate_params,
         &field.arguments,
         subsystem,
         request_context,
@@ -187,6 +362,15 @@ async fn update_operation<'content>(
     .await?;
     let predicate = Predicate::and(access_predicate, arg_predicate);
 
+    // Soft-deleted rows may only be restored (not updated)
+    let predicate = match soft_delete_column_id(return_type, subsystem) {
+        Some(soft_delete_column_id) => Predicate::and(
+            predicate,
+            soft_deleted_predicate(soft_delete_column_id, false),
+        ),
+        None => predicate,
+    };
+
     match data_arg {
         Some(argument) => {
             UpdateOperation {
@@ -203,3 +387,98 @@ async fn update_operation<'content>(
         )),
     }
 }
+
+/// Compute an insert with an `ON CONFLICT ... DO UPDATE` clause.
+///
+/// The creation access rules are checked for each row to be inserted (as in a create mutation).
+/// The update access rules are checked against the data (as in an update mutation) and their
+/// database residue is used to restrict which conflicting rows may be updated.
+async fn upsert_operation<'content>(
+    return_type: &'content OperationReturnType<EntityType>,
+    data_param: &'content DataParameter,
+    on_conflict_param: &'content OnConflictParameter,
+    field: &'content ValidatedField,
+    select: AbstractSelect,
+    subsystem: &'content PostgresSubsystem,
+    request_context: &'content RequestContext<'content>,
+) -> Result<AbstractInsert, PostgresExecutionError> {
+    let data_arg = find_arg(&field.arguments, &data_param.name)
+        .ok_or_else(|| PostgresExecutionError::MissingArgument(data_param.name.clone()))?;
+
+    let target = conflict_target(on_conflict_param, &field.arguments)?;
+
+    let entity_type = return_type.typ(&subsystem.entity_types);
+    let update_access_check = |data: &'content Val| {
+        check_access(
+            entity_type,
+            &field.subfields,
+            &SQLOperationKind::Update,
+            subsystem,
+            request_context,
+            Some(data),
+        )
+    };
+
+    // For a multi-row upsert, a conflicting row may be updated with any of the supplied data, so
+    // require it to satisfy the update access rules for each of them
+    let update_access_predicate = match data_arg {
+        Val::List(data_elems) => try_join_all(data_elems.iter().map(update_access_check))
+            .await?
+            .into_iter()
+            .fold(AbstractPredicate::True, Predicate::and),
+        _ => update_access_check(data_arg).await?,
+    };
+
+    let mut insert = InsertOperation { data_param, select }
+        .to_sql(data_arg, subsystem, request_context)
+        .await?;
+
+    // Without values for all the columns of the constraint, the insert would never conflict
+    let has_all_target_columns = insert.rows.iter().all(|row| {
+        let (self_elems, _) = row.partition_self_and_nested();
+        target
+            .column_ids
+            .iter()
+            .all(|column_id| self_elems.iter().any(|elem| &elem.column == column_id))
+    });
+
+    if !has_all_target_columns {
+        return Err(PostgresExecutionError::Validation(
+            on_conflict_param.name.clone(),
+            format!(
+                "The data must include all fields of the '{}' constraint",
+                target.name
+            ),
+        ));
+    }
+
+    insert.on_conflict = Some(AbstractOnConflict {
+        conflict_columns: target.column_ids.clone(),
+        predicate: update_access_predicate,
+    });
+
+    Ok(insert)
+}
+
+fn conflict_target<'a>(
+    on_conflict_param: &'a OnConflictParameter,
+    arguments: &Arguments,
+) -> Result<&'a ConflictTarget, PostgresExecutionError> {
+    let on_conflict_arg = find_arg(arguments, &on_conflict_param.name)
+        .ok_or_else(|| PostgresExecutionError::MissingArgument(on_conflict_param.name.clone()))?;
+
+    let constraint_name = match get_argument_field(on_conflict_arg, ON_CONFLICT_CONSTRAINT_FIELD) {
+        Some(Val::Enum(name)) | Some(Val::String(name)) => Ok(name),
+        _ => Err(PostgresExecutionError::Validation(
+            on_conflict_param.name.clone(),
+            format!("Missing '{ON_CONFLICT_CONSTRAINT_FIELD}'"),
+        )),
+    }?;
+
+    on_conflict_param.target(constraint_name).ok_or_else(|| {
+        PostgresExecutionError::Validation(
+            on_conflict_param.name.clone(),
+            format!("Unknown constraint '{constraint_name}'"),
+        )
+    })
+}
@@ -7,12 +7,12 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use super::predicate_mapper::compute_predicate;
+use super::predicate_mapper::{compute_predicate, compute_predicates};
 use super::{
     auth_util::check_access, postgres_execution_error::PostgresExecutionError,
     sql_mapper::SQLOperationKind, util::Arguments,
 };
-use crate::util::to_pg_vector;
+use crate::util::{find_arg, to_pg_vector};
 use crate::{
     operation_resolver::OperationSelectionResolver, order_by_mapper::OrderByParameterInput,
     sql_mapper::extract_and_map,
@@ -21,16 +21,16 @@ use async_recursion::async_recursion;
 use async_trait::async_trait;
 use core_plugin_interface::core_model::types::OperationReturnType;
 use core_plugin_interface::core_resolver::{
-    context::RequestContext, validation::field::ValidatedField,
+    context::RequestContext, validation::field::ValidatedField, value::Val,
 };
 use exo_sql::{
     AbstractOrderBy, AbstractPredicate, AbstractSelect, AliasedSelectionElement, Limit, Offset,
     RelationId, SelectionCardinality, SelectionElement,
 };
 use exo_sql::{Function, SQLParamContainer};
-use futures::stream::TryStreamExt;
 use futures::StreamExt;
 use postgres_model::query::UniqueQuery;
+use postgres_model::soft_delete::IncludeDeletedParameter;
 use postgres_model::vector_distance::VectorDistanceField;
 use postgres_model::{
     aggregate::AggregateField,
@@ -49,15 +49,15 @@ impl OperationSelectionResolver for PkQuery {
         request_context: &'a RequestContext<'a>,
         subsystem: &'a PostgresSubsystem,
     ) -> Result<AbstractSelect, PostgresExecutionError> {
-        let predicate = compute_predicate(
-            &self.parameters.predicate_param,
+        let predicate = compute_predicates(
+            &self.parameters.predicate_params,
             &field.arguments,
             subsystem,
             request_context,
         )
         .await?;
 
-        compute_select(
+        let select = compute_select(
             predicate,
             None,
             None,
@@ -67,7 +67,15 @@ impl OperationSelectionResolver for PkQuery {
             subsystem,
             request_context,
         )
-        .await
+        .await?;
+
+        Ok(AbstractSelect {
+            include_deleted: include_deleted(
+                &self.parameters.include_deleted_param,
+                &field.arguments,
+            )?,
+            ..select
+        })
     }
 }
 
@@ -79,17 +87,12 @@ impl OperationSelectionResolver for UniqueQuery {
         request_context: &'a RequestContext<'a>,
         subsystem: &'a PostgresSubsystem,
     ) -> Result<AbstractSelect, PostgresExecutionError> {
-        let predicate = futures::stream::iter(
-            self.parameters
-                .predicate_params
-                .iter()
-                .map(Ok::<_, PostgresExecutionError>),
+        let predicate = compute_predicates(
+            &self.parameters.predicate_params,
+            &field.arguments,
+            subsystem,
+            request_context,
         )
-        .try_fold(AbstractPredicate::True, |acc, p| async {
-            let predicate =
-                compute_predicate(p, &field.arguments, subsystem, request_context).await?;
-            Ok(AbstractPredicate::and(acc, predicate))
-        })
         .await?;
 
         compute_select(
@@ -119,11 +122,12 @@ impl OperationSelectionResolver for CollectionQuery {
             order_by_param,
             limit_param,
             offset_param,
+            include_deleted_param,
         } = &self.parameters;
 
         let arguments = &field.arguments;
 
-        compute_select(
+        let select = compute_select(
             compute_predicate(predicate_param, arguments, subsystem, request_context).await?,
             compute_order_by(order_by_param, arguments, subsystem, request_context).await?,
             extract_and_map(limit_param, arguments, subsystem, request_context).await?,
@@ -133,7 +137,32 @@ impl OperationSelectionResolver for CollectionQuery {
             subsystem,
             request_context,
         )
-        .await
+        .await?;
+
+        Ok(AbstractSelect {
+            include_deleted: include_deleted(include_deleted_param, arguments)?,
+            ..select
+        })
+    }
+}
+
+/// Should soft-deleted entities be included (`includeDeleted: true`)? Queries of a type without
+/// soft deletion don't have the parameter, so this is `false` for them.
+fn include_deleted(
+    param: &Option<IncludeDeletedParameter>,
+    arguments: &Arguments,
+) -> Result<bool, PostgresExecutionError> {
+    let Some(param) = param else {
+        return Ok(false);
+    };
+
+    match find_arg(arguments, &param.name) {
+        None | Some(Val::Null) => Ok(false),
+        Some(Val::Bool(include_deleted)) => Ok(*include_deleted),
+        Some(_) => Err(PostgresExecutionError::Validation(
+            param.name.clone(),
+            "Invalid value: expected a boolean".into(),
+        )),
     }
 }
 
@@ -176,10 +205,13 @@ pub(super) async fn compute_select<'content>(
         order_by,
         offset,
         limit,
+        keyset: None,
+        group_by: None,
+        include_deleted: false,
     })
 }
 
-async fn compute_order_by<'content>(
+pub(crate) async fn compute_order_by<'content>(
     param: &'content OrderByParameter,
     arguments: &'content Arguments,
     subsystem: &'content PostgresSubsystem,
@@ -198,7 +230,7 @@ async fn compute_order_by<'content>(
 }
 
 #[async_recursion]
-async fn content_select<'content>(
+pub(crate) async fn content_select<'content>(
     return_type: &EntityType,
     fields: &'content [ValidatedField],
     subsystem: &'content PostgresSubsystem,
@@ -275,9 +307,14 @@ async fn map_persistent_field<'content>(
                 .resolve_select(field, request_context, subsystem)
                 .await?;
 
+            // The referenced entity stays reachable even if soft-deleted (otherwise, a non-optional
+            // relation field would have no value)
             Ok(SelectionElement::SubSelect(
                 RelationId::ManyToOne(relation.relation_id),
-                nested_abstract_select,
+                AbstractSelect {
+                    include_deleted: true,
+                    ..nested_abstract_select
+                },
             ))
         }
         PostgresRelation::OneToMany(relation) => {
@@ -0,0 +1,151 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![cfg(feature = "network")]
+
+use core_plugin_interface::core_resolver::{
+    context::RequestContext, plugin::SubscriptionStream, validation::field::ValidatedField,
+    value::Val, QueryResponse, QueryResponseBody,
+};
+use exo_sql::{AbstractOperation, AbstractPredicate, Notification, TransactionHolder};
+use futures::StreamExt;
+use indexmap::IndexMap;
+use postgres_model::subscription::CollectionSubscription;
+
+use crate::{
+    abstract_operation_resolver::{resolve_operation_in_transaction, row_level_security_settings},
+    plugin::subsystem_resolver::PostgresSubsystemResolver,
+    postgres_execution_error::PostgresExecutionError,
+    postgres_query::compute_select,
+    predicate_mapper::compute_predicate,
+    util::Arguments,
+};
+
+/// Subscribe to changes to the entities returned by the subscription.
+///
+/// Each change notification carries just the primary key of the inserted or updated row. We
+/// re-fetch the row using the subscriber's selection, `where` argument, and access control rules,
+/// so a subscriber receives only the rows it would have been able to query.
+pub(crate) async fn subscribe<'a>(
+    subscription: &'a CollectionSubscription,
+    field: &'a ValidatedField,
+    request_context: &'a RequestContext<'a>,
+    subsystem_resolver: &'a PostgresSubsystemResolver,
+) -> Result<SubscriptionStream<'a>, PostgresExecutionError> {
+    let listener = subsystem_resolver
+        .notification_listener
+        .as_ref()
+        .ok_or_else(|| {
+            PostgresExecutionError::Generic(
+                "Subscriptions require a direct connection to the database".to_string(),
+            )
+        })?;
+
+    let subsystem = &subsystem_resolver.subsystem;
+    let entity_type = subscription.return_type.typ(&subsystem.entity_types);
+    let channel = subsystem
+        .database
+        .get_table(entity_type.table_id)
+        .change_notification_channel();
+
+    let notifications = listener.listen(&channel).await?;
+
+    Ok(notifications
+        .then(move |notification| {
+            resolve_notification(
+                notification,
+                subscription,
+                field,
+                request_context,
+                subsystem_resolver,
+            )
+        })
+        .filter_map(|response| futures::future::ready(response.map_err(|e| e.into()).transpose()))
+        .boxed())
+}
+
+/// Fetch the row referred to by the notification. Returns `None` if the row doesn't match the
+/// subscription's predicate or isn't accessible to the subscriber.
+async fn resolve_notification<'a>(
+    notification: Notification,
+    subscription: &'a CollectionSubscription,
+    field: &'a ValidatedField,
+    request_context: &'a RequestContext<'a>,
+    subsystem_resolver: &'a PostgresSubsystemResolver,
+) -> Result<Option<QueryResponse>, PostgresExecutionError> {
+    let subsystem = &subsystem_resolver.subsystem;
+
+    let payload: serde_json::Value = serde_json::from_str(&notification.payload).map_err(|e| {
+        PostgresExecutionError::Generic(format!("Invalid change notification payload: {e}"))
+    })?;
+    let pk = payload.get("pk").cloned().ok_or_else(|| {
+        PostgresExecutionError::Generic(
+            "Change notification payload is missing the primary key".to_string(),
+        )
+    })?;
+
+    let entity_type = subscription.return_type.typ(&subsystem.entity_types);
+    // Subscriptions are limited to types with a single primary key field (whose value the
+    // notification carries)
+    let [pk_param] = &subsystem.pk_queries[entity_type.pk_query]
+        .parameters
+        .predicate_params[..]
+    else {
+        return Err(PostgresExecutionError::Generic(format!(
+            "Subscriptions to '{}' are not supported, since it has a composite primary key",
+            entity_type.name
+        )));
+    };
+
+    let pk_arguments: Arguments = IndexMap::from([(pk_param.name.clone(), Val::from(pk))]);
+
+    let predicate = AbstractPredicate::and(
+        compute_predicate(pk_param, &pk_arguments, subsystem, request_context).await?,
+        compute_predicate(
+            &subscription.parameters.predicate_param,
+            &field.arguments,
+            subsystem,
+            request_context,
+        )
+        .await?,
+    );
+
+    let select = compute_select(
+        predicate,
+        None,
+        None,
+        None,
+        &subscription.return_type,
+        &field.subfields,
+        subsystem,
+        request_context,
+    )
+    .await?;
+
+    // Each event is independent of the request that started the subscription (which may have
+    // long finished), so it runs in its own transaction
+    let mut tx = TransactionHolder::default();
+    tx.set_config(row_level_security_settings(subsystem_resolver, request_context).await?);
+    let response = resolve_operation_in_transaction(
+        &AbstractOperation::Select(select),
+        subsystem_resolver,
+        &mut tx,
+    )
+    .await;
+    tx.finalize(response.is_ok())
+        .await
+        .map_err(|e| PostgresExecutionError::Postgres(e.into()))?;
+
+    let response = response?;
+
+    Ok(match response.body {
+        QueryResponseBody::Raw(None) => None,
+        _ => Some(response),
+    })
+}
@@ -28,7 +28,7 @@ use postgres_model::{
 use crate::{
     auth_util::check_retrieve_access,
     cast::literal_column_path,
-    column_path_util::to_column_path,
+    column_path_util::{to_column_path, to_text_search_column_path},
     sql_mapper::{extract_and_map, SQLMapper},
     util::{get_argument_field, Arguments},
 };
@@ -171,6 +171,13 @@ impl<'a> SQLMapper<'a, AbstractPredicate> for PredicateParamInput<'a> {
                                             "Invalid distance parameter".into(),
                                         )),
                                     }
+                                } else if parameter.name == "search" {
+                                    text_search_predicate(
+                                        self.param,
+                                        op_value,
+                                        &self.parent_column_path,
+                                        subsystem,
+                                    )
                                 } else {
                                     let override_op_value_type = match parameter.name.as_str() {
                                         "matchAllKeys" | "matchAnyKey" => {
@@ -180,6 +187,19 @@ impl<'a> SQLMapper<'a, AbstractPredicate> for PredicateParamInput<'a> {
                                                 }),
                                             })
                                         }
+                                        "in" => {
+                                            let param_column = self
+                                                .param
+                                                .column_path_link
+                                                .as_ref()
+                                                .unwrap()
+                                                .self_column_id()
+                                                .get_column(&subsystem.database);
+
+                                            Some(PhysicalColumnType::Array {
+                                                typ: Box::new(param_column.typ.clone()),
+                                            })
+                                        }
                                         _ => None,
                                     };
 
@@ -379,6 +399,7 @@ pub fn predicate_from_name<C: PartialEq + ParamEquality>(
         "lte" => Predicate::Lte(lhs, rhs),
         "gt" => Predicate::Gt(lhs, rhs),
         "gte" => Predicate::Gte(lhs, rhs),
+        "in" => Predicate::In(lhs, rhs),
         "like" => Predicate::StringLike(lhs, rhs, CaseSensitivity::Sensitive),
         "ilike" => Predicate::StringLike(lhs, rhs, CaseSensitivity::Insensitive),
         "startsWith" => Predicate::StringStartsWith(lhs, rhs),
@@ -414,6 +435,35 @@ fn operands<'a>(
     ))
 }
 
+/// Form a full-text search predicate that matches the search terms against the generated
+/// `tsvector` column of a `@searchable` field
+fn text_search_predicate(
+    param: &PredicateParameter,
+    op_value: &Val,
+    parent_column_path: &Option<PhysicalColumnPath>,
+    subsystem: &PostgresSubsystem,
+) -> Result<AbstractPredicate, PostgresExecutionError> {
+    let (_, terms) = operands(param, op_value, None, parent_column_path, subsystem)?;
+
+    let (text_search_column_path, language) = to_text_search_column_path(
+        parent_column_path,
+        &param.column_path_link,
+        &subsystem.database,
+    )
+    .ok_or_else(|| {
+        PostgresExecutionError::Validation(
+            param.name.clone(),
+            "Only @searchable fields may be searched".into(),
+        )
+    })?;
+
+    Ok(AbstractPredicate::TextSearch(
+        ColumnPath::Physical(text_search_column_path),
+        terms,
+        language,
+    ))
+}
+
 pub async fn compute_predicate<'a>(
     param: &'a PredicateParameter,
     arguments: &'a Arguments,
@@ -432,3 +482,22 @@ pub async fn compute_predicate<'a>(
     .await
     .map(|predicate| predicate.unwrap_or(AbstractPredicate::True))
 }
+
+/// Compute the conjunction of the predicates of multiple parameters (such as the parts of a
+/// composite primary key in `membership(userId: 1, groupId: 2)`)
+pub async fn compute_predicates<'a>(
+    params: &'a [PredicateParameter],
+    arguments: &'a Arguments,
+    subsystem: &'a PostgresSubsystem,
+    request_context: &'a RequestContext<'a>,
+) -> Result<AbstractPredicate, PostgresExecutionError> {
+    let mut predicate = AbstractPredicate::True;
+
+    for param in params {
+        let param_predicate =
+            compute_predicate(param, arguments, subsystem, request_context).await?;
+        predicate = AbstractPredicate::and(predicate, param_predicate);
+    }
+
+    Ok(predicate)
+}
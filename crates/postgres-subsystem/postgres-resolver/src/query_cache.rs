@@ -0,0 +1,280 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! An in-process cache of query responses for types annotated with `@cache`.
+//!
+//! Entries are keyed by the SQL of a query along with its parameters (see
+//! [`AbstractSelect::cache_key`](exo_sql::AbstractSelect::cache_key)). Since the parameters
+//! include the query's arguments as well as the context values used by the access predicates, two
+//! requests with the same key are guaranteed to see the same result (as long as the underlying
+//! tables haven't changed).
+//!
+//! An entry expires after the `maxAge` of its type's policy or when a mutation modifies any of the
+//! tables it depends on (once the mutation commits), whichever comes first. The least recently
+//! used entry is evicted when the cache is full.
+//!
+//! A query executing while a mutation commits may see the data from before the mutation. To avoid
+//! caching such a response (after the invalidation that was meant to remove it), each invalidation
+//! starts a new generation, and a response is cached only if the generation hasn't changed since
+//! the query started.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use core_plugin_interface::core_resolver::QueryResponse;
+use exo_env::Environment;
+use exo_sql::TableId;
+use indexmap::IndexMap;
+
+use common::env_const::EXO_QUERY_CACHE_SIZE;
+
+pub struct QueryCache {
+    capacity: usize,
+    entries: Mutex<IndexMap<String, CacheEntry>>,
+    /// Incremented on each invalidation (while holding the `entries` lock)
+    generation: AtomicU64,
+}
+
+struct CacheEntry {
+    response: QueryResponse,
+    table_ids: HashSet<TableId>,
+    expires_at: Instant,
+}
+
+impl QueryCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(IndexMap::new()),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Create a cache with the number of entries specified by `EXO_QUERY_CACHE_SIZE` (if the
+    /// variable is not set or is zero, there is no cache)
+    pub fn from_env(env: &dyn Environment) -> Option<Self> {
+        let capacity: usize = env.get(EXO_QUERY_CACHE_SIZE)?.parse().ok()?;
+
+        (capacity > 0).then(|| Self::new(capacity))
+    }
+
+    pub fn get(&self, key: &str) -> Option<QueryResponse> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let (index, expired) = entries
+            .get_full(key)
+            .map(|(index, _, entry)| (index, entry.expires_at <= Instant::now()))?;
+
+        if expired {
+            entries.shift_remove_index(index);
+            return None;
+        }
+
+        // Mark the entry as the most recently used one
+        let last = entries.len() - 1;
+        entries.move_index(index, last);
+        entries
+            .get_index(last)
+            .map(|(_, entry)| entry.response.clone())
+    }
+
+    /// The current generation, to be obtained before executing a query whose response is to be
+    /// inserted
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Insert a response computed by a query that started at the given `generation`. If there has
+    /// been an invalidation since, the response may predate the invalidating change, so we skip it.
+    pub fn insert(
+        &self,
+        key: String,
+        response: QueryResponse,
+        table_ids: HashSet<TableId>,
+        max_age: u32,
+        generation: u64,
+    ) {
+        let mut entries = self.entries.lock().unwrap();
+
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return;
+        }
+
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            entries.shift_remove_index(0);
+        }
+
+        entries.shift_remove(&key);
+        entries.insert(
+            key,
+            CacheEntry {
+                response,
+                table_ids,
+                expires_at: Instant::now() + Duration::from_secs(max_age.into()),
+            },
+        );
+    }
+
+    /// Remove entries that depend on any of the given tables
+    pub fn invalidate(&self, table_ids: &HashSet<TableId>) {
+        let mut entries = self.entries.lock().unwrap();
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        entries.retain(|_, entry| entry.table_ids.is_disjoint(table_ids));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_plugin_interface::core_resolver::QueryResponseBody;
+    use exo_sql::{Database, PhysicalTable, PhysicalTableName};
+
+    fn response(value: &str) -> QueryResponse {
+        QueryResponse {
+            body: QueryResponseBody::Raw(Some(value.to_string())),
+            headers: vec![],
+        }
+    }
+
+    fn body(response: Option<QueryResponse>) -> Option<String> {
+        response.and_then(|response| match response.body {
+            QueryResponseBody::Raw(value) => value,
+            QueryResponseBody::Json(value) => Some(value.to_string()),
+        })
+    }
+
+    fn table_ids() -> (TableId, TableId) {
+        let mut database = Database::default();
+        let mut insert = |name: &str| {
+            database.insert_table(PhysicalTable {
+                name: PhysicalTableName::new(name, None),
+                columns: vec![],
+                indices: vec![],
+                checks: vec![],
+                notify_changes: false,
+                renamed_from: None,
+                soft_delete_column: None,
+            })
+        };
+
+        (insert("concerts"), insert("venues"))
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let (concerts, _) = table_ids();
+        let cache = QueryCache::new(2);
+
+        cache.insert(
+            "a".into(),
+            response("1"),
+            HashSet::from([concerts]),
+            60,
+            cache.generation(),
+        );
+        cache.insert(
+            "b".into(),
+            response("2"),
+            HashSet::from([concerts]),
+            60,
+            cache.generation(),
+        );
+        // Using "a" makes "b" the least recently used entry
+        assert_eq!(body(cache.get("a")), Some("1".to_string()));
+        cache.insert(
+            "c".into(),
+            response("3"),
+            HashSet::from([concerts]),
+            60,
+            cache.generation(),
+        );
+
+        assert_eq!(body(cache.get("a")), Some("1".to_string()));
+        assert_eq!(body(cache.get("b")), None);
+        assert_eq!(body(cache.get("c")), Some("3".to_string()));
+    }
+
+    #[test]
+    fn invalidates_dependent_entries() {
+        let (concerts, venues) = table_ids();
+        let cache = QueryCache::new(10);
+
+        cache.insert(
+            "concerts".into(),
+            response("1"),
+            HashSet::from([concerts, venues]),
+            60,
+            cache.generation(),
+        );
+        cache.insert(
+            "venues".into(),
+            response("2"),
+            HashSet::from([venues]),
+            60,
+            cache.generation(),
+        );
+
+        cache.invalidate(&HashSet::from([concerts]));
+
+        assert_eq!(body(cache.get("concerts")), None);
+        assert_eq!(body(cache.get("venues")), Some("2".to_string()));
+    }
+
+    #[test]
+    fn expires_entries() {
+        let (concerts, _) = table_ids();
+        let cache = QueryCache::new(10);
+
+        cache.insert(
+            "a".into(),
+            response("1"),
+            HashSet::from([concerts]),
+            0,
+            cache.generation(),
+        );
+
+        assert_eq!(body(cache.get("a")), None);
+    }
+
+    #[test]
+    fn skips_responses_from_before_invalidation() {
+        let (concerts, venues) = table_ids();
+        let cache = QueryCache::new(10);
+
+        // A query started before a mutation (on any table) committed
+        let generation = cache.generation();
+        cache.invalidate(&HashSet::from([venues]));
+        cache.insert(
+            "a".into(),
+            response("1"),
+            HashSet::from([concerts]),
+            60,
+            generation,
+        );
+        assert_eq!(body(cache.get("a")), None);
+
+        // A query started after it
+        let generation = cache.generation();
+        cache.insert(
+            "a".into(),
+            response("1"),
+            HashSet::from([concerts]),
+            60,
+            generation,
+        );
+        assert_eq!(body(cache.get("a")), Some("1".to_string()));
+    }
+}
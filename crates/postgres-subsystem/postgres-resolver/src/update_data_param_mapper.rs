@@ -14,8 +14,8 @@ use core_plugin_interface::core_resolver::context::RequestContext;
 use core_plugin_interface::core_resolver::value::Val;
 use exo_sql::{
     AbstractDelete, AbstractInsert, AbstractPredicate, AbstractSelect, AbstractUpdate, Column,
-    ColumnId, ColumnPath, ManyToOne, NestedAbstractDelete, NestedAbstractInsert,
-    NestedAbstractInsertSet, NestedAbstractUpdate, OneToMany, PhysicalColumnPath, Selection,
+    ColumnId, ColumnPath, NestedAbstractDelete, NestedAbstractInsert, NestedAbstractInsertSet,
+    NestedAbstractUpdate, OneToMany, PhysicalColumnPath, Selection,
 };
 use futures::StreamExt;
 use postgres_model::{
@@ -27,6 +27,7 @@ use postgres_model::{
 
 use crate::{
     auth_util::check_access,
+    postgres_mutation::soft_delete_update,
     sql_mapper::{SQLMapper, SQLOperationKind},
     util::{get_argument_field, return_type_info},
 };
@@ -50,7 +51,7 @@ impl<'a> SQLMapper<'a, AbstractUpdate> for UpdateOperation<'a> {
     ) -> Result<AbstractUpdate, PostgresExecutionError> {
         let data_type = &subsystem.mutation_types[self.data_param.typ.innermost().type_id];
 
-        let self_update_columns = compute_update_columns(data_type, argument, subsystem);
+        let self_update_columns = compute_update_columns(data_type, argument, subsystem)?;
         let (table_id, _, _) = return_type_info(self.return_type, subsystem);
 
         let (nested_updates, nested_inserts, nested_deletes) =
@@ -78,7 +79,7 @@ fn compute_update_columns<'a>(
     data_type: &'a MutationType,
     argument: &'a Val,
     subsystem: &'a PostgresSubsystem,
-) -> Vec<(ColumnId, Column)> {
+) -> Result<Vec<(ColumnId, Column)>, PostgresExecutionError> {
     data_type
         .fields
         .iter()
@@ -86,30 +87,32 @@ fn compute_update_columns<'a>(
             PostgresRelation::Pk { column_id } | PostgresRelation::Scalar { column_id } => {
                 get_argument_field(argument, &field.name).map(|argument_value| {
                     let column = column_id.get_column(&subsystem.database);
+                    cast::check_max_length(argument_value, column, subsystem)?;
                     let value_column = cast::literal_column(argument_value, column);
-                    (*column_id, value_column.unwrap())
+                    Ok((*column_id, value_column.unwrap()))
                 })
             }
-            PostgresRelation::ManyToOne(ManyToOneRelation {
-                foreign_pk_field_id,
-                relation_id,
-                ..
-            }) => {
-                let ManyToOne { self_column_id, .. } = relation_id.deref(&subsystem.database);
+            PostgresRelation::ManyToOne(
+                relation @ ManyToOneRelation {
+                    foreign_pk_field_id,
+                    ..
+                },
+            ) => {
+                let self_column_id = relation.self_column_id(&subsystem.database);
 
                 let self_column = self_column_id.get_column(&subsystem.database);
                 let foreign_type_pk_field_name =
                     &foreign_pk_field_id.resolve(&subsystem.entity_types).name;
 
                 match get_argument_field(argument, &field.name) {
-                    Some(Val::Null) => Some((self_column_id, Column::Null)), // `{..., foreign_field: null}` means set the column to null
+                    Some(Val::Null) => Some(Ok((self_column_id, Column::Null))), // `{..., foreign_field: null}` means set the column to null
                     Some(argument_value) => {
                         // `{..., foreign_field: { id: 1 }}` means set the column to the id of the nested object
                         match get_argument_field(argument_value, foreign_type_pk_field_name) {
                             Some(foreign_type_pk_arg) => {
                                 let value_column =
                                     cast::literal_column(foreign_type_pk_arg, self_column);
-                                Some((self_column_id, value_column.unwrap()))
+                                Some(Ok((self_column_id, value_column.unwrap())))
                             }
                             None => unreachable!("Expected pk argument"), // Validation should have caught this
                         }
@@ -180,16 +183,36 @@ async fn compute_nested_ops<'a>(
                     .await?,
                 );
 
-                nested_deletes.extend(
-                    compute_nested_delete(
-                        arg_type,
-                        argument,
-                        nested_relation,
-                        subsystem,
-                        request_context,
-                    )
-                    .await?,
-                );
+                let deletes = compute_nested_delete(
+                    arg_type,
+                    argument,
+                    nested_relation,
+                    subsystem,
+                    request_context,
+                )
+                .await?;
+
+                // Deleting rows of a `@softDelete` type only sets their deletion time (as with the
+                // type's delete mutations)
+                for NestedAbstractDelete {
+                    nesting_relation,
+                    delete,
+                } in deletes
+                {
+                    match subsystem
+                        .database
+                        .get_soft_delete_column_id(delete.table_id)
+                    {
+                        Some(soft_delete_column_id) => nested_updates.push(NestedAbstractUpdate {
+                            nesting_relation,
+                            update: soft_delete_update(delete, soft_delete_column_id),
+                        }),
+                        None => nested_deletes.push(NestedAbstractDelete {
+                            nesting_relation,
+                            delete,
+                        }),
+                    }
+                }
             }
         }
     }
@@ -263,7 +286,7 @@ async fn compute_nested_update_object_arg<'a>(
 
     let table_id = subsystem.entity_types[field_entity_type.entity_id].table_id;
 
-    let nested = compute_update_columns(field_entity_type, argument, subsystem);
+    let nested = compute_update_columns(field_entity_type, argument, subsystem)?;
     let (pk_columns, nested): (Vec<_>, Vec<_>) = nested.into_iter().partition(|elem| {
         let column = elem.0.get_column(&subsystem.database);
         column.is_pk
@@ -304,6 +327,9 @@ async fn compute_nested_update_object_arg<'a>(
                 order_by: None,
                 offset: None,
                 limit: None,
+                keyset: None,
+                group_by: None,
+                include_deleted: false,
             },
             nested_updates: vec![],
             nested_inserts: vec![],
@@ -342,6 +368,7 @@ async fn compute_nested_inserts<'a>(
             insert: AbstractInsert {
                 table_id,
                 rows,
+                on_conflict: None,
                 selection: AbstractSelect {
                     table_id,
                     selection: Selection::Seq(vec![]),
@@ -349,6 +376,9 @@ async fn compute_nested_inserts<'a>(
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 },
             },
         })
@@ -461,7 +491,7 @@ async fn compute_nested_delete_object_arg<'a>(
 ) -> Result<NestedAbstractDelete, PostgresExecutionError> {
     assert!(matches!(argument, Val::Object(..)));
 
-    let nested = compute_update_columns(field_mutation_type, argument, subsystem);
+    let nested = compute_update_columns(field_mutation_type, argument, subsystem)?;
     let (pk_columns, _nested): (Vec<_>, Vec<_>) = nested.into_iter().partition(|elem| {
         let column = elem.0.get_column(&subsystem.database);
         column.is_pk
@@ -513,6 +543,9 @@ async fn compute_nested_delete_object_arg<'a>(
                 order_by: None,
                 offset: None,
                 limit: None,
+                keyset: None,
+                group_by: None,
+                include_deleted: false,
             },
         },
     })
@@ -20,5 +20,6 @@ pub mod graphiql;
 pub use root_resolver::{
     create_system_resolver, create_system_resolver_from_system, create_system_resolver_or_exit,
     get_endpoint_http_path, get_playground_http_path, resolve, resolve_in_memory,
+    resolve_streaming, trusted_document_enforcement,
 };
 pub use system_loader::{introspection_mode, IntrospectionMode};
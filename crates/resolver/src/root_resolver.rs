@@ -9,28 +9,33 @@
 
 use std::pin::Pin;
 use std::process::exit;
+use std::time::Instant;
 use std::{fs::File, io::BufReader, path::Path};
 
 use crate::system_loader::{StaticLoaders, SystemLoadingError};
 
 #[cfg(not(target_family = "wasm"))]
 use common::env_const::is_production;
+use core_model::cache::CachePolicy;
 use core_plugin_shared::serializable_system::SerializableSystem;
 use core_plugin_shared::trusted_documents::TrustedDocumentEnforcement;
 use core_resolver::http::{Headers, RequestPayload, ResponsePayload};
+use core_resolver::metrics::{self, Metrics};
+use core_resolver::plugin::SubsystemResolutionError;
 use core_resolver::QueryResponse;
 use http::StatusCode;
 
 use super::system_loader::SystemLoader;
 use ::tracing::instrument;
-use async_graphql_parser::Pos;
+use async_graphql_parser::{types::OperationType, Pos};
 use async_stream::try_stream;
 use bytes::Bytes;
 use core_resolver::system_resolver::SystemResolver;
 use core_resolver::system_resolver::{RequestError, SystemResolutionError};
+use core_resolver::validation::operation::ValidatedOperation;
 pub use core_resolver::OperationsPayload;
 use core_resolver::{context::RequestContext, QueryResponseBody};
-use futures::Stream;
+use futures::{stream::BoxStream, Stream, StreamExt};
 
 use exo_env::Environment;
 
@@ -42,10 +47,22 @@ const EXO_ENDPOINT_HTTP_PATH: &str = "EXO_ENDPOINT_HTTP_PATH";
     skip(system_resolver, request)
 )]
 pub async fn resolve_in_memory<'a>(
-    mut request: impl RequestPayload,
+    request: impl RequestPayload,
     system_resolver: &SystemResolver,
     trusted_document_enforcement: TrustedDocumentEnforcement,
 ) -> Result<Vec<(String, QueryResponse)>, SystemResolutionError> {
+    resolve_in_memory_with_cost(request, system_resolver, trusted_document_enforcement)
+        .await
+        .map(|(parts, _)| parts)
+}
+
+/// Same as [resolve_in_memory], but also returns the cost of the resolved operation (to be reported
+/// in the response `extensions`)
+async fn resolve_in_memory_with_cost(
+    mut request: impl RequestPayload,
+    system_resolver: &SystemResolver,
+    trusted_document_enforcement: TrustedDocumentEnforcement,
+) -> Result<(Vec<(String, QueryResponse)>, usize), SystemResolutionError> {
     let method = request.get_head().get_method();
     if method != http::Method::POST {
         return Err(SystemResolutionError::RequestError(
@@ -60,14 +77,115 @@ pub async fn resolve_in_memory<'a>(
         .map_err(|e| SystemResolutionError::RequestError(RequestError::InvalidBodyJson(e)))?;
     let request_context = RequestContext::new(request_head, vec![], system_resolver);
 
-    let response = system_resolver
-        .resolve_operations(
-            operations_payload,
-            &request_context,
-            trusted_document_enforcement,
-        )
-        .await;
+    let metrics = metrics::get();
+    let start = metrics.map(|_| Instant::now());
+    let mut label = None;
+
+    let response = match system_resolver
+        .validate_operations_payload(operations_payload, trusted_document_enforcement)
+    {
+        Ok(operation) => {
+            label = metrics.map(|_| operation_label(&operation));
+
+            resolve_client_operation(&operation, &request_context, system_resolver)
+                .await
+                .map(|parts| (parts, operation.cost))
+        }
+        Err(e) => Err(e),
+    };
+
+    let response = finalize_transaction(&request_context, response).await;
+
+    if let (Some(metrics), Some(start)) = (metrics, start) {
+        record_metrics(metrics, label.as_deref(), start, &response);
+    }
+
+    response
+}
+
+/// Resolves an already validated operation received over a transport that can deliver multiple
+/// responses (such as a WebSocket connection).
+///
+/// A subscription produces one item for each event, whereas a query or a mutation produces a
+/// single item. Errors encountered while executing the operation are reported as stream items, so
+/// the caller can relay them to the client in the same way as any other response.
+#[instrument(
+    name = "resolver::resolve_streaming"
+    skip_all
+)]
+pub async fn resolve_streaming<'a>(
+    operation: &'a ValidatedOperation,
+    request_context: &'a RequestContext<'a>,
+    system_resolver: &'a SystemResolver,
+) -> Result<
+    BoxStream<'a, Result<Vec<(String, QueryResponse)>, SystemResolutionError>>,
+    SystemResolutionError,
+> {
+    match operation.typ {
+        OperationType::Subscription => {
+            // Validation ensures that a subscription has exactly one top-level field
+            let stream = system_resolver
+                .subscribe(&operation.fields[0], request_context)
+                .await?;
 
+            Ok(stream
+                .map(|response| response.map(|part| vec![part]))
+                .boxed())
+        }
+        OperationType::Query | OperationType::Mutation => {
+            let metrics = metrics::get();
+            let start = metrics.map(|_| Instant::now());
+
+            let response =
+                resolve_client_operation(operation, request_context, system_resolver).await;
+            let response = finalize_transaction(request_context, response).await;
+
+            if let (Some(metrics), Some(start)) = (metrics, start) {
+                record_metrics(metrics, Some(&operation_label(operation)), start, &response);
+            }
+
+            Ok(futures::stream::once(async { response }).boxed())
+        }
+    }
+}
+
+/// Resolve a query or mutation sent by a client, which (unlike operations executed on its behalf,
+/// such as through `executeQuery` in a Deno module) is subject to rate limits
+async fn resolve_client_operation(
+    operation: &ValidatedOperation,
+    request_context: &RequestContext<'_>,
+    system_resolver: &SystemResolver,
+) -> Result<Vec<(String, QueryResponse)>, SystemResolutionError> {
+    system_resolver
+        .check_rate_limits(operation, request_context)
+        .await?;
+    system_resolver
+        .resolve_validated_operation(operation, request_context)
+        .await
+}
+
+/// The label to record the metrics of an operation under
+fn operation_label(operation: &ValidatedOperation) -> String {
+    metrics::operation_label(operation.fields.iter().map(|field| field.name.as_str()))
+}
+
+fn record_metrics<T>(
+    metrics: &Metrics,
+    operation_label: Option<&str>,
+    start: Instant,
+    response: &Result<T, SystemResolutionError>,
+) {
+    metrics.record_operation(operation_label, start.elapsed(), response.is_ok());
+
+    if let Err(e) = response {
+        metrics.record_error(e.kind());
+    }
+}
+
+async fn finalize_transaction<T>(
+    request_context: &RequestContext<'_>,
+    response: Result<T, SystemResolutionError>,
+) -> Result<T, SystemResolutionError> {
     let ctx = request_context.get_base_context();
     let mut tx_holder = ctx.transaction_holder.try_lock().unwrap();
 
@@ -80,6 +198,22 @@ pub async fn resolve_in_memory<'a>(
         .and(response)
 }
 
+/// Determine whether trusted documents should be enforced for a request.
+///
+/// Requests from the playground may use any query, unless we are running in production.
+pub fn trusted_document_enforcement(playground_request: bool) -> TrustedDocumentEnforcement {
+    #[cfg(not(target_family = "wasm"))]
+    let is_production = is_production();
+    #[cfg(target_family = "wasm")]
+    let is_production = !playground_request;
+
+    if playground_request && !is_production {
+        TrustedDocumentEnforcement::DoNotEnforce
+    } else {
+        TrustedDocumentEnforcement::Enforce
+    }
+}
+
 /// Resolves an incoming query, returning a response stream containing JSON and a set
 /// of HTTP headers. The JSON may be either the data returned by the query, or a list of errors
 /// if something went wrong.
@@ -96,19 +230,10 @@ pub async fn resolve<'a, E: 'static>(
     system_resolver: &SystemResolver,
     playground_request: bool,
 ) -> ResponsePayload<E> {
-    #[cfg(not(target_family = "wasm"))]
-    let is_production = is_production();
-    #[cfg(target_family = "wasm")]
-    let is_production = !playground_request;
-
-    let response = resolve_in_memory(
+    let response = resolve_in_memory_with_cost(
         request,
         system_resolver,
-        if playground_request && !is_production {
-            TrustedDocumentEnforcement::DoNotEnforce
-        } else {
-            TrustedDocumentEnforcement::Enforce
-        },
+        trusted_document_enforcement(playground_request),
     )
     .await;
 
@@ -121,15 +246,40 @@ pub async fn resolve<'a, E: 'static>(
         };
     }
 
-    let mut headers = if let Ok(ref response) = response {
-        response
+    let mut headers = if let Ok((ref response, _)) = response {
+        let mut headers: Headers = response
             .iter()
             .flat_map(|(_, qr)| qr.headers.clone())
-            .collect()
+            .filter(|(name, _)| !name.eq_ignore_ascii_case(CachePolicy::HEADER_NAME))
+            .collect();
+
+        // The response may be cached only if every part of it may be (each part without a policy,
+        // such as an introspection query, makes the whole response uncacheable)
+        let cache_policy = CachePolicy::combine_all(response.iter().map(|(_, qr)| {
+            qr.headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(CachePolicy::HEADER_NAME))
+                .and_then(|(_, value)| CachePolicy::from_header_value(value))
+        }));
+
+        if let Some(cache_policy) = cache_policy {
+            headers.push((
+                CachePolicy::HEADER_NAME.to_string(),
+                cache_policy.header_value(),
+            ));
+        }
+
+        headers
     } else {
         vec![]
     };
 
+    let (status_code, retry_after_secs) = status_and_retry_after(&response);
+
+    if let Some(retry_after_secs) = retry_after_secs {
+        headers.push(("Retry-After".into(), retry_after_secs.to_string()));
+    }
+
     headers.push(("content-type".into(), "application/json".into()));
 
     let stream = try_stream! {
@@ -159,7 +309,7 @@ pub async fn resolve<'a, E: 'static>(
         }
 
         match response {
-            Ok(parts) => {
+            Ok((parts, cost)) => {
                 let parts_len = parts.len();
                 yield Bytes::from_static(br#"{"data": {"#);
                 for (index, part) in parts.into_iter().enumerate() {
@@ -175,6 +325,8 @@ pub async fn resolve<'a, E: 'static>(
                         yield Bytes::from_static(b", ");
                     }
                 };
+                yield Bytes::from_static(br#"}, "extensions": {"cost": "#);
+                yield Bytes::from(cost.to_string());
                 yield Bytes::from_static(b"}}");
             },
             Err(err) => {
@@ -185,6 +337,19 @@ pub async fn resolve<'a, E: 'static>(
                         .replace('\n', "; ")
                 );
                 yield Bytes::from_static(br#"""#);
+                let code = match err {
+                    SystemResolutionError::PersistedQuery(ref err) => Some(err.code()),
+                    SystemResolutionError::RateLimited(ref err) => Some(err.code()),
+                    SystemResolutionError::SubsystemResolutionError(
+                        SubsystemResolutionError::Timeout,
+                    ) => Some("TIMEOUT"),
+                    _ => None,
+                };
+                if let Some(code) = code {
+                    yield Bytes::from_static(br#", "extensions": {"code": ""#);
+                    yield Bytes::from_static(code.as_bytes());
+                    yield Bytes::from_static(br#""}"#);
+                };
                 if let SystemResolutionError::Validation(err) = err {
                     yield Bytes::from_static(br#", "locations": ["#);
                     report_positions!(err.positions());
@@ -199,10 +364,29 @@ pub async fn resolve<'a, E: 'static>(
     ResponsePayload {
         stream: Some(Box::pin(stream) as Pin<Box<dyn Stream<Item = Result<Bytes, E>>>>),
         headers,
-        status_code: StatusCode::OK,
+        status_code,
     }
 }
 
+/// The status code of the response, along with the value of its `Retry-After` header (if any).
+/// An operation rejected due to a rate limit gets a 429 (Too Many Requests) response.
+fn status_and_retry_after<T>(
+    response: &Result<T, SystemResolutionError>,
+) -> (StatusCode, Option<u64>) {
+    let retry_after_secs = match response {
+        Err(SystemResolutionError::RateLimited(error)) => error.retry_after_secs(),
+        _ => None,
+    };
+
+    let status_code = if retry_after_secs.is_some() {
+        StatusCode::TOO_MANY_REQUESTS
+    } else {
+        StatusCode::OK
+    };
+
+    (status_code, retry_after_secs)
+}
+
 pub fn get_playground_http_path() -> String {
     std::env::var(EXO_PLAYGROUND_HTTP_PATH).unwrap_or_else(|_| "/playground".to_string())
 }
@@ -250,3 +434,33 @@ pub async fn create_system_resolver_or_exit(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use core_resolver::rate_limit::RateLimitError;
+
+    use super::*;
+
+    #[test]
+    fn rate_limited_response() {
+        let exceeded: Result<(), _> = Err(SystemResolutionError::RateLimited(
+            RateLimitError::Exceeded {
+                retry_after: Duration::from_millis(5500),
+            },
+        ));
+        assert_eq!(
+            status_and_retry_after(&exceeded),
+            (StatusCode::TOO_MANY_REQUESTS, Some(6))
+        );
+
+        // A failing store doesn't tell the client to retry
+        let store_error: Result<(), _> = Err(SystemResolutionError::RateLimited(
+            RateLimitError::Store("unavailable".to_string()),
+        ));
+        assert_eq!(status_and_retry_after(&store_error), (StatusCode::OK, None));
+
+        assert_eq!(status_and_retry_after(&Ok(())), (StatusCode::OK, None));
+    }
+}
@@ -9,7 +9,7 @@
 
 use std::sync::Arc;
 
-use common::env_const::EXO_INTROSPECTION;
+use common::env_const::{EXO_INTROSPECTION, EXO_PERSISTED_QUERY_CACHE_SIZE};
 use common::EnvError;
 use core_resolver::context::JwtAuthenticator;
 use introspection_resolver::IntrospectionResolver;
@@ -22,7 +22,11 @@ use core_plugin_shared::{
     system_serializer::SystemSerializer,
 };
 
+use core_resolver::persisted_queries::{InMemoryPersistedQueryStore, PersistedQueryStore};
 use core_resolver::plugin::SubsystemResolver;
+#[cfg(not(target_family = "wasm"))]
+use core_resolver::rate_limit::InMemoryRateLimitStore;
+use core_resolver::rate_limit::RateLimitStore;
 use core_resolver::{introspection::definition::schema::Schema, system_resolver::SystemResolver};
 use exo_env::Environment;
 
@@ -33,6 +37,7 @@ pub type StaticLoaders = Vec<Box<dyn SubsystemLoader>>;
 pub struct SystemLoader;
 
 const EXO_MAX_SELECTION_DEPTH: &str = "EXO_MAX_SELECTION_DEPTH";
+const EXO_MAX_QUERY_COST: &str = "EXO_MAX_QUERY_COST";
 
 impl SystemLoader {
     pub async fn load(
@@ -115,6 +120,10 @@ impl SystemLoader {
         let (normal_query_depth_limit, introspection_query_depth_limit) =
             query_depth_limits(env.as_ref())?;
 
+        let max_query_cost = max_query_cost(env.as_ref())?;
+
+        let persisted_queries = persisted_query_store(env.as_ref())?;
+
         let authenticator = JwtAuthenticator::new_from_env(env.as_ref())
             .await
             .map_err(|e| SystemLoadingError::Config(e.to_string()))?;
@@ -124,11 +133,14 @@ impl SystemLoader {
             query_interception_map,
             mutation_interception_map,
             trusted_documents,
+            persisted_queries,
             schema,
             Arc::new(authenticator),
             env,
             normal_query_depth_limit,
             introspection_query_depth_limit,
+            max_query_cost,
+            rate_limit_store(),
         ))
     }
 
@@ -199,6 +211,52 @@ pub fn query_depth_limits(env: &dyn Environment) -> Result<(usize, usize), Syste
     Ok((query_depth, DEFAULT_INTROSPECTION_QUERY_DEPTH))
 }
 
+/// Returns the maximum cost of an operation (`None` if operations are not limited by their cost)
+fn max_query_cost(env: &dyn Environment) -> Result<Option<usize>, SystemLoadingError> {
+    env.get(EXO_MAX_QUERY_COST)
+        .map(|e| {
+            e.parse::<usize>().map_err(|_| {
+                SystemLoadingError::Config(format!(
+                    "{EXO_MAX_QUERY_COST} env var must be set to a positive integer"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// The store for automatic persisted queries (`None` if disabled by setting the cache size to 0)
+fn persisted_query_store(
+    env: &dyn Environment,
+) -> Result<Option<Box<dyn PersistedQueryStore>>, SystemLoadingError> {
+    const DEFAULT_PERSISTED_QUERY_CACHE_SIZE: usize = 1000;
+
+    let cache_size = match env.get(EXO_PERSISTED_QUERY_CACHE_SIZE) {
+        Some(e) => e.parse::<usize>().map_err(|_| {
+            SystemLoadingError::Config(format!(
+                "{EXO_PERSISTED_QUERY_CACHE_SIZE} env var must be set to a non-negative integer"
+            ))
+        })?,
+        None => DEFAULT_PERSISTED_QUERY_CACHE_SIZE,
+    };
+
+    Ok((cache_size > 0).then(|| {
+        Box::new(InMemoryPersistedQueryStore::new(cache_size)) as Box<dyn PersistedQueryStore>
+    }))
+}
+
+fn rate_limit_store() -> Option<Box<dyn RateLimitStore>> {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        Some(Box::new(InMemoryRateLimitStore::new()))
+    }
+
+    // The in-memory store relies on `std::time::Instant`, which isn't available in WASM
+    #[cfg(target_family = "wasm")]
+    {
+        None
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum SystemLoadingError {
     #[error("System serialization error: {0}")]
@@ -8,10 +8,12 @@
 // by the Apache License, Version 2.0.
 
 mod request;
+mod subscription;
 
 use std::path::Path;
 
 use actix_web::{
+    guard,
     http::header::{CacheControl, CacheDirective},
     web::{self, Redirect, ServiceConfig},
     Error, HttpRequest, HttpResponse, HttpResponseBuilder, Responder,
@@ -21,6 +23,7 @@ use url::Url;
 use common::env_const::{get_deployment_mode, DeploymentMode};
 use core_resolver::{
     http::{RequestHead, RequestPayload, ResponsePayload},
+    metrics,
     system_resolver::SystemResolver,
 };
 use request::ActixRequestHead;
@@ -46,7 +49,16 @@ pub fn configure_resolver(
     move |app| {
         app.app_data(system_resolver)
             .app_data(web::Data::new(endpoint_url))
-            .service(web::scope(&resolve_path).route("", web::to(resolve)));
+            .service(
+                web::scope(&resolve_path)
+                    .route(
+                        "",
+                        web::get()
+                            .guard(guard::fn_guard(subscription::is_websocket_upgrade))
+                            .to(subscription::subscribe),
+                    )
+                    .route("", web::to(resolve)),
+            );
     }
 }
 
@@ -66,6 +78,47 @@ pub fn configure_playground(cfg: &mut ServiceConfig) {
         .route("/", web::get().to(playground_redirect));
 }
 
+/// Serve liveness (`/health`) and readiness (`/ready`) probes
+///
+/// The server is live as soon as it can respond to requests, but ready only once every subsystem
+/// reports that the resources it depends on (such as the database) are available.
+pub fn configure_health(cfg: &mut ServiceConfig) {
+    async fn health() -> impl Responder {
+        HttpResponse::Ok().json(serde_json::json!({ "status": "ok" }))
+    }
+
+    async fn ready(system_resolver: web::Data<SystemResolver>) -> impl Responder {
+        let report = system_resolver.check_readiness().await;
+        // The response includes only the status of each check (this endpoint is unauthenticated)
+        report.log_failures();
+
+        if report.is_ready() {
+            HttpResponse::Ok().json(report)
+        } else {
+            HttpResponse::ServiceUnavailable().json(report)
+        }
+    }
+
+    cfg.route("/health", web::get().to(health))
+        .route("/ready", web::get().to(ready));
+}
+
+/// Serve metrics in the Prometheus text format (if metrics have been enabled)
+pub fn configure_metrics(cfg: &mut ServiceConfig) {
+    async fn serve_metrics(system_resolver: web::Data<SystemResolver>) -> impl Responder {
+        match metrics::get() {
+            Some(metrics) => HttpResponse::Ok()
+                .content_type("text/plain; version=0.0.4")
+                .body(metrics.render(&system_resolver.pool_stats().await)),
+            None => HttpResponse::NotFound().finish(),
+        }
+    }
+
+    if metrics::get().is_some() {
+        cfg.route("/metrics", web::get().to(serve_metrics));
+    }
+}
+
 /// Resolve a GraphQL request
 ///
 /// # Arguments
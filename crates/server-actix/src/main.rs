@@ -13,7 +13,7 @@ use actix_web::{middleware, web, App, HttpServer};
 use resolver::{
     get_endpoint_http_path, get_playground_http_path, introspection_mode, IntrospectionMode,
 };
-use server_actix::{configure_playground, configure_resolver};
+use server_actix::{configure_health, configure_metrics, configure_playground, configure_resolver};
 use thiserror::Error;
 use tracing_actix_web::TracingLogger;
 
@@ -22,7 +22,9 @@ use std::io::ErrorKind;
 use std::net::SocketAddr;
 use std::time;
 
-use common::env_const::{get_deployment_mode, DeploymentMode, EXO_CORS_DOMAINS, EXO_SERVER_PORT};
+use common::env_const::{
+    get_deployment_mode, DeploymentMode, EXO_CORS_DOMAINS, EXO_METRICS_ENABLED, EXO_SERVER_PORT,
+};
 
 use exo_env::SystemEnvironment;
 
@@ -53,6 +55,13 @@ async fn main() -> Result<(), ServerError> {
 
     let system_resolver = web::Data::new(server_common::init().await);
 
+    if env::var(EXO_METRICS_ENABLED)
+        .map(|value| value == "true")
+        .unwrap_or(false)
+    {
+        core_resolver::metrics::enable();
+    }
+
     let server_port = env::var(EXO_SERVER_PORT)
         .map(|port_str| {
             port_str
@@ -72,6 +81,8 @@ async fn main() -> Result<(), ServerError> {
             .wrap(cors)
             .configure(configure_resolver(system_resolver.clone()))
             .configure(configure_playground)
+            .configure(configure_metrics)
+            .configure(configure_health)
     });
 
     let server_host = env::var(EXO_SERVER_HOST);
@@ -111,6 +122,10 @@ async fn main() -> Result<(), ServerError> {
                 );
                 println!("- Endpoint hosted at:");
                 println!("\thttp://{pretty_addr}{}", get_endpoint_http_path());
+                if core_resolver::metrics::get().is_some() {
+                    println!("- Metrics hosted at:");
+                    println!("\thttp://{pretty_addr}/metrics");
+                }
             };
 
             let print_playground_info = || {
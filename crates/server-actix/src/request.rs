@@ -7,8 +7,13 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use actix_web::{dev::ConnectionInfo, http::header::HeaderMap, HttpRequest};
+use actix_web::{
+    dev::ConnectionInfo,
+    http::header::{HeaderMap, HeaderName, HeaderValue},
+    HttpRequest,
+};
 use core_resolver::http::RequestHead;
+use serde_json::{Map, Value};
 
 pub struct ActixRequestHead {
     // we cannot refer to HttpRequest directly, as it holds an Rc (and therefore does
@@ -32,6 +37,25 @@ impl ActixRequestHead {
             query,
         }
     }
+
+    /// Add the connection parameters sent by a WebSocket client as headers.
+    ///
+    /// Browsers don't allow setting headers for a WebSocket handshake, so clients send values such
+    /// as `Authorization` in the `connection_init` message instead. These take precedence over the
+    /// headers in the handshake request. Non-string values are ignored.
+    pub fn with_connection_params(mut self, params: &Map<String, Value>) -> ActixRequestHead {
+        for (key, value) in params {
+            if let Value::String(value) = value {
+                let name = HeaderName::from_bytes(key.to_lowercase().as_bytes());
+                let value = HeaderValue::from_str(value);
+
+                if let (Ok(name), Ok(value)) = (name, value) {
+                    self.headers.insert(name, value);
+                }
+            }
+        }
+        self
+    }
 }
 
 impl RequestHead for ActixRequestHead {
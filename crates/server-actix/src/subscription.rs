@@ -0,0 +1,498 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Serve GraphQL operations over a WebSocket connection using the `graphql-transport-ws`
+//! subprotocol (see https://github.com/enisdenjo/graphql-ws/blob/master/PROTOCOL.md).
+//!
+//! While the protocol is primarily meant for subscriptions, clients may also send queries and
+//! mutations over the same connection (each produces a single `next` message).
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    time::Duration,
+};
+
+use actix_web::{
+    guard::GuardContext,
+    http::header::{HeaderName, HeaderValue},
+    web, HttpRequest, HttpResponse,
+};
+use actix_ws::{CloseCode, CloseReason, Message, MessageStream, Session};
+use futures::{
+    future::{AbortHandle, Abortable},
+    StreamExt,
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use core_resolver::{
+    context::RequestContext,
+    http::RequestHead,
+    system_resolver::{RequestError, SystemResolutionError, SystemResolver},
+    OperationsPayload, QueryResponse,
+};
+
+use crate::request::ActixRequestHead;
+
+const GRAPHQL_TRANSPORT_WS_PROTOCOL: &str = "graphql-transport-ws";
+
+/// How long we wait for the `connection_init` message before closing the connection
+const CONNECTION_INIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    ConnectionInit { payload: Option<Value> },
+    Ping { payload: Option<Value> },
+    Pong {},
+    Subscribe { id: String, payload: Value },
+    Complete { id: String },
+}
+
+/// Guard that matches WebSocket upgrade requests
+pub(crate) fn is_websocket_upgrade(ctx: &GuardContext) -> bool {
+    ctx.head()
+        .headers()
+        .get("upgrade")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.eq_ignore_ascii_case("websocket"))
+        .unwrap_or(false)
+}
+
+pub(crate) async fn subscribe(
+    req: HttpRequest,
+    body: web::Payload,
+    query: web::Query<Value>,
+    system_resolver: web::Data<SystemResolver>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let offers_protocol = req
+        .headers()
+        .get_all("sec-websocket-protocol")
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|protocol| protocol.trim() == GRAPHQL_TRANSPORT_WS_PROTOCOL);
+
+    if !offers_protocol {
+        return Ok(HttpResponse::BadRequest().body(format!(
+            "Only the '{GRAPHQL_TRANSPORT_WS_PROTOCOL}' WebSocket subprotocol is supported"
+        )));
+    }
+
+    let (mut response, session, messages) = actix_ws::handle(&req, body)?;
+    response.headers_mut().insert(
+        HeaderName::from_static("sec-websocket-protocol"),
+        HeaderValue::from_static(GRAPHQL_TRANSPORT_WS_PROTOCOL),
+    );
+
+    let request_head = ActixRequestHead::from_request(req, query.into_inner());
+
+    actix_web::rt::spawn(serve_connection(
+        request_head,
+        session,
+        messages,
+        system_resolver,
+    ));
+
+    Ok(response)
+}
+
+async fn serve_connection(
+    request_head: ActixRequestHead,
+    mut session: Session,
+    mut messages: MessageStream,
+    system_resolver: web::Data<SystemResolver>,
+) {
+    // The request head is available to operations only after the client has initialized the
+    // connection (which may supply additional headers through the `connection_init` payload)
+    let mut pending_request_head = Some(request_head);
+    let mut request_head: Option<Rc<ActixRequestHead>> = None;
+
+    let subscriptions: Rc<RefCell<HashMap<String, AbortHandle>>> = Default::default();
+
+    let initialized = Rc::new(Cell::new(false));
+    actix_web::rt::spawn({
+        let initialized = initialized.clone();
+        let session = session.clone();
+
+        async move {
+            actix_web::rt::time::sleep(CONNECTION_INIT_TIMEOUT).await;
+            if !initialized.get() {
+                let _ = session
+                    .close(Some(close_reason(
+                        4408,
+                        "Connection initialisation timeout",
+                    )))
+                    .await;
+            }
+        }
+    });
+
+    let close = loop {
+        let Some(Ok(message)) = messages.next().await else {
+            break None;
+        };
+
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Ping(bytes) => {
+                if session.pong(&bytes).await.is_err() {
+                    break None;
+                }
+                continue;
+            }
+            Message::Close(_) => break None,
+            _ => continue,
+        };
+
+        let client_message = match serde_json::from_str::<ClientMessage>(&text) {
+            Ok(client_message) => client_message,
+            Err(_) => break Some(close_reason(4400, "Invalid message received")),
+        };
+
+        match client_message {
+            ClientMessage::ConnectionInit { payload } => {
+                let Some(head) = pending_request_head.take() else {
+                    break Some(close_reason(4429, "Too many initialisation requests"));
+                };
+
+                let head = match payload {
+                    Some(Value::Object(params)) => head.with_connection_params(&params),
+                    _ => head,
+                };
+
+                request_head = Some(Rc::new(head));
+                initialized.set(true);
+
+                if send(&mut session, json!({ "type": "connection_ack" }))
+                    .await
+                    .is_err()
+                {
+                    break None;
+                }
+            }
+            ClientMessage::Ping { payload } => {
+                let pong = match payload {
+                    Some(payload) => json!({ "type": "pong", "payload": payload }),
+                    None => json!({ "type": "pong" }),
+                };
+
+                if send(&mut session, pong).await.is_err() {
+                    break None;
+                }
+            }
+            ClientMessage::Pong {} => {}
+            ClientMessage::Subscribe { id, payload } => {
+                let Some(request_head) = request_head.clone() else {
+                    break Some(close_reason(4401, "Unauthorized"));
+                };
+
+                if subscriptions.borrow().contains_key(&id) {
+                    break Some(close_reason(
+                        4409,
+                        &format!("Subscriber for {id} already exists"),
+                    ));
+                }
+
+                let (abort_handle, abort_registration) = AbortHandle::new_pair();
+                subscriptions.borrow_mut().insert(id.clone(), abort_handle);
+
+                let operation = run_operation(
+                    id.clone(),
+                    payload,
+                    request_head,
+                    session.clone(),
+                    system_resolver.clone(),
+                );
+
+                let subscriptions = subscriptions.clone();
+                actix_web::rt::spawn(async move {
+                    // A cancelled operation has already been removed (and the client may have
+                    // reused its id since), so only remove operations that ran to completion
+                    if Abortable::new(operation, abort_registration).await.is_ok() {
+                        subscriptions.borrow_mut().remove(&id);
+                    }
+                });
+            }
+            ClientMessage::Complete { id } => {
+                if let Some(abort_handle) = subscriptions.borrow_mut().remove(&id) {
+                    abort_handle.abort();
+                }
+            }
+        }
+    };
+
+    for (_, abort_handle) in subscriptions.borrow_mut().drain() {
+        abort_handle.abort();
+    }
+
+    let _ = session.close(close).await;
+}
+
+/// Run a single operation, sending each response as a `next` message followed by a `complete`
+/// message (or an `error` message if the operation could not be validated).
+async fn run_operation(
+    id: String,
+    payload: Value,
+    request_head: Rc<ActixRequestHead>,
+    mut session: Session,
+    system_resolver: web::Data<SystemResolver>,
+) {
+    let playground_request = request_head
+        .get_header("_exo_playground")
+        .map(|value| value == "true")
+        .unwrap_or(false);
+
+    let operation = OperationsPayload::from_json(payload)
+        .map_err(|e| SystemResolutionError::RequestError(RequestError::InvalidBodyJson(e)))
+        .and_then(|operations_payload| {
+            system_resolver.validate_operations_payload(
+                operations_payload,
+                resolver::trusted_document_enforcement(playground_request),
+            )
+        });
+
+    let operation = match operation {
+        Ok(operation) => operation,
+        Err(err) => {
+            let _ = send(
+                &mut session,
+                json!({ "id": id, "type": "error", "payload": [error_json(&err)] }),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let request_context =
+        RequestContext::new(request_head.as_ref(), vec![], system_resolver.as_ref());
+
+    let mut responses =
+        match resolver::resolve_streaming(&operation, &request_context, system_resolver.as_ref())
+            .await
+        {
+            Ok(responses) => responses,
+            Err(err) => {
+                let _ = send(
+                    &mut session,
+                    json!({ "id": id, "type": "error", "payload": [error_json(&err)] }),
+                )
+                .await;
+                return;
+            }
+        };
+
+    while let Some(response) = responses.next().await {
+        let payload = match response {
+            Ok(parts) => execution_result(parts),
+            Err(err) => {
+                tracing::error!("Error while resolving subscription: {:?}", err);
+                json!({ "errors": [error_json(&err)] })
+            }
+        };
+
+        if send(
+            &mut session,
+            json!({ "id": id, "type": "next", "payload": payload }),
+        )
+        .await
+        .is_err()
+        {
+            return;
+        }
+    }
+
+    let _ = send(&mut session, json!({ "id": id, "type": "complete" })).await;
+}
+
+fn execution_result(parts: Vec<(String, QueryResponse)>) -> Value {
+    let data = parts
+        .into_iter()
+        .map(|(name, response)| response.body.to_json().map(|value| (name, value)))
+        .collect::<Result<Map<_, _>, _>>();
+
+    match data {
+        Ok(data) => json!({ "data": data }),
+        Err(err) => {
+            tracing::error!("Invalid response from subsystem: {}", err);
+            json!({ "errors": [{ "message": "Internal server error" }] })
+        }
+    }
+}
+
+fn error_json(err: &SystemResolutionError) -> Value {
+    let mut error = json!({ "message": err.user_error_message() });
+
+    if let SystemResolutionError::Validation(err) = err {
+        error["locations"] = err
+            .positions()
+            .into_iter()
+            .map(|pos| json!({ "line": pos.line, "column": pos.column }))
+            .collect();
+    }
+
+    error
+}
+
+async fn send(session: &mut Session, message: Value) -> Result<(), actix_ws::Closed> {
+    session.text(message.to_string()).await
+}
+
+fn close_reason(code: u16, description: &str) -> CloseReason {
+    CloseReason {
+        code: CloseCode::Other(code),
+        description: Some(description.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use core_resolver::QueryResponseBody;
+
+    use super::*;
+
+    fn response(body: QueryResponseBody) -> QueryResponse {
+        QueryResponse {
+            body,
+            headers: vec![],
+        }
+    }
+
+    #[test]
+    fn parse_client_messages() {
+        let parse = |message: Value| serde_json::from_value::<ClientMessage>(message);
+
+        assert!(matches!(
+            parse(
+                json!({ "type": "connection_init", "payload": { "Authorization": "Bearer token" } })
+            ),
+            Ok(ClientMessage::ConnectionInit {
+                payload: Some(Value::Object(_))
+            })
+        ));
+        assert!(matches!(
+            parse(json!({ "type": "connection_init" })),
+            Ok(ClientMessage::ConnectionInit { payload: None })
+        ));
+        assert!(matches!(
+            parse(json!({ "type": "ping" })),
+            Ok(ClientMessage::Ping { payload: None })
+        ));
+        assert!(matches!(
+            parse(json!({ "type": "pong" })),
+            Ok(ClientMessage::Pong {})
+        ));
+        assert!(matches!(
+            parse(json!({ "type": "subscribe", "id": "1", "payload": { "query": "subscription { concerts { id } }" } })),
+            Ok(ClientMessage::Subscribe { id, payload: Value::Object(_) }) if id == "1"
+        ));
+        assert!(matches!(
+            parse(json!({ "type": "complete", "id": "1" })),
+            Ok(ClientMessage::Complete { id }) if id == "1"
+        ));
+
+        // Unknown message types and messages missing required fields are rejected (and close the
+        // connection)
+        assert!(parse(json!({ "type": "start", "id": "1" })).is_err());
+        assert!(parse(json!({ "type": "subscribe", "payload": {} })).is_err());
+        assert!(parse(json!({ "id": "1" })).is_err());
+    }
+
+    #[test]
+    fn websocket_upgrade_guard() {
+        let is_upgrade = |request: TestRequest| {
+            let request = request.to_srv_request();
+            is_websocket_upgrade(&request.guard_ctx())
+        };
+
+        assert!(is_upgrade(
+            TestRequest::default().insert_header(("upgrade", "websocket"))
+        ));
+        assert!(is_upgrade(
+            TestRequest::default().insert_header(("upgrade", "WebSocket"))
+        ));
+        assert!(!is_upgrade(TestRequest::default()));
+        assert!(!is_upgrade(
+            TestRequest::default().insert_header(("upgrade", "h2c"))
+        ));
+    }
+
+    #[test]
+    fn pushed_row_payload() {
+        let parts = vec![(
+            "concerts".to_string(),
+            response(QueryResponseBody::Raw(Some(
+                r#"{"id": 1, "title": "C1"}"#.to_string(),
+            ))),
+        )];
+
+        assert_eq!(
+            execution_result(parts),
+            json!({ "data": { "concerts": { "id": 1, "title": "C1" } } })
+        );
+    }
+
+    #[test]
+    fn multiple_fields_payload() {
+        let parts = vec![
+            (
+                "concerts".to_string(),
+                response(QueryResponseBody::Json(json!([{ "id": 1 }]))),
+            ),
+            ("venue".to_string(), response(QueryResponseBody::Raw(None))),
+        ];
+
+        assert_eq!(
+            execution_result(parts),
+            json!({ "data": { "concerts": [{ "id": 1 }], "venue": null } })
+        );
+    }
+
+    #[test]
+    fn invalid_response_payload() {
+        let parts = vec![(
+            "concerts".to_string(),
+            response(QueryResponseBody::Raw(Some("not json".to_string()))),
+        )];
+
+        assert_eq!(
+            execution_result(parts),
+            json!({ "errors": [{ "message": "Internal server error" }] })
+        );
+    }
+
+    #[test]
+    fn error_payload() {
+        assert_eq!(
+            error_json(&SystemResolutionError::SubscriptionNotSupported),
+            json!({ "message": "Subscriptions are only supported over a WebSocket connection" })
+        );
+
+        // Internal errors are not revealed to the client
+        assert_eq!(
+            error_json(&SystemResolutionError::Generic(
+                "connection refused".to_string()
+            )),
+            json!({ "message": "Internal server error" })
+        );
+    }
+
+    #[test]
+    fn close_reasons() {
+        let reason = close_reason(4400, "Invalid message received");
+
+        assert_eq!(reason.code, CloseCode::Other(4400));
+        assert_eq!(
+            reason.description.as_deref(),
+            Some("Invalid message received")
+        );
+    }
+}
@@ -124,6 +124,7 @@ fn compute_primitive_expr(
             CommonAccessPrimitiveExpression::NumberLiteral(*value),
         ),
         AstExpr::StringList(_, _) => panic!("Module access expressions do not support lists yet"),
+        AstExpr::ObjectLiteral(_, _) => unreachable!(), // Parser allows objects only as named annotation parameters
         AstExpr::LogicalOp(_) => unreachable!(), // Parser has already ensures that the two sides are primitive expressions
         AstExpr::RelationalOp(_) => unreachable!(), // Parser has already ensures that the two sides are primitive expressions
     }
@@ -64,17 +64,19 @@ pub fn build_expanded(building: &mut SystemContextBuilding) {
 }
 
 fn get_or_populate_script(
-    script_path: &str,
-    script: &[u8],
+    resolved_module: &ResolvedModule,
     building: &mut SystemContextBuilding,
 ) -> SerializableSlabIndex<Script> {
+    let script_path = &resolved_module.script_path;
+
     match building.scripts.get_id(script_path) {
         Some(index) => index,
         None => building.scripts.add(
             script_path,
             Script {
                 path: script_path.to_owned(),
-                script: script.to_owned(),
+                script: resolved_module.script.to_owned(),
+                limits: resolved_module.limits,
             },
         ),
     }
@@ -85,11 +87,7 @@ fn create_shallow_module(
     resolved_method: &ResolvedMethod,
     building: &mut SystemContextBuilding,
 ) {
-    let script = get_or_populate_script(
-        &resolved_module.script_path,
-        &resolved_module.script,
-        building,
-    );
+    let script = get_or_populate_script(resolved_module, building);
 
     building.methods.add(
         &resolved_method.name,
@@ -97,6 +95,8 @@ fn create_shallow_module(
             name: resolved_method.name.clone(),
             script,
             access: Access::restrictive(),
+            cache: resolved_method.cache,
+            rate_limit: resolved_method.rate_limit.clone(),
             operation_kind: match resolved_method.operation_kind {
                 ResolvedMethodType::Query => {
                     let query = shallow_module_query(resolved_method, &building.types, building);
@@ -159,6 +159,7 @@ fn shallow_module_query(
         method_id: None,
         argument_param: argument_param(method, building),
         return_type: compute_shallow_return_type(&method.return_type, module_types),
+        cost: method.cost,
     }
 }
 
@@ -172,6 +173,7 @@ fn shallow_module_mutation(
         method_id: None,
         argument_param: argument_param(method, building),
         return_type: compute_shallow_return_type(&method.return_type, module_types),
+        cost: method.cost,
     }
 }
 
@@ -241,11 +243,7 @@ pub fn create_shallow_interceptor(
     resolved_interceptor: &ResolvedInterceptor,
     building: &mut SystemContextBuilding,
 ) {
-    let script = get_or_populate_script(
-        &resolved_module.script_path,
-        &resolved_module.script,
-        building,
-    );
+    let script = get_or_populate_script(resolved_module, building);
 
     building.interceptors.insert(Interceptor {
         module_name: resolved_module.name.clone(),
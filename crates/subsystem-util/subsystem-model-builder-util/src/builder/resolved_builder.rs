@@ -14,9 +14,12 @@ use codemap::Span;
 use codemap_diagnostic::{Diagnostic, Level, SpanLabel, SpanStyle};
 
 use core_model::types::{FieldType, Named};
+use core_model::{cache::CachePolicy, execution_limits::ExecutionLimits, rate_limit::RateLimit};
 use core_model::{mapped_arena::MappedArena, primitive_type::PrimitiveType};
 use core_model_builder::ast::ast_types::AstFieldType;
-use core_model_builder::builder::resolved_builder::AnnotationMapHelper;
+use core_model_builder::builder::resolved_builder::{
+    build_cache_policy, build_cost_weight, build_rate_limit, refers_to_context, AnnotationMapHelper,
+};
 use core_model_builder::builder::system_builder::BaseModelSystem;
 use core_model_builder::typechecker::typ::{Module, TypecheckedSystem};
 use core_model_builder::typechecker::AnnotationMap;
@@ -85,6 +88,7 @@ pub struct ResolvedModule {
     pub name: String,
     pub script: Vec<u8>,
     pub script_path: String,
+    pub limits: ExecutionLimits,
     pub methods: Vec<ResolvedMethod>,
     pub interceptors: Vec<ResolvedInterceptor>,
     pub types_defined: HashSet<String>, // Typed defined in the module
@@ -96,6 +100,9 @@ pub struct ResolvedMethod {
     pub operation_kind: ResolvedMethodType,
     pub is_exported: bool,
     pub access: ResolvedAccess,
+    pub cache: Option<CachePolicy>,
+    pub cost: Option<u32>,
+    pub rate_limit: Option<RateLimit>,
     pub arguments: Vec<ResolvedArgument>,
     pub return_type: FieldType<ResolvedFieldType>,
 }
@@ -247,6 +254,57 @@ async fn resolve_modules(
     Ok(resolved_modules)
 }
 
+/// Build the execution limits of a module from its annotation such as `@deno(path: "stripe.ts",
+/// timeout: "5s", memory: "128MB")`
+fn build_execution_limits(
+    module: &AstModule<Typed>,
+    annotation_name: &str,
+    errors: &mut Vec<Diagnostic>,
+) -> ExecutionLimits {
+    let params = match module.annotations.get(annotation_name) {
+        Some(AstAnnotationParams::Map(params, _)) => params,
+        _ => return ExecutionLimits::default(),
+    };
+
+    let mut limit = |param: &str, parse: fn(&str) -> Option<u64>, expected: &str| {
+        let value = match params.get(param)? {
+            AstExpr::StringLiteral(value, _) => parse(value),
+            _ => None,
+        };
+
+        if value.is_none() {
+            errors.push(Diagnostic {
+                level: Level::Error,
+                message: format!(
+                    "Invalid {param} of module '{}' (expected {expected})",
+                    module.name
+                ),
+                code: Some("C000".to_string()),
+                spans: vec![SpanLabel {
+                    span: module.span,
+                    style: SpanStyle::Primary,
+                    label: None,
+                }],
+            });
+        }
+
+        value
+    };
+
+    ExecutionLimits {
+        timeout_ms: limit(
+            "timeout",
+            ExecutionLimits::parse_timeout,
+            "a duration such as \"500ms\", \"5s\", or \"1m\"",
+        ),
+        memory_bytes: limit(
+            "memory",
+            ExecutionLimits::parse_memory,
+            "a size such as \"64MB\" or \"1GB\"",
+        ),
+    }
+}
+
 async fn resolve_module(
     module: &AstModule<Typed>,
     base_system: &BaseModelSystem,
@@ -261,9 +319,25 @@ async fn resolve_module(
     ) -> Result<(String, Vec<u8>), ModelBuildingError>,
 ) -> Result<(), ModelBuildingError> {
     // Extract the source path from the annotation
-    // `@deno("util/auth.ts")` -> `util/auth.ts`
+    // `@deno("util/auth.ts")` or `@deno(path: "util/auth.ts", ...)` -> `util/auth.ts`
     let module_relative_path = match module.annotations.get(&annotation_name).unwrap() {
         AstAnnotationParams::Single(AstExpr::StringLiteral(s, _), _) => s,
+        AstAnnotationParams::Map(params, _) => match params.get("path") {
+            Some(AstExpr::StringLiteral(s, _)) => s,
+            _ => {
+                errors.push(Diagnostic {
+                    level: Level::Error,
+                    message: format!("The path of the module '{}' must be a string", module.name),
+                    code: Some("C000".to_string()),
+                    spans: vec![SpanLabel {
+                        span: module.span,
+                        style: SpanStyle::Primary,
+                        label: None,
+                    }],
+                });
+                return Err(ModelBuildingError::Diagnosis(errors.clone()));
+            }
+        },
         _ => panic!(),
     }
     .clone();
@@ -275,6 +349,8 @@ async fn resolve_module(
 
     let (script_path, bundled_script) = process_script(module, base_system, &source_path)?;
 
+    let limits = build_execution_limits(module, &annotation_name, errors);
+
     fn extract_intercept_annot<'a>(
         annotations: &'a AnnotationMap,
         key: &str,
@@ -282,17 +358,61 @@ async fn resolve_module(
         annotations.get(key).map(|a| a.as_single())
     }
 
+    // A module-level rate limit applies to each method without its own (sharing the allowance)
+    let module_rate_limit = build_rate_limit(
+        module.annotations.get("rateLimit"),
+        &module.name,
+        types,
+        module.span,
+        errors,
+    );
+
     resolved_modules.add(
         &module.name,
         ResolvedModule {
             name: module.name.clone(),
             script: bundled_script,
             script_path,
+            limits,
             methods: module
                 .methods
                 .iter()
                 .map(|m| {
                     let access = build_access(m.annotations.get("access"));
+                    // Responses depend on the request if the access rule refers to a context or
+                    // the method receives a context (through an injected argument)
+                    let request_dependent = refers_to_context(&access.value, types)
+                        || m
+                            .arguments
+                            .iter()
+                            .any(|argument| argument.annotations.get("inject").is_some());
+                    let cache = build_cache_policy(
+                        m.annotations.get("cache"),
+                        request_dependent,
+                        m.span,
+                        errors,
+                    );
+                    if cache.is_some() && m.typ == AstMethodType::Mutation {
+                        errors.push(Diagnostic {
+                            level: Level::Error,
+                            message: format!("Mutation '{}' cannot have a @cache annotation", m.name),
+                            code: Some("C000".to_string()),
+                            spans: vec![SpanLabel {
+                                span: m.span,
+                                style: SpanStyle::Primary,
+                                label: None,
+                            }],
+                        });
+                    }
+                    let cost = build_cost_weight(m.annotations.get("cost"), m.span, errors);
+                    let rate_limit = build_rate_limit(
+                        m.annotations.get("rateLimit"),
+                        &m.name,
+                        types,
+                        m.span,
+                        errors,
+                    )
+                    .or_else(|| module_rate_limit.clone());
                     ResolvedMethod {
                         name: m.name.clone(),
                         operation_kind: match m.typ {
@@ -301,6 +421,9 @@ async fn resolve_module(
                         },
                         is_exported: m.is_exported,
                         access,
+                        cache,
+                        cost,
+                        rate_limit,
                         arguments: m
                             .arguments
                             .iter()
@@ -553,6 +676,7 @@ mod tests {
         typechecker::{self, annotation_map::AnnotationMapImpl},
     };
     use codemap::CodeMap;
+    use core_model::execution_limits::ExecutionLimits;
     use core_model_builder::{
         ast::ast_types::AstModule, builder::system_builder::BaseModelSystem,
         error::ModelBuildingError, typechecker::Typed,
@@ -602,6 +726,39 @@ mod tests {
         assert_err(model).await;
     }
 
+    #[tokio::test]
+    async fn module_limits() {
+        let model = r#"
+            @deno(path: "x.ts", timeout: "5s", memory: "128MB")
+            module TestModule {
+                query getFoo(key: Int): Int
+            }
+        "#;
+
+        let system = create_resolved_system(model).await.unwrap();
+        let module = system.modules.get_by_key("TestModule").unwrap();
+
+        assert_eq!(
+            module.limits,
+            ExecutionLimits {
+                timeout_ms: Some(5000),
+                memory_bytes: Some(128 * 1024 * 1024),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_module_limits() {
+        let model = r#"
+            @deno(path: "x.ts", timeout: "5 seconds")
+            module TestModule {
+                query getFoo(key: Int): Int
+            }
+        "#;
+
+        assert_err(model).await;
+    }
+
     async fn assert_success(src: &str) {
         assert!(create_resolved_system(src).await.is_ok())
     }
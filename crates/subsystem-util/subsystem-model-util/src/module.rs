@@ -15,7 +15,10 @@ use super::{
     operation::{ModuleMutation, ModuleQuery},
     types::ModuleType,
 };
-use core_model::{mapped_arena::SerializableSlabIndex, types::FieldType};
+use core_model::{
+    cache::CachePolicy, execution_limits::ExecutionLimits, mapped_arena::SerializableSlabIndex,
+    rate_limit::RateLimit, types::FieldType,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ModuleMethod {
@@ -25,6 +28,8 @@ pub struct ModuleMethod {
     pub is_exported: bool,
     pub arguments: Vec<Argument>,
     pub access: Access,
+    pub cache: Option<CachePolicy>,
+    pub rate_limit: Option<RateLimit>,
     pub return_type: ModuleOperationReturnType,
 }
 
@@ -32,6 +37,8 @@ pub struct ModuleMethod {
 pub struct Script {
     pub path: String,
     pub script: Vec<u8>,
+    /// The limits specified for the module (the resolver fills in the rest from the environment)
+    pub limits: ExecutionLimits,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,6 +24,8 @@ pub struct ModuleQuery {
     pub method_id: Option<SerializableSlabIndex<ModuleMethod>>,
     pub argument_param: Vec<ArgumentParameter>,
     pub return_type: ModuleOperationReturnType,
+    /// The weight of the operation for query cost analysis (`@cost(...)`)
+    pub cost: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -32,6 +34,8 @@ pub struct ModuleMutation {
     pub method_id: Option<SerializableSlabIndex<ModuleMethod>>,
     pub argument_param: Vec<ArgumentParameter>,
     pub return_type: ModuleOperationReturnType,
+    /// The weight of the operation for query cost analysis (`@cost(...)`)
+    pub cost: Option<u32>,
 }
 
 impl Operation for ModuleQuery {
@@ -52,6 +56,10 @@ impl Operation for ModuleQuery {
     fn return_type(&self) -> Type {
         return_type(&self.return_type)
     }
+
+    fn cost(&self) -> Option<u32> {
+        self.cost
+    }
 }
 
 impl Operation for ModuleMutation {
@@ -72,6 +80,10 @@ impl Operation for ModuleMutation {
     fn return_type(&self) -> Type {
         return_type(&self.return_type)
     }
+
+    fn cost(&self) -> Option<u32> {
+        self.cost
+    }
 }
 
 fn return_type(module_return_type: &ModuleOperationReturnType) -> Type {
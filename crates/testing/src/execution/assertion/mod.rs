@@ -12,7 +12,7 @@ use std::collections::{HashMap, HashSet};
 use anyhow::{anyhow, Result};
 use exo_deno::{
     deno_core::{url::Url, ModuleType},
-    deno_executor_pool::{DenoScriptDefn, ResolvedModule},
+    deno_executor_pool::{DenoLimits, DenoScriptDefn, ResolvedModule},
     Arg, DenoModule, DenoModuleSharedState, UserCode,
 };
 
@@ -64,6 +64,8 @@ pub async fn dynamic_assert_using_deno(
                 .into_iter()
                 .collect(),
                 npm_snapshot: None,
+                permissions: None,
+                limits: DenoLimits::default(),
             },
         },
         "ExographTest",
@@ -131,6 +133,8 @@ pub async fn evaluate_using_deno(
                 .into_iter()
                 .collect(),
                 npm_snapshot: None,
+                permissions: None,
+                limits: DenoLimits::default(),
             },
         },
         "ExographTest",
@@ -15,23 +15,25 @@ use common::env_const::{
     EXO_POSTGRES_URL,
 };
 use core_plugin_interface::trusted_documents::TrustedDocumentEnforcement;
+use core_resolver::context::RequestContext;
 use core_resolver::http::RequestHead;
 use core_resolver::http::RequestPayload;
 use core_resolver::system_resolver::{SystemResolutionError, SystemResolver};
-use core_resolver::OperationsPayload;
+use core_resolver::{OperationsPayload, QueryResponse};
 use exo_sql::testing::db::EphemeralDatabaseServer;
 use futures::future::OptionFuture;
-use futures::FutureExt;
+use futures::{FutureExt, StreamExt};
 use jsonwebtoken::{encode, EncodingKey, Header};
 use rand::{distributions::Alphanumeric, Rng};
 use regex::Regex;
-use resolver::{create_system_resolver, resolve_in_memory};
+use resolver::{create_system_resolver, resolve_in_memory, resolve_streaming};
 use serde_json::{json, Map, Value};
 use unescape::unescape;
 
 use std::net::{IpAddr, Ipv4Addr};
 use std::path::PathBuf;
 use std::sync::mpsc::Sender;
+use std::sync::Arc;
 use std::time::Duration;
 use std::{collections::HashMap, time::SystemTime};
 
@@ -45,7 +47,7 @@ use super::{TestResult, TestResultKind};
 /// Structure to hold open resources associated with a running testfile.
 /// When dropped, we will clean them up.
 struct TestfileContext {
-    server: SystemResolver,
+    server: Arc<SystemResolver>,
     jwtsecret: String,
     cookies: HashMap<String, String>,
     testvariables: HashMap<String, serde_json::Value>,
@@ -178,7 +180,7 @@ impl IntegrationTest {
 
                 let env = MapEnvironment::from(env);
 
-                create_system_resolver(&exo_ir_file, static_loaders, Box::new(env)).await?
+                Arc::new(create_system_resolver(&exo_ir_file, static_loaders, Box::new(env)).await?)
             };
 
             TestfileContext {
@@ -239,6 +241,10 @@ impl IntegrationTest {
     }
 }
 
+/// How long we wait for the next event pushed to a subscription before considering that there are
+/// no more events
+const SUBSCRIPTION_EVENT_TIMEOUT: Duration = Duration::from_secs(2);
+
 enum OperationResult {
     Finished,
     AssertPassed,
@@ -343,6 +349,7 @@ async fn run_operation(
         auth,
         headers,
         deno_prelude,
+        triggers,
     } = gql;
 
     let deno_prelude = deno_prelude.clone().unwrap_or_default();
@@ -421,9 +428,13 @@ async fn run_operation(
         query_hash: None,
     };
 
-    let request = MemoryRequestPayload::new(operations_payload.to_json()?, request_head);
     // run the operation
-    let body = run_query(request, &ctx.server, &mut ctx.cookies).await;
+    let body = if triggers.is_empty() {
+        let request = MemoryRequestPayload::new(operations_payload.to_json()?, request_head);
+        run_query(request, &ctx.server, &mut ctx.cookies).await
+    } else {
+        run_subscription(operations_payload, request_head, triggers, ctx).await?
+    };
 
     // resolve testvariables from the result of our current operation
     // and extend our collection with them
@@ -464,6 +475,50 @@ async fn run_operation(
     }
 }
 
+/// Run a subscription operation and the trigger operations (while the subscription is active).
+///
+/// Returns the list of payloads pushed to the subscriber, so a test can assert on the events
+/// delivered (or not delivered, for example, due to access control) for the triggers.
+async fn run_subscription(
+    operations_payload: OperationsPayload,
+    request_head: MemoryRequestHead,
+    triggers: &[IntegrationTestOperation],
+    ctx: &mut TestfileContext,
+) -> Result<Value> {
+    let server = ctx.server.clone();
+    let request_context = RequestContext::new(&request_head, vec![], server.as_ref());
+
+    let operation = server
+        .validate_operations_payload(operations_payload, TrustedDocumentEnforcement::DoNotEnforce);
+    let operation = match operation {
+        Ok(operation) => operation,
+        Err(err) => return Ok(response_body(Err(err))),
+    };
+
+    let mut events = match resolve_streaming(&operation, &request_context, server.as_ref()).await {
+        Ok(events) => events,
+        Err(err) => return Ok(response_body(Err(err))),
+    };
+
+    for trigger in triggers {
+        match Box::pin(run_operation(trigger, ctx)).await? {
+            OperationResult::Finished | OperationResult::AssertPassed => {}
+            OperationResult::AssertFailed(e) => {
+                return Err(e.context("While running a subscription trigger"))
+            }
+        }
+    }
+
+    let mut payloads = vec![];
+    while let Ok(Some(event)) =
+        tokio::time::timeout(SUBSCRIPTION_EVENT_TIMEOUT, events.next()).await
+    {
+        payloads.push(response_body(event));
+    }
+
+    Ok(Value::Array(payloads))
+}
+
 pub async fn run_query(
     request: impl RequestPayload,
     server: &SystemResolver,
@@ -471,20 +526,26 @@ pub async fn run_query(
 ) -> Value {
     let res = resolve_in_memory(request, server, TrustedDocumentEnforcement::DoNotEnforce).await;
 
-    match res {
-        Ok(res) => {
-            res.iter().for_each(|(_, r)| {
-                r.headers.iter().for_each(|(k, v)| {
-                    if k.to_ascii_lowercase() == "set-cookie" {
-                        let cookie = v.split(';').next().unwrap();
-                        let mut cookie = cookie.split('=');
-                        let key = cookie.next().unwrap();
-                        let value = cookie.next().unwrap();
-                        cookies.insert(key.to_string(), value.to_string());
-                    }
-                });
+    if let Ok(res) = &res {
+        res.iter().for_each(|(_, r)| {
+            r.headers.iter().for_each(|(k, v)| {
+                if k.to_ascii_lowercase() == "set-cookie" {
+                    let cookie = v.split(';').next().unwrap();
+                    let mut cookie = cookie.split('=');
+                    let key = cookie.next().unwrap();
+                    let value = cookie.next().unwrap();
+                    cookies.insert(key.to_string(), value.to_string());
+                }
             });
+        });
+    }
+
+    response_body(res)
+}
 
+fn response_body(res: Result<Vec<(String, QueryResponse)>, SystemResolutionError>) -> Value {
+    match res {
+        Ok(res) => {
             serde_json::json!({
                 "data": res.iter().map(|(name, result)| {
                     (name.clone(), result.body.to_json().unwrap())
@@ -14,7 +14,7 @@ use core_resolver::{system_resolver::SystemResolver, OperationsPayload};
 use exo_deno::{
     deno_core::{url::Url, ModuleType},
     deno_error::DenoError,
-    deno_executor_pool::{DenoScriptDefn, ResolvedModule},
+    deno_executor_pool::{DenoLimits, DenoScriptDefn, ResolvedModule},
     Arg, DenoModule, DenoModuleSharedState, UserCode,
 };
 use exo_env::MapEnvironment;
@@ -87,6 +87,8 @@ async fn check_introspection(server: &SystemResolver) -> Result<Result<()>> {
                 .into_iter()
                 .collect(),
                 npm_snapshot: None,
+                permissions: None,
+                limits: DenoLimits::default(),
             },
         },
         "ExographTest",
@@ -31,6 +31,10 @@ struct TestfileStage {
     pub variable: Option<String>,
     pub auth: Option<String>,
     pub response: Option<String>,
+    /// Operations to run while the subscription in `operation` is active (the response is then
+    /// the list of payloads pushed to the subscriber)
+    #[serde(default)]
+    pub triggers: Vec<TestfileStage>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -55,6 +59,26 @@ struct TestfileMultipleStages {
     pub stages: Vec<TestfileStage>,
 }
 
+fn load_stage(stage: TestfileStage) -> IntegrationTestOperation {
+    let operations_metadata = parse_query(&stage.operation)
+        .map(|gql_document| build_operations_metadata(&gql_document))
+        .unwrap_or_else(|_| {
+            eprintln!("Invalid GraphQL document; defaulting test variables binding to empty");
+            OperationsMetadata::default()
+        });
+
+    IntegrationTestOperation {
+        document: stage.operation,
+        operations_metadata,
+        auth: stage.auth,
+        variables: stage.variable,
+        expected_payload: stage.response,
+        headers: stage.headers,
+        deno_prelude: stage.deno,
+        triggers: stage.triggers.into_iter().map(load_stage).collect(),
+    }
+}
+
 impl IntegrationTest {
     pub fn name(&self) -> String {
         let relative_testfile_path = {
@@ -115,29 +139,7 @@ impl IntegrationTest {
         };
 
         // validate GraphQL
-        let test_operation_sequence = stages
-            .into_iter()
-            .map(|stage| {
-                let operations_metadata = parse_query(&stage.operation)
-                    .map(|gql_document| build_operations_metadata(&gql_document))
-                    .unwrap_or_else(|_| {
-                        eprintln!(
-                            "Invalid GraphQL document; defaulting test variables binding to empty"
-                        );
-                        OperationsMetadata::default()
-                    });
-
-                Ok(IntegrationTestOperation {
-                    document: stage.operation,
-                    operations_metadata,
-                    auth: stage.auth,
-                    variables: stage.variable,
-                    expected_payload: stage.response,
-                    headers: stage.headers,
-                    deno_prelude: stage.deno,
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
+        let test_operation_sequence = stages.into_iter().map(load_stage).collect();
 
         assert!(common.retries <= 5, "The maximum number of retries is 5");
 
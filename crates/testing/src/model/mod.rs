@@ -40,4 +40,6 @@ pub struct IntegrationTestOperation {
     pub deno_prelude: Option<String>,
     pub auth: Option<String>,    // stringified
     pub headers: Option<String>, // stringified
+    /// Operations to run while the (subscription) operation is active
+    pub triggers: Vec<IntegrationTestOperation>,
 }
@@ -11,3 +11,4 @@ pub use plugin::WasmSubsystemBuilder;
 
 mod plugin;
 mod system_builder;
+mod wit_generator;
@@ -17,7 +17,7 @@ use core_plugin_interface::{
         error::ModelBuildingError,
         plugin::{Interception, SubsystemBuild},
         typechecker::{
-            annotation::{AnnotationSpec, AnnotationTarget},
+            annotation::{AnnotationSpec, AnnotationTarget, MappedAnnotationParamSpec},
             typ::TypecheckedSystem,
         },
     },
@@ -40,7 +40,23 @@ impl SubsystemBuilder for WasmSubsystemBuilder {
                 targets: &[AnnotationTarget::Module],
                 no_params: false,
                 single_params: true,
-                mapped_params: None,
+                mapped_params: Some(&[
+                    MappedAnnotationParamSpec {
+                        name: "path",
+                        optional: false,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "timeout",
+                        optional: true,
+                        keywords: &[],
+                    },
+                    MappedAnnotationParamSpec {
+                        name: "memory",
+                        optional: true,
+                        keywords: &[],
+                    },
+                ]),
             },
         )]
     }
@@ -17,6 +17,8 @@ use core_plugin_interface::{
     },
 };
 use std::path::Path;
+
+use crate::wit_generator;
 use wasm_model::{
     interceptor::Interceptor,
     operation::{WasmMutation, WasmQuery},
@@ -77,13 +79,19 @@ pub async fn build(
 }
 
 fn process_script(
-    _module: &AstModule<Typed>,
-    _base_system: &BaseModelSystem,
+    module: &AstModule<Typed>,
+    base_system: &BaseModelSystem,
     module_fs_path: &Path,
 ) -> Result<(String, Vec<u8>), ModelBuildingError> {
+    let wit_file = wit_generator::generate_wit_file(module, &base_system.contexts)?;
+
     std::fs::read(module_fs_path)
         .map(|o| (module_fs_path.to_str().unwrap().to_string(), o))
         .map_err(|err| {
-            ModelBuildingError::Generic(format!("While trying to read bundled module: {err}"))
+            ModelBuildingError::Generic(format!(
+                "While trying to read the WASM component {}: {err} (build it as a component targeting the world in {})",
+                module_fs_path.display(),
+                wit_file.display()
+            ))
         })
 }
@@ -0,0 +1,412 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+
+use heck::ToKebabCase;
+
+use core_plugin_interface::core_model::{
+    context_type::{ContextFieldType, ContextType},
+    mapped_arena::MappedArena,
+    primitive_type::PrimitiveType,
+};
+use core_plugin_interface::core_model_builder::{
+    ast::ast_types::{AstArgument, AstFieldType, AstModule},
+    error::ModelBuildingError,
+    typechecker::Typed,
+};
+
+/// Types injected by Exograph that a component accesses through the imported `exograph` interface
+/// (instead of through an argument)
+const EXOGRAPH_TYPE_NAMES: [&str; 3] = ["Exograph", "ExographPriv", "Operation"];
+
+/// Generates the WIT world for a WASM module based on its declaration in the exo file, so that the
+/// module can be implemented as a component (for example, using `cargo component` or
+/// `componentize-py`) targeting that world.
+///
+/// # Example:
+/// For a module definition in a exo file as follows:
+/// ```exo
+/// @wasm("todo.wasm")
+/// module TodoModule {
+///     type Todo {
+///       userId: Int
+///       title: String
+///       completed: Boolean
+///     }
+///
+///     query todo(id: Int, exograph: Exograph): Todo
+/// }
+/// ```
+///
+/// The generated WIT will look like this:
+/// ```wit
+/// package exograph:module;
+///
+/// interface exograph {
+///     execute-query: func(query: string, variables: option<string>) -> result<string, string>;
+/// }
+///
+/// world todo-module {
+///     import exograph;
+///
+///     record todo {
+///         user-id: s32,
+///         title: string,
+///         completed: bool,
+///     }
+///
+///     export todo: func(id: s32) -> result<todo, string>;
+/// }
+/// ```
+///
+/// Each function returns a `result` so that the module can report an error to the user.
+pub fn generate_wit(
+    module: &AstModule<Typed>,
+    contexts: &MappedArena<ContextType>,
+) -> Result<String, ModelBuildingError> {
+    let module_type_names: Vec<_> = module.types.iter().map(|typ| typ.name.as_str()).collect();
+    let wit_type = |typ: &AstFieldType<Typed>| field_wit_type(typ, &module_type_names, contexts);
+
+    let mut lines = vec![
+        format!(
+            "// Generated by Exograph from the declaration of the module '{}'. Do not edit.",
+            module.name
+        ),
+        "package exograph:module;".to_string(),
+        String::new(),
+        "interface exograph {".to_string(),
+        "    /// Execute a GraphQL query or mutation (the variables and the result are JSON-encoded)"
+            .to_string(),
+        "    execute-query: func(query: string, variables: option<string>) -> result<string, string>;"
+            .to_string(),
+        "}".to_string(),
+        String::new(),
+        format!("world {} {{", identifier(&module.name)),
+        "    import exograph;".to_string(),
+    ];
+
+    for typ in module.types.iter() {
+        lines.push(String::new());
+        lines.push(format!("    record {} {{", identifier(&typ.name)));
+        for field in typ.fields.iter() {
+            lines.push(format!(
+                "        {}: {},",
+                identifier(&field.name),
+                wit_type(&field.typ)?
+            ));
+        }
+        lines.push("    }".to_string());
+    }
+
+    for context in injected_contexts(module, contexts) {
+        lines.push(String::new());
+        lines.push(format!("    record {} {{", identifier(&context.name)));
+        for field in context.fields.iter() {
+            lines.push(format!(
+                "        {}: {},",
+                identifier(&field.name),
+                context_field_wit_type(&field.typ)?
+            ));
+        }
+        lines.push("    }".to_string());
+    }
+
+    if !module.methods.is_empty() {
+        lines.push(String::new());
+    }
+
+    for method in module.methods.iter() {
+        let arguments = method
+            .arguments
+            .iter()
+            .filter(|argument| !is_exograph_argument(argument))
+            .map(|argument| {
+                Ok(format!(
+                    "{}: {}",
+                    identifier(&argument.name),
+                    wit_type(&argument.typ)?
+                ))
+            })
+            .collect::<Result<Vec<_>, ModelBuildingError>>()?;
+
+        lines.push(format!(
+            "    export {}: func({}) -> result<{}, string>;",
+            identifier(&method.name),
+            arguments.join(", "),
+            wit_type(&method.return_type)?
+        ));
+    }
+
+    lines.push("}".to_string());
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Write the WIT world for the module to `generated/<module name>.wit`
+pub fn generate_wit_file(
+    module: &AstModule<Typed>,
+    contexts: &MappedArena<ContextType>,
+) -> Result<PathBuf, ModelBuildingError> {
+    let wit = generate_wit(module, contexts)?;
+
+    // Assume that (currently satisfied by the cli) that the current working directory is the root of the project.
+    let generated_dir = PathBuf::from("generated");
+    std::fs::create_dir_all(&generated_dir)?;
+
+    let wit_file = generated_dir.join(format!("{}.wit", module.name));
+    std::fs::write(&wit_file, wit)?;
+
+    Ok(wit_file)
+}
+
+fn is_exograph_argument(argument: &AstArgument<Typed>) -> bool {
+    EXOGRAPH_TYPE_NAMES.contains(&argument.typ.name().as_str())
+}
+
+/// Contexts injected into any method of the module (in the order of the context definitions)
+fn injected_contexts<'a>(
+    module: &AstModule<Typed>,
+    contexts: &'a MappedArena<ContextType>,
+) -> Vec<&'a ContextType> {
+    contexts
+        .iter()
+        .map(|(_, context)| context)
+        .filter(|context| {
+            module
+                .methods
+                .iter()
+                .flat_map(|method| method.arguments.iter())
+                .any(|argument| argument.typ.name() == context.name)
+        })
+        .collect()
+}
+
+fn field_wit_type(
+    typ: &AstFieldType<Typed>,
+    module_type_names: &[&str],
+    contexts: &MappedArena<ContextType>,
+) -> Result<String, ModelBuildingError> {
+    match typ {
+        AstFieldType::Optional(typ) => Ok(format!(
+            "option<{}>",
+            field_wit_type(typ, module_type_names, contexts)?
+        )),
+        AstFieldType::Plain(_, name, type_params, ..) => match name.as_str() {
+            "Set" | "Array" => {
+                let element_type = type_params.first().ok_or_else(|| {
+                    ModelBuildingError::Generic(format!("Missing element type for '{name}'"))
+                })?;
+                Ok(format!(
+                    "list<{}>",
+                    field_wit_type(element_type, module_type_names, contexts)?
+                ))
+            }
+            name if module_type_names.contains(&name) || contexts.get_by_key(name).is_some() => {
+                Ok(identifier(name))
+            }
+            name => primitive_wit_type(name),
+        },
+    }
+}
+
+fn context_field_wit_type(typ: &ContextFieldType) -> Result<String, ModelBuildingError> {
+    match typ {
+        ContextFieldType::Optional(typ) => Ok(format!("option<{}>", context_field_wit_type(typ)?)),
+        ContextFieldType::List(typ) => Ok(format!("list<{}>", context_field_wit_type(typ)?)),
+        ContextFieldType::Plain(PrimitiveType::Array(typ)) => Ok(format!(
+            "list<{}>",
+            context_field_wit_type(&ContextFieldType::Plain(*typ.clone()))?
+        )),
+        ContextFieldType::Plain(typ) => primitive_wit_type(&typ.name()),
+    }
+}
+
+fn primitive_wit_type(exo_type_name: &str) -> Result<String, ModelBuildingError> {
+    let wit_type = match exo_type_name {
+        "Int" => "s32",
+        "Float" => "f64",
+        "Boolean" => "bool",
+        "Vector" => "list<f32>",
+        // Represented in their (GraphQL) string form
+        "String" | "Decimal" | "Uuid" | "LocalDate" | "LocalTime" | "LocalDateTime" | "Instant"
+        | "Blob" => "string",
+        _ => {
+            return Err(ModelBuildingError::Generic(format!(
+                "Type '{exo_type_name}' is not supported in WASM modules"
+            )))
+        }
+    };
+
+    Ok(wit_type.to_string())
+}
+
+/// The WIT identifier for a name (escaped with `%` if it is a WIT keyword)
+fn identifier(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as",
+        "bool",
+        "borrow",
+        "char",
+        "constructor",
+        "enum",
+        "export",
+        "f32",
+        "f64",
+        "flags",
+        "from",
+        "func",
+        "import",
+        "include",
+        "interface",
+        "list",
+        "option",
+        "own",
+        "package",
+        "record",
+        "resource",
+        "result",
+        "s16",
+        "s32",
+        "s64",
+        "s8",
+        "static",
+        "string",
+        "tuple",
+        "type",
+        "u16",
+        "u32",
+        "u64",
+        "u8",
+        "use",
+        "variant",
+        "with",
+        "world",
+        "float32",
+        "float64",
+    ];
+
+    let name = name.to_kebab_case();
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("%{name}")
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codemap::CodeMap;
+    use core_plugin_interface::core_model_builder::ast::ast_types::{
+        AstField, AstMethod, AstMethodType, AstModel, AstModelKind,
+    };
+
+    fn fabricate_span() -> codemap::Span {
+        CodeMap::new()
+            .add_file("".to_string(), "".to_string())
+            .span
+            .subspan(0, 0)
+    }
+
+    fn plain_type(name: &str) -> AstFieldType<Typed> {
+        AstFieldType::Plain(None, name.to_string(), vec![], true, fabricate_span())
+    }
+
+    fn argument(name: &str, typ: AstFieldType<Typed>) -> AstArgument<Typed> {
+        AstArgument {
+            name: name.to_string(),
+            typ,
+            annotations: Default::default(),
+        }
+    }
+
+    fn field(name: &str, typ: AstFieldType<Typed>) -> AstField<Typed> {
+        AstField {
+            name: name.to_string(),
+            typ,
+            annotations: Default::default(),
+            default_value: None,
+            span: fabricate_span(),
+        }
+    }
+
+    #[test]
+    fn generates_world_from_module() {
+        let span = fabricate_span();
+
+        let module = AstModule {
+            name: "TodoModule".to_string(),
+            types: vec![AstModel {
+                name: "Todo".to_string(),
+                kind: AstModelKind::Type,
+                fields: vec![
+                    field("userId", plain_type("Int")),
+                    field("title", plain_type("String")),
+                    field(
+                        "tags",
+                        AstFieldType::Plain(
+                            None,
+                            "Array".to_string(),
+                            vec![plain_type("String")],
+                            true,
+                            span,
+                        ),
+                    ),
+                    field(
+                        "completed",
+                        AstFieldType::Optional(Box::new(plain_type("Boolean"))),
+                    ),
+                ],
+                annotations: Default::default(),
+                span,
+            }],
+            methods: vec![AstMethod {
+                name: "todosByUser".to_string(),
+                typ: AstMethodType::Query,
+                arguments: vec![
+                    argument("userId", plain_type("Int")),
+                    argument("exograph", plain_type("Exograph")),
+                ],
+                return_type: AstFieldType::Plain(
+                    None,
+                    "Set".to_string(),
+                    vec![plain_type("Todo")],
+                    true,
+                    span,
+                ),
+                is_exported: false,
+                annotations: Default::default(),
+                span,
+            }],
+            annotations: Default::default(),
+            base_exofile: PathBuf::new(),
+            interceptors: vec![],
+            enums: vec![],
+            span,
+        };
+
+        let wit = generate_wit(&module, &MappedArena::default()).unwrap();
+
+        assert!(wit.contains("package exograph:module;"));
+        assert!(wit.contains("world todo-module {\n    import exograph;\n"));
+        assert!(wit.contains(
+            "    record todo {\n        user-id: s32,\n        title: string,\n        tags: list<string>,\n        completed: option<bool>,\n    }"
+        ));
+        assert!(wit.contains(
+            "    export todos-by-user: func(user-id: s32) -> result<list<todo>, string>;"
+        ));
+    }
+
+    #[test]
+    fn escapes_keywords() {
+        assert_eq!(identifier("type"), "%type");
+        assert_eq!(identifier("userType"), "user-type");
+    }
+}
@@ -17,7 +17,7 @@ use crate::{
 use async_graphql_parser::types::{FieldDefinition, TypeDefinition};
 use core_plugin_interface::{
     core_model::{
-        context_type::ContextType,
+        context_type::{ContextContainer, ContextType},
         mapped_arena::{MappedArena, SerializableSlab},
         type_normalization::{FieldDefinitionProvider, TypeDefinitionProvider},
     },
@@ -79,3 +79,9 @@ impl SystemSerializer for WasmSubsystem {
         bincode::deserialize_from(reader).map_err(ModelSerializationError::Deserialize)
     }
 }
+
+impl ContextContainer for WasmSubsystem {
+    fn contexts(&self) -> &MappedArena<ContextType> {
+        &self.contexts
+    }
+}
@@ -0,0 +1,49 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use core_plugin_interface::{
+    core_resolver::system_resolver::ExographExecuteQueryFn,
+    trusted_documents::TrustedDocumentEnforcement,
+};
+use exo_wasm::CallbackProcessor;
+
+/// Executes queries issued by a component through the `exograph` interface (the WASM counterpart
+/// of `Exograph.executeQuery` in Deno modules)
+pub struct ExoCallbackProcessor<'a> {
+    pub exograph_execute_query: &'a ExographExecuteQueryFn<'a>,
+}
+
+#[async_trait]
+impl<'a> CallbackProcessor for ExoCallbackProcessor<'a> {
+    async fn execute_query(
+        &self,
+        query: String,
+        variables: Option<Value>,
+    ) -> Result<Value, String> {
+        let variables = match variables {
+            Some(Value::Object(variables)) => Some(variables),
+            Some(Value::Null) | None => None,
+            Some(_) => return Err("Query variables must be an object".to_string()),
+        };
+
+        let response = (self.exograph_execute_query)(
+            query,
+            variables,
+            TrustedDocumentEnforcement::DoNotEnforce,
+            Value::Null,
+        )
+        .await
+        .map_err(|e| e.user_error_message())?;
+
+        response.body.to_json().map_err(|e| e.to_string())
+    }
+}
@@ -9,6 +9,7 @@
 
 pub use plugin::WasmSubsystemLoader;
 
+mod exo_execution;
 mod plugin;
 mod wasm_execution_error;
 mod wasm_operation;
@@ -11,10 +11,12 @@ use crate::{wasm_execution_error::WasmExecutionError, wasm_operation::WasmOperat
 use async_graphql_parser::types::{FieldDefinition, OperationType, TypeDefinition};
 use async_trait::async_trait;
 use core_plugin_interface::{
-    core_model::mapped_arena::SerializableSlabIndex,
+    core_model::{execution_limits::ExecutionLimits, mapped_arena::SerializableSlabIndex},
     core_resolver::{
         context::RequestContext,
+        metrics::PoolStats,
         plugin::{SubsystemResolutionError, SubsystemResolver},
+        rate_limit::RateLimitBucket,
         system_resolver::SystemResolver,
         validation::field::ValidatedField,
         InterceptedOperation, QueryResponse,
@@ -24,7 +26,7 @@ use core_plugin_interface::{
     system_serializer::SystemSerializer,
 };
 use exo_env::Environment;
-use exo_wasm::WasmExecutorPool;
+use exo_wasm::{WasmError, WasmExecutorPool};
 use wasm_model::{module::ModuleMethod, subsystem::WasmSubsystem};
 
 pub struct WasmSubsystemLoader {}
@@ -38,9 +40,16 @@ impl SubsystemLoader for WasmSubsystemLoader {
     async fn init(
         &mut self,
         serialized_subsystem: Vec<u8>,
-        _env: &dyn Environment,
+        env: &dyn Environment,
     ) -> Result<Box<dyn SubsystemResolver + Send + Sync>, SubsystemLoadingError> {
-        let subsystem = WasmSubsystem::deserialize(serialized_subsystem)?;
+        let mut subsystem = WasmSubsystem::deserialize(serialized_subsystem)?;
+
+        // Modules without their own limits run within those set through the environment
+        let default_limits =
+            ExecutionLimits::from_env(|key| env.get(key)).map_err(SubsystemLoadingError::Config)?;
+        for (_, script) in subsystem.scripts.iter_mut() {
+            script.limits = script.limits.or(default_limits);
+        }
 
         let executor = WasmExecutorPool::default();
 
@@ -127,6 +136,55 @@ impl SubsystemResolver for WasmSubsystemResolver {
         Err(SubsystemResolutionError::NoInterceptorFound)
     }
 
+    async fn rate_limit<'a>(
+        &'a self,
+        field: &'a ValidatedField,
+        operation_type: OperationType,
+        request_context: &'a RequestContext<'a>,
+    ) -> Result<Option<RateLimitBucket>, SubsystemResolutionError> {
+        let method_id = match operation_type {
+            OperationType::Query => self
+                .subsystem
+                .queries
+                .get_by_key(&field.name)
+                .and_then(|query| query.method_id),
+            OperationType::Mutation => self
+                .subsystem
+                .mutations
+                .get_by_key(&field.name)
+                .and_then(|mutation| mutation.method_id),
+            OperationType::Subscription => None,
+        };
+
+        let Some(rate_limit) =
+            method_id.and_then(|method_id| self.subsystem.methods[method_id].rate_limit.as_ref())
+        else {
+            return Ok(None);
+        };
+
+        let bucket = RateLimitBucket::new(rate_limit, &self.subsystem, request_context)
+            .await
+            .map_err(WasmExecutionError::from)?;
+
+        Ok(Some(bucket))
+    }
+
+    async fn pool_stats(&self) -> Vec<PoolStats> {
+        // Each call runs in its own instance of the module, so the instances in the pool are the
+        // ones in use
+        self.executor
+            .stats()
+            .into_iter()
+            .map(|stats| PoolStats {
+                pool: "wasm",
+                name: stats.script_path,
+                size: stats.busy,
+                in_use: stats.busy,
+                max_size: None,
+            })
+            .collect()
+    }
+
     fn schema_queries(&self) -> Vec<FieldDefinition> {
         self.subsystem.schema_queries()
     }
@@ -164,6 +222,7 @@ impl From<WasmExecutionError> for SubsystemResolutionError {
     fn from(e: WasmExecutionError) -> Self {
         match e {
             WasmExecutionError::Authorization => SubsystemResolutionError::Authorization,
+            WasmExecutionError::Wasm(WasmError::Timeout(_)) => SubsystemResolutionError::Timeout,
             _ => SubsystemResolutionError::UserDisplayError(e.user_error_message()),
         }
     }
@@ -7,6 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use core_plugin_interface::core_resolver::context::ContextExtractionError;
 use thiserror::Error;
 
 use exo_wasm::WasmError;
@@ -27,6 +28,9 @@ pub enum WasmExecutionError {
 
     #[error("{0}")]
     Delegate(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("{0}")]
+    ContextExtraction(#[from] ContextExtractionError),
 }
 
 impl WasmExecutionError {
@@ -7,23 +7,31 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::{plugin::WasmSubsystemResolver, wasm_execution_error::WasmExecutionError};
-use core_plugin_interface::core_resolver::value::val::Val as ExoVal;
-use core_plugin_interface::core_resolver::{
-    context::RequestContext, system_resolver::SystemResolver, validation::field::ValidatedField,
-    QueryResponse, QueryResponseBody,
+use crate::{
+    exo_execution::ExoCallbackProcessor, plugin::WasmSubsystemResolver,
+    wasm_execution_error::WasmExecutionError,
 };
-use std::collections::HashMap;
+use core_plugin_interface::{
+    core_model::cache::CachePolicy,
+    core_resolver::{
+        context::RequestContext,
+        context_extractor::ContextExtractor,
+        exograph_execute_query,
+        system_resolver::{ExographExecuteQueryFn, SystemResolver},
+        validation::field::ValidatedField,
+        QueryResponse, QueryResponseBody,
+    },
+    trusted_documents::TrustedDocumentEnforcement,
+};
+use exo_wasm::WasmLimits;
+use serde_json::Value;
 use wasm_model::module::ModuleMethod;
-use wasmtime::Val;
 
 pub struct WasmOperation<'a> {
     pub method: &'a ModuleMethod,
     pub field: &'a ValidatedField,
-    #[allow(unused)]
     pub request_context: &'a RequestContext<'a>,
     pub subsystem_resolver: &'a WasmSubsystemResolver,
-    #[allow(unused)]
     pub system_resolver: &'a SystemResolver,
 }
 
@@ -31,50 +39,87 @@ impl<'a> WasmOperation<'a> {
     pub async fn execute(&self) -> Result<QueryResponse, WasmExecutionError> {
         let script = &self.subsystem_resolver.subsystem.scripts[self.method.script];
 
-        let mapped_args: HashMap<String, Val> = self
-            .field
-            .arguments
-            .iter()
-            .map(|(gql_name, gql_value)| {
-                (
-                    gql_name.as_str().to_owned(),
-                    match gql_value {
-                        ExoVal::Null => todo!(),
-                        ExoVal::Number(num) => (num.as_i64().unwrap() as i32).into(),
-                        ExoVal::String(_) => todo!(),
-                        ExoVal::Bool(_) => todo!(),
-                        ExoVal::Binary(_) => todo!(),
-                        ExoVal::Enum(_) => todo!(),
-                        ExoVal::List(_) => todo!(),
-                        ExoVal::Object(_) => todo!(),
-                    },
-                )
-            })
-            .collect::<HashMap<_, _>>();
+        let exograph_execute_query: &ExographExecuteQueryFn =
+            exograph_execute_query!(self.system_resolver, self.request_context);
 
-        let args: Vec<_> = self
-            .method
-            .arguments
-            .iter()
-            .map(|arg| {
-                if let Some(val) = mapped_args.get(&arg.name) {
-                    val.clone()
-                } else {
-                    todo!()
-                }
-            })
-            .collect();
+        let callback_processor = ExoCallbackProcessor {
+            exograph_execute_query,
+        };
+
+        let args = self.construct_arg_sequence().await?;
 
         let result = self
             .subsystem_resolver
             .executor
-            .execute(&script.path, &script.script, &self.method.name, args)
+            .execute(
+                &script.path,
+                &script.script,
+                WasmLimits {
+                    timeout: script.limits.timeout(),
+                    max_memory_bytes: script
+                        .limits
+                        .memory_bytes
+                        .map(|bytes| usize::try_from(bytes).unwrap_or(usize::MAX)),
+                },
+                &self.method.name,
+                args,
+                &callback_processor,
+            )
             .await
             .map_err(WasmExecutionError::Wasm)?;
 
+        let headers = self
+            .method
+            .cache
+            .iter()
+            .map(|cache_policy| {
+                (
+                    CachePolicy::HEADER_NAME.to_string(),
+                    cache_policy.header_value(),
+                )
+            })
+            .collect(); // TODO: support headers set by the module
+
         Ok(QueryResponse {
             body: QueryResponseBody::Json(result),
-            headers: vec![], // TODO: support headers
+            headers,
         })
     }
+
+    /// Compute the arguments in the order declared by the method. Injected contexts are passed as
+    /// records. Other injected arguments (such as `Exograph`) have no counterpart in the WIT
+    /// function, since the component uses the imported `exograph` interface instead.
+    async fn construct_arg_sequence(&self) -> Result<Vec<Value>, WasmExecutionError> {
+        let subsystem = &self.subsystem_resolver.subsystem;
+
+        let mut args = vec![];
+
+        for arg in self.method.arguments.iter() {
+            if arg.is_injected {
+                let arg_type = &subsystem.module_types[*arg.type_id.innermost()];
+
+                if subsystem.contexts.get_by_key(&arg_type.name).is_some() {
+                    let context_value = subsystem
+                        .extract_context(self.request_context, &arg_type.name)
+                        .await?
+                        .map(|value| value.into_json())
+                        .transpose()
+                        .map_err(|e| WasmExecutionError::Delegate(Box::new(e)))?
+                        .unwrap_or(Value::Null);
+                    args.push(context_value);
+                }
+            } else {
+                let value = match self.field.arguments.get(&arg.name) {
+                    Some(value) => value
+                        .clone()
+                        .into_json()
+                        .map_err(|_| WasmExecutionError::InvalidArgument(arg.name.clone()))?,
+                    None => Value::Null,
+                };
+                args.push(value);
+            }
+        }
+
+        Ok(args)
+    }
 }
@@ -0,0 +1,12 @@
+export function greeting() {
+    return Deno.env.get("GREETING");
+}
+
+export function home() {
+    return Deno.env.get("HOME");
+}
+
+export async function todoTitle(id) {
+    const r = await fetch(`https://jsonplaceholder.typicode.com/todos/${id}`);
+    return (await r.json()).title;
+}
@@ -1,4 +1,21 @@
-#[no_mangle]
-pub extern "C" fn add(a: i32, b: i32) -> i32 {
-    a + b
+#[allow(warnings)]
+mod bindings;
+
+use bindings::Guest;
+
+struct Component;
+
+impl Guest for Component {
+    fn add(a: i32, b: i32) -> Result<i32, String> {
+        a.checked_add(b).ok_or_else(|| "Overflow".to_string())
+    }
+
+    fn greet(name: String, title: Option<String>) -> Result<String, String> {
+        match title {
+            Some(title) => Ok(format!("Hello, {title} {name}!")),
+            None => Ok(format!("Hello, {name}!")),
+        }
+    }
 }
+
+bindings::export!(Component with_types_in bindings);
@@ -1 +1 @@
-(cd "$( dirname -- "$0"; )/../src/wasm-source" && cargo build --target wasm32-wasi)
\ No newline at end of file
+(cd "$( dirname -- "$0"; )/../src/wasm-source" && cargo component build)
\ No newline at end of file
@@ -7,7 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use deno_core::Extension;
+use deno_core::{v8, Extension};
 use futures::pin_mut;
 use serde_json::Value;
 use std::fmt::Debug;
@@ -15,8 +15,9 @@ use std::{
     panic,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, OnceLock,
     },
+    time::Duration,
 };
 use tokio::sync::{
     mpsc::{Receiver, Sender},
@@ -67,6 +68,12 @@ struct DenoCall<C, R> {
 ///   callback message, it forwards that to the `callback_sender` and loops again. If it receives
 ///   the final result, it breaks the loop returning that result.
 ///
+/// # Limits:
+/// - If a call doesn't complete within the script's timeout, `execute` returns a timeout error and
+///   terminates the script. Likewise, the script is terminated once it exceeds its heap limit.
+/// - A terminated actor finishes its thread (so the pool no longer uses it and creates a fresh actor
+///   for later calls).
+///
 /// # Type Parameters
 /// * `C` - The type of the call context. Call context is any value (such as the name of the current
 ///   operation) that the message processing may need
@@ -79,6 +86,11 @@ pub(crate) struct DenoActor<C, M, R> {
     // Sender to ask the actor to execute a JS/TS call. The actor will poll for messages on the corresponding receiver.
     call_sender: Sender<DenoCall<C, R>>,
     busy: Arc<std::sync::atomic::AtomicBool>,
+    // Set once the script has been terminated for exceeding a limit
+    terminated: Arc<AtomicBool>,
+    // Handle to terminate the script (set once the module has been created)
+    isolate_handle: Arc<OnceLock<v8::IsolateHandle>>,
+    timeout: Option<Duration>,
 }
 
 impl<C, M, R> DenoActor<C, M, R>
@@ -103,8 +115,13 @@ where
         // we will receive DenoCall messages through this channel from call_method
         let (deno_call_sender, mut deno_call_receiver) = tokio::sync::mpsc::channel(1);
         let busy = Arc::new(AtomicBool::new(false));
+        let terminated = Arc::new(AtomicBool::new(false));
+        let isolate_handle = Arc::new(OnceLock::new());
+        let timeout = code.limits().timeout;
 
         let busy_clone = busy.clone();
+        let terminated_clone = terminated.clone();
+        let isolate_handle_clone = isolate_handle.clone();
 
         // start the DenoModule thread
         std::thread::spawn(move || {
@@ -142,6 +159,8 @@ where
                     }
                 };
 
+                let _ = isolate_handle_clone.set(deno_module.isolate_handle());
+
                 // store the request sender in Deno OpState for use by ops
                 deno_module
                     .put(callback_sender)
@@ -160,6 +179,12 @@ where
                         None => break,
                     };
 
+                    // the call timed out before the module was ready to run it (the actor's
+                    // caller sets the flag before looking up the isolate handle)
+                    if terminated_clone.load(Ordering::SeqCst) {
+                        break;
+                    }
+
                     busy_clone.store(true, Ordering::Relaxed); // mark DenoActor as busy
                     let _: Option<R> = deno_module.take().expect("take() should not have failed"); // clear any existing R from GothamStorage
 
@@ -171,10 +196,21 @@ where
                     // take R from GothamStorage
                     let r: Option<R> = deno_module.take().expect("take() should not have failed");
 
-                    // send result of the Deno function back to call_method
-                    final_response_sender
-                        .send(result.map(|result| (result, r)))
-                        .expect("Could not send result in DenoActor thread");
+                    // a terminated module can't be used any longer, so we must stop (after marking
+                    // the actor as terminated to keep the pool from using it)
+                    let terminated =
+                        terminated_clone.load(Ordering::SeqCst) || deno_module.heap_limit_reached();
+                    if terminated {
+                        terminated_clone.store(true, Ordering::SeqCst);
+                    }
+
+                    // send result of the Deno function back to call_method (if the call timed out,
+                    // no one is waiting for it anymore)
+                    let _ = final_response_sender.send(result.map(|result| (result, r)));
+
+                    if terminated {
+                        break;
+                    }
 
                     busy_clone.store(false, Ordering::Relaxed); // unmark DenoActor as busy
                 }
@@ -185,6 +221,9 @@ where
             callback_receiver: Arc::new(Mutex::new(callback_receiver)),
             call_sender: deno_call_sender,
             busy,
+            terminated,
+            isolate_handle,
+            timeout,
         })
     }
 
@@ -192,6 +231,30 @@ where
         self.busy.load(Ordering::Relaxed)
     }
 
+    /// Has the module been loaded (and is thus ready to execute calls)?
+    pub fn is_loaded(&self) -> bool {
+        self.isolate_handle.get().is_some()
+    }
+
+    /// Is the actor's thread still running? (the thread stops if the module fails to load)
+    pub fn is_alive(&self) -> bool {
+        !self.call_sender.is_closed()
+    }
+
+    /// Has the script been terminated for exceeding a limit? (such an actor can't be used any
+    /// longer)
+    pub fn is_terminated(&self) -> bool {
+        self.terminated.load(Ordering::SeqCst)
+    }
+
+    fn terminate(&self) {
+        self.terminated.store(true, Ordering::SeqCst);
+
+        if let Some(isolate_handle) = self.isolate_handle.get() {
+            isolate_handle.terminate_execution();
+        }
+    }
+
     /// Call a deno method
     ///
     /// During the invocation there may be callbacks (such as `execute` a query or `proceed` form an interceptor). Those calls
@@ -230,6 +293,25 @@ where
             ))
         })?;
 
+        let result = self.receive_result(final_result_receiver, callback_sender);
+
+        match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, result).await {
+                Ok(result) => result,
+                Err(_) => {
+                    self.terminate();
+                    Err(DenoError::Timeout(timeout))
+                }
+            },
+            None => result.await,
+        }
+    }
+
+    async fn receive_result(
+        &self,
+        final_result_receiver: oneshot::Receiver<Result<(Value, Option<R>), DenoError>>,
+        callback_sender: tokio::sync::mpsc::Sender<M>,
+    ) -> Result<(Value, Option<R>), DenoError> {
         pin_mut!(final_result_receiver);
 
         // receive loop
@@ -263,6 +345,9 @@ impl<C, M, R> Clone for DenoActor<C, M, R> {
             callback_receiver: self.callback_receiver.clone(),
             call_sender: self.call_sender.clone(),
             busy: self.busy.clone(),
+            terminated: self.terminated.clone(),
+            isolate_handle: self.isolate_handle.clone(),
+            timeout: self.timeout,
         }
     }
 }
@@ -270,6 +355,8 @@ impl<C, M, R> Clone for DenoActor<C, M, R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::deno_executor_pool::{DenoLimits, DenoScriptDefn, ResolvedModule};
+    use deno_core::{ModuleSpecifier, ModuleType};
     use std::path::Path;
     use tokio::sync::mpsc::channel;
 
@@ -305,4 +392,62 @@ mod tests {
 
         assert_eq!(res, 10);
     }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let module_path = "file://test_js/limits.js";
+
+        let actor: DenoActor<(), (), ()> = DenoActor::new(
+            UserCode::LoadFromMemory {
+                path: module_path.to_string(),
+                script: DenoScriptDefn {
+                    modules: vec![(
+                        ModuleSpecifier::parse(module_path).unwrap(),
+                        ResolvedModule::Module(
+                            include_str!("test_js/limits.js").to_string(),
+                            ModuleType::JavaScript,
+                            ModuleSpecifier::parse(module_path).unwrap(),
+                            false,
+                        ),
+                    )]
+                    .into_iter()
+                    .collect(),
+                    npm_snapshot: None,
+                    permissions: None,
+                    limits: DenoLimits {
+                        timeout: Some(Duration::from_millis(500)),
+                        max_heap_bytes: None,
+                    },
+                },
+            },
+            USER_AGENT_NAME,
+            vec![],
+            vec![ADDITIONAL_CODE],
+            Vec::new,
+            EXPLICIT_ERROR_CLASS_NAME,
+            DenoModuleSharedState::default(),
+            |_, _| {},
+        )
+        .unwrap();
+
+        let (to_user_sender, _to_user_receiver) = channel(1);
+
+        let (res, _) = actor
+            .execute(
+                "add".to_string(),
+                vec![Arg::Serde(2_i32.into()), Arg::Serde(3_i32.into())],
+                (),
+                to_user_sender.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res, 5);
+        assert!(!actor.is_terminated());
+
+        let res = actor
+            .execute("spin".to_string(), vec![], (), to_user_sender)
+            .await;
+        assert!(matches!(res, Err(DenoError::Timeout(_))));
+        assert!(actor.is_terminated());
+    }
 }
@@ -11,6 +11,7 @@ use deno_core::{
     error::{AnyError, JsError},
     v8::DataError,
 };
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -23,6 +24,18 @@ pub enum DenoError {
     #[error("{0}")]
     JsError(#[from] JsError),
 
+    // Access to a resource (such as a host or an environment variable) the script isn't permitted
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    // A call didn't complete within the timeout (the script is terminated)
+    #[error("Execution timed out after {}ms", .0.as_millis())]
+    Timeout(Duration),
+
+    // The script exceeded its heap limit (the script is terminated)
+    #[error("Memory limit exceeded")]
+    MemoryLimitExceeded,
+
     #[error("{0}")]
     AnyError(#[from] AnyError),
 
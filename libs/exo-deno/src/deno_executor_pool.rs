@@ -7,7 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc, time::Duration};
 
 use deno_core::{url::Url, Extension, ModuleType};
 use deno_npm::resolution::SerializedNpmResolutionSnapshot;
@@ -16,7 +16,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::Mutex;
 
-use crate::{deno_error::DenoError, Arg};
+use crate::{deno_error::DenoError, deno_permissions::DenoPermissions, Arg};
 
 use super::{
     deno_actor::DenoActor,
@@ -44,6 +44,23 @@ pub struct DenoScriptDefn {
         VirtualDirectory,
         Vec<Vec<u8>>,
     )>,
+    /// The access granted to the script (`None` grants all access)
+    #[serde(default)]
+    pub permissions: Option<DenoPermissions>,
+    /// The limits on running the script. These aren't serialized, since they may also be set
+    /// through the environment when the script is loaded.
+    #[serde(skip)]
+    pub limits: DenoLimits,
+}
+
+/// The limits on running a script (`None` for no limit)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DenoLimits {
+    /// The wall-clock time allowed for each call (the actor running a call that exceeds it is
+    /// terminated)
+    pub timeout: Option<Duration>,
+    /// The maximum size of the V8 heap in bytes
+    pub max_heap_bytes: Option<usize>,
 }
 
 pub struct DenoExecutorConfig<C> {
@@ -78,6 +95,20 @@ impl<C> DenoExecutorConfig<C> {
     }
 }
 
+/// Utilization of the actors created for a script
+#[derive(Debug, Clone)]
+pub struct DenoActorPoolStats {
+    pub script_path: String,
+    /// The number of actors created for the script
+    pub size: usize,
+    /// The number of actors currently executing a method
+    pub busy: usize,
+    /// The number of actors whose script has been loaded
+    pub loaded: usize,
+    /// The number of actors that have stopped (for example, because the script failed to load)
+    pub stopped: usize,
+}
+
 /// DenoExecutorPool maintains a pool of `DenoActor`s for each module to delegate work to.
 ///
 /// Calling `execute` will either select a free actor or allocate a new `DenoActor` to run the function on.
@@ -136,6 +167,40 @@ impl<
         }
     }
 
+    /// The number of actors (and how many of them are busy or stopped) for each script
+    pub async fn stats(&self) -> Vec<DenoActorPoolStats> {
+        let actor_pool_map = self.actor_pool_map.lock().await;
+
+        actor_pool_map
+            .iter()
+            .map(|(script_path, actor_pool)| DenoActorPoolStats {
+                script_path: script_path.clone(),
+                size: actor_pool.len(),
+                busy: actor_pool.iter().filter(|actor| actor.is_busy()).count(),
+                loaded: actor_pool.iter().filter(|actor| actor.is_loaded()).count(),
+                stopped: actor_pool.iter().filter(|actor| !actor.is_alive()).count(),
+            })
+            .collect()
+    }
+
+    /// Start loading a script (unless an actor for it already exists) without executing any method
+    pub async fn preload(
+        &self,
+        script_path: &str,
+        script: DenoScriptDefn,
+    ) -> Result<(), DenoError> {
+        let mut actor_pool_map = self.actor_pool_map.lock().await;
+        let actor_pool = actor_pool_map.entry(script_path.to_string()).or_default();
+
+        actor_pool.retain(|actor| !actor.is_terminated());
+
+        if actor_pool.is_empty() {
+            actor_pool.push(self.create_actor(script_path, script)?);
+        }
+
+        Ok(())
+    }
+
     // Execute a method and obtain its result
     pub async fn execute(
         &self,
@@ -186,6 +251,9 @@ impl<
             let mut actor_pool_map = self.actor_pool_map.lock().await;
             let actor_pool = actor_pool_map.entry(script_path.to_string()).or_default();
 
+            // drop actors terminated for exceeding a limit (their thread exits on its own)
+            actor_pool.retain(|actor| !actor.is_terminated());
+
             let free_actor = actor_pool.iter().find(|actor| !actor.is_busy());
 
             if let Some(actor) = free_actor {
@@ -264,6 +332,8 @@ mod tests {
                     .into_iter()
                     .collect(),
                     npm_snapshot: None,
+                    permissions: None,
+                    limits: DenoLimits::default(),
                 },
                 "addAndDouble",
                 vec![Arg::Serde(2.into()), Arg::Serde(3.into())],
@@ -275,6 +345,63 @@ mod tests {
         assert_eq!(res.unwrap(), 10);
     }
 
+    #[tokio::test]
+    async fn test_preload() {
+        let module_path = "file://test_js/direct.js";
+        let module_script = include_str!("test_js/direct.js").to_string();
+
+        let executor_pool = DenoExecutorPool::<(), (), ()>::new(
+            "ExoDenoTest",
+            vec![],
+            vec![],
+            None,
+            Vec::new,
+            |_, _| {},
+            DenoModuleSharedState::default(),
+        );
+
+        let script = DenoScriptDefn {
+            modules: vec![(
+                ModuleSpecifier::parse(module_path).unwrap(),
+                ResolvedModule::Module(
+                    module_script,
+                    ModuleType::JavaScript,
+                    ModuleSpecifier::parse(module_path).unwrap(),
+                    false,
+                ),
+            )]
+            .into_iter()
+            .collect(),
+            npm_snapshot: None,
+            permissions: None,
+            limits: DenoLimits::default(),
+        };
+
+        assert!(executor_pool.stats().await.is_empty());
+
+        executor_pool
+            .preload(module_path, script.clone())
+            .await
+            .unwrap();
+        // Preloading again doesn't create another actor
+        executor_pool.preload(module_path, script).await.unwrap();
+
+        let mut loaded = false;
+        for _ in 0..100 {
+            let stats = executor_pool.stats().await;
+            assert_eq!(stats.len(), 1);
+            assert_eq!(stats[0].size, 1);
+            assert_eq!(stats[0].busy, 0);
+
+            if stats[0].loaded == 1 {
+                loaded = true;
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(loaded, "The module didn't load");
+    }
+
     #[tokio::test]
     async fn test_actor_executor_concurrent() {
         let module_path = "file://test_js/direct.js";
@@ -316,6 +443,8 @@ mod tests {
                     .into_iter()
                     .collect(),
                     npm_snapshot: None,
+                    permissions: None,
+                    limits: DenoLimits::default(),
                 },
                 method_name,
                 arguments,
@@ -40,6 +40,7 @@ use include_dir::Dir;
 use tempfile::tempfile;
 use tracing::error;
 
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::io::Write;
 use std::path::PathBuf;
@@ -56,11 +57,15 @@ use std::sync::Arc;
 use crate::deno_error::DenoDiagnosticError;
 use crate::deno_error::DenoError;
 use crate::deno_error::DenoInternalError;
+use crate::deno_executor_pool::DenoLimits;
 use crate::deno_executor_pool::DenoScriptDefn;
 use crate::deno_executor_pool::ResolvedModule;
 
 use super::embedded_module_loader::EmbeddedModuleLoader;
 
+/// The class of errors thrown when accessing a resource without permission
+const PERMISSION_DENIED_CLASS_NAME: &str = "PermissionDenied";
+
 fn get_error_class_name(e: &AnyError) -> &'static str {
     deno_runtime::errors::get_error_class_name(e).unwrap_or("Error")
 }
@@ -74,11 +79,23 @@ pub enum UserCode {
     LoadFromFs(PathBuf),
 }
 
+impl UserCode {
+    /// The limits on running the code (only scripts loaded from memory may have limits)
+    pub fn limits(&self) -> DenoLimits {
+        match self {
+            UserCode::LoadFromMemory { script, .. } => script.limits,
+            UserCode::LoadFromFs(_) => DenoLimits::default(),
+        }
+    }
+}
+
 pub struct DenoModule {
     worker: MainWorker,
     shim_object_names: Vec<String>,
     user_code: UserCode,
     explicit_error_class_name: Option<&'static str>,
+    /// Set once the script exceeds its heap limit (after which the script is terminated)
+    heap_limit_reached: Rc<Cell<bool>>,
 }
 
 #[derive(Debug)]
@@ -364,7 +381,10 @@ impl DenoModule {
                 serve_port: None,
                 node_debug: None,
             },
-            create_params: None,
+            create_params: user_code
+                .limits()
+                .max_heap_bytes
+                .map(|max_heap_bytes| v8::CreateParams::default().heap_limits(0, max_heap_bytes)),
             extensions,
             unsafely_ignore_certificate_errors: None,
             root_cert_store_provider: None,
@@ -395,11 +415,37 @@ impl DenoModule {
         };
 
         let main_module = deno_core::resolve_url(&main_module_specifier)?;
-        let permissions = PermissionsContainer::allow_all();
+        let permissions = match &user_code {
+            UserCode::LoadFromMemory {
+                script:
+                    DenoScriptDefn {
+                        permissions: Some(permissions),
+                        ..
+                    },
+                ..
+            } => permissions.to_container()?,
+            _ => PermissionsContainer::allow_all(),
+        };
 
         let mut worker =
             MainWorker::bootstrap_from_options(main_module.clone(), permissions, options);
 
+        let heap_limit_reached = Rc::new(Cell::new(false));
+        if user_code.limits().max_heap_bytes.is_some() {
+            let isolate_handle = worker.js_runtime.v8_isolate().thread_safe_handle();
+            let heap_limit_reached = heap_limit_reached.clone();
+
+            worker
+                .js_runtime
+                .add_near_heap_limit_callback(move |current_limit, _initial_limit| {
+                    heap_limit_reached.set(true);
+                    isolate_handle.terminate_execution();
+                    // Give V8 enough room to unwind the terminated script (instead of aborting
+                    // the process)
+                    current_limit * 2
+                });
+        }
+
         worker.execute_main_module(&main_module).await?;
 
         additional_code.iter().for_each(|code| {
@@ -417,6 +463,7 @@ impl DenoModule {
             shim_object_names,
             user_code,
             explicit_error_class_name,
+            heap_limit_reached,
         };
 
         Ok(deno_module)
@@ -437,6 +484,21 @@ impl DenoModule {
         &mut self,
         function_name: &str,
         args: Vec<Arg>,
+    ) -> Result<Value, DenoError> {
+        let result = self.call_function(function_name, args).await;
+
+        if self.heap_limit_reached() {
+            // Whatever the call returned, it was cut short by the termination
+            return Err(DenoError::MemoryLimitExceeded);
+        }
+
+        result
+    }
+
+    async fn call_function(
+        &mut self,
+        function_name: &str,
+        args: Vec<Arg>,
     ) -> Result<Value, DenoError> {
         let worker = &mut self.worker;
         let runtime = &mut worker.js_runtime;
@@ -504,6 +566,12 @@ impl DenoModule {
 
             let local = match local {
                 Some(value) => value,
+                None if tc_scope_ref.has_terminated() => {
+                    // The script was terminated (for example, for exceeding its heap limit)
+                    return Err(DenoError::AnyError(deno_core::anyhow::anyhow!(
+                        "Execution terminated"
+                    )));
+                }
                 None => {
                     // We will get the exception here for sync functions
                     let exception = tc_scope_ref.exception().unwrap();
@@ -543,6 +611,16 @@ impl DenoModule {
         }
     }
 
+    /// A handle to terminate the running script from another thread
+    pub fn isolate_handle(&mut self) -> v8::IsolateHandle {
+        self.worker.js_runtime.v8_isolate().thread_safe_handle()
+    }
+
+    /// Has the script exceeded its heap limit? (if so, the module can no longer be used)
+    pub fn heap_limit_reached(&self) -> bool {
+        self.heap_limit_reached.get()
+    }
+
     /// Put a single instance of a type into Deno's op_state
     pub fn put<T: 'static>(&mut self, val: T) -> Result<(), DenoError> {
         self.worker
@@ -570,6 +648,19 @@ impl DenoModule {
         js_error: JsError,
     ) -> DenoError {
         match explicit_error_class_name {
+            _ if js_error.name.as_deref() == Some(PERMISSION_DENIED_CLASS_NAME) => {
+                // code accessed a resource outside its permissions (such as `Requires net access to
+                // "example.com:443"`). Drop Deno's hint to re-run with an `--allow-*` flag, which
+                // doesn't apply here.
+                let message = js_error
+                    .message
+                    .unwrap_or_else(|| "Unknown error".to_string());
+                let message = match message.split_once(", run again with") {
+                    Some((requirement, _)) => requirement.to_string(),
+                    None => message,
+                };
+                DenoError::PermissionDenied(message)
+            }
             Some(_) if js_error.name.as_deref() == explicit_error_class_name => {
                 // code threw an explicit error, expose it to user
                 let message = js_error
@@ -617,6 +708,7 @@ mod tests {
     use test_log::test;
 
     use super::*;
+    use crate::deno_permissions::{DenoPermissions, PermissionGrant};
 
     #[test(tokio::test)]
     async fn test_direct_sync() {
@@ -694,6 +786,124 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_denied_permission() {
+        let module_path = "file://test_js/direct.js";
+
+        let mut deno_module = DenoModule::new(
+            UserCode::LoadFromMemory {
+                path: module_path.to_string(),
+                script: DenoScriptDefn {
+                    modules: vec![(
+                        ModuleSpecifier::parse(module_path).unwrap(),
+                        ResolvedModule::Module(
+                            include_str!("test_js/direct.js").to_string(),
+                            ModuleType::JavaScript,
+                            ModuleSpecifier::parse(module_path).unwrap(),
+                            false,
+                        ),
+                    )]
+                    .into_iter()
+                    .collect(),
+                    npm_snapshot: None,
+                    permissions: Some(DenoPermissions {
+                        net: PermissionGrant::Only(vec!["example.com".to_string()]),
+                        ..Default::default()
+                    }),
+                    limits: DenoLimits::default(),
+                },
+            },
+            "deno_module",
+            vec![],
+            vec![],
+            vec![],
+            DenoModuleSharedState::default(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Code that doesn't need any access works as usual
+        let sync_ret_value = deno_module
+            .execute_function(
+                "addAndDouble",
+                vec![
+                    Arg::Serde(Value::Number(4.into())),
+                    Arg::Serde(Value::Number(2.into())),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(sync_ret_value, Value::Number(12.into()));
+
+        let async_ret_value = deno_module
+            .execute_function("getJson", vec![Arg::Serde(Value::Number(4.into()))])
+            .await;
+        assert!(matches!(
+            async_ret_value,
+            Err(DenoError::PermissionDenied(message))
+                if message.contains("jsonplaceholder.typicode.com") && !message.contains("--allow-net")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_heap_limit() {
+        let module_path = "file://test_js/limits.js";
+
+        let mut deno_module = DenoModule::new(
+            UserCode::LoadFromMemory {
+                path: module_path.to_string(),
+                script: DenoScriptDefn {
+                    modules: vec![(
+                        ModuleSpecifier::parse(module_path).unwrap(),
+                        ResolvedModule::Module(
+                            include_str!("test_js/limits.js").to_string(),
+                            ModuleType::JavaScript,
+                            ModuleSpecifier::parse(module_path).unwrap(),
+                            false,
+                        ),
+                    )]
+                    .into_iter()
+                    .collect(),
+                    npm_snapshot: None,
+                    permissions: None,
+                    limits: DenoLimits {
+                        timeout: None,
+                        max_heap_bytes: Some(64 * 1024 * 1024),
+                    },
+                },
+            },
+            "deno_module",
+            vec![],
+            vec![],
+            vec![],
+            DenoModuleSharedState::default(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let ret_value = deno_module
+            .execute_function(
+                "add",
+                vec![
+                    Arg::Serde(Value::Number(4.into())),
+                    Arg::Serde(Value::Number(2.into())),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(ret_value, Value::Number(6.into()));
+
+        let ret_value = deno_module.execute_function("allocate", vec![]).await;
+        assert!(matches!(ret_value, Err(DenoError::MemoryLimitExceeded)));
+        assert!(deno_module.heap_limit_reached());
+    }
+
     #[tokio::test]
     async fn test_shim_sync() {
         static GET_JSON_SHIM: (&str, &[&str]) = ("__shim", &[include_str!("./test_js/shim.js")]);
@@ -0,0 +1,84 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::path::PathBuf;
+
+use deno_core::error::AnyError;
+use deno_runtime::deno_permissions::{Permissions, PermissionsContainer, PermissionsOptions};
+use serde::{Deserialize, Serialize};
+
+/// The access a module has to the outside world.
+///
+/// Each kind of access (network, environment variables, etc.) is denied unless granted.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DenoPermissions {
+    /// Hosts (optionally with a port, such as `api.stripe.com` or `localhost:8080`)
+    pub net: PermissionGrant,
+    /// Environment variables
+    pub env: PermissionGrant,
+    /// Paths that can be read
+    pub read: PermissionGrant,
+    /// Paths that can be written
+    pub write: PermissionGrant,
+    /// Programs that can be run as subprocesses
+    pub run: PermissionGrant,
+    /// System information APIs (such as `hostname` or `osRelease`)
+    pub sys: PermissionGrant,
+    /// Dynamic libraries that can be loaded
+    pub ffi: PermissionGrant,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum PermissionGrant {
+    #[default]
+    Denied,
+    All,
+    Only(Vec<String>),
+}
+
+impl PermissionGrant {
+    /// The list of allowed entries in the form Deno expects (`None` to deny and an empty list to
+    /// allow all)
+    fn allow_list(&self) -> Option<Vec<String>> {
+        match self {
+            PermissionGrant::Denied => None,
+            PermissionGrant::All => Some(vec![]),
+            PermissionGrant::Only(entries) if entries.is_empty() => None,
+            PermissionGrant::Only(entries) => Some(entries.clone()),
+        }
+    }
+
+    fn allow_path_list(&self) -> Option<Vec<PathBuf>> {
+        self.allow_list()
+            .map(|entries| entries.into_iter().map(PathBuf::from).collect())
+    }
+}
+
+impl DenoPermissions {
+    /// The permissions in the form the Deno runtime expects (fails if an entry is invalid, such as
+    /// a malformed host)
+    pub fn to_container(&self) -> Result<PermissionsContainer, AnyError> {
+        let options = PermissionsOptions {
+            allow_net: self.net.allow_list(),
+            allow_env: self.env.allow_list(),
+            allow_read: self.read.allow_path_list(),
+            allow_write: self.write.allow_path_list(),
+            allow_run: self.run.allow_list(),
+            allow_sys: self.sys.allow_list(),
+            allow_ffi: self.ffi.allow_path_list(),
+            // There is no one to answer a prompt, so deny anything not granted upfront
+            prompt: false,
+            ..Default::default()
+        };
+
+        Ok(PermissionsContainer::new(Permissions::from_options(
+            &options,
+        )?))
+    }
+}
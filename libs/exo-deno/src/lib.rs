@@ -16,9 +16,11 @@ pub mod deno_error;
 pub mod deno_executor;
 pub mod deno_executor_pool;
 pub mod deno_module;
+pub mod deno_permissions;
 
-pub use deno_executor_pool::DenoExecutorPool;
+pub use deno_executor_pool::{DenoActorPoolStats, DenoExecutorPool, DenoLimits};
 pub use deno_module::{Arg, DenoModule, DenoModuleSharedState, UserCode};
+pub use deno_permissions::{DenoPermissions, PermissionGrant};
 
 mod deno_actor;
 mod embedded_module_loader;
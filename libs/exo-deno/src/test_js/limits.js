@@ -0,0 +1,23 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+export function add(i, j) {
+  return i + j;
+}
+
+export function spin() {
+  while (true) { }
+}
+
+export function allocate() {
+  const chunks = [];
+  while (true) {
+    chunks.push(new Array(1024 * 1024).fill(chunks.length));
+  }
+}
@@ -7,6 +7,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::collections::HashSet;
+
+use crate::{Database, TableId};
+
 use super::{
     delete::AbstractDelete, insert::AbstractInsert, select::AbstractSelect, update::AbstractUpdate,
 };
@@ -19,3 +23,41 @@ pub enum AbstractOperation {
     Insert(AbstractInsert),
     Update(AbstractUpdate),
 }
+
+impl AbstractOperation {
+    /// The tables that the operation depends on.
+    ///
+    /// For a select, these are the tables whose rows may affect the result. For a mutation, these
+    /// are the tables whose rows may change: the mutated table along with any table that refers to
+    /// it (either through nested mutations or through referential actions such as `ON DELETE
+    /// CASCADE`).
+    pub fn table_ids(&self, database: &Database) -> HashSet<TableId> {
+        let root_table_id = match self {
+            AbstractOperation::Select(select) => return select.table_ids(),
+            AbstractOperation::Delete(delete) => delete.table_id,
+            AbstractOperation::Insert(insert) => insert.table_id,
+            AbstractOperation::Update(update) => update.table_id,
+        };
+
+        let mut table_ids = HashSet::from([root_table_id]);
+
+        loop {
+            let referring_table_ids: Vec<_> = database
+                .relations
+                .iter()
+                .filter(|relation| {
+                    table_ids.contains(&relation.foreign_table_id())
+                        && !table_ids.contains(&relation.self_table_id())
+                })
+                .map(|relation| relation.self_table_id())
+                .collect();
+
+            if referring_table_ids.is_empty() {
+                break;
+            }
+            table_ids.extend(referring_table_ids);
+        }
+
+        table_ids
+    }
+}
@@ -13,7 +13,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     sql::{predicate::ParamEquality, SQLParamContainer},
-    ColumnId, Database, TableId,
+    AbstractPredicate, ColumnId, Database, TableId,
 };
 
 /// A link in `ColumnPath` to a column starting at a root table and ending at a leaf column. This
@@ -32,6 +32,48 @@ pub enum ColumnPath {
     Physical(PhysicalColumnPath),
     Param(SQLParamContainer),
     Null,
+    /// The number of rows in a related table (through a one-to-many relation) that satisfy a
+    /// predicate. For example, the number of drafts of a document would be:
+    /// ```text
+    /// Count {
+    ///     path: [{ self_column: ("document", "id"), linked_column: None }],
+    ///     foreign_column_id: ("draft", "document_id"),
+    ///     predicate: <predicate on the draft table>,
+    /// }
+    /// ```
+    Count {
+        /// The path to the column that the related rows refer to
+        path: PhysicalColumnPath,
+        /// The column in the related table that refers to the leaf column of `path`
+        foreign_column_id: ColumnId,
+        /// The predicate that a related row must satisfy to be counted (with column paths
+        /// starting at the related table)
+        predicate: Box<AbstractPredicate>,
+    },
+}
+
+impl ColumnPath {
+    /// The tables that this column path refers to
+    pub fn table_ids(&self) -> Vec<TableId> {
+        match self {
+            ColumnPath::Physical(path) => path.table_ids().collect(),
+            ColumnPath::Param(_) | ColumnPath::Null => vec![],
+            ColumnPath::Count {
+                path,
+                foreign_column_id,
+                predicate,
+            } => path
+                .table_ids()
+                .chain([foreign_column_id.table_id])
+                .chain(
+                    predicate
+                        .column_paths()
+                        .into_iter()
+                        .flat_map(|column_path| column_path.table_ids()),
+                )
+                .collect(),
+        }
+    }
 }
 
 impl ParamEquality for ColumnPath {
@@ -122,14 +164,22 @@ impl Ord for RelationLink {
 impl ColumnPathLink {
     /// Determines if this link is a one-to-many link.
     ///
-    /// If the self column is a primary key and the linked column links to a table, then this is a
-    /// one-to-many link. For example, when referring from a venue to concerts, the `venue.id` would
-    /// be the self column and `concert.venue_id` would be the linked column.
+    /// If the link isn't one of the database's many-to-one relations (whose self column refers to
+    /// the primary key of the linked table), then this is a one-to-many link. For example, when
+    /// referring from a venue to concerts, the `venue.id` would be the self column and
+    /// `concert.venue_id` would be the linked column. Note that we cannot just check if the self
+    /// column is a primary key, since a foreign key column may be a part of a composite primary
+    /// key (as in a join table keyed on both of its foreign keys).
     pub fn is_one_to_many(&self, database: &Database) -> bool {
         match self {
-            ColumnPathLink::Relation(RelationLink { self_column_id, .. }) => {
-                self_column_id.get_column(database).is_pk
-            }
+            ColumnPathLink::Relation(RelationLink {
+                self_column_id,
+                foreign_column_id,
+                ..
+            }) => !database.relations.iter().any(|relation| {
+                &relation.self_column_id == self_column_id
+                    && &relation.foreign_pk_column_id == foreign_column_id
+            }),
             ColumnPathLink::Leaf(_) => false,
         }
     }
@@ -174,6 +224,27 @@ impl PhysicalColumnPath {
         )
     }
 
+    /// Split a path that ends in a relation link (such as the path to `drafts` in
+    /// `document.drafts`) into a path to the self column of that link and its foreign column.
+    ///
+    /// Returns `None` if the path ends in a leaf column.
+    pub fn split_last_relation(&self) -> Option<(PhysicalColumnPath, ColumnId)> {
+        let (last, init) = self.0.split_last()?;
+
+        match last {
+            ColumnPathLink::Relation(RelationLink {
+                self_column_id,
+                foreign_column_id,
+                ..
+            }) => {
+                let mut path = init.to_vec();
+                path.push(ColumnPathLink::Leaf(*self_column_id));
+                Some((PhysicalColumnPath(path), *foreign_column_id))
+            }
+            ColumnPathLink::Leaf(_) => None,
+        }
+    }
+
     pub fn leaf_column(&self) -> ColumnId {
         match self.0.last().unwrap() {
             ColumnPathLink::Relation(_) => unreachable!("Invariant: last link must be a leaf"),
@@ -189,6 +260,18 @@ impl PhysicalColumnPath {
         self.0[0].self_column_id().table_id
     }
 
+    /// The tables that the path goes through
+    pub fn table_ids(&self) -> impl Iterator<Item = TableId> + '_ {
+        self.0.iter().flat_map(|link| match link {
+            ColumnPathLink::Relation(RelationLink {
+                self_column_id,
+                foreign_column_id,
+                ..
+            }) => vec![self_column_id.table_id, foreign_column_id.table_id],
+            ColumnPathLink::Leaf(column_id) => vec![column_id.table_id],
+        })
+    }
+
     pub fn push(mut self, link: ColumnPathLink) -> Self {
         // Assert that the the last link in the path points to the same table as the new link's self table
         // This checks for the last two invariants (see above):
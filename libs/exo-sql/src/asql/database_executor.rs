@@ -29,9 +29,7 @@ pub struct DatabaseExecutor {
 }
 
 impl DatabaseExecutor {
-    /// Execute an operation on a database.
-    ///
-    /// Currently makes a hard assumption on Postgres implementation, but this could be made more generic.
+    /// Execute an operation on a Postgres database (see `SqliteExecutor` for SQLite).
     pub async fn execute(
         &self,
         operation: &AbstractOperation,
@@ -62,6 +60,10 @@ pub struct TransactionHolder {
     transaction: Option<*mut TransactionWrapper<'static>>,
     finalized: AtomicBool,
     needs_transaction: AtomicBool,
+    /// Configuration parameters to set (locally) once the transaction starts
+    settings: Vec<(String, String)>,
+    /// Functions to run once the transaction commits
+    on_commit: Vec<Box<dyn FnOnce() + Send>>,
 }
 
 /// # Safety
@@ -137,6 +139,10 @@ impl TransactionHolder {
                             .load(std::sync::atomic::Ordering::SeqCst)
                     {
                         let mut tx = Box::new(client.transaction().await?);
+                        for (name, value) in self.settings.iter() {
+                            tx.execute("SELECT set_config($1, $2, true)", &[name, value])
+                                .await?;
+                        }
                         let res = work.execute(database, tx.deref_mut().deref_mut()).await;
 
                         self.transaction = Some(Box::leak(tx));
@@ -172,11 +178,46 @@ impl TransactionHolder {
 
         self.finalized
             .store(true, std::sync::atomic::Ordering::SeqCst);
+
+        let on_commit = std::mem::take(&mut self.on_commit);
+        if commit {
+            on_commit.into_iter().for_each(|f| f());
+        }
+
         Ok(())
     }
 
+    /// Run `f` once the changes made so far are committed. Without a transaction, each change is
+    /// committed as it is made, so `f` runs right away. Otherwise, it runs after the transaction
+    /// commits (and not at all if the transaction is rolled back).
+    pub fn on_commit(&mut self, f: impl FnOnce() + Send + 'static) {
+        if self.in_transaction() {
+            self.on_commit.push(Box::new(f));
+        } else {
+            f()
+        }
+    }
+
     pub fn ensure_transaction(&self) {
         self.needs_transaction
             .store(true, std::sync::atomic::Ordering::SeqCst);
     }
+
+    /// Set configuration parameters (such as `exo.auth_context.id` used by row-level security
+    /// policies) for the duration of the transaction. Since the parameters are local to the
+    /// transaction, this ensures that one is started. Has no effect if a transaction has already
+    /// been started.
+    pub fn set_config(&mut self, settings: Vec<(String, String)>) {
+        if settings.is_empty() || self.in_transaction() {
+            return;
+        }
+
+        self.ensure_transaction();
+        self.settings = settings;
+    }
+
+    /// Has a transaction been started (and not yet finalized)?
+    pub fn in_transaction(&self) -> bool {
+        self.transaction.is_some()
+    }
 }
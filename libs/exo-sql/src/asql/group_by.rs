@@ -0,0 +1,60 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::{
+    sql::{
+        column::Column,
+        function::Function,
+        predicate::{ParamEquality, Predicate},
+    },
+    ColumnId, SQLParamContainer,
+};
+
+/// Grouping of an [`AbstractSelect`](super::select::AbstractSelect).
+///
+/// With a group-by, the select produces one element for each distinct combination of values of
+/// the grouped columns (instead of one for each row) and the selection (along with the order-by)
+/// may use aggregate functions such as `count("todos"."id")` over the rows of each group.
+#[derive(Debug)]
+pub struct AbstractGroupBy {
+    /// The columns (of the root table) to group rows by
+    pub column_ids: Vec<ColumnId>,
+    /// The predicate to filter groups (applied after grouping, unlike the select's predicate)
+    pub having: AbstractHaving,
+}
+
+/// A predicate over the aggregates of a group such as `count("todos"."id") > 5`
+pub type AbstractHaving = Predicate<AggregateOperand>;
+
+/// An operand of a [`AbstractHaving`] predicate
+#[derive(Debug, Clone, PartialEq)]
+pub enum AggregateOperand {
+    /// An aggregate function such as `count("todos"."id")`
+    Function(Function),
+    /// A literal value
+    Param(SQLParamContainer),
+}
+
+impl ParamEquality for AggregateOperand {
+    fn param_eq(&self, other: &Self) -> Option<bool> {
+        match (self, other) {
+            (Self::Param(v1), Self::Param(v2)) => Some(v1 == v2),
+            _ => None,
+        }
+    }
+}
+
+impl AggregateOperand {
+    pub(crate) fn to_column(&self) -> Column {
+        match self {
+            AggregateOperand::Function(function) => Column::Function(function.clone()),
+            AggregateOperand::Param(value) => Column::Param(value.clone()),
+        }
+    }
+}
@@ -22,10 +22,19 @@
 //! ```
 //!
 //! Here, concerts created will have their `venue_id` set to the id of the venue being created.
+//!
+//! An insert may also specify how to resolve a conflict with an existing row (an "upsert"):
+//! ```graphql
+//! mutation {
+//!   upsertVenue(data: {name: "v1", published: true, latitude: 1.2}, onConflict: {constraint: name}) {
+//!     id
+//!   }
+//! }
+//! ```
 
 use super::select::AbstractSelect;
 use crate::sql::column::Column;
-use crate::{ColumnId, OneToManyId, TableId};
+use crate::{AbstractPredicate, ColumnId, OneToManyId, TableId};
 
 #[derive(Debug)]
 pub struct AbstractInsert {
@@ -33,10 +42,27 @@ pub struct AbstractInsert {
     pub table_id: TableId,
     /// Rows to insert
     pub rows: Vec<InsertionRow>,
+    /// How to resolve a conflict with an existing row (if `None`, a conflict is an error)
+    pub on_conflict: Option<AbstractOnConflict>,
     /// The selection to return
     pub selection: AbstractSelect,
 }
 
+/// Conflict resolution for an insert. Translates to `INSERT ... ON CONFLICT (<conflict_columns>)
+/// DO UPDATE SET <column> = EXCLUDED.<column>, ... WHERE <predicate>`.
+///
+/// The columns updated are the same as the columns supplied for each row (other than the
+/// conflict columns themselves).
+#[derive(Debug)]
+pub struct AbstractOnConflict {
+    /// The columns of the primary key or a unique constraint to detect a conflict on
+    pub conflict_columns: Vec<ColumnId>,
+    /// The predicate an existing row must satisfy to be updated (typically the update access
+    /// predicate). A conflicting row not matching the predicate is left untouched and isn't
+    /// returned.
+    pub predicate: AbstractPredicate,
+}
+
 /// A logical row to be inserted (see `InsertionElement` for more details).
 #[derive(Debug)]
 pub struct InsertionRow {
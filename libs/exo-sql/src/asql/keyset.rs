@@ -0,0 +1,357 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::{database_error::DatabaseError, sql::order::Ordering, Database};
+
+use super::{
+    column_path::ColumnPath,
+    order_by::{AbstractOrderBy, AbstractOrderByExpr},
+    predicate::AbstractPredicate,
+};
+
+/// Keyset (also known as "seek") pagination bounds of an [`AbstractSelect`](super::select::AbstractSelect).
+///
+/// Each bound holds one value for each element of the select's order-by (in the same order) and
+/// restricts the result to rows that sort strictly after (or before) that position. Unlike an
+/// offset, the database can use an index to seek to the position and concurrent writes do not
+/// shift rows between pages.
+///
+/// Since the bounds usually come from a client (as cursors), we validate them against the order-by
+/// when creating the keyset and hold on to the resulting predicate.
+#[derive(Debug)]
+pub struct AbstractKeyset {
+    predicate: AbstractPredicate,
+}
+
+impl AbstractKeyset {
+    /// Create a keyset that selects only rows that sort after the `after` position and before the
+    /// `before` position when ordered by `order_by`.
+    ///
+    /// For `ORDER BY a ASC, b DESC` and an `after` position `(x, y)`, the predicate is `a > x OR (a
+    /// = x AND b < y)`. For a `before` position, the comparisons are flipped.
+    ///
+    /// NULLs sort as if larger than any value (as Postgres does by default: last for `ASC` and first
+    /// for `DESC`), so for a nullable column `a`, rows past `x` in ascending order also include
+    /// those with `a IS NULL`, and a position whose value is NULL is compared with `IS NULL`/`IS NOT
+    /// NULL` rather than with operators that would never match.
+    ///
+    /// Fails if a position doesn't have a value for each order-by element or if the order-by has
+    /// an element other than a column (such as a vector distance), since rows can't be compared to
+    /// a position in that case.
+    pub fn new(
+        order_by: &AbstractOrderBy,
+        after: Option<Vec<ColumnPath>>,
+        before: Option<Vec<ColumnPath>>,
+        database: &Database,
+    ) -> Result<Self, DatabaseError> {
+        let after = after
+            .map(|values| bound_predicate(order_by, values, true, database))
+            .transpose()?
+            .unwrap_or(AbstractPredicate::True);
+        let before = before
+            .map(|values| bound_predicate(order_by, values, false, database))
+            .transpose()?
+            .unwrap_or(AbstractPredicate::True);
+
+        Ok(Self {
+            predicate: AbstractPredicate::and(after, before),
+        })
+    }
+
+    /// The predicate that restricts rows to those between the bounds
+    pub fn predicate(&self) -> &AbstractPredicate {
+        &self.predicate
+    }
+}
+
+fn bound_predicate(
+    order_by: &AbstractOrderBy,
+    values: Vec<ColumnPath>,
+    after: bool,
+    database: &Database,
+) -> Result<AbstractPredicate, DatabaseError> {
+    if order_by.0.len() != values.len() {
+        return Err(DatabaseError::Validation(format!(
+            "Keyset pagination requires {} values (one for each order-by element), but got {}",
+            order_by.0.len(),
+            values.len()
+        )));
+    }
+
+    let elements = order_by
+        .0
+        .iter()
+        .zip(values)
+        .map(|((expr, ordering), value)| match expr {
+            AbstractOrderByExpr::Column(path) => {
+                let nullable = path.leaf_column().get_column(database).is_nullable;
+                Ok((
+                    ColumnPath::Physical(path.clone()),
+                    ordering,
+                    nullable,
+                    value,
+                ))
+            }
+            AbstractOrderByExpr::VectorDistance(..) => Err(DatabaseError::Validation(
+                "Keyset pagination is not supported when ordering by vector distance".into(),
+            )),
+            AbstractOrderByExpr::TextSearchRank(..) => Err(DatabaseError::Validation(
+                "Keyset pagination is not supported when ordering by search rank".into(),
+            )),
+            AbstractOrderByExpr::Function(..) => Err(DatabaseError::Validation(
+                "Keyset pagination is not supported when ordering by an aggregate".into(),
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Build from the least significant element, so each step reads "strictly past this column, or
+    // equal to it and past the rest"
+    Ok(elements.into_iter().rev().fold(
+        AbstractPredicate::False,
+        |rest, (column, ordering, nullable, value)| {
+            // Are we moving toward larger values (and thus toward NULLs)?
+            let ascending = (ordering == &Ordering::Asc) == after;
+
+            let (past, equal) = if matches!(value, ColumnPath::Null) {
+                let past = if ascending {
+                    // Nothing sorts past NULL
+                    AbstractPredicate::False
+                } else {
+                    AbstractPredicate::Neq(column.clone(), ColumnPath::Null)
+                };
+                (past, AbstractPredicate::Eq(column, ColumnPath::Null))
+            } else {
+                let past = if ascending {
+                    let greater = AbstractPredicate::Gt(column.clone(), value.clone());
+                    if nullable {
+                        AbstractPredicate::or(
+                            greater,
+                            AbstractPredicate::Eq(column.clone(), ColumnPath::Null),
+                        )
+                    } else {
+                        greater
+                    }
+                } else {
+                    AbstractPredicate::Lt(column.clone(), value.clone())
+                };
+                (past, AbstractPredicate::eq(column, value))
+            };
+
+            AbstractPredicate::or(past, AbstractPredicate::and(equal, rest))
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transform::test_util::TestSetup, Function, PhysicalColumnPath, SQLParamContainer};
+
+    use multiplatform_test::multiplatform_test;
+
+    #[multiplatform_test]
+    fn single_column_after() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_id_column,
+                 ..
+             }| {
+                let id_path = PhysicalColumnPath::leaf(concerts_id_column);
+                let order_by = AbstractOrderBy(vec![(
+                    AbstractOrderByExpr::Column(id_path.clone()),
+                    Ordering::Asc,
+                )]);
+                let value = ColumnPath::Param(SQLParamContainer::i32(5));
+
+                let keyset =
+                    AbstractKeyset::new(&order_by, Some(vec![value.clone()]), None, &database)
+                        .unwrap();
+
+                assert_eq!(
+                    keyset.predicate(),
+                    &AbstractPredicate::Gt(ColumnPath::Physical(id_path), value)
+                );
+            },
+        )
+    }
+
+    #[multiplatform_test]
+    fn multi_column_mixed_ordering() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_id_column,
+                 concerts_name_column,
+                 ..
+             }| {
+                let name_path = PhysicalColumnPath::leaf(concerts_name_column);
+                let id_path = PhysicalColumnPath::leaf(concerts_id_column);
+                let order_by = AbstractOrderBy(vec![
+                    (
+                        AbstractOrderByExpr::Column(name_path.clone()),
+                        Ordering::Desc,
+                    ),
+                    (AbstractOrderByExpr::Column(id_path.clone()), Ordering::Asc),
+                ]);
+                let name_value = ColumnPath::Param(SQLParamContainer::string("c".to_string()));
+                let id_value = ColumnPath::Param(SQLParamContainer::i32(5));
+
+                let keyset = AbstractKeyset::new(
+                    &order_by,
+                    None,
+                    Some(vec![name_value.clone(), id_value.clone()]),
+                    &database,
+                )
+                .unwrap();
+
+                let name_column = ColumnPath::Physical(name_path);
+                let id_column = ColumnPath::Physical(id_path);
+
+                assert_eq!(
+                    keyset.predicate(),
+                    &AbstractPredicate::or(
+                        AbstractPredicate::Gt(name_column.clone(), name_value.clone()),
+                        AbstractPredicate::and(
+                            AbstractPredicate::Eq(name_column, name_value),
+                            AbstractPredicate::Lt(id_column, id_value)
+                        )
+                    )
+                );
+            },
+        )
+    }
+
+    #[multiplatform_test]
+    fn nullable_column() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 venues_table,
+                 venues_id_column,
+                 ..
+             }| {
+                let deleted_at_path = PhysicalColumnPath::leaf(
+                    database.get_column_id(venues_table, "deleted_at").unwrap(),
+                );
+                let id_path = PhysicalColumnPath::leaf(venues_id_column);
+                let order_by = AbstractOrderBy(vec![
+                    (
+                        AbstractOrderByExpr::Column(deleted_at_path.clone()),
+                        Ordering::Asc,
+                    ),
+                    (AbstractOrderByExpr::Column(id_path.clone()), Ordering::Asc),
+                ]);
+                let deleted_at_column = ColumnPath::Physical(deleted_at_path);
+                let id_column = ColumnPath::Physical(id_path);
+                let id_value = ColumnPath::Param(SQLParamContainer::i32(5));
+                let is_null = AbstractPredicate::Eq(deleted_at_column.clone(), ColumnPath::Null);
+
+                // After a non-NULL value, rows with NULL (which sort last) are still ahead
+                let deleted_at_value =
+                    ColumnPath::Param(SQLParamContainer::string("2024-01-01".to_string()));
+                let keyset = AbstractKeyset::new(
+                    &order_by,
+                    Some(vec![deleted_at_value.clone(), id_value.clone()]),
+                    None,
+                    &database,
+                )
+                .unwrap();
+                assert_eq!(
+                    keyset.predicate(),
+                    &AbstractPredicate::or(
+                        AbstractPredicate::or(
+                            AbstractPredicate::Gt(
+                                deleted_at_column.clone(),
+                                deleted_at_value.clone()
+                            ),
+                            is_null.clone()
+                        ),
+                        AbstractPredicate::and(
+                            AbstractPredicate::Eq(deleted_at_column.clone(), deleted_at_value),
+                            AbstractPredicate::Gt(id_column.clone(), id_value.clone())
+                        )
+                    )
+                );
+
+                // After a NULL value, only the rows with NULL and a larger id are ahead
+                let keyset = AbstractKeyset::new(
+                    &order_by,
+                    Some(vec![ColumnPath::Null, id_value.clone()]),
+                    None,
+                    &database,
+                )
+                .unwrap();
+                assert_eq!(
+                    keyset.predicate(),
+                    &AbstractPredicate::and(
+                        is_null.clone(),
+                        AbstractPredicate::Gt(id_column.clone(), id_value.clone())
+                    )
+                );
+
+                // Before a NULL value, all rows with a value are behind
+                let keyset = AbstractKeyset::new(
+                    &order_by,
+                    None,
+                    Some(vec![ColumnPath::Null, id_value.clone()]),
+                    &database,
+                )
+                .unwrap();
+                assert_eq!(
+                    keyset.predicate(),
+                    &AbstractPredicate::or(
+                        AbstractPredicate::Neq(deleted_at_column, ColumnPath::Null),
+                        AbstractPredicate::and(is_null, AbstractPredicate::Lt(id_column, id_value))
+                    )
+                );
+            },
+        )
+    }
+
+    #[multiplatform_test]
+    fn invalid_bounds() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_id_column,
+                 ..
+             }| {
+                let id_path = PhysicalColumnPath::leaf(concerts_id_column);
+                let value = || ColumnPath::Param(SQLParamContainer::i32(5));
+
+                // A value for each order-by element is required
+                let order_by = AbstractOrderBy(vec![(
+                    AbstractOrderByExpr::Column(id_path.clone()),
+                    Ordering::Asc,
+                )]);
+                assert!(AbstractKeyset::new(
+                    &order_by,
+                    Some(vec![value(), value()]),
+                    None,
+                    &database
+                )
+                .is_err());
+                assert!(AbstractKeyset::new(&order_by, None, Some(vec![]), &database).is_err());
+
+                // Rows can't be compared to a position when ordering by an aggregate
+                let order_by = AbstractOrderBy(vec![(
+                    AbstractOrderByExpr::Function(Function::Named {
+                        function_name: "count".to_string(),
+                        column_id: concerts_id_column,
+                    }),
+                    Ordering::Asc,
+                )]);
+                assert!(
+                    AbstractKeyset::new(&order_by, Some(vec![value()]), None, &database).is_err()
+                );
+            },
+        )
+    }
+}
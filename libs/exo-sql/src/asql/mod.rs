@@ -11,11 +11,15 @@ pub mod abstract_operation;
 pub mod column_path;
 pub mod database_executor;
 pub mod delete;
+pub mod group_by;
 pub mod insert;
+pub mod keyset;
 pub mod order_by;
 
 pub mod predicate;
 pub mod select;
 pub mod selection;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_executor;
 
 pub mod update;
@@ -7,7 +7,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::{sql::order::Ordering, ColumnPath, VectorDistanceFunction};
+use crate::{
+    sql::{function::Function, order::Ordering},
+    ColumnPath, VectorDistanceFunction,
+};
 
 use super::column_path::PhysicalColumnPath;
 
@@ -19,6 +22,11 @@ pub struct AbstractOrderBy(pub Vec<(AbstractOrderByExpr, Ordering)>);
 pub enum AbstractOrderByExpr {
     Column(PhysicalColumnPath),
     VectorDistance(ColumnPath, ColumnPath, VectorDistanceFunction),
+    /// Rank of a full-text search match (the `tsvector` column, the search terms, and the
+    /// text-search language)
+    TextSearchRank(ColumnPath, ColumnPath, String),
+    /// An aggregate function such as `count("todos"."id")` (when ordering groups of a group-by)
+    Function(Function),
 }
 
 impl AbstractOrderBy {
@@ -27,13 +35,15 @@ impl AbstractOrderBy {
             .iter()
             .flat_map(|(expr, _)| match expr {
                 AbstractOrderByExpr::Column(path) => vec![path],
-                AbstractOrderByExpr::VectorDistance(lhs, rhs, _) => [lhs, rhs]
+                AbstractOrderByExpr::VectorDistance(lhs, rhs, _)
+                | AbstractOrderByExpr::TextSearchRank(lhs, rhs, _) => [lhs, rhs]
                     .iter()
                     .filter_map(|path| match path {
                         ColumnPath::Physical(path) => Some(path),
                         _ => None,
                     })
                     .collect(),
+                AbstractOrderByExpr::Function(_) => vec![],
             })
             .collect()
     }
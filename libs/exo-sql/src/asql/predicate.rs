@@ -38,6 +38,7 @@ impl AbstractPredicate {
             | AbstractPredicate::JsonMatchAllKeys(l, r) => vec![l, r],
 
             AbstractPredicate::VectorDistance(c1, c2, _, _, c3) => vec![c1, c2, c3],
+            AbstractPredicate::TextSearch(l, r, _) => vec![l, r],
 
             AbstractPredicate::And(l, r) | AbstractPredicate::Or(l, r) => {
                 let mut result = l.column_paths();
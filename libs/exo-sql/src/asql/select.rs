@@ -7,9 +7,24 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::{Limit, Offset, TableId};
+use std::{collections::HashSet, fmt::Write};
 
-use super::{order_by::AbstractOrderBy, predicate::AbstractPredicate, selection::Selection};
+use bytes::BytesMut;
+use tokio_postgres::types::IsNull;
+
+use crate::{
+    sql::{ExpressionBuilder, SQLBuilder},
+    transform::{pg::Postgres, transformer::SelectTransformer},
+    ColumnPath, Database, Limit, Offset, TableId,
+};
+
+use super::{
+    group_by::AbstractGroupBy,
+    keyset::AbstractKeyset,
+    order_by::{AbstractOrderBy, AbstractOrderByExpr},
+    predicate::AbstractPredicate,
+    selection::{Selection, SelectionElement},
+};
 
 /// Represents an abstract select operation, but without specific details about how to execute it.
 #[derive(Debug)]
@@ -27,4 +42,87 @@ pub struct AbstractSelect {
     pub offset: Option<Offset>,
     /// The limit
     pub limit: Option<Limit>,
+    /// The keyset pagination bounds (relative to the `order_by`)
+    pub keyset: Option<AbstractKeyset>,
+    /// The grouping of rows (the selection then applies to each group instead of each row)
+    pub group_by: Option<AbstractGroupBy>,
+    /// Should soft-deleted rows of the table be selected? (has no effect for a table without soft
+    /// deletion)
+    pub include_deleted: bool,
+}
+
+impl AbstractSelect {
+    /// The tables whose rows may affect the result of this select (the selected table along with
+    /// any table that the selection, predicate, or ordering refers to)
+    pub fn table_ids(&self) -> HashSet<TableId> {
+        fn physical_table_ids<'a>(
+            column_paths: impl Iterator<Item = &'a ColumnPath>,
+        ) -> impl Iterator<Item = TableId> + 'a {
+            column_paths.flat_map(|column_path| column_path.table_ids())
+        }
+
+        fn element_table_ids(element: &SelectionElement, table_ids: &mut HashSet<TableId>) {
+            match element {
+                SelectionElement::Object(elements) => elements
+                    .iter()
+                    .for_each(|(_, element)| element_table_ids(element, table_ids)),
+                SelectionElement::SubSelect(_, select) => table_ids.extend(select.table_ids()),
+                SelectionElement::Physical(_)
+                | SelectionElement::Function(_)
+                | SelectionElement::Constant(_) => {}
+            }
+        }
+
+        let mut table_ids = HashSet::from([self.table_id]);
+
+        let elements = match &self.selection {
+            Selection::Seq(elements) | Selection::Json(elements, _) => elements,
+        };
+        elements
+            .iter()
+            .for_each(|element| element_table_ids(&element.column, &mut table_ids));
+
+        table_ids.extend(physical_table_ids(
+            self.predicate.column_paths().into_iter(),
+        ));
+
+        if let Some(AbstractOrderBy(order_by)) = &self.order_by {
+            for (expr, _) in order_by {
+                match expr {
+                    AbstractOrderByExpr::Column(path) => table_ids.extend(path.table_ids()),
+                    AbstractOrderByExpr::VectorDistance(lhs, rhs, _)
+                    | AbstractOrderByExpr::TextSearchRank(lhs, rhs, _) => {
+                        table_ids.extend(physical_table_ids([lhs, rhs].into_iter()))
+                    }
+                    AbstractOrderByExpr::Function(_) => {}
+                }
+            }
+        }
+
+        table_ids
+    }
+
+    /// A key that identifies the result of this select: the SQL along with the (encoded) values
+    /// of its parameters. Returns `None` if a parameter can't be encoded.
+    pub fn cache_key(&self, database: &Database) -> Option<String> {
+        let select = Postgres {}.to_select(self, database);
+
+        let mut builder = SQLBuilder::new();
+        select.build(database, &mut builder);
+        let (mut key, params) = builder.into_sql();
+
+        for (param, param_type) in params {
+            let mut raw = BytesMut::new();
+            write!(key, ";{param_type}=").ok()?;
+            match param.to_sql_checked(&param_type, &mut raw).ok()? {
+                IsNull::Yes => key.push_str("null"),
+                IsNull::No => raw
+                    .iter()
+                    .try_for_each(|byte| write!(key, "{byte:02x}"))
+                    .ok()?,
+            }
+        }
+
+        Some(key)
+    }
 }
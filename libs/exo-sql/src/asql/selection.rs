@@ -24,6 +24,11 @@ impl AliasedSelectionElement {
     pub fn new(alias: String, column: SelectionElement) -> Self {
         Self { alias, column }
     }
+
+    /// Split into the alias and the element (for example, to nest the element in a [`SelectionElement::Object`])
+    pub fn into_parts(self) -> (String, SelectionElement) {
+        (self.alias, self.column)
+    }
 }
 
 /// The cardinality of a json aggregate
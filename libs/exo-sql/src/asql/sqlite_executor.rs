@@ -0,0 +1,189 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::{
+    database_error::DatabaseError,
+    sql::{connect::sqlite_client_manager::SqliteClientManager, transaction::SqliteStepResult},
+    transform::{sqlite::Sqlite, transformer::OperationTransformer},
+    Database,
+};
+
+use super::abstract_operation::AbstractOperation;
+
+/// Executes operations on a SQLite database (the counterpart of
+/// [`DatabaseExecutor`](crate::DatabaseExecutor) for Postgres).
+///
+/// Meant for small tools and tests that need to run against an embedded database. The tables must
+/// already exist, and features without a SQLite counterpart (such as vector, text search, and
+/// JSON containment predicates) are not supported.
+pub struct SqliteExecutor {
+    pub database_client: SqliteClientManager,
+}
+
+impl SqliteExecutor {
+    /// Execute an operation on a database in its own transaction (committed only if all its steps
+    /// succeed).
+    ///
+    /// As with Postgres, a selection returns a single row with the JSON result in its only column.
+    pub fn execute(
+        &self,
+        operation: &AbstractOperation,
+        database: &Database,
+    ) -> Result<SqliteStepResult, DatabaseError> {
+        let transaction_script = Sqlite {}.to_transaction_script(database, operation);
+
+        self.database_client
+            .with_connection(|connection| -> Result<_, DatabaseError> {
+                let tx = connection.transaction()?;
+                let result = transaction_script.execute_sqlite(database, &tx)?;
+                tx.commit()?;
+                Ok(result)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multiplatform_test::multiplatform_test;
+    use rusqlite::types::Value;
+
+    use crate::{
+        schema::{
+            database_spec::DatabaseSpec,
+            table_spec::TableSpec,
+            test_helper::{pk_column, string_column},
+        },
+        AbstractDelete, AbstractInsert, AbstractPredicate, AbstractSelect, AbstractUpdate,
+        AliasedSelectionElement, Column, ColumnPath, ColumnValuePair, InsertionElement,
+        InsertionRow, PhysicalColumnPath, PhysicalTableName, Predicate, SQLParamContainer,
+        Selection, SelectionCardinality, SelectionElement,
+    };
+
+    use super::*;
+
+    #[multiplatform_test]
+    fn insert_update_delete() {
+        let database = DatabaseSpec::new(
+            vec![TableSpec::new(
+                PhysicalTableName::new("venues", None),
+                vec![pk_column("id"), string_column("name")],
+                vec![],
+                vec![],
+            )],
+            vec![],
+        )
+        .to_database();
+
+        let venues_table = database
+            .get_table_id(&PhysicalTableName::new("venues", None))
+            .unwrap();
+        let venues_id_column = database.get_column_id(venues_table, "id").unwrap();
+        let venues_name_column = database.get_column_id(venues_table, "name").unwrap();
+
+        let executor = SqliteExecutor {
+            database_client: SqliteClientManager::open_in_memory().unwrap(),
+        };
+        executor.database_client.with_connection(|connection| {
+            connection
+                .execute_batch(
+                    r#"CREATE TABLE "venues" ("id" INTEGER PRIMARY KEY, "name" TEXT NOT NULL)"#,
+                )
+                .unwrap()
+        });
+
+        let selection = || AbstractSelect {
+            table_id: venues_table,
+            selection: Selection::Json(
+                vec![
+                    AliasedSelectionElement::new(
+                        "id".to_string(),
+                        SelectionElement::Physical(venues_id_column),
+                    ),
+                    AliasedSelectionElement::new(
+                        "name".to_string(),
+                        SelectionElement::Physical(venues_name_column),
+                    ),
+                ],
+                SelectionCardinality::Many,
+            ),
+            predicate: Predicate::True,
+            order_by: None,
+            offset: None,
+            limit: None,
+            keyset: None,
+            group_by: None,
+            include_deleted: false,
+        };
+        let id_predicate = |id: i16| {
+            AbstractPredicate::Eq(
+                ColumnPath::Physical(PhysicalColumnPath::leaf(venues_id_column)),
+                ColumnPath::Param(SQLParamContainer::i16(id)),
+            )
+        };
+        let execute = |operation: AbstractOperation| {
+            executor
+                .execute(&operation, &database)
+                .unwrap()
+                .swap_remove(0)
+                .swap_remove(0)
+        };
+
+        let insert = AbstractOperation::Insert(AbstractInsert {
+            table_id: venues_table,
+            rows: ["v1", "v2"]
+                .into_iter()
+                .map(|name| InsertionRow {
+                    elems: vec![InsertionElement::SelfInsert(ColumnValuePair::new(
+                        venues_name_column,
+                        Column::Param(SQLParamContainer::str(name)),
+                    ))],
+                })
+                .collect(),
+            on_conflict: None,
+            selection: selection(),
+        });
+        assert_eq!(
+            execute(insert),
+            Value::Text(r#"[{"id":1,"name":"v1"},{"id":2,"name":"v2"}]"#.to_string())
+        );
+
+        let update = AbstractOperation::Update(AbstractUpdate {
+            table_id: venues_table,
+            predicate: id_predicate(1),
+            column_values: vec![(
+                venues_name_column,
+                Column::Param(SQLParamContainer::str("v1-updated")),
+            )],
+            nested_updates: vec![],
+            nested_inserts: vec![],
+            nested_deletes: vec![],
+            selection: selection(),
+        });
+        assert_eq!(
+            execute(update),
+            Value::Text(r#"[{"id":1,"name":"v1-updated"}]"#.to_string())
+        );
+
+        let delete = AbstractOperation::Delete(AbstractDelete {
+            table_id: venues_table,
+            predicate: id_predicate(2),
+            selection: selection(),
+        });
+        assert_eq!(
+            execute(delete),
+            Value::Text(r#"[{"id":2,"name":"v2"}]"#.to_string())
+        );
+
+        let select = AbstractOperation::Select(selection());
+        assert_eq!(
+            execute(select),
+            Value::Text(r#"[{"id":1,"name":"v1-updated"}]"#.to_string())
+        );
+    }
+}
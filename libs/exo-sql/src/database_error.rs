@@ -34,6 +34,10 @@ pub enum DatabaseError {
     #[error("Pool: {0}")]
     Pool(#[from] deadpool_postgres::PoolError),
 
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
     #[error("{0} {1}")]
     WithContext(String, #[source] Box<DatabaseError>),
 
@@ -48,6 +52,20 @@ impl DatabaseError {
     pub fn with_context(self, context: String) -> DatabaseError {
         DatabaseError::WithContext(context, Box::new(self))
     }
+
+    /// The name of the CHECK constraint, if this error is due to violating one
+    pub fn violated_check_constraint(&self) -> Option<&str> {
+        match self {
+            DatabaseError::Delegate(e) => e
+                .as_db_error()
+                .filter(|db_error| {
+                    db_error.code() == &tokio_postgres::error::SqlState::CHECK_VIOLATION
+                })
+                .and_then(|db_error| db_error.constraint()),
+            DatabaseError::WithContext(_, e) => e.violated_check_constraint(),
+            _ => None,
+        }
+    }
 }
 
 pub trait WithContext {
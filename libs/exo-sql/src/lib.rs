@@ -13,8 +13,9 @@
 /// an [AbstractOperation] into one or more SQL operations and executing them. This
 /// separation of intention vs execution allows for simplified expression from the
 /// user of the library and leaves out the details of the database operations.
-/// Although, currently it focuses solely on Postgres support, it should be easy to
-/// extend to other databases.
+/// It primarily targets Postgres, but also supports SQLite (through the `sqlite`
+/// feature and `SqliteExecutor`) for tools and tests that need an embedded
+/// database.
 ///
 /// For example, consider [AbstractSelect]. It allows expressing the intention to
 /// query data by specifying the root table, a predicate, and (potentially nested)
@@ -50,7 +51,12 @@ pub use asql::{
     column_path::{ColumnPath, ColumnPathLink, PhysicalColumnPath},
     database_executor::{DatabaseExecutor, TransactionHolder},
     delete::AbstractDelete,
-    insert::{AbstractInsert, ColumnValuePair, InsertionElement, InsertionRow, NestedInsertion},
+    group_by::{AbstractGroupBy, AbstractHaving, AggregateOperand},
+    insert::{
+        AbstractInsert, AbstractOnConflict, ColumnValuePair, InsertionElement, InsertionRow,
+        NestedInsertion,
+    },
+    keyset::AbstractKeyset,
     order_by::{AbstractOrderBy, AbstractOrderByExpr},
     predicate::AbstractPredicate,
     select::AbstractSelect,
@@ -65,19 +71,29 @@ pub use sql::{
     array_util::{self, ArrayEntry},
     column::Column,
     connect::creation::Connect,
-    connect::database_client_manager::DatabaseClientManager,
+    connect::database_client_manager::{DatabaseClientManager, DatabasePoolStatus},
     database::{Database, TableId},
     function::Function,
     limit::Limit,
     offset::Offset,
     order::Ordering,
     physical_column::{ColumnId, FloatBits, IntBits, PhysicalColumn, PhysicalColumnType},
-    physical_table::{PhysicalIndex, PhysicalTable, PhysicalTableName},
+    physical_enum::PhysicalEnum,
+    physical_table::{PhysicalCheck, PhysicalIndex, PhysicalTable, PhysicalTableName},
     predicate::{CaseSensitivity, NumericComparator, ParamEquality, Predicate},
-    relation::{ManyToOne, ManyToOneId, OneToMany, OneToManyId, RelationId},
+    relation::{ManyToOne, ManyToOneId, OneToMany, OneToManyId, ReferentialAction, RelationId},
+    text_search::DEFAULT_TEXT_SEARCH_LANGUAGE,
     vector::{VectorDistanceFunction, DEFAULT_VECTOR_SIZE},
     SQLBytes, SQLParam, SQLParamContainer,
 };
 
+#[cfg(feature = "postgres-url")]
+pub use sql::connect::notification_listener::{Notification, NotificationListener};
+
+#[cfg(feature = "sqlite")]
+pub use asql::sqlite_executor::SqliteExecutor;
+#[cfg(feature = "sqlite")]
+pub use sql::{connect::sqlite_client_manager::SqliteClientManager, transaction::SqliteStepResult};
+
 #[cfg(feature = "bigdecimal")]
 pub use pg_bigdecimal::BigDecimal;
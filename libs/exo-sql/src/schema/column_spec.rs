@@ -13,8 +13,11 @@ use crate::database_error::DatabaseError;
 use crate::sql::connect::database_client::DatabaseClient;
 use crate::{
     Database, FloatBits, IntBits, ManyToOne, PhysicalColumn, PhysicalColumnType, PhysicalTableName,
+    ReferentialAction,
 };
 
+use super::constraint::ForeignKeySpec;
+use super::enum_spec::enum_sql_name;
 use super::issue::{Issue, WithIssues};
 use super::op::SchemaOp;
 use super::statement::SchemaStatement;
@@ -31,6 +34,8 @@ pub struct ColumnSpec {
     pub is_nullable: bool,
     pub unique_constraints: Vec<String>,
     pub default_value: Option<String>,
+    /// The earlier name of the column (set only for a column computed from a model with a rename hint)
+    pub renamed_from: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -63,6 +68,11 @@ pub enum ColumnTypeSpec {
         foreign_table_name: PhysicalTableName,
         foreign_pk_column_name: String,
         foreign_pk_type: Box<ColumnTypeSpec>,
+        on_delete: ReferentialAction,
+        /// The columns of a foreign key to a table with a composite primary key (pairs of a
+        /// column in this table and the primary key column it refers to), in the order of the
+        /// referenced primary key columns. Empty for a single-column foreign key.
+        composite_key_columns: Vec<(String, String)>,
     },
     Float {
         bits: FloatBits,
@@ -71,6 +81,16 @@ pub enum ColumnTypeSpec {
         precision: Option<usize>,
         scale: Option<usize>,
     },
+    Enum {
+        enum_name: String,
+        /// The schema of the enum (`None` for the `public` schema)
+        enum_schema: Option<String>,
+    },
+    /// A generated `tsvector` column computed from the `source_column` (see `@searchable`)
+    TsVector {
+        language: String,
+        source_column: String,
+    },
 }
 
 impl ColumnSpec {
@@ -91,12 +111,18 @@ impl ColumnSpec {
         let db_type = match explicit_type {
             Some(t) => Some(t),
             None => {
-                // Query to find the type of the column and the # of dimensions if the type is an array
+                // Query to find the type of the column, the # of dimensions if the type is an array,
+                // and the name and schema of the enum if the (element) type is a user-defined enum
                 let db_type_query = format!(
                     "
-                    SELECT format_type(atttypid, atttypmod), attndims
-                    FROM pg_attribute
-                    WHERE attrelid = '{}'::regclass AND attname = '{column_name}'",
+                    SELECT format_type(a.atttypid, a.atttypmod), a.attndims, e.typname AS enum_name,
+                        en.nspname AS enum_schema
+                    FROM pg_attribute a
+                    LEFT JOIN pg_type t ON t.oid = a.atttypid
+                    LEFT JOIN pg_type e ON e.typtype = 'e'
+                        AND e.oid = CASE WHEN t.typelem <> 0 THEN t.typelem ELSE t.oid END
+                    LEFT JOIN pg_namespace en ON en.oid = e.typnamespace
+                    WHERE a.attrelid = '{}'::regclass AND a.attname = '{column_name}'",
                     table_name.fully_qualified_name()
                 );
 
@@ -124,15 +150,61 @@ impl ColumnSpec {
                 // So we manually query how many dimensions the column has and append `[]` to
                 // the type
                 sql_type += &"[]".repeat(if dims == 0 { 0 } else { (dims - 1) as usize });
-                match ColumnTypeSpec::from_string(&sql_type) {
-                    Ok(t) => Some(t),
-                    Err(e) => {
-                        issues.push(Issue::Warning(format!(
-                            "skipped column `{}.{column_name}` ({e})",
+
+                let enum_name: Option<String> = row.get("enum_name");
+
+                match enum_name {
+                    Some(enum_name) => {
+                        let enum_schema: Option<String> = row.get("enum_schema");
+                        let mut typ = ColumnTypeSpec::Enum {
+                            enum_name,
+                            enum_schema: enum_schema.filter(|schema| schema != "public"),
+                        };
+                        for _ in 0..sql_type.matches("[]").count() {
+                            typ = ColumnTypeSpec::Array { typ: Box::new(typ) };
+                        }
+                        Some(typ)
+                    }
+                    None if sql_type == "tsvector" => {
+                        // A generated text-search column, so recover its language and source
+                        // column from the generation expression
+                        let db_expr_query = format!(
+                            "
+                            SELECT pg_get_expr(d.adbin, d.adrelid) AS expr
+                            FROM pg_attrdef d
+                            JOIN pg_attribute a ON a.attrelid = d.adrelid AND a.attnum = d.adnum
+                            WHERE a.attrelid = '{}'::regclass AND a.attname = '{column_name}'",
                             table_name.fully_qualified_name()
-                        )));
-                        None
+                        );
+
+                        let expr: Option<String> = client
+                            .query(db_expr_query.as_str(), &[])
+                            .await?
+                            .first()
+                            .map(|row| row.get("expr"));
+
+                        let typ = expr
+                            .as_deref()
+                            .and_then(ColumnTypeSpec::from_text_search_expression);
+
+                        if typ.is_none() {
+                            issues.push(Issue::Warning(format!(
+                                "skipped column `{}.{column_name}` (unsupported tsvector column)",
+                                table_name.fully_qualified_name()
+                            )));
+                        }
+                        typ
                     }
+                    None => match ColumnTypeSpec::from_string(&sql_type) {
+                        Ok(t) => Some(t),
+                        Err(e) => {
+                            issues.push(Issue::Warning(format!(
+                                "skipped column `{}.{column_name}` ({e})",
+                                table_name.fully_qualified_name()
+                            )));
+                            None
+                        }
+                    },
                 }
             }
         };
@@ -209,6 +281,7 @@ impl ColumnSpec {
                 is_nullable: !not_null,
                 unique_constraints,
                 default_value,
+                renamed_from: None,
             }),
             issues,
         })
@@ -223,7 +296,12 @@ impl ColumnSpec {
         } = self
             .typ
             .to_sql(table_spec, &self.name, self.is_auto_increment);
-        let pk_str = if self.is_pk { " PRIMARY KEY" } else { "" };
+        // A composite primary key is declared as a table constraint (see `TableSpec::creation_sql`)
+        let pk_str = if self.is_pk && !table_spec.has_composite_pk() {
+            " PRIMARY KEY"
+        } else {
+            ""
+        };
         let not_null_str = if !self.is_nullable && !self.is_pk {
             // primary keys are implied to be not null
             " NOT NULL"
@@ -253,43 +331,70 @@ impl ColumnSpec {
         new_table: &'a TableSpec,
     ) -> Vec<SchemaOp<'a>> {
         let mut changes = vec![];
-        let table_name_same = self_table.sql_name() == new_table.sql_name();
+        let table_name_same = self_table.sql_name() == new_table.sql_name()
+            || new_table.renamed_from.as_ref() == Some(&self_table.name);
         let column_name_same = self.name == new.name;
+        let is_renamed = new.renamed_from.as_ref() == Some(&self.name);
         let type_same = self.typ == new.typ;
         let is_pk_same = self.is_pk == new.is_pk;
         let is_auto_increment_same = self.is_auto_increment == new.is_auto_increment;
         let is_nullable_same = self.is_nullable == new.is_nullable;
         let default_value_same = self.default_value == new.default_value;
 
-        if !(table_name_same && column_name_same) {
+        if !(table_name_same && (column_name_same || is_renamed)) {
             panic!("Diffing columns must have the same table name and column name");
         }
 
-        // If the column type differs only in reference type, that is taken care by table-level migration
-        if (!type_same && !self.differs_only_in_reference_column(new))
-            || !is_pk_same
-            || !is_auto_increment_same
-        {
+        // Any change in the referenced table (or the referential action) is taken care of by the
+        // table-level migration, so compare only the underlying types
+        let type_changed = !type_same && self.typ.underlying_type() != new.typ.underlying_type();
+
+        // Changing the type of an auto-increment column would need its sequence to be changed as well, so we recreate it
+        let can_alter_type =
+            !self.is_auto_increment && self.typ.alter_type_safety(&new.typ).is_some();
+
+        if (type_changed && !can_alter_type) || !is_pk_same || !is_auto_increment_same {
+            // The table may have been renamed, so the old column is referred through the new table
             changes.push(SchemaOp::DeleteColumn {
-                table: self_table,
+                table: new_table,
                 column: self,
             });
             changes.push(SchemaOp::CreateColumn {
                 table: new_table,
                 column: new,
-            })
-        } else if !is_nullable_same {
+            });
+            return changes;
+        }
+
+        // From here on, the column is referred to by its new name
+        if !column_name_same {
+            changes.push(SchemaOp::RenameColumn {
+                table: new_table,
+                old_column: self,
+                column: new,
+            });
+        }
+
+        if type_changed {
+            changes.push(SchemaOp::AlterColumnType {
+                table: new_table,
+                old_column: self,
+                column: new,
+            });
+        }
+
+        if !is_nullable_same {
             if new.is_nullable && !self.is_nullable {
                 // drop NOT NULL constraint
                 changes.push(SchemaOp::UnsetNotNull {
-                    table: self_table,
-                    column: self,
+                    table: new_table,
+                    column: new,
                 })
             } else {
                 // add NOT NULL constraint
                 changes.push(SchemaOp::SetNotNull {
-                    table: self_table,
-                    column: self,
+                    table: new_table,
+                    column: new,
                 })
             }
         } else if !default_value_same {
@@ -325,6 +430,7 @@ impl ColumnSpec {
             match relation {
                 Some(ManyToOne {
                     foreign_pk_column_id,
+                    on_delete,
                     ..
                 }) => {
                     let foreign_pk_column = foreign_pk_column_id.get_column(database);
@@ -336,6 +442,9 @@ impl ColumnSpec {
                         foreign_pk_type: Box::new(ColumnTypeSpec::from_physical(
                             foreign_pk_column.typ.clone(),
                         )),
+                        on_delete,
+                        // Relations refer only to tables with a single primary key column
+                        composite_key_columns: vec![],
                     }
                 }
                 None => ColumnTypeSpec::from_physical(column.typ),
@@ -350,24 +459,13 @@ impl ColumnSpec {
             is_nullable: column.is_nullable,
             unique_constraints: column.unique_constraints,
             default_value: column.default_value,
+            renamed_from: column.renamed_from,
         }
     }
 
-    fn differs_only_in_reference_column(&self, new: &Self) -> bool {
-        match (&self.typ, &new.typ) {
-            (ColumnTypeSpec::ColumnReference { .. }, ColumnTypeSpec::ColumnReference { .. }) => {
-                (self.typ != new.typ) && {
-                    Self {
-                        typ: ColumnTypeSpec::Int { bits: IntBits::_16 },
-                        ..self.clone()
-                    } == Self {
-                        typ: ColumnTypeSpec::Int { bits: IntBits::_16 },
-                        ..new.clone()
-                    }
-                }
-            }
-            _ => false,
-        }
+    /// The foreign key constraint for a column that references another table
+    pub fn foreign_key(&self, table_name: &PhysicalTableName) -> Option<ForeignKeySpec> {
+        self.typ.foreign_key(table_name, &self.name)
     }
 }
 
@@ -523,6 +621,16 @@ impl ColumnTypeSpec {
                 precision: *precision,
                 scale: *scale,
             },
+            ColumnTypeSpec::Enum { enum_name, .. } => PhysicalColumnType::Enum {
+                enum_name: enum_name.clone(),
+            },
+            ColumnTypeSpec::TsVector {
+                language,
+                source_column,
+            } => PhysicalColumnType::TsVector {
+                language: language.clone(),
+                source_column: source_column.clone(),
+            },
         }
     }
 
@@ -547,14 +655,14 @@ impl ColumnTypeSpec {
                 .to_owned(),
             ),
 
-            ColumnTypeSpec::Numeric { precision, scale } => ("Numeric".to_string(), {
+            ColumnTypeSpec::Numeric { precision, scale } => ("Decimal".to_string(), {
                 let precision_part = precision
-                    .map(|p| format!("@precision({p})"))
+                    .map(|p| format!(" @precision({p})"))
                     .unwrap_or_default();
 
-                let scale_part = scale.map(|s| format!("@scale({s})")).unwrap_or_default();
+                let scale_part = scale.map(|s| format!(" @scale({s})")).unwrap_or_default();
 
-                format!(" {precision_part} {scale_part}")
+                format!("{precision_part}{scale_part}")
             }),
 
             ColumnTypeSpec::String { max_length } => (
@@ -596,16 +704,220 @@ impl ColumnTypeSpec {
             ColumnTypeSpec::Json => ("Json".to_string(), "".to_string()),
             ColumnTypeSpec::Blob => ("Blob".to_string(), "".to_string()),
             ColumnTypeSpec::Uuid => ("Uuid".to_string(), "".to_string()),
-            ColumnTypeSpec::Vector { size } => ("Vector".to_string(), format!("@size({size})",)),
+            ColumnTypeSpec::Vector { size } => ("Vector".to_string(), format!(" @size({size})")),
 
             ColumnTypeSpec::Array { typ } => {
                 let (data_type, annotations) = typ.to_model();
-                (format!("[{data_type}]"), annotations)
+                (format!("Array<{data_type}>"), annotations)
             }
 
             ColumnTypeSpec::ColumnReference {
                 foreign_table_name, ..
             } => (foreign_table_name.name.clone(), "".to_string()),
+
+            // Enum types are named in snake_case in the database (`order_status`), but are
+            // declared in PascalCase in the model (`OrderStatus`)
+            ColumnTypeSpec::Enum { enum_name, .. } => (
+                enum_name
+                    .split('_')
+                    .map(|part| {
+                        let mut chars = part.chars();
+                        match chars.next() {
+                            Some(first) => first.to_uppercase().chain(chars).collect(),
+                            None => String::new(),
+                        }
+                    })
+                    .collect(),
+                "".to_string(),
+            ),
+
+            // Generated columns aren't a part of the model (the source field is marked
+            // `@searchable` instead), so this is only a placeholder
+            ColumnTypeSpec::TsVector { language, .. } => {
+                ("String".to_string(), format!(" // tsvector ({language})"))
+            }
+        }
+    }
+
+    /// Parse the generation expression of a text-search column such as
+    /// `to_tsvector('english'::regconfig, COALESCE(title, ''::text))`
+    pub fn from_text_search_expression(expr: &str) -> Option<ColumnTypeSpec> {
+        let re = Regex::new(
+            r#"^to_tsvector\('(?P<language>\w+)'::regconfig,\s*COALESCE\(\(?"?(?P<column>[^"():]+)"?\)?(::[\w ]+)?,"#,
+        )
+        .unwrap();
+
+        re.captures(expr).map(|captures| ColumnTypeSpec::TsVector {
+            language: captures["language"].to_string(),
+            source_column: captures["column"].to_string(),
+        })
+    }
+
+    /// The foreign key constraint for a column of this type. A foreign key over multiple columns
+    /// (to a table with a composite primary key) belongs to its first column, so the other columns
+    /// have none.
+    pub fn foreign_key(
+        &self,
+        table_name: &PhysicalTableName,
+        column_name: &str,
+    ) -> Option<ForeignKeySpec> {
+        match self {
+            ColumnTypeSpec::ColumnReference {
+                foreign_table_name,
+                foreign_pk_column_name,
+                on_delete,
+                composite_key_columns,
+                ..
+            } => {
+                let (column_names, foreign_pk_column_names) = match composite_key_columns.first() {
+                    None => (
+                        vec![column_name.to_string()],
+                        vec![foreign_pk_column_name.clone()],
+                    ),
+                    Some((first_column_name, _)) if first_column_name == column_name => {
+                        composite_key_columns.iter().cloned().unzip()
+                    }
+                    Some(_) => return None,
+                };
+
+                Some(ForeignKeySpec {
+                    constraint_name: ForeignKeySpec::constraint_name(table_name, column_name),
+                    column_names,
+                    foreign_table_name: foreign_table_name.clone(),
+                    foreign_pk_column_names,
+                    on_delete: *on_delete,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// The type of the values stored in the column (for a foreign key, the referenced column's type)
+    pub fn underlying_type(&self) -> &ColumnTypeSpec {
+        match self {
+            Self::ColumnReference {
+                foreign_pk_type, ..
+            } => foreign_pk_type.underlying_type(),
+            _ => self,
+        }
+    }
+
+    /// Can a column of this type be altered in place to the `new` type (using `ALTER COLUMN ... TYPE`)?
+    ///
+    /// Returns `None` if the column needs to be recreated instead, `Some(true)` if every existing
+    /// value converts without loss (for example, `INT` to `BIGINT` or `VARCHAR(50)` to
+    /// `VARCHAR(200)`), and `Some(false)` if the conversion may fail or lose data (for example,
+    /// `TEXT` to `INT` or `BIGINT` to `INT`).
+    pub fn alter_type_safety(&self, new: &Self) -> Option<bool> {
+        fn at_least<T: PartialOrd>(old: &Option<T>, new: &Option<T>) -> bool {
+            // No limit (`None`) is the widest possible
+            match (old, new) {
+                (_, None) => true,
+                (None, Some(_)) => false,
+                (Some(old), Some(new)) => new >= old,
+            }
+        }
+
+        fn int_bits(bits: &IntBits) -> usize {
+            match bits {
+                IntBits::_16 => 16,
+                IntBits::_32 => 32,
+                IntBits::_64 => 64,
+            }
+        }
+
+        fn float_bits(bits: &FloatBits) -> usize {
+            match bits {
+                FloatBits::_24 => 24,
+                FloatBits::_53 => 53,
+            }
+        }
+
+        match (self, new) {
+            (Self::Array { typ: old_typ }, Self::Array { typ: new_typ }) => {
+                old_typ.alter_type_safety(new_typ)
+            }
+            (Self::Array { .. }, _) | (_, Self::Array { .. }) => None,
+
+            // A foreign key column converts just like its underlying type
+            (Self::ColumnReference { .. }, _) | (_, Self::ColumnReference { .. }) => self
+                .underlying_type()
+                .alter_type_safety(new.underlying_type()),
+
+            // Vectors cannot be converted meaningfully
+            (Self::Vector { .. }, _) | (_, Self::Vector { .. }) => None,
+            (Self::Blob, _) | (_, Self::Blob) => None,
+            // Generated columns must be recreated to change their expression
+            (Self::TsVector { .. }, _) | (_, Self::TsVector { .. }) => None,
+
+            (Self::Int { bits: old_bits }, Self::Int { bits: new_bits }) => {
+                Some(int_bits(new_bits) >= int_bits(old_bits))
+            }
+            (Self::Int { bits }, Self::Float { bits: float }) => {
+                Some(int_bits(bits) < float_bits(float))
+            }
+            (Self::Int { .. }, Self::Numeric { precision, .. }) => Some(precision.is_none()),
+            (Self::Float { bits: old_bits }, Self::Float { bits: new_bits }) => {
+                Some(float_bits(new_bits) >= float_bits(old_bits))
+            }
+            (
+                Self::Numeric {
+                    precision: old_precision,
+                    scale: old_scale,
+                },
+                Self::Numeric {
+                    precision: new_precision,
+                    scale: new_scale,
+                },
+            ) => Some(
+                new_precision.is_none()
+                    || (at_least(old_precision, new_precision)
+                        && at_least(&old_scale.or(Some(0)), &new_scale.or(Some(0)))),
+            ),
+            (Self::Int { .. } | Self::Float { .. } | Self::Numeric { .. }, Self::Int { .. })
+            | (
+                Self::Float { .. } | Self::Numeric { .. },
+                Self::Float { .. } | Self::Numeric { .. },
+            ) => Some(false),
+
+            (
+                Self::String {
+                    max_length: old_max_length,
+                },
+                Self::String {
+                    max_length: new_max_length,
+                },
+            ) => Some(at_least(old_max_length, new_max_length)),
+            // Every value can be represented as text (but may not fit a shorter `VARCHAR`)
+            (_, Self::String { max_length }) => Some(max_length.is_none()),
+            // Parsing text may fail
+            (Self::String { .. }, _) => Some(false),
+
+            (
+                Self::Timestamp {
+                    timezone: old_timezone,
+                    precision: old_precision,
+                },
+                Self::Timestamp {
+                    timezone: new_timezone,
+                    precision: new_precision,
+                },
+            ) => Some(old_timezone == new_timezone && at_least(old_precision, new_precision)),
+            (
+                Self::Time {
+                    precision: old_precision,
+                },
+                Self::Time {
+                    precision: new_precision,
+                },
+            ) => Some(at_least(old_precision, new_precision)),
+            (Self::Date, Self::Timestamp { .. }) => Some(true),
+            (Self::Timestamp { .. }, Self::Date | Self::Time { .. }) => Some(false),
+
+            // Converted through text, so fails if a value doesn't exist in the new enum
+            (Self::Enum { .. }, Self::Enum { .. }) => Some(false),
+
+            _ => None,
         }
     }
 
@@ -749,6 +1061,26 @@ impl ColumnTypeSpec {
                 post_statements: vec![],
             },
 
+            Self::Enum {
+                enum_name,
+                enum_schema,
+            } => SchemaStatement {
+                statement: enum_sql_name(enum_name, enum_schema.as_deref()),
+                pre_statements: vec![],
+                post_statements: vec![],
+            },
+
+            Self::TsVector {
+                language,
+                source_column,
+            } => SchemaStatement {
+                statement: format!(
+                    "tsvector GENERATED ALWAYS AS (to_tsvector('{language}', COALESCE(\"{source_column}\", ''))) STORED"
+                ),
+                pre_statements: vec![],
+                post_statements: vec![],
+            },
+
             Self::Array { typ } => {
                 // 'unwrap' nested arrays all the way to the underlying primitive type
 
@@ -775,32 +1107,16 @@ impl ColumnTypeSpec {
             }
 
             Self::ColumnReference {
-                foreign_table_name,
-                foreign_pk_type,
-                ..
+                foreign_pk_type, ..
             } => {
                 let mut sql_statement =
                     foreign_pk_type.to_sql(table_spec, column_name, is_auto_increment);
 
-                let foreign_table_str = match &foreign_table_name.schema {
-                    Some(schema_name) => {
-                        format!("\"{}\".\"{}\"", schema_name, foreign_table_name.name)
-                    }
-                    None => format!("\"{}\"", foreign_table_name.name),
-                };
-
-                let constraint_name = format!(
-                    "{}_{}_fk",
-                    table_spec.name.fully_qualified_name_with_sep("_"),
-                    column_name
-                );
-
-                let foreign_constraint = format!(
-                    r#"ALTER TABLE {} ADD CONSTRAINT "{constraint_name}" FOREIGN KEY ("{column_name}") REFERENCES {foreign_table_str};"#,
-                    table_spec.sql_name()
-                );
-
-                sql_statement.post_statements.push(foreign_constraint);
+                if let Some(foreign_key) = self.foreign_key(&table_spec.name, column_name) {
+                    sql_statement
+                        .post_statements
+                        .push(foreign_key.creation_sql(&table_spec.name));
+                }
                 sql_statement
             }
         }
@@ -831,6 +1147,18 @@ impl ColumnTypeSpec {
             PhysicalColumnType::Numeric { precision, scale } => {
                 ColumnTypeSpec::Numeric { precision, scale }
             }
+            // The enums of a model are in the `public` schema
+            PhysicalColumnType::Enum { enum_name } => ColumnTypeSpec::Enum {
+                enum_name,
+                enum_schema: None,
+            },
+            PhysicalColumnType::TsVector {
+                language,
+                source_column,
+            } => ColumnTypeSpec::TsVector {
+                language,
+                source_column,
+            },
         }
     }
 }
@@ -13,7 +13,8 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 use crate::{
-    database_error::DatabaseError, sql::connect::database_client::DatabaseClient, PhysicalTableName,
+    database_error::DatabaseError, sql::connect::database_client::DatabaseClient,
+    PhysicalTableName, ReferentialAction,
 };
 
 pub(super) struct PrimaryKeyConstraint {
@@ -23,9 +24,11 @@ pub(super) struct PrimaryKeyConstraint {
 
 pub(super) struct ForeignKeyConstraint {
     pub(super) _constraint_name: String,
-    pub(super) self_columns: HashSet<String>,
+    /// The columns in this table (in the order of the corresponding `foreign_columns`)
+    pub(super) self_columns: Vec<String>,
     pub(super) foreign_table: PhysicalTableName,
-    pub(super) foreign_columns: HashSet<String>,
+    pub(super) foreign_columns: Vec<String>,
+    pub(super) on_delete: ReferentialAction,
 }
 
 #[derive(Debug)]
@@ -38,13 +41,21 @@ pub(super) struct Constraints {
     pub(super) primary_key: PrimaryKeyConstraint,
     pub(super) foreign_constraints: Vec<ForeignKeyConstraint>,
     pub(super) uniques: Vec<UniqueConstraint>,
+    pub(super) checks: Vec<CheckSpec>,
 }
 
 lazy_static! {
     static ref PRIMARY_KEY_RE: Regex = Regex::new(r"PRIMARY KEY \(([^)]+)\)").unwrap();
     static ref FOREIGN_KEY_RE: Regex =
         Regex::new(r"FOREIGN KEY \(([^)]+)\) REFERENCES ([^\(]+)\(([^)]+)\)").unwrap();
+    static ref ON_DELETE_RE: Regex =
+        Regex::new(r"ON DELETE (NO ACTION|RESTRICT|CASCADE|SET NULL|SET DEFAULT)").unwrap();
     static ref UNIQUE_RE: Regex = Regex::new(r"UNIQUE \(([^)]+)\)").unwrap();
+    static ref CHECK_RE: Regex = Regex::new(r"^CHECK \((.*)\)( NO INHERIT)?( NOT VALID)?$").unwrap();
+    // Casts that Postgres adds when normalizing an expression (such as `'-5'::integer`)
+    static ref CAST_RE: Regex =
+        Regex::new(r"::[a-z_]+( varying| precision| with time zone| without time zone)?(\[\])?")
+            .unwrap();
     static ref LIST_RE: Regex = Regex::new(r"(\w+)").unwrap();
 }
 
@@ -82,7 +93,7 @@ impl Constraints {
             .filter(|(contype, _, _)| *contype == 'p')
             .map(|(_, conname, condef)| {
                 let matches = PRIMARY_KEY_RE.captures_iter(condef).next().unwrap();
-                let columns = Self::parse_column_list(&matches[1]);
+                let columns = Self::parse_column_list(&matches[1]).into_iter().collect();
                 PrimaryKeyConstraint {
                     _constraint_name: conname.to_string(),
                     columns,
@@ -98,17 +109,21 @@ impl Constraints {
             .map(|(_, conname, condef)| {
                 let matches = FOREIGN_KEY_RE.captures_iter(condef).next().unwrap();
                 let self_columns = Self::parse_column_list(&matches[1]); // name of the column
-                let foreign_table = matches[2].to_owned(); // name of the table the column refers to
+                let foreign_table = Self::parse_table_name(&matches[2]); // name of the table the column refers to
                 let foreign_columns = Self::parse_column_list(&matches[3]); // name of the column in the referenced table
 
+                // The default action (`NO ACTION`) is omitted from the definition
+                let on_delete = ON_DELETE_RE
+                    .captures(condef)
+                    .and_then(|matches| ReferentialAction::from_sql(&matches[1]))
+                    .unwrap_or_default();
+
                 ForeignKeyConstraint {
                     _constraint_name: conname.to_string(),
                     self_columns,
-                    foreign_table: PhysicalTableName {
-                        name: foreign_table,
-                        schema: None,
-                    },
+                    foreign_table,
                     foreign_columns,
+                    on_delete,
                 }
             })
             .collect::<Vec<_>>();
@@ -118,7 +133,7 @@ impl Constraints {
             .filter(|(contype, _, _)| *contype == 'u')
             .map(|(_, conname, condef)| {
                 let matches = UNIQUE_RE.captures_iter(condef).next().unwrap();
-                let columns = Self::parse_column_list(&matches[1]);
+                let columns = Self::parse_column_list(&matches[1]).into_iter().collect();
                 UniqueConstraint {
                     constraint_name: conname.to_string(),
                     columns,
@@ -126,14 +141,41 @@ impl Constraints {
             })
             .collect();
 
+        let checks = constraints
+            .iter()
+            .filter(|(contype, _, _)| *contype == 'c')
+            .flat_map(|(_, conname, condef)| {
+                CHECK_RE.captures(condef).map(|matches| CheckSpec {
+                    name: conname.to_string(),
+                    expr: matches[1].to_string(),
+                })
+            })
+            .collect();
+
         Ok(Constraints {
             primary_key,
             foreign_constraints,
             uniques,
+            checks,
         })
     }
 
-    fn parse_column_list(column_list: &str) -> HashSet<String> {
+    /// Parse a (possibly schema-qualified and quoted) table name such as `"log"."events"`
+    fn parse_table_name(table_name: &str) -> PhysicalTableName {
+        let parts = table_name
+            .trim()
+            .split('.')
+            .map(|part| part.trim_matches('"'))
+            .collect::<Vec<_>>();
+
+        match parts.as_slice() {
+            [schema, name] if *schema != "public" => PhysicalTableName::new(*name, Some(*schema)),
+            [.., name] => PhysicalTableName::new(*name, None),
+            [] => unreachable!("Splitting a string always produces at least one part"),
+        }
+    }
+
+    fn parse_column_list(column_list: &str) -> Vec<String> {
         // Basically just split the string on commas and remove the quotes (the regex takes care of the quotes)
         LIST_RE
             .captures_iter(column_list)
@@ -147,6 +189,118 @@ impl Constraints {
     }
 }
 
+/// A foreign key constraint on a column (or columns, for a reference to a table with a composite
+/// primary key) that refers to the primary key of another table
+#[derive(Debug, Clone)]
+pub struct ForeignKeySpec {
+    pub constraint_name: String,
+    /// The columns in this table (in the order of the corresponding `foreign_pk_column_names`)
+    pub column_names: Vec<String>,
+    pub foreign_table_name: PhysicalTableName,
+    pub foreign_pk_column_names: Vec<String>,
+    pub on_delete: ReferentialAction,
+}
+
+impl ForeignKeySpec {
+    pub(super) fn constraint_name(table_name: &PhysicalTableName, column_name: &str) -> String {
+        format!(
+            "{}_{column_name}_fk",
+            table_name.fully_qualified_name_with_sep("_")
+        )
+    }
+
+    /// Do the two constraints enforce the same reference? (the constraint name doesn't matter, since
+    /// it follows the table and column names, which may have been renamed)
+    pub fn same_reference(&self, other: &Self) -> bool {
+        self.foreign_table_name == other.foreign_table_name
+            && self.foreign_pk_column_names == other.foreign_pk_column_names
+            && self.on_delete == other.on_delete
+    }
+
+    pub(super) fn creation_sql(&self, table_name: &PhysicalTableName) -> String {
+        let on_delete = match self.on_delete {
+            ReferentialAction::NoAction => "".to_string(),
+            action => format!(" ON DELETE {}", action.to_sql()),
+        };
+
+        format!(
+            r#"ALTER TABLE {} ADD CONSTRAINT "{}" FOREIGN KEY ({}) REFERENCES {}{on_delete};"#,
+            table_name.sql_name(),
+            self.constraint_name,
+            self.column_list(),
+            self.foreign_table_name.sql_name(),
+        )
+    }
+
+    /// The (quoted) comma separated list of the columns in this table
+    pub(super) fn column_list(&self) -> String {
+        self.column_names
+            .iter()
+            .map(|column_name| format!("\"{column_name}\""))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub(super) fn deletion_sql(&self, table_name: &PhysicalTableName) -> String {
+        format!(
+            r#"ALTER TABLE {} DROP CONSTRAINT "{}";"#,
+            table_name.sql_name(),
+            self.constraint_name
+        )
+    }
+}
+
+/// A named `CHECK` constraint
+#[derive(Debug, Clone)]
+pub struct CheckSpec {
+    pub name: String,
+    pub expr: String,
+}
+
+impl CheckSpec {
+    /// Do the two constraints check the same expression?
+    ///
+    /// Postgres normalizes the expression of a constraint (for example, `"age" >= 0` becomes
+    /// `(age >= 0)`), so we compare expressions after removing quotes, parentheses, casts, and
+    /// whitespace.
+    pub fn same_expr(&self, other: &Self) -> bool {
+        fn normalize(expr: &str) -> String {
+            CAST_RE
+                .replace_all(&expr.to_lowercase(), "")
+                .chars()
+                .filter(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | '"' | '\''))
+                .collect()
+        }
+
+        normalize(&self.expr) == normalize(&other.expr)
+    }
+
+    /// Does the expression refer to the column?
+    pub(super) fn involves_column(&self, column_name: &str) -> bool {
+        self.expr
+            .replace('"', "")
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .any(|word| word == column_name)
+    }
+
+    pub(super) fn creation_sql(&self, table_name: &PhysicalTableName) -> String {
+        format!(
+            r#"ALTER TABLE {} ADD CONSTRAINT "{}" CHECK ({});"#,
+            table_name.sql_name(),
+            self.name,
+            self.expr
+        )
+    }
+
+    pub(super) fn deletion_sql(&self, table_name: &PhysicalTableName) -> String {
+        format!(
+            r#"ALTER TABLE {} DROP CONSTRAINT "{}";"#,
+            table_name.sql_name(),
+            self.name
+        )
+    }
+}
+
 /// Returns a comma separated list of the items in the set, sorted alphabetically If `quote_name` is
 /// true, then the items will be quoted Useful when generating SQL unique constraints, where columns
 /// provided will be a set but we need to generate a stable string to compare against the existing
@@ -12,11 +12,13 @@ use std::collections::HashSet;
 use crate::{
     database_error::DatabaseError, schema::column_spec::ColumnSpec,
     sql::connect::database_client::DatabaseClient, Database, ManyToOne, PhysicalColumn,
-    PhysicalIndex, PhysicalTable, PhysicalTableName, TableId,
+    PhysicalEnum, PhysicalIndex, PhysicalTable, PhysicalTableName, TableId,
 };
 
 use super::{
     column_spec::ColumnTypeSpec,
+    constraint::CheckSpec,
+    enum_spec::EnumSpec,
     function_spec::FunctionSpec,
     index_spec::IndexSpec,
     issue::WithIssues,
@@ -28,11 +30,20 @@ use super::{
 pub struct DatabaseSpec {
     pub tables: Vec<TableSpec>,
     pub functions: Vec<FunctionSpec>,
+    pub enums: Vec<EnumSpec>,
 }
 
 impl DatabaseSpec {
     pub fn new(tables: Vec<TableSpec>, functions: Vec<FunctionSpec>) -> Self {
-        Self { tables, functions }
+        Self {
+            tables,
+            functions,
+            enums: vec![],
+        }
+    }
+
+    pub fn with_enums(self, enums: Vec<EnumSpec>) -> Self {
+        Self { enums, ..self }
     }
 
     /// Non-public schemas required by this database spec.
@@ -54,6 +65,15 @@ impl DatabaseSpec {
     pub fn to_database(self) -> Database {
         let mut database = Database::default();
 
+        database.enums = self
+            .enums
+            .into_iter()
+            .map(|enum_spec| PhysicalEnum {
+                name: enum_spec.name,
+                variants: enum_spec.variants,
+            })
+            .collect();
+
         // Step 1: Create tables (without columns)
         let tables: Vec<(TableId, Vec<ColumnSpec>, Vec<IndexSpec>)> = self
             .tables
@@ -78,6 +98,7 @@ impl DatabaseSpec {
                     unique_constraints: column_spec.unique_constraints.to_owned(),
                     default_value: column_spec.default_value.to_owned(),
                     update_sync: false, // There is no good way to know from the database spec if a column should be updated on sync
+                    renamed_from: column_spec.renamed_from.to_owned(),
                 })
                 .collect();
 
@@ -113,8 +134,17 @@ impl DatabaseSpec {
                         ColumnTypeSpec::ColumnReference {
                             foreign_table_name,
                             foreign_pk_column_name,
+                            on_delete,
+                            composite_key_columns,
                             ..
                         } => {
+                            // Relations (like the types in a model) refer only to tables
+                            // with a single primary key column, so the columns of a composite
+                            // foreign key remain plain columns
+                            if !composite_key_columns.is_empty() {
+                                return None;
+                            }
+
                             let foreign_table_id =
                                 database.get_table_id(foreign_table_name).unwrap();
                             let foreign_pk_column_id = database
@@ -140,6 +170,7 @@ impl DatabaseSpec {
                                 self_column_id,
                                 foreign_pk_column_id,
                                 foreign_table_alias,
+                                on_delete: *on_delete,
                             })
                         }
                         _ => None,
@@ -160,14 +191,19 @@ impl DatabaseSpec {
             .tables()
             .into_iter()
             .map(|(_, table)| {
-                let (trigger_specs, function_specs) = match Self::update_trigger(table) {
-                    Some((trigger, function)) => (vec![trigger], vec![function]),
-                    None => (vec![], vec![]),
-                };
+                let mut trigger_specs = vec![];
 
-                all_function_specs.extend(function_specs);
+                if let Some((trigger, function)) = Self::update_trigger(table) {
+                    trigger_specs.push(trigger);
+                    all_function_specs.push(function);
+                }
 
-                TableSpec::new(
+                if let Some((triggers, function)) = Self::notify_triggers(table) {
+                    trigger_specs.extend(triggers);
+                    all_function_specs.push(function);
+                }
+
+                let table_spec = TableSpec::new(
                     table.name.clone(),
                     table
                         .columns
@@ -186,11 +222,30 @@ impl DatabaseSpec {
                         })
                         .collect(),
                     trigger_specs,
-                )
+                );
+
+                TableSpec {
+                    checks: table
+                        .checks
+                        .iter()
+                        .map(|check| CheckSpec {
+                            name: check.name.clone(),
+                            expr: check.expr.clone(),
+                        })
+                        .collect(),
+                    renamed_from: table.renamed_from.clone(),
+                    ..table_spec
+                }
             })
             .collect();
 
-        DatabaseSpec::new(tables, all_function_specs)
+        let enums = database
+            .enums
+            .iter()
+            .map(|e| EnumSpec::new(e.name.clone(), e.variants.clone()))
+            .collect();
+
+        DatabaseSpec::new(tables, all_function_specs).with_enums(enums)
     }
 
     /// Creates a new schema specification from an SQL database.
@@ -241,8 +296,18 @@ impl DatabaseSpec {
         } = FunctionSpec::from_live_db(client).await?;
         issues.extend(functions_issues);
 
+        let WithIssues {
+            value: enums,
+            issues: enums_issues,
+        } = EnumSpec::from_live_db(client).await?;
+        issues.extend(enums_issues);
+
         Ok(WithIssues {
-            value: DatabaseSpec { tables, functions },
+            value: DatabaseSpec {
+                tables,
+                functions,
+                enums,
+            },
             issues,
         })
     }
@@ -293,4 +358,52 @@ impl DatabaseSpec {
             None
         }
     }
+
+    /// Triggers (and the function they execute) to broadcast changes to a table with `NOTIFY`.
+    ///
+    /// Only insertions and updates are broadcast (with the primary key of the affected row), since
+    /// listeners need to query the row (and check access to it) before delivering it.
+    fn notify_triggers(table: &PhysicalTable) -> Option<(Vec<TriggerSpec>, FunctionSpec)> {
+        if !table.notify_changes {
+            return None;
+        }
+
+        // Changes are broadcast only for tables with a single primary key column (which the
+        // builder enforces for types with subscriptions)
+        let [pk_column] = table.get_pk_physical_columns()[..] else {
+            return None;
+        };
+        let table_name = table.name.fully_qualified_name_with_sep("_");
+
+        let function_name = format!("exograph_notify_{table_name}");
+        let function_body = format!(
+            "BEGIN PERFORM pg_notify('{channel}', json_build_object('op', TG_OP, 'pk', NEW.\"{pk}\")::text); RETURN NULL; END;",
+            channel = table.change_notification_channel(),
+            pk = pk_column.name
+        );
+
+        let triggers = [
+            ("insert", TriggerEvent::Insert),
+            ("update", TriggerEvent::Update),
+        ]
+        .into_iter()
+        .map(|(event_name, event)| TriggerSpec {
+            name: format!("exograph_on_{event_name}_notify_{table_name}"),
+            function: function_name.clone(),
+            timing: TriggerTiming::After,
+            orientation: TriggerOrientation::Row,
+            event,
+            table: table.name.clone(),
+        })
+        .collect();
+
+        Some((
+            triggers,
+            FunctionSpec {
+                name: function_name,
+                body: function_body,
+                language: "plpgsql".into(),
+            },
+        ))
+    }
 }
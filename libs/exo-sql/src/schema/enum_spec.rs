@@ -0,0 +1,180 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::{database_error::DatabaseError, sql::connect::database_client::DatabaseClient};
+
+use super::{
+    column_spec::{ColumnSpec, ColumnTypeSpec},
+    issue::WithIssues,
+    op::SchemaOp,
+    table_spec::TableSpec,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumSpec {
+    pub name: String,
+    /// The schema of the enum (`None` for the `public` schema)
+    pub schema: Option<String>,
+    pub variants: Vec<String>,
+}
+
+const ENUMS_QUERY: &str = r#"
+SELECT n.nspname, t.typname, e.enumlabel FROM pg_type t
+    JOIN pg_enum e ON e.enumtypid = t.oid
+    JOIN pg_namespace n ON n.oid = t.typnamespace
+    WHERE n.nspname NOT IN ('pg_catalog', 'information_schema')
+    ORDER BY n.nspname, t.typname, e.enumsortorder
+"#;
+
+impl EnumSpec {
+    pub fn new(name: impl Into<String>, variants: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            schema: None,
+            variants,
+        }
+    }
+
+    pub fn with_schema(self, schema: Option<String>) -> Self {
+        Self { schema, ..self }
+    }
+
+    /// The name qualified with the schema (if not `public`), which identifies the enum across
+    /// schemas
+    pub fn qualified_name(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{schema}.{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The name to refer to the enum in SQL statements, such as `"auth"."role"`
+    pub fn sql_name(&self) -> String {
+        enum_sql_name(&self.name, self.schema.as_deref())
+    }
+
+    /// Does the column type refer to this enum?
+    fn is_type_of(&self, typ: &ColumnTypeSpec) -> bool {
+        matches!(
+            typ,
+            ColumnTypeSpec::Enum { enum_name, enum_schema }
+                if enum_name == &self.name && enum_schema == &self.schema
+        )
+    }
+
+    pub async fn from_live_db(
+        client: &DatabaseClient,
+    ) -> Result<WithIssues<Vec<EnumSpec>>, DatabaseError> {
+        let mut enums: Vec<EnumSpec> = vec![];
+
+        // The query is ordered by the schema and enum name, so all values of an enum are
+        // consecutive
+        for row in client.query(ENUMS_QUERY, &[]).await?.iter() {
+            let raw_schema: String = row.get("nspname");
+            let schema = (raw_schema != "public").then_some(raw_schema);
+            let name: String = row.get("typname");
+            let variant: String = row.get("enumlabel");
+
+            match enums.last_mut() {
+                Some(last) if last.name == name && last.schema == schema => {
+                    last.variants.push(variant)
+                }
+                _ => enums.push(EnumSpec::new(name, vec![variant]).with_schema(schema)),
+            }
+        }
+
+        Ok(WithIssues {
+            value: enums,
+            issues: vec![],
+        })
+    }
+
+    /// Compute the operations to change this enum into the `new` one.
+    ///
+    /// Postgres can only add values to an existing enum. So if the new enum has all the existing
+    /// values in the same order, we add the missing ones in place. Otherwise, we recreate the enum
+    /// and convert the `tables` columns using it to the new type.
+    pub fn diff<'a>(&'a self, new: &'a Self, tables: &'a [TableSpec]) -> Vec<SchemaOp<'a>> {
+        if self.variants == new.variants {
+            return vec![];
+        }
+
+        let retained: Vec<_> = new
+            .variants
+            .iter()
+            .filter(|variant| self.variants.contains(variant))
+            .collect();
+
+        if retained.len() == self.variants.len() && retained.iter().eq(self.variants.iter()) {
+            new.variants
+                .iter()
+                .enumerate()
+                .filter(|(_, variant)| !self.variants.contains(variant))
+                .map(|(index, variant)| SchemaOp::AddEnumValue {
+                    enum_spec: new,
+                    value: variant,
+                    // Place the value before the next existing value (or at the end, if there is none)
+                    before: new.variants[index + 1..]
+                        .iter()
+                        .find(|next| self.variants.contains(next))
+                        .map(|next| next.as_str()),
+                })
+                .collect()
+        } else {
+            vec![SchemaOp::RecreateEnum {
+                old: self,
+                new,
+                columns: self.dependent_columns(tables),
+            }]
+        }
+    }
+
+    /// Columns in `tables` whose type is this enum (or an array of it)
+    fn dependent_columns<'a>(
+        &self,
+        tables: &'a [TableSpec],
+    ) -> Vec<(&'a TableSpec, &'a ColumnSpec)> {
+        tables
+            .iter()
+            .flat_map(|table| table.columns.iter().map(move |column| (table, column)))
+            .filter(|(_, column)| {
+                let mut typ = &column.typ;
+                while let ColumnTypeSpec::Array { typ: inner } = typ {
+                    typ = inner;
+                }
+                self.is_type_of(typ)
+            })
+            .collect()
+    }
+
+    pub fn creation_sql(&self) -> String {
+        format!(
+            "CREATE TYPE {} AS ENUM ({});",
+            self.sql_name(),
+            self.variants
+                .iter()
+                .map(|variant| format!("'{variant}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    pub fn deletion_sql(&self) -> String {
+        format!("DROP TYPE {};", self.sql_name())
+    }
+}
+
+/// The name to refer to an enum in SQL statements, such as `"auth"."role"` (or `"role"` for an enum
+/// in the `public` schema)
+pub fn enum_sql_name(name: &str, schema: Option<&str>) -> String {
+    match schema {
+        Some(schema) => format!("\"{schema}\".\"{name}\""),
+        None => format!("\"{name}\""),
+    }
+}
@@ -31,6 +31,8 @@ pub enum IndexKind {
         distance_function: VectorDistanceFunction,
         params: Option<HNWSParams>,
     },
+    /// A GIN index (used for text-search columns)
+    Gin,
     #[default]
     DatabaseDefault,
 }
@@ -127,6 +129,7 @@ impl IndexSpec {
                                     params: None,
                                 })
                             }
+                            "gin" => Ok(IndexKind::Gin),
                             _ => Ok(IndexKind::default()),
                         }?;
                     Ok(Some(IndexSpec::new(
@@ -206,6 +209,7 @@ impl IndexSpec {
                     .unwrap_or_else(|| "".to_string());
                 format!("USING hnsw ({columns_str} {distance_function_str}){params_str}")
             }
+            IndexKind::Gin => format!("USING gin ({columns_str})"),
             _ => format!("({columns_str})"),
         };
 
@@ -8,7 +8,9 @@
 // by the Apache License, Version 2.0.
 
 pub mod column_spec;
+pub mod constraint;
 pub mod database_spec;
+pub mod enum_spec;
 pub mod function_spec;
 pub mod index_spec;
 pub mod issue;
@@ -18,5 +20,4 @@ pub mod table_spec;
 pub mod test_helper;
 pub mod trigger_spec;
 
-mod constraint;
 mod statement;
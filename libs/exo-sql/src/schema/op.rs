@@ -9,11 +9,18 @@
 
 use std::collections::HashSet;
 
-use crate::schema::{constraint::sorted_comma_list, index_spec::IndexSpec};
+use crate::schema::{
+    constraint::{sorted_comma_list, CheckSpec, ForeignKeySpec},
+    index_spec::IndexSpec,
+};
 
 use super::{
-    column_spec::ColumnSpec, function_spec::FunctionSpec, statement::SchemaStatement,
-    table_spec::TableSpec, trigger_spec::TriggerSpec,
+    column_spec::{ColumnSpec, ColumnTypeSpec},
+    enum_spec::{enum_sql_name, EnumSpec},
+    function_spec::FunctionSpec,
+    statement::SchemaStatement,
+    table_spec::TableSpec,
+    trigger_spec::TriggerSpec,
 };
 
 /// An execution unit of SQL, representing an operation that can create or destroy resources.
@@ -26,12 +33,35 @@ pub enum SchemaOp<'a> {
         schema: String,
     },
 
+    CreateEnum {
+        enum_spec: &'a EnumSpec,
+    },
+    DeleteEnum {
+        enum_spec: &'a EnumSpec,
+    },
+    AddEnumValue {
+        enum_spec: &'a EnumSpec,
+        value: &'a str,
+        before: Option<&'a str>,
+    },
+    /// Replace an enum whose values cannot be changed in place (a value was removed or the values
+    /// were reordered), converting the columns that use it.
+    RecreateEnum {
+        old: &'a EnumSpec,
+        new: &'a EnumSpec,
+        columns: Vec<(&'a TableSpec, &'a ColumnSpec)>,
+    },
+
     CreateTable {
         table: &'a TableSpec,
     },
     DeleteTable {
         table: &'a TableSpec,
     },
+    RenameTable {
+        old_table: &'a TableSpec,
+        table: &'a TableSpec,
+    },
 
     CreateColumn {
         table: &'a TableSpec,
@@ -41,6 +71,16 @@ pub enum SchemaOp<'a> {
         table: &'a TableSpec,
         column: &'a ColumnSpec,
     },
+    RenameColumn {
+        table: &'a TableSpec,
+        old_column: &'a ColumnSpec,
+        column: &'a ColumnSpec,
+    },
+    AlterColumnType {
+        table: &'a TableSpec,
+        old_column: &'a ColumnSpec,
+        column: &'a ColumnSpec,
+    },
     CreateIndex {
         table: &'a TableSpec,
         index: &'a IndexSpec,
@@ -76,6 +116,24 @@ pub enum SchemaOp<'a> {
         constraint: String,
     },
 
+    CreateForeignKey {
+        table: &'a TableSpec,
+        foreign_key: ForeignKeySpec,
+    },
+    DeleteForeignKey {
+        table: &'a TableSpec,
+        foreign_key: ForeignKeySpec,
+    },
+
+    CreateCheckConstraint {
+        table: &'a TableSpec,
+        check: &'a CheckSpec,
+    },
+    DeleteCheckConstraint {
+        table: &'a TableSpec,
+        check: &'a CheckSpec,
+    },
+
     SetNotNull {
         table: &'a TableSpec,
         column: &'a ColumnSpec,
@@ -115,8 +173,74 @@ impl SchemaOp<'_> {
                 ..Default::default()
             },
 
+            SchemaOp::CreateEnum { enum_spec } => SchemaStatement {
+                statement: enum_spec.creation_sql(),
+                ..Default::default()
+            },
+            SchemaOp::DeleteEnum { enum_spec } => SchemaStatement {
+                statement: enum_spec.deletion_sql(),
+                ..Default::default()
+            },
+            SchemaOp::AddEnumValue {
+                enum_spec,
+                value,
+                before,
+            } => SchemaStatement {
+                statement: match before {
+                    Some(before) => format!(
+                        "ALTER TYPE {} ADD VALUE '{value}' BEFORE '{before}';",
+                        enum_spec.sql_name()
+                    ),
+                    None => format!("ALTER TYPE {} ADD VALUE '{value}';", enum_spec.sql_name()),
+                },
+                ..Default::default()
+            },
+            SchemaOp::RecreateEnum { old, new, columns } => {
+                let old_name = format!("{}_old", old.name);
+                let old_sql_name = enum_sql_name(&old_name, old.schema.as_deref());
+
+                // Convert through text, so that each value maps to the same-named value in the new
+                // enum (this fails if a value in use has been removed)
+                let alter_columns = columns.iter().map(|(table, column)| {
+                    let array_suffix = if matches!(column.typ, ColumnTypeSpec::Array { .. }) {
+                        "[]"
+                    } else {
+                        ""
+                    };
+
+                    format!(
+                        "ALTER TABLE {table} ALTER COLUMN \"{column}\" TYPE {enum_name}{array_suffix} USING \"{column}\"::text{array_suffix}::{enum_name}{array_suffix};",
+                        table = table.sql_name(),
+                        column = column.name,
+                        enum_name = new.sql_name(),
+                    )
+                });
+
+                // Switch to the new enum ahead of other operations (which may create columns
+                // that use it), and drop the old one once no column refers to it
+                SchemaStatement {
+                    statement: format!("DROP TYPE {old_sql_name};"),
+                    pre_statements: [
+                        format!("ALTER TYPE {} RENAME TO \"{old_name}\";", old.sql_name()),
+                        new.creation_sql(),
+                    ]
+                    .into_iter()
+                    .chain(alter_columns)
+                    .collect(),
+                    post_statements: vec![],
+                }
+            }
+
             SchemaOp::CreateTable { table } => table.creation_sql(),
             SchemaOp::DeleteTable { table } => table.deletion_sql(),
+            SchemaOp::RenameTable { old_table, table } => SchemaStatement {
+                statement: format!(
+                    "ALTER TABLE {} RENAME TO \"{}\";",
+                    old_table.sql_name(),
+                    table.name.name
+                ),
+                ..Default::default()
+            },
 
             SchemaOp::CreateColumn { table, column } => {
                 let column_stmt = column.to_sql(table);
@@ -139,6 +263,51 @@ impl SchemaOp<'_> {
                 ),
                 ..Default::default()
             },
+            SchemaOp::RenameColumn {
+                table,
+                old_column,
+                column,
+            } => SchemaStatement {
+                statement: format!(
+                    "ALTER TABLE {} RENAME COLUMN \"{}\" TO \"{}\";",
+                    table.sql_name(),
+                    old_column.name,
+                    column.name
+                ),
+                ..Default::default()
+            },
+            SchemaOp::AlterColumnType {
+                table,
+                old_column,
+                column,
+            } => {
+                let new_type = column.typ.to_sql(table, &column.name, false).statement;
+
+                // Strings are converted with the implicit cast (which, unlike an explicit cast,
+                // fails instead of truncating values that are too long). Enums don't have a
+                // direct cast to other types, so we go through text.
+                let using = match (&old_column.typ, &column.typ) {
+                    (ColumnTypeSpec::String { .. }, ColumnTypeSpec::String { .. }) => String::new(),
+                    (ColumnTypeSpec::Enum { .. }, _) => {
+                        format!(" USING \"{}\"::text::{new_type}", column.name)
+                    }
+                    (ColumnTypeSpec::Array { typ }, _)
+                        if matches!(**typ, ColumnTypeSpec::Enum { .. }) =>
+                    {
+                        format!(" USING \"{}\"::text[]::{new_type}", column.name)
+                    }
+                    _ => format!(" USING \"{}\"::{new_type}", column.name),
+                };
+
+                SchemaStatement {
+                    statement: format!(
+                        "ALTER TABLE {} ALTER COLUMN \"{}\" TYPE {new_type}{using};",
+                        table.sql_name(),
+                        column.name,
+                    ),
+                    ..Default::default()
+                }
+            }
 
             SchemaOp::CreateIndex { table, index } => SchemaStatement {
                 statement: index.creation_sql(&table.name),
@@ -202,6 +371,26 @@ impl SchemaOp<'_> {
                 ..Default::default()
             },
 
+            // Add the constraint after all tables are in place (the referenced table may be created
+            // or renamed by the same migration)
+            SchemaOp::CreateForeignKey { table, foreign_key } => SchemaStatement {
+                post_statements: vec![foreign_key.creation_sql(&table.name)],
+                ..Default::default()
+            },
+            SchemaOp::DeleteForeignKey { table, foreign_key } => SchemaStatement {
+                statement: foreign_key.deletion_sql(&table.name),
+                ..Default::default()
+            },
+
+            SchemaOp::CreateCheckConstraint { table, check } => SchemaStatement {
+                statement: check.creation_sql(&table.name),
+                ..Default::default()
+            },
+            SchemaOp::DeleteCheckConstraint { table, check } => SchemaStatement {
+                statement: check.deletion_sql(&table.name),
+                ..Default::default()
+            },
+
             SchemaOp::SetNotNull { table, column } => SchemaStatement {
                 statement: format!(
                     "ALTER TABLE {} ALTER COLUMN \"{}\" SET NOT NULL;",
@@ -252,8 +441,14 @@ impl SchemaOp<'_> {
             SchemaOp::CreateSchema { schema } => Some(format!("The schema `{schema}` exists in the model, but does not exist in the database.")),
             SchemaOp::DeleteSchema { .. } => None, // An extra schema in the database is not a problem
 
+            SchemaOp::CreateEnum { enum_spec } => Some(format!("The enum `{}` exists in the model, but does not exist in the database.", enum_spec.qualified_name())),
+            SchemaOp::DeleteEnum { .. } => None, // An extra enum in the database is not a problem
+            SchemaOp::AddEnumValue { enum_spec, value, .. } => Some(format!("The value `{value}` of the enum `{}` exists in the model, but does not exist in the database.", enum_spec.qualified_name())),
+            SchemaOp::RecreateEnum { new, .. } => Some(format!("The values of the enum `{}` in the model do not match those in the database.", new.qualified_name())),
+
             SchemaOp::CreateTable { table } => Some(format!("The table `{}` exists in the model, but does not exist in the database.", table.sql_name())),
             SchemaOp::DeleteTable { .. } => None, // An extra table in the database is not a problem
+            SchemaOp::RenameTable { old_table, table } => Some(format!("The table `{}` needs to be renamed to `{}`.", old_table.sql_name(), table.sql_name())),
 
             SchemaOp::CreateColumn { table, column } => Some(format!("The column `{}` in the table `{}` exists in the model, but does not exist in the database table.", column.name, table.sql_name())),
             SchemaOp::DeleteColumn { table, column } => {
@@ -266,6 +461,8 @@ impl SchemaOp<'_> {
                     column.name, table.sql_name()))
                 }
             }
+            SchemaOp::RenameColumn { table, old_column, column } => Some(format!("The column `{}` in the table `{}` needs to be renamed to `{}`.", old_column.name, table.sql_name(), column.name)),
+            SchemaOp::AlterColumnType { table, column, .. } => Some(format!("The type of the column `{}` in the table `{}` does not match the model.", column.name, table.sql_name())),
             SchemaOp::CreateIndex { table, index } => Some(format!("The index `{}` in the table `{}` exists in the model, but does not exist in the database table.", index.name, table.sql_name())),
             SchemaOp::DeleteIndex { .. } => None, // An extra index in the database is not a problem
 
@@ -283,6 +480,22 @@ impl SchemaOp<'_> {
                 Some(format!("Extra unique constaint `{}` in table `{}` found that is not require by the model.", constraint, table.sql_name()))
             }
 
+            SchemaOp::CreateForeignKey { table, foreign_key } => {
+                Some(format!("The model requires a foreign key constraint named `{}` for the column `{}` in table `{}` that references the table `{}` with `ON DELETE {}`.", foreign_key.constraint_name, foreign_key.column_names.join(", "), table.sql_name(), foreign_key.foreign_table_name.sql_name(), foreign_key.on_delete.to_sql()))
+            },
+            SchemaOp::DeleteForeignKey { table, foreign_key } => {
+                // An extra (or mismatched) foreign key may make inserts or deletes fail even if the model allows them
+                Some(format!("The foreign key constraint `{}` for the column `{}` in table `{}` (that references the table `{}` with `ON DELETE {}`) does not match the model.", foreign_key.constraint_name, foreign_key.column_names.join(", "), table.sql_name(), foreign_key.foreign_table_name.sql_name(), foreign_key.on_delete.to_sql()))
+            },
+
+            SchemaOp::CreateCheckConstraint { table, check } => {
+                Some(format!("The model requires a check constraint named `{}` with the expression `{}` in table `{}`.", check.name, check.expr, table.sql_name()))
+            },
+            SchemaOp::DeleteCheckConstraint { table, check } => {
+                // An extra check constraint may make inserts fail even if the model allows them
+                Some(format!("The check constraint `{}` with the expression `{}` in table `{}` does not exist in the model.", check.name, check.expr, table.sql_name()))
+            },
+
             SchemaOp::SetNotNull { table, column } => {
                 Some(format!("The model requires that the column `{}` in table `{}` is not nullable. All records in the database must have a non-null value for this column before migration.", column.name, table.sql_name()))
             },
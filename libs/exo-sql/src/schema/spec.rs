@@ -9,7 +9,7 @@
 
 use std::collections::{hash_map::RandomState, hash_set::Difference};
 
-use super::{database_spec::DatabaseSpec, op::SchemaOp};
+use super::{database_spec::DatabaseSpec, op::SchemaOp, table_spec::TableSpec};
 
 pub fn diff<'a>(old: &'a DatabaseSpec, new: &'a DatabaseSpec) -> Vec<SchemaOp<'a>> {
     let mut changes = vec![];
@@ -37,12 +37,27 @@ pub fn diff<'a>(old: &'a DatabaseSpec, new: &'a DatabaseSpec) -> Vec<SchemaOp<'a
         })
     }
 
+    // enum creation and changes (before tables, whose columns may use them)
+    for new_enum in new.enums.iter() {
+        match old
+            .enums
+            .iter()
+            .find(|old_enum| old_enum.qualified_name() == new_enum.qualified_name())
+        {
+            Some(old_enum) => changes.extend(old_enum.diff(new_enum, &old.tables)),
+            None => changes.push(SchemaOp::CreateEnum {
+                enum_spec: new_enum,
+            }),
+        }
+    }
+
     for old_table in old.tables.iter() {
-        // try to find a table with the same name in the new spec
+        // try to find a table with the same name in the new spec (or one that is being renamed from it)
         match new
             .tables
             .iter()
             .find(|new_table| old_table.sql_name() == new_table.sql_name())
+            .or_else(|| renamed_table(old_table, old, new))
         {
             // table exists, compare columns
             Some(new_table) => changes.extend(old_table.diff(new_table)),
@@ -54,11 +69,11 @@ pub fn diff<'a>(old: &'a DatabaseSpec, new: &'a DatabaseSpec) -> Vec<SchemaOp<'a
 
     // try to find a table that needs to be created
     for new_table in new.tables.iter() {
-        if !old
-            .tables
-            .iter()
-            .any(|old_table| new_table.sql_name() == old_table.sql_name())
-        {
+        if !old.tables.iter().any(|old_table| {
+            new_table.sql_name() == old_table.sql_name()
+                || renamed_table(old_table, old, new)
+                    .is_some_and(|renamed| renamed.sql_name() == new_table.sql_name())
+        }) {
             // new table
             changes.push(SchemaOp::CreateTable { table: new_table })
         }
@@ -95,6 +110,19 @@ pub fn diff<'a>(old: &'a DatabaseSpec, new: &'a DatabaseSpec) -> Vec<SchemaOp<'a
         }
     }
 
+    // enum removal (after tables, whose columns may have used them)
+    for old_enum in old.enums.iter() {
+        if !new
+            .enums
+            .iter()
+            .any(|new_enum| new_enum.qualified_name() == old_enum.qualified_name())
+        {
+            changes.push(SchemaOp::DeleteEnum {
+                enum_spec: old_enum,
+            })
+        }
+    }
+
     // extension removal
     let extensions_to_drop =
         sorted_strings(old_required_extensions.difference(&new_required_extensions));
@@ -126,6 +154,33 @@ pub fn diff<'a>(old: &'a DatabaseSpec, new: &'a DatabaseSpec) -> Vec<SchemaOp<'a
     changes
 }
 
+/// Find the table in the `new` spec that is being renamed from `old_table` (through a rename hint).
+///
+/// A rename applies only when the old name is no longer in use by the new spec and the new name
+/// isn't already taken in the old spec.
+fn renamed_table<'a>(
+    old_table: &TableSpec,
+    old: &DatabaseSpec,
+    new: &'a DatabaseSpec,
+) -> Option<&'a TableSpec> {
+    let old_name_in_use = new
+        .tables
+        .iter()
+        .any(|new_table| new_table.sql_name() == old_table.sql_name());
+
+    if old_name_in_use {
+        return None;
+    }
+
+    new.tables.iter().find(|new_table| {
+        new_table.renamed_from.as_ref() == Some(&old_table.name)
+            && !old
+                .tables
+                .iter()
+                .any(|table| table.sql_name() == new_table.sql_name())
+    })
+}
+
 fn sorted_strings(strings: Difference<String, RandomState>) -> Vec<&String> {
     let mut strings: Vec<_> = strings.into_iter().collect();
     strings.sort();
@@ -11,10 +11,10 @@ use std::collections::{HashMap, HashSet};
 
 use crate::database_error::DatabaseError;
 use crate::sql::connect::database_client::DatabaseClient;
-use crate::{PhysicalTable, PhysicalTableName};
+use crate::{PhysicalCheck, PhysicalTable, PhysicalTableName};
 
 use super::column_spec::{ColumnSpec, ColumnTypeSpec};
-use super::constraint::{sorted_comma_list, Constraints};
+use super::constraint::{sorted_comma_list, CheckSpec, Constraints};
 use super::index_spec::IndexSpec;
 use super::issue::WithIssues;
 use super::op::SchemaOp;
@@ -27,6 +27,9 @@ pub struct TableSpec {
     pub columns: Vec<ColumnSpec>,
     pub indices: Vec<IndexSpec>,
     pub triggers: Vec<TriggerSpec>,
+    pub checks: Vec<CheckSpec>,
+    /// The earlier name of the table (set only for a table computed from a model with a rename hint)
+    pub renamed_from: Option<PhysicalTableName>,
 }
 
 impl TableSpec {
@@ -41,6 +44,8 @@ impl TableSpec {
             columns,
             indices,
             triggers,
+            checks: vec![],
+            renamed_from: None,
         }
     }
 
@@ -49,6 +54,20 @@ impl TableSpec {
             name: self.name.clone(),
             columns: vec![],
             indices: vec![],
+            checks: self
+                .checks
+                .iter()
+                .map(|check| PhysicalCheck {
+                    name: check.name.clone(),
+                    expr: check.expr.clone(),
+                })
+                .collect(),
+            notify_changes: self
+                .triggers
+                .iter()
+                .any(|trigger| trigger.name.starts_with("exograph_on_insert_notify_")),
+            renamed_from: self.renamed_from.clone(),
+            soft_delete_column: None,
         }
     }
 
@@ -56,6 +75,11 @@ impl TableSpec {
         self.name.sql_name()
     }
 
+    /// Does the table have a primary key over multiple columns?
+    pub fn has_composite_pk(&self) -> bool {
+        self.columns.iter().filter(|c| c.is_pk).count() > 1
+    }
+
     fn named_unique_constraints(&self) -> HashMap<&String, HashSet<String>> {
         self.columns.iter().fold(HashMap::new(), |mut map, c| {
             {
@@ -86,30 +110,43 @@ impl TableSpec {
         let mut column_type_mapping = HashMap::new();
 
         for foreign_constraint in constraints.foreign_constraints.iter() {
-            // Assumption that there is only one column in the foreign key (for now a correct assumption since we don't support composite keys)
-            let self_column_name = foreign_constraint.self_columns.iter().next().unwrap();
-            let foreign_pk_column_name = foreign_constraint.foreign_columns.iter().next().unwrap();
-
-            let mut column = ColumnSpec::from_live_db(
-                client,
-                &table_name,
-                foreign_pk_column_name,
-                true,
-                None,
-                vec![],
-            )
-            .await?;
-            issues.append(&mut column.issues);
-
-            if let Some(spec) = column.value {
-                column_type_mapping.insert(
-                    self_column_name.clone(),
-                    ColumnTypeSpec::ColumnReference {
-                        foreign_table_name: foreign_constraint.foreign_table.clone(),
-                        foreign_pk_column_name: foreign_pk_column_name.clone(),
-                        foreign_pk_type: Box::new(spec.typ),
-                    },
-                );
+            // A foreign key to a table with a composite primary key spans multiple columns
+            let column_pairs: Vec<_> = foreign_constraint
+                .self_columns
+                .iter()
+                .cloned()
+                .zip(foreign_constraint.foreign_columns.iter().cloned())
+                .collect();
+            let composite_key_columns = if column_pairs.len() > 1 {
+                column_pairs.clone()
+            } else {
+                vec![]
+            };
+
+            for (self_column_name, foreign_pk_column_name) in column_pairs {
+                let mut column = ColumnSpec::from_live_db(
+                    client,
+                    &foreign_constraint.foreign_table,
+                    &foreign_pk_column_name,
+                    true,
+                    None,
+                    vec![],
+                )
+                .await?;
+                issues.append(&mut column.issues);
+
+                if let Some(spec) = column.value {
+                    column_type_mapping.insert(
+                        self_column_name,
+                        ColumnTypeSpec::ColumnReference {
+                            foreign_table_name: foreign_constraint.foreign_table.clone(),
+                            foreign_pk_column_name,
+                            foreign_pk_type: Box::new(spec.typ),
+                            on_delete: foreign_constraint.on_delete,
+                            composite_key_columns: composite_key_columns.clone(),
+                        },
+                    );
+                }
             }
         }
 
@@ -163,6 +200,8 @@ impl TableSpec {
                 columns,
                 indices,
                 triggers,
+                checks: constraints.checks,
+                renamed_from: None,
             },
             issues,
         })
@@ -195,19 +234,46 @@ impl TableSpec {
         let new_column_map: HashMap<_, _> =
             new_columns.iter().map(|c| (c.name.clone(), c)).collect();
 
+        // A column is renamed only if its old name isn't used in the new table, and its new name
+        // isn't already taken in the existing table
+        let renamed_column = |existing_column: &ColumnSpec| {
+            new_columns.iter().find(|new_column| {
+                new_column.renamed_from.as_ref() == Some(&existing_column.name)
+                    && !new_column_map.contains_key(&existing_column.name)
+                    && !existing_column_map.contains_key(&new_column.name)
+            })
+        };
+
         let mut changes = vec![];
 
-        for existing_column in self.columns.iter() {
-            let new_column = new_column_map.get(&existing_column.name);
+        let is_renamed = self.sql_name() != new.sql_name();
+
+        if is_renamed {
+            // Triggers refer to the table by its old name, so remove them before renaming the table
+            changes.extend(self.trigger_deletions(new));
+            changes.push(SchemaOp::RenameTable {
+                old_table: self,
+                table: new,
+            });
+        }
+
+        let matching_new_column = |existing_column: &ColumnSpec| {
+            new_column_map
+                .get(&existing_column.name)
+                .copied()
+                .or_else(|| renamed_column(existing_column))
+        };
 
-            match new_column {
+        // Since the table may have been renamed, we refer to it through the new spec from here on
+        for existing_column in self.columns.iter() {
+            match matching_new_column(existing_column) {
                 Some(new_column) => {
                     changes.extend(existing_column.diff(new_column, self, new));
                 }
                 None => {
                     // column was removed
                     changes.push(SchemaOp::DeleteColumn {
-                        table: self,
+                        table: new,
                         column: existing_column,
                     });
                 }
@@ -216,8 +282,12 @@ impl TableSpec {
 
         for new_column in new.columns.iter() {
             let existing_column = existing_column_map.get(&new_column.name);
+            let is_renamed_column = existing_columns.iter().any(|existing_column| {
+                renamed_column(existing_column)
+                    .is_some_and(|renamed| renamed.name == new_column.name)
+            });
 
-            if existing_column.is_none() {
+            if existing_column.is_none() && !is_renamed_column {
                 // new column
                 changes.push(SchemaOp::CreateColumn {
                     table: new,
@@ -226,13 +296,66 @@ impl TableSpec {
             }
         }
 
+        // Dropping a column (including to recreate it with a different type) also drops any index
+        // on it, so such an index must not be dropped again (and must be recreated if it is still
+        // needed)
+        let dropped_columns: HashSet<String> = changes
+            .iter()
+            .filter_map(|change| match change {
+                SchemaOp::DeleteColumn { column, .. } => Some(column.name.clone()),
+                _ => None,
+            })
+            .collect();
+        let is_dropped_with_column =
+            |index: &IndexSpec| index.columns.iter().any(|c| dropped_columns.contains(c));
+
+        // Similarly, a dropped column takes its foreign key with it (and a created column comes
+        // with its foreign key), so only the retained columns need their foreign keys reconciled
+        for existing_column in self.columns.iter() {
+            if dropped_columns.contains(&existing_column.name) {
+                continue;
+            }
+
+            if let Some(new_column) = matching_new_column(existing_column) {
+                // The constraint is named after the table and column at the time of its creation
+                let existing_foreign_key = existing_column.foreign_key(&self.name);
+                let new_foreign_key = new_column.foreign_key(&new.name);
+
+                match (existing_foreign_key, new_foreign_key) {
+                    (Some(existing_foreign_key), Some(new_foreign_key))
+                        if existing_foreign_key.same_reference(&new_foreign_key) => {}
+                    (existing_foreign_key, new_foreign_key) => {
+                        if let Some(foreign_key) = existing_foreign_key {
+                            changes.push(SchemaOp::DeleteForeignKey {
+                                table: new,
+                                foreign_key,
+                            });
+                        }
+                        if let Some(foreign_key) = new_foreign_key {
+                            changes.push(SchemaOp::CreateForeignKey {
+                                table: new,
+                                foreign_key,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
         for existing_index in self.indices.iter() {
             let new_index = new.indices.iter().find(|i| i.name == existing_index.name);
 
             match new_index {
+                Some(new_index) if is_dropped_with_column(existing_index) => {
+                    changes.push(SchemaOp::CreateIndex {
+                        table: new,
+                        index: new_index,
+                    });
+                }
                 Some(new_index) => {
                     changes.extend(existing_index.diff(new_index, self, new));
                 }
+                None if is_dropped_with_column(existing_index) => {}
                 None => {
                     changes.push(SchemaOp::DeleteIndex {
                         table: self,
@@ -295,13 +418,53 @@ impl TableSpec {
             }
         }
 
-        for trigger in self.triggers.iter() {
-            if !new.triggers.iter().any(|t| t.name == trigger.name) {
-                // trigger deletion
-                changes.push(SchemaOp::DeleteTrigger { trigger });
+        // A dropped column takes any check involving it along (just like its indices)
+        for existing_check in self.checks.iter() {
+            let new_check = new.checks.iter().find(|c| c.name == existing_check.name);
+            let is_dropped_with_column = dropped_columns
+                .iter()
+                .any(|column| existing_check.involves_column(column));
+
+            match new_check {
+                Some(new_check) if is_dropped_with_column => {
+                    changes.push(SchemaOp::CreateCheckConstraint {
+                        table: new,
+                        check: new_check,
+                    });
+                }
+                Some(new_check) if existing_check.same_expr(new_check) => {}
+                None if is_dropped_with_column => {}
+                _ => {
+                    changes.push(SchemaOp::DeleteCheckConstraint {
+                        table: new,
+                        check: existing_check,
+                    });
+                    if let Some(new_check) = new_check {
+                        changes.push(SchemaOp::CreateCheckConstraint {
+                            table: new,
+                            check: new_check,
+                        });
+                    }
+                }
             }
         }
 
+        for new_check in new.checks.iter() {
+            let existing_check = self.checks.iter().find(|c| c.name == new_check.name);
+
+            // A check that is being replaced has been taken care of above
+            if existing_check.is_none() {
+                changes.push(SchemaOp::CreateCheckConstraint {
+                    table: new,
+                    check: new_check,
+                });
+            }
+        }
+
+        if !is_renamed {
+            changes.extend(self.trigger_deletions(new));
+        }
+
         for new_trigger in new.triggers.iter() {
             if !self.triggers.iter().any(|t| t.name == new_trigger.name) {
                 // new trigger
@@ -314,10 +477,18 @@ impl TableSpec {
         changes
     }
 
+    fn trigger_deletions<'a>(&'a self, new: &'a Self) -> Vec<SchemaOp<'a>> {
+        self.triggers
+            .iter()
+            .filter(|trigger| !new.triggers.iter().any(|t| t.name == trigger.name))
+            .map(|trigger| SchemaOp::DeleteTrigger { trigger })
+            .collect()
+    }
+
     /// Converts the table specification to SQL statements.
     pub(super) fn creation_sql(&self) -> SchemaStatement {
         let mut post_statements = Vec::new();
-        let column_stmts: String = self
+        let mut column_stmts: Vec<String> = self
             .columns
             .iter()
             .map(|c| {
@@ -325,8 +496,20 @@ impl TableSpec {
                 post_statements.append(&mut s.post_statements);
                 s.statement
             })
-            .collect::<Vec<_>>()
-            .join(",\n\t");
+            .collect();
+
+        if self.has_composite_pk() {
+            let pk_columns = self
+                .columns
+                .iter()
+                .filter(|c| c.is_pk)
+                .map(|c| format!("\"{}\"", c.name))
+                .collect::<Vec<_>>()
+                .join(", ");
+            column_stmts.push(format!("PRIMARY KEY ({pk_columns})"));
+        }
+
+        let column_stmts = column_stmts.join(",\n\t");
 
         let table_name = self.sql_name();
 
@@ -338,6 +521,10 @@ impl TableSpec {
             ));
         }
 
+        for check in self.checks.iter() {
+            post_statements.push(check.creation_sql(&self.name));
+        }
+
         for index in self.indices.iter() {
             post_statements.push(index.creation_sql(&self.name));
         }
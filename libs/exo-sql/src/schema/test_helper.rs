@@ -15,6 +15,7 @@ pub fn pk_column(name: impl Into<String>) -> ColumnSpec {
         is_nullable: false,
         unique_constraints: vec![],
         default_value: None,
+        renamed_from: None,
     }
 }
 
@@ -34,12 +35,15 @@ pub fn pk_reference_column(
             foreign_pk_type: Box::new(ColumnTypeSpec::Int {
                 bits: crate::IntBits::_16,
             }),
+            on_delete: crate::ReferentialAction::NoAction,
+            composite_key_columns: vec![],
         },
         is_pk: false,
         is_auto_increment: false,
         is_nullable: false,
         unique_constraints: vec![],
         default_value: None,
+        renamed_from: None,
     }
 }
 
@@ -54,6 +58,7 @@ pub fn int_column(name: impl Into<String>) -> ColumnSpec {
         is_nullable: false,
         unique_constraints: vec![],
         default_value: None,
+        renamed_from: None,
     }
 }
 
@@ -66,6 +71,7 @@ pub fn string_column(name: impl Into<String>) -> ColumnSpec {
         is_nullable: false,
         unique_constraints: vec![],
         default_value: None,
+        renamed_from: None,
     }
 }
 
@@ -78,5 +84,22 @@ pub fn json_column(name: impl Into<String>) -> ColumnSpec {
         is_nullable: false,
         unique_constraints: vec![],
         default_value: None,
+        renamed_from: None,
+    }
+}
+
+pub fn nullable_timestamp_column(name: impl Into<String>) -> ColumnSpec {
+    ColumnSpec {
+        name: name.into(),
+        typ: ColumnTypeSpec::Timestamp {
+            timezone: true,
+            precision: None,
+        },
+        is_pk: false,
+        is_auto_increment: false,
+        is_nullable: true,
+        unique_constraints: vec![],
+        default_value: None,
+        renamed_from: None,
     }
 }
@@ -56,6 +56,11 @@ pub enum Column {
     Null,
     /// A function applied to a column. For example, `count(id)` or `lower(first_name)`.
     Function(Function),
+    /// A column with an alias, so that an outer select may refer to it. For example,
+    /// `json_build_object(...) AS "group"`.
+    Aliased { column: Box<Column>, alias: String },
+    /// A reference to an aliased column of a sub-select. For example, `"todos_groups"."group"`.
+    SubSelectColumn { table_alias: String, alias: String },
 }
 
 #[derive(Debug, PartialEq)]
@@ -92,7 +97,12 @@ impl ExpressionBuilder for Column {
             Column::Function(function) => {
                 function.build(database, builder);
             }
-            Column::Param(value) => builder.push_param(value.param()),
+            Column::Param(value) => {
+                builder.push_param(value.param());
+                if let Some(cast_type) = value.cast_type() {
+                    builder.push_cast(cast_type);
+                }
+            }
             Column::ArrayParam { param, wrapper } => {
                 let wrapper_string = match wrapper {
                     ArrayParamWrapper::Any => "ANY",
@@ -100,12 +110,26 @@ impl ExpressionBuilder for Column {
                     ArrayParamWrapper::None => "",
                 };
 
-                if wrapper_string.is_empty() {
+                let push_param = |builder: &mut SQLBuilder| {
                     builder.push_param(param.param());
+                    if let Some(cast_type) = param.cast_type() {
+                        builder.push_cast(cast_type);
+                    }
+                };
+
+                if builder.is_sqlite() && !wrapper_string.is_empty() {
+                    // SQLite has no arrays, so the parameter is passed as a JSON array and the
+                    // predicate uses `IN`/`NOT IN` in place of `= ANY`/`<> ALL` (see
+                    // `ConcretePredicate::build`)
+                    builder.push_str("(SELECT value FROM json_each(");
+                    push_param(builder);
+                    builder.push_str("))");
+                } else if wrapper_string.is_empty() {
+                    push_param(builder);
                 } else {
                     builder.push_str(wrapper_string);
                     builder.push('(');
-                    builder.push_param(param.param());
+                    push_param(builder);
                     builder.push(')');
                 }
             }
@@ -133,6 +157,14 @@ impl ExpressionBuilder for Column {
             Column::Null => {
                 builder.push_str("NULL");
             }
+            Column::Aliased { column, alias } => {
+                column.build(database, builder);
+                builder.push_str(" AS ");
+                builder.push_identifier(alias);
+            }
+            Column::SubSelectColumn { table_alias, alias } => {
+                builder.push_column_with_table_alias(alias, table_alias);
+            }
         }
     }
 }
@@ -14,6 +14,8 @@ use tokio_postgres::Config;
 use crate::database_error::DatabaseError;
 
 use super::database_client::DatabaseClient;
+#[cfg(feature = "postgres-url")]
+use super::notification_listener::NotificationSender;
 
 pub enum DatabaseCreation {
     #[cfg(feature = "postgres-url")]
@@ -40,42 +42,82 @@ impl DatabaseCreation {
 
     #[cfg(feature = "postgres-url")]
     async fn from_url(url: &str) -> Result<DatabaseClient, DatabaseError> {
-        use std::str::FromStr;
+        connect_url(url, None).await.map(DatabaseClient::Direct)
+    }
+}
 
-        use crate::sql::connect::ssl_config::SslConfig;
+/// Connect to the database at the given URL.
+///
+/// If `notification_sender` is provided, notifications received on the connection (see
+/// [NotificationListener](super::notification_listener::NotificationListener)) are forwarded to it.
+#[cfg(feature = "postgres-url")]
+pub(super) async fn connect_url(
+    url: &str,
+    notification_sender: Option<NotificationSender>,
+) -> Result<tokio_postgres::Client, DatabaseError> {
+    use std::str::FromStr;
 
-        let (url, ssl_config) = SslConfig::from_url(url)?;
+    use crate::sql::connect::ssl_config::SslConfig;
 
-        let config = Config::from_str(&url).map_err(|e| {
-            DatabaseError::Delegate(e)
-                .with_context("Failed to parse PostgreSQL connection string".into())
-        })?;
+    let (url, ssl_config) = SslConfig::from_url(url)?;
 
-        match ssl_config {
-            Some(ssl_config) => {
-                let (config, tls) = ssl_config.updated_config(config)?;
+    let config = Config::from_str(&url).map_err(|e| {
+        DatabaseError::Delegate(e)
+            .with_context("Failed to parse PostgreSQL connection string".into())
+    })?;
 
-                let (client, connection) = config.connect(tls).await?;
+    match ssl_config {
+        Some(ssl_config) => {
+            let (config, tls) = ssl_config.updated_config(config)?;
 
-                tokio::spawn(async move {
-                    if let Err(e) = connection.await {
-                        tracing::error!("connection error: {}", e);
-                    }
-                });
+            let (client, connection) = config.connect(tls).await?;
+            tokio::spawn(drive_connection(connection, notification_sender));
 
-                Ok(DatabaseClient::Direct(client))
-            }
-            None => {
-                let tls = tokio_postgres::NoTls;
-                let (client, connection) = config.connect(tls).await?;
+            Ok(client)
+        }
+        None => {
+            let tls = tokio_postgres::NoTls;
+            let (client, connection) = config.connect(tls).await?;
+            tokio::spawn(drive_connection(connection, notification_sender));
 
-                tokio::spawn(async move {
-                    if let Err(e) = connection.await {
-                        tracing::error!("connection error: {}", e);
-                    }
-                });
+            Ok(client)
+        }
+    }
+}
 
-                Ok(DatabaseClient::Direct(client))
+/// Drive the connection to completion (logging any error), forwarding notifications if requested.
+#[cfg(feature = "postgres-url")]
+async fn drive_connection<S, T>(
+    mut connection: tokio_postgres::Connection<S, T>,
+    notification_sender: Option<NotificationSender>,
+) where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    use futures::StreamExt;
+    use tokio_postgres::AsyncMessage;
+
+    use super::notification_listener::Notification;
+
+    let messages = futures::stream::poll_fn(|cx| connection.poll_message(cx));
+    futures::pin_mut!(messages);
+
+    while let Some(message) = messages.next().await {
+        match message {
+            Ok(AsyncMessage::Notification(notification)) => {
+                if let Some(sender) = &notification_sender {
+                    // Sending fails only if there are no receivers, in which case there is no one
+                    // interested in the notification
+                    let _ = sender.send(Notification {
+                        channel: notification.channel().to_string(),
+                        payload: notification.payload().to_string(),
+                    });
+                }
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("connection error: {}", e);
+                break;
             }
         }
     }
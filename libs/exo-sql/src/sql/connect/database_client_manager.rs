@@ -14,6 +14,17 @@ use super::{creation::DatabaseCreation, database_client::DatabaseClient};
 #[cfg(feature = "pool")]
 use super::database_pool::DatabasePool;
 
+/// A snapshot of the connections held by a pool
+#[derive(Debug, Clone, Copy)]
+pub struct DatabasePoolStatus {
+    /// The maximum number of connections the pool may hold
+    pub max_size: usize,
+    /// The number of connections currently in the pool
+    pub size: usize,
+    /// The number of idle connections in the pool
+    pub available: usize,
+}
+
 pub enum DatabaseClientManager {
     #[cfg(feature = "pool")]
     Pooled(DatabasePool),
@@ -74,6 +85,15 @@ impl DatabaseClientManager {
             DatabaseClientManager::Direct(creation) => creation.get_client().await,
         }
     }
+
+    /// The status of the connection pool (`None` if connections are not pooled)
+    pub fn pool_status(&self) -> Option<DatabasePoolStatus> {
+        match self {
+            #[cfg(feature = "pool")]
+            DatabaseClientManager::Pooled(pool) => Some(pool.status()),
+            DatabaseClientManager::Direct(_) => None,
+        }
+    }
 }
 
 #[cfg(feature = "postgres-url")]
@@ -17,7 +17,10 @@ use tokio_postgres::Config;
 
 use crate::database_error::DatabaseError;
 
-use super::{creation::DatabaseCreation, database_client::DatabaseClient};
+use super::{
+    creation::DatabaseCreation, database_client::DatabaseClient,
+    database_client_manager::DatabasePoolStatus,
+};
 
 pub struct DatabasePool {
     pool: Pool,
@@ -41,6 +44,16 @@ impl DatabasePool {
         Ok(DatabaseClient::Pooled(self.pool.get().await?))
     }
 
+    pub fn status(&self) -> DatabasePoolStatus {
+        let status = self.pool.status();
+
+        DatabasePoolStatus {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available,
+        }
+    }
+
     #[cfg(feature = "postgres-url")]
     async fn from_db_url(url: &str, pool_size: Option<usize>) -> Result<Self, DatabaseError> {
         Self::from_helper(pool_size, url).await
@@ -11,4 +11,7 @@ pub mod creation;
 pub mod database_client;
 pub mod database_client_manager;
 pub mod database_pool;
+pub mod notification_listener;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_client_manager;
 pub mod ssl_config;
@@ -0,0 +1,114 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+#![cfg(feature = "postgres-url")]
+
+use std::collections::HashSet;
+
+use futures::{stream::BoxStream, StreamExt};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    Mutex,
+};
+
+use crate::database_error::DatabaseError;
+
+use super::creation::connect_url;
+
+/// Number of notifications buffered for each listener before the slowest listeners start missing
+/// notifications
+const NOTIFICATION_BUFFER_SIZE: usize = 1024;
+
+pub(super) type NotificationSender = broadcast::Sender<Notification>;
+
+/// A notification sent through `NOTIFY` (or `pg_notify`)
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
+}
+
+/// Listens to Postgres notifications using `LISTEN`.
+///
+/// Notifications need a dedicated connection (a pooled connection may be handed to someone else
+/// at any time), so the listener maintains its own connection, which is shared by all listeners
+/// and established lazily. If the connection is lost, all current notification streams end and
+/// the next call to [NotificationListener::listen] reconnects.
+pub struct NotificationListener {
+    url: String,
+    state: Mutex<Option<ListenerState>>,
+}
+
+struct ListenerState {
+    client: tokio_postgres::Client,
+    // We hold on to a receiver (instead of the sender) so that the sender is owned only by the
+    // task driving the connection. This way, receivers get closed when the connection ends.
+    receiver: broadcast::Receiver<Notification>,
+    channels: HashSet<String>,
+}
+
+impl NotificationListener {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Listen to notifications on the given channel
+    pub async fn listen(
+        &self,
+        channel: &str,
+    ) -> Result<BoxStream<'static, Notification>, DatabaseError> {
+        let mut state = self.state.lock().await;
+
+        let connected = matches!(state.as_ref(), Some(existing) if !existing.client.is_closed());
+
+        if !connected {
+            let (sender, receiver) = broadcast::channel(NOTIFICATION_BUFFER_SIZE);
+            let client = connect_url(&self.url, Some(sender)).await?;
+
+            *state = Some(ListenerState {
+                client,
+                receiver,
+                channels: HashSet::new(),
+            });
+        }
+
+        let state = state.as_mut().unwrap();
+
+        if !state.channels.contains(channel) {
+            state
+                .client
+                .batch_execute(&format!("LISTEN \"{channel}\""))
+                .await?;
+            state.channels.insert(channel.to_string());
+        }
+
+        let channel = channel.to_string();
+
+        Ok(
+            futures::stream::unfold(state.receiver.resubscribe(), |mut receiver| async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(notification) => return Some((notification, receiver)),
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::warn!(
+                                "Notification listener lagged; skipped {skipped} notifications"
+                            );
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            })
+            .filter(move |notification| futures::future::ready(notification.channel == channel))
+            .boxed(),
+        )
+    }
+}
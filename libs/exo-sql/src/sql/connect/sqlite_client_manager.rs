@@ -0,0 +1,66 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use std::{path::Path, sync::Mutex};
+
+use rusqlite::Connection;
+
+use crate::database_error::DatabaseError;
+
+/// A connection to a SQLite database (a file or an in-memory database).
+///
+/// SQLite allows only one writer at a time, so we keep a single connection and execute one
+/// operation at a time.
+pub struct SqliteClientManager {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteClientManager {
+    /// Open the database file at the given path (creating it if it doesn't exist)
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DatabaseError> {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// Open a new in-memory database (dropped along with the manager)
+    pub fn open_in_memory() -> Result<Self, DatabaseError> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    /// Open the database specified by a URL of the form `sqlite://<path>` or `sqlite::memory:`
+    pub fn from_url(url: &str) -> Result<Self, DatabaseError> {
+        match url.strip_prefix("sqlite:") {
+            Some(":memory:") => Self::open_in_memory(),
+            Some(path) => Self::open(path.strip_prefix("//").unwrap_or(path)),
+            None => Err(DatabaseError::Config(format!(
+                "Invalid SQLite URL '{url}' (expected 'sqlite://<path>' or 'sqlite::memory:')"
+            ))),
+        }
+    }
+
+    fn new(connection: Connection) -> Result<Self, DatabaseError> {
+        // SQLite doesn't enforce foreign key constraints unless asked to
+        connection.execute_batch("PRAGMA foreign_keys = ON")?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Run `work` with exclusive access to the connection
+    pub fn with_connection<R>(&self, work: impl FnOnce(&mut Connection) -> R) -> R {
+        // A panic while holding the lock doesn't leave the connection in an invalid state (an
+        // uncommitted transaction is rolled back when dropped)
+        let mut connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        work(&mut connection)
+    }
+}
@@ -9,7 +9,10 @@
 
 use std::fmt::{Debug, Formatter};
 
-use crate::{ColumnId, ManyToOne, PhysicalColumn, PhysicalTable, PhysicalTableName};
+use crate::{
+    ColumnId, ManyToOne, PhysicalColumn, PhysicalColumnType, PhysicalEnum, PhysicalTable,
+    PhysicalTableName,
+};
 
 use serde::{Deserialize, Serialize};
 use typed_generational_arena::{Arena, IgnoreGeneration, Index};
@@ -21,6 +24,7 @@ pub type TableId = Index<PhysicalTable, usize, IgnoreGeneration>;
 pub struct Database {
     tables: SerializableSlab<PhysicalTable>,
     pub relations: Vec<ManyToOne>,
+    pub enums: Vec<PhysicalEnum>,
 }
 
 impl Database {
@@ -56,11 +60,20 @@ impl Database {
         })
     }
 
-    pub fn get_pk_column_id(&self, table_id: TableId) -> Option<ColumnId> {
+    /// The primary key columns of the table (more than one for a composite primary key), in the
+    /// order of their declaration
+    pub fn get_pk_column_ids(&self, table_id: TableId) -> Vec<ColumnId> {
         let table = self.get_table(table_id);
         table
-            .get_pk_column_index()
+            .get_pk_column_indices()
             .map(|column_index| new_column_id(table_id, column_index))
+            .collect()
+    }
+
+    /// The column marking soft-deleted rows of the table (if the table uses soft deletion)
+    pub fn get_soft_delete_column_id(&self, table_id: TableId) -> Option<ColumnId> {
+        let column_name = self.get_table(table_id).soft_delete_column.as_ref()?;
+        self.get_column_id(table_id, column_name)
     }
 
     pub fn get_column_id(&self, table_id: TableId, column_name: &str) -> Option<ColumnId> {
@@ -69,6 +82,32 @@ impl Database {
             .map(|column_index| new_column_id(table_id, column_index))
     }
 
+    pub fn get_enum(&self, name: &str) -> Option<&PhysicalEnum> {
+        self.enums.iter().find(|e| e.name == name)
+    }
+
+    /// Get the generated `tsvector` column (and its language) computed from the given column, if
+    /// the column is searchable
+    pub fn get_text_search_column(&self, column_id: ColumnId) -> Option<(ColumnId, &str)> {
+        let table = self.get_table(column_id.table_id);
+        let source_column_name = &table.columns[column_id.column_index].name;
+
+        table
+            .columns
+            .iter()
+            .enumerate()
+            .find_map(|(column_index, column)| match &column.typ {
+                PhysicalColumnType::TsVector {
+                    language,
+                    source_column,
+                } if source_column == source_column_name => Some((
+                    new_column_id(column_id.table_id, column_index),
+                    language.as_str(),
+                )),
+                _ => None,
+            })
+    }
+
     pub fn get_column_mut(&mut self, column_id: ColumnId) -> &mut PhysicalColumn {
         let table = self.get_table_mut(column_id.table_id);
         &mut table.columns[column_id.column_index]
@@ -87,6 +126,7 @@ impl Default for Database {
         Database {
             tables: SerializableSlab::new(),
             relations: vec![],
+            enums: vec![],
         }
     }
 }
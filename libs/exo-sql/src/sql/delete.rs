@@ -9,7 +9,7 @@
 
 use maybe_owned::MaybeOwned;
 
-use crate::{Database, OneToMany, SQLParamContainer};
+use crate::{database_error::DatabaseError, Database, OneToMany};
 
 use super::{
     column::Column,
@@ -76,7 +76,7 @@ impl<'a> TemplateDelete<'a> {
         &'a self,
         prev_step_id: TransactionStepId,
         transaction_context: &TransactionContext,
-    ) -> Vec<Delete<'a>> {
+    ) -> Result<Vec<Delete<'a>>, DatabaseError> {
         let TemplateDelete {
             table,
             predicate,
@@ -89,22 +89,18 @@ impl<'a> TemplateDelete<'a> {
         // Go over all the rows in the previous step and create a concrete update for each row.
         (0..rows)
             .map(|row_index| {
-                let relation_predicate = ConcretePredicate::Eq(
-                    Column::Physical {
-                        column_id: nesting_relation.foreign_column_id,
-                        table_alias: None,
-                    },
-                    Column::Param(SQLParamContainer::from_sql_value(
-                        transaction_context.resolve_value(prev_step_id, row_index, 0),
-                    )),
-                );
+                let relation_predicate = transaction_context.relation_predicate(
+                    nesting_relation,
+                    prev_step_id,
+                    row_index,
+                )?;
 
-                Delete {
+                Ok(Delete {
                     table,
                     predicate: predicate.into(),
                     additional_predicate: Some(relation_predicate),
                     returning: returning.iter().map(MaybeOwned::Borrowed).collect(),
-                }
+                })
             })
             .collect()
     }
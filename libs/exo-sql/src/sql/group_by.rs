@@ -9,21 +9,32 @@
 
 use crate::{ColumnId, Database};
 
-use super::{ExpressionBuilder, SQLBuilder};
+use super::{predicate::ConcretePredicate, ExpressionBuilder, SQLBuilder};
 
 /// A group by clause
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct GroupBy(pub Vec<ColumnId>);
+#[derive(Debug, PartialEq)]
+pub struct GroupBy {
+    /// The columns to group by
+    pub columns: Vec<ColumnId>,
+    /// The predicate to filter the groups
+    pub having: ConcretePredicate,
+}
 
 impl ExpressionBuilder for GroupBy {
-    /// Build expression of the form `GROUP BY <comma-separated-columns>`
+    /// Build expression of the form `GROUP BY <comma-separated-columns> HAVING <predicate>`
     fn build(&self, database: &Database, builder: &mut SQLBuilder) {
         builder.push_str("GROUP BY ");
         let columns = self
-            .0
+            .columns
             .iter()
             .map(|column_id| column_id.get_column(database))
             .collect::<Vec<_>>();
         builder.push_elems(database, &columns, ", ");
+
+        // Avoid correct, but inelegant "HAVING TRUE" clause
+        if self.having != ConcretePredicate::True {
+            builder.push_str(" HAVING ");
+            self.having.build(database, builder);
+        }
     }
 }
@@ -9,11 +9,12 @@
 
 use maybe_owned::MaybeOwned;
 
-use crate::{Database, PhysicalTable};
+use crate::{database_error::DatabaseError, Database, PhysicalTable};
 
 use super::{
     column::{Column, ProxyColumn},
     physical_column::PhysicalColumn,
+    predicate::ConcretePredicate,
     transaction::{TransactionContext, TransactionStepId},
     ExpressionBuilder, SQLBuilder, SQLParamContainer,
 };
@@ -27,14 +28,60 @@ pub struct Insert<'a> {
     pub columns: Vec<&'a PhysicalColumn>,
     /// The values to insert such as `(30, "John"), (35, "Jane")`
     pub values_seq: Vec<Vec<MaybeOwned<'a, Column>>>,
+    /// The conflict resolution such as `ON CONFLICT ("email") DO UPDATE SET ...`
+    pub on_conflict: Option<OnConflict<'a>>,
     /// The columns to return.
     pub returning: Vec<MaybeOwned<'a, Column>>,
 }
 
+/// The `ON CONFLICT (<conflict_columns>) DO UPDATE SET <column> = "excluded".<column>, ... WHERE
+/// <predicate>` clause of an insert.
+#[derive(Debug)]
+pub struct OnConflict<'a> {
+    /// The columns forming the primary key or a unique constraint
+    pub conflict_columns: Vec<&'a PhysicalColumn>,
+    /// The columns to update with the values proposed for insertion
+    pub update_columns: Vec<&'a PhysicalColumn>,
+    /// The predicate the existing row must satisfy to be updated
+    pub predicate: ConcretePredicate,
+}
+
+impl<'a> ExpressionBuilder for OnConflict<'a> {
+    fn build(&self, database: &Database, builder: &mut SQLBuilder) {
+        builder.push_str("ON CONFLICT (");
+        builder.without_fully_qualified_column_names(|builder| {
+            builder.push_elems(database, &self.conflict_columns, ", ");
+        });
+        builder.push_str(") DO UPDATE SET ");
+
+        // Postgres returns a row only if it was inserted or updated. So if there is nothing else
+        // to update, we set the conflict columns to themselves, so that `RETURNING` still reports
+        // the existing row.
+        let update_columns = if self.update_columns.is_empty() {
+            &self.conflict_columns
+        } else {
+            &self.update_columns
+        };
+
+        builder.push_iter(update_columns.iter(), ", ", |builder, column| {
+            builder.without_fully_qualified_column_names(|builder| {
+                column.build(database, builder);
+            });
+            builder.push_str(" = ");
+            builder.push_column_with_table_alias(&column.name, "excluded");
+        });
+
+        if self.predicate != ConcretePredicate::True {
+            builder.push_str(" WHERE ");
+            self.predicate.build(database, builder);
+        }
+    }
+}
+
 impl<'a> ExpressionBuilder for Insert<'a> {
     /// Build the insert statement for the form `INSERT INTO <table> (<columns>) VALUES (<values>)
-    /// RETURNING <returning-columns>`. The `RETURNING` clause is omitted if the list of columns to
-    /// return is empty.
+    /// [ON CONFLICT ...] RETURNING <returning-columns>`. The `RETURNING` clause is omitted if the
+    /// list of columns to return is empty.
     fn build(&self, database: &Database, builder: &mut SQLBuilder) {
         builder.push_str("INSERT INTO ");
         self.table.build(database, builder);
@@ -51,6 +98,11 @@ impl<'a> ExpressionBuilder for Insert<'a> {
         });
         builder.push(')');
 
+        if let Some(on_conflict) = &self.on_conflict {
+            builder.push(' ');
+            on_conflict.build(database, builder);
+        }
+
         if !self.returning.is_empty() {
             builder.push_str(" RETURNING ");
             builder.push_elems(database, &self.returning, ", ")
@@ -79,20 +131,21 @@ impl<'a> TemplateInsert<'a> {
         column_values_seq: &'b [Vec<ProxyColumn>],
         row_index: usize,
         transaction_context: &TransactionContext,
-    ) -> Vec<Vec<MaybeOwned<'b, Column>>> {
+    ) -> Result<Vec<Vec<MaybeOwned<'b, Column>>>, DatabaseError> {
         column_values_seq
             .iter()
             .map(|row| {
                 row.iter()
                     .map(|col| match col {
-                        ProxyColumn::Concrete(col) => col.as_ref().into(),
-                        ProxyColumn::Template { col_index, step_id } => {
-                            MaybeOwned::Owned(Column::Param(SQLParamContainer::from_sql_value(
-                                transaction_context.resolve_value(*step_id, row_index, *col_index),
-                            )))
-                        }
+                        ProxyColumn::Concrete(col) => Ok(col.as_ref().into()),
+                        ProxyColumn::Template { col_index, step_id } => Ok(MaybeOwned::Owned(
+                            Column::Param(SQLParamContainer::from_sql_value(
+                                transaction_context
+                                    .resolve_value(*step_id, row_index, *col_index)?,
+                            )),
+                        )),
                     })
-                    .collect()
+                    .collect::<Result<Vec<_>, DatabaseError>>()
             })
             .collect()
     }
@@ -101,14 +154,14 @@ impl<'a> TemplateInsert<'a> {
         &'a self,
         prev_step_id: TransactionStepId,
         transaction_context: &TransactionContext,
-    ) -> Option<Insert<'a>> {
+    ) -> Result<Option<Insert<'a>>, DatabaseError> {
         let row_count = transaction_context.row_count(prev_step_id);
 
         // If there are template columns, but no way to resolve them, this operation need not be performed
         // For example, if we are updating concert_artists while updating concerts, and there are no matching concerts
         // (determined by the where param to updateConcerts), then we don't need to update the concert_artists
         if self.has_template_columns() && row_count == 0 {
-            None
+            Ok(None)
         } else {
             let TemplateInsert {
                 table,
@@ -118,17 +171,120 @@ impl<'a> TemplateInsert<'a> {
             } = self;
 
             let resolved_cols = (0..row_count)
-                .flat_map(move |row_index| {
+                .map(|row_index| {
                     Self::expand_row(column_values_seq, row_index, transaction_context)
                 })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
                 .collect();
 
-            Some(Insert {
+            Ok(Some(Insert {
                 table,
                 columns: columns.clone(),
                 values_seq: resolved_cols,
+                on_conflict: None,
                 returning: returning.iter().map(|ret| ret.into()).collect(),
-            })
+            }))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::{
+            database_spec::DatabaseSpec,
+            table_spec::TableSpec,
+            test_helper::{int_column, pk_column, string_column},
+        },
+        PhysicalTableName, SQLParamContainer,
+    };
+
+    use multiplatform_test::multiplatform_test;
+
+    use super::*;
+
+    #[multiplatform_test]
+    fn upsert() {
+        let database = DatabaseSpec::new(
+            vec![TableSpec::new(
+                PhysicalTableName::new("people", None),
+                vec![pk_column("id"), string_column("name"), int_column("age")],
+                vec![],
+                vec![],
+            )],
+            vec![],
+        )
+        .to_database();
+
+        let table_id = database
+            .get_table_id(&PhysicalTableName::new("people", None))
+            .unwrap();
+        let table = database.get_table(table_id);
+        let id_col_id = database.get_column_id(table_id, "id").unwrap();
+        let age_col_id = database.get_column_id(table_id, "age").unwrap();
+
+        let id_col = id_col_id.get_column(&database);
+        let age_col = age_col_id.get_column(&database);
+
+        let mut insert = table.insert(
+            vec![id_col, age_col],
+            vec![vec![
+                Column::Param(SQLParamContainer::i32(1)),
+                Column::Param(SQLParamContainer::i32(5)),
+            ]],
+            vec![Column::physical(id_col_id, None).into()],
+        );
+        insert.on_conflict = Some(OnConflict {
+            conflict_columns: vec![id_col],
+            update_columns: vec![age_col],
+            predicate: ConcretePredicate::True,
+        });
+
+        assert_binding!(
+            insert.to_sql(&database),
+            r#"INSERT INTO "people" ("id", "age") VALUES ($1, $2) ON CONFLICT ("id") DO UPDATE SET "age" = "excluded"."age" RETURNING "people"."id""#,
+            1,
+            5
+        );
+    }
+
+    #[multiplatform_test]
+    fn upsert_without_update_columns() {
+        let database = DatabaseSpec::new(
+            vec![TableSpec::new(
+                PhysicalTableName::new("people", None),
+                vec![pk_column("id"), int_column("age")],
+                vec![],
+                vec![],
+            )],
+            vec![],
+        )
+        .to_database();
+
+        let table_id = database
+            .get_table_id(&PhysicalTableName::new("people", None))
+            .unwrap();
+        let table = database.get_table(table_id);
+        let id_col_id = database.get_column_id(table_id, "id").unwrap();
+        let id_col = id_col_id.get_column(&database);
+
+        let mut insert = table.insert(
+            vec![id_col],
+            vec![vec![Column::Param(SQLParamContainer::i32(1))]],
+            vec![Column::physical(id_col_id, None).into()],
+        );
+        insert.on_conflict = Some(OnConflict {
+            conflict_columns: vec![id_col],
+            update_columns: vec![],
+            predicate: ConcretePredicate::True,
+        });
+
+        assert_binding!(
+            insert.to_sql(&database),
+            r#"INSERT INTO "people" ("id") VALUES ($1) ON CONFLICT ("id") DO UPDATE SET "id" = "excluded"."id" RETURNING "people"."id""#,
+            1
+        );
+    }
+}
@@ -11,16 +11,27 @@ use crate::{Column, Database};
 
 use super::{ExpressionBuilder, SQLBuilder};
 
-/// A JSON aggregation corresponding to the Postgres' `json_agg` function.
+/// A JSON aggregation corresponding to the Postgres' `json_agg` function (SQLite's
+/// `json_group_array`).
 #[derive(Debug, PartialEq)]
 pub struct JsonAgg(pub Box<Column>);
 
 impl ExpressionBuilder for JsonAgg {
     /// Build expression of the form `COALESCE(json_agg(<column>)), '[]'::json)`. The COALESCE
     /// wrapper ensures that return an empty array if we have no matching entities.
+    ///
+    /// For SQLite, build `COALESCE(json_group_array(json(<column>)), json_array())`. The `json`
+    /// wrapper ensures that a JSON object coming from a sub-select is aggregated as an object
+    /// (instead of as a string).
     fn build(&self, database: &Database, builder: &mut SQLBuilder) {
-        builder.push_str("COALESCE(json_agg(");
-        self.0.build(database, builder);
-        builder.push_str("), '[]'::json)");
+        if builder.is_sqlite() {
+            builder.push_str("COALESCE(json_group_array(json(");
+            self.0.build(database, builder);
+            builder.push_str(")), json_array())");
+        } else {
+            builder.push_str("COALESCE(json_agg(");
+            self.0.build(database, builder);
+            builder.push_str("), '[]'::json)");
+        }
     }
 }
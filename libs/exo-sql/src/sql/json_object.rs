@@ -14,7 +14,8 @@ use super::{
     ExpressionBuilder, SQLBuilder,
 };
 
-/// A JSON object corresponding to the Postgres' `json_build_object` function.
+/// A JSON object corresponding to the Postgres' `json_build_object` function (SQLite's
+/// `json_object`).
 #[derive(Debug, PartialEq)]
 pub struct JsonObject(pub Vec<JsonObjectElement>);
 
@@ -34,7 +35,11 @@ impl JsonObjectElement {
 impl ExpressionBuilder for JsonObject {
     /// Build expression of the form `json_build_object(<comma-separated-elements>)`.
     fn build(&self, database: &Database, builder: &mut SQLBuilder) {
-        builder.push_str("json_build_object(");
+        builder.push_str(if builder.is_sqlite() {
+            "json_object("
+        } else {
+            "json_build_object("
+        });
         builder.push_elems(database, &self.0, ", ");
         builder.push(')');
     }
@@ -50,7 +55,9 @@ impl ExpressionBuilder for JsonObjectElement {
         builder.push_str(&self.key);
         builder.push_str("', ");
 
-        if let Column::Physical { column_id, .. } = self.value {
+        if builder.is_sqlite() {
+            self.build_sqlite_value(database, builder);
+        } else if let Column::Physical { column_id, .. } = self.value {
             let PhysicalColumn { typ, .. } = column_id.get_column(database);
             match &typ {
                 // encode blob fields in JSON objects as base64
@@ -80,3 +87,41 @@ impl ExpressionBuilder for JsonObjectElement {
         }
     }
 }
+
+impl JsonObjectElement {
+    /// Build the value of the element for SQLite. SQLite stores booleans as integers and JSON as
+    /// text, and a JSON value loses its "JSON-ness" when it comes out of a sub-select. So we
+    /// convert these values back to JSON (otherwise, they would be embedded as numbers or strings).
+    fn build_sqlite_value(&self, database: &Database, builder: &mut SQLBuilder) {
+        match &self.value {
+            Column::Physical { column_id, .. } => match &column_id.get_column(database).typ {
+                PhysicalColumnType::Boolean => {
+                    builder.push_str("json(CASE WHEN ");
+                    self.value.build(database, builder);
+                    builder.push_str(" THEN 'true' WHEN NOT ");
+                    self.value.build(database, builder);
+                    builder.push_str(" THEN 'false' END)");
+                }
+                // SQLite has no arrays, so we store them as JSON
+                PhysicalColumnType::Json | PhysicalColumnType::Array { .. } => {
+                    builder.push_str("json(");
+                    self.value.build(database, builder);
+                    builder.push(')');
+                }
+                // numerics must be outputted as text to avoid any loss in precision
+                PhysicalColumnType::Numeric { .. } => {
+                    builder.push_str("CAST(");
+                    self.value.build(database, builder);
+                    builder.push_str(" AS TEXT)");
+                }
+                _ => self.value.build(database, builder),
+            },
+            Column::SubSelect(_) | Column::SubSelectColumn { .. } => {
+                builder.push_str("json(");
+                self.value.build(database, builder);
+                builder.push(')');
+            }
+            _ => self.value.build(database, builder),
+        }
+    }
+}
@@ -25,6 +25,7 @@ pub mod order;
 pub mod physical_column;
 pub mod predicate;
 pub mod relation;
+pub mod text_search;
 pub mod vector;
 
 pub use sql_bytes::SQLBytes;
@@ -40,6 +41,7 @@ pub(crate) mod json_agg;
 pub(crate) mod json_object;
 pub(crate) mod limit;
 pub(crate) mod offset;
+pub(crate) mod physical_enum;
 pub(crate) mod physical_table;
 pub(crate) mod select;
 pub(crate) mod sql_operation;
@@ -48,7 +50,7 @@ pub(crate) mod transaction;
 pub(crate) mod update;
 
 pub(crate) use expression_builder::ExpressionBuilder;
-pub(crate) use sql_builder::SQLBuilder;
+pub(crate) use sql_builder::{SQLBuilder, SqlDialect};
 pub(crate) use sql_value::SQLValue;
 
 mod expression_builder;
@@ -58,3 +60,5 @@ mod sql_bytes;
 mod sql_param;
 mod sql_param_container;
 mod sql_value;
+#[cfg(feature = "sqlite")]
+mod sqlite_value;
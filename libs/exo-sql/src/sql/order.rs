@@ -8,9 +8,12 @@
 // by the Apache License, Version 2.0.
 
 use super::vector::VectorDistanceFunction;
-use crate::{sql::vector::VectorDistance, ColumnId, Database, SQLParamContainer};
+use crate::{
+    sql::{text_search::TextSearchRank, vector::VectorDistance},
+    ColumnId, Database, SQLParamContainer,
+};
 
-use super::{ExpressionBuilder, SQLBuilder};
+use super::{function::Function, ExpressionBuilder, SQLBuilder};
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
 pub enum Ordering {
     Asc,
@@ -34,6 +37,10 @@ pub enum OrderByElementExpr {
         VectorDistanceOperand,
         VectorDistanceFunction,
     ),
+    /// The rank of the `tsvector` column (the first operand) for the search terms (the second
+    /// operand) using the given text-search language
+    TextSearchRank(VectorDistanceOperand, VectorDistanceOperand, String),
+    Function(Function),
 }
 
 #[derive(Debug, PartialEq)]
@@ -63,6 +70,15 @@ impl ExpressionBuilder for OrderByElement {
                 VectorDistance::new((lhs, self.2.as_ref()), (rhs, self.2.as_ref()), *function)
                     .build(database, builder);
             }
+            OrderByElementExpr::TextSearchRank(document, terms, language) => {
+                TextSearchRank::new(
+                    (document, self.2.as_ref()),
+                    (terms, self.2.as_ref()),
+                    language,
+                )
+                .build(database, builder);
+            }
+            OrderByElementExpr::Function(function) => function.build(database, builder),
         }
         builder.push_space();
 
@@ -71,6 +87,16 @@ impl ExpressionBuilder for OrderByElement {
         } else {
             builder.push_str("DESC");
         }
+
+        // SQLite sorts NULLs as smaller than any value, so make it sort them as Postgres does by
+        // default (as larger), which keyset bounds (see `AbstractKeyset`) rely on
+        if builder.is_sqlite() {
+            builder.push_str(if self.1 == Ordering::Asc {
+                " NULLS LAST"
+            } else {
+                " NULLS FIRST"
+            });
+        }
     }
 }
 
@@ -185,4 +211,40 @@ mod test {
             );
         }
     }
+
+    #[multiplatform_test]
+    fn text_search_rank() {
+        let database = DatabaseSpec::new(
+            vec![TableSpec::new(
+                PhysicalTableName::new("videos", None),
+                vec![pk_column("id"), string_column("title_tsv")],
+                vec![],
+                vec![],
+            )],
+            vec![],
+        )
+        .to_database();
+
+        let table_id = database
+            .get_table_id(&PhysicalTableName::new("videos", None))
+            .unwrap();
+
+        let tsv_col = database.get_column_id(table_id, "title_tsv").unwrap();
+
+        let order_by = OrderBy(vec![OrderByElement(
+            OrderByElementExpr::TextSearchRank(
+                VectorDistanceOperand::PhysicalColumn(tsv_col),
+                VectorDistanceOperand::Param(SQLParamContainer::str("utawaku")),
+                "simple".to_string(),
+            ),
+            Ordering::Desc,
+            None,
+        )]);
+
+        assert_binding!(
+            order_by.to_sql(&database),
+            r#"ORDER BY ts_rank("videos"."title_tsv", websearch_to_tsquery('simple', $1)) DESC"#,
+            "utawaku"
+        );
+    }
 }
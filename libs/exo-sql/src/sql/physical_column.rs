@@ -38,6 +38,10 @@ pub struct PhysicalColumn {
     /// optional default value for this column
     pub default_value: Option<String>,
     pub update_sync: bool,
+
+    /// The earlier name of this column (if it is being renamed), so that migrations can rename it
+    /// in place instead of dropping and recreating it
+    pub renamed_from: Option<String>,
 }
 
 /// Simpler implementation of Debug for PhysicalColumn.
@@ -104,6 +108,15 @@ pub enum PhysicalColumnType {
         precision: Option<usize>,
         scale: Option<usize>,
     },
+    /// A user-defined enum type (created through `CREATE TYPE ... AS ENUM`)
+    Enum {
+        enum_name: String,
+    },
+    /// A generated `tsvector` column computed from a text column (see `@searchable`)
+    TsVector {
+        language: String,
+        source_column: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -147,6 +160,11 @@ impl PhysicalColumnType {
             PhysicalColumnType::Numeric { precision, scale } => {
                 format!("Numeric with precision: {precision:?}, scale: {scale:?}")
             }
+            PhysicalColumnType::Enum { enum_name } => format!("Enum {enum_name}"),
+            PhysicalColumnType::TsVector {
+                language,
+                source_column,
+            } => format!("TsVector of {source_column} ({language})"),
         }
     }
     /// Create a new physical column type given the SQL type string. This is used to reverse-engineer
@@ -294,6 +312,10 @@ impl PhysicalColumnType {
             },
             PhysicalColumnType::Numeric { .. } => Type::NUMERIC,
             PhysicalColumnType::Vector { .. } => Type::FLOAT4_ARRAY,
+            // Enum values are sent as text and cast to the enum type in the query (see
+            // `SQLParamContainer::with_cast`)
+            PhysicalColumnType::Enum { .. } => Type::TEXT,
+            PhysicalColumnType::TsVector { .. } => Type::TS_VECTOR,
         }
     }
 }
@@ -0,0 +1,19 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use serde::{Deserialize, Serialize};
+
+/// An enum type in the database (created using `CREATE TYPE ... AS ENUM`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalEnum {
+    /// The name of the enum type
+    pub name: String,
+    /// The values of the enum in their sort order
+    pub variants: Vec<String>,
+}
@@ -70,6 +70,19 @@ pub struct PhysicalTable {
     pub columns: Vec<PhysicalColumn>,
 
     pub indices: Vec<PhysicalIndex>,
+
+    /// Named `CHECK` constraints (derived from annotations such as `@range` or `@check`)
+    pub checks: Vec<PhysicalCheck>,
+
+    /// Should changes to the rows be broadcast using `NOTIFY` (to support subscriptions)?
+    pub notify_changes: bool,
+
+    /// The earlier name of this table (if it is being renamed)
+    pub renamed_from: Option<PhysicalTableName>,
+
+    /// The timestamp column marking a row as deleted (for a table with soft deletion). Selections
+    /// skip rows with a non-null value in this column unless asked to include them.
+    pub soft_delete_column: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
@@ -79,6 +92,14 @@ pub struct PhysicalIndex {
     pub index_kind: IndexKind,
 }
 
+/// A named `CHECK` constraint such as `"concerts_price_check" CHECK ("price" > 0)`
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct PhysicalCheck {
+    pub name: String,
+    /// The boolean SQL expression the rows must satisfy
+    pub expr: String,
+}
+
 /// The derived implementation of `Debug` is quite verbose, so we implement it manually
 /// to print the table name only.
 impl std::fmt::Debug for PhysicalTable {
@@ -93,8 +114,18 @@ impl std::fmt::Debug for PhysicalTable {
 }
 
 impl PhysicalTable {
-    pub fn get_pk_physical_column(&self) -> Option<&PhysicalColumn> {
-        self.columns.iter().find(|column| column.is_pk)
+    /// The primary key columns (more than one for a composite primary key), in the order of their
+    /// declaration
+    pub fn get_pk_physical_columns(&self) -> Vec<&PhysicalColumn> {
+        self.columns.iter().filter(|column| column.is_pk).collect()
+    }
+
+    /// The channel on which changes to this table are broadcast (if `notify_changes` is set)
+    pub fn change_notification_channel(&self) -> String {
+        format!(
+            "exograph_changes_{}",
+            self.name.fully_qualified_name_with_sep("_")
+        )
     }
 
     pub fn insert<'a, C>(
@@ -113,6 +144,7 @@ impl PhysicalTable {
                 .into_iter()
                 .map(|rows| rows.into_iter().map(|col| col.into()).collect())
                 .collect(),
+            on_conflict: None,
             returning,
         }
     }
@@ -151,8 +183,11 @@ impl PhysicalTable {
         self.columns.iter().position(|c| c.name == name)
     }
 
-    pub(crate) fn get_pk_column_index(&self) -> Option<usize> {
-        self.columns.iter().position(|c| c.is_pk)
+    pub(crate) fn get_pk_column_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.columns
+            .iter()
+            .enumerate()
+            .filter_map(|(index, c)| c.is_pk.then_some(index))
     }
 }
 
@@ -9,7 +9,12 @@
 
 use crate::{Database, VectorDistanceFunction};
 
-use super::{column::Column, vector::VectorDistance, ExpressionBuilder, SQLBuilder};
+use super::{
+    column::{ArrayParamWrapper, Column},
+    text_search::TextSearchQuery,
+    vector::VectorDistance,
+    ExpressionBuilder, SQLBuilder,
+};
 
 /// Case sensitivity for string predicates.
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -58,6 +63,10 @@ where
 
     VectorDistance(C, C, VectorDistanceFunction, NumericComparator, C),
 
+    // full-text search predicate (the `tsvector` column, the search terms, and the text-search
+    // language)
+    TextSearch(C, C, String),
+
     // Prefer Predicate::and(), which simplifies the clause
     And(Box<Predicate<C>>, Box<Predicate<C>>),
     // Prefer Predicate::or(), which simplifies the clause
@@ -111,6 +120,48 @@ where
             (lhs, rhs) => Predicate::Or(Box::new(lhs), Box::new(rhs)),
         }
     }
+
+    /// Map the columns of this predicate, keeping its structure (for example, to form a concrete
+    /// predicate from one whose columns are expressed differently)
+    pub fn map_columns<D>(&self, f: &impl Fn(&C) -> D) -> Predicate<D>
+    where
+        D: PartialEq + ParamEquality,
+    {
+        match self {
+            Predicate::True => Predicate::True,
+            Predicate::False => Predicate::False,
+            Predicate::Eq(l, r) => Predicate::Eq(f(l), f(r)),
+            Predicate::Neq(l, r) => Predicate::Neq(f(l), f(r)),
+            Predicate::Lt(l, r) => Predicate::Lt(f(l), f(r)),
+            Predicate::Lte(l, r) => Predicate::Lte(f(l), f(r)),
+            Predicate::Gt(l, r) => Predicate::Gt(f(l), f(r)),
+            Predicate::Gte(l, r) => Predicate::Gte(f(l), f(r)),
+            Predicate::In(l, r) => Predicate::In(f(l), f(r)),
+            Predicate::StringLike(l, r, case_sensitivity) => {
+                Predicate::StringLike(f(l), f(r), *case_sensitivity)
+            }
+            Predicate::StringStartsWith(l, r) => Predicate::StringStartsWith(f(l), f(r)),
+            Predicate::StringEndsWith(l, r) => Predicate::StringEndsWith(f(l), f(r)),
+            Predicate::JsonContains(l, r) => Predicate::JsonContains(f(l), f(r)),
+            Predicate::JsonContainedBy(l, r) => Predicate::JsonContainedBy(f(l), f(r)),
+            Predicate::JsonMatchKey(l, r) => Predicate::JsonMatchKey(f(l), f(r)),
+            Predicate::JsonMatchAnyKey(l, r) => Predicate::JsonMatchAnyKey(f(l), f(r)),
+            Predicate::JsonMatchAllKeys(l, r) => Predicate::JsonMatchAllKeys(f(l), f(r)),
+            Predicate::VectorDistance(l, r, distance_function, comparator, value) => {
+                Predicate::VectorDistance(f(l), f(r), *distance_function, *comparator, f(value))
+            }
+            Predicate::TextSearch(l, r, language) => {
+                Predicate::TextSearch(f(l), f(r), language.clone())
+            }
+            Predicate::And(l, r) => {
+                Predicate::And(Box::new(l.map_columns(f)), Box::new(r.map_columns(f)))
+            }
+            Predicate::Or(l, r) => {
+                Predicate::Or(Box::new(l.map_columns(f)), Box::new(r.map_columns(f)))
+            }
+            Predicate::Not(p) => Predicate::Not(Box::new(p.map_columns(f))),
+        }
+    }
 }
 
 impl<C> From<bool> for Predicate<C>
@@ -177,6 +228,16 @@ impl ExpressionBuilder for ConcretePredicate {
                 if column2 == &Column::Null {
                     column1.build(database, builder);
                     builder.push_str(" IS NULL");
+                } else if builder.is_sqlite()
+                    && matches!(
+                        column2,
+                        Column::ArrayParam {
+                            wrapper: ArrayParamWrapper::Any,
+                            ..
+                        }
+                    )
+                {
+                    relational_combine(column1, column2, "IN", database, builder)
                 } else {
                     relational_combine(column1, column2, "=", database, builder)
                 }
@@ -185,6 +246,16 @@ impl ExpressionBuilder for ConcretePredicate {
                 if column2 == &Column::Null {
                     column1.build(database, builder);
                     builder.push_str(" IS NOT NULL");
+                } else if builder.is_sqlite()
+                    && matches!(
+                        column2,
+                        Column::ArrayParam {
+                            wrapper: ArrayParamWrapper::All,
+                            ..
+                        }
+                    )
+                {
+                    relational_combine(column1, column2, "NOT IN", database, builder)
                 } else {
                     relational_combine(column1, column2, "<>", database, builder)
                 }
@@ -209,7 +280,8 @@ impl ExpressionBuilder for ConcretePredicate {
                 relational_combine(
                     column1,
                     column2,
-                    if *case_sensitivity == CaseSensitivity::Insensitive {
+                    // SQLite has no ILIKE (its LIKE is case-insensitive for ASCII characters)
+                    if *case_sensitivity == CaseSensitivity::Insensitive && !builder.is_sqlite() {
                         "ILIKE"
                     } else {
                         "LIKE"
@@ -267,6 +339,12 @@ impl ExpressionBuilder for ConcretePredicate {
                 numeric_value.build(database, builder);
             }
 
+            ConcretePredicate::TextSearch(column1, column2, language) => {
+                column1.build(database, builder);
+                builder.push_str(" @@ ");
+                TextSearchQuery::new(column2, language).build(database, builder);
+            }
+
             ConcretePredicate::And(predicate1, predicate2) => {
                 logical_combine(predicate1, predicate2, "AND", database, builder)
             }
@@ -571,4 +649,39 @@ mod tests {
             json_key_list
         );
     }
+
+    #[multiplatform_test]
+    fn text_search_predicate() {
+        let database = DatabaseSpec::new(
+            vec![TableSpec::new(
+                PhysicalTableName::new("videos", None),
+                vec![
+                    pk_column("id"),
+                    string_column("title"),
+                    string_column("title_tsv"),
+                ],
+                vec![],
+                vec![],
+            )],
+            vec![],
+        )
+        .to_database();
+
+        let table_id = database
+            .get_table_id(&PhysicalTableName::new("videos", None))
+            .unwrap();
+
+        let tsv_col_id = database.get_column_id(table_id, "title_tsv").unwrap();
+
+        let search_predicate = ConcretePredicate::TextSearch(
+            Column::physical(tsv_col_id, None),
+            Column::Param(SQLParamContainer::str("utawaku live")),
+            "english".to_string(),
+        );
+        assert_binding!(
+            search_predicate.to_sql(&database),
+            r#""videos"."title_tsv" @@ websearch_to_tsquery('english', $1)"#,
+            "utawaku live"
+        );
+    }
 }
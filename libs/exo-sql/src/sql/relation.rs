@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{ColumnId, ColumnPathLink, Database};
+use crate::{ColumnId, ColumnPathLink, Database, TableId};
 
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct OneToMany {
@@ -16,6 +16,43 @@ pub struct ManyToOne {
     /// multiple columns in a table refer to the same foreign table. For example,
     /// `concerts` may have a `main_venue_id` and a `alt_venue_id`.
     pub foreign_table_alias: Option<String>,
+    /// The action to take on the rows in this table when the referenced row is deleted
+    pub on_delete: ReferentialAction,
+}
+
+/// The referential action of a foreign key constraint (for example, `ON DELETE CASCADE`)
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ReferentialAction {
+    #[default]
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+impl ReferentialAction {
+    /// Parse the action from its SQL form (`"SET NULL"`) or its annotation form (`SET_NULL`)
+    pub fn from_sql(action: &str) -> Option<Self> {
+        match action.to_uppercase().replace('_', " ").as_str() {
+            "NO ACTION" => Some(Self::NoAction),
+            "RESTRICT" => Some(Self::Restrict),
+            "CASCADE" => Some(Self::Cascade),
+            "SET NULL" => Some(Self::SetNull),
+            "SET DEFAULT" => Some(Self::SetDefault),
+            _ => None,
+        }
+    }
+
+    pub fn to_sql(&self) -> &'static str {
+        match self {
+            Self::NoAction => "NO ACTION",
+            Self::Restrict => "RESTRICT",
+            Self::Cascade => "CASCADE",
+            Self::SetNull => "SET NULL",
+            Self::SetDefault => "SET DEFAULT",
+        }
+    }
 }
 
 impl OneToMany {
@@ -25,6 +62,14 @@ impl OneToMany {
 }
 
 impl ManyToOne {
+    pub fn self_table_id(&self) -> TableId {
+        self.self_column_id.table_id
+    }
+
+    pub fn foreign_table_id(&self) -> TableId {
+        self.foreign_pk_column_id.table_id
+    }
+
     fn flipped(&self) -> OneToMany {
         OneToMany {
             self_pk_column_id: self.foreign_pk_column_id,
@@ -48,6 +48,12 @@ impl ExpressionBuilder for Select {
                 alias: Some((alias_name, table_name)),
                 ..
             } => HashMap::from([(table_name.clone(), alias_name.clone())]),
+            // Similarly, for an aliased table, the other parts must use the alias (the table name
+            // may refer to an outer query's table)
+            Table::Physical {
+                table_id,
+                alias: Some(alias),
+            } => HashMap::from([(database.get_table(*table_id).name.clone(), alias.clone())]),
             _ => HashMap::new(),
         };
 
@@ -59,6 +65,7 @@ impl ExpressionBuilder for Select {
                 col.build(database, builder);
 
                 if self.top_level_selection
+                    && !builder.is_sqlite()
                     && matches!(col, Column::JsonObject(_) | Column::JsonAgg(_))
                 {
                     // See the comment on `top_level_selection` for why we do this
@@ -89,6 +96,10 @@ impl ExpressionBuilder for Select {
                 limit.build(database, builder);
             }
             if let Some(offset) = &self.offset {
+                if self.limit.is_none() && builder.is_sqlite() {
+                    // SQLite allows OFFSET only along with LIMIT (where -1 means no limit)
+                    builder.push_str(" LIMIT -1");
+                }
                 builder.push_space();
                 offset.build(database, builder);
             }
@@ -104,8 +115,11 @@ mod tests {
             table_spec::TableSpec,
             test_helper::{int_column, pk_column, string_column},
         },
-        sql::json_object::{JsonObject, JsonObjectElement},
-        PhysicalTableName,
+        sql::{
+            function::Function,
+            json_object::{JsonObject, JsonObjectElement},
+        },
+        PhysicalTableName, SQLParamContainer,
     };
 
     use multiplatform_test::multiplatform_test;
@@ -156,4 +170,59 @@ mod tests {
             r#"SELECT "people"."age", json_build_object('namex', "people"."name", 'agex', "people"."age")::text FROM "people""#
         );
     }
+
+    #[multiplatform_test]
+    fn group_by_having() {
+        let database = DatabaseSpec::new(
+            vec![TableSpec::new(
+                PhysicalTableName {
+                    name: "people".to_owned(),
+                    schema: None,
+                },
+                vec![pk_column("id"), string_column("name"), int_column("age")],
+                vec![],
+                vec![],
+            )],
+            vec![],
+        )
+        .to_database();
+
+        let table_id = database
+            .get_table_id(&PhysicalTableName::new("people", None))
+            .unwrap();
+        let id_col_id = database.get_column_id(table_id, "id").unwrap();
+        let age_col_id = database.get_column_id(table_id, "age").unwrap();
+
+        let count = || {
+            Column::Function(Function::Named {
+                function_name: "count".to_string(),
+                column_id: id_col_id,
+            })
+        };
+
+        let json_col = Column::JsonObject(JsonObject(vec![
+            JsonObjectElement::new("age".to_string(), Column::physical(age_col_id, None)),
+            JsonObjectElement::new("count".to_string(), count()),
+        ]));
+
+        let selected_table = Select {
+            table: Table::physical(table_id, None),
+            columns: vec![json_col],
+            predicate: ConcretePredicate::True,
+            order_by: None,
+            limit: None,
+            offset: None,
+            group_by: Some(GroupBy {
+                columns: vec![age_col_id],
+                having: ConcretePredicate::Gt(count(), Column::Param(SQLParamContainer::i64(1))),
+            }),
+            top_level_selection: true,
+        };
+
+        assert_binding!(
+            selected_table.to_sql(&database),
+            r#"SELECT json_build_object('age', "people"."age", 'count', count("people"."id"))::text FROM "people" GROUP BY "people"."age" HAVING count("people"."id") > $1"#,
+            1i64
+        );
+    }
 }
@@ -13,7 +13,18 @@ use crate::Database;
 
 use super::{physical_table::PhysicalTableName, sql_param::SQLParamWithType, ExpressionBuilder};
 
+/// The SQL dialect to build for. The primitives build Postgres SQL and deviate from it only where
+/// SQLite doesn't support the Postgres form (parameter placeholders, casts, JSON functions, etc.).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    Postgres,
+    #[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+    Sqlite,
+}
+
 pub struct SQLBuilder {
+    /// The dialect of the SQL being built
+    dialect: SqlDialect,
     /// The SQL being built with placeholders for each parameter
     sql: String,
     /// The list of parameters
@@ -30,7 +41,12 @@ pub struct SQLBuilder {
 
 impl SQLBuilder {
     pub fn new() -> Self {
+        Self::with_dialect(SqlDialect::Postgres)
+    }
+
+    pub fn with_dialect(dialect: SqlDialect) -> Self {
         Self {
+            dialect,
             sql: String::new(),
             params: Vec::new(),
             fully_qualify_column_names: true,
@@ -38,6 +54,11 @@ impl SQLBuilder {
         }
     }
 
+    /// Is the SQL being built for SQLite?
+    pub fn is_sqlite(&self) -> bool {
+        self.dialect == SqlDialect::Sqlite
+    }
+
     /// Push a string
     pub fn push_str<T: AsRef<str>>(&mut self, s: T) {
         self.sql.push_str(s.as_ref());
@@ -101,10 +122,20 @@ impl SQLBuilder {
     /// and the parameter will be added to the list of parameters.
     pub fn push_param(&mut self, param: SQLParamWithType) {
         self.params.push(param);
-        self.push('$');
+        // SQLite treats `$1` as a named parameter, so we use its numbered form `?1` instead
+        self.push(if self.is_sqlite() { '?' } else { '$' });
         self.push_str(&self.params.len().to_string());
     }
 
+    /// Push a cast to the given type such as `::"status"` (omitted for SQLite, which stores such
+    /// values with their underlying type)
+    pub fn push_cast<T: AsRef<str>>(&mut self, cast_type: T) {
+        if !self.is_sqlite() {
+            self.push_str("::");
+            self.push_str(cast_type);
+        }
+    }
+
     /// Push elements of an iterator, separated by `sep`. The `push_elem` function provides
     /// the flexibility to map the elements (compared to [`SQLBuilder::push_elems`], which assumes that
     /// the elements implement [`ExpressionBuilder`] and [`build`](ExpressionBuilder::build) is all you need to call).
@@ -7,7 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::Database;
+use crate::{database_error::DatabaseError, Database};
 
 use super::{
     cte::WithQuery,
@@ -56,23 +56,23 @@ impl<'a> TemplateSQLOperation<'a> {
         &'a self,
         prev_step_id: TransactionStepId,
         transaction_context: &TransactionContext,
-    ) -> Vec<SQLOperation<'a>> {
-        match self {
+    ) -> Result<Vec<SQLOperation<'a>>, DatabaseError> {
+        Ok(match self {
             TemplateSQLOperation::Insert(insert) => insert
-                .resolve(prev_step_id, transaction_context)
+                .resolve(prev_step_id, transaction_context)?
                 .into_iter()
                 .map(SQLOperation::Insert)
                 .collect(),
             TemplateSQLOperation::Update(update) => update
-                .resolve(prev_step_id, transaction_context)
+                .resolve(prev_step_id, transaction_context)?
                 .into_iter()
                 .map(SQLOperation::Update)
                 .collect(),
             TemplateSQLOperation::Delete(delete) => delete
-                .resolve(prev_step_id, transaction_context)
+                .resolve(prev_step_id, transaction_context)?
                 .into_iter()
                 .map(SQLOperation::Delete)
                 .collect(),
-        }
+        })
     }
 }
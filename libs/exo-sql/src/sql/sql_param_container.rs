@@ -20,13 +20,26 @@ use crate::{SQLBytes, SQLParam};
 
 use super::{physical_column::to_pg_array_type, sql_param::SQLParamWithType, SQLValue};
 
+/// A parameter along with its type and an optional SQL type to cast it to in the generated SQL.
+///
+/// The cast is needed for types that Postgres will not implicitly convert to from the parameter's
+/// wire type. For example, enum values are sent as `TEXT` and must be written as `$1::"status"`
+/// (or `$1::"status"[]` for an array of them).
 #[derive(Clone)]
-pub struct SQLParamContainer(SQLParamWithType);
+pub struct SQLParamContainer(SQLParamWithType, Option<String>);
 
 impl SQLParamContainer {
     pub fn param(&self) -> SQLParamWithType {
         self.0.clone()
     }
+
+    pub fn cast_type(&self) -> Option<&str> {
+        self.1.as_deref()
+    }
+
+    pub fn with_cast(self, cast_type: String) -> Self {
+        Self(self.0, Some(cast_type))
+    }
 }
 
 impl ToSql for SQLParamContainer {
@@ -47,7 +60,7 @@ impl ToSql for SQLParamContainer {
 
 impl SQLParamContainer {
     pub(crate) fn new<T: SQLParam + 'static>(param: T, param_type: Type) -> Self {
-        Self((Arc::new(param), param_type))
+        Self((Arc::new(param), param_type), None)
     }
 
     pub fn string(value: String) -> Self {
@@ -127,6 +140,10 @@ impl SQLParamContainer {
         Self::new(value, Type::JSONB)
     }
 
+    pub fn enum_value(value: String, enum_name: &str) -> Self {
+        Self::string(value).with_cast(format!("\"{enum_name}\""))
+    }
+
     pub fn from_sql_values(params: Vec<SQLValue>, elem_type: Type) -> Self {
         let collection_type = to_pg_array_type(&elem_type);
 
@@ -141,7 +158,7 @@ impl SQLParamContainer {
 
 impl PartialEq for SQLParamContainer {
     fn eq(&self, other: &Self) -> bool {
-        self.0.eq(&other.0)
+        self.0.eq(&other.0) && self.1 == other.1
     }
 }
 
@@ -69,3 +69,27 @@ impl<'a> FromSql<'a> for SQLValue {
         true
     }
 }
+
+#[cfg(feature = "sqlite")]
+impl SQLValue {
+    /// Convert a value obtained from SQLite (in the same binary form as Postgres would have
+    /// returned it), so that it can be used in a later step. Returns `None` for a NULL value.
+    pub(crate) fn from_sqlite(value: &rusqlite::types::Value) -> Option<Self> {
+        use rusqlite::types::Value;
+
+        let (value, type_) = match value {
+            Value::Null => return None,
+            Value::Integer(value) => (value.to_be_bytes().to_vec(), Type::INT8),
+            Value::Real(value) => (value.to_be_bytes().to_vec(), Type::FLOAT8),
+            Value::Text(value) => (value.as_bytes().to_vec(), Type::TEXT),
+            Value::Blob(value) => (value.clone(), Type::BYTEA),
+        };
+
+        Some(SQLValue { value, type_ })
+    }
+
+    /// The value in the Postgres binary form
+    pub(crate) fn raw(&self) -> &[u8] {
+        &self.value
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Conversion of parameters to SQLite values.
+//!
+//! Parameters are typed for Postgres (see [`SQLParamContainer`]), so we encode them in the Postgres
+//! binary form and decode that into the closest SQLite value. SQLite has no boolean, date/time, or
+//! array types, so booleans become integers, date/time values and UUIDs become (ISO 8601 or
+//! hyphenated) text, and arrays become JSON text.
+
+use bytes::BytesMut;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+#[cfg(feature = "bigdecimal")]
+use pg_bigdecimal::PgNumeric;
+use rusqlite::types::Value;
+use tokio_postgres::types::{FromSql, IsNull, Kind, Type};
+
+use crate::database_error::DatabaseError;
+
+use super::{sql_param::SQLParamWithType, SQLParamContainer, SQLValue};
+
+type BoxError = Box<dyn std::error::Error + Sync + Send>;
+
+/// Convert a parameter to a SQLite value
+pub(crate) fn to_sqlite_value(
+    (param, param_type): &SQLParamWithType,
+) -> Result<Value, DatabaseError> {
+    let param = param.as_ref();
+
+    if let Some(container) = param.as_any().downcast_ref::<SQLParamContainer>() {
+        return to_sqlite_value(&container.param());
+    }
+
+    // Values from earlier steps carry their own type (which may differ from the declared one)
+    if let Some(value) = param.as_any().downcast_ref::<SQLValue>() {
+        return Ok(decode(&value.type_, value.raw())?);
+    }
+
+    if let Some(values) = param.as_any().downcast_ref::<Vec<SQLValue>>() {
+        let elements = values
+            .iter()
+            .map(|value| decode(&value.type_, value.raw()).and_then(to_json))
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(Value::Text(serde_json::Value::Array(elements).to_string()));
+    }
+
+    let mut raw = BytesMut::new();
+    match param.to_sql_checked(param_type, &mut raw)? {
+        IsNull::Yes => Ok(Value::Null),
+        IsNull::No => Ok(decode(param_type, &raw)?),
+    }
+}
+
+/// Decode a value in the Postgres binary form of the given type
+fn decode(ty: &Type, raw: &[u8]) -> Result<Value, BoxError> {
+    Ok(match *ty {
+        Type::BOOL => Value::Integer(bool::from_sql(ty, raw)?.into()),
+        Type::INT2 => Value::Integer(i16::from_sql(ty, raw)?.into()),
+        Type::INT4 => Value::Integer(i32::from_sql(ty, raw)?.into()),
+        Type::INT8 => Value::Integer(i64::from_sql(ty, raw)?),
+        Type::FLOAT4 => Value::Real(f32::from_sql(ty, raw)?.into()),
+        Type::FLOAT8 => Value::Real(f64::from_sql(ty, raw)?),
+        Type::BYTEA => Value::Blob(Vec::<u8>::from_sql(ty, raw)?),
+        Type::UUID => Value::Text(uuid::Uuid::from_sql(ty, raw)?.to_string()),
+        Type::DATE => Value::Text(NaiveDate::from_sql(ty, raw)?.to_string()),
+        Type::TIME => Value::Text(NaiveTime::from_sql(ty, raw)?.to_string()),
+        Type::TIMESTAMP => Value::Text(
+            NaiveDateTime::from_sql(ty, raw)?
+                .format("%Y-%m-%dT%H:%M:%S%.f")
+                .to_string(),
+        ),
+        Type::TIMESTAMPTZ => Value::Text(DateTime::<FixedOffset>::from_sql(ty, raw)?.to_rfc3339()),
+        Type::JSON | Type::JSONB => Value::Text(serde_json::Value::from_sql(ty, raw)?.to_string()),
+        #[cfg(feature = "bigdecimal")]
+        Type::NUMERIC => match PgNumeric::from_sql(ty, raw)?.n {
+            Some(n) => Value::Text(n.to_string()),
+            None => Value::Real(f64::NAN),
+        },
+        _ => match ty.kind() {
+            Kind::Array(member) => Value::Text(decode_array(ty, member, raw)?.to_string()),
+            // Text and text-like types (such as enums, which we send as text)
+            _ => Value::Text(String::from_sql(ty, raw)?),
+        },
+    })
+}
+
+/// Decode an array in the Postgres binary form into a JSON array
+fn decode_array(ty: &Type, member: &Type, raw: &[u8]) -> Result<serde_json::Value, BoxError> {
+    fn elements<'a, T: FromSql<'a>>(
+        ty: &Type,
+        raw: &'a [u8],
+        to_json: impl Fn(T) -> serde_json::Value,
+    ) -> Result<serde_json::Value, BoxError> {
+        Ok(Vec::<Option<T>>::from_sql(ty, raw)?
+            .into_iter()
+            .map(|element| element.map(&to_json).unwrap_or(serde_json::Value::Null))
+            .collect())
+    }
+
+    match *member {
+        Type::BOOL => elements::<bool>(ty, raw, Into::into),
+        Type::INT2 => elements::<i16>(ty, raw, Into::into),
+        Type::INT4 => elements::<i32>(ty, raw, Into::into),
+        Type::INT8 => elements::<i64>(ty, raw, Into::into),
+        Type::FLOAT4 => elements::<f32>(ty, raw, Into::into),
+        Type::FLOAT8 => elements::<f64>(ty, raw, Into::into),
+        Type::UUID => elements(ty, raw, |uuid: uuid::Uuid| uuid.to_string().into()),
+        Type::JSON | Type::JSONB => elements::<serde_json::Value>(ty, raw, |value| value),
+        _ => elements::<String>(ty, raw, Into::into),
+    }
+}
+
+fn to_json(value: Value) -> Result<serde_json::Value, BoxError> {
+    Ok(match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Integer(value) => value.into(),
+        Value::Real(value) => value.into(),
+        Value::Text(value) => value.into(),
+        Value::Blob(_) => return Err("Binary values are not supported in an array".into()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use multiplatform_test::multiplatform_test;
+
+    use super::*;
+
+    #[multiplatform_test]
+    fn scalar_params() {
+        let value = |param: SQLParamContainer| to_sqlite_value(&param.param()).unwrap();
+
+        assert_eq!(value(SQLParamContainer::bool(true)), Value::Integer(1));
+        assert_eq!(value(SQLParamContainer::i32(42)), Value::Integer(42));
+        assert_eq!(value(SQLParamContainer::f64(1.5)), Value::Real(1.5));
+        assert_eq!(
+            value(SQLParamContainer::string("v1".to_string())),
+            Value::Text("v1".to_string())
+        );
+        assert_eq!(
+            value(SQLParamContainer::date(
+                NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+            )),
+            Value::Text("2024-02-29".to_string())
+        );
+        assert_eq!(
+            value(SQLParamContainer::json(serde_json::json!({"a": [1, 2]}))),
+            Value::Text(r#"{"a":[1,2]}"#.to_string())
+        );
+    }
+
+    #[multiplatform_test]
+    fn step_values() {
+        let values = [
+            Value::Integer(1),
+            Value::Text("v1".to_string()),
+            Value::Real(2.5),
+        ]
+        .iter()
+        .map(|value| SQLValue::from_sqlite(value).unwrap())
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            to_sqlite_value(&SQLParamContainer::from_sql_value(values[0].clone()).param()).unwrap(),
+            Value::Integer(1)
+        );
+
+        assert_eq!(
+            to_sqlite_value(&SQLParamContainer::from_sql_values(values, Type::INT4).param())
+                .unwrap(),
+            Value::Text(r#"[1,"v1",2.5]"#.to_string())
+        );
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::Database;
+
+use super::{ExpressionBuilder, SQLBuilder};
+
+/// The text-search configuration used when `@searchable` doesn't specify one
+pub const DEFAULT_TEXT_SEARCH_LANGUAGE: &str = "english";
+
+/// A text-search query formed from user-supplied search terms such as `websearch_to_tsquery('english', $1)`.
+///
+/// We use `websearch_to_tsquery` so that the terms may use the familiar web-search syntax (quoted
+/// phrases, `or`, and `-` to exclude a term) and never fail to parse.
+pub struct TextSearchQuery<'a, C>
+where
+    C: ExpressionBuilder,
+{
+    terms: C,
+    language: &'a str,
+}
+
+impl<'a, C: ExpressionBuilder> TextSearchQuery<'a, C> {
+    pub fn new(terms: C, language: &'a str) -> Self {
+        Self { terms, language }
+    }
+}
+
+impl<C: ExpressionBuilder> ExpressionBuilder for TextSearchQuery<'_, C> {
+    fn build(&self, database: &Database, builder: &mut SQLBuilder) {
+        builder.push_str("websearch_to_tsquery('");
+        builder.push_str(self.language.replace('\'', "''"));
+        builder.push_str("', ");
+        self.terms.build(database, builder);
+        builder.push(')');
+    }
+}
+
+/// The rank of a document (a `tsvector` column) for the given search terms such as
+/// `ts_rank("documents"."title_tsv", websearch_to_tsquery('english', $1))`
+pub struct TextSearchRank<'a, C>
+where
+    C: ExpressionBuilder,
+{
+    document: C,
+    query: TextSearchQuery<'a, C>,
+}
+
+impl<'a, C: ExpressionBuilder> TextSearchRank<'a, C> {
+    pub fn new(document: C, terms: C, language: &'a str) -> Self {
+        Self {
+            document,
+            query: TextSearchQuery::new(terms, language),
+        }
+    }
+}
+
+impl<C: ExpressionBuilder> ExpressionBuilder for TextSearchRank<'_, C> {
+    fn build(&self, database: &Database, builder: &mut SQLBuilder) {
+        builder.push_str("ts_rank(");
+        self.document.build(database, builder);
+        builder.push_str(", ");
+        self.query.build(database, builder);
+        builder.push(')');
+    }
+}
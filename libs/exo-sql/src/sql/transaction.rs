@@ -15,7 +15,7 @@ use tracing::{error, info, instrument};
 use crate::{
     database_error::DatabaseError,
     sql::{select::Select, table::Table, SQLBuilder},
-    Column, Database, Predicate, SQLParamContainer, TableId,
+    Column, Database, OneToMany, Predicate, SQLParamContainer, TableId,
 };
 
 use super::{
@@ -24,38 +24,152 @@ use super::{
     sql_operation::{SQLOperation, TemplateSQLOperation},
     ExpressionBuilder, SQLValue,
 };
+#[cfg(feature = "sqlite")]
+use super::{sqlite_value::to_sqlite_value, SqlDialect};
 
 /// Rows obtained from a SQL operation
 pub type TransactionStepResult = Vec<Row>;
 
+/// Rows obtained from a SQL operation executed on SQLite
+#[cfg(feature = "sqlite")]
+pub type SqliteStepResult = Vec<Vec<rusqlite::types::Value>>;
+
 /// Sequence of SQL operations that are executed in a transaction
 #[derive(Default, Debug)]
 pub struct TransactionScript<'a> {
     steps: Vec<TransactionStep<'a>>,
+    /// The step whose result is the result of the script (if not set, the last step)
+    result_step: Option<TransactionStepId>,
 }
 
 /// Collection of results from steps in a transaction
 pub struct TransactionContext {
-    results: Vec<TransactionStepResult>,
+    results: Vec<StepRows>,
+}
+
+/// Rows obtained from a step (in the form the database client returned them)
+enum StepRows {
+    Postgres(TransactionStepResult),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteStepResult),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TransactionStepId(pub usize);
 
 impl TransactionContext {
-    /// Returns the value of a column in a row from the given step id
-    pub fn resolve_value(&self, step_id: TransactionStepId, row: usize, col: usize) -> SQLValue {
-        self.results[step_id.0][row].get::<usize, SQLValue>(col)
+    /// Returns the value of a column in a row from the given step id. Fails if the value is NULL
+    /// (a later step can't refer to it).
+    pub fn resolve_value(
+        &self,
+        step_id: TransactionStepId,
+        row: usize,
+        col: usize,
+    ) -> Result<SQLValue, DatabaseError> {
+        match &self.results[step_id.0] {
+            StepRows::Postgres(rows) => rows[row].try_get::<usize, SQLValue>(col).map_err(|e| {
+                DatabaseError::Delegate(e)
+                    .with_context("Unexpected value in the result of a transaction step".into())
+            }),
+            #[cfg(feature = "sqlite")]
+            StepRows::Sqlite(rows) => SQLValue::from_sqlite(&rows[row][col]).ok_or_else(|| {
+                DatabaseError::Transaction(
+                    "Unexpected NULL value in the result of a transaction step".into(),
+                )
+            }),
+        }
     }
 
     /// Returns the number of rows in the result of the given step id
     pub fn row_count(&self, step_id: TransactionStepId) -> usize {
-        self.results[step_id.0].len()
+        match &self.results[step_id.0] {
+            StepRows::Postgres(rows) => rows.len(),
+            #[cfg(feature = "sqlite")]
+            StepRows::Sqlite(rows) => rows.len(),
+        }
+    }
+
+    /// Takes the rows of the given step (or of the last step if not specified)
+    fn into_result(mut self, step_id: Option<TransactionStepId>) -> Option<StepRows> {
+        let index = match step_id {
+            Some(step_id) => step_id.0,
+            None => self.results.len().checked_sub(1)?,
+        };
+
+        (index < self.results.len()).then(|| self.results.swap_remove(index))
+    }
+
+    /// A predicate that matches the rows of `table_id` returned by the given step. The step must
+    /// return the primary key columns of the table (in order) as its leading columns.
+    ///
+    /// Forms `pk = ANY($1)` for a single primary key column, and `(pk1 = $1 AND pk2 = $2) OR ...`
+    /// for a composite primary key.
+    pub fn pk_predicate(
+        &self,
+        table_id: TableId,
+        step_id: TransactionStepId,
+        database: &Database,
+    ) -> Result<ConcretePredicate, DatabaseError> {
+        let pk_column_ids = database.get_pk_column_ids(table_id);
+        let rows = self.row_count(step_id);
+
+        match &pk_column_ids[..] {
+            [pk_column_id] => {
+                let pk_column_type = pk_column_id.get_column(database).typ.get_pg_type();
+
+                Ok(Predicate::Eq(
+                    Column::physical(*pk_column_id, None),
+                    Column::ArrayParam {
+                        param: SQLParamContainer::from_sql_values(
+                            (0..rows)
+                                .map(|row| self.resolve_value(step_id, row, 0))
+                                .collect::<Result<Vec<_>, _>>()?,
+                            pk_column_type,
+                        ),
+                        wrapper: ArrayParamWrapper::Any,
+                    },
+                ))
+            }
+            _ => (0..rows).try_fold(Predicate::False, |acc, row| {
+                let row_predicate = pk_column_ids.iter().enumerate().try_fold(
+                    Predicate::True,
+                    |acc, (col, pk_column_id)| {
+                        Ok::<_, DatabaseError>(Predicate::and(
+                            acc,
+                            Predicate::Eq(
+                                Column::physical(*pk_column_id, None),
+                                Column::Param(SQLParamContainer::from_sql_value(
+                                    self.resolve_value(step_id, row, col)?,
+                                )),
+                            ),
+                        ))
+                    },
+                )?;
+                Ok(Predicate::or(acc, row_predicate))
+            }),
+        }
+    }
+
+    /// A predicate that matches the rows of the foreign table of `relation` that refer to a row
+    /// returned by the given step. The step must return the primary key column of the relation's
+    /// self table as its leading column.
+    pub fn relation_predicate(
+        &self,
+        relation: &OneToMany,
+        step_id: TransactionStepId,
+        row: usize,
+    ) -> Result<ConcretePredicate, DatabaseError> {
+        Ok(Predicate::Eq(
+            Column::physical(relation.foreign_column_id, None),
+            Column::Param(SQLParamContainer::from_sql_value(
+                self.resolve_value(step_id, row, 0)?,
+            )),
+        ))
     }
 }
 
 impl<'a> TransactionScript<'a> {
-    /// Returns the result of the last step
+    /// Returns the result of the result step (usually the last step)
     #[instrument(
         name = "TransactionScript::execute"
         skip_all
@@ -70,15 +184,39 @@ impl<'a> TransactionScript<'a> {
         // Execute each step in the transaction and store the result in the transaction_context
         for step in self.steps.into_iter() {
             let result = step.execute(database, tx, &transaction_context).await?;
-            transaction_context.results.push(result)
+            transaction_context.results.push(StepRows::Postgres(result))
         }
 
-        // Return the result of the last step (usually the "select")
-        transaction_context
-            .results
-            .into_iter()
-            .last()
-            .ok_or_else(|| DatabaseError::Transaction("".into()))
+        // Return the result of the result step (usually the "select")
+        match transaction_context.into_result(self.result_step) {
+            Some(StepRows::Postgres(rows)) => Ok(rows),
+            _ => Err(DatabaseError::Transaction("".into())),
+        }
+    }
+
+    /// Execute the script on SQLite and return the result of the result step (usually the last
+    /// step)
+    #[cfg(feature = "sqlite")]
+    #[instrument(
+        name = "TransactionScript::execute_sqlite"
+        skip_all
+        )]
+    pub fn execute_sqlite(
+        self,
+        database: &Database,
+        connection: &rusqlite::Connection,
+    ) -> Result<SqliteStepResult, DatabaseError> {
+        let mut transaction_context = TransactionContext { results: vec![] };
+
+        for step in self.steps.into_iter() {
+            let result = step.execute_sqlite(database, connection, &transaction_context)?;
+            transaction_context.results.push(StepRows::Sqlite(result))
+        }
+
+        match transaction_context.into_result(self.result_step) {
+            Some(StepRows::Sqlite(rows)) => Ok(rows),
+            _ => Err(DatabaseError::Transaction("".into())),
+        }
     }
 
     /// Adds a step to the transaction script and return the step id (which is just the index of the step in the script)
@@ -88,6 +226,13 @@ impl<'a> TransactionScript<'a> {
         TransactionStepId(id)
     }
 
+    /// Use the result of the given step (instead of the last one) as the result of the script.
+    /// Useful when the last steps only have side effects (such as deleting the selected rows).
+    #[cfg_attr(not(feature = "sqlite"), allow(dead_code))]
+    pub fn set_result_step(&mut self, step_id: TransactionStepId) {
+        self.result_step = Some(step_id);
+    }
+
     pub fn needs_transaction(&self) -> bool {
         self.steps.len() > 1
     }
@@ -116,7 +261,7 @@ impl<'a> TransactionStep<'a> {
         match self {
             Self::Concrete(step) => step.execute(database, client).await,
             Self::Template(step) => {
-                let concrete = step.resolve(transaction_context);
+                let concrete = step.resolve(transaction_context)?;
 
                 let mut res: Result<TransactionStepResult, DatabaseError> = Ok(vec![]);
 
@@ -135,16 +280,44 @@ impl<'a> TransactionStep<'a> {
                 res
             }
             Self::Filter(step) => {
-                let concrete = step.resolve(transaction_context, database);
+                let concrete = step.resolve(transaction_context, database)?;
                 concrete.execute(database, client).await
             }
             Self::Dynamic(step) => {
-                step.resolve(transaction_context)
+                step.resolve(transaction_context)?
                     .execute(database, client)
                     .await
             }
         }
     }
+
+    #[cfg(feature = "sqlite")]
+    pub fn execute_sqlite(
+        self,
+        database: &Database,
+        connection: &rusqlite::Connection,
+        transaction_context: &TransactionContext,
+    ) -> Result<SqliteStepResult, DatabaseError> {
+        match self {
+            Self::Concrete(step) => step.execute_sqlite(database, connection),
+            Self::Template(step) => {
+                let mut res = vec![];
+
+                // Execute all substeps and return the result of the last one
+                for substep in step.resolve(transaction_context)? {
+                    res = substep.execute_sqlite(database, connection)?;
+                }
+
+                Ok(res)
+            }
+            Self::Filter(step) => step
+                .resolve(transaction_context, database)?
+                .execute_sqlite(database, connection),
+            Self::Dynamic(step) => step
+                .resolve(transaction_context)?
+                .execute_sqlite(database, connection),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -194,6 +367,42 @@ impl<'a> ConcreteTransactionStep<'a> {
                 DatabaseError::Delegate(e).with_context("Database operation failed".into())
             })
     }
+
+    #[cfg(feature = "sqlite")]
+    pub fn execute_sqlite(
+        self,
+        database: &Database,
+        connection: &rusqlite::Connection,
+    ) -> Result<SqliteStepResult, DatabaseError> {
+        let mut sql_builder = SQLBuilder::with_dialect(SqlDialect::Sqlite);
+        self.operation.build(database, &mut sql_builder);
+        let (stmt, params) = sql_builder.into_sql();
+
+        let params = params
+            .iter()
+            .map(to_sqlite_value)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        info!("Executing SQL operation: {}", stmt);
+
+        let run_query = || -> rusqlite::Result<SqliteStepResult> {
+            let mut statement = connection.prepare_cached(&stmt)?;
+            let column_count = statement.column_count();
+
+            let rows = statement.query_map(rusqlite::params_from_iter(params), |row| {
+                (0..column_count)
+                    .map(|col| row.get::<_, rusqlite::types::Value>(col))
+                    .collect::<Result<Vec<_>, _>>()
+            })?;
+
+            rows.collect::<Result<SqliteStepResult, _>>()
+        };
+
+        run_query().map_err(|e| {
+            error!("Failed to execute query: {e:?}");
+            DatabaseError::Sqlite(e).with_context("Database operation failed".into())
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -206,12 +415,13 @@ impl<'a> TemplateTransactionStep<'a> {
     pub fn resolve(
         &'a self,
         transaction_context: &TransactionContext,
-    ) -> Vec<ConcreteTransactionStep<'a>> {
-        self.operation
-            .resolve(self.prev_step_id, transaction_context)
+    ) -> Result<Vec<ConcreteTransactionStep<'a>>, DatabaseError> {
+        Ok(self
+            .operation
+            .resolve(self.prev_step_id, transaction_context)?
             .into_iter()
             .map(|operation| ConcreteTransactionStep { operation })
-            .collect()
+            .collect())
     }
 }
 
@@ -227,59 +437,47 @@ impl TemplateFilterOperation {
         self,
         transaction_context: &TransactionContext,
         database: &Database,
-    ) -> ConcreteTransactionStep<'a> {
-        let rows = transaction_context.row_count(self.prev_step_id);
-
-        let pk_column_id = database
-            .get_pk_column_id(self.table_id)
-            .expect("No primary key column");
-        let pk_column_type = database
-            .get_table(self.table_id)
-            .get_pk_physical_column()
-            .unwrap()
-            .typ
-            .get_pg_type();
+    ) -> Result<ConcreteTransactionStep<'a>, DatabaseError> {
+        let pk_column_ids = database.get_pk_column_ids(self.table_id);
 
         let op = ConcreteTransactionStep {
             operation: SQLOperation::Select(Select {
                 table: Table::physical(self.table_id, None),
                 predicate: Predicate::and(
-                    Predicate::Eq(
-                        Column::physical(pk_column_id, None),
-                        Column::ArrayParam {
-                            param: SQLParamContainer::from_sql_values(
-                                (0..rows)
-                                    .map(|row| {
-                                        transaction_context.resolve_value(self.prev_step_id, row, 0)
-                                    })
-                                    .collect::<Vec<_>>(),
-                                pk_column_type,
-                            ),
-                            wrapper: ArrayParamWrapper::Any,
-                        },
-                    ),
+                    transaction_context.pk_predicate(self.table_id, self.prev_step_id, database)?,
                     self.predicate,
                 ),
                 order_by: None,
                 offset: None,
                 limit: None,
                 top_level_selection: false,
-                columns: vec![Column::physical(pk_column_id, None)],
+                columns: pk_column_ids
+                    .into_iter()
+                    .map(|pk_column_id| Column::physical(pk_column_id, None))
+                    .collect(),
                 group_by: None,
             }),
         };
 
-        op
+        Ok(op)
     }
 }
 
 /// A step that is resolved at runtime (e.g. a select that depends on the result of a previous step)
 pub struct DynamicTransactionStep<'a> {
-    pub function: Box<dyn FnOnce(&TransactionContext) -> ConcreteTransactionStep<'a> + Send + 'a>,
+    #[allow(clippy::type_complexity)]
+    pub function: Box<
+        dyn FnOnce(&TransactionContext) -> Result<ConcreteTransactionStep<'a>, DatabaseError>
+            + Send
+            + 'a,
+    >,
 }
 
 impl<'a> DynamicTransactionStep<'a> {
-    pub fn resolve(self, transaction_context: &TransactionContext) -> ConcreteTransactionStep<'a> {
+    pub fn resolve(
+        self,
+        transaction_context: &TransactionContext,
+    ) -> Result<ConcreteTransactionStep<'a>, DatabaseError> {
         (self.function)(transaction_context)
     }
 }
@@ -9,14 +9,14 @@
 
 use maybe_owned::MaybeOwned;
 
-use crate::{Database, OneToMany, PhysicalTable};
+use crate::{database_error::DatabaseError, Database, OneToMany, PhysicalTable};
 
 use super::{
     column::Column,
     physical_column::PhysicalColumn,
     predicate::ConcretePredicate,
     transaction::{TransactionContext, TransactionStepId},
-    ExpressionBuilder, SQLBuilder, SQLParamContainer,
+    ExpressionBuilder, SQLBuilder,
 };
 
 /// An update operation.
@@ -96,7 +96,7 @@ impl<'a> TemplateUpdate<'a> {
         &'a self,
         prev_step_id: TransactionStepId,
         transaction_context: &TransactionContext,
-    ) -> Vec<Update<'a>> {
+    ) -> Result<Vec<Update<'a>>, DatabaseError> {
         let rows = transaction_context.row_count(prev_step_id);
 
         // Go over all the rows in the previous step and create a concrete update for each row.
@@ -111,23 +111,19 @@ impl<'a> TemplateUpdate<'a> {
                     })
                     .collect();
 
-                let relation_predicate = ConcretePredicate::Eq(
-                    Column::Physical {
-                        column_id: self.nesting_relation.foreign_column_id,
-                        table_alias: None,
-                    },
-                    Column::Param(SQLParamContainer::from_sql_value(
-                        transaction_context.resolve_value(prev_step_id, row_index, 0),
-                    )),
-                );
-
-                Update {
+                let relation_predicate = transaction_context.relation_predicate(
+                    &self.nesting_relation,
+                    prev_step_id,
+                    row_index,
+                )?;
+
+                Ok(Update {
                     table: self.table,
                     predicate: (&self.predicate).into(),
                     additional_predicate: Some(relation_predicate),
                     column_values: resolved_column_values,
                     returning: self.returning.iter().map(|col| col.into()).collect(),
-                }
+                })
             })
             .collect()
     }
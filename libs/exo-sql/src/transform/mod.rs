@@ -11,6 +11,8 @@
 //! Postgres.
 
 pub(crate) mod pg;
+#[cfg(feature = "sqlite")]
+pub(crate) mod sqlite;
 pub(crate) mod transformer;
 
 mod join_util;
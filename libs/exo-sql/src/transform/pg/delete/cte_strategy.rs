@@ -143,6 +143,9 @@ mod tests {
                         order_by: None,
                         offset: None,
                         limit: None,
+                        keyset: None,
+                        group_by: None,
+                        include_deleted: false,
                     },
                     predicate: Predicate::True,
                 };
@@ -183,6 +186,9 @@ mod tests {
                         order_by: None,
                         offset: None,
                         limit: None,
+                        keyset: None,
+                        group_by: None,
+                        include_deleted: false,
                     },
                     predicate,
                 };
@@ -229,6 +235,9 @@ mod tests {
                         order_by: None,
                         offset: None,
                         limit: None,
+                        keyset: None,
+                        group_by: None,
+                        include_deleted: false,
                     },
                     predicate,
                 };
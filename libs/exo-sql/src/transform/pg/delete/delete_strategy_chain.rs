@@ -57,3 +57,13 @@ impl Default for DeleteStrategyChain<'_> {
         Self::new(vec![&CteStrategy {}])
     }
 }
+
+#[cfg(feature = "sqlite")]
+impl DeleteStrategyChain<'_> {
+    /// A chain for databases that don't support data-modifying statements in a CTE (such as SQLite)
+    pub fn without_cte() -> Self {
+        Self::new(vec![
+            &super::multi_statement_strategy::MultiStatementStrategy {},
+        ])
+    }
+}
@@ -7,8 +7,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub(crate) mod delete_strategy_chain;
 pub(crate) mod delete_transformer;
 
 mod cte_strategy;
 mod delete_strategy;
-mod delete_strategy_chain;
+#[cfg(feature = "sqlite")]
+mod multi_statement_strategy;
@@ -0,0 +1,82 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::{
+    sql::{
+        select::Select,
+        sql_operation::SQLOperation,
+        transaction::{ConcreteTransactionStep, TransactionScript, TransactionStep},
+    },
+    transform::{
+        pg::{selection_level::SelectionLevel, Postgres},
+        transformer::{PredicateTransformer, SelectTransformer},
+    },
+    AbstractDelete, Column, Database, Predicate,
+};
+
+use super::delete_strategy::DeleteStrategy;
+
+/// Deletion strategy for databases that don't support data-modifying statements in a CTE (such as
+/// SQLite). Since the deleted rows can't be selected once deleted, we select them first and then
+/// delete them (in the same transaction):
+///
+/// ```sql
+/// SELECT COALESCE(...)::text FROM "concerts" WHERE "concerts"."name" = $1
+/// DELETE FROM "concerts" WHERE "concerts"."name" = $1
+/// ```
+///
+/// The result of the script is that of the select step.
+pub(crate) struct MultiStatementStrategy {}
+
+impl DeleteStrategy for MultiStatementStrategy {
+    fn id(&self) -> &'static str {
+        "MultiStatementStrategy"
+    }
+
+    fn suitable(&self, _abstract_delete: &AbstractDelete, _database: &Database) -> bool {
+        true
+    }
+
+    fn update_transaction_script<'a>(
+        &self,
+        abstract_delete: &'a AbstractDelete,
+        database: &'a Database,
+        transformer: &Postgres,
+        transaction_script: &mut TransactionScript<'a>,
+    ) {
+        let to_predicate = || {
+            transformer.to_predicate(
+                &abstract_delete.predicate,
+                &SelectionLevel::TopLevel,
+                false,
+                database,
+            )
+        };
+
+        let select = transformer.to_select(&abstract_delete.selection, database);
+        let select = Select {
+            predicate: Predicate::and(to_predicate(), select.predicate),
+            ..select
+        };
+
+        let select_step_id = transaction_script.add_step(TransactionStep::Concrete(
+            ConcreteTransactionStep::new(SQLOperation::Select(select)),
+        ));
+
+        let delete = database
+            .get_table(abstract_delete.table_id)
+            .delete(to_predicate(), vec![]);
+
+        let _ = transaction_script.add_step(TransactionStep::Concrete(
+            ConcreteTransactionStep::new(SQLOperation::Delete(delete)),
+        ));
+
+        transaction_script.set_result_step(select_step_id);
+    }
+}
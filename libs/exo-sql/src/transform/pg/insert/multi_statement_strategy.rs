@@ -10,9 +10,10 @@
 use maybe_owned::MaybeOwned;
 
 use crate::{
+    database_error::DatabaseError,
     sql::{
-        column::{ArrayParamWrapper, ProxyColumn},
-        insert::TemplateInsert,
+        column::ProxyColumn,
+        insert::{OnConflict, TemplateInsert},
         select::Select,
         sql_operation::{SQLOperation, TemplateSQLOperation},
         transaction::{
@@ -20,9 +21,12 @@ use crate::{
             TransactionContext, TransactionScript, TransactionStep, TransactionStepId,
         },
     },
-    transform::{pg::Postgres, transformer::SelectTransformer},
-    AbstractInsert, Column, ColumnId, ColumnValuePair, Database, InsertionRow, NestedInsertion,
-    OneToMany, Predicate, SQLParamContainer, TableId,
+    transform::{
+        pg::{selection_level::SelectionLevel, Postgres},
+        transformer::{PredicateTransformer, SelectTransformer},
+    },
+    AbstractInsert, AbstractOnConflict, Column, ColumnId, ColumnValuePair, Database, InsertionRow,
+    NestedInsertion, OneToMany, Predicate, TableId,
 };
 
 use super::insertion_strategy::InsertionStrategy;
@@ -52,53 +56,51 @@ impl InsertionStrategy for MultiStatementStrategy {
         let AbstractInsert {
             table_id,
             rows,
+            on_conflict,
             selection,
         } = abstract_insert;
 
         let insert_step_ids: Vec<_> = rows
             .iter()
-            .map(|row| insert_row(*table_id, row, parent_step, transaction_script, database))
+            .map(|row| {
+                insert_row(
+                    *table_id,
+                    row,
+                    on_conflict.as_ref(),
+                    parent_step,
+                    transformer,
+                    transaction_script,
+                    database,
+                )
+            })
             .collect();
 
         let select = transformer.to_select(selection, database);
 
-        let pk_column_type = database
-            .get_table(*table_id)
-            .get_pk_physical_column()
-            .unwrap()
-            .typ
-            .get_pg_type();
-
         // Take the previous insert steps and use them as the input to the select
-        // statement to form a predicate `pk IN (insert_step_1_pk, insert_step_2_pk, ...)`
+        // statement to form a predicate `pk IN (insert_step_1_pk, insert_step_2_pk, ...)`.
+        // An insert step may not return a row if it was an upsert whose conflicting row didn't
+        // satisfy the update predicate, so we include only the rows actually returned.
         let select_transformation = Box::new(move |transaction_context: &TransactionContext| {
-            let in_values = SQLParamContainer::from_sql_values(
+            let pk_predicate =
                 insert_step_ids
                     .into_iter()
-                    .map(|insert_step_id| transaction_context.resolve_value(insert_step_id, 0, 0))
-                    .collect::<Vec<_>>(),
-                pk_column_type,
-            );
+                    .try_fold(Predicate::False, |acc, insert_step_id| {
+                        Ok::<_, DatabaseError>(Predicate::or(
+                            acc,
+                            transaction_context.pk_predicate(
+                                *table_id,
+                                insert_step_id,
+                                database,
+                            )?,
+                        ))
+                    })?;
 
-            let predicate = Predicate::and(
-                Predicate::Eq(
-                    Column::physical(
-                        database
-                            .get_pk_column_id(*table_id)
-                            .expect("No primary key column"),
-                        None,
-                    ),
-                    Column::ArrayParam {
-                        param: in_values,
-                        wrapper: ArrayParamWrapper::Any,
-                    },
-                ),
-                select.predicate,
-            );
-            ConcreteTransactionStep::new(SQLOperation::Select(Select {
+            let predicate = Predicate::and(pk_predicate, select.predicate);
+            Ok::<_, DatabaseError>(ConcreteTransactionStep::new(SQLOperation::Select(Select {
                 predicate,
                 ..select
-            }))
+            })))
         });
 
         transaction_script.add_step(TransactionStep::Dynamic(DynamicTransactionStep {
@@ -110,7 +112,9 @@ impl InsertionStrategy for MultiStatementStrategy {
 fn insert_row<'a>(
     table_id: TableId,
     row: &'a InsertionRow,
+    on_conflict: Option<&'a AbstractOnConflict>,
     parent_step: Option<(TransactionStepId, ColumnId)>,
+    transformer: &Postgres,
     transaction_script: &mut TransactionScript<'a>,
     database: &'a Database,
 ) -> TransactionStepId {
@@ -119,13 +123,21 @@ fn insert_row<'a>(
     let self_insert_id = insert_self_row(
         table_id,
         self_row,
+        on_conflict,
         parent_step,
+        transformer,
         transaction_script,
         database,
     );
 
     for nested_row in nested_rows {
-        insert_nested_row(nested_row, self_insert_id, transaction_script, database);
+        insert_nested_row(
+            nested_row,
+            self_insert_id,
+            transformer,
+            transaction_script,
+            database,
+        );
     }
 
     self_insert_id
@@ -134,16 +146,17 @@ fn insert_row<'a>(
 fn insert_self_row<'a>(
     table_id: TableId,
     row: Vec<&'a ColumnValuePair>,
+    on_conflict: Option<&'a AbstractOnConflict>,
     parent_step: Option<(TransactionStepId, ColumnId)>,
+    transformer: &Postgres,
     transaction_script: &mut TransactionScript<'a>,
     database: &'a Database,
 ) -> TransactionStepId {
-    let pk_column = Column::physical(
-        database
-            .get_pk_column_id(table_id)
-            .expect("No primary key column"),
-        None,
-    );
+    let pk_columns: Vec<_> = database
+        .get_pk_column_ids(table_id)
+        .into_iter()
+        .map(|pk_column_id| Column::physical(pk_column_id, None))
+        .collect();
 
     let table = database.get_table(table_id);
 
@@ -170,7 +183,7 @@ fn insert_self_row<'a>(
                 table,
                 columns,
                 column_values_seq: vec![proxy_values],
-                returning: vec![pk_column],
+                returning: pk_columns,
             });
             transaction_script.add_step(TransactionStep::Template(TemplateTransactionStep {
                 operation: insert,
@@ -178,8 +191,39 @@ fn insert_self_row<'a>(
             }))
         }
         None => {
-            let insert =
-                SQLOperation::Insert(table.insert(columns, vec![values], vec![pk_column.into()]));
+            let on_conflict = on_conflict.map(|on_conflict| {
+                let conflict_columns: Vec<_> = on_conflict
+                    .conflict_columns
+                    .iter()
+                    .map(|column_id| column_id.get_column(database))
+                    .collect();
+
+                let update_columns = columns
+                    .iter()
+                    .filter(|column| !conflict_columns.contains(*column))
+                    .copied()
+                    .collect();
+
+                OnConflict {
+                    conflict_columns,
+                    update_columns,
+                    predicate: transformer.to_predicate(
+                        &on_conflict.predicate,
+                        &SelectionLevel::TopLevel,
+                        false,
+                        database,
+                    ),
+                }
+            });
+
+            let mut insert = table.insert(
+                columns,
+                vec![values],
+                pk_columns.into_iter().map(MaybeOwned::Owned).collect(),
+            );
+            insert.on_conflict = on_conflict;
+
+            let insert = SQLOperation::Insert(insert);
             transaction_script.add_step(TransactionStep::Concrete(ConcreteTransactionStep::new(
                 insert,
             )))
@@ -190,6 +234,7 @@ fn insert_self_row<'a>(
 fn insert_nested_row<'a>(
     nested_row: &'a NestedInsertion,
     parent_step_id: TransactionStepId,
+    transformer: &Postgres,
     transaction_script: &mut TransactionScript<'a>,
     database: &'a Database,
 ) {
@@ -206,7 +251,9 @@ fn insert_nested_row<'a>(
         insert_row(
             foreign_column_id.table_id,
             insertion,
+            None,
             Some((parent_step_id, foreign_column_id)),
+            transformer,
             transaction_script,
             database,
         );
@@ -7,10 +7,10 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-mod delete;
+pub(crate) mod delete;
 mod insert;
 mod select;
-mod update;
+pub(crate) mod update;
 
 mod order_by_transformer;
 mod predicate_transformer;
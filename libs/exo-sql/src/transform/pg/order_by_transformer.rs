@@ -35,25 +35,36 @@ impl OrderByTransformer for Postgres {
                         OrderByElement::new(column_id, *ordering, table_alias)
                     }
                     AbstractOrderByExpr::VectorDistance(lhs, rhs, op) => {
-                        fn to_column(column_path: &ColumnPath) -> VectorDistanceOperand {
-                            match column_path {
-                                ColumnPath::Physical(path) => {
-                                    VectorDistanceOperand::PhysicalColumn(path.leaf_column())
-                                }
-                                ColumnPath::Param(value) => {
-                                    VectorDistanceOperand::Param(value.clone())
-                                }
-                                _ => panic!("Expected physical column path or a parameter"),
-                            }
-                        }
                         let lhs_column = to_column(lhs);
                         let rhs_column = to_column(rhs);
                         let expr = OrderByElementExpr::VectorDistance(lhs_column, rhs_column, *op);
 
                         OrderByElement(expr, *ordering, None)
                     }
+                    AbstractOrderByExpr::TextSearchRank(document, terms, language) => {
+                        let expr = OrderByElementExpr::TextSearchRank(
+                            to_column(document),
+                            to_column(terms),
+                            language.clone(),
+                        );
+
+                        OrderByElement(expr, *ordering, None)
+                    }
+                    AbstractOrderByExpr::Function(function) => OrderByElement(
+                        OrderByElementExpr::Function(function.clone()),
+                        *ordering,
+                        None,
+                    ),
                 })
                 .collect(),
         )
     }
 }
+
+fn to_column(column_path: &ColumnPath) -> VectorDistanceOperand {
+    match column_path {
+        ColumnPath::Physical(path) => VectorDistanceOperand::PhysicalColumn(path.leaf_column()),
+        ColumnPath::Param(value) => VectorDistanceOperand::Param(value.clone()),
+        _ => panic!("Expected physical column path or a parameter"),
+    }
+}
@@ -9,10 +9,16 @@
 
 use crate::{
     asql::column_path::{ColumnPathLink, RelationLink},
-    sql::predicate::ConcretePredicate,
-    transform::{pg::selection_level::SelectionLevel, transformer::PredicateTransformer},
-    AbstractPredicate, AbstractSelect, AliasedSelectionElement, Column, ColumnPath, Database,
-    NumericComparator, Selection, SelectionElement, VectorDistanceFunction,
+    sql::{
+        column::ArrayParamWrapper, function::Function, predicate::ConcretePredicate, table::Table,
+    },
+    transform::{
+        pg::selection_level::{SelectionLevel, ALIAS_SEPARATOR},
+        transformer::PredicateTransformer,
+    },
+    AbstractPredicate, AbstractSelect, AliasedSelectionElement, Column, ColumnId, ColumnPath,
+    Database, NumericComparator, PhysicalColumnPath, Selection, SelectionElement,
+    VectorDistanceFunction,
 };
 
 use super::Postgres;
@@ -26,7 +32,7 @@ impl PredicateTransformer for Postgres {
         database: &Database,
     ) -> ConcretePredicate {
         if tables_supplied {
-            to_join_predicate(predicate, selection_level, database)
+            to_join_predicate(self, predicate, selection_level, database)
         } else {
             to_subselect_predicate(self, predicate, selection_level, database)
         }
@@ -38,12 +44,13 @@ impl PredicateTransformer for Postgres {
 /// The predicate generated will look like "concerts.price = $1 AND venues.name = $2". It assumes
 /// that the join would have brought in "concerts" and "venues" through a join.
 fn to_join_predicate(
+    transformer: &Postgres,
     predicate: &AbstractPredicate,
     selection_level: &SelectionLevel,
     database: &Database,
 ) -> ConcretePredicate {
     let compute_leaf_column =
-        |column_path: &ColumnPath| leaf_column(column_path, selection_level, database);
+        |column_path: &ColumnPath| leaf_column(transformer, column_path, selection_level, database);
 
     match predicate {
         AbstractPredicate::True => ConcretePredicate::True,
@@ -67,9 +74,17 @@ fn to_join_predicate(
         AbstractPredicate::Gte(l, r) => {
             ConcretePredicate::Gte(compute_leaf_column(l), compute_leaf_column(r))
         }
-        AbstractPredicate::In(l, r) => {
-            ConcretePredicate::In(compute_leaf_column(l), compute_leaf_column(r))
-        }
+        AbstractPredicate::In(l, r) => match r {
+            // A list of values (as opposed to a subselect) is matched using `= ANY($1)`
+            ColumnPath::Param(values) => ConcretePredicate::Eq(
+                compute_leaf_column(l),
+                Column::ArrayParam {
+                    param: values.clone(),
+                    wrapper: ArrayParamWrapper::Any,
+                },
+            ),
+            _ => ConcretePredicate::In(compute_leaf_column(l), compute_leaf_column(r)),
+        },
 
         AbstractPredicate::StringLike(l, r, cs) => {
             ConcretePredicate::StringLike(compute_leaf_column(l), compute_leaf_column(r), *cs)
@@ -111,17 +126,26 @@ fn to_join_predicate(
             compute_leaf_column(threshold),
         ),
 
+        AbstractPredicate::TextSearch(l, r, language) => ConcretePredicate::TextSearch(
+            compute_leaf_column(l),
+            compute_leaf_column(r),
+            language.clone(),
+        ),
+
         AbstractPredicate::And(l, r) => ConcretePredicate::and(
-            to_join_predicate(l, selection_level, database),
-            to_join_predicate(r, selection_level, database),
+            to_join_predicate(transformer, l, selection_level, database),
+            to_join_predicate(transformer, r, selection_level, database),
         ),
         AbstractPredicate::Or(l, r) => ConcretePredicate::or(
-            to_join_predicate(l, selection_level, database),
-            to_join_predicate(r, selection_level, database),
+            to_join_predicate(transformer, l, selection_level, database),
+            to_join_predicate(transformer, r, selection_level, database),
         ),
-        AbstractPredicate::Not(p) => {
-            ConcretePredicate::Not(Box::new(to_join_predicate(p, selection_level, database)))
-        }
+        AbstractPredicate::Not(p) => ConcretePredicate::Not(Box::new(to_join_predicate(
+            transformer,
+            p,
+            selection_level,
+            database,
+        ))),
     }
 }
 
@@ -169,7 +193,7 @@ fn to_subselect_predicate(
                 to_subselect_predicate(transformer, p1, selection_level, database),
                 to_subselect_predicate(transformer, p2, selection_level, database),
             ),
-            _ => to_join_predicate(predicate, selection_level, database),
+            _ => to_join_predicate(transformer, predicate, selection_level, database),
         },
     }
 }
@@ -197,6 +221,9 @@ fn form_subselect(
         order_by: None,
         offset: None,
         limit: None,
+        keyset: None,
+        group_by: None,
+        include_deleted: false,
     };
 
     let select = select_transformer.compute_select(
@@ -212,20 +239,127 @@ fn form_subselect(
 }
 
 fn leaf_column(
+    transformer: &Postgres,
     column_path: &ColumnPath,
     selection_level: &SelectionLevel,
     database: &Database,
 ) -> Column {
     match column_path {
-        ColumnPath::Physical(links) => {
-            let alias = links
-                .alias()
-                .map(|links_alias| selection_level.alias(links_alias, database));
-            Column::physical(links.leaf_column(), alias)
-        }
+        ColumnPath::Physical(links) => physical_leaf_column(links, selection_level, database),
         ColumnPath::Param(l) => Column::Param(l.clone()),
         ColumnPath::Null => Column::Null,
+        ColumnPath::Count {
+            path,
+            foreign_column_id,
+            predicate,
+        } => count_subselect(
+            transformer,
+            path.leaf_column(),
+            path_table_alias(path, selection_level, database),
+            *foreign_column_id,
+            predicate,
+            database,
+        ),
+    }
+}
+
+fn physical_leaf_column(
+    links: &PhysicalColumnPath,
+    selection_level: &SelectionLevel,
+    database: &Database,
+) -> Column {
+    Column::physical(
+        links.leaf_column(),
+        path_table_alias(links, selection_level, database),
+    )
+}
+
+/// The alias of the table at the end of the path
+fn path_table_alias(
+    links: &PhysicalColumnPath,
+    selection_level: &SelectionLevel,
+    database: &Database,
+) -> Option<String> {
+    links
+        .alias()
+        .map(|links_alias| selection_level.alias(links_alias, database))
+}
+
+/// A subselect that counts the related rows satisfying the predicate. The subselect is correlated
+/// with the outer query through the `self_column_id` (in the table aliased as `self_table_alias`).
+///
+/// For example, to count the drafts of a document, the subselect will look like:
+/// ```sql
+/// (SELECT COUNT("drafts$count"."document_id") FROM "drafts" AS "drafts$count" WHERE "drafts$count"."document_id" = "documents"."id" AND <predicate>)
+/// ```
+///
+/// We alias the counted table, since it may be the same as the outer one (for a self-referential
+/// relation such as the reports of an employee), in which case, its name would refer to the inner
+/// table.
+fn count_subselect(
+    transformer: &Postgres,
+    self_column_id: ColumnId,
+    self_table_alias: Option<String>,
+    foreign_column_id: ColumnId,
+    predicate: &AbstractPredicate,
+    database: &Database,
+) -> Column {
+    let foreign_table_id = foreign_column_id.table_id;
+    let self_table_id = self_column_id.table_id;
+
+    let abstract_select = AbstractSelect {
+        table_id: foreign_table_id,
+        selection: Selection::Seq(vec![AliasedSelectionElement::new(
+            "count".to_string(),
+            SelectionElement::Function(Function::Named {
+                function_name: "COUNT".to_string(),
+                column_id: foreign_column_id,
+            }),
+        )]),
+        predicate: predicate.clone(),
+        order_by: None,
+        offset: None,
+        limit: None,
+        keyset: None,
+        group_by: None,
+        include_deleted: false,
+    };
+
+    let mut select = transformer.compute_select(
+        &abstract_select,
+        &SelectionLevel::TopLevel,
+        false, // each related row must be counted exactly once
+        database,
+    );
+
+    // With a join (needed only if the predicate refers to a relation), the join condition refers
+    // to the table by its name, so we can't alias it
+    if let Table::Physical { alias, .. } = &mut select.table {
+        *alias = Some(format!(
+            "{}{ALIAS_SEPARATOR}count",
+            database
+                .get_table(foreign_table_id)
+                .name
+                .fully_qualified_name_with_sep(ALIAS_SEPARATOR)
+        ));
     }
+
+    // Once the inner table is aliased, the table name (if not aliased already) refers to the outer
+    // table. Referring to it explicitly keeps it from being replaced with the inner alias.
+    let self_table_alias = self_table_alias.or_else(|| {
+        (self_table_id == foreign_table_id && matches!(select.table, Table::Physical { .. }))
+            .then(|| database.get_table(self_table_id).name.name.clone())
+    });
+
+    select.predicate = ConcretePredicate::and(
+        ConcretePredicate::Eq(
+            Column::physical(foreign_column_id, None),
+            Column::physical(self_column_id, self_table_alias),
+        ),
+        select.predicate,
+    );
+
+    Column::SubSelect(Box::new(select))
 }
 
 fn attempt_subselect_predicate(
@@ -244,6 +378,24 @@ fn attempt_subselect_predicate(
                     _ => None,
                 }
             }
+            ColumnPath::Count {
+                path,
+                foreign_column_id,
+                predicate,
+            } => {
+                // Push the count down to the table that the related rows refer to
+                match path.split_head() {
+                    (ColumnPathLink::Relation(link), Some(tail)) => Some((
+                        link,
+                        ColumnPath::Count {
+                            path: tail,
+                            foreign_column_id: *foreign_column_id,
+                            predicate: predicate.clone(),
+                        },
+                    )),
+                    _ => None,
+                }
+            }
             ColumnPath::Param(_) | ColumnPath::Null => None,
         }
     }
@@ -413,6 +565,10 @@ fn attempt_subselect_predicate(
             )
         }
 
+        AbstractPredicate::TextSearch(l, r, language) => binary_operator(l, r, |l, r| {
+            AbstractPredicate::TextSearch(l, r, language.clone())
+        }),
+
         AbstractPredicate::And(l, r) => logical_binary_op(l, r, AbstractPredicate::And),
         AbstractPredicate::Or(l, r) => logical_binary_op(l, r, AbstractPredicate::Or),
         AbstractPredicate::Not(p) => attempt_subselect_predicate(p)
@@ -484,7 +640,13 @@ mod tests {
         test_nested_op_predicate(AbstractPredicate::Lte, |l, r| format!("{l} <= {r}"));
         test_nested_op_predicate(AbstractPredicate::Gt, |l, r| format!("{l} > {r}"));
         test_nested_op_predicate(AbstractPredicate::Gte, |l, r| format!("{l} >= {r}"));
-        test_nested_op_predicate(AbstractPredicate::In, |l, r| format!("{l} IN {r}"));
+        test_nested_op_predicate(AbstractPredicate::In, |l, r| {
+            if r == "$1" {
+                format!("{l} = ANY({r})")
+            } else {
+                format!("{l} IN {r}")
+            }
+        });
 
         test_nested_op_predicate(AbstractPredicate::StringStartsWith, |l, r| {
             format!("{l} LIKE {r} || '%'")
@@ -576,6 +738,110 @@ mod tests {
         );
     }
 
+    #[multiplatform_test]
+    fn count_predicate() {
+        TestSetup::with_setup(
+            move |TestSetup {
+                      database,
+                      venues_id_column,
+                      concerts_venue_id_column,
+                      concerts_name_column,
+                      ..
+                  }| {
+                let abstract_predicate = AbstractPredicate::Gte(
+                    ColumnPath::Count {
+                        path: PhysicalColumnPath::leaf(venues_id_column),
+                        foreign_column_id: concerts_venue_id_column,
+                        predicate: Box::new(AbstractPredicate::Eq(
+                            ColumnPath::Physical(PhysicalColumnPath::leaf(concerts_name_column)),
+                            ColumnPath::Param(SQLParamContainer::string("v1".to_string())),
+                        )),
+                    },
+                    ColumnPath::Param(SQLParamContainer::i64(2)),
+                );
+
+                for tables_supplied in [true, false] {
+                    let predicate = Postgres {}.to_predicate(
+                        &abstract_predicate,
+                        &SelectionLevel::TopLevel,
+                        tables_supplied,
+                        &database,
+                    );
+
+                    assert_binding!(
+                        predicate.to_sql(&database),
+                        r#"(SELECT COUNT("concerts$count"."venue_id") FROM "concerts" AS "concerts$count" WHERE ("concerts$count"."venue_id" = "venues"."id" AND "concerts$count"."name" = $1)) >= $2"#,
+                        "v1".to_string(),
+                        2i64
+                    );
+                }
+            },
+        );
+    }
+
+    #[multiplatform_test]
+    fn self_referential_count_predicate() {
+        use crate::{
+            schema::{
+                database_spec::DatabaseSpec,
+                table_spec::TableSpec,
+                test_helper::{pk_column, pk_reference_column, string_column},
+            },
+            PhysicalTableName,
+        };
+
+        let database = DatabaseSpec::new(
+            vec![TableSpec::new(
+                PhysicalTableName::new("employees", None),
+                vec![
+                    pk_column("id"),
+                    pk_reference_column("manager_id", "employees", None),
+                    string_column("name"),
+                ],
+                vec![],
+                vec![],
+            )],
+            vec![],
+        )
+        .to_database();
+
+        let employees_table_id = database
+            .get_table_id(&PhysicalTableName::new("employees", None))
+            .unwrap();
+        let id_column = database.get_column_id(employees_table_id, "id").unwrap();
+        let manager_id_column = database
+            .get_column_id(employees_table_id, "manager_id")
+            .unwrap();
+        let name_column = database.get_column_id(employees_table_id, "name").unwrap();
+
+        // Count the reports of an employee
+        let abstract_predicate = AbstractPredicate::Gte(
+            ColumnPath::Count {
+                path: PhysicalColumnPath::leaf(id_column),
+                foreign_column_id: manager_id_column,
+                predicate: Box::new(AbstractPredicate::Eq(
+                    ColumnPath::Physical(PhysicalColumnPath::leaf(name_column)),
+                    ColumnPath::Param(SQLParamContainer::string("v1".to_string())),
+                )),
+            },
+            ColumnPath::Param(SQLParamContainer::i64(2)),
+        );
+
+        let predicate = Postgres {}.to_predicate(
+            &abstract_predicate,
+            &SelectionLevel::TopLevel,
+            false,
+            &database,
+        );
+
+        assert_binding!(
+            predicate.to_sql(&database),
+            r#"(SELECT COUNT("employees$count"."manager_id") FROM "employees" AS "employees$count" WHERE ("employees$count"."manager_id" = "employees"."id" AND "employees$count"."name" = $1)) >= $2"#,
+            "v1".to_string(),
+            2i64
+        );
+    }
+
     fn test_nested_op_predicate<OP>(op: OP, op_combinator: fn(&str, &str) -> String)
     where
         OP: Clone + Fn(ColumnPath, ColumnPath) -> AbstractPredicate,
@@ -0,0 +1,163 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+use crate::{
+    asql::group_by::AggregateOperand,
+    sql::{
+        group_by::GroupBy, json_agg::JsonAgg, predicate::ConcretePredicate, select::Select,
+        table::Table,
+    },
+    transform::{
+        join_util,
+        transformer::{OrderByTransformer, PredicateTransformer},
+    },
+    Column, Database, Selection, SelectionCardinality,
+};
+
+use super::{
+    selection::json_object,
+    selection_context::SelectionContext,
+    selection_strategy::{compute_relation_predicate, SelectionStrategy},
+};
+
+/// The alias of the column holding the json object for each group
+const GROUP_COLUMN_ALIAS: &str = "group";
+
+/// Strategy for selections with a group-by clause
+///
+/// Pre-conditions:
+/// - A group-by clause is present
+///
+/// Consider the following GraphQL query:
+/// ```graphql
+/// {
+///   concertsGroupBy(by: [venueId], where: {price: {gt: 10}}, having: {id: {count: {gt: 2}}}) {
+///     key {
+///       venueId
+///     }
+///     agg {
+///       id {
+///         count
+///       }
+///     }
+///   }
+/// }
+/// ```
+///
+/// Since each group's json object uses aggregate functions (such as `count`), we can't aggregate
+/// the objects with `json_agg` in the same select (Postgres doesn't allow nested aggregates).
+/// Instead, we form a subselect that produces an object for each group and aggregate those in
+/// the outer select:
+///
+/// ```sql
+/// SELECT COALESCE(json_agg("concerts_groups"."group"), '[]'::json)::text FROM (
+///     SELECT json_build_object('key', json_build_object('venueId', "concerts"."venue_id"), 'agg', ...) AS "group"
+///         FROM "concerts" WHERE "concerts"."price" > $1
+///         GROUP BY "concerts"."venue_id" HAVING count("concerts"."id") > $2
+/// ) AS "concerts_groups"
+/// ```
+///
+/// Like [`SubqueryWithInPredicateStrategy`](super::subquery_with_in_predicate_strategy::SubqueryWithInPredicateStrategy),
+/// we don't join tables for the predicate, so a one-to-many predicate doesn't feed duplicate
+/// rows into the aggregates.
+pub(crate) struct GroupByStrategy {}
+
+impl SelectionStrategy for GroupByStrategy {
+    fn id(&self) -> &'static str {
+        "GroupByStrategy"
+    }
+
+    fn suitable(&self, selection_context: &SelectionContext) -> bool {
+        selection_context.abstract_select.group_by.is_some()
+    }
+
+    fn to_select(&self, selection_context: SelectionContext<'_>, database: &Database) -> Select {
+        let SelectionContext {
+            abstract_select,
+            predicate,
+            selection_level,
+            transformer,
+            ..
+        } = selection_context;
+
+        let abstract_group_by = abstract_select
+            .group_by
+            .as_ref()
+            .expect("GroupByStrategy requires a group-by clause");
+
+        let table =
+            join_util::compute_join(abstract_select.table_id, &[], selection_level, database);
+
+        let additional_predicate = compute_relation_predicate(selection_level, false, database);
+        let predicate = transformer.to_predicate(&predicate, selection_level, false, database);
+        let predicate = ConcretePredicate::and(predicate, additional_predicate);
+
+        let group_by = GroupBy {
+            columns: abstract_group_by.column_ids.clone(),
+            having: abstract_group_by
+                .having
+                .map_columns(&AggregateOperand::to_column),
+        };
+
+        let group_select = |columns: Vec<Column>, top_level_selection: bool| Select {
+            table,
+            columns,
+            predicate,
+            order_by: abstract_select
+                .order_by
+                .as_ref()
+                .map(|order_by| transformer.to_order_by(order_by)),
+            offset: abstract_select.offset.clone(),
+            limit: abstract_select.limit.clone(),
+            group_by: Some(group_by),
+            top_level_selection,
+        };
+
+        match &abstract_select.selection {
+            Selection::Json(seq, SelectionCardinality::Many) => {
+                let group_object = json_object(seq, selection_level, transformer, database);
+
+                let inner_select = group_select(
+                    vec![Column::Aliased {
+                        column: Box::new(group_object),
+                        alias: GROUP_COLUMN_ALIAS.to_string(),
+                    }],
+                    false,
+                );
+
+                let table_name = &database.get_table(abstract_select.table_id).name;
+                let table_alias = format!("{}_groups", table_name.synthetic_name());
+
+                Select {
+                    table: Table::SubSelect {
+                        select: Box::new(inner_select),
+                        alias: Some((table_alias.clone(), table_name.clone())),
+                    },
+                    columns: vec![Column::JsonAgg(JsonAgg(Box::new(
+                        Column::SubSelectColumn {
+                            table_alias,
+                            alias: GROUP_COLUMN_ALIAS.to_string(),
+                        },
+                    )))],
+                    predicate: ConcretePredicate::True,
+                    order_by: None,
+                    offset: None,
+                    limit: None,
+                    group_by: None,
+                    top_level_selection: selection_level.is_top_level(),
+                }
+            }
+            // Without the need to aggregate the groups, we can use a select that returns a row for each group
+            selection => group_select(
+                selection.selection_aggregate(selection_level, transformer, database),
+                selection_level.is_top_level(),
+            ),
+        }
+    }
+}
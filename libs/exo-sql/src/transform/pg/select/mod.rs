@@ -9,6 +9,7 @@
 
 pub(crate) mod select_transformer;
 
+mod group_by_strategy;
 mod plain_join_strategy;
 mod plain_subquery_strategy;
 mod selection;
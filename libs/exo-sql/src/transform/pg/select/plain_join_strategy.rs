@@ -97,6 +97,7 @@ impl SelectionStrategy for PlainJoinStrategy {
     fn to_select(&self, selection_context: SelectionContext<'_>, database: &Database) -> Select {
         let SelectionContext {
             abstract_select,
+            predicate,
             selection_level,
             predicate_column_paths,
             order_by_column_paths,
@@ -106,7 +107,7 @@ impl SelectionStrategy for PlainJoinStrategy {
 
         let (join, predicate) = join_info(
             abstract_select.table_id,
-            &abstract_select.predicate,
+            &predicate,
             predicate_column_paths,
             order_by_column_paths,
             selection_level,
@@ -71,6 +71,7 @@ impl SelectionStrategy for PlainSubqueryStrategy {
     fn to_select(&self, selection_context: SelectionContext<'_>, database: &Database) -> Select {
         let SelectionContext {
             abstract_select,
+            predicate,
             selection_level,
             predicate_column_paths,
             order_by_column_paths,
@@ -80,7 +81,7 @@ impl SelectionStrategy for PlainSubqueryStrategy {
 
         let (join, predicate) = join_info(
             abstract_select.table_id,
-            &abstract_select.predicate,
+            &predicate,
             predicate_column_paths,
             order_by_column_paths,
             selection_level,
@@ -156,13 +156,15 @@ mod tests {
     use crate::{
         asql::{
             column_path::{ColumnPath, PhysicalColumnPath},
+            group_by::{AbstractGroupBy, AggregateOperand},
+            keyset::AbstractKeyset,
             order_by::AbstractOrderByExpr,
             predicate::AbstractPredicate,
             selection::{
                 AliasedSelectionElement, Selection, SelectionCardinality, SelectionElement,
             },
         },
-        sql::{predicate::Predicate, SQLParamContainer},
+        sql::{function::Function, predicate::Predicate, SQLParamContainer},
         transform::{pg::Postgres, test_util::TestSetup, transformer::SelectTransformer},
         AbstractOrderBy, Limit, Offset, Ordering, RelationId,
     };
@@ -191,6 +193,9 @@ mod tests {
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -226,6 +231,9 @@ mod tests {
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -260,6 +268,9 @@ mod tests {
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -318,6 +329,9 @@ mod tests {
                                         order_by: None,
                                         offset: None,
                                         limit: None,
+                                        keyset: None,
+                                        group_by: None,
+                                        include_deleted: false,
                                     },
                                 ),
                             ),
@@ -328,6 +342,9 @@ mod tests {
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -339,6 +356,92 @@ mod tests {
         );
     }
 
+    #[multiplatform_test]
+    fn soft_deleted_rows() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 mut database,
+                 concerts_table,
+                 venues_table,
+                 concerts_id_column,
+                 venues_id_column,
+                 concerts_venue_id_column,
+                 ..
+             }| {
+                database.get_table_mut(venues_table).soft_delete_column =
+                    Some("deleted_at".to_string());
+
+                let venues_select = |include_deleted: bool| AbstractSelect {
+                    table_id: venues_table,
+                    selection: Selection::Json(
+                        vec![AliasedSelectionElement::new(
+                            "id".to_string(),
+                            SelectionElement::Physical(venues_id_column),
+                        )],
+                        SelectionCardinality::One,
+                    ),
+                    predicate: Predicate::True,
+                    order_by: None,
+                    offset: None,
+                    limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted,
+                };
+
+                let select = Postgres {}.to_select(&venues_select(false), &database);
+                assert_binding!(
+                    select.to_sql(&database),
+                    r#"SELECT json_build_object('id', "venues"."id")::text FROM "venues" WHERE "venues"."deleted_at" IS NULL"#
+                );
+
+                let select = Postgres {}.to_select(&venues_select(true), &database);
+                assert_binding!(
+                    select.to_sql(&database),
+                    r#"SELECT json_build_object('id', "venues"."id")::text FROM "venues""#
+                );
+
+                // The rows of a related table are filtered as well
+                let aselect = AbstractSelect {
+                    table_id: concerts_table,
+                    selection: Selection::Json(
+                        vec![
+                            AliasedSelectionElement::new(
+                                "id".to_string(),
+                                SelectionElement::Physical(concerts_id_column),
+                            ),
+                            AliasedSelectionElement::new(
+                                "venue".to_string(),
+                                SelectionElement::SubSelect(
+                                    RelationId::OneToMany(
+                                        concerts_venue_id_column
+                                            .get_otm_relation(&database)
+                                            .unwrap(),
+                                    ),
+                                    venues_select(false),
+                                ),
+                            ),
+                        ],
+                        SelectionCardinality::Many,
+                    ),
+                    predicate: Predicate::True,
+                    order_by: None,
+                    offset: None,
+                    limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
+                };
+
+                let select = Postgres {}.to_select(&aselect, &database);
+                assert_binding!(
+                    select.to_sql(&database),
+                    r#"SELECT COALESCE(json_agg(json_build_object('id', "concerts"."id", 'venue', (SELECT json_build_object('id', "venues"."id") FROM "venues" WHERE ("venues"."deleted_at" IS NULL AND "venues"."id" = "concerts"."venue_id")))), '[]'::json)::text FROM "concerts""#
+                );
+            },
+        );
+    }
+
     #[multiplatform_test]
     fn nested_one_to_many_json() {
         TestSetup::with_setup(
@@ -380,6 +483,9 @@ mod tests {
                                         order_by: None,
                                         offset: None,
                                         limit: None,
+                                        keyset: None,
+                                        group_by: None,
+                                        include_deleted: false,
                                     },
                                 ),
                             ),
@@ -390,6 +496,9 @@ mod tests {
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -450,6 +559,9 @@ mod tests {
                                         order_by: None,
                                         offset: None,
                                         limit: None,
+                                        keyset: None,
+                                        group_by: None,
+                                        include_deleted: false,
                                     },
                                 ),
                             ),
@@ -460,6 +572,9 @@ mod tests {
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -508,6 +623,9 @@ mod tests {
                     order_by: None,
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -545,6 +663,9 @@ mod tests {
                     )])),
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -582,6 +703,9 @@ mod tests {
                     order_by: None,
                     offset: Some(Offset(10)),
                     limit: Some(Limit(20)),
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -596,6 +720,123 @@ mod tests {
         );
     }
 
+    #[multiplatform_test]
+    fn with_keyset() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_table,
+                 concerts_id_column,
+                 concerts_name_column,
+                 ..
+             }| {
+                let order_by = AbstractOrderBy(vec![
+                    (
+                        AbstractOrderByExpr::Column(PhysicalColumnPath::leaf(concerts_name_column)),
+                        Ordering::Asc,
+                    ),
+                    (
+                        AbstractOrderByExpr::Column(PhysicalColumnPath::leaf(concerts_id_column)),
+                        Ordering::Asc,
+                    ),
+                ]);
+                let keyset = AbstractKeyset::new(
+                    &order_by,
+                    Some(vec![
+                        ColumnPath::Param(SQLParamContainer::string("c1".to_string())),
+                        ColumnPath::Param(SQLParamContainer::i32(5)),
+                    ]),
+                    None,
+                    &database,
+                )
+                .unwrap();
+
+                let aselect = AbstractSelect {
+                    table_id: concerts_table,
+                    selection: Selection::Seq(vec![AliasedSelectionElement::new(
+                        "id".to_string(),
+                        SelectionElement::Physical(concerts_id_column),
+                    )]),
+                    predicate: Predicate::True,
+                    order_by: Some(order_by),
+                    offset: None,
+                    limit: Some(Limit(20)),
+                    keyset: Some(keyset),
+                    group_by: None,
+                    include_deleted: false,
+                };
+
+                let select = Postgres {}.to_select(&aselect, &database);
+                assert_binding!(
+                    select.to_sql(&database),
+                    r#"SELECT "concerts"."id" FROM "concerts" WHERE ("concerts"."name" > $1 OR ("concerts"."name" = $2 AND "concerts"."id" > $3)) ORDER BY "concerts"."name" ASC, "concerts"."id" ASC LIMIT $4"#,
+                    "c1".to_string(),
+                    "c1".to_string(),
+                    5,
+                    20i64
+                );
+            },
+        );
+    }
+
+    #[multiplatform_test]
+    fn with_group_by() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_table,
+                 concerts_id_column,
+                 concerts_venue_id_column,
+                 ..
+             }| {
+                let count = || Function::Named {
+                    function_name: "count".to_string(),
+                    column_id: concerts_id_column,
+                };
+
+                let aselect = AbstractSelect {
+                    table_id: concerts_table,
+                    selection: Selection::Json(
+                        vec![
+                            AliasedSelectionElement::new(
+                                "venueId".to_string(),
+                                SelectionElement::Physical(concerts_venue_id_column),
+                            ),
+                            AliasedSelectionElement::new(
+                                "count".to_string(),
+                                SelectionElement::Function(count()),
+                            ),
+                        ],
+                        SelectionCardinality::Many,
+                    ),
+                    predicate: Predicate::True,
+                    order_by: Some(AbstractOrderBy(vec![(
+                        AbstractOrderByExpr::Function(count()),
+                        Ordering::Desc,
+                    )])),
+                    offset: None,
+                    limit: None,
+                    keyset: None,
+                    group_by: Some(AbstractGroupBy {
+                        column_ids: vec![concerts_venue_id_column],
+                        having: Predicate::Gt(
+                            AggregateOperand::Function(count()),
+                            AggregateOperand::Param(SQLParamContainer::i64(1)),
+                        ),
+                    }),
+                    include_deleted: false,
+                };
+
+                let select = Postgres {}.to_select(&aselect, &database);
+                assert_binding!(
+                    select.to_sql(&database),
+                    r#"SELECT COALESCE(json_agg("concerts_groups"."group"), '[]'::json)::text FROM (SELECT json_build_object('venueId', "concerts"."venue_id", 'count', count("concerts"."id")) AS "group" FROM "concerts" GROUP BY "concerts"."venue_id" HAVING count("concerts"."id") > $1 ORDER BY count("concerts"."id") DESC) AS "concerts_groups""#,
+                    1i64
+                );
+            },
+        );
+    }
+
     #[multiplatform_test]
     fn nested_order_by() {
         TestSetup::with_setup(
@@ -625,6 +866,9 @@ mod tests {
                     )])),
                     offset: None,
                     limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
                 };
 
                 let select = Postgres {}.to_select(&aselect, &database);
@@ -42,17 +42,7 @@ impl Selection {
                     .collect(),
             ),
             Selection::Json(seq, cardinality) => {
-                let object_elems = seq
-                    .iter()
-                    .map(|AliasedSelectionElement { alias, column }| {
-                        JsonObjectElement::new(
-                            alias.clone(),
-                            column.to_sql(selection_level, select_transformer, database),
-                        )
-                    })
-                    .collect();
-
-                let json_obj = Column::JsonObject(JsonObject(object_elems));
+                let json_obj = json_object(seq, selection_level, select_transformer, database);
 
                 match cardinality {
                     SelectionCardinality::One => SelectionSQL::Single(json_obj),
@@ -77,6 +67,26 @@ impl Selection {
     }
 }
 
+/// Form a json object (such as `json_build_object('id', "concerts"."id")`) with an element for each of the given selection elements
+pub fn json_object(
+    seq: &[AliasedSelectionElement],
+    selection_level: &SelectionLevel,
+    select_transformer: &Postgres,
+    database: &Database,
+) -> Column {
+    let object_elems = seq
+        .iter()
+        .map(|AliasedSelectionElement { alias, column }| {
+            JsonObjectElement::new(
+                alias.clone(),
+                column.to_sql(selection_level, select_transformer, database),
+            )
+        })
+        .collect();
+
+    Column::JsonObject(JsonObject(object_elems))
+}
+
 impl SelectionElement {
     pub fn to_sql(
         &self,
@@ -9,13 +9,15 @@
 
 use crate::{
     transform::pg::{selection_level::SelectionLevel, Postgres},
-    AbstractSelect, ColumnPath, Database, PhysicalColumnPath,
+    AbstractPredicate, AbstractSelect, ColumnPath, Database, PhysicalColumnPath,
 };
 
 /// A context for the selection transformation to avoid repeating the same work
 /// by each strategy.
 pub(crate) struct SelectionContext<'c> {
     pub abstract_select: &'c AbstractSelect,
+    /// The select's predicate combined with its keyset bounds and the soft-deletion filter (if any)
+    pub predicate: AbstractPredicate,
     pub has_a_one_to_many_predicate: bool,
     pub predicate_column_paths: Vec<PhysicalColumnPath>,
     pub order_by_column_paths: Vec<PhysicalColumnPath>,
@@ -32,12 +34,37 @@ impl<'c> SelectionContext<'c> {
         allow_duplicate_rows: bool,
         transformer: &'c Postgres,
     ) -> Self {
-        let predicate_column_paths: Vec<_> = abstract_select
-            .predicate
+        let predicate = match &abstract_select.keyset {
+            Some(keyset) => AbstractPredicate::and(
+                abstract_select.predicate.clone(),
+                keyset.predicate().clone(),
+            ),
+            None => abstract_select.predicate.clone(),
+        };
+
+        // Skip soft-deleted rows (unless asked to include them). Since nested selections (for
+        // relations) as well as subselects in predicates go through here, this applies to them too.
+        let predicate = match database.get_soft_delete_column_id(abstract_select.table_id) {
+            Some(soft_delete_column_id) if !abstract_select.include_deleted => {
+                AbstractPredicate::and(
+                    predicate,
+                    AbstractPredicate::Eq(
+                        ColumnPath::Physical(PhysicalColumnPath::leaf(soft_delete_column_id)),
+                        ColumnPath::Null,
+                    ),
+                )
+            }
+            _ => predicate,
+        };
+
+        let predicate_column_paths: Vec<_> = predicate
             .column_paths()
             .iter()
             .flat_map(|p| match p {
                 ColumnPath::Physical(links) => Some(links.clone()),
+                // The related rows are counted in a subselect, so only the path leading to the
+                // relation matters here
+                ColumnPath::Count { path, .. } => Some(path.clone()),
                 _ => None,
             })
             .collect();
@@ -65,6 +92,7 @@ impl<'c> SelectionContext<'c> {
 
         Self {
             abstract_select,
+            predicate,
             has_a_one_to_many_predicate,
             predicate_column_paths,
             order_by_column_paths,
@@ -12,8 +12,9 @@ use tracing::debug;
 use crate::{sql::select::Select, Database};
 
 use super::{
-    plain_join_strategy::PlainJoinStrategy, plain_subquery_strategy::PlainSubqueryStrategy,
-    selection_context::SelectionContext, selection_strategy::SelectionStrategy,
+    group_by_strategy::GroupByStrategy, plain_join_strategy::PlainJoinStrategy,
+    plain_subquery_strategy::PlainSubqueryStrategy, selection_context::SelectionContext,
+    selection_strategy::SelectionStrategy,
     subquery_with_in_predicate_strategy::SubqueryWithInPredicateStrategy,
 };
 
@@ -51,6 +52,7 @@ impl<'s> SelectionStrategyChain<'s> {
 impl Default for SelectionStrategyChain<'_> {
     fn default() -> Self {
         Self::new(vec![
+            &GroupByStrategy {},
             &PlainJoinStrategy {},
             &PlainSubqueryStrategy {},
             &SubqueryWithInPredicateStrategy {},
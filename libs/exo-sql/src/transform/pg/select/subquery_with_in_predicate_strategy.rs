@@ -120,6 +120,7 @@ impl SelectionStrategy for SubqueryWithInPredicateStrategy {
     fn to_select(&self, selection_context: SelectionContext<'_>, database: &Database) -> Select {
         let SelectionContext {
             abstract_select,
+            predicate,
             selection_level,
             order_by_column_paths,
             transformer,
@@ -141,8 +142,7 @@ impl SelectionStrategy for SubqueryWithInPredicateStrategy {
         // We don't use the the columns specified in the abstract predicate to form the join (we use
         // only order-by), so we let the predicate transformer know that it should not assume that
         // all tables are joined.
-        let predicate =
-            transformer.to_predicate(&abstract_select.predicate, selection_level, false, database);
+        let predicate = transformer.to_predicate(&predicate, selection_level, false, database);
         let predicate = ConcretePredicate::and(predicate, additional_predicate);
 
         let inner_select = compute_inner_select(
@@ -7,9 +7,9 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+pub(crate) mod update_strategy_chain;
 pub(crate) mod update_transformer;
 
 mod cte_strategy;
 mod multi_statement_strategy;
 mod update_strategy;
-mod update_strategy_chain;
@@ -10,8 +10,8 @@
 use maybe_owned::MaybeOwned;
 
 use crate::{
+    database_error::DatabaseError,
     sql::{
-        column::ArrayParamWrapper,
         delete::TemplateDelete,
         select::Select,
         sql_operation::TemplateSQLOperation,
@@ -23,7 +23,7 @@ use crate::{
     },
     transform::transformer::{InsertTransformer, PredicateTransformer},
     ColumnId, NestedAbstractDelete, NestedAbstractInsert, NestedAbstractInsertSet,
-    NestedAbstractUpdate, PhysicalColumn, Predicate, SQLParamContainer,
+    NestedAbstractUpdate, PhysicalColumn, Predicate,
 };
 
 use crate::{
@@ -87,25 +87,21 @@ impl UpdateStrategy for MultiStatementStrategy {
             database,
         );
 
-        // Select only the primary key column, so that we can use that
-        // as the proxy column in the nested updates added to the transaction script.
-        let return_col = Column::physical(
-            database
-                .get_pk_column_id(abstract_update.table_id)
-                .expect("No primary key column"),
-            None,
-        );
+        // Select only the primary key columns, so that we can use them
+        // as the proxy columns in the nested updates added to the transaction script.
+        let return_cols = database
+            .get_pk_column_ids(abstract_update.table_id)
+            .into_iter()
+            .map(|pk_column_id| Column::physical(pk_column_id, None).into())
+            .collect();
 
         let table = database.get_table(abstract_update.table_id);
         let column_values = column_id_values
             .into_iter()
             .map(|(col_id, col)| (col_id.get_column(database), col))
             .collect();
-        let root_update = SQLOperation::Update(table.update(
-            column_values,
-            predicate.into(),
-            vec![return_col.into()],
-        ));
+        let root_update =
+            SQLOperation::Update(table.update(column_values, predicate.into(), return_cols));
 
         let root_step_id = transaction_script.add_step(TransactionStep::Concrete(
             ConcreteTransactionStep::new(root_update),
@@ -169,38 +165,21 @@ impl UpdateStrategy for MultiStatementStrategy {
 
         let select = transformer.to_select(&abstract_update.selection, database);
 
-        let pk_column_type = table.get_pk_physical_column().unwrap().typ.get_pg_type();
-
         // Take the root step and use ids returned by it as the input to the select
         // statement to form a predicate `pk IN (update_pk1, update_pk2, ...)`
         let select_transformation = Box::new(move |transaction_context: &TransactionContext| {
-            let update_count = transaction_context.row_count(root_step_id);
-            let update_ids = SQLParamContainer::from_sql_values(
-                (0..update_count)
-                    .map(|i| transaction_context.resolve_value(root_step_id, i, 0))
-                    .collect::<Vec<_>>(),
-                pk_column_type,
-            );
-
             let predicate = Predicate::and(
-                Predicate::Eq(
-                    Column::physical(
-                        database
-                            .get_pk_column_id(abstract_update.table_id)
-                            .expect("No primary key column"),
-                        None,
-                    ),
-                    Column::ArrayParam {
-                        param: update_ids,
-                        wrapper: ArrayParamWrapper::Any,
-                    },
-                ),
+                transaction_context.pk_predicate(
+                    abstract_update.table_id,
+                    root_step_id,
+                    database,
+                )?,
                 select.predicate,
             );
-            ConcreteTransactionStep::new(SQLOperation::Select(Select {
+            Ok::<_, DatabaseError>(ConcreteTransactionStep::new(SQLOperation::Select(Select {
                 predicate,
                 ..select
-            }))
+            })))
         });
 
         transaction_script.add_step(TransactionStep::Dynamic(DynamicTransactionStep {
@@ -335,6 +314,9 @@ mod tests {
                         order_by: None,
                         offset: None,
                         limit: None,
+                        keyset: None,
+                        group_by: None,
+                        include_deleted: false,
                     },
                 };
 
@@ -387,6 +369,9 @@ mod tests {
                             order_by: None,
                             offset: None,
                             limit: None,
+                            keyset: None,
+                            group_by: None,
+                            include_deleted: false,
                         },
                         nested_updates: vec![],
                         nested_inserts: vec![],
@@ -420,6 +405,9 @@ mod tests {
                         order_by: None,
                         offset: None,
                         limit: None,
+                        keyset: None,
+                        group_by: None,
+                        include_deleted: false,
                     },
                 };
 
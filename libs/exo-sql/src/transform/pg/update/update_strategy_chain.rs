@@ -60,3 +60,11 @@ impl Default for UpdateStrategyChain<'_> {
         Self::new(vec![&CteStrategy {}, &MultiStatementStrategy {}])
     }
 }
+
+#[cfg(feature = "sqlite")]
+impl UpdateStrategyChain<'_> {
+    /// A chain for databases that don't support data-modifying statements in a CTE (such as SQLite)
+    pub fn without_cte() -> Self {
+        Self::new(vec![&MultiStatementStrategy {}])
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Transform abstract operations into transaction scripts for SQLite.
+//!
+//! The transformation to SQL primitives is the same as for Postgres (the differences in syntax are
+//! handled while building SQL for [`SqlDialect::Sqlite`](crate::sql::SqlDialect)). However, SQLite
+//! doesn't allow data-modifying statements (`INSERT`, `UPDATE`, `DELETE`) in a CTE, so we use only
+//! the strategies that execute them as separate statements.
+
+use crate::{
+    asql::abstract_operation::AbstractOperation,
+    sql::transaction::TransactionScript,
+    transform::{
+        pg::{
+            delete::delete_strategy_chain::DeleteStrategyChain,
+            update::update_strategy_chain::UpdateStrategyChain, Postgres,
+        },
+        transformer::{InsertTransformer, OperationTransformer, SelectTransformer},
+    },
+    Database,
+};
+
+pub struct Sqlite {}
+
+impl OperationTransformer for Sqlite {
+    fn to_transaction_script<'a>(
+        &self,
+        database: &'a Database,
+        abstract_operation: &'a AbstractOperation,
+    ) -> TransactionScript<'a> {
+        let transformer = Postgres {};
+
+        match abstract_operation {
+            AbstractOperation::Select(select) => {
+                SelectTransformer::to_transaction_script(&transformer, select, database)
+            }
+            AbstractOperation::Delete(delete) => {
+                let mut transaction_script = TransactionScript::default();
+                DeleteStrategyChain::without_cte().update_transaction_script(
+                    delete,
+                    database,
+                    &transformer,
+                    &mut transaction_script,
+                );
+                transaction_script
+            }
+            // Postgres inserts using multiple statements as well
+            AbstractOperation::Insert(insert) => {
+                InsertTransformer::to_transaction_script(&transformer, insert, None, database)
+            }
+            AbstractOperation::Update(update) => {
+                let mut transaction_script = TransactionScript::default();
+                UpdateStrategyChain::without_cte().update_transaction_script(
+                    update,
+                    database,
+                    &transformer,
+                    &mut transaction_script,
+                );
+                transaction_script
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multiplatform_test::multiplatform_test;
+    use tokio_postgres::types::Type;
+
+    use crate::{
+        sql::{ExpressionBuilder, SQLBuilder, SqlDialect},
+        transform::test_util::TestSetup,
+        AbstractOrderBy, AbstractOrderByExpr, AbstractPredicate, AbstractSelect,
+        AliasedSelectionElement, ColumnPath, Limit, Offset, Ordering, PhysicalColumnPath,
+        Predicate, RelationId, SQLParamContainer, Selection, SelectionCardinality,
+        SelectionElement,
+    };
+
+    use super::*;
+
+    #[multiplatform_test]
+    fn nested_one_to_many_json() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_table,
+                 venues_table,
+                 concerts_id_column,
+                 venues_id_column,
+                 concerts_venue_id_column,
+                 ..
+             }| {
+                let aselect = AbstractSelect {
+                    table_id: venues_table,
+                    selection: Selection::Json(
+                        vec![
+                            AliasedSelectionElement::new(
+                                "id".to_string(),
+                                SelectionElement::Physical(venues_id_column),
+                            ),
+                            AliasedSelectionElement::new(
+                                "concerts".to_string(),
+                                SelectionElement::SubSelect(
+                                    RelationId::OneToMany(
+                                        concerts_venue_id_column
+                                            .get_otm_relation(&database)
+                                            .unwrap(),
+                                    ),
+                                    AbstractSelect {
+                                        table_id: concerts_table,
+                                        selection: Selection::Json(
+                                            vec![AliasedSelectionElement::new(
+                                                "id".to_string(),
+                                                SelectionElement::Physical(concerts_id_column),
+                                            )],
+                                            SelectionCardinality::Many,
+                                        ),
+                                        predicate: Predicate::True,
+                                        order_by: None,
+                                        offset: None,
+                                        limit: None,
+                                        keyset: None,
+                                        group_by: None,
+                                        include_deleted: false,
+                                    },
+                                ),
+                            ),
+                        ],
+                        SelectionCardinality::Many,
+                    ),
+                    predicate: Predicate::True,
+                    order_by: None,
+                    offset: None,
+                    limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
+                };
+
+                let select = Postgres {}.to_select(&aselect, &database);
+                let mut builder = SQLBuilder::with_dialect(SqlDialect::Sqlite);
+                select.build(&database, &mut builder);
+
+                assert_binding!(
+                    builder.into_sql(),
+                    r#"SELECT COALESCE(json_group_array(json(json_object('id', "venues"."id", 'concerts', json((SELECT COALESCE(json_group_array(json(json_object('id', "concerts"."id"))), json_array()) FROM "concerts" WHERE "venues"."id" = "concerts"."venue_id"))))), json_array()) FROM "venues""#
+                );
+            },
+        );
+    }
+
+    #[multiplatform_test]
+    fn array_param_and_offset() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_table,
+                 concerts_id_column,
+                 ..
+             }| {
+                let aselect = AbstractSelect {
+                    table_id: concerts_table,
+                    selection: Selection::Seq(vec![AliasedSelectionElement::new(
+                        "id".to_string(),
+                        SelectionElement::Physical(concerts_id_column),
+                    )]),
+                    predicate: AbstractPredicate::In(
+                        ColumnPath::Physical(PhysicalColumnPath::leaf(concerts_id_column)),
+                        ColumnPath::Param(SQLParamContainer::new(vec![1i16, 2], Type::INT2_ARRAY)),
+                    ),
+                    order_by: None,
+                    offset: Some(Offset(3)),
+                    limit: None,
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
+                };
+
+                let select = Postgres {}.to_select(&aselect, &database);
+                let mut builder = SQLBuilder::with_dialect(SqlDialect::Sqlite);
+                select.build(&database, &mut builder);
+
+                assert_binding!(
+                    builder.into_sql(),
+                    r#"SELECT "concerts"."id" FROM "concerts" WHERE "concerts"."id" IN (SELECT value FROM json_each(?1)) LIMIT -1 OFFSET ?2"#,
+                    vec![1i16, 2],
+                    3i64
+                );
+            },
+        );
+    }
+
+    #[multiplatform_test]
+    fn order_by_sorts_nulls_as_postgres() {
+        TestSetup::with_setup(
+            |TestSetup {
+                 database,
+                 concerts_table,
+                 concerts_id_column,
+                 concerts_name_column,
+                 ..
+             }| {
+                let aselect = AbstractSelect {
+                    table_id: concerts_table,
+                    selection: Selection::Seq(vec![AliasedSelectionElement::new(
+                        "id".to_string(),
+                        SelectionElement::Physical(concerts_id_column),
+                    )]),
+                    predicate: Predicate::True,
+                    order_by: Some(AbstractOrderBy(vec![
+                        (
+                            AbstractOrderByExpr::Column(PhysicalColumnPath::leaf(
+                                concerts_name_column,
+                            )),
+                            Ordering::Asc,
+                        ),
+                        (
+                            AbstractOrderByExpr::Column(PhysicalColumnPath::leaf(
+                                concerts_id_column,
+                            )),
+                            Ordering::Desc,
+                        ),
+                    ])),
+                    offset: None,
+                    limit: Some(Limit(10)),
+                    keyset: None,
+                    group_by: None,
+                    include_deleted: false,
+                };
+
+                let select = Postgres {}.to_select(&aselect, &database);
+                let mut builder = SQLBuilder::with_dialect(SqlDialect::Sqlite);
+                select.build(&database, &mut builder);
+
+                assert_binding!(
+                    builder.into_sql(),
+                    r#"SELECT "concerts"."id" FROM "concerts" ORDER BY "concerts"."name" ASC NULLS LAST, "concerts"."id" DESC NULLS FIRST LIMIT ?1"#,
+                    10i64
+                );
+            },
+        );
+    }
+}
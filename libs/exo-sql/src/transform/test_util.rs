@@ -9,7 +9,9 @@
 
 #![cfg(test)]
 
-use crate::schema::test_helper::{pk_column, pk_reference_column, string_column};
+use crate::schema::test_helper::{
+    nullable_timestamp_column, pk_column, pk_reference_column, string_column,
+};
 use crate::schema::{database_spec::DatabaseSpec, table_spec::TableSpec};
 use crate::{ColumnId, Database, PhysicalTableName, TableId};
 
@@ -62,7 +64,12 @@ impl TestSetup {
                 ),
                 TableSpec::new(
                     PhysicalTableName::new("venues", None),
-                    vec![pk_column("id"), string_column("name")],
+                    vec![
+                        pk_column("id"),
+                        string_column("name"),
+                        // Marked as the soft-deletion column by the tests that need it
+                        nullable_timestamp_column("deleted_at"),
+                    ],
                     vec![],
                     vec![],
                 ),
@@ -9,11 +9,13 @@
 
 /// This code has no concept of Exograph.
 ///
-/// Module to encapsulate the logic creating a WASM module that supports
+/// Module to encapsulate the logic creating a WASM component that supports
 /// embedding.
+mod val_conversion;
 mod wasm_error;
 mod wasm_executor;
 mod wasm_executor_pool;
 
 pub use wasm_error::WasmError;
-pub use wasm_executor_pool::WasmExecutorPool;
+pub use wasm_executor::{CallbackProcessor, WasmLimits};
+pub use wasm_executor_pool::{WasmExecutorPool, WasmExecutorPoolStats};
@@ -0,0 +1,190 @@
+// Copyright Exograph, Inc. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file at the root of this repository.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Conversion between JSON values and component model values.
+//!
+//! Records map to JSON objects. Since WIT identifiers are kebab-case, a record field such as
+//! `user-id` maps to the `userId` key of the object. Options map to the value or `null`.
+
+use heck::ToLowerCamelCase;
+use serde_json::{Map, Number, Value};
+use wasmtime::component::{types::Type, Val};
+
+use crate::wasm_error::WasmError;
+
+pub(crate) fn to_component_val(value: Value, typ: &Type) -> Result<Val, WasmError> {
+    let invalid_value = |value: &Value| WasmError::InvalidValue(value.to_string(), type_name(typ));
+
+    macro_rules! integer {
+        ($variant:ident, $int_type:ty) => {
+            value
+                .as_i64()
+                .and_then(|n| <$int_type>::try_from(n).ok())
+                .map(Val::$variant)
+                .ok_or_else(|| invalid_value(&value))
+        };
+    }
+
+    match typ {
+        Type::Bool => value
+            .as_bool()
+            .map(Val::Bool)
+            .ok_or_else(|| invalid_value(&value)),
+        Type::S8 => integer!(S8, i8),
+        Type::U8 => integer!(U8, u8),
+        Type::S16 => integer!(S16, i16),
+        Type::U16 => integer!(U16, u16),
+        Type::S32 => integer!(S32, i32),
+        Type::U32 => integer!(U32, u32),
+        Type::S64 => integer!(S64, i64),
+        Type::U64 => value
+            .as_u64()
+            .map(Val::U64)
+            .ok_or_else(|| invalid_value(&value)),
+        Type::Float32 => value
+            .as_f64()
+            .map(|n| Val::Float32(n as f32))
+            .ok_or_else(|| invalid_value(&value)),
+        Type::Float64 => value
+            .as_f64()
+            .map(Val::Float64)
+            .ok_or_else(|| invalid_value(&value)),
+        Type::Char => {
+            let mut chars = value.as_str().map(|s| s.chars()).into_iter().flatten();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Val::Char(c)),
+                _ => Err(invalid_value(&value)),
+            }
+        }
+        Type::String => match value {
+            Value::String(s) => Ok(Val::String(s)),
+            _ => Err(invalid_value(&value)),
+        },
+        Type::List(list) => match value {
+            Value::Array(elements) => Ok(Val::List(
+                elements
+                    .into_iter()
+                    .map(|element| to_component_val(element, &list.ty()))
+                    .collect::<Result<_, _>>()?,
+            )),
+            _ => Err(invalid_value(&value)),
+        },
+        Type::Record(record) => match value {
+            Value::Object(mut object) => Ok(Val::Record(
+                record
+                    .fields()
+                    .map(|field| {
+                        let value = object
+                            .remove(&field.name.to_lower_camel_case())
+                            .unwrap_or(Value::Null);
+                        Ok((field.name.to_string(), to_component_val(value, &field.ty)?))
+                    })
+                    .collect::<Result<_, WasmError>>()?,
+            )),
+            _ => Err(invalid_value(&value)),
+        },
+        Type::Option(option) => match value {
+            Value::Null => Ok(Val::Option(None)),
+            value => Ok(Val::Option(Some(Box::new(to_component_val(
+                value,
+                &option.ty(),
+            )?)))),
+        },
+        _ => Err(WasmError::UnsupportedType(type_name(typ))),
+    }
+}
+
+/// Convert the value returned by a function. An error returned through a `result` becomes
+/// [WasmError::Explicit].
+pub(crate) fn from_component_result(value: Val) -> Result<Value, WasmError> {
+    match value {
+        Val::Result(Ok(value)) => value.map_or(Ok(Value::Null), |value| from_component_val(*value)),
+        Val::Result(Err(error)) => Err(WasmError::Explicit(match error.map(|error| *error) {
+            Some(Val::String(message)) => message,
+            Some(error) => from_component_val(error)?.to_string(),
+            None => "Error returned by the WASM module".to_string(),
+        })),
+        value => from_component_val(value),
+    }
+}
+
+pub(crate) fn from_component_val(value: Val) -> Result<Value, WasmError> {
+    Ok(match value {
+        Val::Bool(b) => Value::Bool(b),
+        Val::S8(n) => n.into(),
+        Val::U8(n) => n.into(),
+        Val::S16(n) => n.into(),
+        Val::U16(n) => n.into(),
+        Val::S32(n) => n.into(),
+        Val::U32(n) => n.into(),
+        Val::S64(n) => n.into(),
+        Val::U64(n) => n.into(),
+        Val::Float32(n) => Number::from_f64(n.into()).map_or(Value::Null, Value::Number),
+        Val::Float64(n) => Number::from_f64(n).map_or(Value::Null, Value::Number),
+        Val::Char(c) => Value::String(c.to_string()),
+        Val::String(s) => Value::String(s),
+        Val::List(elements) => Value::Array(
+            elements
+                .into_iter()
+                .map(from_component_val)
+                .collect::<Result<_, _>>()?,
+        ),
+        Val::Record(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| Ok((name.to_lower_camel_case(), from_component_val(value)?)))
+                .collect::<Result<Map<_, _>, WasmError>>()?,
+        ),
+        Val::Option(value) => match value {
+            Some(value) => from_component_val(*value)?,
+            None => Value::Null,
+        },
+        value => return Err(WasmError::UnsupportedType(format!("{value:?}"))),
+    })
+}
+
+fn type_name(typ: &Type) -> String {
+    format!("{typ:?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_fields_use_camel_case() {
+        let value = Val::Record(vec![
+            ("user-id".to_string(), Val::S32(1)),
+            (
+                "title".to_string(),
+                Val::Option(Some(Box::new(Val::String("Learn WIT".to_string())))),
+            ),
+            ("completed".to_string(), Val::Option(None)),
+        ]);
+
+        assert_eq!(
+            from_component_val(value).unwrap(),
+            serde_json::json!({ "userId": 1, "title": "Learn WIT", "completed": null })
+        );
+    }
+
+    #[test]
+    fn result_errors_are_explicit() {
+        let value = Val::Result(Err(Some(Box::new(Val::String("Not found".to_string())))));
+
+        assert!(matches!(
+            from_component_result(value),
+            Err(WasmError::Explicit(message)) if message == "Not found"
+        ));
+        assert_eq!(
+            from_component_result(Val::Result(Ok(Some(Box::new(Val::S32(42)))))).unwrap(),
+            serde_json::json!(42)
+        );
+    }
+}
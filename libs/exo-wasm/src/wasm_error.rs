@@ -7,6 +7,7 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -21,12 +22,16 @@ pub enum WasmError {
     #[error("Unsupported WASM type '{0}'")]
     UnsupportedType(String),
 
-    #[error("{0}")]
-    StringArrayError(#[from] wasi_common::StringArrayError),
+    #[error("Cannot convert '{0}' to the WASM type '{1}'")]
+    InvalidValue(String, String),
 
     #[error("Failed to locate method '{0}'")]
     MethodNotFound(String),
 
-    #[error("Failed to convert '{0}' to a WASM function")]
-    InvalidMethod(String),
+    #[error("Method '{0}' expects {1} arguments, but {2} were provided")]
+    ArgumentCount(String, usize, usize),
+
+    // A call didn't complete within the timeout (the call is stopped)
+    #[error("Execution timed out after {}ms", .0.as_millis())]
+    Timeout(Duration),
 }
@@ -7,60 +7,260 @@
 // the Business Source License, use of this software will be governed
 // by the Apache License, Version 2.0.
 
-use crate::wasm_error::WasmError;
+use crate::{
+    val_conversion::{from_component_result, to_component_val},
+    wasm_error::WasmError,
+};
 
-use wasi_common::sync::WasiCtxBuilder;
-use wasmtime::{Engine, Linker, Module, Store, Val};
+use std::time::Duration;
 
+use async_trait::async_trait;
+use heck::ToKebabCase;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+use wasmtime::{
+    component::{Component, Linker, ResourceTable, Val},
+    Config, Engine, Store, StoreContextMut, StoreLimits, StoreLimitsBuilder,
+};
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
+
+/// The interface a component imports to call back into the host. Its declaration in WIT:
+///
+/// ```wit
+/// package exograph:module;
+///
+/// interface exograph {
+///     execute-query: func(query: string, variables: option<string>) -> result<string, string>;
+/// }
+/// ```
+///
+/// The variables and the result are JSON-encoded.
+const EXOGRAPH_INTERFACE: &str = "exograph:module/exograph";
+
+/// How often a component with a timeout yields (so that we can stop it once the timeout elapses)
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// The limits on running a component (`None` for no limit)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// The wall-clock time allowed for each call
+    pub timeout: Option<Duration>,
+    /// The maximum size of each linear memory of the component in bytes
+    pub max_memory_bytes: Option<usize>,
+}
+
+/// Processes callbacks made by a component through the imported `exograph` interface
+#[async_trait]
+pub trait CallbackProcessor: Sync {
+    async fn execute_query(&self, query: String, variables: Option<Value>)
+        -> Result<Value, String>;
+}
+
+struct ExecuteQueryRequest {
+    query: String,
+    variables: Option<Value>,
+    response_sender: oneshot::Sender<Result<Value, String>>,
+}
+
+struct WasmState {
+    wasi: WasiCtx,
+    table: ResourceTable,
+    query_sender: mpsc::UnboundedSender<ExecuteQueryRequest>,
+    limits: StoreLimits,
+}
+
+impl WasiView for WasmState {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi
+    }
+}
+
+/// Executes functions exported by a component.
+///
+/// Each call gets a fresh instance (in its own store), so a call stopped for exceeding a limit
+/// leaves nothing behind to affect later calls.
 #[derive(Clone)]
 pub struct WasmExecutor {
-    module: Module,
+    component: Component,
+    limits: WasmLimits,
 }
 
 impl WasmExecutor {
-    pub fn new(module_source: &[u8]) -> Result<WasmExecutor, WasmError> {
-        let engine = Engine::default();
-        let module = Module::from_binary(&engine, module_source)?;
+    pub fn new(component_source: &[u8], limits: WasmLimits) -> Result<WasmExecutor, WasmError> {
+        let mut config = Config::new();
+        config
+            .wasm_component_model(true)
+            .async_support(true)
+            .epoch_interruption(limits.timeout.is_some());
+
+        let engine = Engine::new(&config)?;
+        let component = Component::from_binary(&engine, component_source)?;
 
-        Ok(WasmExecutor { module })
+        if limits.timeout.is_some() {
+            // Advance the epoch periodically (for as long as the engine is in use), so that running
+            // components yield regularly
+            let engine = engine.weak();
+            std::thread::spawn(move || {
+                while let Some(engine) = engine.upgrade() {
+                    engine.increment_epoch();
+                    drop(engine);
+                    std::thread::sleep(EPOCH_TICK);
+                }
+            });
+        }
+
+        Ok(WasmExecutor { component, limits })
     }
 
-    pub fn execute(
+    /// Call the exported function corresponding to `method_name` (the WIT name of a function is
+    /// the kebab-case version of the method name).
+    ///
+    /// The arguments and the result are JSON values, which are converted to and from the
+    /// function's WIT types. If the function returns a `result`, its error is reported as
+    /// [WasmError::Explicit].
+    ///
+    /// If the call doesn't complete within the timeout, it is stopped and reported as
+    /// [WasmError::Timeout].
+    pub async fn execute(
         &self,
         method_name: &str,
-        arguments: Vec<Val>,
-    ) -> Result<serde_json::Value, WasmError> {
-        let mut linker = Linker::new(self.module.engine());
-        wasi_common::sync::add_to_linker(&mut linker, |s| s)?;
-
-        let wasi = WasiCtxBuilder::new()
-            .inherit_stdio()
-            .inherit_args()?
-            .build();
-
-        let mut store = Store::new(self.module.engine(), wasi);
-
-        linker.module(&mut store, "", &self.module)?;
-
-        let func = linker
-            .get(&mut store, "", method_name)
-            .ok_or_else(|| WasmError::MethodNotFound(method_name.to_string()))?
-            .into_func()
-            .ok_or_else(|| WasmError::InvalidMethod(method_name.to_string()))?;
-
-        let mut results = [0i32.into()];
-        func.call(store, &arguments, &mut results)?;
-        let result = &results[0];
-
-        match result {
-            wasmtime::Val::I32(n) => Ok((*n).into()),
-            wasmtime::Val::I64(n) => Ok((*n).into()),
-            wasmtime::Val::F32(n) => Ok((*n).into()),
-            wasmtime::Val::F64(n) => Ok((*n).into()),
-            wasmtime::Val::V128(_) => Err(WasmError::UnsupportedType("V128".to_string())),
-            wasmtime::Val::FuncRef(_) => Err(WasmError::UnsupportedType("FuncRef".to_string())),
-            wasmtime::Val::ExternRef(_) => Err(WasmError::UnsupportedType("ExternRef".to_string())),
-            wasmtime::Val::AnyRef(_) => Err(WasmError::UnsupportedType("AnyRef".to_string())),
+        arguments: Vec<Value>,
+        callback_processor: &dyn CallbackProcessor,
+    ) -> Result<Value, WasmError> {
+        let engine = self.component.engine();
+
+        let mut linker = Linker::new(engine);
+        wasmtime_wasi::add_to_linker_async(&mut linker)?;
+        linker
+            .instance(EXOGRAPH_INTERFACE)?
+            .func_new_async("execute-query", |store, params, results| {
+                Box::new(execute_query(store, params, results))
+            })?;
+
+        let wasi = WasiCtxBuilder::new().inherit_stdio().build();
+
+        let mut limits = StoreLimitsBuilder::new().trap_on_grow_failure(true);
+        if let Some(max_memory_bytes) = self.limits.max_memory_bytes {
+            limits = limits.memory_size(max_memory_bytes);
+        }
+
+        let (query_sender, mut query_receiver) = mpsc::unbounded_channel();
+        let mut store = Store::new(
+            engine,
+            WasmState {
+                wasi,
+                table: ResourceTable::new(),
+                query_sender,
+                limits: limits.build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        if self.limits.timeout.is_some() {
+            // Yield at every epoch tick (giving us a chance to stop the call)
+            store.epoch_deadline_async_yield_and_update(1);
+        }
+
+        let call = async move {
+            let instance = linker
+                .instantiate_async(&mut store, &self.component)
+                .await?;
+
+            let func = instance
+                .get_func(&mut store, method_name.to_kebab_case().as_str())
+                .ok_or_else(|| WasmError::MethodNotFound(method_name.to_string()))?;
+
+            let param_types = func.params(&store);
+            if param_types.len() != arguments.len() {
+                return Err(WasmError::ArgumentCount(
+                    method_name.to_string(),
+                    param_types.len(),
+                    arguments.len(),
+                ));
+            }
+
+            let arguments = arguments
+                .into_iter()
+                .zip(param_types.iter())
+                .map(|(argument, typ)| to_component_val(argument, typ))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut results = vec![Val::Bool(false); func.results(&store).len()];
+            func.call_async(&mut store, &arguments, &mut results)
+                .await?;
+            func.post_return_async(&mut store).await?;
+
+            match results.pop() {
+                Some(result) => from_component_result(result),
+                None => Ok(Value::Null),
+            }
+        };
+        tokio::pin!(call);
+
+        let deadline = async {
+            match self.limits.timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(deadline);
+
+        // Process the component's callbacks while it is running
+        loop {
+            tokio::select! {
+                result = &mut call => return result,
+                // Dropping the call (along with its store) stops the component
+                _ = &mut deadline => {
+                    return Err(WasmError::Timeout(self.limits.timeout.unwrap_or_default()))
+                }
+                Some(request) = query_receiver.recv() => {
+                    let response = callback_processor
+                        .execute_query(request.query, request.variables)
+                        .await;
+                    // The component may no longer be waiting for the response (if it trapped)
+                    let _ = request.response_sender.send(response);
+                }
+            }
         }
     }
 }
+
+/// The host implementation of `execute-query` (see [EXOGRAPH_INTERFACE])
+async fn execute_query(
+    store: StoreContextMut<'_, WasmState>,
+    params: &[Val],
+    results: &mut [Val],
+) -> anyhow::Result<()> {
+    let (query, variables) = match params {
+        [Val::String(query), Val::Option(variables)] => (query.clone(), variables.as_deref()),
+        _ => anyhow::bail!("Invalid arguments to 'execute-query'"),
+    };
+
+    let variables = match variables {
+        Some(Val::String(variables)) => Some(serde_json::from_str(variables)?),
+        Some(_) => anyhow::bail!("Invalid variables passed to 'execute-query'"),
+        None => None,
+    };
+
+    let (response_sender, response_receiver) = oneshot::channel();
+    store
+        .data()
+        .query_sender
+        .send(ExecuteQueryRequest {
+            query,
+            variables,
+            response_sender,
+        })
+        .map_err(|_| anyhow::anyhow!("Could not send request from 'execute-query'"))?;
+
+    let response = match response_receiver.await? {
+        Ok(value) => Ok(Some(Box::new(Val::String(value.to_string())))),
+        Err(error) => Err(Some(Box::new(Val::String(error)))),
+    };
+    results[0] = Val::Result(response);
+
+    Ok(())
+}
@@ -10,15 +10,52 @@
 use serde_json::Value;
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
 };
-use wasmtime::Val;
 
-use crate::{wasm_error::WasmError, wasm_executor::WasmExecutor};
+use crate::{
+    wasm_error::WasmError,
+    wasm_executor::{CallbackProcessor, WasmExecutor, WasmLimits},
+};
+
+/// Utilization of the executor for a module
+#[derive(Debug, Clone)]
+pub struct WasmExecutorPoolStats {
+    pub script_path: String,
+    /// The number of calls currently executing (each in its own instance of the module)
+    pub busy: usize,
+}
 
 #[derive(Default)]
 pub struct WasmExecutorPool {
-    pub(crate) pool: Arc<Mutex<HashMap<String, WasmExecutor>>>,
+    pub(crate) pool: Arc<Mutex<HashMap<String, PooledExecutor>>>,
+}
+
+/// An executor along with the number of calls it is currently executing
+#[derive(Clone)]
+pub(crate) struct PooledExecutor {
+    executor: WasmExecutor,
+    busy: Arc<AtomicUsize>,
+}
+
+/// Counts a call as executing for as long as it is alive (so that a failed or cancelled call
+/// doesn't remain counted)
+struct BusyGuard(Arc<AtomicUsize>);
+
+impl BusyGuard {
+    fn new(busy: Arc<AtomicUsize>) -> Self {
+        busy.fetch_add(1, Ordering::SeqCst);
+        Self(busy)
+    }
+}
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl WasmExecutorPool {
@@ -26,24 +63,70 @@ impl WasmExecutorPool {
         &self,
         script_path: &str,
         script: &[u8],
+        limits: WasmLimits,
         method_name: &str,
-        arguments: Vec<Val>,
+        arguments: Vec<Value>,
+        callback_processor: &dyn CallbackProcessor,
     ) -> Result<Value, WasmError> {
-        let executor = self.get_executor(script_path, script)?;
+        let PooledExecutor { executor, busy } = self.get_executor(script_path, script, limits)?;
+        let _busy_guard = BusyGuard::new(busy);
+
+        executor
+            .execute(method_name, arguments, callback_processor)
+            .await
+    }
 
-        executor.execute(method_name, arguments)
+    /// The number of calls currently executing for each module
+    pub fn stats(&self) -> Vec<WasmExecutorPoolStats> {
+        let pool = self.pool.lock().unwrap();
+
+        pool.iter()
+            .map(|(script_path, pooled)| WasmExecutorPoolStats {
+                script_path: script_path.clone(),
+                busy: pooled.busy.load(Ordering::SeqCst),
+            })
+            .collect()
     }
 
     fn get_executor(
         &self,
         module_name: &str,
         module_source: &[u8],
-    ) -> Result<WasmExecutor, WasmError> {
+        limits: WasmLimits,
+    ) -> Result<PooledExecutor, WasmError> {
         let mut pool = self.pool.lock().unwrap();
-        let executor = pool
-            .entry(module_name.to_string())
-            .or_insert_with(|| WasmExecutor::new(module_source).unwrap());
+        let executor = match pool.get(module_name) {
+            Some(executor) => executor.clone(),
+            None => {
+                let executor = PooledExecutor {
+                    executor: WasmExecutor::new(module_source, limits)?,
+                    busy: Arc::new(AtomicUsize::new(0)),
+                };
+                pool.insert(module_name.to_string(), executor.clone());
+                executor
+            }
+        };
+
+        Ok(executor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn busy_guard_counts_while_alive() {
+        let busy = Arc::new(AtomicUsize::new(0));
+
+        let first = BusyGuard::new(busy.clone());
+        let second = BusyGuard::new(busy.clone());
+        assert_eq!(busy.load(Ordering::SeqCst), 2);
+
+        drop(first);
+        assert_eq!(busy.load(Ordering::SeqCst), 1);
 
-        Ok(executor.clone())
+        drop(second);
+        assert_eq!(busy.load(Ordering::SeqCst), 0);
     }
 }